@@ -0,0 +1,600 @@
+//! Adventure packets: a world's history turned into a tabletop kit
+//!
+//! Picks the most promising ruins, monster lairs and dungeons and writes one
+//! Markdown handout per site:
+//! - Maps of each z-level the site spans, cut from the world's z-level layout
+//! - Hooks drawn from the site's chronicle, raids, hoards and fall
+//! - The nearest living settlements and who leads them (from the hero registry)
+//! - A d6 rumor table mixing true facts with plausible falsehoods, marked for
+//!   the game master
+//!
+//! The Markdown is plain (headings, lists, tables and fenced maps) so it prints
+//! or converts to PDF without any special styling.
+
+use std::collections::HashSet;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::history::monsters::MonsterSpecies;
+use crate::history::{HeroRole, Settlement, SettlementState, WorldHistory, Year};
+use crate::section_export::{legend, Material};
+use crate::world::WorldData;
+use crate::zlevel::{MAX_Z, MIN_Z};
+
+/// Tiles shown around the site on each side of its maps
+const MAP_RADIUS: i32 = 8;
+
+/// Most underground z-levels drawn for one site
+const MAX_MAP_LEVELS: usize = 3;
+
+/// Z-levels above the ground searched for walls and towers on the surface plan
+const PLAN_HEADROOM: i32 = 2;
+
+/// How far (tiles) to look for settlements, battles and tombs around a site
+const NEARBY_RADIUS: usize = 14;
+
+/// Settlements listed per site
+const NEARBY_SETTLEMENTS: usize = 3;
+
+/// Closest two sites in one packet may be (tiles)
+const MIN_SITE_SPACING: usize = 4;
+
+/// Rows of the rumor table (one die)
+const RUMOR_DIE: usize = 6;
+
+/// What kind of place an adventure site is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SiteKind {
+    /// An abandoned, ruined or destroyed settlement
+    Ruin,
+    /// A monster lair
+    Lair,
+    /// A dungeon or cave system
+    Dungeon,
+}
+
+impl SiteKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SiteKind::Ruin => "Ruin",
+            SiteKind::Lair => "Lair",
+            SiteKind::Dungeon => "Dungeon",
+        }
+    }
+}
+
+/// A living settlement near a site, and whoever speaks for it
+#[derive(Clone, Debug)]
+pub struct NearbySettlement {
+    pub name: String,
+    pub kind: String,
+    pub faction: String,
+    pub distance: usize,
+    /// "Name, role" of a living hero of the settlement's people
+    pub leader: Option<String>,
+    /// The leader's philosophy, doctrine or faith
+    pub leader_lore: Option<String>,
+}
+
+/// One line of the rumor table
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rumor {
+    pub text: String,
+    pub is_true: bool,
+}
+
+/// A map of the ground around a site
+#[derive(Clone, Debug)]
+pub struct SiteMap {
+    /// The z-level cut, or None for the plan of the surface
+    pub z: Option<i32>,
+    pub rows: Vec<String>,
+}
+
+/// Everything a game master needs to run one site
+#[derive(Clone, Debug)]
+pub struct AdventureSite {
+    pub kind: SiteKind,
+    pub name: String,
+    pub x: usize,
+    pub y: usize,
+    pub summary: String,
+    pub hooks: Vec<String>,
+    pub maps: Vec<SiteMap>,
+    pub nearby: Vec<NearbySettlement>,
+    pub rumors: Vec<Rumor>,
+}
+
+/// A set of adventure sites from one world
+#[derive(Clone, Debug)]
+pub struct AdventurePacket {
+    pub seed: u64,
+    pub sites: Vec<AdventureSite>,
+}
+
+/// A candidate site before it is written up
+struct Candidate {
+    kind: SiteKind,
+    name: String,
+    x: usize,
+    y: usize,
+    score: f32,
+    /// Underground z-levels worth drawing, and the tiles to mark on the maps
+    levels: Vec<i32>,
+    marks: Vec<(usize, usize, i32, char)>,
+    /// True statements about the site, for hooks and rumors
+    facts: Vec<String>,
+    summary: String,
+}
+
+impl AdventureSite {
+    /// Markdown handout for the site
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n*{} at ({}, {})*\n\n{}\n\n", self.name, self.kind.name(), self.x, self.y, self.summary);
+
+        out.push_str("## Hooks\n\n");
+        for hook in &self.hooks {
+            out.push_str(&format!("- {}\n", hook));
+        }
+
+        out.push_str("\n## Maps\n\n");
+        for map in &self.maps {
+            match map.z {
+                Some(z) => out.push_str(&format!("### Z-level {}\n\n```\n", z)),
+                None => out.push_str("### Surface\n\n```\n"),
+            }
+            for row in &map.rows {
+                out.push_str(row);
+                out.push('\n');
+            }
+            out.push_str("```\n\n");
+        }
+        out.push_str(&format!("{}  'X' site  '!' hoard  'o' chamber\n\n", legend()));
+
+        out.push_str("## Nearby settlements\n\n");
+        if self.nearby.is_empty() {
+            out.push_str("None within a few days' travel.\n");
+        }
+        for settlement in &self.nearby {
+            out.push_str(&format!(
+                "- **{}** ({} of {}, {} tiles away)",
+                settlement.name, settlement.kind, settlement.faction, settlement.distance
+            ));
+            match settlement.leader {
+                Some(ref leader) => out.push_str(&format!(": led by {}", leader)),
+                None => out.push_str(": no notable leader"),
+            }
+            if let Some(ref lore) = settlement.leader_lore {
+                out.push_str(&format!(". {}", lore));
+            }
+            out.push('\n');
+        }
+
+        out.push_str("\n## Rumors\n\n| d6 | Rumor | GM |\n|---|---|---|\n");
+        for (i, rumor) in self.rumors.iter().enumerate() {
+            out.push_str(&format!("| {} | {} | {} |\n", i + 1, rumor.text, if rumor.is_true { "true" } else { "false" }));
+        }
+        out
+    }
+
+    /// File name: index in the packet plus a slug of the name
+    pub fn filename(&self, index: usize) -> String {
+        let slug: String =
+            self.name.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' }).collect();
+        format!("{:02}_{}.md", index + 1, slug.trim_matches('_'))
+    }
+}
+
+impl AdventurePacket {
+    /// Index page linking every site
+    pub fn index_markdown(&self) -> String {
+        let mut out = format!("# Adventure packet\n\nWorld seed {}. {} sites.\n\n", self.seed, self.sites.len());
+        out.push_str("| # | Site | Kind | Location |\n|---|---|---|---|\n");
+        for (i, site) in self.sites.iter().enumerate() {
+            out.push_str(&format!(
+                "| {} | [{}]({}) | {} | ({}, {}) |\n",
+                i + 1,
+                site.name,
+                site.filename(i),
+                site.kind.name(),
+                site.x,
+                site.y
+            ));
+        }
+        out
+    }
+
+    /// Write `README.md` and one Markdown file per site into `dir`; returns the files written
+    pub fn export(&self, dir: &str) -> std::io::Result<Vec<String>> {
+        std::fs::create_dir_all(dir)?;
+        let mut paths = Vec::new();
+        let index = std::path::Path::new(dir).join("README.md");
+        std::fs::write(&index, self.index_markdown())?;
+        paths.push(index.display().to_string());
+        for (i, site) in self.sites.iter().enumerate() {
+            let path = std::path::Path::new(dir).join(site.filename(i));
+            std::fs::write(&path, site.to_markdown())?;
+            paths.push(path.display().to_string());
+        }
+        Ok(paths)
+    }
+}
+
+/// Horizontal distance in tiles, wrapping east-west
+fn distance(world: &WorldData, a: (usize, usize), b: (usize, usize)) -> usize {
+    let dx = a.0.abs_diff(b.0);
+    dx.min(world.width - dx).max(a.1.abs_diff(b.1))
+}
+
+/// Build a packet of up to `count` sites (None if the world has no history)
+pub fn build_packet(world: &WorldData, count: usize) -> Option<AdventurePacket> {
+    let history = world.history.as_ref()?;
+    let mut candidates = candidates(world, history);
+    // Worst first, so each queue pops its best site
+    candidates.sort_by(|a, b| a.score.total_cmp(&b.score).then(b.name.cmp(&a.name)));
+
+    // Take the best of each kind in turn, so a packet is not all lairs
+    let mut picked: Vec<Candidate> = Vec::new();
+    let mut queues: Vec<Vec<Candidate>> = vec![Vec::new(), Vec::new(), Vec::new()];
+    for candidate in candidates {
+        let queue = match candidate.kind {
+            SiteKind::Lair => 0,
+            SiteKind::Dungeon => 1,
+            SiteKind::Ruin => 2,
+        };
+        queues[queue].push(candidate);
+    }
+    while picked.len() < count && queues.iter().any(|q| !q.is_empty()) {
+        for queue in queues.iter_mut() {
+            while let Some(candidate) = queue.pop() {
+                let spaced = picked.iter().all(|p| distance(world, (p.x, p.y), (candidate.x, candidate.y)) >= MIN_SITE_SPACING);
+                if spaced {
+                    picked.push(candidate);
+                    break;
+                }
+            }
+            if picked.len() == count {
+                break;
+            }
+        }
+    }
+
+    let mut used_leaders = HashSet::new();
+    let sites = picked
+        .into_iter()
+        .enumerate()
+        .map(|(i, candidate)| write_up(world, history, candidate, i, &mut used_leaders))
+        .collect();
+    Some(AdventurePacket { seed: history.seed, sites })
+}
+
+/// Every ruin, lair and dungeon worth a visit, scored
+fn candidates(world: &WorldData, history: &WorldHistory) -> Vec<Candidate> {
+    let mut out = Vec::new();
+    let surface = |x: usize, y: usize| *world.surface_z.get(x, y);
+
+    for lair in history.monsters.lairs.values() {
+        let mut facts = vec![format!("A {} lairs at {}", lair.species.name().to_lowercase(), lair.name)];
+        if let Some((year, attack)) = lair.attacks.iter().max_by_key(|(year, _)| *year) {
+            facts.push(format!(
+                "The {} of {} has raided {} times; the last, {}: {}",
+                lair.species.name().to_lowercase(),
+                lair.name,
+                lair.attacks.len(),
+                year.to_string().to_lowercase(),
+                attack
+            ));
+        }
+        for id in &lair.hoard {
+            if let Some(artifact) = history.artifacts.get(*id) {
+                facts.push(format!("{} lies in the hoard of {}", artifact.summary(), lair.name));
+            }
+        }
+        let (mut site, mut levels) = ((lair.x, lair.y), Vec::new());
+        let mut marks = vec![(lair.x, lair.y, surface(lair.x, lair.y), 'X')];
+        if let Some(ref structure) = lair.structure {
+            // The entrance is the site (a troll's bridge may be off its lair tile)
+            let (ex, ey, ez) = structure.entrance;
+            site = (ex, ey);
+            marks = vec![(ex, ey, ez, 'X')];
+            for &(cx, cy, cz) in &structure.chambers {
+                levels.push(cz);
+                marks.push((cx, cy, cz, 'o'));
+            }
+            if let Some((hx, hy, hz)) = structure.hoard {
+                marks.push((hx, hy, hz, '!'));
+            }
+            if structure.depth() > 0 {
+                facts.push(format!("The deepest chamber of {} lies {} levels below the entrance", lair.name, structure.depth()));
+            }
+        }
+        let state = if lair.active { "active" } else { "abandoned" };
+        out.push(Candidate {
+            kind: SiteKind::Lair,
+            name: lair.name.clone(),
+            x: site.0,
+            y: site.1,
+            score: lair.danger as f32 * 2.0 + lair.attacks.len() as f32 + lair.hoard.len() as f32 * 4.0
+                + lair.active as u8 as f32 * 3.0,
+            levels,
+            marks,
+            facts,
+            summary: format!(
+                "An {} {} lair ({}), danger {}/10.",
+                state,
+                lair.species.name().to_lowercase(),
+                lair.structure.as_ref().map_or("a simple den".to_string(), |s| s.describe()),
+                lair.danger.min(10)
+            ),
+        });
+    }
+
+    for dungeon in history.dungeons.dungeons.values() {
+        let (x, y) = dungeon.location;
+        let mut facts: Vec<String> = dungeon.history.clone();
+        for id in &dungeon.artifacts_present {
+            if let Some(artifact) = history.artifacts.get(*id) {
+                facts.push(format!("{} is hidden somewhere in {}", artifact.summary(), dungeon.name));
+            }
+        }
+        facts.push(format!("The passages of {} reach {}", dungeon.name, dungeon.depth_range_str()));
+        // Spread the drawn levels from the top of the dungeon to its floor
+        let top = dungeon.depth_max.min(surface(x, y) - 1);
+        let bottom = dungeon.depth_min.max(MIN_Z);
+        let span = (top - bottom).max(0);
+        let steps = (span as usize).min(MAX_MAP_LEVELS - 1) as i32;
+        let levels = (0..=steps).map(|i| top - span * i / steps.max(1)).collect();
+        out.push(Candidate {
+            kind: SiteKind::Dungeon,
+            name: dungeon.name.clone(),
+            x,
+            y,
+            score: dungeon.artifacts_present.len() as f32 * 5.0 + dungeon.size as f32 * 0.2 + dungeon.age() as f32 * 0.005,
+            levels,
+            marks: vec![(x, y, surface(x, y), 'X')],
+            facts,
+            summary: format!(
+                "{} dug {}, {} ({}).",
+                dungeon.original_purpose.name(),
+                dungeon.founded_year,
+                if dungeon.abandoned_year.is_some() { "since abandoned" } else { "still in use" },
+                dungeon.depth_range_str()
+            ),
+        });
+    }
+
+    for settlement in history.territories.settlements.values().filter(|s| !s.is_active()) {
+        let (x, y) = (settlement.x, settlement.y);
+        let mut facts = Vec::new();
+        if let Some(year) = settlement.abandoned {
+            let reason = settlement.abandonment_reason.map_or("abandoned", |r| r.name());
+            facts.push(format!("{} was {} {}", settlement.name, reason, year.to_string().to_lowercase()));
+        }
+        facts.push(format!("At its height {} souls lived there", settlement.peak_population));
+        for event in history.timeline.events_at(x, y) {
+            facts.push(format!("{}, {}", event.name, event.year.to_string().to_lowercase()));
+        }
+        let z = surface(x, y);
+        let decay = settlement.state.decay_factor();
+        out.push(Candidate {
+            kind: SiteKind::Ruin,
+            name: format!("Ruins of {}", settlement.name),
+            x,
+            y,
+            score: decay * 6.0 + (settlement.peak_population as f32).log10() * 2.0 + settlement.age() as f32 * 0.005,
+            levels: Vec::new(),
+            marks: vec![(x, y, z, 'X')],
+            facts,
+            summary: format!(
+                "A {} {} founded {}, now {}.",
+                settlement.architecture.name().to_lowercase(),
+                settlement.settlement_type.name().to_lowercase(),
+                settlement.founded,
+                settlement.state.name().to_lowercase()
+            ),
+        });
+    }
+    out
+}
+
+/// Turn a picked candidate into a full write-up
+fn write_up(
+    world: &WorldData,
+    history: &WorldHistory,
+    candidate: Candidate,
+    index: usize,
+    used_leaders: &mut HashSet<u32>,
+) -> AdventureSite {
+    let mut rng = ChaCha8Rng::seed_from_u64(history.seed.wrapping_add(0xAD7E) ^ ((index as u64 + 1) << 16));
+    let site = (candidate.x, candidate.y);
+
+    // Hooks: what the site is known for, plus what happened around it
+    let mut hooks: Vec<String> = candidate.facts.iter().take(3).cloned().collect();
+    if let Some(flavor) = history.tile_story(site.0, site.1).flavor() {
+        if !hooks.iter().any(|h| flavor.contains(h.as_str())) {
+            hooks.push(flavor);
+        }
+    }
+    let mut nearby_facts = Vec::new();
+    for event in history.timeline.events.values() {
+        let Some(at) = event.location else { continue };
+        if at != site && distance(world, at, site) <= NEARBY_RADIUS / 2 && event.event_type.leaves_evidence() {
+            let name = event.name.strip_prefix("The ").unwrap_or(&event.name);
+            nearby_facts.push((event.year, format!("Not far off lies the site of the {}, {}", name, event.year.to_string().to_lowercase())));
+        }
+    }
+    for hero in history.heroes.all() {
+        if let Some((hx, hy, _)) = hero.burial_site {
+            if distance(world, (hx, hy), site) <= NEARBY_RADIUS / 2 {
+                nearby_facts.push((hero.death_year.unwrap_or_default(), format!("{} the {} is buried close by", hero.full_name(), hero.role.name().to_lowercase())));
+            }
+        }
+    }
+    nearby_facts.sort_by_key(|(year, text)| (std::cmp::Reverse(*year), text.clone()));
+    if let Some((_, fact)) = nearby_facts.first() {
+        hooks.push(fact.clone());
+    }
+
+    let nearby = nearby_settlements(world, history, site, used_leaders);
+    let rumors = rumor_table(&candidate, &nearby_facts, &mut rng);
+    let mut maps = vec![site_map(world, &candidate, None)];
+    maps.extend(candidate.levels_deduped().into_iter().map(|z| site_map(world, &candidate, Some(z))));
+
+    AdventureSite {
+        kind: candidate.kind,
+        name: candidate.name.clone(),
+        x: candidate.x,
+        y: candidate.y,
+        summary: candidate.summary.clone(),
+        hooks,
+        maps,
+        nearby,
+        rumors,
+    }
+}
+
+impl Candidate {
+    /// Underground levels to draw, each once, top down
+    fn levels_deduped(&self) -> Vec<i32> {
+        let mut levels: Vec<i32> = self.levels.iter().filter(|&&z| (MIN_Z..=MAX_Z).contains(&z)).copied().collect();
+        levels.sort_by(|a, b| b.cmp(a));
+        levels.dedup();
+        levels.truncate(MAX_MAP_LEVELS);
+        levels
+    }
+}
+
+/// Material map around the site, with the site's marks drawn in: one z-level,
+/// or (None) the plan of the surface with whatever stands on it
+fn site_map(world: &WorldData, candidate: &Candidate, z: Option<i32>) -> SiteMap {
+    let mut rows = Vec::new();
+    for dy in -MAP_RADIUS..=MAP_RADIUS {
+        let y = candidate.y as i32 + dy;
+        if y < 0 || y >= world.height as i32 {
+            continue;
+        }
+        let row: String = (-MAP_RADIUS..=MAP_RADIUS)
+            .map(|dx| {
+                let (x, y) = ((candidate.x as i32 + dx).rem_euclid(world.width as i32) as usize, y as usize);
+                let ground = *world.surface_z.get(x, y);
+                let level = z.unwrap_or_else(|| {
+                    // Topmost thing that isn't sky, from just above the ground down to it
+                    let top = (ground + PLAN_HEADROOM).min(MAX_Z);
+                    (ground..=top).rev().find(|&z| Material::of(*world.zlevels.get(x, y, z)) != Material::Sky).unwrap_or(ground)
+                });
+                let on_map = |mz: i32| z.map_or(mz >= ground, |z| mz == z);
+                let mark = candidate.marks.iter().find(|&&(mx, my, mz, _)| (mx, my) == (x, y) && on_map(mz));
+                mark.map_or_else(|| Material::of(*world.zlevels.get(x, y, level)).glyph(), |m| m.3)
+            })
+            .collect();
+        rows.push(row);
+    }
+    SiteMap { z, rows }
+}
+
+/// The closest living settlements, each with a distinct living hero of its people
+fn nearby_settlements(
+    world: &WorldData,
+    history: &WorldHistory,
+    site: (usize, usize),
+    used_leaders: &mut HashSet<u32>,
+) -> Vec<NearbySettlement> {
+    let mut settlements: Vec<&Settlement> = history
+        .territories
+        .settlements
+        .values()
+        .filter(|s| matches!(s.state, SettlementState::Thriving | SettlementState::Declining))
+        .filter(|s| distance(world, (s.x, s.y), site) <= NEARBY_RADIUS)
+        .collect();
+    settlements.sort_by_key(|s| (distance(world, (s.x, s.y), site), s.id.0));
+    settlements.truncate(NEARBY_SETTLEMENTS);
+
+    let role_rank = |role: HeroRole| match role {
+        HeroRole::Ruler => 0,
+        HeroRole::General => 1,
+        HeroRole::Priest => 2,
+        HeroRole::Scholar => 3,
+        _ => 4,
+    };
+    settlements
+        .into_iter()
+        .map(|s| {
+            let faction = s.current_faction.unwrap_or(s.original_faction);
+            let mut heroes: Vec<_> = history
+                .heroes
+                .heroes_of_faction(faction)
+                .into_iter()
+                .filter(|h| h.death_year.is_none() && h.role != HeroRole::Villain && !used_leaders.contains(&h.id.0))
+                .collect();
+            heroes.sort_by_key(|h| (role_rank(h.role), std::cmp::Reverse(h.fame), h.id.0));
+            let leader = heroes.first().copied();
+            if let Some(hero) = leader {
+                used_leaders.insert(hero.id.0);
+            }
+            NearbySettlement {
+                name: s.name.clone(),
+                kind: s.settlement_type.name().to_lowercase(),
+                faction: history.factions.get(faction).map_or_else(|| "no one".to_string(), |f| f.name.clone()),
+                distance: distance(world, (s.x, s.y), site),
+                leader: leader.map(|h| format!("{}, {}", h.full_name(), h.role.name().to_lowercase())),
+                leader_lore: leader.and_then(|h| h.lore_summary()),
+            }
+        })
+        .collect()
+}
+
+/// A d6 table: up to four true rumors, the rest plausible falsehoods, shuffled
+fn rumor_table(candidate: &Candidate, nearby_facts: &[(Year, String)], rng: &mut ChaCha8Rng) -> Vec<Rumor> {
+    let mut truths: Vec<String> = candidate.facts.clone();
+    truths.extend(nearby_facts.iter().map(|(_, fact)| fact.clone()));
+    truths.shuffle(rng);
+    truths.truncate(4);
+
+    let species = MonsterSpecies::all();
+    let false_rumors = [
+        format!("A {} guards {}", species[rng.gen_range(0..species.len())].name().to_lowercase(), candidate.name),
+        format!("Nothing is left in {}; it was picked clean a generation ago", candidate.name),
+        format!("A secret way into {} opens only under a full moon", candidate.name),
+        format!("Whoever sleeps a night in {} wakes ten years older", candidate.name),
+        format!("The bandit queen of the hills keeps her ransom money in {}", candidate.name),
+        format!("A tunnel runs from {} all the way to the sea", candidate.name),
+    ];
+    let mut rumors: Vec<Rumor> = truths.into_iter().map(|text| Rumor { text, is_true: true }).collect();
+    let mut lies: Vec<&String> = false_rumors.iter().collect();
+    lies.shuffle(rng);
+    for lie in lies.into_iter().take(RUMOR_DIE - rumors.len()) {
+        rumors.push(Rumor { text: lie.clone(), is_true: false });
+    }
+    rumors.shuffle(rng);
+    rumors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packet_sites_have_maps_hooks_and_rumors() {
+        let world = crate::world::generate_world(96, 48, 7);
+        let packet = build_packet(&world, 5).expect("the world has a history");
+        assert!(!packet.sites.is_empty() && packet.sites.len() <= 5);
+        let kinds: HashSet<SiteKind> = packet.sites.iter().map(|s| s.kind).collect();
+        assert!(kinds.len() > 1, "a packet mixes kinds of site");
+
+        let leaders: Vec<&String> = packet.sites.iter().flat_map(|s| &s.nearby).filter_map(|n| n.leader.as_ref()).collect();
+        assert_eq!(leaders.len(), leaders.iter().collect::<HashSet<_>>().len(), "no leader runs two settlements");
+
+        for (i, site) in packet.sites.iter().enumerate() {
+            assert!(!site.hooks.is_empty());
+            assert!(site.maps.iter().any(|m| m.rows.iter().any(|r| r.contains('X'))), "{} marks its site", site.name);
+            assert_eq!(site.rumors.len(), RUMOR_DIE);
+            assert!(site.rumors.iter().any(|r| r.is_true));
+            let markdown = site.to_markdown();
+            assert!(markdown.starts_with(&format!("# {}", site.name)));
+            assert!(markdown.contains("| 6 |"));
+            assert!(packet.index_markdown().contains(&site.filename(i)));
+        }
+    }
+}
@@ -0,0 +1,541 @@
+//! Aeolian landforms: desert dune fields
+//!
+//! Sand seas form in sandy deserts, their pattern set by how much sand there is and
+//! how steadily the prevailing wind (`climate::get_prevailing_wind`) blows:
+//! - Barchans: isolated crescents with horns trailing downwind, where sand is scarce
+//! - Longitudinal (seif) dunes: long ridges running with the wind across the big ergs
+//! - Star dunes: tall pyramids with radiating arms where two wind belts meet and the
+//!   wind comes from every quarter
+//!
+//! Dune fields creep downwind over the centuries. `apply_dune_history` records the
+//! settlements and trade roads the advancing sand overran, and `dune_relief` draws
+//! each type's pattern for local maps and exports.
+
+use std::f64::consts::TAU;
+
+use crate::biomes::ExtendedBiome;
+use crate::climate;
+use crate::history::types::Year;
+#[cfg(feature = "history")]
+use crate::history::types::{AbandonmentReason, SettlementState};
+#[cfg(feature = "history")]
+use crate::history::{EventType, HistoricalEvent, WorldHistory};
+use crate::tilemap::Tilemap;
+
+/// Normalized latitudes (0 = equator, 1 = pole) where neighbouring wind belts meet
+const BELT_EDGES: [f32; 3] = [0.15, 0.35, 0.65];
+
+/// Half-width (normalized latitude) of the zone where two belts' winds mix
+const BELT_MIXING: f32 = 0.08;
+
+/// Furthest (tiles) a dune front is followed downwind
+const MAX_FRONT_DISTANCE: usize = 64;
+
+/// A kind of dune field
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DuneType {
+    #[default]
+    None,
+    Barchan,
+    Longitudinal,
+    Star,
+}
+
+impl DuneType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DuneType::None => "None",
+            DuneType::Barchan => "Barchan",
+            DuneType::Longitudinal => "Longitudinal",
+            DuneType::Star => "Star",
+        }
+    }
+
+    /// Distance between neighbouring dunes, in local tiles
+    pub fn local_spacing(&self) -> f64 {
+        match self {
+            DuneType::None => 1.0,
+            DuneType::Barchan => 20.0,
+            DuneType::Longitudinal => 14.0,
+            DuneType::Star => 32.0,
+        }
+    }
+
+    /// Crest height above the interdune floor, in local z-levels
+    pub fn local_height(&self) -> f32 {
+        match self {
+            DuneType::None => 0.0,
+            DuneType::Barchan => 2.0,
+            DuneType::Longitudinal => 3.0,
+            DuneType::Star => 5.0,
+        }
+    }
+}
+
+/// Parameters for dune field generation
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DuneConfig {
+    /// Radius (tiles) over which sand supply is measured
+    pub supply_radius: i32,
+    /// Sand supply below which only isolated barchans form
+    pub barchan_max_supply: f32,
+    /// Wind variability above which plentiful sand piles into star dunes
+    pub star_min_variability: f32,
+    /// How far dune fields advance downwind (tiles per thousand years)
+    pub migration_rate: f32,
+}
+
+impl Default for DuneConfig {
+    fn default() -> Self {
+        Self {
+            supply_radius: 4,
+            barchan_max_supply: 0.5,
+            star_min_variability: 0.6,
+            migration_rate: 2.0,
+        }
+    }
+}
+
+/// Dune fields of a world
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DuneMap {
+    pub dune: Tilemap<DuneType>,
+    /// Sand supply (0-1): the share of sandy desert around each tile
+    pub supply: Tilemap<f32>,
+    /// How far dune fields advance downwind (tiles per thousand years)
+    pub migration_rate: f32,
+}
+
+impl DuneMap {
+    /// Number of tiles with the given dune type
+    pub fn count(&self, kind: DuneType) -> usize {
+        self.dune.iter().filter(|(_, _, &d)| d == kind).count()
+    }
+
+    /// Unit vector the wind blows towards on row `y`
+    pub fn wind(&self, y: usize) -> (f32, f32) {
+        prevailing_wind(y, self.dune.height)
+    }
+
+    /// Tiles from `(x, y)` downwind to the leading edge of its dune field (0 off the field)
+    pub fn front_distance(&self, x: usize, y: usize) -> usize {
+        let (wx, wy) = self.wind(y);
+        let mut distance = 0;
+        while distance < MAX_FRONT_DISTANCE {
+            let step = (distance + 1) as f32;
+            let ny = (y as f32 + wy * step).round();
+            if ny < 0.0 || ny >= self.dune.height as f32 {
+                break;
+            }
+            let (nx, ny) = self.dune.wrap_coords((x as f32 + wx * step).round() as i32, ny as i32);
+            if *self.dune.get(nx, ny) == DuneType::None {
+                break;
+            }
+            distance += 1;
+        }
+        if *self.dune.get(x, y) == DuneType::None {
+            0
+        } else {
+            distance + 1
+        }
+    }
+
+    /// Year the advancing sand reached `(x, y)`, if that happened between `start` and `end`
+    pub fn burial_year(&self, x: usize, y: usize, start: Year, end: Year) -> Option<Year> {
+        if *self.dune.get(x, y) == DuneType::None || self.migration_rate <= 0.0 {
+            return None;
+        }
+        let years_ago = self.front_distance(x, y) as f32 / self.migration_rate * 1000.0;
+        let year = end.0 - years_ago as i32;
+        (year >= start.0).then_some(Year(year))
+    }
+
+    /// Short description for tile info panels
+    pub fn describe(&self, x: usize, y: usize) -> Option<String> {
+        match *self.dune.get(x, y) {
+            DuneType::None => None,
+            kind => Some(format!("{} dunes drifting {}", kind.name(), compass(self.wind(y)))),
+        }
+    }
+}
+
+/// Unit vector the prevailing wind blows towards on row `y` of a map `height` rows tall
+pub fn prevailing_wind(y: usize, height: usize) -> (f32, f32) {
+    let (wx, wy) = climate::get_prevailing_wind(latitude_normalized(y, height));
+    let len = (wx * wx + wy * wy).sqrt().max(f32::EPSILON);
+    (wx / len, wy / len)
+}
+
+/// Latitude as the climate model measures it (0 = equator, 1 = pole)
+fn latitude_normalized(y: usize, height: usize) -> f32 {
+    (y as f32 / height as f32 - 0.5).abs() * 2.0
+}
+
+/// How changeable the wind is (0 = steady belt, 1 = where two belts meet)
+pub fn wind_variability(latitude_normalized: f32) -> f32 {
+    BELT_EDGES
+        .iter()
+        .map(|edge| 1.0 - (latitude_normalized - edge).abs() / BELT_MIXING)
+        .fold(0.0f32, f32::max)
+}
+
+/// Compass direction a wind vector points to (y grows southwards)
+fn compass((wx, wy): (f32, f32)) -> &'static str {
+    const NAMES: [&str; 8] = ["east", "southeast", "south", "southwest", "west", "northwest", "north", "northeast"];
+    let octant = (wy.atan2(wx) / std::f32::consts::FRAC_PI_4).round().rem_euclid(8.0) as usize;
+    NAMES[octant % 8]
+}
+
+fn is_sandy(biome: ExtendedBiome) -> bool {
+    matches!(biome, ExtendedBiome::Desert | ExtendedBiome::SingingDunes)
+}
+
+// =============================================================================
+// DUNE FIELDS
+// =============================================================================
+
+/// Find dune fields in sandy deserts and choose each tile's dune type
+pub fn generate_dunes(heightmap: &Tilemap<f32>, biomes: &Tilemap<ExtendedBiome>, config: &DuneConfig) -> DuneMap {
+    let (width, height) = (heightmap.width, heightmap.height);
+    let mut sandy = Tilemap::new_with(width, height, false);
+    for (x, y, &h) in heightmap.iter() {
+        sandy.set(x, y, h > 0.0 && is_sandy(*biomes.get(x, y)));
+    }
+
+    let mut dune = Tilemap::new_with(width, height, DuneType::None);
+    let mut supply = Tilemap::new_with(width, height, 0.0f32);
+    let r = config.supply_radius.max(0);
+    for y in 0..height {
+        for x in 0..width {
+            if !*sandy.get(x, y) {
+                continue;
+            }
+            let (mut sand, mut total) = (0usize, 0usize);
+            for dy in -r..=r {
+                let ny = y as i32 + dy;
+                if ny < 0 || ny >= height as i32 {
+                    continue;
+                }
+                for dx in -r..=r {
+                    let (nx, ny) = sandy.wrap_coords(x as i32 + dx, ny);
+                    total += 1;
+                    if *sandy.get(nx, ny) {
+                        sand += 1;
+                    }
+                }
+            }
+            let s = sand as f32 / total as f32;
+            supply.set(x, y, s);
+
+            let kind = if s < config.barchan_max_supply {
+                DuneType::Barchan
+            } else if wind_variability(latitude_normalized(y, height)) >= config.star_min_variability {
+                DuneType::Star
+            } else {
+                DuneType::Longitudinal
+            };
+            dune.set(x, y, kind);
+        }
+    }
+
+    DuneMap { dune, supply, migration_rate: config.migration_rate }
+}
+
+// =============================================================================
+// MIGRATION
+// =============================================================================
+
+/// Bury the settlements and trade roads that migrating dunes overran during
+/// recorded history. Returns the number of events added.
+#[cfg(feature = "history")]
+pub fn apply_dune_history(history: &mut WorldHistory, dunes: &DuneMap) -> usize {
+    let (start, end) = match (history.timeline.eras.first(), history.timeline.eras.last()) {
+        (Some(first), Some(last)) => (first.start, last.end),
+        _ => return 0,
+    };
+    let mut added = 0;
+
+    let mut settlement_ids: Vec<_> = history.territories.settlements.keys().copied().collect();
+    settlement_ids.sort_by_key(|id| id.0);
+    for id in settlement_ids {
+        let settlement = &history.territories.settlements[&id];
+        let Some(year) = dunes.burial_year(settlement.x, settlement.y, start, end) else {
+            continue;
+        };
+        if year <= settlement.founded || settlement.abandoned.is_some_and(|a| a <= year) {
+            continue;
+        }
+        let (x, y) = (settlement.x, settlement.y);
+        let name = settlement.name.clone();
+        let faction = settlement.current_faction.or(Some(settlement.original_faction));
+
+        let settlement = history.territories.settlements.get_mut(&id).expect("id was collected above");
+        settlement.state = SettlementState::Ruined;
+        settlement.abandoned = Some(year);
+        settlement.abandonment_reason = Some(AbandonmentReason::NaturalDisaster);
+        if let Some(occupation) = settlement.occupations.last_mut().filter(|o| o.2.is_none()) {
+            occupation.2 = Some(year);
+        }
+
+        let event_id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id: event_id,
+            year,
+            event_type: EventType::SandBurial,
+            faction,
+            other_faction: None,
+            location: Some((x, y)),
+            settlement: Some(id),
+            name: format!("Burial of {}", name),
+            description: format!(
+                "{} dunes crept over {} until its last people left",
+                dunes.dune.get(x, y).name(),
+                name
+            ),
+            casualties: 0,
+            has_evidence: EventType::SandBurial.leaves_evidence(),
+        });
+        added += 1;
+    }
+
+    let mut route_ids: Vec<_> = history.trade.routes.keys().copied().collect();
+    route_ids.sort_by_key(|id| id.0);
+    for id in route_ids {
+        let route = &history.trade.routes[&id];
+        // The road is cut where the sand first reaches it
+        let cut = route
+            .path
+            .iter()
+            .filter_map(|&(x, y)| dunes.burial_year(x, y, start, end).map(|year| (year, (x, y))))
+            .filter(|(year, _)| *year > route.established)
+            .min_by_key(|(year, _)| *year);
+        let Some((year, (x, y))) = cut else {
+            continue;
+        };
+        if route.abandoned.is_some_and(|a| a <= year) {
+            continue;
+        }
+        let place = |(sx, sy): (usize, usize)| {
+            history.settlement_at(sx, sy).map(|s| s.name.clone()).unwrap_or_else(|| format!("({}, {})", sx, sy))
+        };
+        let (from, to) = (place(route.start), place(route.end));
+        let faction = history.faction_at(x, y).map(|f| f.id);
+
+        let route = history.trade.routes.get_mut(&id).expect("id was collected above");
+        route.active = false;
+        route.abandoned = Some(year);
+
+        let event_id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id: event_id,
+            year,
+            event_type: EventType::SandBurial,
+            faction,
+            other_faction: None,
+            location: Some((x, y)),
+            settlement: None,
+            name: format!("Burial of the {}-{} Road", from, to),
+            description: format!("Drifting sand swallowed the road between {} and {}", from, to),
+            casualties: 0,
+            has_evidence: EventType::SandBurial.leaves_evidence(),
+        });
+        added += 1;
+    }
+
+    added
+}
+
+// =============================================================================
+// RELIEF PATTERNS
+// =============================================================================
+
+/// Dune relief at `(x, y)`: 0 on the interdune floor, 1 on the highest crests.
+/// Coordinates are measured in dune spacings, so callers choose the scale; `wind`
+/// is the unit vector the wind blows towards.
+pub fn dune_relief(kind: DuneType, wind: (f32, f32), x: f64, y: f64, seed: u64) -> f32 {
+    let (wx, wy) = (wind.0 as f64, wind.1 as f64);
+    let along = x * wx + y * wy;
+    let across = -x * wy + y * wx;
+
+    match kind {
+        DuneType::None => 0.0,
+        DuneType::Longitudinal => {
+            // Parallel ridges that wander a little and swell and thin along their length
+            let phase = hash01(0, 0, seed) * TAU;
+            let c = across + 0.18 * (along * 0.45 + phase).sin() + 0.08 * (along * 1.3 + 2.0 * phase).sin();
+            let ridge = 1.0 - (2.0 * (c - c.floor()) - 1.0).abs();
+            let swell = 0.75 + 0.25 * (along * 0.3 + hash01(c.floor() as i64, 1, seed) * TAU).sin();
+            (ridge.powf(1.5) * swell) as f32
+        }
+        DuneType::Barchan => cell_max(along, across, seed, |da, dc, cell| {
+            // Not every cell holds a dune: barchans stand apart on bare ground
+            if cell[3] > 0.75 {
+                return 0.0;
+            }
+            // A disc with its downwind side scooped out leaves a crescent whose
+            // horns trail downwind and whose hollow is the steep slip face
+            let r = 0.2 + 0.1 * cell[2];
+            let body = 1.0 - (da * da + dc * dc).sqrt() / r;
+            let hollow = ((da - 0.5 * r).powi(2) + dc * dc).sqrt();
+            let cut = ((hollow - 0.8 * r) / (0.15 * r)).clamp(0.0, 1.0);
+            (body.max(0.0) * cut / 0.7).min(1.0)
+        }),
+        DuneType::Star => cell_max(along, across, seed, |da, dc, cell| {
+            // A central peak with three to five radiating arms
+            let arms = 3.0 + (cell[3] * 3.0).floor();
+            let theta = dc.atan2(da) + cell[2] * TAU;
+            let reach = 0.18 + 0.27 * (arms * theta / 2.0).cos().abs().powi(4);
+            let r = (da * da + dc * dc).sqrt();
+            (1.0 - r / reach).max(0.0).powf(1.2)
+        }),
+    }
+}
+
+/// Highest dune among the jittered unit cells around `(a, c)`; `dune` gets the
+/// offset from its cell's centre and four per-cell random numbers
+fn cell_max(a: f64, c: f64, seed: u64, dune: impl Fn(f64, f64, [f64; 4]) -> f64) -> f32 {
+    let (ia, ic) = (a.floor() as i64, c.floor() as i64);
+    let mut best = 0.0f64;
+    for i in ia - 1..=ia + 1 {
+        for j in ic - 1..=ic + 1 {
+            let cell = [
+                hash01(i, j, seed),
+                hash01(i, j, seed.wrapping_add(1)),
+                hash01(i, j, seed.wrapping_add(2)),
+                hash01(i, j, seed.wrapping_add(3)),
+            ];
+            let ca = i as f64 + 0.5 + 0.5 * (cell[0] - 0.5);
+            let cc = j as f64 + 0.5 + 0.5 * (cell[1] - 0.5);
+            best = best.max(dune(a - ca, c - cc, cell));
+        }
+    }
+    best as f32
+}
+
+/// Deterministic hash of a cell to [0, 1)
+fn hash01(i: i64, j: i64, seed: u64) -> f64 {
+    let mut h = seed ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (j as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xC4CE_B9FE_1A85_EC53);
+    h ^= h >> 33;
+    (h >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "history")]
+    use crate::history::territories::{Settlement, TerritoryRegistry};
+    #[cfg(feature = "history")]
+    use crate::history::timeline::Era;
+    #[cfg(feature = "history")]
+    use crate::history::trade::TradeRoute;
+    #[cfg(feature = "history")]
+    use crate::history::types::*;
+
+    /// A 64x64 world: a big erg in the west, one lone sandy tile in the east
+    fn desert() -> (Tilemap<f32>, Tilemap<ExtendedBiome>) {
+        let heightmap = Tilemap::new_with(64, 64, 200.0f32);
+        let mut biomes = Tilemap::new_with(64, 64, ExtendedBiome::TemperateGrassland);
+        for y in 0..64 {
+            for x in 4..36 {
+                biomes.set(x, y, ExtendedBiome::Desert);
+            }
+        }
+        biomes.set(50, 24, ExtendedBiome::Desert);
+        (heightmap, biomes)
+    }
+
+    #[test]
+    fn test_sand_and_wind_choose_dune_types() {
+        let (heightmap, biomes) = desert();
+        let dunes = generate_dunes(&heightmap, &biomes, &DuneConfig::default());
+
+        assert_eq!(*dunes.dune.get(40, 24), DuneType::None);
+        assert_eq!(*dunes.dune.get(50, 24), DuneType::Barchan);
+        // Row 24 lies in the steady trade winds, row 21 where they meet the westerlies
+        assert!(wind_variability(latitude_normalized(24, 64)) < 0.1);
+        assert_eq!(*dunes.dune.get(20, 24), DuneType::Longitudinal);
+        assert_eq!(*dunes.dune.get(20, 21), DuneType::Star);
+        assert_eq!(dunes.describe(20, 24).as_deref(), Some("Longitudinal dunes drifting west"));
+
+        // Longitudinal ridges run with the wind: relief changes little along it
+        let wind = dunes.wind(24);
+        let (wx, wy) = (wind.0 as f64 * 0.05, wind.1 as f64 * 0.05);
+        let relief = |dx: f64, dy: f64| dune_relief(DuneType::Longitudinal, wind, 0.3 + dx, 0.3 + dy, 5);
+        let spread = |dx: f64, dy: f64| {
+            (0..20).map(|i| (relief(dx * i as f64, dy * i as f64) - relief(0.0, 0.0)).abs()).fold(0.0f32, f32::max)
+        };
+        assert!(spread(wx, wy) < spread(-wy, wx));
+        for kind in [DuneType::Barchan, DuneType::Longitudinal, DuneType::Star] {
+            for i in 0..200 {
+                let v = dune_relief(kind, wind, i as f64 * 0.137, i as f64 * 0.071, 3);
+                assert!((0.0..=1.0).contains(&v));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_migrating_dunes_bury_settlements_and_roads() {
+        let (heightmap, biomes) = desert();
+        let dunes = generate_dunes(&heightmap, &biomes, &DuneConfig::default());
+        let mut history = WorldHistory::empty();
+        history.timeline.eras.push(Era {
+            name: "Age of Sand".to_string(),
+            era_type: EraType::GoldenAge,
+            start: Year(-3000),
+            end: Year(0),
+            events: Vec::new(),
+        });
+        history.territories = TerritoryRegistry::new(64, 64);
+        let settlement = |id: u32, x: usize, founded: i32| Settlement {
+            id: SettlementId(id),
+            name: format!("Town{}", id),
+            settlement_type: SettlementType::Town,
+            original_faction: FactionId(0),
+            current_faction: Some(FactionId(0)),
+            x,
+            y: 24,
+            size: 1,
+            state: SettlementState::Thriving,
+            founded: Year(founded),
+            abandoned: None,
+            abandonment_reason: None,
+            peak_population: 500,
+            architecture: ArchitectureStyle::Rustic,
+            occupations: vec![(FactionId(0), Year(founded), None)],
+        };
+        // Wind blows west on row 24, so the leading edge is the erg's west side:
+        // x = 6 is 3 tiles behind it (buried 1500 years ago at 2 tiles per millennium)
+        history.territories.add_settlement(settlement(0, 6, -2500));
+        history.territories.add_settlement(settlement(1, 8, -500));
+        history.territories.add_settlement(settlement(2, 40, -2500));
+        let route_id = history.trade.new_id();
+        history.trade.routes.insert(route_id, TradeRoute {
+            id: route_id,
+            start: (40, 24),
+            end: (8, 24),
+            path: (8..=40).map(|x| (x, 24)).collect(),
+            active: true,
+            established: Year(-2800),
+            abandoned: None,
+            resources: Vec::new(),
+            waypoints: Vec::new(),
+        });
+
+        let added = apply_dune_history(&mut history, &dunes);
+        assert_eq!(added, 2);
+        let buried = &history.territories.settlements[&SettlementId(0)];
+        assert_eq!(buried.state, SettlementState::Ruined);
+        assert_eq!(buried.abandoned, Some(Year(-1500)));
+        // Founded on sand that had already arrived; off the erg entirely
+        assert!(history.territories.settlements[&SettlementId(1)].is_active());
+        assert!(history.territories.settlements[&SettlementId(2)].is_active());
+        let route = &history.trade.routes[&route_id];
+        assert!(!route.active && route.abandoned.is_some());
+        assert!(history.timeline.events_at(6, 24).iter().any(|e| e.event_type == EventType::SandBurial));
+    }
+}
@@ -0,0 +1,680 @@
+//! World aging: the same world N thousand years later
+//!
+//! `advance_ages` fast-forwards a generated world through the long, slow
+//! processes that turn a living map into an ancient one:
+//! - Sea level drifts up or down, drowning coasts or exposing shelves
+//! - Rain keeps eroding the land, and the rivers re-route over the new terrain
+//! - Earthquakes keep striking the faults, breaking the ground and bringing
+//!   down slopes
+//! - Standing structures decay: walls fall to ruins, floors and roads are
+//!   buried under vegetation, and forest reclaims the grassland around them,
+//!   growing back stage by stage from the year each field was abandoned
+//! - Settlements empty out one by one and the factions that built them fade;
+//!   every abandoned site is left as a ruin on the surface
+//!
+//! - Scenario events that fall due in the span strike on schedule
+//!
+//! Every date in the history moves back by the same span, so the chronicle
+//! still explains the ruins: they are the settlements it describes, left to
+//! the elements when their people went.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::biome_feathering::{self, FeatherConfig};
+use crate::biomes::ExtendedBiome;
+use crate::budget::ResourceBudget;
+use crate::climate::Biome;
+use crate::erosion::{self, RiverErosionParams};
+use crate::exploration::ExplorationRecord;
+use crate::gazetteer;
+use crate::history::types::{AbandonmentReason, EraType, SettlementState, Year};
+use crate::history::{Era, EventType, HistoricalEvent, WorldHistory};
+use crate::lakes;
+use crate::names::NameLayer;
+use crate::scenario;
+use crate::seismic::{self, SeismicConfig};
+use crate::succession::{self, SuccessionConfig};
+use crate::tilemap::Tilemap;
+use crate::water_bodies;
+use crate::waves::{self, WaveConfig};
+use crate::world::WorldData;
+use crate::zlevel::{self, ZTile, MAX_Z, MIN_Z, SEA_LEVEL_Z};
+
+/// Mixes the span into the world seed so different spans age differently
+const AGING_SEED_MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Uniform rock hardness, as in the generation pipeline's erosion passes
+const HARDNESS: f32 = 0.3;
+
+/// Rates of the long-term processes
+#[derive(Clone, Debug)]
+pub struct AgingConfig {
+    /// Largest sea-level change per thousand years (m), up or down
+    pub sea_drift_per_kyr: f32,
+    /// Cap on the total sea-level change (m)
+    pub max_sea_drift: f32,
+    /// River erosion passes per thousand years
+    pub erosion_passes_per_kyr: f32,
+    /// Cap on river erosion passes, however long the span
+    pub max_erosion_passes: usize,
+    /// Share of exposed structure tiles that decay per thousand years
+    pub decay_per_kyr: f32,
+    /// Moisture above which ruins are overgrown and grassland becomes forest
+    pub regrowth_moisture: f32,
+    /// Years over which half of the remaining settlements are abandoned
+    pub settlement_half_life: f32,
+    /// Worker threads the aging passes may use
+    pub budget: ResourceBudget,
+}
+
+impl Default for AgingConfig {
+    fn default() -> Self {
+        Self {
+            sea_drift_per_kyr: 6.0,
+            max_sea_drift: 60.0,
+            erosion_passes_per_kyr: 0.5,
+            max_erosion_passes: 5,
+            decay_per_kyr: 0.6,
+            regrowth_moisture: 0.4,
+            settlement_half_life: 1500.0,
+            budget: ResourceBudget::default(),
+        }
+    }
+}
+
+/// What changed while the world aged
+#[derive(Clone, Debug, Default)]
+pub struct AgingReport {
+    pub years: u32,
+    /// Sea-level change (m, positive = the sea rose)
+    pub sea_level_change: f32,
+    /// Land tiles drowned by the sea
+    pub flooded: usize,
+    /// Seabed tiles left dry
+    pub emerged: usize,
+    /// Material carried off by the rivers (m, summed over tiles)
+    pub eroded: f64,
+    /// River segments before and after the rivers re-routed
+    pub rivers_before: usize,
+    pub rivers_after: usize,
+    /// Earthquakes that struck during the span
+    pub earthquakes: usize,
+    /// Scenario events that fell due during the span
+    pub scripted: usize,
+    /// Structure tiles that fell to ruin or were overgrown
+    pub decayed: usize,
+    /// Abandoned settlement sites left as ruins on the surface
+    pub ruins: usize,
+    /// Tiles around ruins that returned to forest
+    pub reforested: usize,
+    /// Settlements abandoned during the span
+    pub settlements_abandoned: usize,
+    /// Factions that died out during the span
+    pub factions_collapsed: usize,
+}
+
+impl AgingReport {
+    /// One-paragraph summary for the CLI
+    pub fn summary(&self) -> String {
+        format!(
+            "{} years later: sea {} {:.0} m ({} tiles flooded, {} emerged), {:.0} m eroded, \
+             {} -> {} river segments, {} earthquakes, {} scenario events, {} structure tiles decayed, {} settlement ruins, {} tiles reforested, \
+             {} settlements abandoned, {} factions gone",
+            self.years,
+            if self.sea_level_change >= 0.0 { "rose" } else { "fell" },
+            self.sea_level_change.abs(),
+            self.flooded,
+            self.emerged,
+            self.eroded,
+            self.rivers_before,
+            self.rivers_after,
+            self.earthquakes,
+            self.scripted,
+            self.decayed,
+            self.ruins,
+            self.reforested,
+            self.settlements_abandoned,
+            self.factions_collapsed
+        )
+    }
+}
+
+/// Age `world` by `years` with the default rates
+pub fn advance_ages(world: &mut WorldData, years: u32) -> AgingReport {
+    advance_ages_with(world, years, &AgingConfig::default())
+}
+
+/// Age `world` by `years`, within the thread limit of `config.budget`
+pub fn advance_ages_with(world: &mut WorldData, years: u32, config: &AgingConfig) -> AgingReport {
+    config.budget.install(|| age_world(world, years, config))
+}
+
+fn age_world(world: &mut WorldData, years: u32, config: &AgingConfig) -> AgingReport {
+    let mut report = AgingReport { years, ..Default::default() };
+    if years == 0 {
+        return report;
+    }
+    let kyr = years as f32 / 1000.0;
+    let seed = world.seed ^ (years as u64).wrapping_mul(AGING_SEED_MIX);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let old_heightmap = world.heightmap.clone();
+    let old_surface = world.surface_z.clone();
+
+    // Sea level drift: lowering the land is raising the sea
+    let drift = rng.gen_range(-1.0f32..=1.0) * config.sea_drift_per_kyr * kyr;
+    report.sea_level_change = drift.clamp(-config.max_sea_drift, config.max_sea_drift);
+    for (_, _, h) in world.heightmap.iter_mut() {
+        *h -= report.sea_level_change;
+    }
+
+    // Earthquakes, dated in the old age's calendar until the history shifts below
+    let quakes = match world.seismic {
+        Some(ref mut seismic) => seismic::trigger_earthquakes(
+            seismic,
+            &mut world.heightmap,
+            &world.plate_map,
+            (Year(0), Year(years as i32)),
+            &SeismicConfig::default(),
+            seed,
+        ),
+        None => Vec::new(),
+    };
+    report.earthquakes = quakes.len();
+
+    // Scenario events of the span, in the same calendar; comets strike before the rivers re-route
+    let (elapsed, due) = match world.scenario {
+        Some(ref state) => (state.elapsed, state.scenario.between(state.elapsed, state.elapsed + years as i32)),
+        None => (0, Vec::new()),
+    };
+    let impacts = scenario::carve_comet_strikes(&mut world.heightmap, &due);
+
+    // Rivers cut down again, pits fill with sediment, and the rivers find their new courses
+    let passes = ((kyr * config.erosion_passes_per_kyr).round() as usize).min(config.max_erosion_passes);
+    if passes > 0 {
+        let hardness = Tilemap::new_with(world.width, world.height, HARDNESS);
+        let params = RiverErosionParams { passes, ..Default::default() };
+        report.eroded = erosion::rivers::erode_rivers(&mut world.heightmap, &hardness, &params).total_eroded;
+        world.heightmap = erosion::rivers::fill_depressions_public(&world.heightmap);
+    }
+    report.rivers_before = world.river_network.as_ref().map_or(0, |n| n.segments.len());
+    let rivers = erosion::trace_bezier_rivers(&world.heightmap, None, seed);
+    report.rivers_after = rivers.segments.len();
+    world.river_network = Some(rivers);
+
+    // Vegetation keeps growing back; land that rose from the sea starts over, on average mid-span
+    if let Some(ref mut succession) = world.succession {
+        succession.advance(years);
+    }
+
+    // Coastlines: drowned land and exposed shelf take the biome of their new elevation
+    for y in 0..world.height {
+        for x in 0..world.width {
+            let (before, after) = (*old_heightmap.get(x, y), *world.heightmap.get(x, y));
+            let (flooded, emerged) = (before >= 0.0 && after < 0.0, before < 0.0 && after >= 0.0);
+            if flooded || emerged {
+                let base = Biome::classify(after, *world.temperature.get(x, y), *world.moisture.get(x, y));
+                world.biomes.set(x, y, ExtendedBiome::from_base(base));
+                if let Some(ref mut succession) = world.succession {
+                    succession.reset(x, y, emerged.then(|| ExtendedBiome::from_base(base)), years / 2);
+                }
+            }
+            report.flooded += flooded as usize;
+            report.emerged += emerged as usize;
+        }
+    }
+    scenario::mark_crater_biomes(&mut world.biomes, &world.heightmap, &impacts);
+
+    // Z-level columns follow the new surface
+    for y in 0..world.height {
+        for x in 0..world.width {
+            let elevation = *world.heightmap.get(x, y);
+            let (before, after) = (*old_surface.get(x, y), zlevel::height_to_z(elevation).clamp(MIN_Z, MAX_Z));
+            rebuild_column(world, x, y, before, after, elevation);
+        }
+    }
+
+    if let Some(ref mut history) = world.history {
+        if let Some(ref seismic) = world.seismic {
+            seismic::record_earthquakes(history, &quakes, seismic, &SeismicConfig::default());
+        }
+        report.scripted = scenario::inject_events(history, &due, elapsed);
+        let (abandoned, collapsed) = age_history(history, years, &old_heightmap, &world.heightmap, config, &mut rng);
+        report.settlements_abandoned = abandoned;
+        report.factions_collapsed = collapsed;
+    }
+    if let Some(ref mut seismic) = world.seismic {
+        for quake in &mut seismic.earthquakes {
+            quake.year.0 -= years as i32;
+        }
+    }
+    if let Some(ref mut state) = world.scenario {
+        state.elapsed += years as i32;
+    }
+    report.decayed = decay_structures(world, kyr, config, &mut rng);
+    report.ruins = mark_settlement_ruins(world, config);
+    report.reforested = match world.succession {
+        Some(ref mut succession) => {
+            // Fields of the settlements abandoned in the span regrow from the year they were left
+            let succession_config = SuccessionConfig::default();
+            if let Some(ref history) = world.history {
+                succession::apply_history_disturbances(succession, history, &succession_config);
+            }
+            succession::apply_succession_biomes(&mut world.biomes, succession, &succession_config)
+        }
+        None => reforest_ruins(world, config),
+    };
+
+    let (water_body_map, mut water_bodies) = water_bodies::detect_water_bodies(&world.heightmap);
+    if let Some(ref mut record) = world.exploration {
+        shift_exploration(record, -(years as i32));
+    }
+    world.gazetteer = Some(gazetteer::name_water_bodies(
+        &world.heightmap,
+        &water_body_map,
+        &mut water_bodies,
+        world.history.as_ref(),
+        seed,
+    ));
+    world.lakes = Some(lakes::build_lake_graph(
+        &world.heightmap,
+        &water_body_map,
+        &water_bodies,
+        &world.moisture,
+        &world.temperature,
+        world.scale.km_per_tile,
+    ));
+    world.water_body_map = water_body_map;
+    world.water_bodies = water_bodies;
+    if world.waves.is_some() {
+        // Sea level drift moved the coast: measure the waves on it again
+        world.waves = Some(waves::measure_waves(&world.heightmap, world.scale.km_per_tile, &WaveConfig::default()));
+    }
+    if world.biome_feather_map.is_some() {
+        world.biome_feather_map =
+            Some(biome_feathering::compute_biome_feathering(&world.biomes, &FeatherConfig::default(), seed));
+    }
+    world.names = Some(NameLayer::of(world));
+
+    report
+}
+
+/// Move a column's surface from `before` to `after`: cut-down ground opens to
+/// air (or sea), built-up ground buries whatever stood on it, and water
+/// follows sea level above the new surface.
+fn rebuild_column(world: &mut WorldData, x: usize, y: usize, before: i32, after: i32, elevation: f32) {
+    let zlevels = &mut world.zlevels;
+    for z in (after + 1)..=before {
+        zlevels.set(x, y, z, ZTile::Air);
+    }
+    for z in before..after {
+        let tile = *zlevels.get(x, y, z);
+        if matches!(tile, ZTile::Surface | ZTile::Air | ZTile::Water) {
+            zlevels.set(x, y, z, ZTile::Solid);
+        }
+    }
+    if after != before {
+        zlevels.set(x, y, after, ZTile::Surface);
+    }
+    for z in (after + 1)..=SEA_LEVEL_Z {
+        match (*zlevels.get(x, y, z), elevation < 0.0) {
+            (ZTile::Air, true) => zlevels.set(x, y, z, ZTile::Water),
+            (ZTile::Water, false) => zlevels.set(x, y, z, ZTile::Air),
+            _ => {}
+        }
+    }
+    world.surface_z.set(x, y, after);
+}
+
+/// Decay exposed structure tiles: walls fall, roofs and doors become rubble,
+/// and floors and roads are overgrown where it is wet enough
+fn decay_structures(world: &mut WorldData, kyr: f32, config: &AgingConfig, rng: &mut ChaCha8Rng) -> usize {
+    let chance = 1.0 - (-config.decay_per_kyr * kyr).exp();
+    let mut decayed = 0;
+    for y in 0..world.height {
+        for x in 0..world.width {
+            let surface = *world.surface_z.get(x, y);
+            let wet = *world.moisture.get(x, y) >= config.regrowth_moisture;
+            for z in surface.max(MIN_Z)..=(surface + 3).min(MAX_Z) {
+                let tile = *world.zlevels.get(x, y, z);
+                let ruin = match tile {
+                    ZTile::StoneWall | ZTile::BrickWall | ZTile::WoodWall | ZTile::FortressWall => ZTile::RuinedWall,
+                    ZTile::Door | ZTile::Window | ZTile::Column | ZTile::FortressGate => ZTile::Rubble,
+                    ZTile::HeroStatue => ZTile::RuinedStatue,
+                    ZTile::StoneFloor
+                    | ZTile::WoodFloor
+                    | ZTile::CobblestoneFloor
+                    | ZTile::DirtFloor
+                    | ZTile::DirtRoad
+                    | ZTile::StoneRoad => {
+                        if wet {
+                            ZTile::OvergrownGarden
+                        } else {
+                            ZTile::Rubble
+                        }
+                    }
+                    _ => continue,
+                };
+                if rng.gen::<f32>() < chance {
+                    world.zlevels.set(x, y, z, ruin);
+                    decayed += 1;
+                }
+            }
+        }
+    }
+    decayed
+}
+
+/// Leave a ruin on the site of every abandoned settlement that no structure
+/// marks yet: overgrown where it is wet, rubble elsewhere
+fn mark_settlement_ruins(world: &mut WorldData, config: &AgingConfig) -> usize {
+    let Some(ref history) = world.history else {
+        return 0;
+    };
+    let mut marked = 0;
+    for settlement in history.territories.settlements.values().filter(|s| !s.is_active()) {
+        let (x, y) = (settlement.x, settlement.y);
+        let z = *world.surface_z.get(x, y);
+        if *world.zlevels.get(x, y, z) != ZTile::Surface {
+            continue;
+        }
+        let ruin = if *world.moisture.get(x, y) >= config.regrowth_moisture { ZTile::OvergrownGarden } else { ZTile::Rubble };
+        world.zlevels.set(x, y, z, ruin);
+        marked += 1;
+    }
+    marked
+}
+
+/// Forest takes back wet grassland wherever ruins stand
+fn reforest_ruins(world: &mut WorldData, config: &AgingConfig) -> usize {
+    let mut reforested = 0;
+    for y in 0..world.height {
+        for x in 0..world.width {
+            if *world.moisture.get(x, y) < config.regrowth_moisture {
+                continue;
+            }
+            let surface = *world.surface_z.get(x, y);
+            let has_ruins = (surface.max(MIN_Z)..=(surface + 3).min(MAX_Z)).any(|z| {
+                matches!(world.zlevels.get(x, y, z), ZTile::RuinedWall | ZTile::Rubble | ZTile::OvergrownGarden | ZTile::RuinedStatue)
+            });
+            if !has_ruins {
+                continue;
+            }
+            let temperature = *world.temperature.get(x, y);
+            let forest = match world.biomes.get(x, y) {
+                ExtendedBiome::TemperateGrassland if temperature < 5.0 => ExtendedBiome::BorealForest,
+                ExtendedBiome::TemperateGrassland => ExtendedBiome::TemperateForest,
+                ExtendedBiome::Savanna => ExtendedBiome::TropicalForest,
+                _ => continue,
+            };
+            world.biomes.set(x, y, forest);
+            reforested += 1;
+        }
+    }
+    reforested
+}
+
+/// Push the whole chronicle `years` into the past, then fill the gap: most
+/// settlements are abandoned, the factions left without one fade away, and
+/// every hero of the old age dies. Returns (settlements abandoned, factions collapsed).
+fn age_history(
+    history: &mut WorldHistory,
+    years: u32,
+    old_heightmap: &Tilemap<f32>,
+    heightmap: &Tilemap<f32>,
+    config: &AgingConfig,
+    rng: &mut ChaCha8Rng,
+) -> (usize, usize) {
+    let span = years as i32;
+    shift_history(history, -span);
+    let then = Year(-span);
+    let mut new_events = Vec::new();
+
+    let survival = 0.5f32.powf(years as f32 / config.settlement_half_life);
+    let mut settlement_ids: Vec<_> = history.territories.settlements.keys().copied().collect();
+    settlement_ids.sort_by_key(|id| id.0);
+    let mut abandoned = 0;
+    for id in settlement_ids {
+        let settlement = &history.territories.settlements[&id];
+        let (x, y) = (settlement.x, settlement.y);
+        let drowned = *old_heightmap.get(x, y) >= 0.0 && *heightmap.get(x, y) < 0.0;
+        if !settlement.is_active() || (!drowned && rng.gen::<f32>() < survival) {
+            continue;
+        }
+        let year = Year(then.0 + rng.gen_range(1..=span));
+        let reason = if drowned { AbandonmentReason::NaturalDisaster } else { AbandonmentReason::Unknown };
+        let name = settlement.name.clone();
+        let faction = settlement.current_faction.or(Some(settlement.original_faction));
+
+        let settlement = history.territories.settlements.get_mut(&id).expect("id was collected above");
+        settlement.state = SettlementState::Ruined;
+        settlement.abandoned = Some(year);
+        settlement.abandonment_reason = Some(reason);
+        if let Some(occupation) = settlement.occupations.last_mut().filter(|o| o.2.is_none()) {
+            occupation.2 = Some(year);
+        }
+
+        let event_id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id: event_id,
+            year,
+            event_type: EventType::SettlementAbandoned,
+            faction,
+            other_faction: None,
+            location: Some((x, y)),
+            settlement: Some(id),
+            name: format!("Abandonment of {}", name),
+            description: if drowned {
+                format!("The rising sea crept into {} until its last people left", name)
+            } else {
+                format!("{} dwindled over the generations until no one was left", name)
+            },
+            casualties: 0,
+            has_evidence: EventType::SettlementAbandoned.leaves_evidence(),
+        });
+        new_events.push(event_id);
+        abandoned += 1;
+    }
+
+    // A faction with no settlement left fades with its last one
+    let mut faction_ids: Vec<_> = history.factions.factions.keys().copied().collect();
+    faction_ids.sort_by_key(|id| id.0);
+    let mut collapsed = 0;
+    for id in faction_ids {
+        if history.factions.factions[&id].is_collapsed() {
+            continue;
+        }
+        let held = history
+            .territories
+            .settlements
+            .values()
+            .filter(|s| s.current_faction.unwrap_or(s.original_faction) == id);
+        let (mut active, mut last) = (false, None);
+        for s in held {
+            active |= s.is_active();
+            last = last.max(s.abandoned);
+        }
+        if active {
+            continue;
+        }
+        let year = last.filter(|&y| y > then).unwrap_or(Year(then.0 + 1));
+        let faction = history.factions.factions.get_mut(&id).expect("id was collected above");
+        faction.collapsed = Some(year);
+        faction.collapse_reason = Some(AbandonmentReason::FactionCollapse);
+        let name = faction.name.clone();
+        for territory in history.territories.territories.iter_mut().filter(|t| t.faction == id && t.lost.is_none()) {
+            territory.lost = Some(year);
+        }
+        for (_, _, owner) in history.territories.territory_map.iter_mut() {
+            if *owner == Some(id) {
+                *owner = None;
+            }
+        }
+
+        let event_id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id: event_id,
+            year,
+            event_type: EventType::FactionCollapsed,
+            faction: Some(id),
+            other_faction: None,
+            location: None,
+            settlement: None,
+            name: format!("Fading of {}", name),
+            description: format!("The last of {} passed from the world", name),
+            casualties: 0,
+            has_evidence: EventType::FactionCollapsed.leaves_evidence(),
+        });
+        new_events.push(event_id);
+        collapsed += 1;
+    }
+
+    // No one of the old age lives to see the new one
+    for hero in history.heroes.heroes.values_mut().filter(|h| h.death_year.is_none()) {
+        let lifespan = rng.gen_range(1..=span.min(60));
+        hero.death_year = Some(Year(hero.birth_year.0.max(then.0) + lifespan).min(Year(0)));
+    }
+    for route in history.trade.routes.values_mut().filter(|r| r.active) {
+        route.active = false;
+        route.abandoned = Some(Year(then.0 + rng.gen_range(1..=span)));
+    }
+
+    new_events.sort_by_key(|id| history.timeline.events[id].year);
+    history.timeline.eras.push(Era {
+        name: "The Long Silence".to_string(),
+        era_type: EraType::DarkAge,
+        start: then,
+        end: Year(0),
+        events: new_events,
+    });
+    (abandoned, collapsed)
+}
+
+/// Add `delta` to every date in the history
+fn shift_history(history: &mut WorldHistory, delta: i32) {
+    let shift = |year: &mut Year| year.0 += delta;
+
+    for event in history.timeline.events.values_mut() {
+        shift(&mut event.year);
+    }
+    for era in &mut history.timeline.eras {
+        shift(&mut era.start);
+        shift(&mut era.end);
+    }
+    for faction in history.factions.factions.values_mut() {
+        shift(&mut faction.founded);
+        faction.collapsed.as_mut().map(shift);
+    }
+    for territory in &mut history.territories.territories {
+        shift(&mut territory.established);
+        territory.lost.as_mut().map(shift);
+    }
+    for settlement in history.territories.settlements.values_mut() {
+        shift(&mut settlement.founded);
+        settlement.abandoned.as_mut().map(shift);
+        for (_, from, until) in &mut settlement.occupations {
+            shift(from);
+            until.as_mut().map(shift);
+        }
+    }
+    for lair in history.monsters.lairs.values_mut() {
+        for (year, _) in &mut lair.attacks {
+            shift(year);
+        }
+    }
+    for site in &mut history.trade.resources {
+        shift(&mut site.discovered);
+        site.depleted_year.as_mut().map(shift);
+    }
+    for route in history.trade.routes.values_mut() {
+        shift(&mut route.established);
+        route.abandoned.as_mut().map(shift);
+    }
+    for hero in history.heroes.heroes.values_mut() {
+        shift(&mut hero.birth_year);
+        hero.death_year.as_mut().map(shift);
+    }
+    for artifact in history.artifacts.artifacts.values_mut() {
+        shift(&mut artifact.creation_year);
+        for record in &mut artifact.history {
+            shift(&mut record.year);
+        }
+    }
+    for dungeon in history.dungeons.dungeons.values_mut() {
+        shift(&mut dungeon.founded_year);
+        dungeon.abandoned_year.as_mut().map(shift);
+    }
+}
+
+/// Add `delta` to every date in an exploration record
+fn shift_exploration(record: &mut ExplorationRecord, delta: i32) {
+    for expedition in &mut record.expeditions {
+        expedition.year.0 += delta;
+    }
+    for discovery in &mut record.discoveries {
+        discovery.year.0 += delta;
+    }
+    for chart in &mut record.charts {
+        for (_, _, year) in chart.charted.iter_mut() {
+            if let Some(year) = year {
+                year.0 += delta;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advance_ages_ruins_match_history() {
+        let mut world = crate::world::generate_world(96, 48, 7);
+        let before = world.history.clone().unwrap();
+        let years = 5000;
+        let report = advance_ages(&mut world, years);
+        let history = world.history.as_ref().unwrap();
+
+        // The old chronicle is intact, only older
+        for (id, event) in &before.timeline.events {
+            assert_eq!(history.timeline.events[id].year.0, event.year.0 - years as i32);
+        }
+        for (id, s) in &before.territories.settlements {
+            let aged = &history.territories.settlements[id];
+            assert_eq!((aged.x, aged.y, aged.founded.0), (s.x, s.y, s.founded.0 - years as i32));
+            if s.is_active() && !aged.is_active() {
+                assert!(aged.abandoned.is_some_and(|y| y > Year(-(years as i32)) && y <= Year(0)));
+            }
+        }
+        assert!(report.settlements_abandoned > 0);
+        for s in history.territories.settlements.values().filter(|s| !s.is_active()) {
+            assert_ne!(*world.zlevels.get(s.x, s.y, *world.surface_z.get(s.x, s.y)), ZTile::Surface);
+        }
+        assert!(history.heroes.all().all(|h| h.death_year.is_some()));
+        assert_eq!(history.timeline.eras.last().unwrap().end, Year(0));
+
+        // Columns agree with the new surface
+        for y in 0..world.height {
+            for x in 0..world.width {
+                let z = *world.surface_z.get(x, y);
+                assert_eq!(z, zlevel::height_to_z(*world.heightmap.get(x, y)).clamp(MIN_Z, MAX_Z));
+                assert!(z == MAX_Z || !matches!(world.zlevels.get(x, y, z + 1), ZTile::Solid));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sea_drift_moves_the_coast() {
+        let mut world = crate::world::generate_world(64, 32, 11);
+        let config = AgingConfig {
+            sea_drift_per_kyr: 40.0,
+            max_sea_drift: 200.0,
+            erosion_passes_per_kyr: 0.0,
+            ..Default::default()
+        };
+        let report = advance_ages_with(&mut world, 4000, &config);
+        assert!(report.sea_level_change.abs() > 0.0);
+        assert!(report.flooded + report.emerged > 0);
+        assert_eq!(advance_ages(&mut world, 0).flooded, 0);
+    }
+}
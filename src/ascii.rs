@@ -8,6 +8,7 @@ use std::io::{self, Write};
 use chrono::Local;
 
 use crate::biomes::ExtendedBiome;
+use crate::names::NameLayer;
 use crate::plates::{PlateId, Plate, PlateType};
 use crate::scale::MapScale;
 use crate::tilemap::Tilemap;
@@ -51,6 +52,11 @@ impl AsciiMode {
             AsciiMode::Stress,
         ]
     }
+
+    /// Look up a mode by name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<AsciiMode> {
+        Self::all().iter().copied().find(|m| m.name().eq_ignore_ascii_case(name))
+    }
 }
 
 /// Get ASCII character for a biome
@@ -174,6 +180,9 @@ pub fn biome_char(biome: &ExtendedBiome) -> char {
         ExtendedBiome::BuriedTemple => '/',      // / for sand-covered
         ExtendedBiome::OvergrownCitadel => '\\', // \ for vine-covered
         ExtendedBiome::DarkTower => 'Ω',        // Ω for the singular dark tower
+        ExtendedBiome::WorldTree => 'Ψ',        // Ψ for the branching world tree
+        ExtendedBiome::TitanForge => '§',       // § for the titan forge
+        ExtendedBiome::StormSpire => '¶',       // ¶ for the storm spire
 
         // OCEAN BIOMES - Realistic Shallow/Coastal
         ExtendedBiome::CoralReef => '⌇',         // coral branches
@@ -217,6 +226,9 @@ pub fn biome_char(biome: &ExtendedBiome) -> char {
         ExtendedBiome::FumaroleField => '≋',     // steam vents
         ExtendedBiome::VolcanicBeach => '▪',     // black sand
         ExtendedBiome::HotSpot => '●',           // active hot spot
+        ExtendedBiome::IceSheet => '#',          // ice sheet
+        ExtendedBiome::PackIce => '=',           // pack ice floes
+        ExtendedBiome::Polynya => '~',           // open water in the ice
     }
 }
 
@@ -273,35 +285,63 @@ pub fn plate_char(plate_id: PlateId, plates: &[Plate]) -> char {
     } else {
         let plate = &plates[plate_id.0 as usize];
         let base = if plate.plate_type == PlateType::Continental { 'A' } else { 'a' };
-        let offset = (plate_id.0 % 26) as u8;
+        let offset = plate_id.0 % 26;
         (base as u8 + offset) as char
     }
 }
 
+/// Borrowed world layers for the ASCII renderers
+pub struct MapLayers<'a> {
+    pub heightmap: &'a Tilemap<f32>,
+    pub biomes: &'a Tilemap<ExtendedBiome>,
+    pub temperature: &'a Tilemap<f32>,
+    pub moisture: &'a Tilemap<f32>,
+    pub stress_map: &'a Tilemap<f32>,
+    pub plate_map: &'a Tilemap<PlateId>,
+    pub plates: &'a [Plate],
+}
+
+impl<'a> MapLayers<'a> {
+    pub fn of(world: &'a crate::world::WorldData) -> Self {
+        Self {
+            heightmap: &world.heightmap,
+            biomes: &world.biomes,
+            temperature: &world.temperature,
+            moisture: &world.moisture,
+            stress_map: &world.stress_map,
+            plate_map: &world.plate_map,
+            plates: &world.plates,
+        }
+    }
+
+    /// Colour of one tile in the given layer
+    pub fn color(&self, mode: AsciiMode, x: usize, y: usize) -> (u8, u8, u8) {
+        match mode {
+            AsciiMode::Biome => self.biomes.get(x, y).color(),
+            AsciiMode::Height => height_color(*self.heightmap.get(x, y)),
+            AsciiMode::Temperature => temperature_color(*self.temperature.get(x, y)),
+            AsciiMode::Moisture => moisture_color(*self.moisture.get(x, y)),
+            AsciiMode::Stress => stress_color(*self.stress_map.get(x, y)),
+            AsciiMode::Plates => plate_color(*self.plate_map.get(x, y), self.plates),
+        }
+    }
+}
+
 /// Render a map to ASCII string
-pub fn render_ascii_map(
-    heightmap: &Tilemap<f32>,
-    biomes: &Tilemap<ExtendedBiome>,
-    temperature: &Tilemap<f32>,
-    moisture: &Tilemap<f32>,
-    stress_map: &Tilemap<f32>,
-    plate_map: &Tilemap<PlateId>,
-    plates: &[Plate],
-    mode: AsciiMode,
-) -> String {
-    let width = heightmap.width;
-    let height = heightmap.height;
+pub fn render_ascii_map(layers: &MapLayers, mode: AsciiMode) -> String {
+    let width = layers.heightmap.width;
+    let height = layers.heightmap.height;
     let mut result = String::with_capacity((width + 1) * height);
 
     for y in 0..height {
         for x in 0..width {
             let ch = match mode {
-                AsciiMode::Biome => biome_char(biomes.get(x, y)),
-                AsciiMode::Height => height_char(*heightmap.get(x, y)),
-                AsciiMode::Temperature => temperature_char(*temperature.get(x, y)),
-                AsciiMode::Moisture => moisture_char(*moisture.get(x, y)),
-                AsciiMode::Stress => stress_char(*stress_map.get(x, y)),
-                AsciiMode::Plates => plate_char(*plate_map.get(x, y), plates),
+                AsciiMode::Biome => biome_char(layers.biomes.get(x, y)),
+                AsciiMode::Height => height_char(*layers.heightmap.get(x, y)),
+                AsciiMode::Temperature => temperature_char(*layers.temperature.get(x, y)),
+                AsciiMode::Moisture => moisture_char(*layers.moisture.get(x, y)),
+                AsciiMode::Stress => stress_char(*layers.stress_map.get(x, y)),
+                AsciiMode::Plates => plate_char(*layers.plate_map.get(x, y), layers.plates),
             };
             result.push(ch);
         }
@@ -380,21 +420,16 @@ pub fn calculate_biome_stats(biomes: &Tilemap<ExtendedBiome>) -> HashMap<Extende
 
 /// Export world data to ASCII file
 pub fn export_world_file(
-    heightmap: &Tilemap<f32>,
-    biomes: &Tilemap<ExtendedBiome>,
-    temperature: &Tilemap<f32>,
-    moisture: &Tilemap<f32>,
-    stress_map: &Tilemap<f32>,
-    plate_map: &Tilemap<PlateId>,
-    plates: &[Plate],
+    layers: &MapLayers,
     scale: &MapScale,
+    names: Option<&NameLayer>,
     seed: u64,
     path: &str,
     verbose: bool,
 ) -> io::Result<()> {
     let mut file = File::create(path)?;
-    let width = heightmap.width;
-    let height = heightmap.height;
+    let width = layers.heightmap.width;
+    let height = layers.heightmap.height;
     let total = width * height;
 
     // Header
@@ -408,16 +443,17 @@ pub fn export_world_file(
 
     // Biome map
     writeln!(file, "=== MAP (Biome View) ===")?;
-    let map_str = render_ascii_map(
-        heightmap, biomes, temperature, moisture, stress_map, plate_map, plates,
-        AsciiMode::Biome,
-    );
+    let map_str = render_ascii_map(layers, AsciiMode::Biome);
     write!(file, "{}", map_str)?;
     writeln!(file)?;
 
     // Legend
     write!(file, "{}", biome_legend())?;
     writeln!(file)?;
+    if let Some(names) = names {
+        write!(file, "{}", names.legend())?;
+        writeln!(file)?;
+    }
 
     // Statistics
     writeln!(file, "=== STATISTICS ===")?;
@@ -428,7 +464,7 @@ pub fn export_world_file(
     let mut water_count = 0;
     for y in 0..height {
         for x in 0..width {
-            if *heightmap.get(x, y) > 0.0 {
+            if *layers.heightmap.get(x, y) > 0.0 {
                 land_count += 1;
             } else {
                 water_count += 1;
@@ -441,7 +477,7 @@ pub fn export_world_file(
 
     // Biome distribution
     writeln!(file, "Biome Distribution:")?;
-    let stats = calculate_biome_stats(biomes);
+    let stats = calculate_biome_stats(layers.biomes);
     let mut sorted_stats: Vec<_> = stats.iter().collect();
     sorted_stats.sort_by(|a, b| b.1.cmp(a.1)); // Sort by count descending
     for (biome, count) in sorted_stats {
@@ -456,7 +492,7 @@ pub fn export_world_file(
     let mut sum_h = 0.0f64;
     for y in 0..height {
         for x in 0..width {
-            let h = *heightmap.get(x, y);
+            let h = *layers.heightmap.get(x, y);
             min_h = min_h.min(h);
             max_h = max_h.max(h);
             sum_h += h as f64;
@@ -471,7 +507,7 @@ pub fn export_world_file(
     let mut max_t = f32::MIN;
     for y in 0..height {
         for x in 0..width {
-            let t = *temperature.get(x, y);
+            let t = *layers.temperature.get(x, y);
             min_t = min_t.min(t);
             max_t = max_t.max(t);
         }
@@ -480,9 +516,9 @@ pub fn export_world_file(
     writeln!(file)?;
 
     // Plate stats
-    let continental = plates.iter().filter(|p| p.plate_type == PlateType::Continental).count();
-    let oceanic = plates.iter().filter(|p| p.plate_type == PlateType::Oceanic).count();
-    writeln!(file, "Plates: {} total ({} continental, {} oceanic)", plates.len(), continental, oceanic)?;
+    let continental = layers.plates.iter().filter(|p| p.plate_type == PlateType::Continental).count();
+    let oceanic = layers.plates.iter().filter(|p| p.plate_type == PlateType::Oceanic).count();
+    writeln!(file, "Plates: {} total ({} continental, {} oceanic)", layers.plates.len(), continental, oceanic)?;
     writeln!(file)?;
 
     // Verbose tile data
@@ -491,11 +527,11 @@ pub fn export_world_file(
         writeln!(file, "[x,y,elevation,temperature,moisture,biome,stress]")?;
         for y in 0..height {
             for x in 0..width {
-                let h = *heightmap.get(x, y);
-                let t = *temperature.get(x, y);
-                let m = *moisture.get(x, y);
-                let b = biomes.get(x, y).display_name();
-                let s = *stress_map.get(x, y);
+                let h = *layers.heightmap.get(x, y);
+                let t = *layers.temperature.get(x, y);
+                let m = *layers.moisture.get(x, y);
+                let b = layers.biomes.get(x, y).display_name();
+                let s = *layers.stress_map.get(x, y);
                 writeln!(file, "{},{},{:.1},{:.1},{:.2},{},{:.3}", x, y, h, t, m, b, s)?;
             }
         }
@@ -505,20 +541,8 @@ pub fn export_world_file(
 }
 
 /// Print ASCII map to stdout
-pub fn print_ascii_map(
-    heightmap: &Tilemap<f32>,
-    biomes: &Tilemap<ExtendedBiome>,
-    temperature: &Tilemap<f32>,
-    moisture: &Tilemap<f32>,
-    stress_map: &Tilemap<f32>,
-    plate_map: &Tilemap<PlateId>,
-    plates: &[Plate],
-    mode: AsciiMode,
-) {
-    let map_str = render_ascii_map(
-        heightmap, biomes, temperature, moisture, stress_map, plate_map, plates,
-        mode,
-    );
+pub fn print_ascii_map(layers: &MapLayers, mode: AsciiMode) {
+    let map_str = render_ascii_map(layers, mode);
     print!("{}", map_str);
 }
 
@@ -704,19 +728,27 @@ pub fn stress_color(stress: f32) -> (u8, u8, u8) {
     }
 }
 
+/// Get color for a plate, based on plate type
+pub fn plate_color(plate_id: PlateId, plates: &[Plate]) -> (u8, u8, u8) {
+    if plate_id.is_none() {
+        return (30, 30, 30);
+    }
+    let plate = &plates[plate_id.0 as usize];
+    if plate.plate_type == PlateType::Continental {
+        // Continental: earthy browns/greens
+        let hue = plate_id.0 * 37 % 60;
+        (100 + hue, 80 + hue / 2, 60)
+    } else {
+        // Oceanic: blues
+        let hue = plate_id.0 * 43 % 60;
+        (40, 60 + hue / 2, 120 + hue)
+    }
+}
+
 /// Render a colorized ASCII map to string with ANSI codes
-pub fn render_colored_ascii_map(
-    heightmap: &Tilemap<f32>,
-    biomes: &Tilemap<ExtendedBiome>,
-    temperature: &Tilemap<f32>,
-    moisture: &Tilemap<f32>,
-    stress_map: &Tilemap<f32>,
-    plate_map: &Tilemap<PlateId>,
-    plates: &[Plate],
-    mode: AsciiMode,
-) -> String {
-    let width = heightmap.width;
-    let height = heightmap.height;
+pub fn render_colored_ascii_map(layers: &MapLayers, mode: AsciiMode) -> String {
+    let width = layers.heightmap.width;
+    let height = layers.heightmap.height;
     // Estimate: each cell needs ~40 bytes for ANSI codes
     let mut result = String::with_capacity(width * height * 45);
 
@@ -724,52 +756,38 @@ pub fn render_colored_ascii_map(
         for x in 0..width {
             let (ch, fg, bg) = match mode {
                 AsciiMode::Biome => {
-                    let biome = biomes.get(x, y);
+                    let biome = layers.biomes.get(x, y);
                     (biome_char(biome), biome_fg_color(biome), biome_bg_color(biome))
                 }
                 AsciiMode::Height => {
-                    let h = *heightmap.get(x, y);
+                    let h = *layers.heightmap.get(x, y);
                     let color = height_color(h);
                     // Use darker version for foreground
                     let fg = (color.0.saturating_sub(40), color.1.saturating_sub(40), color.2.saturating_sub(40));
                     (height_char(h), fg, color)
                 }
                 AsciiMode::Temperature => {
-                    let t = *temperature.get(x, y);
+                    let t = *layers.temperature.get(x, y);
                     let color = temperature_color(t);
                     let fg = (color.0.saturating_sub(40), color.1.saturating_sub(40), color.2.saturating_sub(40));
                     (temperature_char(t), fg, color)
                 }
                 AsciiMode::Moisture => {
-                    let m = *moisture.get(x, y);
+                    let m = *layers.moisture.get(x, y);
                     let color = moisture_color(m);
                     let fg = (color.0.saturating_sub(40), color.1.saturating_sub(40), color.2.saturating_sub(40));
                     (moisture_char(m), fg, color)
                 }
                 AsciiMode::Stress => {
-                    let s = *stress_map.get(x, y);
+                    let s = *layers.stress_map.get(x, y);
                     let color = stress_color(s);
                     let fg = (color.0.saturating_sub(40), color.1.saturating_sub(40), color.2.saturating_sub(40));
                     (stress_char(s), fg, color)
                 }
                 AsciiMode::Plates => {
-                    let pid = *plate_map.get(x, y);
-                    let ch = plate_char(pid, plates);
-                    // Color based on plate type
-                    let color = if pid.is_none() {
-                        (30, 30, 30)
-                    } else {
-                        let plate = &plates[pid.0 as usize];
-                        if plate.plate_type == PlateType::Continental {
-                            // Continental: earthy browns/greens
-                            let hue = (pid.0 * 37 % 60) as u8;
-                            (100 + hue, 80 + hue / 2, 60)
-                        } else {
-                            // Oceanic: blues
-                            let hue = (pid.0 * 43 % 60) as u8;
-                            (40, 60 + hue / 2, 120 + hue)
-                        }
-                    };
+                    let pid = *layers.plate_map.get(x, y);
+                    let ch = plate_char(pid, layers.plates);
+                    let color = plate_color(pid, layers.plates);
                     let fg = (color.0.saturating_add(60), color.1.saturating_add(60), color.2.saturating_add(60));
                     (ch, fg, color)
                 }
@@ -783,23 +801,153 @@ pub fn render_colored_ascii_map(
 }
 
 /// Print colorized ASCII map to stdout
-pub fn print_colored_ascii_map(
-    heightmap: &Tilemap<f32>,
-    biomes: &Tilemap<ExtendedBiome>,
-    temperature: &Tilemap<f32>,
-    moisture: &Tilemap<f32>,
-    stress_map: &Tilemap<f32>,
-    plate_map: &Tilemap<PlateId>,
-    plates: &[Plate],
-    mode: AsciiMode,
-) {
-    let map_str = render_colored_ascii_map(
-        heightmap, biomes, temperature, moisture, stress_map, plate_map, plates,
-        mode,
-    );
+pub fn print_colored_ascii_map(layers: &MapLayers, mode: AsciiMode) {
+    let map_str = render_colored_ascii_map(layers, mode);
     print!("{}", map_str);
 }
 
+// ============================================================================
+// HIGH-DENSITY RENDERING (half-blocks and braille)
+// ============================================================================
+
+/// How each terminal cell is subdivided
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellMode {
+    /// Upper half block: two vertically stacked pixels per cell (1x2)
+    HalfBlock,
+    /// Braille patterns: eight dots per cell (2x4), two colours per cell
+    Braille,
+}
+
+impl CellMode {
+    pub fn from_name(name: &str) -> Option<CellMode> {
+        match name.to_ascii_lowercase().as_str() {
+            "half" | "halfblock" | "half-block" => Some(CellMode::HalfBlock),
+            "braille" => Some(CellMode::Braille),
+            _ => None,
+        }
+    }
+
+    /// Subpixels per cell (columns, rows)
+    fn subpixels(&self) -> (usize, usize) {
+        match self {
+            CellMode::HalfBlock => (1, 2),
+            CellMode::Braille => (2, 4),
+        }
+    }
+}
+
+/// Terminal colour support
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24-bit colour
+    TrueColor,
+    /// xterm 256-colour palette (6x6x6 cube)
+    Ansi256,
+}
+
+impl ColorDepth {
+    /// Truecolor when `COLORTERM` advertises it, else the 256-colour palette
+    pub fn detect() -> ColorDepth {
+        match std::env::var("COLORTERM") {
+            Ok(v) if v.contains("truecolor") || v.contains("24bit") => ColorDepth::TrueColor,
+            _ => ColorDepth::Ansi256,
+        }
+    }
+
+    fn escape(&self, color: (u8, u8, u8), background: bool) -> String {
+        let layer = if background { 48 } else { 38 };
+        match self {
+            ColorDepth::TrueColor => format!("\x1b[{};2;{};{};{}m", layer, color.0, color.1, color.2),
+            ColorDepth::Ansi256 => {
+                let level = |c: u8| (c as u16 * 5 + 127) / 255;
+                let index = 16 + 36 * level(color.0) + 6 * level(color.1) + level(color.2);
+                format!("\x1b[{};5;{}m", layer, index)
+            }
+        }
+    }
+}
+
+/// Options for `render_dense_map`
+#[derive(Clone, Copy, Debug)]
+pub struct DenseOptions {
+    pub cells: CellMode,
+    pub depth: ColorDepth,
+    /// Terminal columns to fill
+    pub columns: usize,
+}
+
+fn luminance(c: (u8, u8, u8)) -> f32 {
+    0.299 * c.0 as f32 + 0.587 * c.1 as f32 + 0.114 * c.2 as f32
+}
+
+fn average(colors: &[(u8, u8, u8)]) -> (u8, u8, u8) {
+    let n = colors.len().max(1) as u32;
+    let sum = colors.iter().fold((0u32, 0u32, 0u32), |acc, c| (acc.0 + c.0 as u32, acc.1 + c.1 as u32, acc.2 + c.2 as u32));
+    ((sum.0 / n) as u8, (sum.1 / n) as u8, (sum.2 / n) as u8)
+}
+
+/// Braille dot bits indexed by [row][column] within a cell
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Render a map layer at subpixel resolution using half blocks or braille,
+/// `columns` cells wide with the map's aspect ratio kept (terminal cells are
+/// about twice as tall as wide, so both modes give roughly square subpixels)
+pub fn render_dense_map(layers: &MapLayers, mode: AsciiMode, options: &DenseOptions) -> String {
+    let (width, height) = (layers.heightmap.width, layers.heightmap.height);
+    let (sub_w, sub_h) = options.cells.subpixels();
+    let columns = options.columns.max(1);
+    let pixels_w = columns * sub_w;
+    let rows = ((pixels_w * height) as f32 / width as f32 / sub_h as f32).ceil().max(1.0) as usize;
+
+    // Nearest tile for each subpixel
+    let pixel = |px: usize, py: usize| {
+        let x = (px * width / pixels_w).min(width - 1);
+        let y = (py * height / (rows * sub_h)).min(height - 1);
+        layers.color(mode, x, y)
+    };
+
+    let mut result = String::with_capacity(columns * rows * 40);
+    for row in 0..rows {
+        for col in 0..columns {
+            let (fg, bg, ch) = match options.cells {
+                CellMode::HalfBlock => (pixel(col, row * 2), pixel(col, row * 2 + 1), '▀'),
+                CellMode::Braille => {
+                    let mut dots = [[(0u8, 0u8, 0u8); 2]; 4];
+                    for (dy, line) in dots.iter_mut().enumerate() {
+                        for (dx, dot) in line.iter_mut().enumerate() {
+                            *dot = pixel(col * 2 + dx, row * 4 + dy);
+                        }
+                    }
+                    // Split the eight dots into a bright and a dark colour; bright dots are raised
+                    let mean = dots.iter().flatten().map(|&c| luminance(c)).sum::<f32>() / 8.0;
+                    let mut bits = 0;
+                    let (mut on, mut off) = (Vec::new(), Vec::new());
+                    for (dy, line) in dots.iter().enumerate() {
+                        for (dx, &dot) in line.iter().enumerate() {
+                            if luminance(dot) > mean {
+                                bits |= BRAILLE_DOTS[dy][dx];
+                                on.push(dot);
+                            } else {
+                                off.push(dot);
+                            }
+                        }
+                    }
+                    let bg = average(&off);
+                    let fg = if on.is_empty() { bg } else { average(&on) };
+                    (fg, bg, char::from_u32(0x2800 + bits).unwrap_or(' '))
+                }
+            };
+            result.push_str(&options.depth.escape(fg, false));
+            result.push_str(&options.depth.escape(bg, true));
+            result.push(ch);
+        }
+        result.push_str("\x1b[0m\n");
+    }
+
+    result
+}
+
 /// Generate colorized legend for biome characters
 pub fn biome_legend_colored() -> String {
     let mut legend = String::new();
@@ -1140,7 +1288,7 @@ fn export_png_legend(
 }
 
 /// Create a simple 5x7 bitmap font for common ASCII characters
-fn create_bitmap_font() -> HashMap<char, [u8; 7]> {
+pub(crate) fn create_bitmap_font() -> HashMap<char, [u8; 7]> {
     let mut font = HashMap::new();
 
     // Each entry is 7 rows of 5-bit patterns (MSB = leftmost pixel)
@@ -1253,3 +1401,32 @@ fn create_bitmap_font() -> HashMap<char, [u8; 7]> {
 
     font
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::generate_test_world;
+
+    #[test]
+    fn test_dense_map_cells() {
+        let mut world = generate_test_world();
+        for y in 0..world.height {
+            world.biomes.set(0, y, ExtendedBiome::DeepOcean);
+            world.biomes.set(1, y, ExtendedBiome::Desert);
+        }
+        let layers = MapLayers::of(&world);
+
+        let options = DenseOptions { cells: CellMode::HalfBlock, depth: ColorDepth::TrueColor, columns: 8 };
+        let half = render_dense_map(&layers, AsciiMode::Biome, &options);
+        let lines: Vec<&str> = half.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines.iter().all(|l| l.matches('▀').count() == 8));
+
+        // A braille cell straddling ocean and desert raises only the brighter (desert) dots
+        let options = DenseOptions { cells: CellMode::Braille, depth: ColorDepth::Ansi256, columns: 2 };
+        let braille = render_dense_map(&layers, AsciiMode::Biome, &options);
+        let cell = braille.lines().next().unwrap().chars().find(|c| ('\u{2800}'..='\u{28ff}').contains(c)).unwrap();
+        assert_eq!(cell as u32 - 0x2800, 0x08 | 0x10 | 0x20 | 0x80);
+        assert!(braille.contains("\x1b[38;5;"));
+    }
+}
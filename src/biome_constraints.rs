@@ -0,0 +1,574 @@
+//! Placement constraints for unique biomes
+//!
+//! One-per-map fantasy biomes are described by `UniqueBiomeRule`s instead of
+//! hand-written placement code. A rule combines hard constraints (climate envelope,
+//! required adjacency, minimum distance from other unique biomes, plate boundary
+//! type) with a suitability score (elevation weight and preferred host biomes).
+//!
+//! Rules are serde-loadable, so a `PlacementSpec` JSON file can add, remove or
+//! retune unique biomes. The solver places each rule in order, picking among
+//! valid tiles weighted by score, and reports which constraint eliminated the
+//! candidates whenever a rule cannot be satisfied.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::ExtendedBiome;
+use crate::tilemap::Tilemap;
+
+/// Stress magnitude above which a tile counts as a plate boundary
+const BOUNDARY_STRESS: f32 = 0.2;
+
+/// Stress magnitude below which a tile counts as plate interior
+const INTERIOR_STRESS: f32 = 0.05;
+
+/// Inclusive value range; unset bounds are open
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ValueRange {
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+}
+
+impl ValueRange {
+    pub fn new(min: Option<f32>, max: Option<f32>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn contains(&self, value: f32) -> bool {
+        self.min.is_none_or(|m| value >= m) && self.max.is_none_or(|m| value <= m)
+    }
+}
+
+/// Something a unique biome must touch
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Adjacency {
+    /// A tile below sea level
+    Ocean,
+    /// A tile above sea level
+    Land,
+    /// A specific biome
+    Biome(ExtendedBiome),
+}
+
+/// Required tectonic setting, derived from the stress map
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum BoundaryType {
+    /// Colliding plates (mountain building, volcanic arcs)
+    Convergent,
+    /// Spreading plates (rifts)
+    Divergent,
+    /// Any boundary, convergent or divergent
+    AnyBoundary,
+    /// Stable plate interior
+    Interior,
+}
+
+impl BoundaryType {
+    pub fn matches(&self, stress: f32) -> bool {
+        match self {
+            BoundaryType::Convergent => stress > BOUNDARY_STRESS,
+            BoundaryType::Divergent => stress < -BOUNDARY_STRESS,
+            BoundaryType::AnyBoundary => stress.abs() > BOUNDARY_STRESS,
+            BoundaryType::Interior => stress.abs() < INTERIOR_STRESS,
+        }
+    }
+}
+
+/// Placement rule for one unique biome
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct UniqueBiomeRule {
+    pub biome: ExtendedBiome,
+    /// How many copies to place
+    pub count: usize,
+    /// Allowed elevation (meters)
+    pub elevation: ValueRange,
+    /// Allowed temperature (°C)
+    pub temperature: ValueRange,
+    /// Allowed moisture (0-1)
+    pub moisture: ValueRange,
+    /// Everything listed must be found within `adjacency_radius` tiles
+    pub adjacent_to: Vec<Adjacency>,
+    pub adjacency_radius: usize,
+    /// Minimum distance (tiles) from every other unique biome already placed
+    pub min_distance: usize,
+    /// Required plate boundary setting
+    pub boundary: Option<BoundaryType>,
+    /// Allow placement below sea level
+    pub allow_water: bool,
+    /// Score per km of elevation (capped at 2 km)
+    pub elevation_weight: f32,
+    /// Score bonus when replacing one of these biomes
+    pub preferred_biomes: HashMap<ExtendedBiome, f32>,
+    /// Score bonus when replacing any other biome
+    pub default_bonus: f32,
+    /// Tiles scoring at or below this are not considered
+    pub min_score: f32,
+}
+
+impl Default for UniqueBiomeRule {
+    fn default() -> Self {
+        Self {
+            biome: ExtendedBiome::DarkTower,
+            count: 1,
+            elevation: ValueRange::default(),
+            temperature: ValueRange::default(),
+            moisture: ValueRange::default(),
+            adjacent_to: Vec::new(),
+            adjacency_radius: 1,
+            min_distance: 0,
+            boundary: None,
+            allow_water: false,
+            elevation_weight: 0.0,
+            preferred_biomes: HashMap::new(),
+            default_bonus: 0.1,
+            min_score: 0.0,
+        }
+    }
+}
+
+/// A set of unique biome rules, applied in order
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PlacementSpec {
+    pub rules: Vec<UniqueBiomeRule>,
+}
+
+impl PlacementSpec {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("PlacementSpec is always serializable")
+    }
+
+    /// Load a placement spec from a JSON file
+    pub fn load(path: &str) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+fn preferred(pairs: &[(ExtendedBiome, f32)]) -> HashMap<ExtendedBiome, f32> {
+    pairs.iter().copied().collect()
+}
+
+/// Built-in unique biomes: the Dark Tower and the World Tree, Titan Forge and Storm Spire
+pub fn default_spec() -> PlacementSpec {
+    use ExtendedBiome as B;
+
+    PlacementSpec {
+        rules: vec![
+            // The Dark Tower looms on high ground among ruins, peaks and wastelands
+            UniqueBiomeRule {
+                biome: B::DarkTower,
+                elevation: ValueRange::new(Some(0.01), None),
+                elevation_weight: 1.0,
+                preferred_biomes: preferred(&[
+                    (B::CyclopeanRuins, 3.0), (B::BuriedTemple, 3.0), (B::OvergrownCitadel, 3.0),
+                    (B::AlpineTundra, 2.5), (B::SnowyPeaks, 2.5), (B::RazorPeaks, 2.5),
+                    (B::VolcanicWasteland, 2.0), (B::Ashlands, 2.0), (B::BoneFields, 2.0), (B::VoidScar, 2.0),
+                    (B::WhisperingStones, 1.5), (B::StarfallCrater, 1.5), (B::LeyNexus, 1.5),
+                    (B::Tundra, 0.5), (B::Desert, 0.5), (B::Savanna, 0.5),
+                ]),
+                default_bonus: 0.1,
+                min_score: 0.5,
+                ..UniqueBiomeRule::default()
+            },
+            // The World Tree grows in mild, wet lowland forest far from the Dark Tower
+            UniqueBiomeRule {
+                biome: B::WorldTree,
+                elevation: ValueRange::new(Some(0.01), Some(1500.0)),
+                temperature: ValueRange::new(Some(5.0), Some(28.0)),
+                moisture: ValueRange::new(Some(0.45), None),
+                min_distance: 24,
+                preferred_biomes: preferred(&[
+                    (B::AncientGrove, 4.0), (B::TemperateRainforest, 3.0), (B::TemperateForest, 2.0),
+                    (B::TropicalRainforest, 1.5), (B::BioluminescentForest, 1.5),
+                ]),
+                default_bonus: 0.2,
+                min_score: 0.1,
+                ..UniqueBiomeRule::default()
+            },
+            // The Titan Forge sits where colliding plates feed it volcanic fire
+            UniqueBiomeRule {
+                biome: B::TitanForge,
+                elevation: ValueRange::new(Some(300.0), None),
+                boundary: Some(BoundaryType::Convergent),
+                min_distance: 24,
+                elevation_weight: 0.5,
+                preferred_biomes: preferred(&[
+                    (B::VolcanicCone, 3.0), (B::LavaField, 2.0), (B::VolcanicWasteland, 2.0),
+                    (B::ObsidianFields, 2.0), (B::BasaltColumns, 1.5), (B::Caldera, 2.5),
+                ]),
+                default_bonus: 0.2,
+                min_score: 0.1,
+                ..UniqueBiomeRule::default()
+            },
+            // The Storm Spire rises from a sea cliff
+            UniqueBiomeRule {
+                biome: B::StormSpire,
+                elevation: ValueRange::new(Some(50.0), None),
+                adjacent_to: vec![Adjacency::Ocean],
+                adjacency_radius: 1,
+                min_distance: 24,
+                elevation_weight: 1.0,
+                preferred_biomes: preferred(&[
+                    (B::Tundra, 1.0), (B::Foothills, 1.0), (B::AlpineTundra, 1.5), (B::RazorPeaks, 1.5),
+                ]),
+                default_bonus: 0.2,
+                min_score: 0.1,
+                ..UniqueBiomeRule::default()
+            },
+        ],
+    }
+}
+
+/// Which constraint rejected a candidate tile
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Rejection {
+    Water,
+    Elevation,
+    Temperature,
+    Moisture,
+    Boundary,
+    Adjacency,
+    Distance,
+    Score,
+}
+
+impl Rejection {
+    fn describe(&self) -> &'static str {
+        match self {
+            Rejection::Water => "below sea level",
+            Rejection::Elevation => "outside elevation range",
+            Rejection::Temperature => "outside temperature range",
+            Rejection::Moisture => "outside moisture range",
+            Rejection::Boundary => "wrong plate boundary",
+            Rejection::Adjacency => "missing required neighbor",
+            Rejection::Distance => "too close to another unique biome",
+            Rejection::Score => "score too low",
+        }
+    }
+}
+
+/// Why a rule could not be (fully) satisfied
+#[derive(Clone, Debug)]
+pub struct PlacementFailure {
+    pub biome: ExtendedBiome,
+    /// Copies placed before running out of valid tiles
+    pub placed: usize,
+    pub requested: usize,
+    /// Tiles rejected by each constraint (first failing constraint only)
+    pub rejections: HashMap<Rejection, usize>,
+}
+
+impl fmt::Display for PlacementFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: placed {}/{}; no valid tile left",
+            self.biome.display_name(),
+            self.placed,
+            self.requested
+        )?;
+        let mut reasons: Vec<_> = self.rejections.iter().filter(|(_, &n)| n > 0).collect();
+        reasons.sort_by(|a, b| b.1.cmp(a.1));
+        for (i, (reason, count)) in reasons.iter().enumerate() {
+            write!(f, "{} {} {}", if i == 0 { " (" } else { "," }, count, reason.describe())?;
+        }
+        if !reasons.is_empty() {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of solving a placement spec
+#[derive(Clone, Debug, Default)]
+pub struct PlacementReport {
+    /// Every placed unique biome and its location
+    pub placed: Vec<(ExtendedBiome, usize, usize)>,
+    pub failures: Vec<PlacementFailure>,
+}
+
+/// Climate and terrain layers consulted by the solver
+pub struct PlacementLayers<'a> {
+    pub heightmap: &'a Tilemap<f32>,
+    pub temperature: &'a Tilemap<f32>,
+    pub moisture: &'a Tilemap<f32>,
+    pub stress_map: &'a Tilemap<f32>,
+}
+
+/// Squared distance with horizontal wrapping
+fn wrapped_dist_sq(width: usize, a: (usize, usize), b: (usize, usize)) -> usize {
+    let dx = a.0.abs_diff(b.0);
+    let dx = dx.min(width - dx);
+    let dy = a.1.abs_diff(b.1);
+    dx * dx + dy * dy
+}
+
+fn has_adjacent(
+    adjacency: Adjacency,
+    x: usize,
+    y: usize,
+    radius: usize,
+    biomes: &Tilemap<ExtendedBiome>,
+    heightmap: &Tilemap<f32>,
+) -> bool {
+    let width = biomes.width as i64;
+    let r = radius.max(1) as i64;
+    for dy in -r..=r {
+        let ny = y as i64 + dy;
+        if ny < 0 || ny >= biomes.height as i64 {
+            continue;
+        }
+        for dx in -r..=r {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            let nx = (x as i64 + dx).rem_euclid(width) as usize;
+            let ny = ny as usize;
+            let found = match adjacency {
+                Adjacency::Ocean => *heightmap.get(nx, ny) < 0.0,
+                Adjacency::Land => *heightmap.get(nx, ny) >= 0.0,
+                Adjacency::Biome(b) => *biomes.get(nx, ny) == b,
+            };
+            if found {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Check a tile against a rule; returns its score or the first failing constraint
+fn evaluate(
+    rule: &UniqueBiomeRule,
+    x: usize,
+    y: usize,
+    biomes: &Tilemap<ExtendedBiome>,
+    layers: &PlacementLayers,
+    placed: &[(ExtendedBiome, usize, usize)],
+) -> Result<f32, Rejection> {
+    let elev = *layers.heightmap.get(x, y);
+    if !rule.allow_water && elev <= 0.0 {
+        return Err(Rejection::Water);
+    }
+    if !rule.elevation.contains(elev) {
+        return Err(Rejection::Elevation);
+    }
+    if !rule.temperature.contains(*layers.temperature.get(x, y)) {
+        return Err(Rejection::Temperature);
+    }
+    if !rule.moisture.contains(*layers.moisture.get(x, y)) {
+        return Err(Rejection::Moisture);
+    }
+    if let Some(boundary) = rule.boundary {
+        if !boundary.matches(*layers.stress_map.get(x, y)) {
+            return Err(Rejection::Boundary);
+        }
+    }
+    if !rule
+        .adjacent_to
+        .iter()
+        .all(|&a| has_adjacent(a, x, y, rule.adjacency_radius, biomes, layers.heightmap))
+    {
+        return Err(Rejection::Adjacency);
+    }
+    let min_dist_sq = rule.min_distance * rule.min_distance;
+    if placed.iter().any(|&(_, px, py)| wrapped_dist_sq(biomes.width, (x, y), (px, py)) < min_dist_sq) {
+        return Err(Rejection::Distance);
+    }
+
+    let biome = *biomes.get(x, y);
+    let score = rule.elevation_weight * (elev / 1000.0).min(2.0)
+        + rule.preferred_biomes.get(&biome).copied().unwrap_or(rule.default_bonus);
+    if score <= rule.min_score {
+        return Err(Rejection::Score);
+    }
+    Ok(score)
+}
+
+/// Place every rule of a spec onto the biome map, in order.
+/// Each copy is chosen among valid tiles with probability proportional to its score.
+pub fn solve_placement(
+    spec: &PlacementSpec,
+    biomes: &mut Tilemap<ExtendedBiome>,
+    layers: &PlacementLayers,
+    seed: u64,
+) -> PlacementReport {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0xDA4C_70BE));
+    let mut report = PlacementReport::default();
+
+    for rule in &spec.rules {
+        let mut placed_for_rule = 0;
+
+        while placed_for_rule < rule.count {
+            let mut candidates: Vec<(usize, usize, f32)> = Vec::new();
+            let mut rejections: HashMap<Rejection, usize> = HashMap::new();
+
+            for y in 0..biomes.height {
+                for x in 0..biomes.width {
+                    if biomes.get(x, y).is_unique() {
+                        continue;
+                    }
+                    match evaluate(rule, x, y, biomes, layers, &report.placed) {
+                        Ok(score) => candidates.push((x, y, score)),
+                        Err(reason) => *rejections.entry(reason).or_insert(0) += 1,
+                    }
+                }
+            }
+
+            if candidates.is_empty() {
+                report.failures.push(PlacementFailure {
+                    biome: rule.biome,
+                    placed: placed_for_rule,
+                    requested: rule.count,
+                    rejections,
+                });
+                break;
+            }
+
+            let total_score: f32 = candidates.iter().map(|(_, _, s)| s).sum();
+            let mut pick = rng.gen::<f32>() * total_score;
+            let mut chosen = *candidates.last().unwrap();
+            for candidate in &candidates {
+                pick -= candidate.2;
+                if pick <= 0.0 {
+                    chosen = *candidate;
+                    break;
+                }
+            }
+
+            let (x, y, _) = chosen;
+            biomes.set(x, y, rule.biome);
+            report.placed.push((rule.biome, x, y));
+            placed_for_rule += 1;
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixture {
+        biomes: Tilemap<ExtendedBiome>,
+        heightmap: Tilemap<f32>,
+        temperature: Tilemap<f32>,
+        moisture: Tilemap<f32>,
+        stress: Tilemap<f32>,
+    }
+
+    impl Fixture {
+        /// Ocean on the left third, rising land to the right, convergent stress in the last column
+        fn new() -> Self {
+            let (w, h) = (30, 10);
+            let mut heightmap = Tilemap::new_with(w, h, 0.0f32);
+            let mut stress = Tilemap::new_with(w, h, 0.0f32);
+            for (x, _, v) in heightmap.iter_mut() {
+                *v = if x < 10 { -500.0 } else { (x as f32 - 9.0) * 100.0 };
+            }
+            for (x, _, s) in stress.iter_mut() {
+                if x == 25 {
+                    *s = 0.8;
+                }
+            }
+            Self {
+                biomes: Tilemap::new_with(w, h, ExtendedBiome::TemperateGrassland),
+                heightmap,
+                temperature: Tilemap::new_with(w, h, 15.0),
+                moisture: Tilemap::new_with(w, h, 0.5),
+                stress,
+            }
+        }
+
+        fn solve(&mut self, spec: &PlacementSpec) -> PlacementReport {
+            let layers = PlacementLayers {
+                heightmap: &self.heightmap,
+                temperature: &self.temperature,
+                moisture: &self.moisture,
+                stress_map: &self.stress,
+            };
+            solve_placement(spec, &mut self.biomes, &layers, 1)
+        }
+    }
+
+    #[test]
+    fn test_adjacency_and_boundary_constraints() {
+        let mut f = Fixture::new();
+        let spec = PlacementSpec {
+            rules: vec![
+                UniqueBiomeRule { biome: ExtendedBiome::StormSpire, adjacent_to: vec![Adjacency::Ocean], ..Default::default() },
+                UniqueBiomeRule { biome: ExtendedBiome::TitanForge, boundary: Some(BoundaryType::Convergent), ..Default::default() },
+            ],
+        };
+        let report = f.solve(&spec);
+        assert!(report.failures.is_empty());
+        let (_, sx, _) = report.placed[0];
+        let (_, tx, _) = report.placed[1];
+        // Column 29 touches the ocean across the wrapped map edge
+        assert!(sx == 10 || sx == 29, "storm spire must touch the ocean");
+        assert_eq!(tx, 25, "titan forge must sit on the convergent boundary");
+    }
+
+    #[test]
+    fn test_min_distance_and_failure_report() {
+        let mut f = Fixture::new();
+        let spec = PlacementSpec {
+            rules: vec![UniqueBiomeRule {
+                biome: ExtendedBiome::WorldTree,
+                count: 5,
+                min_distance: 15,
+                ..Default::default()
+            }],
+        };
+        let report = f.solve(&spec);
+        // Only 20 land columns: at most two trees can be 15 tiles apart
+        assert!(report.placed.len() < 5);
+        for (i, a) in report.placed.iter().enumerate() {
+            for b in &report.placed[i + 1..] {
+                assert!(wrapped_dist_sq(30, (a.1, a.2), (b.1, b.2)) >= 225);
+            }
+        }
+        let failure = &report.failures[0];
+        assert_eq!(failure.requested, 5);
+        assert!(failure.rejections[&Rejection::Water] > 0);
+        assert!(failure.rejections[&Rejection::Distance] > 0);
+        assert!(failure.to_string().contains("too close"));
+    }
+
+    #[test]
+    fn test_climate_envelope_failure() {
+        let mut f = Fixture::new();
+        let spec = PlacementSpec {
+            rules: vec![UniqueBiomeRule {
+                biome: ExtendedBiome::WorldTree,
+                temperature: ValueRange::new(Some(30.0), None),
+                ..Default::default()
+            }],
+        };
+        let report = f.solve(&spec);
+        assert!(report.placed.is_empty());
+        assert_eq!(report.failures[0].rejections[&Rejection::Temperature], 200);
+    }
+
+    #[test]
+    fn test_spec_json_round_trip() {
+        let spec = default_spec();
+        assert_eq!(PlacementSpec::from_json(&spec.to_json()).unwrap(), spec);
+
+        let custom = r#"{"rules": [{"biome": "StormSpire", "adjacent_to": ["Ocean", {"Biome": "Tundra"}], "boundary": "Interior"}]}"#;
+        let parsed = PlacementSpec::from_json(custom).unwrap();
+        assert_eq!(parsed.rules[0].count, 1);
+        assert_eq!(parsed.rules[0].adjacent_to[1], Adjacency::Biome(ExtendedBiome::Tundra));
+        assert_eq!(parsed.rules[0].boundary, Some(BoundaryType::Interior));
+    }
+}
@@ -1,9 +1,15 @@
-//! Perimeter-Based Biome Feathering (Phase 2a)
+//! Distance-Field Biome Feathering (Phase 2a)
 //!
-//! Replaces hard-edge biome transitions with smooth Gaussian-distributed borders
-//! using weighted spline interpolation for natural-looking boundaries.
-
-use std::collections::VecDeque;
+//! Replaces hard-edge biome transitions with smooth borders built on signed
+//! distance fields. Every tile knows its Euclidean distance to the nearest tile
+//! of a different biome; blend weights come from each nearby biome's signed
+//! distance run through a configurable falloff curve. Band widths vary with
+//! low-frequency noise rather than per-tile jitter, so wide transitions stay
+//! stable instead of breaking into speckle. Discrete picks inside a band use
+//! ordered (Bayer) dithering, which is deterministic and evenly spread.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use noise::{NoiseFn, Perlin, Seedable};
 
 use crate::biomes::ExtendedBiome;
@@ -13,32 +19,55 @@ use crate::tilemap::Tilemap;
 // CONFIGURATION
 // =============================================================================
 
+/// Shape of the blend across a transition band
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FalloffCurve {
+    /// Constant-rate blend
+    Linear,
+    /// Hermite smoothstep: gentle at the band edges, steep at the boundary
+    Smoothstep,
+    /// Raised cosine: like smoothstep with a slightly softer middle
+    Cosine,
+}
+
+impl FalloffCurve {
+    /// Map a signed band position (-1 outside, 0 at the boundary, 1 inside) to a weight
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(-1.0, 1.0);
+        match self {
+            FalloffCurve::Linear => 0.5 + 0.5 * t,
+            FalloffCurve::Smoothstep => smooth_step(-1.0, 1.0, t),
+            FalloffCurve::Cosine => 0.5 - 0.5 * ((t + 1.0) * 0.5 * std::f32::consts::PI).cos(),
+        }
+    }
+}
+
 /// Configuration for biome feathering
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct FeatherConfig {
-    /// Gaussian sigma for border depth variance (0.5-2.0)
-    pub gaussian_sigma: f32,
-    /// Minimum border width in tiles
+    /// Minimum transition half-width in tiles
     pub min_depth: usize,
-    /// Maximum border width in tiles
+    /// Maximum transition half-width in tiles
     pub max_depth: usize,
-    /// Catmull-Rom spline weights for interpolation
-    pub spline_weights: [f32; 4],
-    /// Noise frequency for border perturbation
+    /// Blend curve across the band
+    pub falloff: FalloffCurve,
+    /// Noise frequency for band width variation (low values keep bands smooth)
     pub noise_frequency: f64,
-    /// Noise amplitude for border perturbation
-    pub noise_amplitude: f32,
+    /// Distance (tiles) over which vegetation thins toward an unlike biome
+    pub edge_thinning_width: f32,
+    /// Largest fraction of vegetation removed right at such an edge
+    pub edge_thinning_strength: f32,
 }
 
 impl Default for FeatherConfig {
     fn default() -> Self {
         Self {
-            gaussian_sigma: 1.0,
             min_depth: 2,
             max_depth: 8,
-            spline_weights: [0.5, 0.5, 0.5, 0.5], // Catmull-Rom defaults
-            noise_frequency: 0.1,
-            noise_amplitude: 0.3,
+            falloff: FalloffCurve::Smoothstep,
+            noise_frequency: 0.05,
+            edge_thinning_width: 1.5,
+            edge_thinning_strength: 0.6,
         }
     }
 }
@@ -47,13 +76,20 @@ impl Default for FeatherConfig {
 // FEATHER MAP
 // =============================================================================
 
+/// 4x4 Bayer matrix for ordered dithering
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
 /// Precomputed feathering data for efficient runtime lookup
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct BiomeFeatherMap {
-    /// Distance to nearest biome boundary (0 at edge, positive inland)
+    /// Distance to nearest biome boundary (0.5 on an edge tile, positive inland)
     pub depth_map: Tilemap<f32>,
     /// Gradient direction pointing toward nearest boundary (normalized)
     pub gradient_map: Tilemap<(f32, f32)>,
+    /// Biome across the nearest boundary (None if the map holds a single biome)
+    pub nearest_other: Tilemap<Option<ExtendedBiome>>,
+    /// How unlike the nearest other biome is (0 = compatible, 1 = nothing in common)
+    pub edge_contrast: Tilemap<f32>,
     /// Per-tile blend weights for each neighboring biome
     /// Format: Vec of (biome, weight) pairs sorted by weight descending
     pub blend_weights: Tilemap<Vec<(ExtendedBiome, f32)>>,
@@ -92,6 +128,56 @@ impl BiomeFeatherMap {
         let weights = self.blend_weights.get(x, y);
         weights.len() > 1 && weights.get(1).map(|(_, w)| *w > 0.1).unwrap_or(false)
     }
+
+    /// Distance in tiles from the centre of a tile to the nearest different biome
+    pub fn distance_to_different_biome(&self, x: usize, y: usize) -> f32 {
+        *self.depth_map.get(x, y)
+    }
+
+    /// The biome across the nearest boundary
+    pub fn nearest_different_biome(&self, x: usize, y: usize) -> Option<ExtendedBiome> {
+        *self.nearest_other.get(x, y)
+    }
+
+    /// Sub-tile distance to the nearest different biome, at offset (u, v) in 0-1
+    /// within world tile (x, y), extrapolated along the boundary gradient
+    pub fn edge_distance_at(&self, x: usize, y: usize, u: f32, v: f32) -> f32 {
+        let (gx, gy) = *self.gradient_map.get(x, y);
+        (*self.depth_map.get(x, y) - (gx * u + gy * v)).max(0.0)
+    }
+
+    /// Vegetation density multiplier (0-1) at a sub-tile position: thins
+    /// toward boundaries with unlike biomes, untouched between similar ones
+    pub fn edge_thinning(&self, x: usize, y: usize, u: f32, v: f32) -> f32 {
+        let contrast = *self.edge_contrast.get(x, y);
+        if contrast <= 0.0 {
+            return 1.0;
+        }
+        let d = self.edge_distance_at(x, y, u, v);
+        let closeness = 1.0 - smooth_step(0.0, self.config.edge_thinning_width.max(0.01), d);
+        1.0 - self.config.edge_thinning_strength * contrast * closeness
+    }
+
+    /// Pick a single biome for a fine-grid position inside world tile (x, y) by
+    /// ordered dithering of the blend weights. `fine_x`/`fine_y` are absolute
+    /// fine-grid coordinates (e.g. local tile coordinates), so neighbouring
+    /// chunks continue the same pattern.
+    pub fn dithered_biome(&self, x: usize, y: usize, fine_x: usize, fine_y: usize) -> Option<ExtendedBiome> {
+        let threshold = (BAYER_4X4[fine_y % 4][fine_x % 4] as f32 + 0.5) / 16.0;
+        pick_weighted(self.blend_weights.get(x, y), threshold)
+    }
+}
+
+/// The biome whose cumulative weight first passes `threshold` (0-1)
+fn pick_weighted(weights: &[(ExtendedBiome, f32)], threshold: f32) -> Option<ExtendedBiome> {
+    let mut cumulative = 0.0;
+    for &(biome, w) in weights {
+        cumulative += w;
+        if threshold < cumulative {
+            return Some(biome);
+        }
+    }
+    weights.last().map(|&(b, _)| b)
 }
 
 // =============================================================================
@@ -104,96 +190,120 @@ pub fn compute_biome_feathering(
     config: &FeatherConfig,
     seed: u64,
 ) -> BiomeFeatherMap {
-    let width = biomes.width;
-    let height = biomes.height;
     let noise = Perlin::new(1).set_seed(seed as u32);
 
-    // Step 1: Compute distance field from biome boundaries
-    let (depth_map, gradient_map) = compute_distance_field(biomes, config, &noise);
+    // Step 1: Distance field from biome boundaries
+    let (depth_map, gradient_map, nearest_other) = compute_distance_field(biomes);
 
-    // Step 2: Compute blend weights using Catmull-Rom interpolation
+    let mut edge_contrast = Tilemap::new_with(biomes.width, biomes.height, 0.0f32);
+    for (x, y, other) in nearest_other.iter() {
+        if let Some(other) = other {
+            edge_contrast.set(x, y, 1.0 - biome_compatibility(*biomes.get(x, y), *other));
+        }
+    }
+
+    // Step 2: Blend weights from signed distances
     let blend_weights = compute_blend_weights(biomes, &depth_map, config, &noise);
 
     BiomeFeatherMap {
         depth_map,
         gradient_map,
+        nearest_other,
+        edge_contrast,
         blend_weights,
         config: config.clone(),
     }
 }
 
-/// Compute signed distance field from biome boundaries
-fn compute_distance_field(
-    biomes: &Tilemap<ExtendedBiome>,
-    config: &FeatherConfig,
-    noise: &Perlin,
-) -> (Tilemap<f32>, Tilemap<(f32, f32)>) {
+/// Offset from `from` to `to` with x wrapping the short way around the map
+fn wrapped_delta(from: (usize, usize), to: (usize, usize), width: usize) -> (f32, f32) {
+    let mut dx = to.0 as i32 - from.0 as i32;
+    if dx.abs() > width as i32 / 2 {
+        dx -= dx.signum() * width as i32;
+    }
+    (dx as f32, to.1 as f32 - from.1 as f32)
+}
+
+/// Distance to the nearest biome boundary, the unit gradient toward it, and
+/// the biome across it, for every tile
+type DistanceField = (Tilemap<f32>, Tilemap<(f32, f32)>, Tilemap<Option<ExtendedBiome>>);
+
+/// Euclidean distance from each tile to the nearest tile of another biome.
+///
+/// Seeds every edge tile with its nearest foreign neighbour, then propagates
+/// those nearest-foreign positions through each region in distance order
+/// (Dijkstra with vector propagation). The result is within a fraction of a
+/// tile of the exact distance and is independent of iteration order.
+/// Returns distances to the boundary (half a tile short of the foreign tile),
+/// unit gradients toward it, and the biome across it.
+fn compute_distance_field(biomes: &Tilemap<ExtendedBiome>) -> DistanceField {
     let width = biomes.width;
     let height = biomes.height;
+    let unreached = (width.max(height)) as f32;
 
-    // Initialize with max distance
-    let mut distance = Tilemap::new_with(width, height, f32::MAX);
-    let mut gradient = Tilemap::new_with(width, height, (0.0f32, 0.0f32));
-
-    // Find all boundary cells (cells adjacent to different biome)
-    let mut boundary_queue: VecDeque<(usize, usize, f32)> = VecDeque::new();
-
-    for y in 0..height {
-        for x in 0..width {
-            let biome = *biomes.get(x, y);
-
-            // Check if any neighbor has a different biome
-            let is_boundary = biomes.neighbors_8(x, y).into_iter().any(|(nx, ny)| {
-                *biomes.get(nx, ny) != biome
-            });
+    let mut best = Tilemap::new_with(width, height, f32::MAX);
+    let mut foreign: Tilemap<Option<(usize, usize)>> = Tilemap::new_with(width, height, None);
+    // Min-heap on (distance bits, y, x); positive f32 bit patterns order like the floats
+    let mut queue: BinaryHeap<Reverse<(u32, usize, usize)>> = BinaryHeap::new();
 
-            if is_boundary {
-                // Add Gaussian jitter to boundary depth
-                let jitter = gaussian_jitter(x, y, noise, config.gaussian_sigma, config.noise_frequency);
-                let depth = (jitter * config.noise_amplitude).max(0.0);
+    let dist = |p: (usize, usize), f: (usize, usize)| {
+        let (dx, dy) = wrapped_delta(p, f, width);
+        (dx * dx + dy * dy).sqrt()
+    };
 
-                distance.set(x, y, depth);
-                boundary_queue.push_back((x, y, depth));
+    for (x, y, &biome) in biomes.iter() {
+        let mut nearest: Option<((usize, usize), f32)> = None;
+        for (nx, ny) in biomes.neighbors_8(x, y) {
+            if *biomes.get(nx, ny) != biome {
+                let d = dist((x, y), (nx, ny));
+                if nearest.is_none_or(|(_, nd)| d < nd) {
+                    nearest = Some(((nx, ny), d));
+                }
             }
         }
+        if let Some((f, d)) = nearest {
+            best.set(x, y, d);
+            foreign.set(x, y, Some(f));
+            queue.push(Reverse((d.to_bits(), y, x)));
+        }
     }
 
-    // BFS to propagate distance from boundaries
-    while let Some((x, y, dist)) = boundary_queue.pop_front() {
+    while let Some(Reverse((bits, y, x))) = queue.pop() {
+        if f32::from_bits(bits) > *best.get(x, y) {
+            continue;
+        }
+        let Some(f) = *foreign.get(x, y) else { continue };
+        let biome = *biomes.get(x, y);
         for (nx, ny) in biomes.neighbors_8(x, y) {
-            let current_dist = *distance.get(nx, ny);
-            let new_dist = dist + 1.0;
-
-            if new_dist < current_dist && new_dist < config.max_depth as f32 {
-                distance.set(nx, ny, new_dist);
-
-                // Compute gradient (direction toward boundary)
-                let gx = x as f32 - nx as f32;
-                let gy = y as f32 - ny as f32;
-                let len = (gx * gx + gy * gy).sqrt();
-                if len > 0.0 {
-                    gradient.set(nx, ny, (gx / len, gy / len));
-                }
-
-                boundary_queue.push_back((nx, ny, new_dist));
+            if *biomes.get(nx, ny) != biome {
+                continue;
+            }
+            let d = dist((nx, ny), f);
+            if d < *best.get(nx, ny) {
+                best.set(nx, ny, d);
+                foreign.set(nx, ny, Some(f));
+                queue.push(Reverse((d.to_bits(), ny, nx)));
             }
         }
     }
 
-    // Normalize distances: cells at max distance or beyond get max depth
-    for y in 0..height {
-        for x in 0..width {
-            let d = *distance.get(x, y);
-            if d >= f32::MAX - 1.0 {
-                distance.set(x, y, config.max_depth as f32);
-            }
+    let mut depth = Tilemap::new_with(width, height, unreached);
+    let mut gradient = Tilemap::new_with(width, height, (0.0f32, 0.0f32));
+    let mut nearest_other = Tilemap::new_with(width, height, None);
+    for (x, y, f) in foreign.iter() {
+        if let Some(f) = *f {
+            let d = *best.get(x, y);
+            let (dx, dy) = wrapped_delta((x, y), f, width);
+            depth.set(x, y, d - 0.5);
+            gradient.set(x, y, (dx / d, dy / d));
+            nearest_other.set(x, y, Some(*biomes.get(f.0, f.1)));
         }
     }
 
-    (distance, gradient)
+    (depth, gradient, nearest_other)
 }
 
-/// Compute blend weights for each tile based on neighboring biomes
+/// Compute blend weights for each tile from the signed distances of nearby biomes
 fn compute_blend_weights(
     biomes: &Tilemap<ExtendedBiome>,
     depth_map: &Tilemap<f32>,
@@ -202,6 +312,7 @@ fn compute_blend_weights(
 ) -> Tilemap<Vec<(ExtendedBiome, f32)>> {
     let width = biomes.width;
     let height = biomes.height;
+    let radius = config.max_depth.max(1) as i32 + 1;
 
     let mut weights_map: Tilemap<Vec<(ExtendedBiome, f32)>> = Tilemap::new_with(width, height, Vec::new());
 
@@ -209,61 +320,54 @@ fn compute_blend_weights(
         for x in 0..width {
             let depth = *depth_map.get(x, y);
             let center_biome = *biomes.get(x, y);
+            let band = band_width(x, y, config, noise);
 
-            // If deep in biome center, just use that biome
-            if depth >= config.max_depth as f32 - 1.0 {
+            // Deep inside the region: only the region's own biome
+            if depth >= band {
                 weights_map.set(x, y, vec![(center_biome, 1.0)]);
                 continue;
             }
 
-            // Collect biomes within blend radius
-            let blend_radius = config.max_depth;
-            let mut biome_contributions: std::collections::HashMap<ExtendedBiome, f32> = std::collections::HashMap::new();
-
-            // Add center biome
-            let center_weight = catmull_rom_weight(depth, config.max_depth as f32, &config.spline_weights);
-            biome_contributions.insert(center_biome, center_weight);
-
-            // Sample nearby biomes
-            for dy in -(blend_radius as i32)..=(blend_radius as i32) {
-                for dx in -(blend_radius as i32)..=(blend_radius as i32) {
-                    let dist = ((dx * dx + dy * dy) as f32).sqrt();
-                    if dist > blend_radius as f32 || (dx == 0 && dy == 0) {
+            // Distance to the nearest tile of each other biome in range (scan order keeps this deterministic)
+            let mut nearest: Vec<(ExtendedBiome, f32)> = Vec::new();
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    let nx = (x as i32 + dx).rem_euclid(width as i32) as usize;
+                    let ny = y as i32 + dy;
+                    if ny < 0 || ny >= height as i32 {
                         continue;
                     }
-
-                    let nx = (x as i32 + dx).rem_euclid(width as i32) as usize;
-                    let ny = (y as i32 + dy).clamp(0, height as i32 - 1) as usize;
-
-                    let neighbor_biome = *biomes.get(nx, ny);
-                    if neighbor_biome == center_biome {
+                    let other = *biomes.get(nx, ny as usize);
+                    if other == center_biome {
                         continue;
                     }
-
-                    // Weight based on distance and compatibility
-                    let dist_weight = 1.0 - (dist / blend_radius as f32);
-                    let compat = biome_compatibility(center_biome, neighbor_biome);
-                    let weight = dist_weight * compat * (1.0 - center_weight);
-
-                    if weight > 0.01 {
-                        *biome_contributions.entry(neighbor_biome).or_insert(0.0) += weight;
+                    let d = ((dx * dx + dy * dy) as f32).sqrt();
+                    match nearest.iter_mut().find(|(b, _)| *b == other) {
+                        Some(entry) => entry.1 = entry.1.min(d),
+                        None => nearest.push((other, d)),
                     }
                 }
             }
 
-            // Normalize weights
-            let total: f32 = biome_contributions.values().sum();
-            let mut weights: Vec<(ExtendedBiome, f32)> = if total > 0.0 {
-                biome_contributions
-                    .into_iter()
-                    .map(|(b, w)| (b, w / total))
-                    .filter(|(_, w)| *w > 0.01)
-                    .collect()
-            } else {
-                vec![(center_biome, 1.0)]
-            };
-
-            // Sort by weight descending
+            // Signed distances: positive inside the tile's own region, negative outside the others
+            let mut weights = vec![(center_biome, config.falloff.apply(depth / band))];
+            for (other, d) in nearest {
+                let signed = -(d - 0.5);
+                // Unlike biomes meet over a narrower band
+                let width = band * (0.5 + 0.5 * biome_compatibility(center_biome, other));
+                let w = config.falloff.apply(signed / width);
+                if w > 0.01 {
+                    weights.push((other, w));
+                }
+            }
+
+            let total: f32 = weights.iter().map(|&(_, w)| w).sum();
+            let mut weights: Vec<(ExtendedBiome, f32)> = weights
+                .into_iter()
+                .map(|(b, w)| (b, w / total))
+                .filter(|&(_, w)| w > 0.01)
+                .collect();
+            // Stable sort keeps the centre biome first on ties
             weights.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
             weights_map.set(x, y, weights);
@@ -273,6 +377,14 @@ fn compute_blend_weights(
     weights_map
 }
 
+/// Transition half-width at a tile, varied smoothly between min and max depth
+fn band_width(x: usize, y: usize, config: &FeatherConfig, noise: &Perlin) -> f32 {
+    let n = noise.get([x as f64 * config.noise_frequency, y as f64 * config.noise_frequency]) as f32;
+    let t = (0.5 + 0.5 * n).clamp(0.0, 1.0);
+    let min = config.min_depth.max(1) as f32;
+    min + (config.max_depth as f32 - min).max(0.0) * t
+}
+
 /// Compute biome compatibility for blending (0.0 = incompatible, 1.0 = very compatible)
 fn biome_compatibility(a: ExtendedBiome, b: ExtendedBiome) -> f32 {
     use ExtendedBiome::*;
@@ -285,7 +397,7 @@ fn biome_compatibility(a: ExtendedBiome, b: ExtendedBiome) -> f32 {
     // Group biomes by category for compatibility scoring
     let is_water = |b: ExtendedBiome| matches!(b,
         DeepOcean | Ocean | CoastalWater | Lagoon | AcidLake | LavaLake |
-        FrozenLake | BioluminescentWater | AbyssalVents | Sargasso
+        FrozenLake | BioluminescentWater | AbyssalVents | Sargasso | PackIce | Polynya
     );
 
     let is_forest = |b: ExtendedBiome| matches!(b,
@@ -295,7 +407,8 @@ fn biome_compatibility(a: ExtendedBiome, b: ExtendedBiome) -> f32 {
     );
 
     let is_cold = |b: ExtendedBiome| matches!(b,
-        Ice | Tundra | AlpineTundra | SnowyPeaks | FrozenLake | AuroraWastes
+        Ice | Tundra | AlpineTundra | SnowyPeaks | FrozenLake | AuroraWastes |
+        IceSheet | PackIce
     );
 
     let is_hot = |b: ExtendedBiome| matches!(b,
@@ -346,36 +459,6 @@ fn biome_compatibility(a: ExtendedBiome, b: ExtendedBiome) -> f32 {
     0.5
 }
 
-/// Catmull-Rom spline weight function
-fn catmull_rom_weight(distance: f32, max_distance: f32, weights: &[f32; 4]) -> f32 {
-    let t = (distance / max_distance).clamp(0.0, 1.0);
-    let t2 = t * t;
-    let t3 = t2 * t;
-
-    // Catmull-Rom basis functions
-    let w0 = weights[0] * (-0.5 * t3 + t2 - 0.5 * t);
-    let w1 = weights[1] * (1.5 * t3 - 2.5 * t2 + 1.0);
-    let w2 = weights[2] * (-1.5 * t3 + 2.0 * t2 + 0.5 * t);
-    let w3 = weights[3] * (0.5 * t3 - 0.5 * t2);
-
-    // Return blend factor (1.0 at center, decreasing toward edges)
-    (w0 + w1 + w2 + w3).clamp(0.0, 1.0)
-}
-
-/// Generate Gaussian jitter for border depth variation
-fn gaussian_jitter(x: usize, y: usize, noise: &Perlin, sigma: f32, frequency: f64) -> f32 {
-    let nx = x as f64 * frequency;
-    let ny = y as f64 * frequency;
-
-    // Use multiple noise octaves for Gaussian-like distribution
-    let n1 = noise.get([nx, ny, 0.0]) as f32;
-    let n2 = noise.get([nx * 2.0, ny * 2.0, 1.0]) as f32 * 0.5;
-    let n3 = noise.get([nx * 4.0, ny * 4.0, 2.0]) as f32 * 0.25;
-
-    // Combine and scale by sigma
-    (n1 + n2 + n3) * sigma
-}
-
 /// Smooth step interpolation (Hermite smoothstep)
 fn smooth_step(edge0: f32, edge1: f32, x: f32) -> f32 {
     let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
@@ -428,7 +511,7 @@ mod tests {
     #[test]
     fn test_feather_config_default() {
         let config = FeatherConfig::default();
-        assert!(config.gaussian_sigma > 0.0);
+        assert!(config.edge_thinning_width > 0.0);
         assert!(config.max_depth > config.min_depth);
     }
 
@@ -452,4 +535,58 @@ mod tests {
         assert!((smooth_step(0.0, 1.0, 1.0) - 1.0).abs() < 0.001);
         assert!((smooth_step(0.0, 1.0, 0.5) - 0.5).abs() < 0.001);
     }
+
+    #[test]
+    fn test_falloff_curves() {
+        for curve in [FalloffCurve::Linear, FalloffCurve::Smoothstep, FalloffCurve::Cosine] {
+            assert!((curve.apply(0.0) - 0.5).abs() < 1e-6);
+            assert_eq!(curve.apply(-2.0), 0.0);
+            assert!((curve.apply(1.0) - 1.0).abs() < 1e-6);
+            assert!((curve.apply(0.3) + curve.apply(-0.3) - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_distance_field_band_is_monotonic() {
+        // Forest in the west half, grassland in the east: one straight boundary at x = 16 (and the seam)
+        let mut biomes = Tilemap::new_with(32, 16, ExtendedBiome::TemperateGrassland);
+        for y in 0..16 {
+            for x in 0..16 {
+                biomes.set(x, y, ExtendedBiome::TemperateForest);
+            }
+        }
+        let config = FeatherConfig::default();
+        let map = compute_biome_feathering(&biomes, &config, 9);
+
+        // Euclidean distance from the boundary, wrapping across the seam
+        assert_eq!(map.distance_to_different_biome(15, 8), 0.5);
+        assert_eq!(map.distance_to_different_biome(12, 8), 3.5);
+        assert_eq!(map.distance_to_different_biome(1, 8), 1.5);
+        assert_eq!(map.nearest_different_biome(12, 8), Some(ExtendedBiome::TemperateGrassland));
+        assert_eq!(*map.gradient_map.get(12, 8), (1.0, 0.0));
+
+        // No speckle: along a row the forest weight never increases approaching the boundary
+        let forest_weight = |x: usize| {
+            map.get_biome_weights(x, 8)
+                .iter()
+                .find(|(b, _)| *b == ExtendedBiome::TemperateForest)
+                .map_or(0.0, |&(_, w)| w)
+        };
+        for x in 8..23 {
+            assert!(forest_weight(x + 1) <= forest_weight(x) + 1e-6, "x = {}", x);
+        }
+        assert!(map.is_transition_zone(15, 8));
+
+        // Thinning: vegetation drops toward the unlike edge, never below (1 - strength)
+        let inland = map.edge_thinning(10, 8, 0.0, 0.0);
+        let edge = map.edge_thinning(15, 8, 0.9, 0.0);
+        assert_eq!(inland, 1.0);
+        assert!(edge < inland && edge >= 1.0 - config.edge_thinning_strength);
+
+        // Deterministic dithering picks every blended biome somewhere in a 4x4 block
+        let picks: Vec<_> = (0..16).filter_map(|i| map.dithered_biome(15, 8, i % 4, i / 4)).collect();
+        assert!(picks.contains(&ExtendedBiome::TemperateForest));
+        assert!(picks.contains(&ExtendedBiome::TemperateGrassland));
+        assert_eq!(map.dithered_biome(15, 8, 5, 6), map.dithered_biome(15, 8, 1, 2));
+    }
 }
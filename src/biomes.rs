@@ -122,6 +122,9 @@ pub enum ExtendedBiome {
     BuriedTemple,
     OvergrownCitadel,
     DarkTower,
+    WorldTree,
+    TitanForge,
+    StormSpire,
 
     // ============ OCEAN BIOMES ============
 
@@ -167,6 +170,11 @@ pub enum ExtendedBiome {
     FumaroleField,    // Steam vents and sulfurous terrain
     VolcanicBeach,    // Black sand beaches near volcanoes
     HotSpot,          // Active volcanic hot spot area
+
+    // ============ POLAR BIOMES ============
+    IceSheet,         // Continental ice sheet covering polar land
+    PackIce,          // Frozen polar sea
+    Polynya,          // Open water enclosed by sea ice
 }
 
 impl ExtendedBiome {
@@ -306,6 +314,9 @@ impl ExtendedBiome {
             ExtendedBiome::BuriedTemple => (170, 150, 120),       // Sand-covered stone
             ExtendedBiome::OvergrownCitadel => (60, 90, 50),      // Vine-covered green
             ExtendedBiome::DarkTower => (25, 20, 30),              // Ominous dark obsidian
+            ExtendedBiome::WorldTree => (40, 125, 60),            // Vast living canopy
+            ExtendedBiome::TitanForge => (185, 75, 25),           // Glowing forge-fire
+            ExtendedBiome::StormSpire => (120, 140, 175),         // Storm-lashed grey stone
 
             // Ocean Biomes - Realistic Shallow
             ExtendedBiome::CoralReef => (255, 180, 150),          // Coral pink-orange
@@ -349,6 +360,9 @@ impl ExtendedBiome {
             ExtendedBiome::FumaroleField => (200, 190, 120),      // Sulfur yellow
             ExtendedBiome::VolcanicBeach => (40, 40, 45),         // Black sand
             ExtendedBiome::HotSpot => (180, 80, 50),              // Warm orange-red
+            ExtendedBiome::IceSheet => (250, 252, 255),           // Blinding white
+            ExtendedBiome::PackIce => (215, 232, 245),            // Pale blue-white
+            ExtendedBiome::Polynya => (30, 70, 120),              // Dark open water
         }
     }
 
@@ -438,6 +452,9 @@ impl ExtendedBiome {
             ExtendedBiome::BuriedTemple => "Buried Temple",
             ExtendedBiome::OvergrownCitadel => "Overgrown Citadel",
             ExtendedBiome::DarkTower => "Dark Tower",
+            ExtendedBiome::WorldTree => "World Tree",
+            ExtendedBiome::TitanForge => "Titan Forge",
+            ExtendedBiome::StormSpire => "Storm Spire",
 
             // Ocean Biomes - Realistic
             ExtendedBiome::CoralReef => "Coral Reef",
@@ -477,6 +494,9 @@ impl ExtendedBiome {
             ExtendedBiome::FumaroleField => "Fumarole Field",
             ExtendedBiome::VolcanicBeach => "Volcanic Beach",
             ExtendedBiome::HotSpot => "Hot Spot",
+            ExtendedBiome::IceSheet => "Ice Sheet",
+            ExtendedBiome::PackIce => "Pack Ice",
+            ExtendedBiome::Polynya => "Polynya",
         }
     }
 
@@ -724,7 +744,12 @@ impl ExtendedBiome {
 
     /// Check if this is a unique biome (exactly one per map, guaranteed)
     pub fn is_unique(&self) -> bool {
-        matches!(self, ExtendedBiome::DarkTower)
+        matches!(self,
+            ExtendedBiome::DarkTower |
+            ExtendedBiome::WorldTree |
+            ExtendedBiome::TitanForge |
+            ExtendedBiome::StormSpire
+        )
     }
 
     /// Get biome category for UI grouping
@@ -808,7 +833,12 @@ impl ExtendedBiome {
             ExtendedBiome::CyclopeanRuins |
             ExtendedBiome::BuriedTemple |
             ExtendedBiome::OvergrownCitadel |
-            ExtendedBiome::DarkTower => BiomeCategory::Ruins,
+            ExtendedBiome::DarkTower |
+            ExtendedBiome::TitanForge |
+            ExtendedBiome::StormSpire => BiomeCategory::Ruins,
+
+            // Unique wonders
+            ExtendedBiome::WorldTree => BiomeCategory::Mystical,
 
             // Ocean Zones - Realistic and Fantasy underwater biomes
             ExtendedBiome::CoralReef |
@@ -3256,118 +3286,25 @@ fn apply_cluster(
 // ============================================================================
 // UNIQUE BIOME PLACEMENT
 // ============================================================================
-// Some biomes appear only once per map (e.g., Dark Tower).
+// Some biomes appear only once per map (e.g., Dark Tower). Their placement
+// rules live in `biome_constraints` so they can be overridden from JSON.
 
-/// Place unique biomes (exactly one per map).
+/// Place unique biomes (exactly one per map) using the built-in placement rules.
 /// Returns the number of unique biomes placed.
 pub fn place_unique_biomes(
     biomes: &mut Tilemap<ExtendedBiome>,
     heightmap: &Tilemap<f32>,
+    temperature: &Tilemap<f32>,
+    moisture: &Tilemap<f32>,
+    stress_map: &Tilemap<f32>,
     seed: u64,
 ) -> usize {
-    use rand::SeedableRng;
-    use rand_chacha::ChaCha8Rng;
-
-    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0xDA4C_70BE));
-    let mut placed_count = 0;
-
-    // Place the Dark Tower
-    if place_dark_tower(biomes, heightmap, &mut rng) {
-        placed_count += 1;
-    }
-
-    placed_count
-}
-
-/// Place exactly one Dark Tower on the map.
-/// The Dark Tower appears on high ground, preferring dramatic locations.
-fn place_dark_tower(
-    biomes: &mut Tilemap<ExtendedBiome>,
-    heightmap: &Tilemap<f32>,
-    rng: &mut impl rand::Rng,
-) -> bool {
-    let width = biomes.width;
-    let height = biomes.height;
-
-    // Collect valid candidates for the Dark Tower location
-    // Must be: land (elevation > 0), high elevation preferred
-    // Preferably near or on ruins, mountains, or desolate areas
-    let mut candidates: Vec<(usize, usize, f32)> = Vec::new();
-
-    for y in 0..height {
-        for x in 0..width {
-            let elev = *heightmap.get(x, y);
-            let biome = *biomes.get(x, y);
-
-            // Must be land
-            if elev <= 0.0 {
-                continue;
-            }
-
-            // Calculate suitability score
-            let mut score: f32 = 0.0;
-
-            // Higher elevation is better (towers on high ground)
-            score += (elev / 1000.0).min(2.0);
-
-            // Bonus for certain biomes that make thematic sense
-            match biome {
-                // Ancient ruins - perfect location
-                ExtendedBiome::CyclopeanRuins |
-                ExtendedBiome::BuriedTemple |
-                ExtendedBiome::OvergrownCitadel => score += 3.0,
-
-                // Mountain/alpine areas - dramatic locations
-                ExtendedBiome::AlpineTundra |
-                ExtendedBiome::SnowyPeaks |
-                ExtendedBiome::RazorPeaks => score += 2.5,
-
-                // Wastelands and desolate areas
-                ExtendedBiome::VolcanicWasteland |
-                ExtendedBiome::Ashlands |
-                ExtendedBiome::BoneFields |
-                ExtendedBiome::VoidScar => score += 2.0,
-
-                // Other interesting locations
-                ExtendedBiome::WhisperingStones |
-                ExtendedBiome::StarfallCrater |
-                ExtendedBiome::LeyNexus => score += 1.5,
-
-                // Tundra and sparse lands work
-                ExtendedBiome::Tundra |
-                ExtendedBiome::Desert |
-                ExtendedBiome::Savanna => score += 0.5,
-
-                // Forests and other biomes are less ideal but possible
-                _ => score += 0.1,
-            }
-
-            // Only consider tiles with reasonable scores
-            if score > 0.5 {
-                candidates.push((x, y, score));
-            }
-        }
-    }
-
-    if candidates.is_empty() {
-        return false;
-    }
-
-    // Weight selection by score - higher scores more likely
-    let total_score: f32 = candidates.iter().map(|(_, _, s)| s).sum();
-
-    let mut pick = rng.gen::<f32>() * total_score;
-    let mut chosen = candidates.last().unwrap();
-
-    for candidate in &candidates {
-        pick -= candidate.2;
-        if pick <= 0.0 {
-            chosen = candidate;
-            break;
-        }
-    }
-
-    let (x, y, _) = *chosen;
-    biomes.set(x, y, ExtendedBiome::DarkTower);
-    true
+    let layers = crate::biome_constraints::PlacementLayers {
+        heightmap,
+        temperature,
+        moisture,
+        stress_map,
+    };
+    let spec = crate::biome_constraints::default_spec();
+    crate::biome_constraints::solve_placement(&spec, biomes, &layers, seed).placed.len()
 }
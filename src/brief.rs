@@ -0,0 +1,154 @@
+//! World briefs: natural-language descriptions turned into presets by an LLM
+//!
+//! The model never drives generation directly. It writes a `WorldPreset`,
+//! which is validated like any hand-written preset; a reply that fails to
+//! parse or validate is sent back with the error until the model gets it
+//! right or runs out of attempts. The preset can then be saved, read,
+//! edited and reused without the model.
+//!
+//! Any OpenAI-compatible chat completions endpoint works (a local Ollama
+//! server by default). `PLANET_LLM_ENDPOINT`, `PLANET_LLM_MODEL` and
+//! `PLANET_LLM_API_KEY` override the defaults.
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::preset::{WorldPreset, SCHEMA};
+
+/// How long one model reply may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Where and how to reach the model
+#[derive(Clone, Debug)]
+pub struct LlmConfig {
+    /// Chat completions URL
+    pub endpoint: String,
+    pub model: String,
+    /// Bearer token, if the endpoint needs one
+    pub api_key: Option<String>,
+    /// Replies tried before giving up on a valid preset
+    pub max_attempts: usize,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        Self {
+            endpoint: var("PLANET_LLM_ENDPOINT")
+                .unwrap_or_else(|| "http://localhost:11434/v1/chat/completions".to_string()),
+            model: var("PLANET_LLM_MODEL").unwrap_or_else(|| "llama3.1".to_string()),
+            api_key: var("PLANET_LLM_API_KEY"),
+            max_attempts: 3,
+        }
+    }
+}
+
+/// Ask the model for a preset matching `brief`
+pub fn preset_from_brief(brief: &str, config: &LlmConfig) -> Result<WorldPreset, String> {
+    let messages = vec![
+        system_message(),
+        json!({ "role": "user", "content": format!("World brief: {}", brief) }),
+    ];
+    let mut preset = converse(messages, config)?;
+    preset.brief = Some(brief.to_string());
+    Ok(preset)
+}
+
+/// Ask the model to change `preset` as `feedback` says, keeping everything else
+pub fn refine_preset(preset: &WorldPreset, feedback: &str, config: &LlmConfig) -> Result<WorldPreset, String> {
+    let messages = vec![
+        system_message(),
+        json!({
+            "role": "user",
+            "content": format!(
+                "Current preset:\n{}\n\nChange it as follows, keeping every other field as it is: {}",
+                preset.to_json(),
+                feedback
+            ),
+        }),
+    ];
+    let mut refined = converse(messages, config)?;
+    refined.brief = preset.brief.clone();
+    Ok(refined)
+}
+
+fn system_message() -> Value {
+    json!({
+        "role": "system",
+        "content": format!(
+            "You configure a procedural planet generator. Translate the user's description into a world \
+             preset. Reply with a single JSON object and nothing else, following this format:\n{}\n\
+             Only use the values listed. Leave a field null (or out) when the description says nothing about it.",
+            SCHEMA
+        ),
+    })
+}
+
+/// Keep asking until a reply parses and validates, feeding each error back
+fn converse(mut messages: Vec<Value>, config: &LlmConfig) -> Result<WorldPreset, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut last_error = String::from("no attempts were made");
+    for _ in 0..config.max_attempts.max(1) {
+        let reply = complete(&client, &messages, config)?;
+        let parsed = extract_json(&reply)
+            .ok_or_else(|| "the reply contained no JSON object".to_string())
+            .and_then(WorldPreset::from_json);
+        match parsed {
+            Ok(preset) => return Ok(preset),
+            Err(e) => {
+                messages.push(json!({ "role": "assistant", "content": reply }));
+                messages.push(json!({
+                    "role": "user",
+                    "content": format!("That preset is invalid: {}. Reply with the corrected JSON object only.", e),
+                }));
+                last_error = e;
+            }
+        }
+    }
+    Err(format!("no valid preset after {} attempts (last error: {})", config.max_attempts.max(1), last_error))
+}
+
+/// One chat completion: the text of the first choice
+fn complete(client: &reqwest::blocking::Client, messages: &[Value], config: &LlmConfig) -> Result<String, String> {
+    let body = json!({ "model": config.model, "messages": messages, "temperature": 0.2 });
+    let mut request = client.post(&config.endpoint).json(&body);
+    if let Some(ref key) = config.api_key {
+        request = request.bearer_auth(key);
+    }
+    let response: Value = request
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json())
+        .map_err(|e| format!("LLM request to {} failed: {}", config.endpoint, e))?;
+    response["choices"][0]["message"]["content"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "the LLM response had no message content".to_string())
+}
+
+/// The outermost `{...}` of a reply, skipping any prose or code fences around it
+pub(crate) fn extract_json(reply: &str) -> Option<&str> {
+    let start = reply.find('{')?;
+    let end = reply.rfind('}')?;
+    (end > start).then(|| &reply[start..=end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_json_from_chatty_reply() {
+        let reply = "Here is your world:\n```json\n{ \"name\": \"Ashfall\", \"magic\": true }\n```\nEnjoy!";
+        let preset = WorldPreset::from_json(extract_json(reply).unwrap()).unwrap();
+        assert_eq!(preset.name, "Ashfall");
+        assert!(preset.magic);
+        assert_eq!(extract_json("no json here"), None);
+        assert_eq!(extract_json("} backwards {"), None);
+    }
+}
@@ -0,0 +1,224 @@
+//! Thread and memory budget for embedding generation in a host application
+//!
+//! A `ResourceBudget` caps the worker threads parallel stages may use, the
+//! memory a generation run is expected to need, and whether the GPU may be
+//! used at all. It is honored by erosion (and so by sweeps, autotuning and
+//! hot reloads that pass the same `ErosionParams` on), by `WorldGenerator`,
+//! by world aging (`AgingConfig::budget`), and by the local chunk caches of
+//! the explorer, the viewer and local map exports (`ExportOptions::budget`).
+//!
+//! Memory is checked against an estimate made before anything is allocated,
+//! so a host can refuse or shrink a request instead of finding out the hard way.
+
+use std::mem::size_of;
+
+use crate::biomes::ExtendedBiome;
+use crate::erosion::hydraulic::DROPLET_BATCH_SIZE;
+use crate::erosion::utils::create_erosion_brush;
+use crate::erosion::ErosionParams;
+use crate::multiscale::local::LocalChunk;
+use crate::multiscale::DEFAULT_LOCAL_CACHE_SIZE;
+use crate::plates::PlateId;
+use crate::water_bodies::WaterBodyId;
+use crate::zlevel::{self, ZTile};
+
+/// Optional per-tile maps (polar, dunes, loess, seismic, succession, ...),
+/// counted as this many `f32` layers
+const OPTIONAL_LAYERS: usize = 12;
+
+/// Scratch layers erosion keeps alongside the heightmap (snapshot, deltas,
+/// hardness, flow accumulation, ice thickness and flux)
+const EROSION_WORKING_LAYERS: usize = 6;
+
+/// Steps a droplet typically survives; `droplet_max_steps` is only the cap
+const TYPICAL_DROPLET_STEPS: usize = 64;
+
+/// History bookkeeping per tile (territory, trade and frontier maps)
+const HISTORY_BYTES_PER_TILE: usize = 24;
+
+/// Limits a generation run must stay within
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResourceBudget {
+    /// Worker threads for parallel stages (None = all cores)
+    pub max_threads: Option<usize>,
+    /// Memory a run may need, in bytes (None = unlimited)
+    pub max_memory_bytes: Option<usize>,
+    /// Whether erosion may run on the GPU
+    pub allow_gpu: bool,
+}
+
+impl Default for ResourceBudget {
+    fn default() -> Self {
+        Self { max_threads: None, max_memory_bytes: None, allow_gpu: true }
+    }
+}
+
+/// Predicted memory of a generation run, in bytes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    /// Surface layers kept in the world (elevation, climate, biomes, plates, water, ...)
+    pub layers: usize,
+    /// Z-level voxels
+    pub zlevels: usize,
+    /// Scratch space while erosion runs
+    pub erosion: usize,
+    /// History simulation
+    pub history: usize,
+}
+
+impl MemoryEstimate {
+    /// Everything above held at once
+    pub fn total(&self) -> usize {
+        self.layers + self.zlevels + self.erosion + self.history
+    }
+
+    /// Format as human-readable string
+    pub fn summary(&self) -> String {
+        let mb = |bytes: usize| bytes as f64 / (1024.0 * 1024.0);
+        format!(
+            "Layers: {:.1}MB | Z-levels: {:.1}MB | Erosion: {:.1}MB | History: {:.1}MB | Total: {:.1}MB",
+            mb(self.layers),
+            mb(self.zlevels),
+            mb(self.erosion),
+            mb(self.history),
+            mb(self.total())
+        )
+    }
+}
+
+/// A run that would not fit its budget
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BudgetError {
+    /// The estimated memory exceeds `max_memory_bytes`
+    Memory { estimated: usize, limit: usize },
+}
+
+impl std::fmt::Display for BudgetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BudgetError::Memory { estimated, limit } => {
+                write!(f, "Estimated {} bytes exceeds the memory budget of {} bytes", estimated, limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BudgetError {}
+
+/// Predict the memory of generating a `width` x `height` world with the given
+/// erosion parameters, with or without history
+pub fn estimate_memory(width: usize, height: usize, erosion: &ErosionParams, history: bool) -> MemoryEstimate {
+    let tiles = width * height;
+    let per_tile = 5 * size_of::<f32>()
+        + size_of::<i32>()
+        + size_of::<ExtendedBiome>()
+        + size_of::<PlateId>()
+        + size_of::<WaterBodyId>()
+        + OPTIONAL_LAYERS * size_of::<f32>();
+    let depth = (zlevel::MAX_Z - zlevel::MIN_Z + 1) as usize;
+
+    let mut erosion_bytes = EROSION_WORKING_LAYERS * size_of::<f32>() * tiles;
+    if erosion.enable_hydraulic {
+        let brush = create_erosion_brush(erosion.droplet_erosion_radius).len();
+        let steps = erosion.droplet_max_steps.min(TYPICAL_DROPLET_STEPS);
+        erosion_bytes += DROPLET_BATCH_SIZE.min(erosion.hydraulic_iterations) * steps * brush * size_of::<(usize, f32)>();
+    }
+
+    MemoryEstimate {
+        layers: per_tile * tiles,
+        zlevels: depth * size_of::<ZTile>() * tiles,
+        erosion: erosion_bytes,
+        history: if history { HISTORY_BYTES_PER_TILE * tiles } else { 0 },
+    }
+}
+
+impl ResourceBudget {
+    /// Err if `estimate` would not fit the memory budget
+    pub fn check(&self, estimate: &MemoryEstimate) -> Result<(), BudgetError> {
+        match self.max_memory_bytes {
+            Some(limit) if estimate.total() > limit => Err(BudgetError::Memory { estimated: estimate.total(), limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Limit `params` to the budget's threads and GPU permission
+    pub fn apply_to_erosion(&self, params: &mut ErosionParams) {
+        if self.max_threads.is_some() {
+            params.max_threads = self.max_threads;
+        }
+        params.use_gpu &= self.allow_gpu;
+    }
+
+    /// Local chunks the multiscale cache may hold: as many as fit the memory
+    /// budget, never more than the default
+    pub fn local_cache_size(&self) -> usize {
+        match self.max_memory_bytes {
+            Some(limit) => (limit / LocalChunk::estimated_size()).clamp(1, DEFAULT_LOCAL_CACHE_SIZE),
+            None => DEFAULT_LOCAL_CACHE_SIZE,
+        }
+    }
+
+    /// Run `op` with parallel work limited to `max_threads`
+    pub fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        with_threads(self.max_threads, op)
+    }
+}
+
+/// Run `op` on a pool of `threads` workers, or on the global pool when `None`
+pub fn with_threads<R: Send>(threads: Option<usize>, op: impl FnOnce() -> R + Send) -> R {
+    let pool = threads.and_then(|n| rayon::ThreadPoolBuilder::new().num_threads(n.max(1)).build().ok());
+    match pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_scales_and_budget_checks() {
+        let params = ErosionParams::fast();
+        let small = estimate_memory(64, 32, &params, false);
+        let large = estimate_memory(128, 64, &params, true);
+        assert_eq!(large.layers, small.layers * 4);
+        assert_eq!(large.zlevels, small.zlevels * 4);
+        assert!(large.history > 0 && small.history == 0);
+
+        let no_droplets = ErosionParams { enable_hydraulic: false, ..params.clone() };
+        assert!(estimate_memory(64, 32, &no_droplets, false).erosion < small.erosion);
+
+        let tight = ResourceBudget { max_memory_bytes: Some(small.total()), ..Default::default() };
+        assert!(tight.check(&small).is_ok());
+        assert_eq!(
+            tight.check(&large),
+            Err(BudgetError::Memory { estimated: large.total(), limit: small.total() })
+        );
+        assert!(ResourceBudget::default().check(&large).is_ok());
+
+        #[cfg(feature = "history")]
+        {
+            let generator = crate::world::WorldGenerator::new().size(128, 64).erosion(params).with_history(Default::default());
+            assert_eq!(generator.estimate_memory(), large);
+            assert!(generator.budget(tight).try_generate().is_err(), "refused before generating");
+        }
+    }
+
+    #[test]
+    fn test_threads_gpu_and_cache_size() {
+        let budget = ResourceBudget { max_threads: Some(2), allow_gpu: false, ..Default::default() };
+        assert_eq!(budget.install(rayon::current_num_threads), 2);
+
+        let mut params = ErosionParams::default();
+        budget.apply_to_erosion(&mut params);
+        assert_eq!(params.max_threads, Some(2));
+        assert!(!params.use_gpu);
+
+        assert_eq!(ResourceBudget::default().local_cache_size(), DEFAULT_LOCAL_CACHE_SIZE);
+        let three_chunks = ResourceBudget { max_memory_bytes: Some(LocalChunk::estimated_size() * 3), ..Default::default() };
+        assert_eq!(three_chunks.local_cache_size(), 3);
+        let none = ResourceBudget { max_memory_bytes: Some(0), ..Default::default() };
+        assert_eq!(none.local_cache_size(), 1);
+    }
+}
@@ -0,0 +1,410 @@
+//! Campaigns: several saved worlds sharing one meta-history
+//!
+//! A campaign file (JSON) lists saved worlds and how they relate: a later age
+//! of the same planet, or a colony founded from another world. Sequels are
+//! generated from the end state of their parent world:
+//! - A later age keeps the parent's terrain and grows a new history on it;
+//!   the old age's structures remain as ruins and its greatest artifacts
+//!   survive as relics in dungeons and tombs
+//! - A colony is a new planet; the parent's most famous living figures
+//!   emigrate to found it, bringing the artifacts they carry
+//!
+//! Every carried artifact and emigrant is recorded as a cross-world reference,
+//! so either world can look up where the other's people and objects went.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::history::artifacts::{ArtifactEvent, ArtifactEventType, ArtifactLocation, ArtifactRarity};
+use crate::history::types::{FactionId, HeroId, Year};
+use crate::history::WorldHistory;
+use crate::world::{self, WorldData};
+
+/// Mixing constant for deriving sequel seeds from the parent seed
+const SEED_MIX: u64 = 0xD1B5_4A32_D192_ED03;
+
+/// Faction id no world uses: relics of an earlier age have no living makers
+pub const LOST_AGE: FactionId = FactionId(u32::MAX);
+
+/// How a campaign world follows from its parent
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum WorldRelation {
+    /// The first world of the campaign
+    Origin,
+    /// The same planet, `years_after` years after the parent's present
+    LaterAge { years_after: i32 },
+    /// A new world settled from the parent
+    Colony,
+}
+
+impl WorldRelation {
+    pub fn describe(&self) -> String {
+        match self {
+            WorldRelation::Origin => "origin".to_string(),
+            WorldRelation::LaterAge { years_after } => format!("{} years later", years_after),
+            WorldRelation::Colony => "colony".to_string(),
+        }
+    }
+}
+
+/// A saved world in a campaign
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct CampaignWorld {
+    pub label: String,
+    /// World file written by `WorldData::save`
+    pub path: String,
+    /// Seed of the world's history (later ages share their parent's terrain seed)
+    pub seed: u64,
+    pub relation: WorldRelation,
+    /// Label of the world this one follows from
+    pub parent: Option<String>,
+}
+
+/// What crossed between worlds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ReferenceKind {
+    Artifact,
+    Emigrant,
+}
+
+/// An artifact or figure of one world that reappears in another
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct CrossWorldReference {
+    pub kind: ReferenceKind,
+    pub name: String,
+    pub from_world: String,
+    /// Artifact or hero id in the source world
+    pub from_id: u32,
+    pub to_world: String,
+    /// Artifact or hero id in the destination world
+    pub to_id: u32,
+}
+
+/// Limits on what a sequel inherits
+#[derive(Clone, Debug)]
+pub struct SequelConfig {
+    /// Most artifacts that survive into a later age
+    pub max_relics: usize,
+    /// Most figures that emigrate to a colony
+    pub max_emigrants: usize,
+}
+
+impl Default for SequelConfig {
+    fn default() -> Self {
+        Self { max_relics: 5, max_emigrants: 3 }
+    }
+}
+
+/// The worlds of a campaign and their shared meta-history
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct Campaign {
+    pub name: String,
+    pub worlds: Vec<CampaignWorld>,
+    pub references: Vec<CrossWorldReference>,
+}
+
+impl Campaign {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), ..Default::default() }
+    }
+
+    pub fn load(path: &str) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        serde_json::from_str(&text).map_err(std::io::Error::other)
+    }
+
+    /// Load a campaign, or start a new one named after the file if it doesn't exist yet
+    pub fn load_or_new(path: &str) -> std::io::Result<Self> {
+        if std::path::Path::new(path).exists() {
+            return Self::load(path);
+        }
+        let name = std::path::Path::new(path).file_stem().map_or("campaign".into(), |s| s.to_string_lossy());
+        Ok(Self::new(&name))
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    pub fn world(&self, label: &str) -> Option<&CampaignWorld> {
+        self.worlds.iter().find(|w| w.label == label)
+    }
+
+    /// Register a saved world
+    pub fn add_world(&mut self, label: &str, path: &str, world: &WorldData, relation: WorldRelation, parent: Option<&str>) -> std::io::Result<()> {
+        if self.world(label).is_some() {
+            return Err(invalid(format!("campaign already has a world labelled '{}'", label)));
+        }
+        if let Some(parent) = parent.filter(|p| self.world(p).is_none()) {
+            return Err(invalid(format!("no world labelled '{}' in the campaign", parent)));
+        }
+        self.worlds.push(CampaignWorld {
+            label: label.to_string(),
+            path: path.to_string(),
+            seed: world.history.as_ref().map_or(world.seed, |h| h.seed),
+            relation,
+            parent: parent.map(str::to_string),
+        });
+        Ok(())
+    }
+
+    /// A world and its ancestors, oldest first
+    pub fn lineage(&self, label: &str) -> Vec<&CampaignWorld> {
+        let mut chain = Vec::new();
+        let mut next = self.world(label);
+        while let Some(world) = next {
+            chain.push(world);
+            next = world.parent.as_deref().and_then(|p| self.world(p));
+        }
+        chain.reverse();
+        chain
+    }
+
+    /// References leaving or entering a world
+    pub fn references_of<'a>(&'a self, label: &'a str) -> impl Iterator<Item = &'a CrossWorldReference> + 'a {
+        self.references.iter().filter(move |r| r.from_world == label || r.to_world == label)
+    }
+
+    /// Generate a sequel to the world labelled `parent`, save it to `path` and
+    /// record it (and everything it inherited) in the campaign
+    pub fn create_sequel(
+        &mut self,
+        parent: &str,
+        label: &str,
+        path: &str,
+        relation: WorldRelation,
+        config: &SequelConfig,
+    ) -> std::io::Result<WorldData> {
+        let Some(parent_entry) = self.world(parent) else {
+            return Err(invalid(format!("no world labelled '{}' in the campaign", parent)));
+        };
+        if self.world(label).is_some() {
+            return Err(invalid(format!("campaign already has a world labelled '{}'", label)));
+        }
+        if relation == WorldRelation::Origin {
+            return Err(invalid("a sequel must be a later age or a colony".to_string()));
+        }
+        let parent_world = WorldData::load(&parent_entry.path)?;
+        let seed = sequel_seed(parent_entry.seed, self.worlds.len());
+
+        let (world, references) = build_sequel(&parent_world, parent, label, relation, seed, config);
+        world.save(path)?;
+        self.add_world(label, path, &world, relation, Some(parent))?;
+        self.references.extend(references);
+        Ok(world)
+    }
+}
+
+fn invalid(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+}
+
+/// Derive a sequel's seed from its parent's seed and its place in the campaign
+pub fn sequel_seed(parent_seed: u64, index: usize) -> u64 {
+    parent_seed ^ (index as u64 + 1).wrapping_mul(SEED_MIX)
+}
+
+/// Generate a sequel world from `parent`'s end state, returning it with the
+/// references for everything it inherited
+pub fn build_sequel(
+    parent: &WorldData,
+    parent_label: &str,
+    label: &str,
+    relation: WorldRelation,
+    seed: u64,
+    config: &SequelConfig,
+) -> (WorldData, Vec<CrossWorldReference>) {
+    let mut world = match relation {
+        WorldRelation::LaterAge { .. } => {
+            let mut world = parent.clone();
+            world.regenerate_history(seed);
+            world
+        }
+        WorldRelation::Origin | WorldRelation::Colony => world::generate_world(parent.width, parent.height, seed),
+    };
+    let references = match (parent.history.as_ref(), world.history.as_mut()) {
+        (Some(old), Some(new)) => inherit(old, new, parent_label, label, relation, config),
+        _ => Vec::new(),
+    };
+    (world, references)
+}
+
+/// Carry relics (later age) or emigrants and their artifacts (colony) from `old` into `new`
+fn inherit(
+    old: &WorldHistory,
+    new: &mut WorldHistory,
+    from: &str,
+    to: &str,
+    relation: WorldRelation,
+    config: &SequelConfig,
+) -> Vec<CrossWorldReference> {
+    let reference = |kind, name: &str, from_id, to_id| CrossWorldReference {
+        kind,
+        name: name.to_string(),
+        from_world: from.to_string(),
+        from_id,
+        to_world: to.to_string(),
+        to_id,
+    };
+    let mut references = Vec::new();
+
+    // The parent's present lands at `offset` in the sequel's calendar
+    let (offset, hosts) = match relation {
+        WorldRelation::LaterAge { years_after } => (-years_after, None),
+        _ => {
+            let mut founders: Vec<_> = new.factions.all().collect();
+            founders.sort_by_key(|f| (f.founded, f.id.0));
+            match founders.first() {
+                Some(f) => (f.founded.0, Some(f.id)),
+                None => return references,
+            }
+        }
+    };
+
+    // Emigrants: the most famous figures alive at the parent's end
+    let mut emigrants: HashMap<HeroId, HeroId> = HashMap::new();
+    if let Some(faction) = hosts {
+        let mut living: Vec<_> = old.heroes.all().filter(|h| h.death_year.is_none()).collect();
+        living.sort_by_key(|h| (Reverse(h.fame), h.id.0));
+        for hero in living.into_iter().take(config.max_emigrants) {
+            let mut founder = hero.clone();
+            founder.id = new.heroes.new_id();
+            founder.faction = faction;
+            founder.birth_year = Year(hero.birth_year.0 + offset);
+            founder.death_location = None;
+            founder.achievements.clear();
+            founder.artifacts_created.clear();
+            founder.burial_site = None;
+            founder.titles.push(format!("Founder from {}", from));
+            emigrants.insert(hero.id, founder.id);
+            references.push(reference(ReferenceKind::Emigrant, &hero.full_name(), hero.id.0, founder.id.0));
+            new.heroes.add(founder);
+        }
+    }
+
+    // Artifacts: what the emigrants carry, or the greatest survivors of the old age
+    let mut carried: Vec<_> = old
+        .artifacts
+        .all()
+        .filter(|a| !a.is_destroyed)
+        .filter(|a| match hosts {
+            Some(_) => a.current_owner.is_some_and(|o| emigrants.contains_key(&o)),
+            None => a.rarity >= ArtifactRarity::Epic,
+        })
+        .collect();
+    carried.sort_by_key(|a| (Reverse(a.rarity), a.creation_year, a.id.0));
+    if hosts.is_none() {
+        carried.truncate(config.max_relics);
+    }
+
+    let mut dungeons: Vec<_> = new.dungeons.dungeons.values().map(|d| (d.id, d.name.clone(), d.location, d.depth_min)).collect();
+    dungeons.sort_by_key(|d| d.0 .0);
+
+    for (i, artifact) in carried.into_iter().enumerate() {
+        let mut relic = artifact.clone();
+        relic.id = new.artifacts.new_id();
+        let map = |id: Option<HeroId>| id.and_then(|id| emigrants.get(&id).copied());
+        relic.creator = map(artifact.creator);
+        relic.created_for = map(artifact.created_for);
+        relic.creation_year = Year(artifact.creation_year.0 + offset);
+        for record in &mut relic.history {
+            record.year = Year(record.year.0 + offset);
+            record.person = map(record.person);
+        }
+
+        match hosts {
+            Some(faction) => {
+                let bearer = map(artifact.current_owner);
+                relic.faction_origin = faction;
+                relic.current_owner = bearer;
+                if let Some(bearer) = bearer {
+                    relic.current_location = ArtifactLocation::WithHero(bearer);
+                }
+                relic.history.push(ArtifactEvent {
+                    year: Year(offset),
+                    event_type: ArtifactEventType::Gifted,
+                    location: None,
+                    person: bearer,
+                    description: format!("Carried from {} to found a new home", from),
+                });
+            }
+            None => {
+                relic.faction_origin = LOST_AGE;
+                relic.current_owner = None;
+                // The terrain is unchanged, so relics resting somewhere stay there;
+                // the rest end up in the new age's dungeons
+                relic.current_location = match (artifact.current_location.coordinates(), dungeons.get(i % dungeons.len().max(1))) {
+                    (Some((x, y, z)), _) => ArtifactLocation::Hidden { x, y, z },
+                    (None, Some((id, name, (x, y), z))) => {
+                        new.artifacts.artifacts_by_dungeon.entry(*id).or_default().push(relic.id);
+                        if let Some(dungeon) = new.dungeons.dungeons.get_mut(id) {
+                            dungeon.artifacts_present.push(relic.id);
+                        }
+                        ArtifactLocation::InDungeon { x: *x, y: *y, z: *z, dungeon_name: name.clone() }
+                    }
+                    (None, None) => continue,
+                };
+                relic.history.push(ArtifactEvent {
+                    year: Year(offset),
+                    event_type: ArtifactEventType::Lost,
+                    location: relic.current_location.coordinates(),
+                    person: None,
+                    description: format!("Passed out of memory when the age of {} ended", from),
+                });
+            }
+        }
+
+        references.push(reference(ReferenceKind::Artifact, &artifact.name, artifact.id.0, relic.id.0));
+        new.artifacts.add(relic);
+    }
+
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_later_age_inherits_relics() {
+        let parent = world::generate_world(96, 48, 7);
+        let relation = WorldRelation::LaterAge { years_after: 800 };
+        let (sequel, references) = build_sequel(&parent, "first", "second", relation, sequel_seed(7, 1), &SequelConfig::default());
+
+        // Same planet, new history
+        assert_eq!(sequel.heightmap.get(40, 20), parent.heightmap.get(40, 20));
+        let history = sequel.history.as_ref().unwrap();
+        assert_eq!(history.seed, sequel_seed(7, 1));
+
+        assert!(!references.is_empty() && references.len() <= 5);
+        for r in &references {
+            assert_eq!(r.kind, ReferenceKind::Artifact);
+            let relic = history.artifacts.get(crate::history::types::ArtifactId(r.to_id)).unwrap();
+            assert_eq!(relic.name, r.name);
+            assert_eq!(relic.faction_origin, LOST_AGE);
+            assert!(relic.history.iter().all(|e| e.year <= Year(-800)));
+        }
+    }
+
+    #[test]
+    fn test_campaign_registry() {
+        let world = world::generate_world(64, 32, 3);
+        let mut campaign = Campaign::new("saga");
+        campaign.add_world("home", "home.bin", &world, WorldRelation::Origin, None).unwrap();
+        campaign.add_world("moon", "moon.bin", &world, WorldRelation::Colony, Some("home")).unwrap();
+        assert!(campaign.add_world("home", "again.bin", &world, WorldRelation::Origin, None).is_err());
+        assert!(campaign.add_world("orphan", "o.bin", &world, WorldRelation::Colony, Some("nowhere")).is_err());
+
+        let lineage: Vec<_> = campaign.lineage("moon").iter().map(|w| w.label.as_str()).collect();
+        assert_eq!(lineage, vec!["home", "moon"]);
+
+        let path = std::env::temp_dir().join("campaign_registry_test.json");
+        let path = path.to_str().unwrap();
+        campaign.save(path).unwrap();
+        let loaded = Campaign::load(path).unwrap();
+        assert_eq!(loaded.worlds.len(), 2);
+        assert_eq!(loaded.world("moon").unwrap().relation, WorldRelation::Colony);
+        let _ = std::fs::remove_file(path);
+    }
+}
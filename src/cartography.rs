@@ -0,0 +1,532 @@
+//! Cartographic themes for raster map exports
+//!
+//! A `MapTheme` decides how a world looks on paper: how terrain is coloured,
+//! the paper and ink, hatching, river and road line weights, and the label
+//! face. Themes are JSON data files, so a map's look can change without code
+//! edits. The built-in themes live in `themes/` and are compiled in:
+//! - `parchment`: fantasy map, biome colours faded into paper, hatched seas
+//! - `satellite`: hypsometric relief with hillshading, no labels
+//! - `retro`: coloured ASCII glyphs on black, one per tile
+//! - `political`: faction territories with borders, roads and bold labels
+
+use std::collections::HashMap;
+
+use image::{Rgb, RgbImage};
+
+use crate::ascii;
+use crate::history::FactionId;
+use crate::multiscale::is_water_biome;
+use crate::names::PlaceKind;
+use crate::tilemap::Tilemap;
+use crate::world::WorldData;
+
+/// An RGB colour as stored in theme files
+pub type Color = (u8, u8, u8);
+
+/// Elevation (m) above which `HatchArea::Mountains` hatching applies
+const MOUNTAIN_HATCH_ELEVATION: f32 = 1800.0;
+
+/// Elevation difference (m) across one tile that gives full hillshade
+const HILLSHADE_RELIEF: f32 = 1000.0;
+
+/// Glyph cell size below which glyph themes fall back to flat colour
+const MIN_GLYPH_CELL: u32 = 6;
+
+const BUILTIN_THEMES: &[(&str, &str)] = &[
+    ("parchment", include_str!("../themes/parchment.json")),
+    ("satellite", include_str!("../themes/satellite.json")),
+    ("retro", include_str!("../themes/retro.json")),
+    ("political", include_str!("../themes/political.json")),
+];
+
+/// How the terrain underneath lines and labels is coloured
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TerrainStyle {
+    /// Biome colours
+    Biomes,
+    /// Colour ramp by elevation (`elevation_stops`)
+    Hypsometric,
+    /// Faction territories over plain land and water
+    Political,
+    /// One biome glyph per tile, in the biome colour
+    Glyphs,
+}
+
+/// Where hatching lines are drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HatchArea {
+    Water,
+    Mountains,
+    /// Unexplored areas of known-world maps only
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Hatching {
+    pub over: HatchArea,
+    /// Pixels between diagonal lines
+    pub spacing: u32,
+    pub color: Color,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LineStyle {
+    pub color: Color,
+    /// Line weight in output pixels
+    pub width: f32,
+    #[serde(default)]
+    pub dashed: bool,
+}
+
+/// Label typefaces available to the rasterizer (5x7 bitmap glyphs)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelFont {
+    Plain,
+    Bold,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LabelStyle {
+    pub font: LabelFont,
+    /// Integer glyph magnification
+    pub scale: u32,
+    pub color: Color,
+    /// Outline letters in the paper colour
+    #[serde(default)]
+    pub halo: bool,
+    #[serde(default)]
+    pub uppercase: bool,
+}
+
+/// A named cartographic style
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MapTheme {
+    pub name: String,
+    pub terrain: TerrainStyle,
+    /// Background, and the colour terrain is faded toward
+    pub paper: Color,
+    /// Borders, unknown-area hatching and misc linework
+    pub ink: Color,
+    /// Unclaimed land (political)
+    pub land: Color,
+    /// Water (political)
+    pub water: Color,
+    /// (elevation m, colour) ramp for hypsometric terrain, ascending
+    #[serde(default)]
+    pub elevation_stops: Vec<(f32, Color)>,
+    /// How far terrain colours are faded toward the paper (0-1)
+    #[serde(default)]
+    pub tint: f32,
+    /// Hillshade strength on land (0-1)
+    #[serde(default)]
+    pub hillshade: f32,
+    #[serde(default)]
+    pub hatching: Option<Hatching>,
+    #[serde(default)]
+    pub rivers: Option<LineStyle>,
+    #[serde(default)]
+    pub roads: Option<LineStyle>,
+    #[serde(default)]
+    pub labels: Option<LabelStyle>,
+}
+
+impl Default for MapTheme {
+    fn default() -> Self {
+        Self::builtin("parchment").expect("parchment theme is built in")
+    }
+}
+
+impl MapTheme {
+    /// Names of the compiled-in themes
+    pub fn builtin_names() -> Vec<&'static str> {
+        BUILTIN_THEMES.iter().map(|&(name, _)| name).collect()
+    }
+
+    pub fn builtin(name: &str) -> Option<MapTheme> {
+        BUILTIN_THEMES
+            .iter()
+            .find(|&&(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(_, json)| serde_json::from_str(json).expect("built-in theme is valid JSON"))
+    }
+
+    /// Load a theme from a JSON file
+    pub fn load(path: &str) -> Result<MapTheme, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        serde_json::from_str(&json).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// A built-in theme by name, otherwise a theme file path
+    pub fn resolve(spec: &str) -> Result<MapTheme, String> {
+        match Self::builtin(spec) {
+            Some(theme) => Ok(theme),
+            None if std::path::Path::new(spec).exists() => Self::load(spec),
+            None => Err(format!(
+                "unknown theme '{}' (built in: {})",
+                spec,
+                Self::builtin_names().join(", ")
+            )),
+        }
+    }
+
+    fn hypsometric(&self, elevation: f32) -> Color {
+        let stops = &self.elevation_stops;
+        if stops.is_empty() {
+            return ascii::height_color(elevation);
+        }
+        if elevation <= stops[0].0 {
+            return stops[0].1;
+        }
+        for pair in stops.windows(2) {
+            let ((h0, c0), (h1, c1)) = (pair[0], pair[1]);
+            if elevation <= h1 {
+                return blend(c0, c1, (elevation - h0) / (h1 - h0).max(f32::EPSILON));
+            }
+        }
+        stops[stops.len() - 1].1
+    }
+}
+
+/// A text label anchored at a world tile
+#[derive(Clone, Debug)]
+pub struct MapLabel {
+    pub text: String,
+    pub x: usize,
+    pub y: usize,
+}
+
+pub(crate) fn blend(a: Color, b: Color, t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |a: u8, b: u8| (a as f32 * (1.0 - t) + b as f32 * t).round() as u8;
+    (mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
+fn put(img: &mut RgbImage, x: i32, y: i32, c: Color) {
+    if x >= 0 && y >= 0 && (x as u32) < img.width() && (y as u32) < img.height() {
+        img.put_pixel(x as u32, y as u32, Rgb([c.0, c.1, c.2]));
+    }
+}
+
+/// True on the diagonal hatching lines
+pub(crate) fn on_hatch(x: u32, y: u32, spacing: u32) -> bool {
+    (x + y).is_multiple_of(spacing.max(2))
+}
+
+/// Terrain, hatching, rivers and roads at the given output size, without labels
+pub fn render_base(world: &WorldData, theme: &MapTheme, width: usize, height: usize) -> RgbImage {
+    let mut img = RgbImage::new(width as u32, height as u32);
+    let elevation = world.heightmap.resample(width, height);
+    let biomes = world.biomes.resample_nearest(width, height);
+    let tiles_per_pixel = world.width as f32 / width as f32;
+
+    let territory = match theme.terrain {
+        TerrainStyle::Political => territories(world, width, height),
+        _ => None,
+    };
+
+    for (x, y, &h) in elevation.iter() {
+        let biome = *biomes.get(x, y);
+        let water = h <= 0.0 || is_water_biome(biome);
+        let mut color = match theme.terrain {
+            TerrainStyle::Biomes => biome.color(),
+            TerrainStyle::Hypsometric => theme.hypsometric(h),
+            TerrainStyle::Political if water => theme.water,
+            TerrainStyle::Political => territory
+                .as_ref()
+                .and_then(|(t, colors)| t.get(x, y).and_then(|id| colors.get(&id).copied()))
+                .unwrap_or(theme.land),
+            TerrainStyle::Glyphs => theme.paper,
+        };
+
+        if theme.hillshade > 0.0 && !water && theme.terrain != TerrainStyle::Glyphs {
+            // Light from the north-west
+            let (x0, y0) = (x.saturating_sub(1), y.saturating_sub(1));
+            let (x1, y1) = ((x + 1).min(width - 1), (y + 1).min(height - 1));
+            let relief = (*elevation.get(x0, y0) - *elevation.get(x1, y1)) / (2.0 * tiles_per_pixel.min(1.0));
+            let light = (relief / HILLSHADE_RELIEF * tiles_per_pixel.max(1.0)).clamp(-1.0, 1.0);
+            let target = if light > 0.0 { (255, 255, 255) } else { (0, 0, 0) };
+            color = blend(color, target, light.abs() * theme.hillshade * 0.5);
+        }
+        color = blend(color, theme.paper, theme.tint);
+
+        if let Some(hatching) = &theme.hatching {
+            let hatched = match hatching.over {
+                HatchArea::Water => water,
+                HatchArea::Mountains => h > MOUNTAIN_HATCH_ELEVATION,
+                HatchArea::Unknown => false,
+            };
+            if hatched && on_hatch(x as u32, y as u32, hatching.spacing) {
+                color = hatching.color;
+            }
+        }
+
+        img.put_pixel(x as u32, y as u32, Rgb([color.0, color.1, color.2]));
+    }
+
+    if let Some((territory, _)) = territory {
+        draw_borders(&mut img, &territory, theme.ink);
+    }
+    if theme.terrain == TerrainStyle::Glyphs {
+        draw_glyphs(&mut img, world);
+    }
+    if let Some(style) = &theme.rivers {
+        draw_rivers(&mut img, world, style);
+    }
+    #[cfg(feature = "history")]
+    if let Some(style) = &theme.roads {
+        draw_roads(&mut img, world, style);
+    }
+
+    img
+}
+
+/// The full themed map: `render_base` plus place name labels
+pub fn render_map(world: &WorldData, theme: &MapTheme, width: usize, height: usize) -> RgbImage {
+    let mut img = render_base(world, theme, width, height);
+    if let Some(style) = &theme.labels {
+        draw_labels(&mut img, world, theme, style, &world_labels(world));
+    }
+    img
+}
+
+/// Named places from towns up, highest label priority first
+fn world_labels(world: &WorldData) -> Vec<MapLabel> {
+    let Some(names) = &world.names else {
+        return Vec::new();
+    };
+    names
+        .by_priority()
+        .into_iter()
+        .filter(|(_, place)| place.priority >= PlaceKind::Town.base_priority())
+        .map(|((x, y), place)| MapLabel { text: place.name.clone(), x, y })
+        .collect()
+}
+
+/// Owner of each pixel, and each faction's colour
+type Territories = (Tilemap<Option<FactionId>>, HashMap<FactionId, Color>);
+
+/// Who owns each pixel at the output size, and each faction's colour
+#[cfg(feature = "history")]
+fn territories(world: &WorldData, width: usize, height: usize) -> Option<Territories> {
+    let history = world.history.as_ref()?;
+    let colors = history.factions.all().map(|f| (f.id, f.color)).collect();
+    Some((history.territories.territory_map.resample_nearest(width, height), colors))
+}
+
+/// A world without history has no territories to colour
+#[cfg(not(feature = "history"))]
+fn territories(_: &WorldData, _: usize, _: usize) -> Option<Territories> {
+    None
+}
+
+/// Outline territory edges in ink
+fn draw_borders(img: &mut RgbImage, territory: &Tilemap<Option<FactionId>>, ink: Color) {
+    for (x, y, owner) in territory.iter() {
+        let east = territory.get((x + 1) % territory.width, y);
+        let south = territory.get(x, (y + 1).min(territory.height - 1));
+        let edge = |other: &Option<_>| owner.is_some() && other.is_some() && owner != other;
+        if edge(east) || edge(south) {
+            put(img, x as i32, y as i32, ink);
+        }
+    }
+}
+
+/// Draw each tile's biome glyph, coloured by biome, over the paper
+fn draw_glyphs(img: &mut RgbImage, world: &WorldData) {
+    let cell_w = img.width() / world.width as u32;
+    let cell_h = img.height() / world.height as u32;
+    let font = ascii::create_bitmap_font();
+    for (x, y, biome) in world.biomes.iter() {
+        let (cx, cy) = (x as u32 * cell_w, y as u32 * cell_h);
+        let glyph = font.get(&ascii::biome_char(biome));
+        match glyph {
+            Some(rows) if cell_w.min(cell_h) >= MIN_GLYPH_CELL => {
+                let (ox, oy) = ((cell_w.saturating_sub(5)) / 2, (cell_h.saturating_sub(7)) / 2);
+                for (row, bits) in rows.iter().enumerate() {
+                    for col in 0..5u32 {
+                        if (bits >> (4 - col)) & 1 == 1 {
+                            put(img, (cx + ox + col) as i32, (cy + oy + row as u32) as i32, biome.color());
+                        }
+                    }
+                }
+            }
+            _ => {
+                for py in cy..cy + cell_h.max(1) {
+                    for px in cx..cx + cell_w.max(1) {
+                        put(img, px as i32, py as i32, biome.color());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Stamp a filled disc of the given diameter
+fn stamp(img: &mut RgbImage, x: f32, y: f32, diameter: f32, color: Color) {
+    let r = (diameter / 2.0).max(0.5);
+    let reach = r.ceil() as i32;
+    for dy in -reach..=reach {
+        for dx in -reach..=reach {
+            let (px, py) = (x.floor() as i32 + dx, y.floor() as i32 + dy);
+            let (cx, cy) = (px as f32 + 0.5 - x, py as f32 + 0.5 - y);
+            if cx * cx + cy * cy <= r * r {
+                put(img, px, py, color);
+            }
+        }
+    }
+}
+
+fn draw_rivers(img: &mut RgbImage, world: &WorldData, style: &LineStyle) {
+    let Some(network) = &world.river_network else { return };
+    let sx = img.width() as f32 / world.width as f32;
+    let sy = img.height() as f32 / world.height as f32;
+    for segment in &network.segments {
+        let samples = (segment.approximate_length(10) * sx.max(sy) * 2.0) as usize + 2;
+        for i in 0..=samples {
+            let pt = segment.evaluate(i as f32 / samples as f32);
+            // Wider rivers draw heavier, scaled from the theme's base weight
+            let weight = style.width * pt.width.max(0.5).sqrt();
+            stamp(img, (pt.world_x + 0.5) * sx, (pt.world_y + 0.5) * sy, weight, style.color);
+        }
+    }
+}
+
+#[cfg(feature = "history")]
+fn draw_roads(img: &mut RgbImage, world: &WorldData, style: &LineStyle) {
+    let Some(history) = &world.history else { return };
+    let sx = img.width() as f32 / world.width as f32;
+    let sy = img.height() as f32 / world.height as f32;
+    for route in history.trade.routes.values() {
+        let mut travelled = 0.0f32;
+        for pair in route.path.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            // Don't streak across the map where a route wraps around the seam
+            if x0.abs_diff(x1) > world.width / 2 {
+                continue;
+            }
+            let (ax, ay) = ((x0 as f32 + 0.5) * sx, (y0 as f32 + 0.5) * sy);
+            let (bx, by) = ((x1 as f32 + 0.5) * sx, (y1 as f32 + 0.5) * sy);
+            let length = ((bx - ax).powi(2) + (by - ay).powi(2)).sqrt();
+            let steps = (length * 2.0).ceil().max(1.0) as usize;
+            for i in 0..=steps {
+                let t = i as f32 / steps as f32;
+                let along = travelled + t * length;
+                if style.dashed && (along / (4.0 * style.width.max(1.0))) as u32 % 2 == 1 {
+                    continue;
+                }
+                stamp(img, ax + (bx - ax) * t, ay + (by - ay) * t, style.width, style.color);
+            }
+            travelled += length;
+        }
+    }
+}
+
+/// Draw labels centred on their tiles, skipping any that would overlap one already placed
+pub fn draw_labels(img: &mut RgbImage, world: &WorldData, theme: &MapTheme, style: &LabelStyle, labels: &[MapLabel]) {
+    let font = ascii::create_bitmap_font();
+    let scale = style.scale.max(1) as i32;
+    let advance = 6 * scale + if style.font == LabelFont::Bold { scale } else { 0 };
+    let sx = img.width() as f32 / world.width as f32;
+    let sy = img.height() as f32 / world.height as f32;
+    let mut placed: Vec<(i32, i32, i32, i32)> = Vec::new();
+
+    for label in labels {
+        let text = if style.uppercase { label.text.to_uppercase() } else { label.text.clone() };
+        let text_w = text.chars().count() as i32 * advance;
+        let text_h = 7 * scale;
+        let left = ((label.x as f32 + 0.5) * sx) as i32 - text_w / 2;
+        let top = ((label.y as f32 + 0.5) * sy) as i32 - text_h / 2;
+        let bounds = (left - 2, top - 2, left + text_w + 2, top + text_h + 2);
+        if bounds.0 < 0 || bounds.1 < 0 || bounds.2 > img.width() as i32 || bounds.3 > img.height() as i32 {
+            continue;
+        }
+        let overlaps = |b: &&(i32, i32, i32, i32)| bounds.0 < b.2 && b.0 < bounds.2 && bounds.1 < b.3 && b.1 < bounds.3;
+        if placed.iter().any(|b| overlaps(&b)) {
+            continue;
+        }
+        placed.push(bounds);
+
+        // Collect the lit pixels once so the halo can be drawn underneath
+        let mut pixels = Vec::new();
+        for (i, ch) in text.chars().enumerate() {
+            let Some(rows) = font.get(&ch) else { continue };
+            let gx = left + i as i32 * advance;
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..5i32 {
+                    if (bits >> (4 - col)) & 1 == 0 {
+                        continue;
+                    }
+                    for dy in 0..scale {
+                        for dx in 0..scale {
+                            let (px, py) = (gx + col * scale + dx, top + row as i32 * scale + dy);
+                            pixels.push((px, py));
+                            if style.font == LabelFont::Bold {
+                                pixels.push((px + scale, py));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        if style.halo {
+            for &(px, py) in &pixels {
+                for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                    put(img, px + dx, py + dy, theme.paper);
+                }
+            }
+        }
+        for &(px, py) in &pixels {
+            put(img, px, py, style.color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biomes::ExtendedBiome;
+    use crate::world::generate_test_world;
+
+    #[test]
+    fn test_builtin_themes_and_files() {
+        for name in MapTheme::builtin_names() {
+            let theme = MapTheme::builtin(name).unwrap();
+            assert_eq!(theme.name, name);
+            assert_eq!(MapTheme::resolve(&name.to_uppercase()), Ok(theme));
+        }
+        assert!(MapTheme::resolve("no-such-theme").is_err());
+
+        // A user theme: optional sections may be left out
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ink.json");
+        let json = r#"{ "name": "ink", "terrain": "hypsometric", "paper": [255, 255, 255],
+            "ink": [0, 0, 0], "land": [250, 250, 250], "water": [200, 200, 255],
+            "elevation_stops": [[0, [0, 0, 255]], [1000, [255, 0, 0]]] }"#;
+        std::fs::write(&path, json).unwrap();
+        let theme = MapTheme::resolve(path.to_str().unwrap()).unwrap();
+        assert_eq!(theme.labels, None);
+        assert_eq!(theme.hypsometric(500.0), (128, 0, 128));
+        assert_eq!(theme.hypsometric(-50.0), (0, 0, 255));
+    }
+
+    #[test]
+    fn test_render_themes() {
+        let mut world = generate_test_world();
+        world.heightmap.set(0, 0, -500.0);
+        world.biomes.set(0, 0, ExtendedBiome::Ocean);
+
+        for name in MapTheme::builtin_names() {
+            let theme = MapTheme::builtin(name).unwrap();
+            let img = render_map(&world, &theme, 32, 32);
+            assert_eq!((img.width(), img.height()), (32, 32));
+        }
+
+        // Political maps paint water in the theme's water colour
+        let political = MapTheme::builtin("political").unwrap();
+        let img = render_base(&world, &political, 4, 4);
+        let expected = blend(political.water, political.paper, political.tint);
+        assert_eq!(img.get_pixel(0, 0).0, [expected.0, expected.1, expected.2]);
+    }
+}
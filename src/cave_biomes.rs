@@ -0,0 +1,563 @@
+//! Underground biomes: mushroom caverns, crystal galleries and sunless seas
+//!
+//! Every connected cave region (open cave flood-filled within one cavern layer)
+//! gets a biome from three things:
+//! - Depth: the deeper the layer, the less surface water reaches it
+//! - Moisture seepage: rain soaking down from the surface, plus the aquifers,
+//!   underground rivers and cave lakes the region touches
+//! - Geothermal gradient: the rock temperature from `geothermal`, which warms
+//!   with depth and faster under stressed crust and near magma
+//!
+//! Wet regions flood into sunless seas, hot rock grows crystal galleries and
+//! damp, mild regions fill with fungus; the rest stays bare stone. Each biome
+//! redresses its region's floor with its own tiles and lists the fauna that
+//! spawn there and the lore encounters a subterranean wanderer can meet.
+
+use std::collections::VecDeque;
+
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::geothermal::GeothermalMap;
+use crate::history::monsters::MonsterSpecies;
+use crate::tilemap::Tilemap;
+use crate::zlevel::{Tilemap3D, ZTile, CAVERN_1_MAX, CAVERN_1_MIN, CAVERN_2_MAX, CAVERN_2_MIN, CAVERN_3_MAX, CAVERN_3_MIN};
+
+/// Marks a z-level tile that belongs to no cave region
+const NO_REGION: u16 = u16::MAX;
+
+/// Underground biome of a cave region
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum CaveBiome {
+    /// Bare stone: too dry, too cold or too hot for anything to grow
+    Barren,
+    /// Damp, mild caverns overgrown with moss, fungus and giant mushrooms
+    MushroomCavern,
+    /// Hot rock where mineral water has grown crystals over every surface
+    CrystalGallery,
+    /// Caverns flooded by seepage into lightless lakes
+    SunlessSea,
+}
+
+impl CaveBiome {
+    pub fn all() -> &'static [CaveBiome] {
+        &[CaveBiome::Barren, CaveBiome::MushroomCavern, CaveBiome::CrystalGallery, CaveBiome::SunlessSea]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CaveBiome::Barren => "Barren Cave",
+            CaveBiome::MushroomCavern => "Mushroom Cavern",
+            CaveBiome::CrystalGallery => "Crystal Gallery",
+            CaveBiome::SunlessSea => "Sunless Sea",
+        }
+    }
+
+    /// Creatures that spawn in this biome, with relative weights
+    pub fn fauna(&self) -> &'static [CaveFauna] {
+        match self {
+            CaveBiome::Barren => &[
+                CaveFauna { name: "Cave Crawler", weight: 4, group: (1, 2), species: Some(MonsterSpecies::CaveCrawler) },
+                CaveFauna { name: "Rock Bat", weight: 5, group: (3, 12), species: None },
+                CaveFauna { name: "Deep Worm", weight: 1, group: (1, 1), species: Some(MonsterSpecies::DeepWorm) },
+            ],
+            CaveBiome::MushroomCavern => &[
+                CaveFauna { name: "Spore Beetle", weight: 6, group: (2, 8), species: None },
+                CaveFauna { name: "Cap Grazer", weight: 4, group: (1, 4), species: None },
+                CaveFauna { name: "Giant Spider", weight: 2, group: (1, 3), species: Some(MonsterSpecies::GiantSpider) },
+                CaveFauna { name: "Goblin Foragers", weight: 2, group: (3, 6), species: Some(MonsterSpecies::GoblinBand) },
+            ],
+            CaveBiome::CrystalGallery => &[
+                CaveFauna { name: "Crystal Mite", weight: 5, group: (4, 16), species: None },
+                CaveFauna { name: "Shard Lizard", weight: 3, group: (1, 2), species: None },
+                CaveFauna { name: "Stone Elemental", weight: 1, group: (1, 1), species: Some(MonsterSpecies::Elemental) },
+                CaveFauna { name: "Dark Elf Prospectors", weight: 2, group: (2, 5), species: Some(MonsterSpecies::DarkElf) },
+            ],
+            CaveBiome::SunlessSea => &[
+                CaveFauna { name: "Blind Cavefish", weight: 6, group: (5, 20), species: None },
+                CaveFauna { name: "Pale Eel", weight: 3, group: (1, 3), species: None },
+                CaveFauna { name: "Shore Crawler", weight: 2, group: (1, 2), species: Some(MonsterSpecies::CaveCrawler) },
+                CaveFauna { name: "Deep Worm", weight: 1, group: (1, 1), species: Some(MonsterSpecies::DeepWorm) },
+            ],
+        }
+    }
+
+    /// Lore encounters a wanderer can meet in this biome
+    pub fn encounters(&self) -> &'static [CaveEncounter] {
+        match self {
+            CaveBiome::Barren => &[CaveEncounter::LostExpedition, CaveEncounter::EchoingVoices, CaveEncounter::AncientCarvings],
+            CaveBiome::MushroomCavern => &[CaveEncounter::SporeVisions, CaveEncounter::HermitGardener, CaveEncounter::LostExpedition],
+            CaveBiome::CrystalGallery => &[CaveEncounter::SingingCrystals, CaveEncounter::AncientCarvings, CaveEncounter::EchoingVoices],
+            CaveBiome::SunlessSea => &[CaveEncounter::BlindFerryman, CaveEncounter::DrownedShrine, CaveEncounter::LostExpedition],
+        }
+    }
+
+    /// Pick a creature to spawn and how many of them
+    pub fn spawn(&self, rng: &mut impl Rng) -> (&'static CaveFauna, u32) {
+        let fauna = self.fauna();
+        let total: u32 = fauna.iter().map(|f| f.weight).sum();
+        let mut roll = rng.gen_range(0..total);
+        let pick = fauna
+            .iter()
+            .find(|f| {
+                if roll < f.weight {
+                    return true;
+                }
+                roll -= f.weight;
+                false
+            })
+            .unwrap_or(&fauna[0]);
+        (pick, rng.gen_range(pick.group.0..=pick.group.1))
+    }
+
+    /// Pick a lore encounter for a wanderer
+    pub fn encounter(&self, rng: &mut impl Rng) -> CaveEncounter {
+        let encounters = self.encounters();
+        encounters[rng.gen_range(0..encounters.len())]
+    }
+}
+
+/// A creature on a cave biome's spawn list
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CaveFauna {
+    pub name: &'static str,
+    /// Relative spawn weight within the biome
+    pub weight: u32,
+    /// Smallest and largest group
+    pub group: (u32, u32),
+    /// Monster species whose lairs this creature comes from, if any
+    pub species: Option<MonsterSpecies>,
+}
+
+/// Lore encounters for subterranean wanderers
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum CaveEncounter {
+    /// The camp and last journal of an expedition that never came back
+    LostExpedition,
+    /// Voices from nowhere repeating words spoken long ago
+    EchoingVoices,
+    /// Carvings left by a people who lived down here before
+    AncientCarvings,
+    /// Breathing the spores brings visions of the surface
+    SporeVisions,
+    /// A recluse tending a garden of edible fungus
+    HermitGardener,
+    /// Crystals ringing in a chord that answers footsteps
+    SingingCrystals,
+    /// A blind ferryman who rows travellers across the dark water
+    BlindFerryman,
+    /// A shrine half-sunk in the lake, still lit by someone
+    DrownedShrine,
+}
+
+impl CaveEncounter {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CaveEncounter::LostExpedition => "Lost Expedition",
+            CaveEncounter::EchoingVoices => "Echoing Voices",
+            CaveEncounter::AncientCarvings => "Ancient Carvings",
+            CaveEncounter::SporeVisions => "Spore Visions",
+            CaveEncounter::HermitGardener => "Hermit Gardener",
+            CaveEncounter::SingingCrystals => "Singing Crystals",
+            CaveEncounter::BlindFerryman => "Blind Ferryman",
+            CaveEncounter::DrownedShrine => "Drowned Shrine",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            CaveEncounter::LostExpedition => "A cold camp, rotted packs and a journal whose last pages stop mid-sentence.",
+            CaveEncounter::EchoingVoices => "Voices drift through the passages, repeating words no one here has spoken.",
+            CaveEncounter::AncientCarvings => "Faces and maps cut into the rock by a people who lived below before the cities above.",
+            CaveEncounter::SporeVisions => "The spores hang thick; for a moment the wanderer sees the sky through the rock.",
+            CaveEncounter::HermitGardener => "A recluse tends rows of pale fungus and trades food for news of the surface.",
+            CaveEncounter::SingingCrystals => "The crystals ring in a low chord that answers every footstep.",
+            CaveEncounter::BlindFerryman => "A blind ferryman waits at the shore and asks only for a story as his fare.",
+            CaveEncounter::DrownedShrine => "A shrine half-sunk in the black water, its candles somehow still lit.",
+        }
+    }
+}
+
+/// Thresholds for assigning cave biomes
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct CaveBiomeConfig {
+    /// Fraction of surface moisture lost per cavern layer it seeps through
+    pub seepage_loss_per_layer: f32,
+    /// Seepage (0-1) at which a region floods into a sunless sea
+    pub sea_seepage: f32,
+    /// Smallest region (tiles) that can hold a sea
+    pub min_sea_tiles: usize,
+    /// Z-levels of a sea region under water, counted up from its floor
+    pub sea_depth: i32,
+    /// Temperature (°C) at which crystals grow
+    pub crystal_heat: f32,
+    /// Seepage (0-1) fungus needs
+    pub mushroom_seepage: f32,
+    /// Temperature range (°C) fungus grows in
+    pub mushroom_heat: (f32, f32),
+}
+
+impl Default for CaveBiomeConfig {
+    fn default() -> Self {
+        Self {
+            seepage_loss_per_layer: 0.3,
+            sea_seepage: 0.55,
+            min_sea_tiles: 12,
+            sea_depth: 2,
+            crystal_heat: 65.0,
+            mushroom_seepage: 0.3,
+            mushroom_heat: (5.0, 50.0),
+        }
+    }
+}
+
+/// One connected cave region and what grows there
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct CaveRegion {
+    pub biome: CaveBiome,
+    /// Cavern layer (0 shallow, 1 middle, 2 deep)
+    pub layer: usize,
+    /// Number of z-level tiles in the region
+    pub tiles: usize,
+    /// Lowest z-level the region reaches
+    pub floor_z: i32,
+    /// Mean rock temperature (°C)
+    pub temperature: f32,
+    /// Moisture seepage (0-1)
+    pub seepage: f32,
+}
+
+/// Cave regions of a world and the biome of each
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct CaveBiomeMap {
+    /// Region index per cavern-layer tile (`NO_REGION` outside caves)
+    region_map: Tilemap3D<u16>,
+    pub regions: Vec<CaveRegion>,
+}
+
+impl CaveBiomeMap {
+    /// Region containing a z-level tile
+    pub fn region_at(&self, x: usize, y: usize, z: i32) -> Option<&CaveRegion> {
+        if !self.region_map.is_valid_z(z) {
+            return None;
+        }
+        self.regions.get(*self.region_map.get(x, y, z) as usize)
+    }
+
+    pub fn biome_at(&self, x: usize, y: usize, z: i32) -> Option<CaveBiome> {
+        self.region_at(x, y, z).map(|r| r.biome)
+    }
+
+    /// Biome of the largest region under a world tile within a cavern layer
+    pub fn layer_biome(&self, x: usize, y: usize, layer: usize) -> Option<CaveBiome> {
+        let (z_min, z_max) = layer_range(layer)?;
+        (z_min..=z_max)
+            .filter_map(|z| self.region_at(x, y, z))
+            .max_by_key(|r| r.tiles)
+            .map(|r| r.biome)
+    }
+
+    /// Number of regions with a biome
+    pub fn count(&self, biome: CaveBiome) -> usize {
+        self.regions.iter().filter(|r| r.biome == biome).count()
+    }
+
+    /// Short description for tile info panels
+    pub fn describe(&self, x: usize, y: usize, z: i32) -> Option<String> {
+        let region = self.region_at(x, y, z)?;
+        Some(format!("{} ({:.0}°C, seepage {:.0}%)", region.biome.name(), region.temperature, region.seepage * 100.0))
+    }
+}
+
+/// Cavern layer (0 shallow, 1 middle, 2 deep) of a z-level
+pub fn cavern_layer(z: i32) -> Option<usize> {
+    (0..3).find(|&layer| layer_range(layer).is_some_and(|(lo, hi)| (lo..=hi).contains(&z)))
+}
+
+fn layer_range(layer: usize) -> Option<(i32, i32)> {
+    match layer {
+        0 => Some((CAVERN_1_MIN, CAVERN_1_MAX)),
+        1 => Some((CAVERN_2_MIN, CAVERN_2_MAX)),
+        2 => Some((CAVERN_3_MIN, CAVERN_3_MAX)),
+        _ => None,
+    }
+}
+
+/// Water the region can seep from when it touches it
+fn is_seep_source(tile: ZTile) -> bool {
+    tile.is_underground_water() || tile == ZTile::Spring
+}
+
+/// Floor tiles a biome palette may redress
+fn is_floor_dressing(tile: ZTile) -> bool {
+    matches!(
+        tile,
+        ZTile::CaveFloor | ZTile::FungalGrowth | ZTile::GiantMushroom | ZTile::CaveMoss | ZTile::CrystalFormation
+    )
+}
+
+/// Split the caves into regions, assign each a biome and redress its floor
+pub fn generate_cave_biomes(
+    zlevels: &mut Tilemap3D<ZTile>,
+    geothermal: &GeothermalMap,
+    moisture: &Tilemap<f32>,
+    config: &CaveBiomeConfig,
+    seed: u64,
+) -> CaveBiomeMap {
+    let (width, height) = (zlevels.width, zlevels.height);
+    let mut region_map = Tilemap3D::new_with(width, height, CAVERN_3_MIN, CAVERN_1_MAX, NO_REGION);
+    let mut regions = Vec::new();
+    let mut members: Vec<Vec<(usize, usize, i32)>> = Vec::new();
+
+    for layer in 0..3 {
+        let (z_min, z_max) = layer_range(layer).unwrap_or_default();
+        for z in z_min..=z_max {
+            for y in 0..height {
+                for x in 0..width {
+                    if !zlevels.get(x, y, z).is_cave() || *region_map.get(x, y, z) != NO_REGION {
+                        continue;
+                    }
+                    let Ok(id) = u16::try_from(regions.len()) else {
+                        continue;
+                    };
+                    if id == NO_REGION {
+                        continue;
+                    }
+                    let tiles = flood_region(zlevels, &mut region_map, (x, y, z), (z_min, z_max), id);
+                    regions.push(classify(zlevels, geothermal, moisture, config, layer, &tiles));
+                    members.push(tiles);
+                }
+            }
+        }
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(47_361));
+    for (region, tiles) in regions.iter().zip(&members) {
+        apply_palette(zlevels, region, tiles, config, &mut rng);
+    }
+
+    CaveBiomeMap { region_map, regions }
+}
+
+/// Flood-fill one region of open cave within a layer (6-connected, wrapping east-west)
+fn flood_region(
+    zlevels: &Tilemap3D<ZTile>,
+    region_map: &mut Tilemap3D<u16>,
+    start: (usize, usize, i32),
+    (z_min, z_max): (i32, i32),
+    id: u16,
+) -> Vec<(usize, usize, i32)> {
+    let (width, height) = (zlevels.width, zlevels.height);
+    let mut tiles = Vec::new();
+    let mut queue = VecDeque::from([start]);
+    region_map.set(start.0, start.1, start.2, id);
+
+    while let Some((x, y, z)) = queue.pop_front() {
+        tiles.push((x, y, z));
+        for (dx, dy, dz) in [(-1, 0, 0), (1, 0, 0), (0, -1, 0), (0, 1, 0), (0, 0, -1), (0, 0, 1)] {
+            let (nz, ny) = (z + dz, y as i32 + dy);
+            if nz < z_min || nz > z_max || ny < 0 || ny >= height as i32 {
+                continue;
+            }
+            let (nx, ny) = ((x as i32 + dx).rem_euclid(width as i32) as usize, ny as usize);
+            if zlevels.get(nx, ny, nz).is_cave() && *region_map.get(nx, ny, nz) == NO_REGION {
+                region_map.set(nx, ny, nz, id);
+                queue.push_back((nx, ny, nz));
+            }
+        }
+    }
+    tiles
+}
+
+/// Measure a region's heat and seepage and pick its biome
+fn classify(
+    zlevels: &Tilemap3D<ZTile>,
+    geothermal: &GeothermalMap,
+    moisture: &Tilemap<f32>,
+    config: &CaveBiomeConfig,
+    layer: usize,
+    tiles: &[(usize, usize, i32)],
+) -> CaveRegion {
+    let (width, height) = (zlevels.width, zlevels.height);
+    let mut heat = 0.0;
+    let mut surface_moisture = 0.0;
+    let mut wet_tiles = 0;
+    for &(x, y, z) in tiles {
+        heat += geothermal.temperature_at(x, y, z);
+        surface_moisture += moisture.get(x, y).clamp(0.0, 1.0);
+
+        let touches_water = is_seep_source(*zlevels.get(x, y, z))
+            || [(-1, 0, 0), (1, 0, 0), (0, -1, 0), (0, 1, 0), (0, 0, -1), (0, 0, 1)].iter().any(|&(dx, dy, dz)| {
+                let (nz, ny) = (z + dz, y as i32 + dy);
+                zlevels.is_valid_z(nz)
+                    && (0..height as i32).contains(&ny)
+                    && is_seep_source(*zlevels.get((x as i32 + dx).rem_euclid(width as i32) as usize, ny as usize, nz))
+            });
+        if touches_water {
+            wet_tiles += 1;
+        }
+    }
+    let count = tiles.len().max(1) as f32;
+    let temperature = heat / count;
+    let soaked = surface_moisture / count * (1.0 - config.seepage_loss_per_layer).powi(layer as i32);
+    // A tenth of the region touching water is as wet as it gets
+    let contact = (wet_tiles as f32 / count * 10.0).min(1.0);
+    let seepage = (0.6 * soaked + 0.4 * contact).clamp(0.0, 1.0);
+
+    let biome = if seepage >= config.sea_seepage && tiles.len() >= config.min_sea_tiles {
+        CaveBiome::SunlessSea
+    } else if temperature >= config.crystal_heat {
+        CaveBiome::CrystalGallery
+    } else if seepage >= config.mushroom_seepage
+        && (config.mushroom_heat.0..=config.mushroom_heat.1).contains(&temperature)
+    {
+        CaveBiome::MushroomCavern
+    } else {
+        CaveBiome::Barren
+    };
+
+    CaveRegion {
+        biome,
+        layer,
+        tiles: tiles.len(),
+        floor_z: tiles.iter().map(|t| t.2).min().unwrap_or(0),
+        temperature,
+        seepage,
+    }
+}
+
+/// Redress a region's floor with its biome's tiles; formations, ramps, magma
+/// and waterfalls are left as they are
+fn apply_palette(
+    zlevels: &mut Tilemap3D<ZTile>,
+    region: &CaveRegion,
+    tiles: &[(usize, usize, i32)],
+    config: &CaveBiomeConfig,
+    rng: &mut ChaCha8Rng,
+) {
+    for &(x, y, z) in tiles {
+        if !is_floor_dressing(*zlevels.get(x, y, z)) {
+            continue;
+        }
+        let roll: f64 = rng.gen();
+        let tile = match region.biome {
+            CaveBiome::Barren => ZTile::CaveFloor,
+            CaveBiome::MushroomCavern => match roll {
+                r if r < 0.12 => ZTile::GiantMushroom,
+                r if r < 0.37 => ZTile::FungalGrowth,
+                r if r < 0.62 => ZTile::CaveMoss,
+                _ => ZTile::CaveFloor,
+            },
+            CaveBiome::CrystalGallery if roll < 0.2 => ZTile::CrystalFormation,
+            CaveBiome::CrystalGallery => ZTile::CaveFloor,
+            CaveBiome::SunlessSea if z < region.floor_z + config.sea_depth => ZTile::CaveLake,
+            CaveBiome::SunlessSea if roll < 0.15 => ZTile::CaveMoss,
+            CaveBiome::SunlessSea => ZTile::CaveFloor,
+        };
+        zlevels.set(x, y, z, tile);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geothermal::{generate_geothermal, GeothermalConfig};
+    use crate::zlevel::{MAX_Z, MIN_Z};
+
+    /// Solid rock with two separate pockets of cave in the middle layer
+    fn two_pockets() -> (Tilemap3D<ZTile>, Tilemap<f32>) {
+        let mut zlevels = Tilemap3D::new_with(16, 8, MIN_Z, MAX_Z, ZTile::Solid);
+        for z in CAVERN_2_MIN..=CAVERN_2_MIN + 2 {
+            for y in 2..6 {
+                for x in 1..5 {
+                    zlevels.set(x, y, z, ZTile::CaveFloor);
+                }
+                for x in 9..13 {
+                    zlevels.set(x, y, z, ZTile::CaveFloor);
+                }
+            }
+        }
+        (zlevels, Tilemap::new_with(16, 8, 200.0f32))
+    }
+
+    #[test]
+    fn test_regions_take_biome_from_seepage_and_heat() {
+        let (mut zlevels, heightmap) = two_pockets();
+        // The west pocket sits under an aquifer; the east one under stressed crust
+        for y in 2..6 {
+            for x in 1..5 {
+                zlevels.set(x, y, CAVERN_2_MIN + 3, ZTile::Aquifer);
+            }
+        }
+        let temperature = Tilemap::new_with(16, 8, 15.0f32);
+        let moisture = Tilemap::new_with(16, 8, 0.7f32);
+        let mut stress = Tilemap::new_with(16, 8, 0.0f32);
+        for y in 0..8 {
+            for x in 8..16 {
+                stress.set(x, y, 0.9);
+            }
+        }
+
+        let geothermal = generate_geothermal(&heightmap, &temperature, &stress, &zlevels, &GeothermalConfig::default());
+        let config = CaveBiomeConfig::default();
+        let caves = generate_cave_biomes(&mut zlevels, &geothermal, &moisture, &config, 3);
+        assert_eq!(caves.regions.len(), 2);
+        assert_eq!(caves.biome_at(2, 3, CAVERN_2_MIN + 1), Some(CaveBiome::SunlessSea));
+        assert_eq!(caves.biome_at(10, 3, CAVERN_2_MIN), Some(CaveBiome::CrystalGallery));
+        assert_eq!(caves.biome_at(7, 3, CAVERN_2_MIN), None);
+        assert_eq!(caves.layer_biome(2, 3, 1), Some(CaveBiome::SunlessSea));
+        assert_eq!(caves.layer_biome(2, 3, 0), None);
+
+        // The sea floods its lowest levels and leaves a shore above
+        assert_eq!(*zlevels.get(2, 3, CAVERN_2_MIN), ZTile::CaveLake);
+        assert_eq!(*zlevels.get(2, 3, CAVERN_2_MIN + 1), ZTile::CaveLake);
+        assert!(matches!(*zlevels.get(2, 3, CAVERN_2_MIN + 2), ZTile::CaveFloor | ZTile::CaveMoss));
+        for z in CAVERN_2_MIN..=CAVERN_2_MIN + 2 {
+            for y in 2..6 {
+                for x in 9..13 {
+                    assert!(matches!(*zlevels.get(x, y, z), ZTile::CaveFloor | ZTile::CrystalFormation));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_damp_mild_caves_grow_mushrooms() {
+        let (mut zlevels, heightmap) = two_pockets();
+        let temperature = Tilemap::new_with(16, 8, 10.0f32);
+        let mut moisture = Tilemap::new_with(16, 8, 0.8f32);
+        for y in 0..8 {
+            for x in 8..16 {
+                moisture.set(x, y, 0.1);
+            }
+        }
+        let stress = Tilemap::new_with(16, 8, 0.0f32);
+
+        let geothermal = generate_geothermal(&heightmap, &temperature, &stress, &zlevels, &GeothermalConfig::default());
+        let caves = generate_cave_biomes(&mut zlevels, &geothermal, &moisture, &CaveBiomeConfig::default(), 3);
+        assert_eq!(caves.biome_at(2, 3, CAVERN_2_MIN), Some(CaveBiome::MushroomCavern));
+        assert_eq!(caves.biome_at(10, 3, CAVERN_2_MIN), Some(CaveBiome::Barren));
+        assert_eq!(caves.count(CaveBiome::MushroomCavern), 1);
+        let fungal = (CAVERN_2_MIN..=CAVERN_2_MIN + 2)
+            .flat_map(|z| (2..6).flat_map(move |y| (1..5).map(move |x| (x, y, z))))
+            .filter(|&(x, y, z)| zlevels.get(x, y, z).is_cave_biome())
+            .count();
+        assert!(fungal > 0);
+    }
+
+    #[test]
+    fn test_spawn_lists_and_encounters() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        for biome in CaveBiome::all() {
+            assert!(!biome.fauna().is_empty() && !biome.encounters().is_empty());
+            for _ in 0..20 {
+                let (fauna, count) = biome.spawn(&mut rng);
+                assert!((fauna.group.0..=fauna.group.1).contains(&count));
+                assert!(biome.encounters().contains(&biome.encounter(&mut rng)));
+            }
+        }
+        assert_eq!(cavern_layer(CAVERN_1_MAX), Some(0));
+        assert_eq!(cavern_layer(CAVERN_3_MIN - 1), None);
+    }
+}
@@ -0,0 +1,355 @@
+//! Alien climate chemistry
+//!
+//! Lets non-Earthlike worlds reuse the normal terrain pipeline with a different
+//! working fluid. A `ClimateChemistry` defines the solvent (its freezing and boiling
+//! points), the planet's temperature range, and a `BiomePalette` mapping climate
+//! classes to biomes. Everything is serde-loadable, so new chemistries can be
+//! described in JSON without touching the code.
+//!
+//! Built-in presets:
+//! - `titan`: methane seas and hydrocarbon dunes on a frozen world
+//! - `sulfur`: sulfuric acid lakes, sulfur plains and ash on a scorching world
+//! - `fungal`: an ammonia-cold world dominated by fungal and spore biomes
+
+use std::collections::HashMap;
+
+use crate::biomes::ExtendedBiome;
+use crate::tilemap::Tilemap;
+
+/// Temperature range produced by `climate::generate_temperature` on Earthlike worlds
+const EARTH_POLE_TEMP: f32 = -30.0;
+const EARTH_EQUATOR_TEMP: f32 = 30.0;
+
+/// Working fluid of the hydrosphere
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Solvent {
+    Water,
+    Methane,
+    Ammonia,
+    SulfuricAcid,
+    /// User-defined solvent with explicit phase transitions (°C)
+    Custom { name: String, freezing_point: f32, boiling_point: f32 },
+}
+
+impl Solvent {
+    /// Freezing point at surface pressure (°C)
+    pub fn freezing_point(&self) -> f32 {
+        match self {
+            Solvent::Water => 0.0,
+            Solvent::Methane => -182.5,
+            Solvent::Ammonia => -77.7,
+            Solvent::SulfuricAcid => 10.0,
+            Solvent::Custom { freezing_point, .. } => *freezing_point,
+        }
+    }
+
+    /// Boiling point at surface pressure (°C)
+    pub fn boiling_point(&self) -> f32 {
+        match self {
+            Solvent::Water => 100.0,
+            Solvent::Methane => -161.5,
+            Solvent::Ammonia => -33.3,
+            Solvent::SulfuricAcid => 337.0,
+            Solvent::Custom { boiling_point, .. } => *boiling_point,
+        }
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            Solvent::Water => "water",
+            Solvent::Methane => "methane",
+            Solvent::Ammonia => "ammonia",
+            Solvent::SulfuricAcid => "sulfuric acid",
+            Solvent::Custom { name, .. } => name,
+        }
+    }
+}
+
+/// Climate classes that the palette maps to biomes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum ClimateClass {
+    /// Deep liquid solvent
+    DeepSea,
+    /// Shallow liquid solvent near the coast
+    Shallows,
+    /// Basin below sea level where the solvent is frozen solid
+    FrozenSea,
+    /// Basin below sea level where the solvent has boiled away
+    DryBasin,
+    /// Land colder than the solvent's freezing point
+    FrozenLand,
+    /// Land hotter than the solvent's boiling point
+    ScorchedLand,
+    /// Liquid-range land with little solvent moisture
+    Arid,
+    /// Liquid-range land with moderate moisture
+    Temperate,
+    /// Liquid-range land with abundant moisture
+    Wet,
+    /// High elevation land
+    Highland,
+}
+
+/// Maps climate classes to biomes (missing entries fall back to `default_biome`)
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BiomePalette {
+    pub biomes: HashMap<ClimateClass, ExtendedBiome>,
+    pub default_biome: ExtendedBiome,
+}
+
+impl BiomePalette {
+    fn from_pairs(pairs: &[(ClimateClass, ExtendedBiome)], default_biome: ExtendedBiome) -> Self {
+        Self {
+            biomes: pairs.iter().copied().collect(),
+            default_biome,
+        }
+    }
+
+    pub fn get(&self, class: ClimateClass) -> ExtendedBiome {
+        self.biomes.get(&class).copied().unwrap_or(self.default_biome)
+    }
+}
+
+/// Full description of an exotic world's climate chemistry
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ClimateChemistry {
+    pub name: String,
+    pub solvent: Solvent,
+    /// Mean surface temperature at the equator (°C)
+    pub equator_temp: f32,
+    /// Mean surface temperature at the poles (°C)
+    pub pole_temp: f32,
+    /// Elevation (meters) above which land counts as highland
+    pub highland_elevation: f32,
+    /// Water depth (meters) separating shallows from deep sea
+    pub shallow_depth: f32,
+    pub palette: BiomePalette,
+}
+
+impl ClimateChemistry {
+    /// Methane world like Titan: hydrocarbon seas, ice bedrock, tar dunes
+    pub fn titan() -> Self {
+        use ClimateClass::*;
+        use ExtendedBiome as B;
+        Self {
+            name: "titan".to_string(),
+            solvent: Solvent::Methane,
+            equator_temp: -170.0,
+            pole_temp: -185.0,
+            highland_elevation: 1500.0,
+            shallow_depth: 200.0,
+            palette: BiomePalette::from_pairs(
+                &[
+                    (DeepSea, B::InkSea),
+                    (Shallows, B::TarPits),
+                    (FrozenSea, B::FrozenLake),
+                    (DryBasin, B::SaltFlats),
+                    (FrozenLand, B::Ice),
+                    (ScorchedLand, B::Desert),
+                    (Arid, B::SingingDunes),
+                    (Temperate, B::Tundra),
+                    (Wet, B::Marsh),
+                    (Highland, B::CrystalWasteland),
+                ],
+                B::Tundra,
+            ),
+        }
+    }
+
+    /// Hot acid world: sulfuric acid lakes, sulfur plains, ash and basalt
+    pub fn sulfur() -> Self {
+        use ClimateClass::*;
+        use ExtendedBiome as B;
+        Self {
+            name: "sulfur".to_string(),
+            solvent: Solvent::SulfuricAcid,
+            equator_temp: 320.0,
+            pole_temp: 120.0,
+            highland_elevation: 1500.0,
+            shallow_depth: 200.0,
+            palette: BiomePalette::from_pairs(
+                &[
+                    (DeepSea, B::AcidLake),
+                    (Shallows, B::BrinePools),
+                    (FrozenSea, B::SaltFlats),
+                    (DryBasin, B::GlassDesert),
+                    (FrozenLand, B::BasaltColumns),
+                    (ScorchedLand, B::VolcanicWasteland),
+                    (Arid, B::Ashlands),
+                    (Temperate, B::SulfurVents),
+                    (Wet, B::FumaroleField),
+                    (Highland, B::ObsidianFields),
+                ],
+                B::Ashlands,
+            ),
+        }
+    }
+
+    /// Cold ammonia world overgrown with fungal life
+    pub fn fungal() -> Self {
+        use ClimateClass::*;
+        use ExtendedBiome as B;
+        Self {
+            name: "fungal".to_string(),
+            solvent: Solvent::Ammonia,
+            equator_temp: -30.0,
+            pole_temp: -95.0,
+            highland_elevation: 1500.0,
+            shallow_depth: 200.0,
+            palette: BiomePalette::from_pairs(
+                &[
+                    (DeepSea, B::PhosphorShallows),
+                    (Shallows, B::BioluminescentWater),
+                    (FrozenSea, B::FrozenLake),
+                    (DryBasin, B::SaltFlats),
+                    (FrozenLand, B::AuroraWastes),
+                    (ScorchedLand, B::SporeWastes),
+                    (Arid, B::SporeWastes),
+                    (Temperate, B::MushroomForest),
+                    (Wet, B::FungalBloom),
+                    (Highland, B::SiliconGrove),
+                ],
+                B::MushroomForest,
+            ),
+        }
+    }
+
+    /// Look up a built-in preset by name
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "titan" | "methane" => Some(Self::titan()),
+            "sulfur" | "venus" => Some(Self::sulfur()),
+            "fungal" | "ammonia" => Some(Self::fungal()),
+            _ => None,
+        }
+    }
+
+    /// Parse a chemistry from JSON
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Load a preset by name, or a JSON chemistry file if `spec` is not a preset name
+    pub fn load(spec: &str) -> std::io::Result<Self> {
+        if let Some(chemistry) = Self::preset(spec) {
+            return Ok(chemistry);
+        }
+        let json = std::fs::read_to_string(spec)?;
+        Self::from_json(&json).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Remap an Earthlike temperature map onto this world's temperature range,
+    /// preserving the latitude, elevation and ocean patterns
+    pub fn remap_temperature(&self, temperature: &Tilemap<f32>) -> Tilemap<f32> {
+        let scale = (self.equator_temp - self.pole_temp) / (EARTH_EQUATOR_TEMP - EARTH_POLE_TEMP);
+        let mut remapped = temperature.clone();
+        for (_, _, t) in remapped.iter_mut() {
+            *t = self.pole_temp + (*t - EARTH_POLE_TEMP) * scale;
+        }
+        remapped
+    }
+
+    /// Classify a tile given its elevation, (remapped) temperature and moisture
+    pub fn classify(&self, elevation: f32, temperature: f32, moisture: f32) -> ClimateClass {
+        let freezing = self.solvent.freezing_point();
+        let boiling = self.solvent.boiling_point();
+
+        if elevation < 0.0 {
+            return if temperature < freezing {
+                ClimateClass::FrozenSea
+            } else if temperature > boiling {
+                ClimateClass::DryBasin
+            } else if elevation > -self.shallow_depth {
+                ClimateClass::Shallows
+            } else {
+                ClimateClass::DeepSea
+            };
+        }
+
+        if temperature < freezing {
+            ClimateClass::FrozenLand
+        } else if temperature > boiling {
+            ClimateClass::ScorchedLand
+        } else if elevation > self.highland_elevation {
+            ClimateClass::Highland
+        } else if moisture < 0.3 {
+            ClimateClass::Arid
+        } else if moisture < 0.6 {
+            ClimateClass::Temperate
+        } else {
+            ClimateClass::Wet
+        }
+    }
+}
+
+/// Generate a biome map using an exotic chemistry's palette.
+/// `temperature` should already be remapped with `ClimateChemistry::remap_temperature`.
+pub fn generate_exotic_biomes(
+    heightmap: &Tilemap<f32>,
+    temperature: &Tilemap<f32>,
+    moisture: &Tilemap<f32>,
+    chemistry: &ClimateChemistry,
+) -> Tilemap<ExtendedBiome> {
+    let mut biomes = Tilemap::new_with(heightmap.width, heightmap.height, chemistry.palette.default_biome);
+    for (x, y, biome) in biomes.iter_mut() {
+        let class = chemistry.classify(*heightmap.get(x, y), *temperature.get(x, y), *moisture.get(x, y));
+        *biome = chemistry.palette.get(class);
+    }
+    biomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_have_consistent_phase_points() {
+        for name in ["titan", "sulfur", "fungal"] {
+            let chem = ClimateChemistry::preset(name).unwrap();
+            assert!(chem.solvent.freezing_point() < chem.solvent.boiling_point());
+            assert!(chem.pole_temp < chem.equator_temp);
+        }
+        assert!(ClimateChemistry::preset("unobtainium").is_none());
+    }
+
+    #[test]
+    fn test_titan_classification() {
+        let titan = ClimateChemistry::titan();
+        // Liquid methane between -182.5 and -161.5
+        assert_eq!(titan.classify(-1000.0, -175.0, 0.5), ClimateClass::DeepSea);
+        assert_eq!(titan.classify(-50.0, -175.0, 0.5), ClimateClass::Shallows);
+        assert_eq!(titan.classify(-1000.0, -190.0, 0.5), ClimateClass::FrozenSea);
+        assert_eq!(titan.classify(200.0, -175.0, 0.1), ClimateClass::Arid);
+        assert_eq!(titan.classify(200.0, -175.0, 0.9), ClimateClass::Wet);
+        assert_eq!(titan.palette.get(ClimateClass::DeepSea), ExtendedBiome::InkSea);
+    }
+
+    #[test]
+    fn test_remap_temperature_range() {
+        let mut temp = Tilemap::new_with(2, 1, 0.0f32);
+        temp.set(0, 0, EARTH_POLE_TEMP);
+        temp.set(1, 0, EARTH_EQUATOR_TEMP);
+        let sulfur = ClimateChemistry::sulfur();
+        let remapped = sulfur.remap_temperature(&temp);
+        assert!((remapped.get(0, 0) - sulfur.pole_temp).abs() < 1e-3);
+        assert!((remapped.get(1, 0) - sulfur.equator_temp).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let chem = ClimateChemistry::fungal();
+        let json = serde_json::to_string(&chem).unwrap();
+        assert_eq!(ClimateChemistry::from_json(&json).unwrap(), chem);
+
+        let custom = r#"{
+            "name": "brine", "equator_temp": 10.0, "pole_temp": -60.0,
+            "highland_elevation": 2000.0, "shallow_depth": 100.0,
+            "solvent": {"Custom": {"name": "brine", "freezing_point": -21.0, "boiling_point": 108.0}},
+            "palette": {"biomes": {"DeepSea": "BrinePools"}, "default_biome": "SaltFlats"}
+        }"#;
+        let chem = ClimateChemistry::from_json(custom).unwrap();
+        assert_eq!(chem.solvent.freezing_point(), -21.0);
+        assert_eq!(chem.palette.get(ClimateClass::DeepSea), ExtendedBiome::BrinePools);
+        assert_eq!(chem.palette.get(ClimateClass::Wet), ExtendedBiome::SaltFlats);
+    }
+}
@@ -69,7 +69,7 @@ pub fn generate_temperature(
 /// Calculate prevailing wind direction based on latitude
 /// Returns unit vector (dx, dy) pointing in wind direction (where wind is blowing TO)
 /// latitude_normalized: 0 = equator, 1 = pole
-fn get_prevailing_wind(latitude_normalized: f32) -> (f32, f32) {
+pub fn get_prevailing_wind(latitude_normalized: f32) -> (f32, f32) {
     if latitude_normalized < 0.15 {
         // Equatorial doldrums - weak/variable winds, slight easterly
         (0.3, 0.0)
@@ -252,8 +252,7 @@ pub fn generate_moisture_scaled(
                     let sample_dist = t * rain_shadow_range;
                     let sx = (x as f32 + upwind_dir.0 * sample_dist) as i32;
                     let sy = (y as f32 + upwind_dir.1 * sample_dist) as i32;
-                    let sx = sx.rem_euclid(width as i32) as usize;
-                    let sy = sy.clamp(0, height as i32 - 1) as usize;
+                    let (sx, sy) = heightmap.wrap_coords(sx, sy);
 
                     let blocking_elev = *heightmap.get(sx, sy);
 
@@ -280,8 +279,7 @@ pub fn generate_moisture_scaled(
                         let sample_dist = t * rain_shadow_range * 0.7;
                         let sx = (x as f32 + offset_dir.0 * sample_dist) as i32;
                         let sy = (y as f32 + offset_dir.1 * sample_dist) as i32;
-                        let sx = sx.rem_euclid(width as i32) as usize;
-                        let sy = sy.clamp(0, height as i32 - 1) as usize;
+                        let (sx, sy) = heightmap.wrap_coords(sx, sy);
 
                         let blocking_elev = *heightmap.get(sx, sy);
                         if blocking_elev > elevation + 400.0 {
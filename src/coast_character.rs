@@ -0,0 +1,533 @@
+//! Coastline Character (Phase 3b)
+//!
+//! Gives coasts regional character derived from how the land was shaped,
+//! rather than from noise alone:
+//! - Fjords: long, deep, narrow inlets where glaciers carved valleys down to the sea
+//! - Rias: funnel-shaped, branching drowned river valleys on hilly unglaciated coasts
+//! - Barrier islands: thin sand ridges off low, gently shelving coasts, with lagoons behind
+//!
+//! A single `complexity` parameter scales how much of each coast is reworked.
+
+use noise::{NoiseFn, Perlin, Seedable};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+
+use crate::biomes::ExtendedBiome;
+use crate::erosion::rivers::{compute_flow_accumulation, compute_flow_direction, DX, DY, NO_FLOW};
+use crate::tilemap::Tilemap;
+
+// =============================================================================
+// CONFIGURATION
+// =============================================================================
+
+/// Glacial erosion depth (m) that counts as fully glaciated
+const FULL_GLACIAL_EROSION: f32 = 20.0;
+
+/// Tiles a glacier's influence reaches beyond the bedrock it eroded
+const GLACIAL_REACH: i32 = 3;
+
+/// Temperature (°C) below which ice-age land is treated as glaciated
+const GLACIAL_TEMP: f32 = -2.0;
+
+/// Minimum flow accumulation for a valley to become a fjord or ria
+const FJORD_MIN_FLOW: f32 = 6.0;
+const RIA_MIN_FLOW: f32 = 25.0;
+
+/// Relief (m) within a few tiles of the mouth that makes a coast hilly enough for rias
+const RIA_MIN_RELIEF: f32 = 100.0;
+
+/// Minimum spacing (tiles) between inlet mouths
+const INLET_SPACING: i32 = 5;
+
+/// Shoreline land below this elevation (m) counts as a low coastal plain...
+const LOW_COAST: f32 = 60.0;
+
+/// ...as long as nothing next to it rises above this (m)
+const LOW_BACKSHORE: f32 = 150.0;
+
+/// Sea floor shallower than this (m) counts as a shelf barriers can build on
+const SHELF_DEPTH: f32 = -200.0;
+
+/// Glaciation (0-1) above which a valley mouth becomes a fjord rather than a ria
+const FJORD_GLACIATION: f32 = 0.5;
+
+/// Parameters for coastline character
+#[derive(Clone, Debug)]
+pub struct CoastCharacterParams {
+    /// Overall amount of coastal reworking (0 = off, 1 = every eligible coast)
+    pub complexity: f32,
+    /// Cooling (°C) of the last ice age, used when no glacial erosion record exists
+    pub ice_age_cooling: f32,
+    /// Fjord floor depth (m, negative)
+    pub fjord_depth: f32,
+    /// Ria floor depth at the mouth (m, negative)
+    pub ria_depth: f32,
+    /// Longest fjord or ria, in tiles
+    pub max_inlet_length: usize,
+    /// Barrier island crest height (m)
+    pub barrier_height: f32,
+    /// Barrier distance offshore, in tiles
+    pub barrier_offset: usize,
+    /// Fraction of a barrier chain broken by tidal inlets
+    pub inlet_fraction: f32,
+    /// Lagoon floor depth (m, negative)
+    pub lagoon_depth: f32,
+}
+
+impl Default for CoastCharacterParams {
+    fn default() -> Self {
+        Self {
+            complexity: 0.5,
+            ice_age_cooling: 8.0,
+            fjord_depth: -250.0,
+            ria_depth: -30.0,
+            max_inlet_length: 24,
+            barrier_height: 3.0,
+            barrier_offset: 2,
+            inlet_fraction: 0.15,
+            lagoon_depth: -4.0,
+        }
+    }
+}
+
+/// What shaped a coastal tile
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoastType {
+    None,
+    Fjord,
+    Ria,
+    BarrierIsland,
+    Lagoon,
+}
+
+/// Coastal tiles reworked by `apply_coast_character`
+#[derive(Clone)]
+pub struct CoastCharacter {
+    pub coast_type: Tilemap<CoastType>,
+    pub fjords: usize,
+    pub rias: usize,
+}
+
+impl CoastCharacter {
+    pub fn count(&self, kind: CoastType) -> usize {
+        self.coast_type.iter().filter(|(_, _, &t)| t == kind).count()
+    }
+}
+
+// =============================================================================
+// GLACIAL HISTORY
+// =============================================================================
+
+/// How strongly each tile was glaciated (0-1).
+///
+/// Uses the glacial erosion record from the erosion simulation when available
+/// (spread a few tiles, since glaciers shape the valleys below where they cut
+/// deepest); otherwise reconstructs the last ice age by cooling today's climate.
+pub fn glaciation_history(
+    heightmap: &Tilemap<f32>,
+    temperature: &Tilemap<f32>,
+    glacial_erosion: Option<&Tilemap<f32>>,
+    params: &CoastCharacterParams,
+) -> Tilemap<f32> {
+    let width = heightmap.width;
+    let height = heightmap.height;
+    let mut glaciation = Tilemap::new_with(width, height, 0.0f32);
+
+    match glacial_erosion {
+        Some(eroded) => {
+            for (x, y, &e) in eroded.iter() {
+                let g = (e / FULL_GLACIAL_EROSION).min(1.0);
+                if g <= 0.0 {
+                    continue;
+                }
+                for dy in -GLACIAL_REACH..=GLACIAL_REACH {
+                    for dx in -GLACIAL_REACH..=GLACIAL_REACH {
+                        let (nx, ny) = glaciation.wrap_coords(x as i32 + dx, y as i32 + dy);
+                        if *glaciation.get(nx, ny) < g {
+                            glaciation.set(nx, ny, g);
+                        }
+                    }
+                }
+            }
+        }
+        None => {
+            for (x, y, &t) in temperature.iter() {
+                let ice_age = t - params.ice_age_cooling;
+                glaciation.set(x, y, ((GLACIAL_TEMP - ice_age) / 6.0).clamp(0.0, 1.0));
+            }
+        }
+    }
+
+    glaciation
+}
+
+// =============================================================================
+// COASTLINE CHARACTER
+// =============================================================================
+
+fn neighbor(heightmap: &Tilemap<f32>, x: usize, y: usize, dir: usize) -> Option<(usize, usize)> {
+    let ny = y as i32 + DY[dir];
+    if ny < 0 || ny >= heightmap.height as i32 {
+        return None;
+    }
+    let nx = (x as i32 + DX[dir]).rem_euclid(heightmap.width as i32) as usize;
+    Some((nx, ny as usize))
+}
+
+/// Upstream neighbours of a tile (those whose flow drains into it), largest flow first
+fn upstream(flow_dir: &Tilemap<u8>, flow_acc: &Tilemap<f32>, heightmap: &Tilemap<f32>, x: usize, y: usize) -> Vec<(usize, usize)> {
+    let mut up: Vec<(usize, usize)> = (0..8)
+        .filter_map(|dir| neighbor(heightmap, x, y, dir))
+        .filter(|&(nx, ny)| {
+            let d = *flow_dir.get(nx, ny);
+            d != NO_FLOW && neighbor(heightmap, nx, ny, d as usize) == Some((x, y))
+        })
+        .collect();
+    up.sort_by(|a, b| flow_acc.get(b.0, b.1).partial_cmp(flow_acc.get(a.0, a.1)).unwrap_or(std::cmp::Ordering::Equal));
+    up
+}
+
+/// Relief (max - min elevation) within `radius` tiles
+fn local_relief(heightmap: &Tilemap<f32>, x: usize, y: usize, radius: i32) -> f32 {
+    let (mut lo, mut hi) = (f32::MAX, f32::MIN);
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            let (nx, ny) = heightmap.wrap_coords(x as i32 + dx, y as i32 + dy);
+            let h = *heightmap.get(nx, ny);
+            lo = lo.min(h);
+            hi = hi.max(h);
+        }
+    }
+    hi - lo
+}
+
+/// Inject fjords, rias and barrier islands into the heightmap
+pub fn apply_coast_character(
+    heightmap: &mut Tilemap<f32>,
+    temperature: &Tilemap<f32>,
+    glaciation: &Tilemap<f32>,
+    params: &CoastCharacterParams,
+    seed: u64,
+) -> CoastCharacter {
+    let width = heightmap.width;
+    let height = heightmap.height;
+    let mut coast_type = Tilemap::new_with(width, height, CoastType::None);
+    let complexity = params.complexity.clamp(0.0, 1.0);
+    if complexity <= 0.0 {
+        return CoastCharacter { coast_type, fjords: 0, rias: 0 };
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0xC0A5));
+    let flow_dir = compute_flow_direction(heightmap);
+    let flow_acc = compute_flow_accumulation(heightmap, &flow_dir);
+
+    // Valley mouths: land tiles draining straight into the sea, biggest valleys first
+    let mut mouths: Vec<(usize, usize)> = Vec::new();
+    for (x, y, &h) in heightmap.iter() {
+        let dir = *flow_dir.get(x, y);
+        if h <= 0.0 || dir == NO_FLOW {
+            continue;
+        }
+        if let Some((nx, ny)) = neighbor(heightmap, x, y, dir as usize) {
+            if *heightmap.get(nx, ny) <= 0.0 && *flow_acc.get(x, y) >= FJORD_MIN_FLOW {
+                mouths.push((x, y));
+            }
+        }
+    }
+    mouths.sort_by(|a, b| {
+        flow_acc.get(b.0, b.1).partial_cmp(flow_acc.get(a.0, a.1)).unwrap_or(std::cmp::Ordering::Equal).then(a.cmp(b))
+    });
+
+    let mut fjords = 0;
+    let mut rias = 0;
+    let mut taken: Vec<(usize, usize)> = Vec::new();
+    for (mx, my) in mouths {
+        let spaced = taken.iter().all(|&(tx, ty)| {
+            let dx = (tx as i32 - mx as i32).abs();
+            let dx = dx.min(width as i32 - dx);
+            dx.max((ty as i32 - my as i32).abs()) >= INLET_SPACING
+        });
+        if !spaced {
+            continue;
+        }
+        let glacial = *glaciation.get(mx, my);
+        let flow = *flow_acc.get(mx, my);
+
+        let kind = if glacial >= FJORD_GLACIATION {
+            CoastType::Fjord
+        } else if flow >= RIA_MIN_FLOW && local_relief(heightmap, mx, my, 3) >= RIA_MIN_RELIEF {
+            CoastType::Ria
+        } else {
+            continue;
+        };
+        if rng.gen::<f32>() >= complexity {
+            continue;
+        }
+
+        let carved = match kind {
+            CoastType::Fjord => {
+                let length = (params.max_inlet_length as f32 * (0.4 + 0.6 * complexity) * glacial).round() as usize;
+                carve_fjord(heightmap, &mut coast_type, &flow_dir, &flow_acc, (mx, my), length, params)
+            }
+            _ => {
+                let length = (params.max_inlet_length as f32 * 0.6 * complexity).round() as usize;
+                carve_ria(heightmap, &mut coast_type, &flow_dir, &flow_acc, (mx, my), length, params.ria_depth, 1)
+            }
+        };
+        if carved > 1 {
+            taken.push((mx, my));
+            if kind == CoastType::Fjord {
+                fjords += 1;
+            } else {
+                rias += 1;
+            }
+        }
+    }
+
+    build_barriers(heightmap, &mut coast_type, temperature, glaciation, params, complexity, seed);
+
+    CoastCharacter { coast_type, fjords, rias }
+}
+
+/// Carve a narrow glacial trough up the main valley: a shallow sill at the
+/// mouth, the deepest water partway in, shoaling toward the head
+fn carve_fjord(
+    heightmap: &mut Tilemap<f32>,
+    coast_type: &mut Tilemap<CoastType>,
+    flow_dir: &Tilemap<u8>,
+    flow_acc: &Tilemap<f32>,
+    mouth: (usize, usize),
+    length: usize,
+    params: &CoastCharacterParams,
+) -> usize {
+    let (mut x, mut y) = mouth;
+    let mut carved = 0;
+    for step in 0..length {
+        let t = step as f32 / length.max(1) as f32;
+        let profile = if t < 0.1 { 0.4 } else { 1.0 - 0.6 * t };
+        let floor = params.fjord_depth * profile;
+        if *heightmap.get(x, y) > floor {
+            heightmap.set(x, y, floor);
+        }
+        coast_type.set(x, y, CoastType::Fjord);
+        carved += 1;
+
+        match upstream(flow_dir, flow_acc, heightmap, x, y).first() {
+            Some(&(nx, ny)) if *coast_type.get(nx, ny) == CoastType::None => (x, y) = (nx, ny),
+            _ => break,
+        }
+    }
+    carved
+}
+
+/// Drown a river valley and its larger tributaries: wide and deepest at the
+/// mouth, narrowing and shoaling upstream
+#[allow(clippy::too_many_arguments)]
+fn carve_ria(
+    heightmap: &mut Tilemap<f32>,
+    coast_type: &mut Tilemap<CoastType>,
+    flow_dir: &Tilemap<u8>,
+    flow_acc: &Tilemap<f32>,
+    mouth: (usize, usize),
+    length: usize,
+    depth: f32,
+    level: u32,
+) -> usize {
+    let (mut x, mut y) = mouth;
+    let mut carved = 0;
+    for step in 0..length {
+        let t = step as f32 / length.max(1) as f32;
+        let floor = (depth * (1.0 - t)).min(-1.0);
+        let radius = if level == 1 { (2.0 * (1.0 - t)).round() as i32 } else { 0 };
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let (nx, ny) = heightmap.wrap_coords(x as i32 + dx, y as i32 + dy);
+                let edge_floor = if dx == 0 && dy == 0 { floor } else { floor * 0.5 };
+                if *heightmap.get(nx, ny) > edge_floor {
+                    heightmap.set(nx, ny, edge_floor.min(-0.5));
+                    coast_type.set(nx, ny, CoastType::Ria);
+                }
+            }
+        }
+        coast_type.set(x, y, CoastType::Ria);
+        carved += 1;
+
+        let up = upstream(flow_dir, flow_acc, heightmap, x, y);
+        let main_flow = up.first().map_or(0.0, |&(ux, uy)| *flow_acc.get(ux, uy));
+        // Large tributaries are drowned too, giving the ria its branching outline
+        if level < 2 {
+            for &(bx, by) in up.iter().skip(1) {
+                if *flow_acc.get(bx, by) >= main_flow * 0.3 && *coast_type.get(bx, by) != CoastType::Fjord {
+                    carved += carve_ria(heightmap, coast_type, flow_dir, flow_acc, (bx, by), (length - step) / 3, floor, level + 1);
+                }
+            }
+        }
+        // Flow paths never loop, so the only tiles to stop at are other inlets' fjords
+        match up.first() {
+            Some(&(nx, ny)) if *coast_type.get(nx, ny) != CoastType::Fjord => (x, y) = (nx, ny),
+            _ => break,
+        }
+    }
+    carved
+}
+
+/// Raise barrier islands a few tiles off low, gently shelving, unglaciated
+/// coasts and turn the water behind them into shallow lagoons
+fn build_barriers(
+    heightmap: &mut Tilemap<f32>,
+    coast_type: &mut Tilemap<CoastType>,
+    temperature: &Tilemap<f32>,
+    glaciation: &Tilemap<f32>,
+    params: &CoastCharacterParams,
+    complexity: f32,
+    seed: u64,
+) {
+    let width = heightmap.width;
+    let height = heightmap.height;
+    let offset = params.barrier_offset.max(1);
+    let regional = Perlin::new(1).set_seed(seed.wrapping_add(0xBA55) as u32);
+
+    // Distance offshore and the coastal land tile each sea tile is nearest to
+    let mut distance = Tilemap::new_with(width, height, usize::MAX);
+    let mut source = Tilemap::new_with(width, height, (0usize, 0usize));
+    let mut queue = VecDeque::new();
+    for (x, y, &h) in heightmap.iter() {
+        if h > 0.0 {
+            distance.set(x, y, 0);
+            source.set(x, y, (x, y));
+            queue.push_back((x, y));
+        }
+    }
+    while let Some((x, y)) = queue.pop_front() {
+        let d = *distance.get(x, y);
+        if d >= offset {
+            continue;
+        }
+        for (nx, ny) in heightmap.neighbors_8(x, y) {
+            if *distance.get(nx, ny) == usize::MAX {
+                distance.set(nx, ny, d + 1);
+                source.set(nx, ny, *source.get(x, y));
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    let eligible = |hm: &Tilemap<f32>, (sx, sy): (usize, usize)| {
+        let h = *hm.get(sx, sy);
+        let gentle = hm.neighbors_8(sx, sy).iter().all(|&(nx, ny)| *hm.get(nx, ny) < LOW_BACKSHORE);
+        // Barrier coasts come in long regional stretches, more of them at higher complexity
+        let stretch = regional.get([sx as f64 * 0.04, sy as f64 * 0.04]) as f32;
+        h < LOW_COAST
+            && gentle
+            && *temperature.get(sx, sy) > 5.0
+            && *glaciation.get(sx, sy) < FJORD_GLACIATION
+            && *coast_type.get(sx, sy) == CoastType::None
+            && stretch < complexity * 1.2 - 0.6
+    };
+
+    let mut ridge = Vec::new();
+    let mut lagoon = Vec::new();
+    for (x, y, &d) in distance.iter() {
+        if d == 0 || d == usize::MAX || *heightmap.get(x, y) < SHELF_DEPTH {
+            continue;
+        }
+        if !eligible(heightmap, *source.get(x, y)) {
+            continue;
+        }
+        if d == offset {
+            // Tidal inlets break the chain
+            let inlet = regional.get([x as f64 * 0.35, y as f64 * 0.35, 7.5]) as f32;
+            if inlet < 1.0 - 2.0 * params.inlet_fraction {
+                ridge.push((x, y));
+            }
+        } else {
+            lagoon.push((x, y));
+        }
+    }
+
+    for (x, y) in ridge {
+        heightmap.set(x, y, params.barrier_height);
+        coast_type.set(x, y, CoastType::BarrierIsland);
+    }
+    for (x, y) in lagoon {
+        let h = *heightmap.get(x, y);
+        heightmap.set(x, y, h.max(params.lagoon_depth).min(-0.5));
+        coast_type.set(x, y, CoastType::Lagoon);
+    }
+}
+
+/// Turn open water behind barrier islands into lagoon biome. Returns the number of tiles changed.
+pub fn apply_coast_biomes(biomes: &mut Tilemap<ExtendedBiome>, coast: &CoastCharacter) -> usize {
+    let mut changed = 0;
+    for (x, y, &kind) in coast.coast_type.iter() {
+        if kind == CoastType::Lagoon
+            && matches!(*biomes.get(x, y), ExtendedBiome::Ocean | ExtendedBiome::CoastalWater | ExtendedBiome::DeepOcean)
+        {
+            biomes.set(x, y, ExtendedBiome::Lagoon);
+            changed += 1;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Land in the west sloping down to a sea in the east, with a valley along row 16
+    fn valley_coast(peak: f32) -> Tilemap<f32> {
+        let (w, h) = (64, 32);
+        let mut heightmap = Tilemap::new_with(w, h, -40.0f32);
+        for y in 0..h {
+            for x in 0..40 {
+                let slope = peak * (40 - x) as f32 / 40.0;
+                let valley = (y as f32 - 16.0).abs() * peak / 60.0;
+                heightmap.set(x, y, slope + valley + 1.0);
+            }
+        }
+        heightmap
+    }
+
+    #[test]
+    fn test_glaciated_valley_becomes_fjord() {
+        let mut heightmap = valley_coast(2000.0);
+        let cold = Tilemap::new_with(64, 32, -10.0f32);
+        let params = CoastCharacterParams { complexity: 1.0, ..Default::default() };
+        let glaciation = glaciation_history(&heightmap, &cold, None, &params);
+        assert_eq!(*glaciation.get(5, 5), 1.0);
+
+        let coast = apply_coast_character(&mut heightmap, &cold, &glaciation, &params, 1);
+        assert!(coast.fjords > 0);
+        assert!(coast.count(CoastType::Fjord) > 5);
+        assert_eq!(coast.count(CoastType::BarrierIsland), 0);
+        // The fjord floor runs well below sea level where land used to be
+        let (fx, fy, _) = coast.coast_type.iter().find(|&(x, _, &t)| t == CoastType::Fjord && x < 35).unwrap();
+        assert!(*heightmap.get(fx, fy) < -50.0);
+
+        // Complexity zero leaves the coast alone
+        let mut untouched = valley_coast(2000.0);
+        let off = CoastCharacterParams { complexity: 0.0, ..Default::default() };
+        let coast = apply_coast_character(&mut untouched, &cold, &glaciation, &off, 1);
+        assert_eq!(coast.fjords + coast.rias, 0);
+        assert_eq!(*untouched.get(39, 16), *valley_coast(2000.0).get(39, 16));
+    }
+
+    #[test]
+    fn test_low_warm_coast_gets_barrier_and_lagoon() {
+        let mut heightmap = valley_coast(30.0);
+        let warm = Tilemap::new_with(64, 32, 22.0f32);
+        let params = CoastCharacterParams { complexity: 1.0, ..Default::default() };
+        let glaciation = glaciation_history(&heightmap, &warm, None, &params);
+        let coast = apply_coast_character(&mut heightmap, &warm, &glaciation, &params, 3);
+
+        assert!(coast.count(CoastType::BarrierIsland) > 0);
+        assert!(coast.count(CoastType::Lagoon) > 0);
+        assert_eq!(coast.fjords, 0);
+        let (bx, by, _) = coast.coast_type.iter().find(|&(_, _, &t)| t == CoastType::BarrierIsland).unwrap();
+        assert_eq!(*heightmap.get(bx, by), params.barrier_height);
+
+        let mut biomes = Tilemap::new_with(64, 32, ExtendedBiome::Ocean);
+        assert_eq!(apply_coast_biomes(&mut biomes, &coast), coast.count(CoastType::Lagoon));
+    }
+}
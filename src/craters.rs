@@ -0,0 +1,456 @@
+//! Impact cratering for moons and dead planets
+//!
+//! Generates crater populations from a power-law size-frequency distribution and
+//! stamps them onto a heightmap, oldest first so younger impacts overprint older ones:
+//! - Simple craters: parabolic bowls with raised rims
+//! - Complex craters: flat floors with central peaks above a size threshold
+//! - Ejecta blankets thinning with distance from the rim
+//! - Maria: the lowest basins flooded flat by ancient lava
+//!
+//! Also provides an airless "no hydrosphere" world mode that skips rivers, climate
+//! and biome generation entirely and produces regolith material layers instead.
+
+use noise::{NoiseFn, Perlin, Seedable};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::ExtendedBiome;
+use crate::plates::{self, PlateId};
+use crate::scale::MapScale;
+use crate::tilemap::Tilemap;
+use crate::water_bodies::WaterBodyId;
+use crate::world::WorldData;
+use crate::zlevel;
+
+/// Parameters for crater population and shape
+#[derive(Clone, Debug)]
+pub struct CraterParams {
+    /// Number of craters per 10,000 tiles
+    pub density: f32,
+    /// Smallest crater radius (tiles)
+    pub min_radius: f32,
+    /// Largest crater radius (tiles)
+    pub max_radius: f32,
+    /// Cumulative size-frequency exponent: N(>r) ∝ r^-exponent (~2 for the Moon)
+    pub size_exponent: f32,
+    /// Depth-to-diameter ratio of simple craters
+    pub depth_ratio: f32,
+    /// Rim height as a fraction of depth
+    pub rim_ratio: f32,
+    /// Radius above which craters become complex (flat floor + central peak)
+    pub complex_radius: f32,
+    /// Ejecta blanket extent as a multiple of crater radius
+    pub ejecta_extent: f32,
+    /// Fraction of the surface (lowest first) flooded as maria; 0 disables
+    pub maria_fraction: f32,
+}
+
+impl Default for CraterParams {
+    fn default() -> Self {
+        Self {
+            density: 40.0,
+            min_radius: 1.5,
+            max_radius: 40.0,
+            size_exponent: 2.0,
+            depth_ratio: 0.2,
+            rim_ratio: 0.35,
+            complex_radius: 8.0,
+            ejecta_extent: 2.5,
+            maria_fraction: 0.12,
+        }
+    }
+}
+
+/// A single impact crater
+#[derive(Clone, Debug)]
+pub struct Crater {
+    pub x: usize,
+    pub y: usize,
+    /// Rim radius in tiles
+    pub radius: f32,
+    /// Floor depth below the pre-impact surface (meters)
+    pub depth: f32,
+    /// Complex craters have a flat floor and central peak
+    pub is_complex: bool,
+    /// Relative age (0 = oldest, 1 = youngest)
+    pub age: f32,
+}
+
+/// Surface material of an airless body
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SurfaceMaterial {
+    /// Old, heavily gardened highland regolith
+    #[default]
+    HighlandRegolith,
+    /// Fresh ejecta blanket around young craters
+    Ejecta,
+    /// Crater floor breccia
+    CraterFloor,
+    /// Flat dark lava plains filling old basins
+    MareBasalt,
+}
+
+impl SurfaceMaterial {
+    /// Biome used to display this material with the standard renderers
+    pub fn display_biome(&self) -> ExtendedBiome {
+        match self {
+            SurfaceMaterial::HighlandRegolith => ExtendedBiome::Ashlands,
+            SurfaceMaterial::Ejecta => ExtendedBiome::GlassDesert,
+            SurfaceMaterial::CraterFloor => ExtendedBiome::StarfallCrater,
+            SurfaceMaterial::MareBasalt => ExtendedBiome::LavaField,
+        }
+    }
+}
+
+/// Sample a crater population.
+/// Radii follow a truncated power law so small craters vastly outnumber large ones.
+pub fn generate_craters(width: usize, height: usize, params: &CraterParams, rng: &mut ChaCha8Rng) -> Vec<Crater> {
+    let count = ((width * height) as f32 / 10_000.0 * params.density).round() as usize;
+    let r_min = params.min_radius.max(0.5);
+    let r_max = params.max_radius.max(r_min);
+    let a = params.size_exponent.max(0.1);
+
+    let mut craters: Vec<Crater> = (0..count)
+        .map(|i| {
+            // Inverse-CDF sampling of a truncated Pareto distribution
+            let u: f32 = rng.gen();
+            let lo = r_min.powf(-a);
+            let hi = r_max.powf(-a);
+            let radius = (lo - u * (lo - hi)).powf(-1.0 / a);
+
+            Crater {
+                x: rng.gen_range(0..width),
+                y: rng.gen_range(0..height),
+                radius,
+                depth: 0.0,
+                is_complex: radius >= params.complex_radius,
+                age: i as f32 / count.max(1) as f32,
+            }
+        })
+        .collect();
+
+    for crater in &mut craters {
+        crater.depth = crater_depth(crater.radius, params);
+    }
+
+    craters
+}
+
+/// Floor depth (meters) of a fresh crater of the given rim radius
+pub fn crater_depth(radius: f32, params: &CraterParams) -> f32 {
+    // Depth scales with diameter; complex craters are relatively shallower
+    let diameter_m = radius * 2.0 * 1000.0;
+    let ratio = if radius >= params.complex_radius { params.depth_ratio * 0.5 } else { params.depth_ratio };
+    (diameter_m * ratio * 0.05).min(4000.0)
+}
+
+/// Elevation change at normalized distance `t` (distance / radius) from a crater center
+fn crater_profile(crater: &Crater, t: f32, params: &CraterParams) -> f32 {
+    let depth = crater.depth;
+    let rim = depth * params.rim_ratio;
+
+    if t < 1.0 {
+        if crater.is_complex {
+            // Flat floor out to 60% of the radius, then a terraced wall up to the rim
+            let floor = -depth;
+            let bowl = if t < 0.6 {
+                floor
+            } else {
+                let s = (t - 0.6) / 0.4;
+                floor + (depth + rim) * s * s
+            };
+            // Central peak rising from the floor
+            let peak = if t < 0.2 {
+                depth * 0.6 * (1.0 - t / 0.2).powi(2)
+            } else {
+                0.0
+            };
+            bowl + peak
+        } else {
+            // Parabolic bowl rising to the rim
+            -depth + (depth + rim) * t * t
+        }
+    } else if t < params.ejecta_extent {
+        // Ejecta thickness falls off roughly with the cube of distance
+        rim * t.powi(-3)
+    } else {
+        0.0
+    }
+}
+
+/// Stamp craters onto a heightmap (in the given order) and record ejecta coverage.
+/// Returns a map of the youngest crater index affecting each tile and whether the
+/// tile lies inside (true) or on the ejecta blanket (false) of that crater.
+pub fn apply_craters(
+    heightmap: &mut Tilemap<f32>,
+    craters: &[Crater],
+    params: &CraterParams,
+) -> Tilemap<Option<(usize, bool)>> {
+    let width = heightmap.width as i32;
+    let height = heightmap.height as i32;
+    let mut coverage: Tilemap<Option<(usize, bool)>> = Tilemap::new_with(heightmap.width, heightmap.height, None);
+
+    for (idx, crater) in craters.iter().enumerate() {
+        let reach = crater.radius * params.ejecta_extent;
+        let r = reach.ceil() as i32;
+
+        for dy in -r..=r {
+            let y = crater.y as i32 + dy;
+            if y < 0 || y >= height {
+                continue;
+            }
+            for dx in -r..=r {
+                let dist = ((dx * dx + dy * dy) as f32).sqrt();
+                if dist > reach {
+                    continue;
+                }
+                let x = (crater.x as i32 + dx).rem_euclid(width) as usize;
+                let y = y as usize;
+                let t = dist / crater.radius;
+                let delta = crater_profile(crater, t, params);
+
+                if t < 1.0 {
+                    // Excavation resets the surface toward the crater shape, erasing
+                    // older relief inside the rim instead of simply adding to it
+                    let h = *heightmap.get(x, y);
+                    let blend = (1.0 - t).clamp(0.0, 1.0).sqrt();
+                    heightmap.set(x, y, h + delta * (0.5 + 0.5 * blend));
+                    coverage.set(x, y, Some((idx, true)));
+                } else {
+                    *heightmap.get_mut(x, y) += delta;
+                    if delta > crater.depth * params.rim_ratio * 0.05 {
+                        coverage.set(x, y, Some((idx, false)));
+                    }
+                }
+            }
+        }
+    }
+
+    coverage
+}
+
+/// Flood the lowest `fraction` of the surface to a common level (lunar maria).
+/// Returns the mask of flooded tiles.
+pub fn flood_maria(heightmap: &mut Tilemap<f32>, fraction: f32) -> Tilemap<bool> {
+    let mut mask = Tilemap::new_with(heightmap.width, heightmap.height, false);
+    if fraction <= 0.0 {
+        return mask;
+    }
+
+    let mut heights: Vec<f32> = heightmap.iter().map(|(_, _, &h)| h).collect();
+    heights.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let idx = ((heights.len() as f32 * fraction.min(1.0)) as usize).min(heights.len() - 1);
+    let level = heights[idx];
+
+    for (x, y, h) in heightmap.iter_mut() {
+        if *h < level {
+            *h = level;
+            mask.set(x, y, true);
+        }
+    }
+
+    mask
+}
+
+/// Generated airless body
+pub struct AirlessWorld {
+    pub heightmap: Tilemap<f32>,
+    pub craters: Vec<Crater>,
+    /// Dominant surface material per tile
+    pub surface_material: Tilemap<SurfaceMaterial>,
+    /// Regolith thickness per tile (meters)
+    pub regolith_depth: Tilemap<f32>,
+    /// Tiles flooded by mare basalt
+    pub maria_mask: Tilemap<bool>,
+}
+
+/// Generate a cratered airless body: rolling highland terrain, an impact history,
+/// maria flooding, and regolith layers. No rivers, climate or biomes are simulated.
+pub fn generate_airless_terrain(width: usize, height: usize, seed: u64, params: &CraterParams) -> AirlessWorld {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    // Primordial crust: low-amplitude rolling highlands
+    let noise = Perlin::new(1).set_seed(seed as u32);
+    let mut heightmap = Tilemap::new_with(width, height, 0.0f32);
+    for (x, y, h) in heightmap.iter_mut() {
+        let nx = x as f64 / width as f64 * 6.0;
+        let ny = y as f64 / height as f64 * 3.0;
+        let v = noise.get([nx, ny]) * 0.6 + noise.get([nx * 3.0, ny * 3.0]) * 0.3 + noise.get([nx * 9.0, ny * 9.0]) * 0.1;
+        *h = 1500.0 + v as f32 * 1200.0;
+    }
+
+    let craters = generate_craters(width, height, params, &mut rng);
+
+    // The largest (oldest) basins form first and are flooded before later impacts,
+    // so young craters punch through the maria as on the Moon
+    let split = craters.len() / 3;
+    let (early, late) = craters.split_at(split);
+    let early_coverage = apply_craters(&mut heightmap, early, params);
+    let maria_mask = flood_maria(&mut heightmap, params.maria_fraction);
+    let late_coverage = apply_craters(&mut heightmap, late, params);
+
+    let mut surface_material = Tilemap::new_with(width, height, SurfaceMaterial::HighlandRegolith);
+    let mut regolith_depth = Tilemap::new_with(width, height, 0.0f32);
+
+    for y in 0..height {
+        for x in 0..width {
+            // Youngest impact wins; late craters are indexed after the early split
+            let coverage = late_coverage
+                .get(x, y)
+                .map(|(i, inside)| (i + split, inside))
+                .or(*early_coverage.get(x, y));
+
+            let material = match coverage {
+                Some((i, true)) if craters[i].age > 0.7 => SurfaceMaterial::CraterFloor,
+                Some((i, false)) if craters[i].age > 0.7 => SurfaceMaterial::Ejecta,
+                _ if *maria_mask.get(x, y) => SurfaceMaterial::MareBasalt,
+                Some((_, true)) => SurfaceMaterial::CraterFloor,
+                _ => SurfaceMaterial::HighlandRegolith,
+            };
+            surface_material.set(x, y, material);
+
+            // Regolith accumulates with exposure age: old highlands are thickest,
+            // maria thinner, fresh impact surfaces barely gardened
+            let depth = match material {
+                SurfaceMaterial::HighlandRegolith => 10.0,
+                SurfaceMaterial::MareBasalt => 4.0,
+                SurfaceMaterial::CraterFloor => 2.0,
+                SurfaceMaterial::Ejecta => 6.0,
+            };
+            let age_factor = coverage.map(|(i, _)| 1.0 - craters[i].age * 0.8).unwrap_or(1.0);
+            regolith_depth.set(x, y, depth * age_factor);
+        }
+    }
+
+    // Dead worlds have no sea level; shift the datum so every tile is dry land
+    let min_h = heightmap.iter().map(|(_, _, &h)| h).fold(f32::MAX, f32::min);
+    if min_h < 0.0 {
+        for (_, _, h) in heightmap.iter_mut() {
+            *h -= min_h;
+        }
+    }
+
+    AirlessWorld {
+        heightmap,
+        craters,
+        surface_material,
+        regolith_depth,
+        maria_mask,
+    }
+}
+
+/// Surface temperature of an airless body: extreme equator-to-pole contrast and no
+/// elevation lapse rate (there is no atmosphere to cool with altitude)
+fn airless_temperature(width: usize, height: usize) -> Tilemap<f32> {
+    let mut temperature = Tilemap::new_with(width, height, 0.0f32);
+    for (_, y, t) in temperature.iter_mut() {
+        let lat = ((y as f32 / (height - 1).max(1) as f32) - 0.5).abs() * 2.0;
+        *t = 100.0 - 280.0 * lat.powf(0.7);
+    }
+    temperature
+}
+
+/// Generate a complete "no hydrosphere" world (moon or dead planet).
+///
+/// Skips plates, erosion, rivers, climate, biomes, water bodies, structures and
+/// history. Biomes are filled from the surface material so the standard renderers
+/// and explorer still work.
+pub fn generate_airless_world(width: usize, height: usize, seed: u64, params: &CraterParams) -> (WorldData, AirlessWorld) {
+    let airless = generate_airless_terrain(width, height, seed, params);
+
+    let heightmap = airless.heightmap.clone();
+    let temperature = airless_temperature(width, height);
+    let moisture = Tilemap::new_with(width, height, 0.0f32);
+    let mut biomes = Tilemap::new_with(width, height, ExtendedBiome::Ashlands);
+    for (x, y, m) in airless.surface_material.iter() {
+        biomes.set(x, y, m.display_biome());
+    }
+
+    // A single stagnant-lid plate: dead worlds have no active tectonics
+    let stress_map = Tilemap::new_with(width, height, 0.0f32);
+    let plate_map = Tilemap::new_with(width, height, PlateId(0));
+    let plates = vec![plates::Plate {
+        id: PlateId(0),
+        plate_type: plates::PlateType::Continental,
+        velocity: plates::Vec2::new(0.0, 0.0),
+        base_elevation: 0.0,
+        color: [140, 140, 140],
+    }];
+
+    let water_body_map = Tilemap::new_with(width, height, WaterBodyId::NONE);
+    let (zlevels, surface_z) = zlevel::generate_zlevels(&heightmap);
+
+    let world = WorldData::new(
+        seed,
+        MapScale::default(),
+        heightmap,
+        temperature,
+        moisture,
+        biomes,
+        stress_map,
+        plate_map,
+        plates,
+        None,
+        water_body_map,
+        Vec::new(),
+        zlevels,
+        surface_z,
+        None,
+        None,
+        None,
+    );
+
+    (world, airless)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_frequency_favors_small_craters() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let params = CraterParams::default();
+        let craters = generate_craters(200, 100, &params, &mut rng);
+        assert_eq!(craters.len(), 80);
+
+        let small = craters.iter().filter(|c| c.radius < 3.0).count();
+        let large = craters.iter().filter(|c| c.radius >= 10.0).count();
+        assert!(small > large * 3, "small={} large={}", small, large);
+        assert!(craters.iter().all(|c| c.radius >= params.min_radius && c.radius <= params.max_radius + 1e-3));
+    }
+
+    #[test]
+    fn test_complex_crater_has_central_peak() {
+        let params = CraterParams::default();
+        let crater = Crater { x: 0, y: 0, radius: 20.0, depth: 1000.0, is_complex: true, age: 0.5 };
+        let center = crater_profile(&crater, 0.0, &params);
+        let floor = crater_profile(&crater, 0.4, &params);
+        let rim = crater_profile(&crater, 0.999, &params);
+        assert!(center > floor, "central peak should rise above the floor");
+        assert!(rim > 0.0, "rim should be raised");
+        assert!(crater_profile(&crater, params.ejecta_extent + 0.1, &params) == 0.0);
+    }
+
+    #[test]
+    fn test_flood_maria_flattens_lowest_tiles() {
+        let mut map = Tilemap::new_with(10, 10, 0.0f32);
+        for (x, y, h) in map.iter_mut() {
+            *h = (x + y * 10) as f32;
+        }
+        let mask = flood_maria(&mut map, 0.2);
+        let flooded = mask.iter().filter(|(_, _, &m)| m).count();
+        assert_eq!(flooded, 20);
+        assert_eq!(*map.get(0, 0), 20.0);
+    }
+
+    #[test]
+    fn test_airless_world_is_dry() {
+        let (world, airless) = generate_airless_world(64, 32, 5, &CraterParams::default());
+        assert!(world.heightmap.iter().all(|(_, _, &h)| h >= 0.0));
+        assert!(world.water_bodies.is_empty());
+        assert!(world.moisture.iter().all(|(_, _, &m)| m == 0.0));
+        assert!(!airless.craters.is_empty());
+        assert!(airless.regolith_depth.iter().all(|(_, _, &d)| d > 0.0));
+    }
+}
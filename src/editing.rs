@@ -0,0 +1,613 @@
+//! Heightmap editing API
+//!
+//! A non-destructive editing layer for interactive world editors built on the crate:
+//! - Brushes: raise, lower, smooth and flatten with configurable radius and falloff
+//! - Stamps: mountains, craters and volcanoes placed at a point
+//! - River carving along a tile path
+//! - Undo/redo history storing per-cell diffs rather than full heightmap copies
+//! - Dirty-region tracking so dependent layers (climate, biomes, water bodies) can be
+//!   refreshed only where the terrain changed
+
+use std::collections::{HashMap, HashSet};
+
+use crate::biomes;
+use crate::climate;
+use crate::lakes;
+use crate::tilemap::Tilemap;
+use crate::water_bodies::{self, WaterBody, WaterBodyId};
+use crate::waves::{self, WaveConfig};
+use crate::world::WorldData;
+
+/// How brush strength decays from the center to the edge
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Falloff {
+    /// Full strength across the whole radius
+    Constant,
+    /// Linear decay to zero at the edge
+    Linear,
+    /// Smoothstep decay (soft edges)
+    Smooth,
+}
+
+impl Falloff {
+    /// Weight for a normalized distance (0 = center, 1 = edge)
+    pub fn weight(&self, t: f32) -> f32 {
+        if t >= 1.0 {
+            return 0.0;
+        }
+        match self {
+            Falloff::Constant => 1.0,
+            Falloff::Linear => 1.0 - t,
+            Falloff::Smooth => {
+                let s = 1.0 - t;
+                s * s * (3.0 - 2.0 * s)
+            }
+        }
+    }
+}
+
+/// A circular editing brush
+#[derive(Clone, Copy, Debug)]
+pub struct Brush {
+    /// Radius in tiles
+    pub radius: f32,
+    /// Strength: meters per application for raise/lower, 0-1 blend for smooth/flatten
+    pub strength: f32,
+    pub falloff: Falloff,
+}
+
+impl Brush {
+    pub fn new(radius: f32, strength: f32) -> Self {
+        Self { radius, strength, falloff: Falloff::Smooth }
+    }
+
+    pub fn with_falloff(mut self, falloff: Falloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+}
+
+/// Landform stamps placed at a single point
+#[derive(Clone, Copy, Debug)]
+pub enum Stamp {
+    /// Cone-shaped mountain with a rounded summit
+    Mountain { radius: f32, height: f32 },
+    /// Bowl-shaped crater with a raised rim
+    Crater { radius: f32, depth: f32, rim_height: f32 },
+    /// Volcanic cone with a summit caldera
+    Volcano { radius: f32, height: f32, caldera_radius: f32 },
+}
+
+/// Axis-aligned tile region touched by edits (inclusive bounds)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EditRegion {
+    pub min_x: usize,
+    pub min_y: usize,
+    pub max_x: usize,
+    pub max_y: usize,
+}
+
+impl EditRegion {
+    fn point(x: usize, y: usize) -> Self {
+        Self { min_x: x, min_y: y, max_x: x, max_y: y }
+    }
+
+    fn include(&mut self, x: usize, y: usize) {
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+
+    /// Merge another region into this one
+    pub fn union(&mut self, other: &EditRegion) {
+        self.include(other.min_x, other.min_y);
+        self.include(other.max_x, other.max_y);
+    }
+
+    /// Grow the region by `margin` tiles, clamped to the map
+    pub fn expanded(&self, margin: usize, width: usize, height: usize) -> Self {
+        Self {
+            min_x: self.min_x.saturating_sub(margin),
+            min_y: self.min_y.saturating_sub(margin),
+            max_x: (self.max_x + margin).min(width - 1),
+            max_y: (self.max_y + margin).min(height - 1),
+        }
+    }
+
+    /// Check whether a tile lies inside the region
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+}
+
+/// One undoable edit: the old and new value of every changed cell
+#[derive(Clone, Debug)]
+struct EditRecord {
+    changes: Vec<(usize, usize, f32, f32)>,
+    region: EditRegion,
+}
+
+/// Heightmap editor with undo/redo history
+pub struct HeightmapEditor {
+    heightmap: Tilemap<f32>,
+    undo_stack: Vec<EditRecord>,
+    redo_stack: Vec<EditRecord>,
+    dirty: Option<EditRegion>,
+    /// Maximum number of edits kept in the undo history
+    pub history_limit: usize,
+}
+
+impl HeightmapEditor {
+    /// Start editing a heightmap
+    pub fn new(heightmap: Tilemap<f32>) -> Self {
+        Self {
+            heightmap,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            dirty: None,
+            history_limit: 256,
+        }
+    }
+
+    /// Current heightmap
+    pub fn heightmap(&self) -> &Tilemap<f32> {
+        &self.heightmap
+    }
+
+    /// Finish editing and return the heightmap
+    pub fn into_heightmap(self) -> Tilemap<f32> {
+        self.heightmap
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Region changed since the last call (edits, undos and redos), resetting the tracker
+    pub fn take_dirty_region(&mut self) -> Option<EditRegion> {
+        self.dirty.take()
+    }
+
+    /// Raise terrain under the brush
+    pub fn raise(&mut self, cx: usize, cy: usize, brush: &Brush) {
+        let strength = brush.strength;
+        self.apply_brush(cx, cy, brush, |_, h, w| h + strength * w);
+    }
+
+    /// Lower terrain under the brush
+    pub fn lower(&mut self, cx: usize, cy: usize, brush: &Brush) {
+        let strength = brush.strength;
+        self.apply_brush(cx, cy, brush, |_, h, w| h - strength * w);
+    }
+
+    /// Blend terrain under the brush toward its 3x3 neighborhood average
+    pub fn smooth(&mut self, cx: usize, cy: usize, brush: &Brush) {
+        let source = self.heightmap.clone();
+        let blend = brush.strength.clamp(0.0, 1.0);
+        self.apply_brush(cx, cy, brush, |(x, y), h, w| {
+            let avg = source.neighbor_average(x, y);
+            h + (avg - h) * blend * w
+        });
+    }
+
+    /// Blend terrain under the brush toward a target elevation
+    pub fn flatten(&mut self, cx: usize, cy: usize, brush: &Brush, target: f32) {
+        let blend = brush.strength.clamp(0.0, 1.0);
+        self.apply_brush(cx, cy, brush, |_, h, w| h + (target - h) * blend * w);
+    }
+
+    /// Place a landform stamp centered at (cx, cy)
+    pub fn stamp(&mut self, cx: usize, cy: usize, stamp: &Stamp) {
+        let radius = match *stamp {
+            Stamp::Mountain { radius, .. } => radius,
+            // The crater rim and ejecta extend slightly beyond the bowl
+            Stamp::Crater { radius, .. } => radius * 1.5,
+            Stamp::Volcano { radius, .. } => radius,
+        };
+        let stamp = *stamp;
+
+        let mut changes = Vec::new();
+        self.for_each_in_radius(cx, cy, radius, |x, y, dist| {
+            let delta = match stamp {
+                Stamp::Mountain { radius, height } => {
+                    let t = dist / radius;
+                    height * Falloff::Smooth.weight(t)
+                }
+                Stamp::Crater { radius, depth, rim_height } => {
+                    let t = dist / radius;
+                    if t < 1.0 {
+                        // Parabolic bowl rising to the rim
+                        -depth * (1.0 - t * t) + rim_height * t * t
+                    } else {
+                        // Rim decaying outward
+                        let s = ((t - 1.0) / 0.5).min(1.0);
+                        rim_height * (1.0 - s) * (1.0 - s)
+                    }
+                }
+                Stamp::Volcano { radius, height, caldera_radius } => {
+                    let t = dist / radius;
+                    let cone = height * (1.0 - t).max(0.0).powf(1.5);
+                    if dist < caldera_radius {
+                        // Caldera floor sits below the cone lip
+                        let lip = height * (1.0 - caldera_radius / radius).max(0.0).powf(1.5);
+                        lip - height * 0.15 * (1.0 - dist / caldera_radius)
+                    } else {
+                        cone
+                    }
+                }
+            };
+            if delta != 0.0 {
+                changes.push((x, y, delta));
+            }
+        });
+
+        let edits: Vec<(usize, usize, f32)> = changes
+            .into_iter()
+            .map(|(x, y, d)| (x, y, *self.heightmap.get(x, y) + d))
+            .collect();
+        self.commit(edits);
+    }
+
+    /// Carve a river channel along a path of tiles.
+    /// The channel bed descends monotonically along the path so water can flow.
+    pub fn carve_river(&mut self, path: &[(usize, usize)], depth: f32, width: f32) {
+        if path.is_empty() {
+            return;
+        }
+
+        // Target bed elevation per path point, never rising downstream
+        let mut beds = Vec::with_capacity(path.len());
+        let mut bed = f32::MAX;
+        for &(x, y) in path {
+            let target = *self.heightmap.get(x, y) - depth;
+            bed = bed.min(target);
+            beds.push(bed);
+        }
+
+        let mut targets: HashMap<(usize, usize), f32> = HashMap::new();
+        for (i, &(px, py)) in path.iter().enumerate() {
+            let bed = beds[i];
+            let half_width = width.max(1.0);
+            self.for_each_in_radius(px, py, half_width, |x, y, dist| {
+                // V-shaped cross section: full depth at the thalweg, none at the banks
+                let w = Falloff::Linear.weight(dist / half_width);
+                let h = *self.heightmap.get(x, y);
+                let carved = h + (bed - h).min(0.0) * w;
+                let entry = targets.entry((x, y)).or_insert(h);
+                *entry = entry.min(carved);
+            });
+        }
+
+        let edits: Vec<(usize, usize, f32)> = targets.into_iter().map(|((x, y), h)| (x, y, h)).collect();
+        self.commit(edits);
+    }
+
+    /// Undo the most recent edit. Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(record) = self.undo_stack.pop() else {
+            return false;
+        };
+        for &(x, y, old, _) in &record.changes {
+            self.heightmap.set(x, y, old);
+        }
+        self.mark_dirty(&record.region);
+        self.redo_stack.push(record);
+        true
+    }
+
+    /// Redo the most recently undone edit. Returns false if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(record) = self.redo_stack.pop() else {
+            return false;
+        };
+        for &(x, y, _, new) in &record.changes {
+            self.heightmap.set(x, y, new);
+        }
+        self.mark_dirty(&record.region);
+        self.undo_stack.push(record);
+        true
+    }
+
+    fn apply_brush<F>(&mut self, cx: usize, cy: usize, brush: &Brush, f: F)
+    where
+        F: Fn((usize, usize), f32, f32) -> f32,
+    {
+        let mut edits = Vec::new();
+        self.for_each_in_radius(cx, cy, brush.radius, |x, y, dist| {
+            let w = brush.falloff.weight(dist / brush.radius.max(0.5));
+            if w > 0.0 {
+                let h = *self.heightmap.get(x, y);
+                edits.push((x, y, f((x, y), h, w)));
+            }
+        });
+        self.commit(edits);
+    }
+
+    /// Visit tiles within `radius` of (cx, cy), wrapping horizontally
+    fn for_each_in_radius<F>(&self, cx: usize, cy: usize, radius: f32, mut f: F)
+    where
+        F: FnMut(usize, usize, f32),
+    {
+        let width = self.heightmap.width as i32;
+        let height = self.heightmap.height as i32;
+        let r = radius.ceil() as i32;
+
+        for dy in -r..=r {
+            let y = cy as i32 + dy;
+            if y < 0 || y >= height {
+                continue;
+            }
+            for dx in -r..=r {
+                let dist = ((dx * dx + dy * dy) as f32).sqrt();
+                if dist > radius {
+                    continue;
+                }
+                let x = (cx as i32 + dx).rem_euclid(width);
+                f(x as usize, y as usize, dist);
+            }
+        }
+    }
+
+    /// Apply a batch of (x, y, new_value) edits as a single undoable step
+    fn commit(&mut self, edits: Vec<(usize, usize, f32)>) {
+        let mut changes = Vec::with_capacity(edits.len());
+        let mut region: Option<EditRegion> = None;
+
+        for (x, y, new) in edits {
+            let old = *self.heightmap.get(x, y);
+            if old == new {
+                continue;
+            }
+            self.heightmap.set(x, y, new);
+            changes.push((x, y, old, new));
+            match region.as_mut() {
+                Some(r) => r.include(x, y),
+                None => region = Some(EditRegion::point(x, y)),
+            }
+        }
+
+        let Some(region) = region else {
+            return;
+        };
+        self.mark_dirty(&region);
+        self.redo_stack.clear();
+        self.undo_stack.push(EditRecord { changes, region });
+        if self.undo_stack.len() > self.history_limit {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    fn mark_dirty(&mut self, region: &EditRegion) {
+        match self.dirty.as_mut() {
+            Some(d) => d.union(region),
+            None => self.dirty = Some(*region),
+        }
+    }
+}
+
+/// Margin (tiles) around an edit whose climate is refreshed, covering rain-shadow
+/// and ocean-distance effects that spread beyond the edited cells
+const REFRESH_MARGIN: usize = 8;
+
+/// Re-run the layers that depend on elevation after `world.heightmap` was edited.
+///
+/// Temperature, moisture and biomes are recomputed and copied back only inside the
+/// (expanded) edited region so the rest of the world, including hand-placed rare and
+/// unique biomes, is left untouched. Water bodies are re-detected globally because a
+/// local edit can merge or split lakes and oceans; each keeps the ID and name of the
+/// body it overlaps most, so the gazetteer and place names still point at it.
+pub fn refresh_dependent_layers(world: &mut WorldData, region: &EditRegion) {
+    let width = world.width;
+    let height = world.height;
+    let area = region.expanded(REFRESH_MARGIN, width, height);
+
+    let temperature = climate::generate_temperature(&world.heightmap, width, height);
+    let moisture = climate::generate_moisture(&world.heightmap, width, height);
+    let biome_config = biomes::WorldBiomeConfig::default();
+    let new_biomes = biomes::generate_extended_biomes(
+        &world.heightmap,
+        &temperature,
+        &moisture,
+        &world.stress_map,
+        &biome_config,
+        world.seed,
+    );
+
+    for y in area.min_y..=area.max_y {
+        for x in area.min_x..=area.max_x {
+            world.temperature.set(x, y, *temperature.get(x, y));
+            world.moisture.set(x, y, *moisture.get(x, y));
+            world.biomes.set(x, y, *new_biomes.get(x, y));
+        }
+    }
+
+    let (mut water_body_map, mut water_bodies) = water_bodies::detect_water_bodies(&world.heightmap);
+    keep_water_body_ids(&world.water_body_map, &world.water_bodies, &mut water_body_map, &mut water_bodies);
+    world.lakes = Some(lakes::build_lake_graph(
+        &world.heightmap,
+        &water_body_map,
+        &water_bodies,
+        &world.moisture,
+        &world.temperature,
+        world.scale.km_per_tile,
+    ));
+    world.water_body_map = water_body_map;
+    world.water_bodies = water_bodies;
+    if world.waves.is_some() {
+        // An edit can move the coast: measure the waves on it again
+        world.waves = Some(waves::measure_waves(&world.heightmap, world.scale.km_per_tile, &WaveConfig::default()));
+    }
+}
+
+/// Renumber freshly detected water bodies after the ones they replace: each old
+/// body hands its ID and name to the new body sharing the most tiles with it,
+/// largest overlaps first. The ocean stays the ocean, and bodies the edit created
+/// get IDs no old body used.
+fn keep_water_body_ids(
+    old_map: &Tilemap<WaterBodyId>,
+    old_bodies: &[WaterBody],
+    new_map: &mut Tilemap<WaterBodyId>,
+    new_bodies: &mut [WaterBody],
+) {
+    let mut overlaps: HashMap<(WaterBodyId, WaterBodyId), usize> = HashMap::new();
+    for (x, y, &new_id) in new_map.iter() {
+        let old_id = *old_map.get(x, y);
+        if !new_id.is_none() && !old_id.is_none() {
+            *overlaps.entry((new_id, old_id)).or_default() += 1;
+        }
+    }
+    let mut pairs: Vec<_> = overlaps.into_iter().collect();
+    pairs.sort_by_key(|&((new_id, old_id), count)| (std::cmp::Reverse(count), new_id.0, old_id.0));
+
+    let mut renumbered: HashMap<WaterBodyId, WaterBodyId> = HashMap::new();
+    let mut taken = HashSet::new();
+    for ((new_id, old_id), _) in pairs {
+        if (new_id == WaterBodyId::OCEAN) != (old_id == WaterBodyId::OCEAN) {
+            continue;
+        }
+        if !renumbered.contains_key(&new_id) && taken.insert(old_id) {
+            renumbered.insert(new_id, old_id);
+        }
+    }
+    let mut next_id = old_bodies.iter().map(|b| b.id.0).chain(new_bodies.iter().map(|b| b.id.0)).max().unwrap_or(1);
+    for body in new_bodies.iter() {
+        renumbered.entry(body.id).or_insert_with(|| {
+            next_id += 1;
+            WaterBodyId(next_id)
+        });
+    }
+
+    let names: HashMap<WaterBodyId, &Option<String>> = old_bodies.iter().map(|b| (b.id, &b.name)).collect();
+    for body in new_bodies.iter_mut() {
+        body.id = renumbered[&body.id];
+        body.name = names.get(&body.id).and_then(|name| (*name).clone());
+    }
+    for (_, _, id) in new_map.iter_mut() {
+        if let Some(&kept) = renumbered.get(id) {
+            *id = kept;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat(size: usize, h: f32) -> Tilemap<f32> {
+        Tilemap::new_with(size, size, h)
+    }
+
+    #[test]
+    fn test_raise_and_undo_redo() {
+        let mut editor = HeightmapEditor::new(flat(16, 100.0));
+        editor.raise(8, 8, &Brush::new(3.0, 50.0));
+        assert!((*editor.heightmap().get(8, 8) - 150.0).abs() < 1e-3);
+        assert_eq!(*editor.heightmap().get(0, 0), 100.0);
+
+        assert!(editor.undo());
+        assert_eq!(*editor.heightmap().get(8, 8), 100.0);
+        assert!(!editor.undo());
+
+        assert!(editor.redo());
+        assert!((*editor.heightmap().get(8, 8) - 150.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_new_edit_clears_redo() {
+        let mut editor = HeightmapEditor::new(flat(16, 0.0));
+        editor.raise(4, 4, &Brush::new(2.0, 10.0));
+        editor.undo();
+        assert!(editor.can_redo());
+        editor.lower(4, 4, &Brush::new(2.0, 10.0));
+        assert!(!editor.can_redo());
+    }
+
+    #[test]
+    fn test_flatten_moves_toward_target() {
+        let mut editor = HeightmapEditor::new(flat(16, 500.0));
+        let brush = Brush::new(4.0, 1.0).with_falloff(Falloff::Constant);
+        editor.flatten(8, 8, &brush, 200.0);
+        assert_eq!(*editor.heightmap().get(8, 8), 200.0);
+        assert_eq!(*editor.heightmap().get(10, 8), 200.0);
+        assert_eq!(*editor.heightmap().get(15, 15), 500.0);
+    }
+
+    #[test]
+    fn test_crater_stamp_has_bowl_and_rim() {
+        let mut editor = HeightmapEditor::new(flat(32, 0.0));
+        editor.stamp(16, 16, &Stamp::Crater { radius: 6.0, depth: 300.0, rim_height: 80.0 });
+        assert!(*editor.heightmap().get(16, 16) < -250.0);
+        assert!(*editor.heightmap().get(22, 16) > 50.0);
+    }
+
+    #[test]
+    fn test_carve_river_descends_monotonically() {
+        let mut map = flat(32, 0.0);
+        for y in 0..32 {
+            for x in 0..32 {
+                map.set(x, y, 500.0 - x as f32 * 5.0);
+            }
+        }
+        // Add a bump the river must cut through
+        map.set(10, 16, 600.0);
+
+        let mut editor = HeightmapEditor::new(map);
+        let path: Vec<(usize, usize)> = (2..20).map(|x| (x, 16)).collect();
+        editor.carve_river(&path, 20.0, 1.5);
+
+        for pair in path.windows(2) {
+            let a = *editor.heightmap().get(pair[0].0, pair[0].1);
+            let b = *editor.heightmap().get(pair[1].0, pair[1].1);
+            assert!(b <= a, "river bed rose from {} to {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_named_lake_keeps_its_name_through_an_edit() {
+        use crate::water_bodies::WaterBodyType;
+
+        let mut world = crate::world::generate_world(96, 48, 3);
+        let lake = world
+            .water_bodies
+            .iter()
+            .filter(|b| b.body_type == WaterBodyType::Lake && b.name.is_some())
+            .max_by_key(|b| b.bounds.1)
+            .expect("a named lake")
+            .clone();
+
+        // Dig a pond on dry land north of the lake, outside the refreshed margin:
+        // it is detected first, so fresh numbering would shift the lake's ID
+        let (x, y) = (0..lake.bounds.1.saturating_sub(REFRESH_MARGIN + 2))
+            .flat_map(|y| (0..world.width).map(move |x| (x, y)))
+            .find(|&(x, y)| {
+                *world.heightmap.get(x, y) > 50.0
+                    && world.heightmap.neighbors(x, y).into_iter().all(|(nx, ny)| world.water_body_map.get(nx, ny).is_none())
+            })
+            .expect("dry land north of the lake");
+        world.heightmap.set(x, y, -5.0);
+        refresh_dependent_layers(&mut world, &EditRegion::point(x, y));
+
+        let pond = *world.water_body_map.get(x, y);
+        assert!(!pond.is_none() && pond != lake.id);
+        let kept = world.water_bodies.iter().find(|b| b.id == lake.id).expect("lake keeps its ID");
+        assert_eq!(kept.name, lake.name);
+        let entry = world.gazetteer.as_ref().unwrap().entries.iter().find(|e| Some(&e.name) == lake.name.as_ref()).unwrap();
+        assert_eq!(*world.water_body_map.get(entry.x, entry.y), lake.id);
+    }
+
+    #[test]
+    fn test_dirty_region_tracking() {
+        let mut editor = HeightmapEditor::new(flat(32, 0.0));
+        editor.raise(5, 5, &Brush::new(2.0, 1.0));
+        editor.raise(20, 10, &Brush::new(1.0, 1.0));
+        let region = editor.take_dirty_region().unwrap();
+        assert!(region.contains(5, 5));
+        assert!(region.contains(20, 10));
+        assert!(editor.take_dirty_region().is_none());
+    }
+}
@@ -0,0 +1,325 @@
+//! Erosion parameter autotuning
+//!
+//! Searches `ErosionParams` for the combination that maximizes the geomorphometry
+//! realism score, or that best matches user-specified metric targets (drainage density,
+//! hypsometric integral, ...). Uses a simple two-phase random search: uniform sampling
+//! of the parameter box followed by shrinking perturbations around the best candidate.
+//! The winning parameters can be saved as a JSON preset and reused with
+//! `ErosionParams::load_preset`.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::geomorphometry::GeomorphometryResults;
+use super::params::ErosionParams;
+use super::sweep::{BaseTerrain, SweepParam};
+
+/// Fraction of the evaluation budget spent on uniform exploration
+const EXPLORATION_FRACTION: f32 = 0.5;
+
+/// Initial perturbation radius (fraction of each parameter's range) during refinement
+const INITIAL_STEP: f32 = 0.25;
+
+/// Minimum perturbation radius during refinement
+const MIN_STEP: f32 = 0.02;
+
+/// Target values for geomorphometry metrics (unset targets are ignored)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TuneTargets {
+    pub drainage_density: Option<f32>,
+    pub hypsometric_integral: Option<f32>,
+    pub bifurcation_ratio: Option<f32>,
+    pub hacks_law_exponent: Option<f32>,
+    pub concavity_index: Option<f32>,
+    pub spectral_slope: Option<f32>,
+}
+
+impl TuneTargets {
+    /// Parse targets from `name=value` pairs separated by commas
+    /// (e.g. `drainage_density=0.3,hypsometric_integral=0.45`)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut targets = TuneTargets::default();
+        for pair in spec.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected NAME=VALUE, got '{}'", pair))?;
+            let value: f32 = value
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid value '{}' for {}", value.trim(), name.trim()))?;
+            match name.trim() {
+                "drainage_density" => targets.drainage_density = Some(value),
+                "hypsometric_integral" => targets.hypsometric_integral = Some(value),
+                "bifurcation_ratio" => targets.bifurcation_ratio = Some(value),
+                "hacks_law_exponent" => targets.hacks_law_exponent = Some(value),
+                "concavity_index" => targets.concavity_index = Some(value),
+                "spectral_slope" => targets.spectral_slope = Some(value),
+                other => return Err(format!("unknown tuning target '{}'", other)),
+            }
+        }
+        Ok(targets)
+    }
+
+    /// Sum of squared relative errors against the set targets (0 = perfect match)
+    pub fn error(&self, metrics: &GeomorphometryResults) -> f32 {
+        let pairs = [
+            (self.drainage_density, metrics.drainage_density),
+            (self.hypsometric_integral, metrics.hypsometric_integral),
+            (self.bifurcation_ratio, metrics.bifurcation_ratio),
+            (self.hacks_law_exponent, metrics.hacks_law_exponent),
+            (self.concavity_index, metrics.concavity_index),
+            (self.spectral_slope, metrics.spectral_slope),
+        ];
+
+        pairs
+            .iter()
+            .filter_map(|&(target, actual)| target.map(|t| (t, actual)))
+            .map(|(target, actual)| {
+                let rel = (actual - target) / target.abs().max(1e-3);
+                rel * rel
+            })
+            .sum()
+    }
+}
+
+/// What the autotuner optimizes
+#[derive(Clone, Debug, PartialEq)]
+pub enum TuneObjective {
+    /// Maximize `GeomorphometryResults::realism_score`
+    MaximizeRealism,
+    /// Minimize relative error against the given metric targets
+    MatchTargets(TuneTargets),
+}
+
+impl TuneObjective {
+    /// Fitness of a result (higher is better)
+    pub fn fitness(&self, metrics: &GeomorphometryResults) -> f32 {
+        match self {
+            TuneObjective::MaximizeRealism => metrics.realism_score(),
+            TuneObjective::MatchTargets(targets) => -targets.error(metrics),
+        }
+    }
+}
+
+/// Search range for one parameter
+#[derive(Clone, Copy, Debug)]
+pub struct TuneBound {
+    pub param: SweepParam,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl TuneBound {
+    pub fn new(param: SweepParam, min: f32, max: f32) -> Self {
+        Self { param, min, max }
+    }
+
+    fn clamp(&self, value: f32) -> f32 {
+        value.clamp(self.min.min(self.max), self.max.max(self.min))
+    }
+}
+
+/// Default search box: the parameters that most affect drainage structure
+pub fn default_bounds() -> Vec<TuneBound> {
+    vec![
+        TuneBound::new(SweepParam::DropletErosionRate, 0.01, 0.2),
+        TuneBound::new(SweepParam::DropletDepositRate, 0.02, 0.3),
+        TuneBound::new(SweepParam::DropletEvaporation, 0.001, 0.02),
+        TuneBound::new(SweepParam::RiverErosionRate, 0.2, 1.0),
+        TuneBound::new(SweepParam::RiverSourceMinAccumulation, 5.0, 50.0),
+        TuneBound::new(SweepParam::RiverMaxErosion, 50.0, 250.0),
+    ]
+}
+
+/// Configuration for an autotune run
+#[derive(Clone, Debug)]
+pub struct AutotuneConfig {
+    /// Map width used for every evaluation
+    pub width: usize,
+    /// Map height used for every evaluation
+    pub height: usize,
+    /// World seed (fixes plates and base terrain)
+    pub seed: u64,
+    /// Number of tectonic plates (random if None)
+    pub num_plates: Option<usize>,
+    /// Starting parameters; fields outside `bounds` are kept as-is
+    pub base_params: ErosionParams,
+    /// Parameters to search and their ranges
+    pub bounds: Vec<TuneBound>,
+    /// Optimization objective
+    pub objective: TuneObjective,
+    /// Number of erosion runs to evaluate (including the starting point)
+    pub evaluations: usize,
+    /// Seed for the search itself (separate from the world seed)
+    pub search_seed: u64,
+}
+
+/// One evaluated candidate
+#[derive(Clone, Debug)]
+pub struct TuneCandidate {
+    /// Values for each bound, in `AutotuneConfig::bounds` order
+    pub values: Vec<f32>,
+    pub fitness: f32,
+    pub realism_score: f32,
+}
+
+/// Result of an autotune run
+#[derive(Clone, Debug)]
+pub struct AutotuneResult {
+    /// Best parameters found
+    pub best_params: ErosionParams,
+    /// Fitness of the best parameters
+    pub best_fitness: f32,
+    /// Metrics measured with the best parameters
+    pub best_metrics: GeomorphometryResults,
+    /// Every evaluated candidate in order
+    pub history: Vec<TuneCandidate>,
+}
+
+fn params_from_values(base: &ErosionParams, bounds: &[TuneBound], values: &[f32]) -> ErosionParams {
+    let mut params = base.clone();
+    for (bound, &value) in bounds.iter().zip(values) {
+        bound.param.apply(&mut params, value);
+    }
+    params
+}
+
+/// Search erosion parameters for the best fitness under the configured objective
+pub fn autotune(config: &AutotuneConfig) -> AutotuneResult {
+    let base = BaseTerrain::generate(config.width, config.height, config.seed, config.num_plates);
+    let mut rng = ChaCha8Rng::seed_from_u64(config.search_seed);
+    let evaluations = config.evaluations.max(1);
+    let exploration = ((evaluations as f32 * EXPLORATION_FRACTION) as usize).max(1);
+
+    // Start from the base parameters so the result is never worse than the input
+    let start: Vec<f32> = config
+        .bounds
+        .iter()
+        .map(|b| b.clamp(b.param.get(&config.base_params)))
+        .collect();
+
+    let mut history: Vec<TuneCandidate> = Vec::with_capacity(evaluations);
+    let mut best: Option<(Vec<f32>, f32, GeomorphometryResults)> = None;
+
+    for i in 0..evaluations {
+        let values: Vec<f32> = if i == 0 {
+            start.clone()
+        } else if i < exploration || best.is_none() {
+            config.bounds.iter().map(|b| rng.gen_range(b.min.min(b.max)..=b.max.max(b.min))).collect()
+        } else {
+            // Shrink the perturbation radius as refinement progresses
+            let progress = (i - exploration) as f32 / (evaluations - exploration).max(1) as f32;
+            let step = (INITIAL_STEP * (1.0 - progress)).max(MIN_STEP);
+            let center = &best.as_ref().unwrap().0;
+            config
+                .bounds
+                .iter()
+                .zip(center)
+                .map(|(b, &c)| {
+                    let range = (b.max - b.min).abs();
+                    b.clamp(c + rng.gen_range(-1.0f32..=1.0) * step * range)
+                })
+                .collect()
+        };
+
+        let params = params_from_values(&config.base_params, &config.bounds, &values);
+        let (_, metrics) = base.erode_and_analyze(&params);
+        let fitness = config.objective.fitness(&metrics);
+        let realism_score = metrics.realism_score();
+
+        println!(
+            "Autotune {}/{}: fitness {:.3} (realism {:.1})",
+            i + 1,
+            evaluations,
+            fitness,
+            realism_score
+        );
+
+        let improved = best.as_ref().map(|(_, f, _)| fitness > *f).unwrap_or(true);
+        if improved {
+            best = Some((values.clone(), fitness, metrics));
+        }
+        history.push(TuneCandidate { values, fitness, realism_score });
+    }
+
+    let (best_values, best_fitness, best_metrics) = best.expect("at least one evaluation is always run");
+    AutotuneResult {
+        best_params: params_from_values(&config.base_params, &config.bounds, &best_values),
+        best_fitness,
+        best_metrics,
+        history,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_targets_parse() {
+        let targets = TuneTargets::parse("drainage_density=0.3, hypsometric_integral=0.45").unwrap();
+        assert_eq!(targets.drainage_density, Some(0.3));
+        assert_eq!(targets.hypsometric_integral, Some(0.45));
+        assert_eq!(targets.bifurcation_ratio, None);
+
+        assert!(TuneTargets::parse("drainage_density").is_err());
+        assert!(TuneTargets::parse("unknown_metric=1.0").is_err());
+    }
+
+    #[test]
+    fn test_target_error_zero_on_match() {
+        let metrics = GeomorphometryResults {
+            drainage_density: 0.3,
+            hypsometric_integral: 0.45,
+            ..Default::default()
+        };
+        let targets = TuneTargets::parse("drainage_density=0.3,hypsometric_integral=0.45").unwrap();
+        assert!(targets.error(&metrics) < 1e-6);
+
+        let off = TuneTargets::parse("drainage_density=0.6").unwrap();
+        assert!(off.error(&metrics) > 0.2);
+    }
+
+    #[test]
+    fn test_autotune_never_worse_than_start() {
+        let base_params = ErosionParams {
+            hydraulic_iterations: 200,
+            glacial_timesteps: 2,
+            use_gpu: false,
+            ..ErosionParams::default()
+        };
+        let config = AutotuneConfig {
+            width: 48,
+            height: 24,
+            seed: 11,
+            num_plates: Some(4),
+            base_params,
+            bounds: default_bounds(),
+            objective: TuneObjective::MaximizeRealism,
+            evaluations: 4,
+            search_seed: 3,
+        };
+
+        let result = autotune(&config);
+        assert_eq!(result.history.len(), 4);
+        assert!(result.history.iter().all(|c| c.fitness <= result.best_fitness));
+        assert!(result.best_fitness >= result.history[0].fitness);
+    }
+
+    #[test]
+    fn test_preset_round_trip() {
+        let params = ErosionParams {
+            droplet_erosion_rate: 0.123,
+            glacial_timesteps: 77,
+            ..ErosionParams::default()
+        };
+        let json = params.to_preset_json();
+        let loaded = ErosionParams::from_preset_json(&json).unwrap();
+        assert_eq!(params, loaded);
+
+        // Partial presets fill missing fields from defaults
+        let partial = ErosionParams::from_preset_json(r#"{"river_erosion_rate": 0.5}"#).unwrap();
+        assert_eq!(partial.river_erosion_rate, 0.5);
+        assert_eq!(partial.glacial_timesteps, ErosionParams::default().glacial_timesteps);
+    }
+}
@@ -61,6 +61,8 @@ pub struct GeomorphometryResults {
     pub relative_relief: f32,
     /// Geomorphon distribution: (summits, ridges, spurs, slopes, valleys, pits, flats, etc.)
     pub geomorphon_counts: [usize; 10],
+    /// Log-log slope β of the radial power spectrum - target: -3.5 to -2.5
+    pub spectral_slope: f32,
 }
 
 impl Default for GeomorphometryResults {
@@ -93,6 +95,7 @@ impl Default for GeomorphometryResults {
             knickpoint_density: 0.0,
             relative_relief: 0.0,
             geomorphon_counts: [0; 10],
+            spectral_slope: 0.0,
         }
     }
 }
@@ -245,6 +248,11 @@ impl GeomorphometryResults {
             self.geomorphon_counts[0], self.geomorphon_counts[1], self.geomorphon_counts[2],
             self.geomorphon_counts[3], self.geomorphon_counts[4], self.geomorphon_counts[5]);
 
+        // 20. Spectral Slope
+        let ss_status = if (-3.5..=-2.5).contains(&self.spectral_slope) { "PASS" } else { "WARN" };
+        println!("20. Spectral Slope (β):       {:.2} [target: -3.5 to -2.5] {}",
+            self.spectral_slope, ss_status);
+
         println!("==============================================\n");
     }
 
@@ -565,6 +573,9 @@ pub fn analyze(heightmap: &Tilemap<f32>, flow_threshold: f32) -> GeomorphometryR
     // 19. Geomorphon Distribution
     results.geomorphon_counts = compute_geomorphons(heightmap);
 
+    // 20. Power Spectrum Roll-off
+    results.spectral_slope = super::spectrum::power_spectrum(heightmap).slope();
+
     results
 }
 
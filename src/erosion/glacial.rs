@@ -26,6 +26,8 @@ struct GlacialState {
     flux_y: Tilemap<f32>,
     /// Basal sliding velocity magnitude
     sliding_velocity: Tilemap<f32>,
+    /// Cumulative bedrock eroded by ice
+    eroded: Tilemap<f32>,
 }
 
 impl GlacialState {
@@ -39,6 +41,7 @@ impl GlacialState {
             flux_x: Tilemap::new_with(width, height, 0.0f32),
             flux_y: Tilemap::new_with(width, height, 0.0f32),
             sliding_velocity: Tilemap::new_with(width, height, 0.0f32),
+            eroded: Tilemap::new_with(width, height, 0.0f32),
         }
     }
 
@@ -106,6 +109,7 @@ pub fn simulate(
         }
     }
 
+    stats.glacial_erosion = Some(state.eroded);
     stats
 }
 
@@ -321,6 +325,8 @@ fn apply_erosion(
             if actual_erosion > 0.0 && actual_erosion.is_finite() {
                 let current = *state.bedrock.get(x, y);
                 state.bedrock.set(x, y, current - actual_erosion);
+                let eroded = *state.eroded.get(x, y);
+                state.eroded.set(x, y, eroded + actual_erosion);
 
                 stats.total_eroded += actual_erosion as f64;
                 stats.max_erosion = stats.max_erosion.max(actual_erosion);
@@ -352,6 +352,8 @@ impl GpuErosionContext {
             iterations: params.hydraulic_iterations,
             river_lengths: Vec::new(),
             steps_taken: 0,
+            glacial_erosion: None,
+            hotspots: Vec::new(),
         }
     }
 }
@@ -22,6 +22,9 @@ use rand_chacha::ChaCha8Rng;
 use rayon::prelude::*;
 use std::sync::atomic::{AtomicU64, Ordering};
 
+/// Droplets simulated in parallel between heightmap updates
+pub const DROPLET_BATCH_SIZE: usize = 10_000;
+
 /// A water droplet for hydraulic erosion simulation
 struct WaterDroplet {
     /// Position (floating point for interpolation)
@@ -283,7 +286,7 @@ pub fn simulate_parallel(
     let brush = create_erosion_brush(params.droplet_erosion_radius);
 
     // Process in batches - each batch runs in parallel, then we apply changes
-    let batch_size = 10_000;
+    let batch_size = DROPLET_BATCH_SIZE;
     let num_batches = (params.hydraulic_iterations + batch_size - 1) / batch_size;
 
     // Atomic counters for statistics
@@ -361,6 +364,8 @@ pub fn simulate_parallel(
         iterations: params.hydraulic_iterations,
         river_lengths: Vec::new(),
         steps_taken: 0,
+        glacial_erosion: None,
+        hotspots: Vec::new(),
     }
 }
 
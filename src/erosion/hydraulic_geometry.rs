@@ -0,0 +1,202 @@
+//! Hydraulic geometry of the river network
+//!
+//! Channel width and depth follow discharge as power laws (Leopold & Maddock:
+//! width ~ Q^0.5, depth ~ Q^0.4), so a headwater stream is a few metres wide
+//! while a continental trunk river is kilometres across and tens of metres
+//! deep. Discharge comes from the drainage area under each point of the
+//! network. At flood stage a river spills over a floodway that is much wider
+//! than its channel on gentle lowland gradients and hugs it in steep valleys.
+//!
+//! Widths are physical, so at coarse scales most channels stay under a tile
+//! (and are drawn one tile wide), while great rivers and their floodways span
+//! several tiles on regional maps or at print resolution.
+
+use crate::tilemap::Tilemap;
+
+use super::river_geometry::{BezierRiverSegment, RiverNetwork};
+
+/// Share of rainfall that reaches the rivers, as metres of water a year
+const RUNOFF_M_PER_YEAR: f32 = 0.5;
+
+const SECONDS_PER_YEAR: f32 = 31_557_600.0;
+
+/// Channel width (m) = WIDTH_COEFF * Q^WIDTH_EXPONENT
+const WIDTH_COEFF: f32 = 8.0;
+const WIDTH_EXPONENT: f32 = 0.5;
+
+/// Bankfull depth (m) = DEPTH_COEFF * Q^DEPTH_EXPONENT
+const DEPTH_COEFF: f32 = 0.27;
+const DEPTH_EXPONENT: f32 = 0.39;
+
+/// Floodway width over channel width on a flat floodplain
+const FLOODWAY_RATIO: f32 = 30.0;
+
+/// Channel gradient (m per km) at which the floodway narrows to half
+const FLOODWAY_SLOPE_HALF: f32 = 1.0;
+
+/// Discharge, width and depth of one river segment at its downstream end
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Reach {
+    /// Index into `RiverNetwork::segments`
+    pub segment: usize,
+    /// Mean discharge (m³/s)
+    pub discharge: f32,
+    /// Bankfull channel width (m)
+    pub width_m: f32,
+    /// Bankfull depth (m)
+    pub depth_m: f32,
+    /// Width of ground under water at flood stage (m)
+    pub flood_width_m: f32,
+    /// Channel gradient (m per km)
+    pub gradient: f32,
+}
+
+/// What a tile holds in the rasterized network
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Waterway {
+    #[default]
+    None,
+    /// Under water only at flood stage
+    Floodway,
+    /// The river channel itself
+    Channel,
+}
+
+/// Mean discharge (m³/s) draining `flow_accumulation` tiles
+pub fn discharge(flow_accumulation: f32, km_per_tile: f32) -> f32 {
+    let area_m2 = flow_accumulation.max(1.0) * km_per_tile * km_per_tile * 1.0e6;
+    area_m2 * RUNOFF_M_PER_YEAR / SECONDS_PER_YEAR
+}
+
+/// Bankfull channel width (m) at `discharge`
+pub fn channel_width(discharge: f32) -> f32 {
+    WIDTH_COEFF * discharge.max(0.0).powf(WIDTH_EXPONENT)
+}
+
+/// Bankfull depth (m) at `discharge`
+pub fn channel_depth(discharge: f32) -> f32 {
+    DEPTH_COEFF * discharge.max(0.0).powf(DEPTH_EXPONENT)
+}
+
+/// Flood-stage width (m) of a channel `width_m` wide on a `gradient` (m/km)
+pub fn flood_width(width_m: f32, gradient: f32) -> f32 {
+    width_m * (1.0 + (FLOODWAY_RATIO - 1.0) / (1.0 + gradient.max(0.0) / FLOODWAY_SLOPE_HALF))
+}
+
+/// Geometry of every segment in the network
+pub fn reach_geometry(network: &RiverNetwork, km_per_tile: f32) -> Vec<Reach> {
+    network
+        .segments
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| {
+            let q = discharge(segment.p0.flow_accumulation.max(segment.p3.flow_accumulation), km_per_tile);
+            let gradient = segment_gradient(segment, km_per_tile);
+            let width_m = channel_width(q);
+            Reach {
+                segment: i,
+                discharge: q,
+                width_m,
+                depth_m: channel_depth(q),
+                flood_width_m: flood_width(width_m, gradient),
+                gradient,
+            }
+        })
+        .collect()
+}
+
+/// Drop along a segment (m per km)
+fn segment_gradient(segment: &BezierRiverSegment, km_per_tile: f32) -> f32 {
+    let length_km = segment.approximate_length(10) * km_per_tile;
+    if length_km <= 0.0 {
+        return 0.0;
+    }
+    (segment.p0.elevation - segment.p3.elevation).max(0.0) / length_km
+}
+
+/// Rasterize channels and floodways at `width` x `height`, which may be finer
+/// than the world (`world_size`). Every river keeps at least a one-cell thread.
+pub fn rasterize_waterways(
+    network: &RiverNetwork,
+    km_per_tile: f32,
+    world_size: (usize, usize),
+    width: usize,
+    height: usize,
+) -> Tilemap<Waterway> {
+    let mut map = Tilemap::new_with(width, height, Waterway::None);
+    let sx = width as f32 / world_size.0 as f32;
+    let sy = height as f32 / world_size.1 as f32;
+    // Output cells per metre
+    let cells_per_m = sx.max(sy) / (km_per_tile * 1000.0);
+
+    for segment in &network.segments {
+        let gradient = segment_gradient(segment, km_per_tile);
+        let samples = (segment.approximate_length(10) * sx.max(sy) * 2.0) as usize + 2;
+        for i in 0..=samples {
+            let pt = segment.evaluate(i as f32 / samples as f32);
+            let width_m = channel_width(discharge(pt.flow_accumulation, km_per_tile));
+            let (cx, cy) = ((pt.world_x + 0.5) * sx, (pt.world_y + 0.5) * sy);
+            stamp(&mut map, cx, cy, flood_width(width_m, gradient) * cells_per_m, Waterway::Floodway);
+            stamp(&mut map, cx, cy, (width_m * cells_per_m).max(1.0), Waterway::Channel);
+        }
+    }
+    map
+}
+
+/// Raise cells within a disc of the given diameter to `kind` (x wraps)
+fn stamp(map: &mut Tilemap<Waterway>, x: f32, y: f32, diameter: f32, kind: Waterway) {
+    let r = (diameter / 2.0).max(0.5);
+    let reach = r.ceil() as i32;
+    let (width, height) = (map.width as i32, map.height as i32);
+    for dy in -reach..=reach {
+        for dx in -reach..=reach {
+            let (px, py) = (x.floor() as i32 + dx, y.floor() as i32 + dy);
+            let (cx, cy) = (px as f32 + 0.5 - x, py as f32 + 0.5 - y);
+            if py < 0 || py >= height || cx * cx + cy * cy > r * r {
+                continue;
+            }
+            let cell = map.get_mut(px.rem_euclid(width) as usize, py as usize);
+            *cell = (*cell).max(kind);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::erosion::river_geometry::{RiverControlPoint, RiverNetworkParams};
+
+    #[test]
+    fn test_great_rivers_span_tiles_and_flood_wider() {
+        // A trunk river draining 40,000 tiles of 5 km beside a headwater stream
+        let big = discharge(40_000.0, 5.0);
+        let small = discharge(20.0, 5.0);
+        assert!(big > 10_000.0 && small < 50.0, "{} {}", big, small);
+        assert!(channel_width(big) > 1000.0 && channel_depth(big) > 10.0);
+        assert!(channel_width(small) < 60.0);
+        // Floodways spread on flat ground and hug steep channels
+        assert!(flood_width(1000.0, 0.1) > 3.0 * flood_width(1000.0, 20.0));
+
+        let point = |x: f32, flow: f32, elevation: f32| RiverControlPoint::new(x, 8.0, flow, 1.0, elevation);
+        let mut network = RiverNetwork::new(RiverNetworkParams::default());
+        network.segments.push(BezierRiverSegment {
+            p0: point(2.0, 40_000.0, 20.0),
+            p1: point(8.0, 40_000.0, 15.0),
+            p2: point(14.0, 40_000.0, 10.0),
+            p3: point(20.0, 40_000.0, 5.0),
+            tributaries: Vec::new(),
+            id: 0,
+        });
+        let reaches = reach_geometry(&network, 5.0);
+        assert!(reaches[0].flood_width_m > reaches[0].width_m);
+
+        // Its floodway spans several tiles; at 8x print resolution so does the channel
+        let tiles = rasterize_waterways(&network, 5.0, (24, 16), 24, 16);
+        assert!((0..16).filter(|&y| *tiles.get(10, y) == Waterway::Floodway).count() >= 4);
+        let map = rasterize_waterways(&network, 5.0, (24, 16), 192, 128);
+        let column = |x: usize, kind: Waterway| (0..128).filter(|&y| *map.get(x, y) == kind).count();
+        assert!(column(80, Waterway::Channel) >= 2);
+        assert!(column(80, Waterway::Floodway) > 10 * column(80, Waterway::Channel));
+        assert_eq!(column(180, Waterway::Channel), 0);
+    }
+}
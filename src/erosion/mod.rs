@@ -5,23 +5,39 @@
 //! - **Hydraulic erosion**: Particle-based water droplet simulation for detail
 //! - **Glacial erosion**: Shallow Ice Approximation (SIA) for U-shaped valleys and fjords
 
+#[cfg(feature = "simulation")]
+pub mod autotune;
 pub mod geomorphometry;
 pub mod glacial;
+#[cfg(feature = "gpu")]
 pub mod gpu;
 pub mod hydraulic;
+pub mod hydraulic_geometry;
 pub mod materials;
 pub mod params;
+pub mod presets;
 pub mod river_geometry;
 pub mod rivers;
+pub mod spectrum;
+#[cfg(feature = "simulation")]
+pub mod sweep;
 pub mod utils;
 
+#[cfg(feature = "simulation")]
+pub use autotune::{AutotuneConfig, AutotuneResult, TuneObjective, TuneTargets, autotune};
+pub use hydraulic_geometry::{Reach, Waterway, rasterize_waterways, reach_geometry};
 pub use materials::{RockType, generate_material_map, generate_hardness_map};
 pub use params::ErosionParams;
+pub use presets::ErosionPreset;
 pub use rivers::RiverErosionParams;
 pub use river_geometry::{RiverNetwork, RiverNetworkParams, trace_bezier_rivers};
+pub use spectrum::{PowerSpectrum, SpectralShaping, power_spectrum, shape_spectrum};
+#[cfg(feature = "simulation")]
+pub use sweep::{SweepAxis, SweepConfig, SweepParam, SweepResult, run_sweep};
 
 use crate::tilemap::Tilemap;
-use crate::plates::{Plate, PlateId};
+use crate::plates::{apply_hotspots, place_hotspots, Hotspot, HotspotParams, Plate, PlateId};
+use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
 /// Statistics from erosion simulation
@@ -41,6 +57,10 @@ pub struct ErosionStats {
     pub max_deposition: f32,
     /// Lengths of all rivers found in the network analysis
     pub river_lengths: Vec<usize>,
+    /// Depth of bedrock removed by glaciers at each tile (None when glacial erosion didn't run)
+    pub glacial_erosion: Option<Tilemap<f32>>,
+    /// Mantle plumes raised before erosion (empty unless hotspots are enabled)
+    pub hotspots: Vec<Hotspot>,
 }
 
 impl Default for ErosionStats {
@@ -53,6 +73,8 @@ impl Default for ErosionStats {
             max_erosion: 0.0,
             max_deposition: 0.0,
             river_lengths: Vec::new(),
+            glacial_erosion: None,
+            hotspots: Vec::new(),
         }
     }
 }
@@ -286,6 +308,14 @@ pub fn simulate_erosion(
     // Variable hardness creates too much noise
     let hardness = Tilemap::new_with(heightmap.width, heightmap.height, 0.3f32);
 
+    // Hotspot volcanoes go up first so the rivers and glaciers wear them down
+    if params.enable_hotspots {
+        let hotspot_params = HotspotParams { count: params.hotspot_count, ..Default::default() };
+        let mut hotspot_rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x407590));
+        stats.hotspots = place_hotspots(plate_map, plates, &hotspot_params, &mut hotspot_rng);
+        apply_hotspots(heightmap, &stats.hotspots);
+    }
+
     // Run flow-based river erosion first (carves major drainage channels)
     if params.enable_rivers {
         let river_params = RiverErosionParams {
@@ -312,11 +342,13 @@ pub fn simulate_erosion(
     // Run particle-based hydraulic erosion (adds detail to channels)
     // Uses GPU if available and enabled, otherwise parallel CPU implementation
     if params.enable_hydraulic {
-        let hydraulic_stats = if params.use_gpu {
-            gpu::simulate_gpu_or_cpu(heightmap, &hardness, params, seed)
-        } else {
+        let hydraulic_stats = crate::budget::with_threads(params.max_threads, || {
+            #[cfg(feature = "gpu")]
+            if params.use_gpu {
+                return gpu::simulate_gpu_or_cpu(heightmap, &hardness, params, seed);
+            }
             hydraulic::simulate_parallel(heightmap, &hardness, params, seed)
-        };
+        });
         stats.total_eroded += hydraulic_stats.total_eroded;
         stats.total_deposited += hydraulic_stats.total_deposited;
         stats.iterations += hydraulic_stats.iterations;
@@ -332,6 +364,7 @@ pub fn simulate_erosion(
         stats.iterations += glacial_stats.iterations;
         stats.max_erosion = stats.max_erosion.max(glacial_stats.max_erosion);
         stats.max_deposition = stats.max_deposition.max(glacial_stats.max_deposition);
+        stats.glacial_erosion = glacial_stats.glacial_erosion;
     }
 
     // Analyze river network connectivity (numerical verification)
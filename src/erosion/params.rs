@@ -1,7 +1,8 @@
 //! Erosion simulation parameters and configuration
 
 /// Global erosion simulation parameters
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct ErosionParams {
     // =========================================================================
     // Hydraulic Erosion Parameters
@@ -120,6 +121,16 @@ pub struct ErosionParams {
     /// Width of river channel (for cross-section erosion)
     pub river_channel_width: usize,
 
+    // =========================================================================
+    // Hotspot Volcanism
+    // =========================================================================
+
+    /// Raise hotspot island chains and flood basalt plateaus before eroding
+    pub enable_hotspots: bool,
+
+    /// Mantle plumes to place (0 = scale with the map area)
+    pub hotspot_count: usize,
+
     // =========================================================================
     // General Settings
     // =========================================================================
@@ -133,8 +144,11 @@ pub struct ErosionParams {
     /// Enable geomorphometry analysis (realism scoring)
     pub enable_analysis: bool,
 
-    /// Use GPU acceleration for hydraulic erosion (if available)
+    /// Use GPU acceleration for hydraulic erosion (if available; ignored without the `gpu` feature)
     pub use_gpu: bool,
+
+    /// Worker threads for parallel hydraulic erosion (None = all cores)
+    pub max_threads: Option<usize>,
 }
 
 impl Default for ErosionParams {
@@ -179,11 +193,16 @@ impl Default for ErosionParams {
             river_max_deposition: 0.0,             // No deposition
             river_channel_width: 2,                // Wide channels for visibility
 
+            // Hotspots are opt-in so existing presets keep their terrain
+            enable_hotspots: false,
+            hotspot_count: 0,
+
             // General
             enable_hydraulic: true,       // Enabled (was false)
             enable_glacial: true,         // Enabled for fjords and glacial valleys
             enable_analysis: false,       // Disabled by default (use --analyze to enable)
             use_gpu: true,                // Use GPU if available
+            max_threads: None,            // Every core
         }
     }
 }
@@ -223,6 +242,28 @@ impl ErosionParams {
         }
     }
 
+    /// Serialize these parameters as a JSON preset
+    pub fn to_preset_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("ErosionParams is always serializable")
+    }
+
+    /// Parse parameters from a JSON preset (missing fields take default values)
+    pub fn from_preset_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Save these parameters as a reusable JSON preset file
+    pub fn save_preset(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.to_preset_json())
+    }
+
+    /// Load parameters from a JSON preset file
+    pub fn load_preset(path: &str) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_preset_json(&json)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
     /// Compute ice density * gravity (commonly used in SIA)
     pub fn rho_g(&self) -> f32 {
         self.ice_density * self.gravity
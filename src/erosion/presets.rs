@@ -0,0 +1,219 @@
+//! Built-in erosion presets
+//!
+//! Tuned `ErosionParams` bundles for common landscape looks, so a world can be given
+//! a character without tuning a dozen coupled parameters by hand:
+//!
+//! - **alpine-young**: sharp young ranges. Little hydraulic smoothing, narrow deep
+//!   river cuts and valley glaciers at altitude; ridges and peaks stay crisp.
+//! - **old-rolling-hills**: long-worn lowlands. Many droplets with wide footprints and
+//!   generous deposition fill hollows and round off summits; rivers stay shallow.
+//! - **desert-mesas**: arid plateaus. Droplets evaporate quickly and rivers are sparse
+//!   but cut deep single-tile canyons, leaving flat tops between steep walls. No ice.
+//! - **tropical-dissected**: rain-soaked uplands. A dense, fine drainage network
+//!   etches every slope into ridges and ravines. No ice.
+//! - **glaciated-shield**: ice-scoured cratons. Ice forms at higher temperatures and
+//!   erodes hard for a long time, leaving smoothed rock and a young, immature drainage.
+//!
+//! A preset is a starting point: its parameters can be saved with
+//! `ErosionParams::save_preset` and edited like any other preset file.
+
+use super::params::ErosionParams;
+
+/// A built-in erosion look
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ErosionPreset {
+    AlpineYoung,
+    OldRollingHills,
+    DesertMesas,
+    TropicalDissected,
+    GlaciatedShield,
+}
+
+impl ErosionPreset {
+    /// All built-in presets
+    pub fn all() -> &'static [ErosionPreset] {
+        &[
+            ErosionPreset::AlpineYoung,
+            ErosionPreset::OldRollingHills,
+            ErosionPreset::DesertMesas,
+            ErosionPreset::TropicalDissected,
+            ErosionPreset::GlaciatedShield,
+        ]
+    }
+
+    /// Name used on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErosionPreset::AlpineYoung => "alpine-young",
+            ErosionPreset::OldRollingHills => "old-rolling-hills",
+            ErosionPreset::DesertMesas => "desert-mesas",
+            ErosionPreset::TropicalDissected => "tropical-dissected",
+            ErosionPreset::GlaciatedShield => "glaciated-shield",
+        }
+    }
+
+    /// One-line description of the look
+    pub fn description(&self) -> &'static str {
+        match self {
+            ErosionPreset::AlpineYoung => "sharp young ranges with glacial valleys and narrow gorges",
+            ErosionPreset::OldRollingHills => "worn, rounded lowlands with shallow meandering rivers",
+            ErosionPreset::DesertMesas => "flat-topped plateaus cut by sparse, deep canyons",
+            ErosionPreset::TropicalDissected => "slopes finely etched by a dense drainage network",
+            ErosionPreset::GlaciatedShield => "ice-scoured low relief with an immature drainage",
+        }
+    }
+
+    /// Look up a preset by name, ignoring case and `-`/`_` (so `AlpineYoung` works too)
+    pub fn from_name(name: &str) -> Option<ErosionPreset> {
+        let key = |s: &str| s.chars().filter(|c| *c != '-' && *c != '_').collect::<String>().to_ascii_lowercase();
+        let wanted = key(name);
+        Self::all().iter().copied().find(|p| key(p.name()) == wanted)
+    }
+
+    /// The tuned parameters for this look
+    pub fn params(&self) -> ErosionParams {
+        let base = ErosionParams::default();
+        match self {
+            ErosionPreset::AlpineYoung => ErosionParams {
+                hydraulic_iterations: 250_000,
+                droplet_erosion_rate: 0.03,
+                droplet_deposit_rate: 0.05,
+                droplet_erosion_radius: 1,
+                glacial_timesteps: 600,
+                glaciation_temperature: -1.0,
+                erosion_coefficient: 1.5e-4,
+                river_max_erosion: 200.0,
+                river_channel_width: 1,
+                ..base
+            },
+            ErosionPreset::OldRollingHills => ErosionParams {
+                hydraulic_iterations: 900_000,
+                droplet_inertia: 0.4,
+                droplet_erosion_rate: 0.08,
+                droplet_deposit_rate: 0.3,
+                droplet_erosion_radius: 3,
+                river_capacity_factor: 12.0,
+                river_max_erosion: 60.0,
+                river_deposition_rate: 0.8,
+                river_max_deposition: 20.0,
+                river_channel_width: 3,
+                enable_glacial: false,
+                ..base
+            },
+            ErosionPreset::DesertMesas => ErosionParams {
+                hydraulic_iterations: 150_000,
+                droplet_evaporation: 0.01,
+                droplet_erosion_rate: 0.04,
+                droplet_deposit_rate: 0.02,
+                droplet_erosion_radius: 1,
+                river_source_min_accumulation: 60.0,
+                river_max_erosion: 250.0,
+                river_channel_width: 1,
+                enable_glacial: false,
+                ..base
+            },
+            ErosionPreset::TropicalDissected => ErosionParams {
+                hydraulic_iterations: 800_000,
+                droplet_evaporation: 0.001,
+                droplet_erosion_rate: 0.07,
+                droplet_erosion_radius: 1,
+                river_source_min_accumulation: 4.0,
+                river_source_min_elevation: 50.0,
+                river_capacity_factor: 14.0,
+                river_max_erosion: 120.0,
+                river_channel_width: 1,
+                enable_glacial: false,
+                ..base
+            },
+            ErosionPreset::GlaciatedShield => ErosionParams {
+                hydraulic_iterations: 200_000,
+                glacial_timesteps: 1000,
+                glaciation_temperature: 5.0,
+                erosion_coefficient: 3e-4,
+                ice_sliding_coefficient: 1e-3,
+                river_source_min_accumulation: 20.0,
+                river_max_erosion: 40.0,
+                ..base
+            },
+        }
+    }
+}
+
+impl ErosionParams {
+    /// A built-in preset by name, otherwise a JSON preset file
+    pub fn load(spec: &str) -> std::io::Result<Self> {
+        match ErosionPreset::from_name(spec) {
+            Some(preset) => Ok(preset.params()),
+            None => Self::load_preset(spec),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_and_round_trip() {
+        for preset in ErosionPreset::all() {
+            assert_eq!(ErosionPreset::from_name(preset.name()), Some(*preset));
+            let params = preset.params();
+            let json = params.to_preset_json();
+            assert_eq!(ErosionParams::from_preset_json(&json).unwrap().to_preset_json(), json);
+        }
+        assert_eq!(ErosionPreset::from_name("AlpineYoung"), Some(ErosionPreset::AlpineYoung));
+        assert_eq!(ErosionPreset::from_name("glaciated_shield"), Some(ErosionPreset::GlaciatedShield));
+        assert_eq!(ErosionPreset::from_name("volcanic"), None);
+        assert_eq!(ErosionParams::load("desert-mesas").unwrap().river_source_min_accumulation, 60.0);
+    }
+
+    #[test]
+    #[cfg(feature = "simulation")]
+    fn test_looks_on_shared_terrain() {
+        use crate::erosion::sweep::BaseTerrain;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        // The same "before" terrain eroded with each preset, scaled down for test speed
+        let before = BaseTerrain::generate(64, 32, 17, Some(6));
+        let erode = |preset: ErosionPreset| {
+            let params = preset.params();
+            let params = ErosionParams {
+                hydraulic_iterations: params.hydraulic_iterations / 100,
+                glacial_timesteps: params.glacial_timesteps / 20,
+                use_gpu: false,
+                ..params
+            };
+            let mut heightmap = before.heightmap.clone();
+            let mut rng = ChaCha8Rng::seed_from_u64(before.seed);
+            let (stats, _) = crate::erosion::simulate_erosion(
+                &mut heightmap,
+                &before.plate_map,
+                &before.plates,
+                &before.stress_map,
+                &before.temperature,
+                &params,
+                &mut rng,
+                before.seed,
+            );
+            stats
+        };
+        let ice = |stats: &crate::erosion::ErosionStats| {
+            stats.glacial_erosion.as_ref().map(|g| g.iter().map(|(_, _, &d)| d as f64).sum::<f64>()).unwrap_or(0.0)
+        };
+
+        let alpine = erode(ErosionPreset::AlpineYoung);
+        let hills = erode(ErosionPreset::OldRollingHills);
+        let desert = erode(ErosionPreset::DesertMesas);
+        let tropical = erode(ErosionPreset::TropicalDissected);
+        let shield = erode(ErosionPreset::GlaciatedShield);
+
+        // Dense tropical drainage vs sparse desert canyons
+        assert!(tropical.river_lengths.len() > desert.river_lengths.len());
+        // Old hills fill their hollows more than young ranges do
+        assert!(hills.total_deposited > alpine.total_deposited);
+        // Ice: the shield is scoured hardest, the warm looks not at all
+        assert!(ice(&shield) > ice(&alpine));
+        assert!(hills.glacial_erosion.is_none() && desert.glacial_erosion.is_none());
+    }
+}
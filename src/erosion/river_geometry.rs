@@ -12,7 +12,7 @@ use noise::{NoiseFn, Perlin, Seedable};
 // =============================================================================
 
 /// A control point along a river's Bezier path
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct RiverControlPoint {
     /// World X coordinate (can be fractional for smooth interpolation)
     pub world_x: f32,
@@ -50,7 +50,7 @@ impl RiverControlPoint {
 }
 
 /// A cubic Bezier segment of a river
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct BezierRiverSegment {
     /// Start point (P0)
     pub p0: RiverControlPoint,
@@ -138,7 +138,7 @@ impl BezierRiverSegment {
 }
 
 /// A confluence point where rivers merge
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ConfluencePoint {
     /// World position
     pub x: f32,
@@ -150,7 +150,7 @@ pub struct ConfluencePoint {
 }
 
 /// The complete river network
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct RiverNetwork {
     /// All Bezier segments in the network
     pub segments: Vec<BezierRiverSegment>,
@@ -163,7 +163,7 @@ pub struct RiverNetwork {
 }
 
 /// Parameters for river network generation
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct RiverNetworkParams {
     /// Minimum flow accumulation to be considered a river source
     pub source_threshold: f32,
@@ -391,9 +391,6 @@ fn apply_erosion(
     flow: f32,
     source_threshold: f32,
 ) {
-    let width = heightmap.width;
-    let height = heightmap.height;
-
     // Calculate dynamic width based on flow
     let half_width = calculate_river_width(flow, base_width, source_threshold);
 
@@ -402,8 +399,7 @@ fn apply_erosion(
 
     // Apply V-shaped profile across channel
     for i in -(half_width as i32)..=(half_width as i32) {
-        let nx = (x as i32 + perp_dx * i).rem_euclid(width as i32) as usize;
-        let ny = (y as i32 + perp_dy * i).clamp(0, height as i32 - 1) as usize;
+        let (nx, ny) = heightmap.wrap_coords(x as i32 + perp_dx * i, y as i32 + perp_dy * i);
 
         // V-shape: full erosion at center, decreasing toward edges
         let dist = i.abs() as f32;
@@ -428,9 +424,6 @@ fn apply_deposition(
     flow: f32,
     source_threshold: f32,
 ) {
-    let width = heightmap.width;
-    let height = heightmap.height;
-
     // Calculate dynamic width based on flow
     let half_width = calculate_river_width(flow, base_width, source_threshold);
 
@@ -448,8 +441,7 @@ fn apply_deposition(
             continue;
         }
 
-        let nx = (x as i32 + perp_dx * i).rem_euclid(width as i32) as usize;
-        let ny = (y as i32 + perp_dy * i).clamp(0, height as i32 - 1) as usize;
+        let (nx, ny) = heightmap.wrap_coords(x as i32 + perp_dx * i, y as i32 + perp_dy * i);
 
         // Falloff from inner edge outward
         let dist_from_channel = (i.abs() - inner_radius) as f32;
@@ -469,9 +461,6 @@ fn apply_delta_deposition(
     amount: f32,
     flow_dir: u8,
 ) {
-    let width = heightmap.width;
-    let height = heightmap.height;
-
     // Get flow direction vector
     let (flow_dx, flow_dy) = if flow_dir < 8 {
         (DX[flow_dir as usize], DY[flow_dir as usize])
@@ -495,8 +484,7 @@ fn apply_delta_deposition(
                 continue;
             }
 
-            let nx = (x as i32 + dx).rem_euclid(width as i32) as usize;
-            let ny = (y as i32 + dy).clamp(0, height as i32 - 1) as usize;
+            let (nx, ny) = heightmap.wrap_coords(x as i32 + dx, y as i32 + dy);
 
             let dist = (dist_sq as f32).sqrt();
             let falloff = 1.0 - (dist / (fan_radius as f32 + 1.0));
@@ -0,0 +1,308 @@
+//! Frequency spectrum of a heightmap, and shaping toward a target spectrum
+//!
+//! Real terrain is close to self-affine: the radially averaged power spectrum
+//! of its elevation falls off as a power law, P(f) ~ f^β, with β around -3
+//! for most landscapes (steeper for smooth, old terrain; shallower for rough,
+//! young terrain). `power_spectrum` measures this roll-off so generated worlds
+//! can be compared against it, and `shape_spectrum` nudges the octaves of a
+//! heightmap toward a target slope while leaving river channels in place.
+
+use crate::plates::smooth_stress;
+use crate::tilemap::Tilemap;
+
+use super::rivers::{compute_flow_accumulation, compute_flow_direction};
+
+/// Radial frequency bins (log-spaced) in a spectrum
+const SPECTRUM_BINS: usize = 24;
+
+/// Share of the frequency range (by log) left out at each end of the slope
+/// fit: the lowest bins hold only a handful of samples, the highest are
+/// flattened by smoothing passes
+const FIT_MARGIN: f32 = 0.15;
+
+/// Largest octave gain the shaping applies in one pass
+const MAX_BAND_GAIN: f32 = 2.0;
+
+/// One radial bin of a power spectrum
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpectrumBin {
+    /// Centre frequency (cycles per tile)
+    pub frequency: f32,
+    /// Mean power of the Fourier coefficients in the bin
+    pub power: f32,
+}
+
+/// Radially averaged power spectrum, lowest frequency first
+#[derive(Clone, Debug, Default)]
+pub struct PowerSpectrum {
+    pub bins: Vec<SpectrumBin>,
+}
+
+impl PowerSpectrum {
+    /// Log-log slope β of power against frequency, fitted over the middle of
+    /// the range (0 if there are too few bins)
+    pub fn slope(&self) -> f32 {
+        let bins: Vec<&SpectrumBin> = self.bins.iter().filter(|b| b.power > 0.0).collect();
+        if bins.len() < 3 {
+            return 0.0;
+        }
+        let (lo, hi) = (bins[0].frequency.ln(), bins[bins.len() - 1].frequency.ln());
+        let margin = (hi - lo) * FIT_MARGIN;
+        let points: Vec<(f32, f32)> = bins
+            .iter()
+            .map(|b| (b.frequency.ln(), b.power.ln()))
+            .filter(|&(f, _)| f >= lo + margin && f <= hi - margin)
+            .collect();
+        let n = points.len() as f32;
+        if n < 2.0 {
+            return 0.0;
+        }
+        let (mx, my) = (points.iter().map(|p| p.0).sum::<f32>() / n, points.iter().map(|p| p.1).sum::<f32>() / n);
+        let sxy: f32 = points.iter().map(|p| (p.0 - mx) * (p.1 - my)).sum();
+        let sxx: f32 = points.iter().map(|p| (p.0 - mx).powi(2)).sum();
+        if sxx > 0.0 { sxy / sxx } else { 0.0 }
+    }
+
+    /// CSV with one row per bin
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("frequency,power\n");
+        for bin in &self.bins {
+            out.push_str(&format!("{},{}\n", bin.frequency, bin.power));
+        }
+        out
+    }
+}
+
+/// How `shape_spectrum` reshapes a heightmap
+#[derive(Clone, Debug)]
+pub struct SpectralShaping {
+    /// Target log-log slope β of the power spectrum
+    pub slope: f32,
+    /// How far each octave moves toward its target (0 = not at all, 1 = fully)
+    pub strength: f32,
+    /// Flow accumulation (tiles) above which a channel is kept as carved
+    pub river_threshold: f32,
+}
+
+impl Default for SpectralShaping {
+    fn default() -> Self {
+        Self { slope: -3.0, strength: 0.5, river_threshold: 40.0 }
+    }
+}
+
+/// What a shaping pass changed
+#[derive(Clone, Debug)]
+pub struct ShapingReport {
+    pub slope_before: f32,
+    pub slope_after: f32,
+    /// Tiles held in place as river channels
+    pub river_tiles: usize,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+/// In-place radix-2 FFT; `data.len()` must be a power of two
+fn fft(data: &mut [Complex]) {
+    let n = data.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -std::f64::consts::TAU / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f64).sin_cos();
+                let (a, b) = (data[start + k], data[start + k + len / 2]);
+                let t = Complex { re: b.re * cos - b.im * sin, im: b.re * sin + b.im * cos };
+                data[start + k] = Complex { re: a.re + t.re, im: a.im + t.im };
+                data[start + k + len / 2] = Complex { re: a.re - t.re, im: a.im - t.im };
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Radially averaged power spectrum of `heightmap`.
+///
+/// The map is resampled up to power-of-two sides (frequencies stay in cycles
+/// per original tile). East-west it wraps, so only the north-south direction
+/// is tapered with a Hann window.
+pub fn power_spectrum(heightmap: &Tilemap<f32>) -> PowerSpectrum {
+    let (width, height) = (heightmap.width, heightmap.height);
+    if width < 4 || height < 4 {
+        return PowerSpectrum::default();
+    }
+    let (fw, fh) = (width.next_power_of_two(), height.next_power_of_two());
+    let resampled = if (fw, fh) == (width, height) { heightmap.clone() } else { heightmap.resample(fw, fh) };
+    let mean = resampled.iter().map(|(_, _, &h)| h as f64).sum::<f64>() / (fw * fh) as f64;
+
+    let mut grid = vec![Complex::default(); fw * fh];
+    for (x, y, &h) in resampled.iter() {
+        let window = 0.5 - 0.5 * (std::f64::consts::TAU * (y as f64 + 0.5) / fh as f64).cos();
+        grid[y * fw + x].re = (h as f64 - mean) * window;
+    }
+    for row in grid.chunks_mut(fw) {
+        fft(row);
+    }
+    let mut column = vec![Complex::default(); fh];
+    for x in 0..fw {
+        for y in 0..fh {
+            column[y] = grid[y * fw + x];
+        }
+        fft(&mut column);
+        for y in 0..fh {
+            grid[y * fw + x] = column[y];
+        }
+    }
+
+    // Log-spaced bins from the lowest resolvable frequency to Nyquist
+    let f_min = 1.0 / width.max(height) as f64;
+    let f_max = 0.5;
+    let bin_of = |f: f64| ((f / f_min).ln() / (f_max / f_min).ln() * SPECTRUM_BINS as f64) as usize;
+    let mut sums = vec![(0.0f64, 0usize); SPECTRUM_BINS];
+    for ky in 0..fh {
+        for kx in 0..fw {
+            // Signed wavenumbers, in cycles per original tile
+            let sx = if kx <= fw / 2 { kx as f64 } else { kx as f64 - fw as f64 } / width as f64;
+            let sy = if ky <= fh / 2 { ky as f64 } else { ky as f64 - fh as f64 } / height as f64;
+            let f = (sx * sx + sy * sy).sqrt();
+            if f < f_min || f > f_max {
+                continue;
+            }
+            let c = grid[ky * fw + kx];
+            let bin = bin_of(f).min(SPECTRUM_BINS - 1);
+            sums[bin].0 += c.re * c.re + c.im * c.im;
+            sums[bin].1 += 1;
+        }
+    }
+    let bins = sums
+        .iter()
+        .enumerate()
+        .filter(|(_, &(_, count))| count > 0)
+        .map(|(i, &(sum, count))| SpectrumBin {
+            frequency: (f_min * (f_max / f_min).powf((i as f64 + 0.5) / SPECTRUM_BINS as f64)) as f32,
+            power: (sum / count as f64 / (fw * fh) as f64) as f32,
+        })
+        .collect();
+    PowerSpectrum { bins }
+}
+
+/// Move each octave of the heightmap toward the power the target slope gives
+/// it, relative to the coarsest octave (which holds the continents and stays
+/// as it is). River channels keep their carved elevations and the coastline
+/// keeps its side of sea level.
+pub fn shape_spectrum(heightmap: &mut Tilemap<f32>, shaping: &SpectralShaping) -> ShapingReport {
+    let (width, height) = (heightmap.width, heightmap.height);
+    let slope_before = power_spectrum(heightmap).slope();
+
+    // Gaussian pyramid: sigma 1, 2, 4, ... up to an eighth of the short side
+    let mut levels = vec![heightmap.clone()];
+    let mut sigma = 1.0f32;
+    while sigma <= height.min(width) as f32 / 8.0 {
+        levels.push(smooth_stress(heightmap, (sigma * 3.0).ceil() as usize, sigma));
+        sigma *= 2.0;
+    }
+    let octaves = levels.len() - 1;
+    if octaves < 2 {
+        return ShapingReport { slope_before, slope_after: slope_before, river_tiles: 0 };
+    }
+    let variance = |a: &Tilemap<f32>, b: &Tilemap<f32>| {
+        a.iter().map(|(x, y, &v)| (v - *b.get(x, y)).powi(2) as f64).sum::<f64>() / (width * height) as f64
+    };
+    let powers: Vec<f64> = (0..octaves).map(|i| variance(&levels[i], &levels[i + 1])).collect();
+
+    // Octave i is centred near 2^-i of the finest one; with P ~ f^β its
+    // power scales as f^(β + 2)
+    let anchor = octaves - 1;
+    let gains: Vec<f32> = (0..octaves)
+        .map(|i| {
+            let target = powers[anchor] * 2f64.powf((shaping.slope as f64 + 2.0) * (anchor - i) as f64);
+            let gain = if powers[i] > 0.0 { (target / powers[i]).sqrt() as f32 } else { 1.0 };
+            1.0 + (gain.clamp(1.0 / MAX_BAND_GAIN, MAX_BAND_GAIN) - 1.0) * shaping.strength.clamp(0.0, 1.0)
+        })
+        .collect();
+
+    // Channels (and a feathered margin around them) hold still
+    let flow = compute_flow_accumulation(heightmap, &compute_flow_direction(heightmap));
+    let mut rivers = Tilemap::new_with(width, height, 0.0f32);
+    let mut river_tiles = 0;
+    for (x, y, r) in rivers.iter_mut() {
+        if *heightmap.get(x, y) > 0.0 && *flow.get(x, y) >= shaping.river_threshold {
+            *r = 1.0;
+            river_tiles += 1;
+        }
+    }
+    let hold = smooth_stress(&rivers, 2, 1.0);
+
+    let original = heightmap.clone();
+    for (x, y, h) in heightmap.iter_mut() {
+        let orig = *original.get(x, y);
+        if *rivers.get(x, y) > 0.0 {
+            continue;
+        }
+        let delta: f32 = (0..octaves).map(|i| (gains[i] - 1.0) * (*levels[i].get(x, y) - *levels[i + 1].get(x, y))).sum();
+        let shaped = orig + delta * (1.0 - (*hold.get(x, y) * 2.0).min(1.0));
+        *h = if orig > 0.0 { shaped.max(orig.min(1.0)) } else { shaped.min(orig.max(-1.0)) };
+    }
+
+    ShapingReport { slope_before, slope_after: power_spectrum(heightmap).slope(), river_tiles }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use noise::{NoiseFn, Perlin};
+
+    /// fBm terrain with a known roll-off: each octave halves the amplitude
+    fn fbm_terrain(width: usize, height: usize, persistence: f64) -> Tilemap<f32> {
+        let noise = Perlin::new(3);
+        let mut map = Tilemap::new_with(width, height, 0.0f32);
+        for (x, y, h) in map.iter_mut() {
+            let (mut amp, mut freq, mut sum) = (1000.0, 1.0 / 32.0, 0.0);
+            for _ in 0..6 {
+                sum += amp * noise.get([x as f64 * freq, y as f64 * freq]);
+                amp *= persistence;
+                freq *= 2.0;
+            }
+            *h = sum as f32 + 200.0;
+        }
+        map
+    }
+
+    #[test]
+    fn test_spectrum_slope_and_shaping() {
+        // Rougher terrain (higher persistence) rolls off more gently
+        let smooth = power_spectrum(&fbm_terrain(128, 64, 0.35)).slope();
+        let rough = power_spectrum(&fbm_terrain(128, 64, 0.7)).slope();
+        assert!(smooth < rough && rough < 0.0, "smooth {} rough {}", smooth, rough);
+
+        let mut terrain = fbm_terrain(128, 64, 0.7);
+        let before = terrain.clone();
+        let report = shape_spectrum(&mut terrain, &SpectralShaping { slope: -4.0, strength: 1.0, river_threshold: 30.0 });
+        assert!(report.slope_after < report.slope_before);
+        assert!(report.river_tiles > 0);
+        // Channels keep their elevation; no tile crosses sea level
+        let flow = compute_flow_accumulation(&before, &compute_flow_direction(&before));
+        for (x, y, &h) in terrain.iter() {
+            let orig = *before.get(x, y);
+            if orig > 0.0 && *flow.get(x, y) >= 30.0 {
+                assert_eq!(h, orig);
+            }
+            assert_eq!(h > 0.0, orig > 0.0);
+        }
+    }
+}
@@ -0,0 +1,465 @@
+//! Parameter sweeps over erosion settings
+//!
+//! Generates a grid of worlds that share the same pre-erosion terrain but vary one or
+//! two `ErosionParams` fields, recording geomorphometry metrics for each cell. The result
+//! can be written as a CSV (one row per cell) and a contact-sheet PNG for side-by-side
+//! visual comparison, replacing one-off guess-and-check tuning runs.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::climate;
+use crate::heightmap;
+use crate::plates::{self, Plate, PlateId};
+use crate::tilemap::Tilemap;
+
+use super::geomorphometry::{self, GeomorphometryResults};
+use super::params::ErosionParams;
+
+/// Flow accumulation threshold used when scoring eroded terrain (matches `simulate_erosion`)
+const ANALYSIS_THRESHOLD: f32 = 5.0;
+
+/// Pixels of padding between cells in the contact sheet
+const SHEET_PADDING: u32 = 4;
+
+/// An erosion parameter that can be varied along a sweep axis
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SweepParam {
+    HydraulicIterations,
+    DropletErosionRate,
+    DropletDepositRate,
+    DropletEvaporation,
+    DropletInertia,
+    DropletCapacityFactor,
+    GlacialTimesteps,
+    GlaciationTemperature,
+    IceErosionCoefficient,
+    RiverErosionRate,
+    RiverCapacityFactor,
+    RiverMaxErosion,
+    RiverSourceMinAccumulation,
+}
+
+impl SweepParam {
+    /// All sweepable parameters
+    pub fn all() -> &'static [SweepParam] {
+        &[
+            SweepParam::HydraulicIterations,
+            SweepParam::DropletErosionRate,
+            SweepParam::DropletDepositRate,
+            SweepParam::DropletEvaporation,
+            SweepParam::DropletInertia,
+            SweepParam::DropletCapacityFactor,
+            SweepParam::GlacialTimesteps,
+            SweepParam::GlaciationTemperature,
+            SweepParam::IceErosionCoefficient,
+            SweepParam::RiverErosionRate,
+            SweepParam::RiverCapacityFactor,
+            SweepParam::RiverMaxErosion,
+            SweepParam::RiverSourceMinAccumulation,
+        ]
+    }
+
+    /// Name of the parameter, matching the `ErosionParams` field name
+    pub fn name(&self) -> &'static str {
+        match self {
+            SweepParam::HydraulicIterations => "hydraulic_iterations",
+            SweepParam::DropletErosionRate => "droplet_erosion_rate",
+            SweepParam::DropletDepositRate => "droplet_deposit_rate",
+            SweepParam::DropletEvaporation => "droplet_evaporation",
+            SweepParam::DropletInertia => "droplet_inertia",
+            SweepParam::DropletCapacityFactor => "droplet_capacity_factor",
+            SweepParam::GlacialTimesteps => "glacial_timesteps",
+            SweepParam::GlaciationTemperature => "glaciation_temperature",
+            SweepParam::IceErosionCoefficient => "erosion_coefficient",
+            SweepParam::RiverErosionRate => "river_erosion_rate",
+            SweepParam::RiverCapacityFactor => "river_capacity_factor",
+            SweepParam::RiverMaxErosion => "river_max_erosion",
+            SweepParam::RiverSourceMinAccumulation => "river_source_min_accumulation",
+        }
+    }
+
+    /// Look up a parameter by its field name
+    pub fn from_name(name: &str) -> Option<SweepParam> {
+        Self::all().iter().copied().find(|p| p.name() == name)
+    }
+
+    /// Read the current value of this parameter
+    pub fn get(&self, params: &ErosionParams) -> f32 {
+        match self {
+            SweepParam::HydraulicIterations => params.hydraulic_iterations as f32,
+            SweepParam::DropletErosionRate => params.droplet_erosion_rate,
+            SweepParam::DropletDepositRate => params.droplet_deposit_rate,
+            SweepParam::DropletEvaporation => params.droplet_evaporation,
+            SweepParam::DropletInertia => params.droplet_inertia,
+            SweepParam::DropletCapacityFactor => params.droplet_capacity_factor,
+            SweepParam::GlacialTimesteps => params.glacial_timesteps as f32,
+            SweepParam::GlaciationTemperature => params.glaciation_temperature,
+            SweepParam::IceErosionCoefficient => params.erosion_coefficient,
+            SweepParam::RiverErosionRate => params.river_erosion_rate,
+            SweepParam::RiverCapacityFactor => params.river_capacity_factor,
+            SweepParam::RiverMaxErosion => params.river_max_erosion,
+            SweepParam::RiverSourceMinAccumulation => params.river_source_min_accumulation,
+        }
+    }
+
+    /// Write a value into the parameter set (integer fields are rounded)
+    pub fn apply(&self, params: &mut ErosionParams, value: f32) {
+        match self {
+            SweepParam::HydraulicIterations => params.hydraulic_iterations = value.round().max(0.0) as usize,
+            SweepParam::DropletErosionRate => params.droplet_erosion_rate = value,
+            SweepParam::DropletDepositRate => params.droplet_deposit_rate = value,
+            SweepParam::DropletEvaporation => params.droplet_evaporation = value,
+            SweepParam::DropletInertia => params.droplet_inertia = value,
+            SweepParam::DropletCapacityFactor => params.droplet_capacity_factor = value,
+            SweepParam::GlacialTimesteps => params.glacial_timesteps = value.round().max(0.0) as usize,
+            SweepParam::GlaciationTemperature => params.glaciation_temperature = value,
+            SweepParam::IceErosionCoefficient => params.erosion_coefficient = value,
+            SweepParam::RiverErosionRate => params.river_erosion_rate = value,
+            SweepParam::RiverCapacityFactor => params.river_capacity_factor = value,
+            SweepParam::RiverMaxErosion => params.river_max_erosion = value,
+            SweepParam::RiverSourceMinAccumulation => params.river_source_min_accumulation = value,
+        }
+    }
+}
+
+/// One axis of a sweep: a parameter and the values it takes
+#[derive(Clone, Debug)]
+pub struct SweepAxis {
+    pub param: SweepParam,
+    pub values: Vec<f32>,
+}
+
+impl SweepAxis {
+    /// Evenly spaced values from `min` to `max` inclusive
+    pub fn linspace(param: SweepParam, min: f32, max: f32, steps: usize) -> Self {
+        let steps = steps.max(1);
+        let values = if steps == 1 {
+            vec![min]
+        } else {
+            (0..steps)
+                .map(|i| min + (max - min) * i as f32 / (steps - 1) as f32)
+                .collect()
+        };
+        Self { param, values }
+    }
+
+    /// Parse an axis from `name=min,max,steps` (e.g. `droplet_erosion_rate=0.01,0.1,4`)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (name, range) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("expected NAME=MIN,MAX,STEPS, got '{}'", spec))?;
+        let param = SweepParam::from_name(name.trim())
+            .ok_or_else(|| format!("unknown sweep parameter '{}'", name.trim()))?;
+
+        let parts: Vec<&str> = range.split(',').map(|s| s.trim()).collect();
+        if parts.len() != 3 {
+            return Err(format!("expected MIN,MAX,STEPS, got '{}'", range));
+        }
+        let min: f32 = parts[0].parse().map_err(|_| format!("invalid min '{}'", parts[0]))?;
+        let max: f32 = parts[1].parse().map_err(|_| format!("invalid max '{}'", parts[1]))?;
+        let steps: usize = parts[2].parse().map_err(|_| format!("invalid steps '{}'", parts[2]))?;
+        if steps == 0 {
+            return Err("steps must be at least 1".to_string());
+        }
+
+        Ok(Self::linspace(param, min, max, steps))
+    }
+}
+
+/// Configuration for an erosion parameter sweep
+#[derive(Clone, Debug)]
+pub struct SweepConfig {
+    /// Map width for every cell
+    pub width: usize,
+    /// Map height for every cell
+    pub height: usize,
+    /// Seed shared by all cells (same plates and base terrain)
+    pub seed: u64,
+    /// Number of tectonic plates (random if None)
+    pub num_plates: Option<usize>,
+    /// Parameters used for everything not varied by the axes
+    pub base_params: ErosionParams,
+    /// Axis varied along the contact sheet columns
+    pub x_axis: SweepAxis,
+    /// Optional axis varied along the contact sheet rows
+    pub y_axis: Option<SweepAxis>,
+}
+
+/// Result of one sweep cell
+#[derive(Clone)]
+pub struct SweepCell {
+    /// Column index in the grid
+    pub column: usize,
+    /// Row index in the grid
+    pub row: usize,
+    /// Value of the x-axis parameter
+    pub x_value: f32,
+    /// Value of the y-axis parameter (if a second axis was swept)
+    pub y_value: Option<f32>,
+    /// Geomorphometry metrics after erosion
+    pub metrics: GeomorphometryResults,
+    /// Overall realism score (0-100)
+    pub realism_score: f32,
+    /// Eroded heightmap for this cell
+    pub heightmap: Tilemap<f32>,
+}
+
+/// Full sweep output
+pub struct SweepResult {
+    pub x_param: SweepParam,
+    pub y_param: Option<SweepParam>,
+    pub columns: usize,
+    pub rows: usize,
+    pub cells: Vec<SweepCell>,
+}
+
+/// Pre-erosion terrain shared by every evaluation of a sweep or autotune run.
+///
+/// Plates, base heightmap and temperature are generated once from the seed so that
+/// differences between evaluations come only from the erosion parameters.
+pub struct BaseTerrain {
+    pub seed: u64,
+    pub plate_map: Tilemap<PlateId>,
+    pub plates: Vec<Plate>,
+    pub stress_map: Tilemap<f32>,
+    pub heightmap: Tilemap<f32>,
+    pub temperature: Tilemap<f32>,
+}
+
+impl BaseTerrain {
+    /// Generate the shared pre-erosion terrain
+    pub fn generate(width: usize, height: usize, seed: u64, num_plates: Option<usize>) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let (plate_map, plates) = plates::generate_plates(width, height, num_plates, plates::WorldStyle::Random, None, &mut rng);
+        let stress_map = plates::calculate_stress(&plate_map, &plates);
+        let heightmap = heightmap::generate_heightmap(&plate_map, &plates, &stress_map, None, seed);
+        let temperature = climate::generate_temperature(&heightmap, width, height);
+        Self { seed, plate_map, plates, stress_map, heightmap, temperature }
+    }
+
+    /// Erode a copy of the base heightmap and measure it
+    pub fn erode_and_analyze(&self, params: &ErosionParams) -> (Tilemap<f32>, GeomorphometryResults) {
+        let mut params = params.clone();
+        params.enable_analysis = false;
+
+        // Every evaluation starts from the same RNG state so only the parameters differ
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let mut heightmap = self.heightmap.clone();
+        super::simulate_erosion(
+            &mut heightmap,
+            &self.plate_map,
+            &self.plates,
+            &self.stress_map,
+            &self.temperature,
+            &params,
+            &mut rng,
+            self.seed,
+        );
+
+        let metrics = geomorphometry::analyze(&heightmap, ANALYSIS_THRESHOLD);
+        (heightmap, metrics)
+    }
+}
+
+/// Run a parameter sweep.
+///
+/// Each cell erodes its own copy of a shared `BaseTerrain`, so differences between
+/// cells come only from the swept values.
+pub fn run_sweep(config: &SweepConfig) -> SweepResult {
+    let base = BaseTerrain::generate(config.width, config.height, config.seed, config.num_plates);
+
+    let y_values: Vec<Option<f32>> = match &config.y_axis {
+        Some(axis) => axis.values.iter().map(|&v| Some(v)).collect(),
+        None => vec![None],
+    };
+
+    let mut cells = Vec::new();
+    for (row, y_value) in y_values.iter().enumerate() {
+        for (column, &x_value) in config.x_axis.values.iter().enumerate() {
+            let mut params = config.base_params.clone();
+            config.x_axis.param.apply(&mut params, x_value);
+            if let (Some(axis), Some(v)) = (&config.y_axis, y_value) {
+                axis.param.apply(&mut params, *v);
+            }
+
+            println!(
+                "Sweep cell ({}, {}): {}={}{}",
+                column,
+                row,
+                config.x_axis.param.name(),
+                x_value,
+                match (&config.y_axis, y_value) {
+                    (Some(axis), Some(v)) => format!(", {}={}", axis.param.name(), v),
+                    _ => String::new(),
+                }
+            );
+
+            let (heightmap, metrics) = base.erode_and_analyze(&params);
+            let realism_score = metrics.realism_score();
+
+            cells.push(SweepCell {
+                column,
+                row,
+                x_value,
+                y_value: *y_value,
+                metrics,
+                realism_score,
+                heightmap,
+            });
+        }
+    }
+
+    SweepResult {
+        x_param: config.x_axis.param,
+        y_param: config.y_axis.as_ref().map(|a| a.param),
+        columns: config.x_axis.values.len(),
+        rows: y_values.len(),
+        cells,
+    }
+}
+
+impl SweepResult {
+    /// Cell with the highest realism score
+    pub fn best_cell(&self) -> Option<&SweepCell> {
+        self.cells
+            .iter()
+            .max_by(|a, b| a.realism_score.partial_cmp(&b.realism_score).unwrap_or(std::cmp::Ordering::Equal))
+    }
+
+    /// Render the metrics table as CSV (one row per cell)
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        out.push_str("column,row,");
+        out.push_str(self.x_param.name());
+        if let Some(y) = self.y_param {
+            out.push(',');
+            out.push_str(y.name());
+        }
+        out.push_str(",realism_score,bifurcation_ratio,drainage_density,hacks_law_exponent,concavity_index,\
+fractal_dimension,sinuosity_index,pit_count,hypsometric_integral,morans_i,slope_skewness,\
+surface_roughness,knickpoint_density,relative_relief,spectral_slope\n");
+
+        for cell in &self.cells {
+            let m = &cell.metrics;
+            out.push_str(&format!("{},{},{}", cell.column, cell.row, cell.x_value));
+            if let Some(v) = cell.y_value {
+                out.push_str(&format!(",{}", v));
+            }
+            out.push_str(&format!(
+                ",{:.2},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{},{:.4},{:.4},{:.4},{:.4},{:.5},{:.2},{:.3}\n",
+                cell.realism_score,
+                m.bifurcation_ratio,
+                m.drainage_density,
+                m.hacks_law_exponent,
+                m.concavity_index,
+                m.fractal_dimension,
+                m.sinuosity_index,
+                m.pit_count,
+                m.hypsometric_integral,
+                m.morans_i,
+                m.slope_skewness,
+                m.surface_roughness,
+                m.knickpoint_density,
+                m.relative_relief,
+                m.spectral_slope,
+            ));
+        }
+
+        out
+    }
+
+    /// Write the metrics table to a CSV file
+    pub fn write_csv(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.to_csv())
+    }
+
+    /// Export a contact sheet: every cell's heightmap in a grid, columns following
+    /// the x-axis and rows the y-axis.
+    pub fn export_contact_sheet(&self, path: &str) -> Result<(), image::ImageError> {
+        use image::{Rgb, RgbImage};
+
+        let (cell_w, cell_h) = self
+            .cells
+            .first()
+            .map(|c| (c.heightmap.width as u32, c.heightmap.height as u32))
+            .unwrap_or((1, 1));
+
+        let sheet_w = self.columns as u32 * (cell_w + SHEET_PADDING) + SHEET_PADDING;
+        let sheet_h = self.rows as u32 * (cell_h + SHEET_PADDING) + SHEET_PADDING;
+        let mut img = RgbImage::from_pixel(sheet_w, sheet_h, Rgb([32, 32, 32]));
+
+        for cell in &self.cells {
+            let ox = SHEET_PADDING + cell.column as u32 * (cell_w + SHEET_PADDING);
+            let oy = SHEET_PADDING + cell.row as u32 * (cell_h + SHEET_PADDING);
+            for (x, y, &h) in cell.heightmap.iter() {
+                let (r, g, b) = crate::ascii::height_color(h);
+                img.put_pixel(ox + x as u32, oy + y as u32, Rgb([r, g, b]));
+            }
+        }
+
+        img.save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_param_names_round_trip() {
+        for param in SweepParam::all() {
+            assert_eq!(SweepParam::from_name(param.name()), Some(*param));
+        }
+        assert_eq!(SweepParam::from_name("not_a_param"), None);
+    }
+
+    #[test]
+    fn test_apply_and_get() {
+        let mut params = ErosionParams::default();
+        SweepParam::DropletErosionRate.apply(&mut params, 0.2);
+        assert_eq!(SweepParam::DropletErosionRate.get(&params), 0.2);
+        SweepParam::GlacialTimesteps.apply(&mut params, 42.6);
+        assert_eq!(params.glacial_timesteps, 43);
+    }
+
+    #[test]
+    fn test_axis_parse() {
+        let axis = SweepAxis::parse("river_erosion_rate=0.0,1.0,5").unwrap();
+        assert_eq!(axis.param, SweepParam::RiverErosionRate);
+        assert_eq!(axis.values, vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+
+        assert!(SweepAxis::parse("river_erosion_rate=0.0,1.0").is_err());
+        assert!(SweepAxis::parse("bogus=0,1,2").is_err());
+        assert!(SweepAxis::parse("river_erosion_rate=0,1,0").is_err());
+    }
+
+    #[test]
+    fn test_small_sweep_grid() {
+        let base_params = ErosionParams {
+            hydraulic_iterations: 200,
+            glacial_timesteps: 2,
+            use_gpu: false,
+            ..ErosionParams::default()
+        };
+        let config = SweepConfig {
+            width: 48,
+            height: 24,
+            seed: 7,
+            num_plates: Some(4),
+            base_params,
+            x_axis: SweepAxis::linspace(SweepParam::RiverErosionRate, 0.2, 1.0, 2),
+            y_axis: Some(SweepAxis::linspace(SweepParam::GlacialTimesteps, 1.0, 3.0, 2)),
+        };
+
+        let result = run_sweep(&config);
+        assert_eq!(result.columns, 2);
+        assert_eq!(result.rows, 2);
+        assert_eq!(result.cells.len(), 4);
+        assert!(result.best_cell().is_some());
+
+        let csv = result.to_csv();
+        assert_eq!(csv.lines().count(), 5);
+        assert!(csv.starts_with("column,row,river_erosion_rate,glacial_timesteps,realism_score"));
+    }
+}
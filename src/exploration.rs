@@ -0,0 +1,619 @@
+//! Exploration and cartography
+//!
+//! Simulates how each faction charted the world over recorded history:
+//! - The land and waters around a settlement are charted when it is founded
+//! - Expeditions set out from the faction's settlements: coastal voyages hug the
+//!   shore, overland treks strike out towards land the faction hasn't charted yet.
+//!   An expedition is led by one of the faction's explorers when one is alive, and
+//!   names the straits, capes and islands it is first to chart after its leader
+//! - Charts change hands: both partners copy each other's maps when a trade route
+//!   opens between them, and a conqueror seizes the maps of the people it conquers
+//!
+//! `ExplorationRecord` keeps the year each faction first charted each tile, which
+//! the known-world maps use for their fog of war, and `apply_exploration_history`
+//! writes the expeditions and discoveries into the chronicle.
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::history::types::Year;
+use crate::history::{EventType, FactionId, HeroId, HeroRole, HistoricalEvent, NameGenerator, WorldHistory};
+use crate::tilemap::Tilemap;
+
+/// Tiles around a new settlement its founders chart
+const HOME_SIGHT: i32 = 5;
+
+/// Tiles an expedition charts on either side of its route
+const EXPEDITION_SIGHT: i32 = 3;
+
+/// Largest landmass (tiles) counted as an island
+const ISLAND_MAX_TILES: usize = 40;
+
+/// Tiles around a named discovery that can't be named again
+const DISCOVERY_SPACING: i32 = 6;
+
+/// Parameters for the exploration simulation
+#[derive(Clone, Debug)]
+pub struct ExplorationConfig {
+    /// Years of a faction's existence per expedition it sends out
+    pub expedition_interval: i32,
+    /// Most expeditions one faction sends out
+    pub max_expeditions: usize,
+    /// Steps (tiles) an expedition travels
+    pub expedition_length: usize,
+    /// Most places one expedition names
+    pub max_discoveries: usize,
+}
+
+impl Default for ExplorationConfig {
+    fn default() -> Self {
+        Self {
+            expedition_interval: 120,
+            max_expeditions: 8,
+            expedition_length: 60,
+            max_discoveries: 3,
+        }
+    }
+}
+
+/// How an expedition travels
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ExpeditionKind {
+    /// By ship along the coast
+    Coastal,
+    /// On foot across the land
+    Overland,
+}
+
+/// A kind of named discovery
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DiscoveryKind {
+    /// Narrow channel of sea between two shores
+    Strait,
+    /// Headland jutting into the sea
+    Cape,
+    /// Small landmass
+    Island,
+}
+
+impl DiscoveryKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiscoveryKind::Strait => "strait",
+            DiscoveryKind::Cape => "cape",
+            DiscoveryKind::Island => "island",
+        }
+    }
+
+    /// "the Straits of Aldric", "Cape Aldric", "Aldric's Isle"
+    fn place_name(&self, root: &str) -> String {
+        match self {
+            DiscoveryKind::Strait => format!("the Straits of {}", root),
+            DiscoveryKind::Cape => format!("Cape {}", root),
+            DiscoveryKind::Island => format!("{}'s Isle", root),
+        }
+    }
+}
+
+/// One expedition
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Expedition {
+    pub faction: FactionId,
+    /// Explorer who led it (None for an expedition led by no one of note)
+    pub leader: Option<HeroId>,
+    pub year: Year,
+    pub kind: ExpeditionKind,
+    /// Tiles travelled, starting at the home settlement
+    pub route: Vec<(usize, usize)>,
+    /// Name of the settlement it set out from
+    pub origin: String,
+    /// Tiles the faction charted for the first time
+    pub newly_charted: usize,
+}
+
+/// A named place first charted by an expedition
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Discovery {
+    pub name: String,
+    pub kind: DiscoveryKind,
+    pub x: usize,
+    pub y: usize,
+    pub year: Year,
+    pub faction: FactionId,
+    pub explorer: Option<HeroId>,
+    /// Index into `ExplorationRecord::expeditions`
+    pub expedition: usize,
+}
+
+/// The tiles one faction has charted
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct FactionChart {
+    pub faction: FactionId,
+    /// Year each tile was first charted (None = never)
+    pub charted: Tilemap<Option<Year>>,
+}
+
+/// How the world was charted over history
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct ExplorationRecord {
+    pub charts: Vec<FactionChart>,
+    pub expeditions: Vec<Expedition>,
+    pub discoveries: Vec<Discovery>,
+}
+
+impl ExplorationRecord {
+    pub fn chart(&self, faction: FactionId) -> Option<&FactionChart> {
+        self.charts.iter().find(|c| c.faction == faction)
+    }
+
+    /// Year `faction` first charted a tile, if it ever did
+    pub fn charted_by(&self, faction: FactionId, x: usize, y: usize) -> Option<Year> {
+        self.chart(faction).and_then(|c| *c.charted.get(x, y))
+    }
+
+    /// Short description for tile info panels
+    pub fn describe(&self, x: usize, y: usize) -> Option<String> {
+        self.discoveries
+            .iter()
+            .find(|d| d.x == x && d.y == y)
+            .map(|d| format!("{} (charted in year {})", d.name, d.year.0))
+    }
+}
+
+/// Something that changes what a faction has charted, in chronological order
+enum Happening {
+    /// A settlement is founded (or taken) and its surroundings charted
+    Settle { faction: FactionId, x: usize, y: usize },
+    Expedition { faction: FactionId },
+    /// `from`'s charts are copied into `to`'s
+    Share { from: FactionId, to: FactionId },
+}
+
+/// Simulate each faction's exploration over the history's eras
+pub fn simulate_exploration(
+    history: &WorldHistory,
+    heightmap: &Tilemap<f32>,
+    config: &ExplorationConfig,
+    seed: u64,
+) -> ExplorationRecord {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0xE4A10E));
+    let names = NameGenerator::new(seed.wrapping_add(0xE4A10E));
+    let (width, height) = (heightmap.width, heightmap.height);
+    let end = history.timeline.eras.last().map_or(Year(0), |e| e.end);
+
+    let mut factions: Vec<_> = history.factions.all().collect();
+    factions.sort_by_key(|f| f.id.0);
+    let mut settlements: Vec<_> = history.territories.settlements.values().collect();
+    settlements.sort_by_key(|s| s.id.0);
+
+    // Everything that changes a faction's charts, in the order it happened
+    let mut happenings: Vec<(Year, Happening)> = Vec::new();
+    for s in &settlements {
+        happenings.push((s.founded, Happening::Settle { faction: s.original_faction, x: s.x, y: s.y }));
+        for &(occupier, since, _) in &s.occupations {
+            if occupier != s.original_faction {
+                happenings.push((since, Happening::Settle { faction: occupier, x: s.x, y: s.y }));
+                happenings.push((since, Happening::Share { from: s.original_faction, to: occupier }));
+            }
+        }
+    }
+    for faction in &factions {
+        let last = faction.collapsed.unwrap_or(end);
+        let count = ((last.0 - faction.founded.0) / config.expedition_interval.max(1)).clamp(0, config.max_expeditions as i32);
+        for i in 0..count {
+            let offset = (i as f32 + rng.gen_range(0.2..0.8)) * config.expedition_interval as f32;
+            happenings.push((Year(faction.founded.0 + offset as i32), Happening::Expedition { faction: faction.id }));
+        }
+    }
+    let mut routes: Vec<_> = history.trade.routes.values().collect();
+    routes.sort_by_key(|r| r.id.0);
+    for route in routes {
+        let owner = |(x, y): (usize, usize)| {
+            history.settlement_at(x, y).map(|s| s.current_faction.unwrap_or(s.original_faction))
+        };
+        if let (Some(a), Some(b)) = (owner(route.start), owner(route.end)) {
+            if a != b {
+                happenings.push((route.established, Happening::Share { from: a, to: b }));
+                happenings.push((route.established, Happening::Share { from: b, to: a }));
+            }
+        }
+    }
+    // Settling and exploring come before the maps of that year change hands
+    happenings.sort_by_key(|(year, h)| (*year, matches!(h, Happening::Share { .. })));
+
+    let mut record = ExplorationRecord {
+        charts: factions
+            .iter()
+            .map(|f| FactionChart { faction: f.id, charted: Tilemap::new_with(width, height, None) })
+            .collect(),
+        ..Default::default()
+    };
+    let index_of = |id: FactionId| factions.iter().position(|f| f.id == id);
+    let mut anyone_charted = Tilemap::new_with(width, height, false);
+    let mut named = Tilemap::new_with(width, height, false);
+
+    for (year, happening) in happenings {
+        match happening {
+            Happening::Settle { faction, x, y } => {
+                if let Some(i) = index_of(faction) {
+                    chart_around(&mut record.charts[i].charted, &mut anyone_charted, x, y, HOME_SIGHT, year);
+                }
+            }
+            Happening::Share { from, to } => {
+                if let (Some(from), Some(to)) = (index_of(from), index_of(to)) {
+                    let known: Vec<_> = record.charts[from]
+                        .charted
+                        .iter()
+                        .filter(|(_, _, c)| c.is_some_and(|c| c <= year))
+                        .map(|(x, y, _)| (x, y))
+                        .collect();
+                    let chart = &mut record.charts[to].charted;
+                    for (x, y) in known {
+                        if chart.get(x, y).is_none() {
+                            chart.set(x, y, Some(year));
+                        }
+                    }
+                }
+            }
+            Happening::Expedition { faction } => {
+                let Some(i) = index_of(faction) else {
+                    continue;
+                };
+                let homes: Vec<_> = settlements
+                    .iter()
+                    .filter(|s| {
+                        s.current_faction.unwrap_or(s.original_faction) == faction
+                            && s.founded <= year
+                            && s.abandoned.is_none_or(|a| a > year)
+                    })
+                    .collect();
+                let Some(home) = homes.choose(&mut rng) else {
+                    continue;
+                };
+                let mut explorers: Vec<_> = history
+                    .heroes
+                    .heroes_of_faction(faction)
+                    .into_iter()
+                    .filter(|h| h.role == HeroRole::Explorer && h.alive_at(year))
+                    .collect();
+                explorers.sort_by_key(|h| h.id.0);
+                let leader = explorers.choose(&mut rng).copied();
+                let root = leader.map_or_else(|| names.settlement_name(factions[i].species, &mut rng), |h| h.name.clone());
+
+                let expedition_index = record.expeditions.len();
+                let chart = &mut record.charts[i].charted;
+                let (kind, route) = plan_route(heightmap, chart, (home.x, home.y), config.expedition_length, &mut rng);
+                let mut newly_charted = 0;
+                let mut found = 0;
+                for &(x, y) in &route {
+                    for (cx, cy) in chart_around(chart, &mut anyone_charted, x, y, EXPEDITION_SIGHT, year) {
+                        newly_charted += 1;
+                        if found >= config.max_discoveries || *named.get(cx, cy) {
+                            continue;
+                        }
+                        let Some(kind) = classify(heightmap, cx, cy) else {
+                            continue;
+                        };
+                        mark_named(&mut named, cx, cy);
+                        record.discoveries.push(Discovery {
+                            name: kind.place_name(&root),
+                            kind,
+                            x: cx,
+                            y: cy,
+                            year,
+                            faction,
+                            explorer: leader.map(|h| h.id),
+                            expedition: expedition_index,
+                        });
+                        found += 1;
+                    }
+                }
+                record.expeditions.push(Expedition {
+                    faction,
+                    leader: leader.map(|h| h.id),
+                    year,
+                    kind,
+                    route,
+                    origin: home.name.clone(),
+                    newly_charted,
+                });
+            }
+        }
+    }
+
+    record
+}
+
+/// Chart every tile within `radius` of (cx, cy) not yet on the chart. Returns the
+/// tiles nobody had charted before.
+fn chart_around(
+    chart: &mut Tilemap<Option<Year>>,
+    anyone_charted: &mut Tilemap<bool>,
+    cx: usize,
+    cy: usize,
+    radius: i32,
+    year: Year,
+) -> Vec<(usize, usize)> {
+    let mut first = Vec::new();
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            let y = cy as i32 + dy;
+            if dx * dx + dy * dy > radius * radius || y < 0 || y >= chart.height as i32 {
+                continue;
+            }
+            let (x, y) = chart.wrap_coords(cx as i32 + dx, y);
+            if chart.get(x, y).is_none() {
+                chart.set(x, y, Some(year));
+            }
+            if !*anyone_charted.get(x, y) {
+                anyone_charted.set(x, y, true);
+                first.push((x, y));
+            }
+        }
+    }
+    first
+}
+
+fn is_sea(heightmap: &Tilemap<f32>, x: usize, y: usize) -> bool {
+    *heightmap.get(x, y) <= 0.0
+}
+
+/// Sea tile next to land
+fn is_coastal_water(heightmap: &Tilemap<f32>, x: usize, y: usize) -> bool {
+    is_sea(heightmap, x, y) && heightmap.neighbors(x, y).into_iter().any(|(nx, ny)| !is_sea(heightmap, nx, ny))
+}
+
+/// Route of an expedition from `home`: along the coast when the settlement is
+/// near the sea, otherwise overland. Each step favours tiles the faction hasn't
+/// charted and keeps roughly to its heading.
+fn plan_route(
+    heightmap: &Tilemap<f32>,
+    chart: &Tilemap<Option<Year>>,
+    home: (usize, usize),
+    length: usize,
+    rng: &mut ChaCha8Rng,
+) -> (ExpeditionKind, Vec<(usize, usize)>) {
+    let harbour = (-2i32..=2)
+        .flat_map(|dy| (-2i32..=2).map(move |dx| (dx, dy)))
+        .filter(|&(_, dy)| {
+            let y = home.1 as i32 + dy;
+            y >= 0 && y < heightmap.height as i32
+        })
+        .map(|(dx, dy)| heightmap.wrap_coords(home.0 as i32 + dx, home.1 as i32 + dy))
+        .find(|&(x, y)| is_coastal_water(heightmap, x, y));
+    let (kind, start) = match harbour {
+        Some(tile) => (ExpeditionKind::Coastal, tile),
+        None => (ExpeditionKind::Overland, home),
+    };
+    let passable = |x: usize, y: usize| match kind {
+        ExpeditionKind::Coastal => is_coastal_water(heightmap, x, y),
+        ExpeditionKind::Overland => !is_sea(heightmap, x, y),
+    };
+
+    let mut route = vec![home];
+    if start != home {
+        route.push(start);
+    }
+    let mut heading = (rng.gen_range(-1.0f32..1.0), rng.gen_range(-1.0f32..1.0));
+    let (mut x, mut y) = start;
+    for _ in 0..length {
+        let next = heightmap
+            .neighbors_8(x, y)
+            .into_iter()
+            .filter(|&(nx, ny)| passable(nx, ny) && !route.contains(&(nx, ny)))
+            .map(|(nx, ny)| {
+                let (dx, dy) = (wrapped_delta(x, nx, heightmap.width), ny as f32 - y as f32);
+                let unknown = if chart.get(nx, ny).is_none() { 2.0 } else { 0.0 };
+                let score = unknown + dx * heading.0 + dy * heading.1 + rng.gen_range(0.0..0.5);
+                (score, (nx, ny), (dx, dy))
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        let Some((_, tile, step)) = next else {
+            break;
+        };
+        heading = (heading.0 * 0.7 + step.0 * 0.3, heading.1 * 0.7 + step.1 * 0.3);
+        (x, y) = tile;
+        route.push(tile);
+    }
+    (kind, route)
+}
+
+/// Signed x step from `from` to `to` across the east-west seam
+fn wrapped_delta(from: usize, to: usize, width: usize) -> f32 {
+    let d = to as i32 - from as i32;
+    let w = width as i32;
+    (if d > w / 2 { d - w } else if d < -w / 2 { d + w } else { d }) as f32
+}
+
+/// What, if anything, is worth naming at a tile
+fn classify(heightmap: &Tilemap<f32>, x: usize, y: usize) -> Option<DiscoveryKind> {
+    let land = |dx: i32, dy: i32| {
+        let ny = y as i32 + dy;
+        if ny < 0 || ny >= heightmap.height as i32 {
+            return false;
+        }
+        let (nx, ny) = heightmap.wrap_coords(x as i32 + dx, ny);
+        !is_sea(heightmap, nx, ny)
+    };
+
+    if is_sea(heightmap, x, y) {
+        // Water running between two shores at most two tiles off
+        let across_x = (land(-1, 0) || land(-2, 0)) && (land(1, 0) || land(2, 0)) && !land(0, -1) && !land(0, 1);
+        let across_y = (land(0, -1) || land(0, -2)) && (land(0, 1) || land(0, 2)) && !land(-1, 0) && !land(1, 0);
+        return (across_x || across_y).then_some(DiscoveryKind::Strait);
+    }
+    if is_island(heightmap, x, y) {
+        return Some(DiscoveryKind::Island);
+    }
+    let wet = heightmap.neighbors_8(x, y).into_iter().filter(|&(nx, ny)| is_sea(heightmap, nx, ny)).count();
+    (wet >= 5).then_some(DiscoveryKind::Cape)
+}
+
+/// Whether a land tile belongs to a landmass of at most `ISLAND_MAX_TILES`
+fn is_island(heightmap: &Tilemap<f32>, x: usize, y: usize) -> bool {
+    let mut seen = vec![(x, y)];
+    let mut frontier = vec![(x, y)];
+    while let Some((cx, cy)) = frontier.pop() {
+        for (nx, ny) in heightmap.neighbors(cx, cy) {
+            if !is_sea(heightmap, nx, ny) && !seen.contains(&(nx, ny)) {
+                if seen.len() >= ISLAND_MAX_TILES {
+                    return false;
+                }
+                seen.push((nx, ny));
+                frontier.push((nx, ny));
+            }
+        }
+    }
+    true
+}
+
+fn mark_named(named: &mut Tilemap<bool>, cx: usize, cy: usize) {
+    for dy in -DISCOVERY_SPACING..=DISCOVERY_SPACING {
+        for dx in -DISCOVERY_SPACING..=DISCOVERY_SPACING {
+            let y = cy as i32 + dy;
+            if y >= 0 && y < named.height as i32 {
+                let (x, y) = named.wrap_coords(cx as i32 + dx, y);
+                named.set(x, y, true);
+            }
+        }
+    }
+}
+
+// =============================================================================
+// CHRONICLE
+// =============================================================================
+
+/// Record expeditions and discoveries in the timeline and credit their leaders.
+/// Returns the number of events added.
+pub fn apply_exploration_history(history: &mut WorldHistory, record: &ExplorationRecord) -> usize {
+    let mut added = 0;
+    let mut expedition_events = Vec::with_capacity(record.expeditions.len());
+
+    for expedition in &record.expeditions {
+        let leader = expedition.leader.and_then(|id| history.heroes.get(id)).map(|h| (h.name.clone(), h.full_name()));
+        let how = match expedition.kind {
+            ExpeditionKind::Coastal => "sailed the coast from",
+            ExpeditionKind::Overland => "set out overland from",
+        };
+        let (name, description) = match leader {
+            Some((name, full_name)) => (
+                format!("The {} Expedition", name),
+                format!("{} {} {}, charting {} new tiles", full_name, how, expedition.origin, expedition.newly_charted),
+            ),
+            None => (
+                format!("The {} Expedition", expedition.origin),
+                format!("An expedition {} {}, charting {} new tiles", how, expedition.origin, expedition.newly_charted),
+            ),
+        };
+        let id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id,
+            year: expedition.year,
+            event_type: EventType::Expedition,
+            faction: Some(expedition.faction),
+            other_faction: None,
+            location: expedition.route.first().copied(),
+            settlement: None,
+            name,
+            description,
+            casualties: 0,
+            has_evidence: EventType::Expedition.leaves_evidence(),
+        });
+        if let Some(hero) = expedition.leader.and_then(|id| history.heroes.get_mut(id)) {
+            hero.achievements.push(id);
+            hero.fame += 5;
+        }
+        expedition_events.push(id);
+        added += 1;
+    }
+
+    for discovery in &record.discoveries {
+        let expedition = &history.timeline.events[&expedition_events[discovery.expedition]];
+        let description = format!("First charted by {}", expedition.name.trim_start_matches("The "));
+        let id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id,
+            year: discovery.year,
+            event_type: EventType::PlaceDiscovered,
+            faction: Some(discovery.faction),
+            other_faction: None,
+            location: Some((discovery.x, discovery.y)),
+            settlement: None,
+            name: discovery.name.clone(),
+            description,
+            casualties: 0,
+            has_evidence: EventType::PlaceDiscovered.leaves_evidence(),
+        });
+        if let Some(hero) = discovery.explorer.and_then(|id| history.heroes.get_mut(id)) {
+            hero.achievements.push(id);
+            hero.fame += 10;
+        }
+        added += 1;
+    }
+
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_coastal_features() {
+        // A continent in the west, a channel two tiles wide, a long peninsula
+        // reaching into open sea, and a lone islet
+        let mut heightmap = Tilemap::new_with(48, 24, -100.0f32);
+        for y in 0..24 {
+            for x in 0..20 {
+                heightmap.set(x, y, 100.0);
+            }
+            heightmap.set(22, y, 100.0);
+            heightmap.set(23, y, 100.0);
+        }
+        for x in 24..30 {
+            heightmap.set(x, 12, 100.0);
+        }
+        heightmap.set(40, 5, 100.0);
+        heightmap.set(41, 5, 100.0);
+
+        assert_eq!(classify(&heightmap, 21, 6), Some(DiscoveryKind::Strait));
+        assert_eq!(classify(&heightmap, 29, 12), Some(DiscoveryKind::Cape));
+        assert_eq!(classify(&heightmap, 40, 5), Some(DiscoveryKind::Island));
+        assert_eq!(classify(&heightmap, 5, 5), None);
+        assert_eq!(classify(&heightmap, 35, 20), None);
+        assert_eq!(wrapped_delta(47, 0, 48), 1.0);
+    }
+
+    #[test]
+    fn test_exploration_charts_and_chronicles() {
+        let world = crate::world::generate_world(96, 48, 7);
+        let mut history = world.history.clone().unwrap();
+        let record = simulate_exploration(&history, &world.heightmap, &ExplorationConfig::default(), 7);
+        assert!(!record.expeditions.is_empty());
+
+        // Founders chart their home, expeditions chart their routes
+        for s in history.territories.settlements.values() {
+            let founded = record.charted_by(s.original_faction, s.x, s.y).unwrap();
+            assert!(founded <= s.founded);
+        }
+        for expedition in &record.expeditions {
+            for &(x, y) in &expedition.route {
+                assert!(record.charted_by(expedition.faction, x, y).is_some_and(|c| c <= expedition.year));
+            }
+        }
+        for d in &record.discoveries {
+            assert_eq!(record.charted_by(d.faction, d.x, d.y), Some(d.year));
+            assert!(record.describe(d.x, d.y).is_some());
+        }
+
+        let before = history.timeline.events.len();
+        let added = apply_exploration_history(&mut history, &record);
+        assert_eq!(added, record.expeditions.len() + record.discoveries.len());
+        assert_eq!(history.timeline.events.len(), before + added);
+        for expedition in record.expeditions.iter().filter(|e| e.leader.is_some()) {
+            let hero = history.heroes.get(expedition.leader.unwrap()).unwrap();
+            assert!(hero.achievements.iter().any(|id| history.timeline.events[id].event_type == EventType::Expedition));
+        }
+    }
+}
@@ -5,6 +5,7 @@
 
 use std::io::{self, stdout};
 use std::error::Error;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use crossterm::{
@@ -19,11 +20,16 @@ use ratatui::{
 };
 
 use crate::ascii::{biome_char, height_color, temperature_color, moisture_color, stress_color};
+use crate::budget::ResourceBudget;
 use crate::multiscale::{
     ChunkCache, LocalCoord, ScaleLevel,
     LocalChunk, LocalTile, LocalTerrain, LocalFeature,
     LOCAL_SIZE,
 };
+use crate::hot_reload::HotReload;
+use crate::history::tile_story::TileStory;
+use crate::names::PlaceKind;
+use crate::succession::SuccessionConfig;
 use crate::world::{WorldData, generate_world};
 use crate::zlevel::{self, ZTile, z_to_height, z_to_height_ceiling, z_level_description};
 
@@ -37,6 +43,57 @@ struct Viewport {
     height: usize,
 }
 
+impl Viewport {
+    /// Check if a world tile is visible (x wraps around the map)
+    fn contains(&self, x: usize, y: usize, map_width: usize) -> bool {
+        let dx = (x + map_width - self.x % map_width) % map_width;
+        dx < self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Directory where per-world bookmark files are stored
+const BOOKMARK_DIR: &str = "saves/bookmarks";
+
+/// Minimap size in terminal cells (including border)
+const MINIMAP_WIDTH: u16 = 34;
+const MINIMAP_HEIGHT: u16 = 18;
+
+/// A named world location
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Bookmark {
+    name: String,
+    x: usize,
+    y: usize,
+}
+
+/// Bookmarked locations for one world seed, persisted as JSON
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+struct Bookmarks {
+    seed: u64,
+    locations: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    fn path(dir: &Path, seed: u64) -> PathBuf {
+        dir.join(format!("world_{}.json", seed))
+    }
+
+    /// Load bookmarks for a seed (empty if none have been saved)
+    fn load(dir: &Path, seed: u64) -> Self {
+        std::fs::read_to_string(Self::path(dir, seed))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or(Bookmarks { seed, locations: Vec::new() })
+    }
+
+    fn save(&self, dir: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        std::fs::write(Self::path(dir, self.seed), json)
+    }
+}
+
 /// View mode for the map display
 #[derive(Clone, Copy, PartialEq)]
 enum ViewMode {
@@ -144,22 +201,32 @@ struct Explorer {
     local_chunks: [[Option<LocalChunk>; 3]; 3],
     /// Verification report to display (press Y to generate)
     verification_report: Option<String>,
+    /// History of the tile under the cursor (press I to show)
+    tile_story: Option<TileStory>,
+    /// Show the corner minimap
+    show_minimap: bool,
+    /// Saved locations for this world seed
+    bookmarks: Bookmarks,
+    /// Map area from the last frame (for mouse hit-testing)
+    map_area: Rect,
+    /// Last mouse position while dragging, and whether the mouse moved
+    drag_last: Option<(u16, u16)>,
+    dragged: bool,
+    /// Outcome of the last tuning-file reload (kept in the status bar)
+    reload_status: Option<String>,
 }
 
 impl Explorer {
-    fn new(world: WorldData) -> Self {
+    fn new(world: WorldData, budget: &ResourceBudget) -> Self {
         let cursor_x = world.heightmap.width / 2;
         let cursor_y = world.heightmap.height / 2;
         // Start at surface level at the cursor position
         let cursor_z = *world.surface_z.get(cursor_x, cursor_y);
 
-        // Create chunk cache with persistence enabled
+        // Create chunk cache with persistence enabled, as large as the budget allows
         // Chunks are saved to "saves/chunks/world_{seed}/" directory
-        let chunk_cache = ChunkCache::with_persistence(
-            "saves/chunks",
-            world.seed,
-            super::multiscale::DEFAULT_LOCAL_CACHE_SIZE,
-        );
+        let chunk_cache = ChunkCache::with_persistence("saves/chunks", world.seed, budget.local_cache_size());
+        let bookmarks = Bookmarks::load(Path::new(BOOKMARK_DIR), world.seed);
 
         Explorer {
             world,
@@ -181,6 +248,25 @@ impl Explorer {
                 [None, None, None],
             ],
             verification_report: None,
+            tile_story: None,
+            show_minimap: true,
+            bookmarks,
+            map_area: Rect::default(),
+            drag_last: None,
+            dragged: false,
+            reload_status: None,
+        }
+    }
+
+    /// Gather the history of the tile under the cursor
+    fn show_tile_story(&mut self) {
+        let story = self.world.history.as_ref()
+            .map(|h| h.tile_story(self.cursor_x, self.cursor_y))
+            .unwrap_or_default();
+        if story.is_empty() {
+            self.message = Some("Nothing of note ever happened here".to_string());
+        } else {
+            self.tile_story = Some(story);
         }
     }
 
@@ -203,6 +289,26 @@ impl Explorer {
         ));
     }
 
+    /// Pick up a hot reload: drop local chunks generated from the old world
+    fn after_reload(&mut self, result: Result<String, String>) {
+        match result {
+            Ok(status) => {
+                if let Err(e) = self.chunk_cache.clear_persisted() {
+                    self.message = Some(format!("Failed to clear saved chunks: {}", e));
+                }
+                self.verification_report = None;
+                match self.scale_mode {
+                    ScaleMode::Local { world_x, world_y, .. } => self.load_local_chunks(world_x, world_y),
+                    ScaleMode::World { .. } => {
+                        self.cursor_z = *self.world.surface_z.get(self.cursor_x, self.cursor_y);
+                    }
+                }
+                self.reload_status = Some(status);
+            }
+            Err(e) => self.reload_status = Some(format!("Reload failed: {}", e)),
+        }
+    }
+
     /// Load all 9 chunks in a 3x3 grid around the given world coordinates.
     ///
     /// Uses the chunk cache with boundary conditions to ensure seamless edges
@@ -300,6 +406,7 @@ impl Explorer {
         self.cursor_y = height / 2;
         self.cursor_z = *self.world.surface_z.get(self.cursor_x, self.cursor_y);
         self.zoom = 1;
+        self.bookmarks = Bookmarks::load(Path::new(BOOKMARK_DIR), new_seed);
 
         self.message = Some(format!("New world generated! Seed: {}", new_seed));
     }
@@ -363,10 +470,14 @@ impl Explorer {
                     ZTile::StoneWall | ZTile::BrickWall | ZTile::WoodWall => " [BUILDING]",
                     _ => "",
                 };
+                let magma_warning = self.world.geothermal.as_ref()
+                    .and_then(|g| g.magma_warning(self.cursor_x, self.cursor_y))
+                    .map(|w| format!(" | WARNING: {}", w))
+                    .unwrap_or_default();
 
                 self.message = Some(format!(
-                    "Embarked at ({}, {}) - {:?} | Z:{}{}",
-                    self.cursor_x, self.cursor_y, biome, spawn_z, structure_info
+                    "Embarked at ({}, {}) - {:?} | Z:{}{}{}",
+                    self.cursor_x, self.cursor_y, biome, spawn_z, structure_info, magma_warning
                 ));
             }
             ScaleMode::Local { .. } => {
@@ -494,7 +605,11 @@ impl Explorer {
             self.local_cursor_z = preserved_z;
 
             let biome = self.world.biomes.get(wx, wy);
-            self.message = Some(format!("Entered world tile ({}, {}) at z={} - {:?}", wx, wy, preserved_z, biome));
+            let flavor = self.world.history.as_ref()
+                .and_then(|h| h.tile_story(wx, wy).flavor())
+                .map(|s| format!(" | {}", s))
+                .unwrap_or_default();
+            self.message = Some(format!("Entered world tile ({}, {}) at z={} - {:?}{}", wx, wy, preserved_z, biome, flavor));
         }
     }
 
@@ -555,6 +670,83 @@ impl Explorer {
         } else {
             String::new()
         };
+        let magic_str = self.world.magic.as_ref()
+            .and_then(|m| m.describe(x, y))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let polar_str = self.world.polar.as_ref()
+            .and_then(|p| p.describe(x, y))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let landform_str = self.world.landforms.as_ref()
+            .and_then(|l| l.describe(x, y))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let dune_str = self.world.dunes.as_ref()
+            .and_then(|d| d.describe(x, y))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let loess_str = self.world.loess.as_ref()
+            .and_then(|l| l.describe(x, y))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let discovery_str = self.world.exploration.as_ref()
+            .and_then(|r| r.describe(x, y))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let seismic_str = self.world.seismic.as_ref()
+            .and_then(|s| s.describe(x, y))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let slope_str = self.world.mass_wasting.as_ref()
+            .and_then(|m| m.describe(x, y))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let wave_str = self.world.waves.as_ref()
+            .and_then(|w| w.describe(x, y))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let succession_str = self.world.succession.as_ref()
+            .and_then(|s| s.describe(x, y, &SuccessionConfig::default()))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let flora_str = self.world.flora.as_ref()
+            .and_then(|f| f.describe(x, y))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let cave_str = self.world.cave_biomes.as_ref()
+            .and_then(|c| c.describe(x, y, self.cursor_z))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let conduit_str = self.world.underground_rivers.as_ref()
+            .and_then(|u| u.describe(x, y))
+            .map(|s| format!(" | {}", s))
+            .unwrap_or_default();
+        let supply_str = self.world.history.as_ref()
+            .and_then(|h| h.settlement_at(x, y).and_then(|s| h.territories.supply.get(&s.id)))
+            .map(|s| format!(" | {}", s.describe()))
+            .unwrap_or_default();
+        let border_str = self.world.history.as_ref()
+            .and_then(|h| h.territories.border_at(x, y).map(|b| {
+                let other = b.neighbour
+                    .and_then(|id| h.factions.get(id))
+                    .map(|f| format!(" with {}", f.name))
+                    .unwrap_or_default();
+                format!(" | {} border{}", b.frontier.name(), other)
+            }))
+            .unwrap_or_default();
+        let water_id = *self.world.water_body_map.get(x, y);
+        let name_str = self.world.names.as_ref()
+            .and_then(|n| n.name_at(x, y, water_id.0))
+            // The history summary already names the settlement, dungeon or lair standing here
+            .filter(|&(at, place)| at != (x, y) || place.kind.is_water() || place.kind == PlaceKind::Landmark)
+            .map(|(_, place)| if place.kind.is_water() {
+                format!(" | {}", place.name)
+            } else {
+                format!(" | {} ({})", place.name, place.kind.name())
+            })
+            .unwrap_or_default();
+        let history_str = name_str + &history_str + &supply_str + &border_str + &magic_str + &polar_str + &landform_str + &dune_str + &loess_str + &discovery_str + &seismic_str + &slope_str + &wave_str + &succession_str + &flora_str + &cave_str + &conduit_str;
 
         if self.cursor_z == surface_z {
             // At surface - show biome
@@ -563,7 +755,8 @@ impl Explorer {
                 x, y, tile_name, biome, height, temp, moisture * 100.0, history_str,
             )
         } else {
-            // Underground - show tile type
+            // Underground - show tile type and the rock temperature at this depth
+            let temp = self.world.temperature_at(x, y, self.cursor_z);
             format!(
                 "({}, {}) | {} | Depth: {} | {:.1}°C | {:.0}%{}",
                 x, y, tile_name, surface_z - self.cursor_z, temp, moisture * 100.0, history_str,
@@ -659,21 +852,8 @@ impl Explorer {
         // Calculate viewport centered on cursor, accounting for zoom
         let view_width = area.width as usize;
         let view_height = area.height as usize;
-
-        // Map area visible = screen size * zoom
-        let map_view_width = view_width * zoom;
-        let map_view_height = view_height * zoom;
-
-        let start_x = if self.cursor_x >= map_view_width / 2 {
-            self.cursor_x - map_view_width / 2
-        } else {
-            0
-        };
-        let start_y = if self.cursor_y >= map_view_height / 2 {
-            self.cursor_y - map_view_height / 2
-        } else {
-            0
-        };
+        let viewport = self.world_viewport(area);
+        let (start_x, start_y) = (viewport.x, viewport.y);
 
         for dy in 0..view_height {
             for dx in 0..view_width {
@@ -708,6 +888,122 @@ impl Explorer {
         }
     }
 
+    /// World tiles covered by the map area (centered on the cursor, scaled by zoom)
+    fn world_viewport(&self, area: Rect) -> Viewport {
+        let width = area.width as usize * self.zoom;
+        let height = area.height as usize * self.zoom;
+        Viewport {
+            x: self.cursor_x.saturating_sub(width / 2),
+            y: self.cursor_y.saturating_sub(height / 2),
+            width,
+            height,
+        }
+    }
+
+    /// Map a screen cell in the map area to a world tile
+    fn screen_to_world(&self, column: u16, row: u16) -> Option<(usize, usize)> {
+        let area = self.map_area;
+        if column < area.x || column >= area.x + area.width || row < area.y || row >= area.y + area.height {
+            return None;
+        }
+        let viewport = self.world_viewport(area);
+        let x = (viewport.x + (column - area.x) as usize * self.zoom) % self.world.heightmap.width;
+        let y = viewport.y + (row - area.y) as usize * self.zoom;
+        (y < self.world.heightmap.height).then_some((x, y))
+    }
+
+    /// Screen rectangle of the minimap (top-right corner of the map area)
+    fn minimap_rect(&self) -> Option<Rect> {
+        let area = self.map_area;
+        if !self.show_minimap || area.width < MINIMAP_WIDTH * 2 || area.height < MINIMAP_HEIGHT + 2 {
+            return None;
+        }
+        Some(Rect::new(area.x + area.width - MINIMAP_WIDTH, area.y, MINIMAP_WIDTH, MINIMAP_HEIGHT))
+    }
+
+    /// Map a screen cell inside the minimap to the world tile it summarizes
+    fn minimap_to_world(&self, column: u16, row: u16) -> Option<(usize, usize)> {
+        let rect = self.minimap_rect()?;
+        let inner = Rect::new(rect.x + 1, rect.y + 1, rect.width - 2, rect.height - 2);
+        if column < inner.x || column >= inner.x + inner.width || row < inner.y || row >= inner.y + inner.height {
+            return None;
+        }
+        let x = (column - inner.x) as usize * self.world.heightmap.width / inner.width as usize;
+        let y = (row - inner.y) as usize * self.world.heightmap.height / inner.height as usize;
+        Some((x, y))
+    }
+
+    /// Render the overview minimap with the current viewport outlined
+    fn render_minimap(&self, buf: &mut Buffer) {
+        let Some(rect) = self.minimap_rect() else { return };
+        let width = self.world.heightmap.width;
+        let height = self.world.heightmap.height;
+
+        Clear.render(rect, buf);
+        let block = Block::default()
+            .title(" Map ")
+            .borders(Borders::ALL)
+            .style(Style::default().bg(Color::Black).fg(Color::Gray));
+        let inner = block.inner(rect);
+        block.render(rect, buf);
+
+        let viewport = self.world_viewport(self.map_area);
+        let (cells_w, cells_h) = (inner.width as usize, inner.height as usize);
+        let cell_tile = |cx: usize, cy: usize| (cx * width / cells_w, cy * height / cells_h);
+        let visible = |cx: usize, cy: usize| {
+            let (x, y) = cell_tile(cx, cy);
+            viewport.contains(x, y, width)
+        };
+
+        for cy in 0..cells_h {
+            for cx in 0..cells_w {
+                let (x, y) = cell_tile(cx, cy);
+                let (r, g, b) = self.world.biomes.get(x, y).color();
+                // Outline the viewport: visible cells with a hidden neighbor
+                let edge = visible(cx, cy) && (
+                    cx == 0 || cy == 0 || cx + 1 == cells_w || cy + 1 == cells_h
+                    || !visible(cx - 1, cy) || !visible(cx + 1, cy)
+                    || !visible(cx, cy - 1) || !visible(cx, cy + 1)
+                );
+                let cell = &mut buf[(inner.x + cx as u16, inner.y + cy as u16)];
+                cell.set_bg(Color::Rgb(r, g, b));
+                if edge {
+                    cell.set_char('·').set_fg(Color::Yellow);
+                } else {
+                    cell.set_char(' ');
+                }
+            }
+        }
+    }
+
+    /// Bookmark the world cursor position and persist it for this seed
+    fn add_bookmark(&mut self) {
+        let (x, y) = (self.cursor_x, self.cursor_y);
+        if self.bookmarks.locations.iter().any(|b| b.x == x && b.y == y) {
+            self.message = Some("Already bookmarked".to_string());
+            return;
+        }
+        let biome = self.world.biomes.get(x, y).display_name();
+        let name = format!("{} {}", biome, self.bookmarks.locations.len() + 1);
+        self.bookmarks.locations.push(Bookmark { name: name.clone(), x, y });
+        self.message = Some(match self.bookmarks.save(Path::new(BOOKMARK_DIR)) {
+            Ok(()) => format!("Bookmarked: {} ({}, {})", name, x, y),
+            Err(e) => format!("Bookmark not saved: {}", e),
+        });
+    }
+
+    /// Jump the world cursor to a bookmark (0-based index)
+    fn goto_bookmark(&mut self, index: usize) {
+        if let Some(bookmark) = self.bookmarks.locations.get(index).cloned() {
+            self.cursor_x = bookmark.x.min(self.world.heightmap.width - 1);
+            self.cursor_y = bookmark.y.min(self.world.heightmap.height - 1);
+            self.cursor_z = *self.world.surface_z.get(self.cursor_x, self.cursor_y);
+            self.message = Some(format!("Bookmark {}: {}", index + 1, bookmark.name));
+        } else {
+            self.message = Some(format!("No bookmark {}", index + 1));
+        }
+    }
+
     /// Render local-scale map from the 3x3 chunk grid
     fn render_local_map(&self, area: Rect, buf: &mut Buffer) {
         // Check if we have the center chunk
@@ -976,6 +1272,9 @@ impl Explorer {
             LocalFeature::OreVein => {
                 return ('◆', Color::Rgb(200, 170, 100), Color::Rgb(60, 50, 30));
             }
+            LocalFeature::Plant { .. } => {
+                return ('"', Color::Rgb(120, 170, 70), Color::Rgb(25, 40, 15));
+            }
             LocalFeature::None => {}
         }
 
@@ -1338,6 +1637,10 @@ impl Explorer {
                         // Underground lake - dark blue
                         ('~', Color::Rgb(40, 80, 120), Color::Rgb(10, 20, 40))
                     }
+                    ZTile::FrozenCaveLake => {
+                        // Frozen underground lake - pale ice blue
+                        ('≡', Color::Rgb(190, 220, 240), Color::Rgb(40, 60, 80))
+                    }
                     ZTile::Waterfall => {
                         // Falling water - bright blue
                         ('|', Color::Rgb(150, 200, 255), Color::Rgb(30, 60, 100))
@@ -1951,6 +2254,16 @@ impl Explorer {
             "",
             "View Modes:",
             "  V - Cycle view mode (Biome/Height/Temp/Moisture/Plates/Stress)",
+            "  M - Toggle minimap",
+            "",
+            "Mouse:",
+            "  Click - Inspect tile (click minimap to jump)",
+            "  I - Toggle the history of the tile",
+            "  Drag - Pan the map",
+            "",
+            "Bookmarks (saved per seed):",
+            "  B - Bookmark cursor location",
+            "  1-9 - Jump to bookmark",
             "",
             "Other:",
             "  ? - Toggle this help",
@@ -1986,6 +2299,33 @@ impl Explorer {
         }
     }
 
+    fn render_tile_story(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(ref story) = self.tile_story {
+            let lines = story.lines();
+            let width = 70.min(area.width.saturating_sub(4));
+            let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(4));
+            let x = area.x + (area.width.saturating_sub(width)) / 2;
+            let y = area.y + (area.height.saturating_sub(height)) / 2;
+            let story_area = Rect::new(x, y, width, height);
+
+            Clear.render(story_area, buf);
+            let title = format!(" History of ({}, {}) (I to close) ", story.x, story.y);
+            let block = Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .style(Style::default().bg(Color::Black));
+            let inner = block.inner(story_area);
+            block.render(story_area, buf);
+
+            // Keep the most recent entries when the story is too long to fit
+            let skip = lines.len().saturating_sub(inner.height as usize);
+            for (i, line) in lines.iter().skip(skip).enumerate() {
+                let truncated: String = line.chars().take(inner.width as usize).collect();
+                buf.set_string(inner.x, inner.y + i as u16, &truncated, Style::default().fg(Color::White));
+            }
+        }
+    }
+
     fn render_verification_report(&self, area: Rect, buf: &mut Buffer) {
         if let Some(ref report) = self.verification_report {
             let lines: Vec<&str> = report.lines().collect();
@@ -2077,6 +2417,7 @@ fn cave_tile_char(tile: ZTile) -> char {
         ZTile::MagmaTube => '○',
         ZTile::ObsidianFloor => '_',
         ZTile::CaveLake => '~',
+        ZTile::FrozenCaveLake => '≡',
         ZTile::Waterfall => '|',
         ZTile::RampUp => '↑',
         ZTile::RampDown => '↓',
@@ -2143,6 +2484,7 @@ fn ztile_name(tile: ZTile) -> &'static str {
         ZTile::ObsidianFloor => "Obsidian Floor (_)",
         // Water integration
         ZTile::CaveLake => "Cave Lake (~)",
+        ZTile::FrozenCaveLake => "Frozen Cave Lake (≡)",
         ZTile::Waterfall => "Waterfall (|)",
         // Vertical passages
         ZTile::RampUp => "Ramp Up (↑) - ascend",
@@ -2574,6 +2916,16 @@ fn get_tile_color_for_export(
 
 /// Run the explorer
 pub fn run_explorer(world: WorldData) -> Result<(), Box<dyn Error>> {
+    run_explorer_watching(world, None, &ResourceBudget::default())
+}
+
+/// Run the explorer, re-running generation stages whenever the watched tuning file changes.
+/// Streamed local chunks are cached within `budget`.
+pub fn run_explorer_watching(
+    world: WorldData,
+    mut hot_reload: Option<HotReload>,
+    budget: &ResourceBudget,
+) -> Result<(), Box<dyn Error>> {
     // Setup terminal
     terminal::enable_raw_mode()?;
     let mut stdout = stdout();
@@ -2581,9 +2933,15 @@ pub fn run_explorer(world: WorldData) -> Result<(), Box<dyn Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut explorer = Explorer::new(world);
+    let mut explorer = Explorer::new(world, budget);
 
     loop {
+        if let Some(ref mut reload) = hot_reload {
+            if let Some(result) = reload.poll(&mut explorer.world) {
+                explorer.after_reload(result);
+            }
+        }
+
         // Render
         terminal.draw(|f| {
             let size = f.area();
@@ -2600,12 +2958,17 @@ pub fn run_explorer(world: WorldData) -> Result<(), Box<dyn Error>> {
             let map_area = chunks[0];
             let status_area = chunks[1];
 
-            // Render map
+            // Render map (remember the area for mouse hit-testing)
+            explorer.map_area = map_area;
             explorer.render_map(map_area, f.buffer_mut());
+            if let ScaleMode::World { .. } = explorer.scale_mode {
+                explorer.render_minimap(f.buffer_mut());
+            }
 
             // Render status bar
             let zoom_str = if explorer.zoom > 1 { format!(" | Zoom:{}x", explorer.zoom) } else { String::new() };
-            let msg_str = explorer.message.as_ref().map(|m| format!(" | {}", m)).unwrap_or_default();
+            let msg_str = explorer.message.as_ref().or(explorer.reload_status.as_ref())
+                .map(|m| format!(" | {}", m)).unwrap_or_default();
             let scale_str = explorer.scale_status();
             let world_pos = match explorer.scale_mode {
                 ScaleMode::World { .. } => format!("({},{})", explorer.cursor_x, explorer.cursor_y),
@@ -2637,6 +3000,11 @@ pub fn run_explorer(world: WorldData) -> Result<(), Box<dyn Error>> {
             if explorer.verification_report.is_some() {
                 explorer.render_verification_report(map_area, f.buffer_mut());
             }
+
+            // Render tile story if active
+            if explorer.tile_story.is_some() {
+                explorer.render_tile_story(map_area, f.buffer_mut());
+            }
         })?;
 
         // Handle input
@@ -2723,6 +3091,21 @@ pub fn run_explorer(world: WorldData) -> Result<(), Box<dyn Error>> {
                         KeyCode::Char('0') => explorer.go_to_sea_level(),
                         KeyCode::Char('S') => explorer.go_to_surface(),
 
+                        // Minimap and bookmarks
+                        KeyCode::Char('m') | KeyCode::Char('M') => {
+                            explorer.show_minimap = !explorer.show_minimap;
+                        }
+                        KeyCode::Char('b') | KeyCode::Char('B') => {
+                            if let ScaleMode::World { .. } = explorer.scale_mode {
+                                explorer.add_bookmark();
+                            }
+                        }
+                        KeyCode::Char(c @ '1'..='9') => {
+                            if let ScaleMode::World { .. } = explorer.scale_mode {
+                                explorer.goto_bookmark(c as usize - '1' as usize);
+                            }
+                        }
+
                         // Scale zoom controls (Enter/Z to zoom in, Backspace/X to zoom out)
                         KeyCode::Enter | KeyCode::Char('z') => explorer.scale_zoom_in(),
                         KeyCode::Backspace | KeyCode::Char('x') => explorer.scale_zoom_out(),
@@ -2758,6 +3141,15 @@ pub fn run_explorer(world: WorldData) -> Result<(), Box<dyn Error>> {
                             }
                         }
 
+                        // Tile history
+                        KeyCode::Char('i') | KeyCode::Char('I') => {
+                            if explorer.tile_story.is_some() {
+                                explorer.tile_story = None;
+                            } else {
+                                explorer.show_tile_story();
+                            }
+                        }
+
                         // Run verification
                         KeyCode::Char('y') | KeyCode::Char('Y') => {
                             if explorer.verification_report.is_some() {
@@ -2771,31 +3163,53 @@ pub fn run_explorer(world: WorldData) -> Result<(), Box<dyn Error>> {
                         _ => {}
                     }
                 }
-                Event::Mouse(MouseEvent { kind: MouseEventKind::Down(MouseButton::Left), column, row, .. }) => {
-                    // Click to move cursor
-                    let size = terminal.size()?;
-                    if row < size.height - 1 {
-                        let view_width = size.width as usize;
-                        let view_height = (size.height - 1) as usize;
-
-                        let start_x = if explorer.cursor_x >= view_width / 2 {
-                            explorer.cursor_x - view_width / 2
-                        } else {
-                            0
-                        };
-                        let start_y = if explorer.cursor_y >= view_height / 2 {
-                            explorer.cursor_y - view_height / 2
-                        } else {
-                            0
-                        };
-
-                        let new_x = (start_x + column as usize) % explorer.world.heightmap.width;
-                        let new_y = (start_y + row as usize).min(explorer.world.heightmap.height - 1);
-
-                        explorer.cursor_x = new_x;
-                        explorer.cursor_y = new_y;
+                Event::Mouse(MouseEvent { kind, column, row, .. }) => {
+                    // Mouse only drives the world map
+                    if let ScaleMode::Local { .. } = explorer.scale_mode {
+                        continue;
+                    }
+                    match kind {
+                        MouseEventKind::Down(MouseButton::Left) => {
+                            explorer.drag_last = Some((column, row));
+                            explorer.dragged = false;
+                        }
+                        MouseEventKind::Drag(MouseButton::Left) => {
+                            // Drag to pan: the map follows the mouse
+                            if let Some((last_col, last_row)) = explorer.drag_last {
+                                let zoom = explorer.zoom as i32;
+                                let dx = (last_col as i32 - column as i32) * zoom;
+                                let dy = (last_row as i32 - row as i32) * zoom;
+                                if dx != 0 || dy != 0 {
+                                    explorer.move_cursor(dx, dy);
+                                    explorer.dragged = true;
+                                }
+                                explorer.drag_last = Some((column, row));
+                            }
+                        }
+                        MouseEventKind::Up(MouseButton::Left) => {
+                            // Click without dragging: jump via minimap or inspect the tile
+                            if !explorer.dragged {
+                                let target = explorer.minimap_to_world(column, row)
+                                    .or_else(|| explorer.screen_to_world(column, row));
+                                if let Some((x, y)) = target {
+                                    explorer.cursor_x = x;
+                                    explorer.cursor_y = y;
+                                    explorer.message = Some(explorer.tile_info());
+                                }
+                            }
+                            explorer.drag_last = None;
+                            explorer.dragged = false;
+                        }
+                        MouseEventKind::ScrollUp => explorer.zoom_in(),
+                        MouseEventKind::ScrollDown => explorer.zoom_out(),
+                        _ => {}
                     }
                 }
+                Event::Resize(width, height) => {
+                    // Redraw at the new size on the next frame
+                    terminal.autoresize()?;
+                    explorer.message = Some(format!("Resized to {}x{}", width, height));
+                }
                 _ => {}
             }
         }
@@ -2808,3 +3222,30 @@ pub fn run_explorer(world: WorldData) -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_viewport_wraps_horizontally() {
+        let viewport = Viewport { x: 90, y: 10, width: 20, height: 5 };
+        assert!(viewport.contains(95, 12, 100));
+        assert!(viewport.contains(5, 12, 100));
+        assert!(!viewport.contains(10, 12, 100));
+        assert!(!viewport.contains(95, 15, 100));
+    }
+
+    #[test]
+    fn test_bookmarks_roundtrip_per_seed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut bookmarks = Bookmarks::load(dir.path(), 42);
+        assert!(bookmarks.locations.is_empty());
+
+        bookmarks.locations.push(Bookmark { name: "Dark Tower 1".to_string(), x: 3, y: 7 });
+        bookmarks.save(dir.path()).unwrap();
+
+        assert_eq!(Bookmarks::load(dir.path(), 42).locations, bookmarks.locations);
+        assert!(Bookmarks::load(dir.path(), 43).locations.is_empty());
+    }
+}
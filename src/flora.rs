@@ -0,0 +1,469 @@
+//! Plant species and where they grow
+//!
+//! The species a world can hold are data: `flora/plants.json` lists each
+//! plant's form, the biomes it grows in, what people use it for (food, fiber,
+//! medicine, poison) and the seasons it can be gathered. A world draws a few
+//! species for each of its biomes from that registry and spreads them in
+//! regional patches, so the same forest has chanterelles in one valley and
+//! deathcaps in the next.
+//!
+//! The catalog is read by:
+//! - Settlement supply, which counts what foragers gather around each settlement
+//! - `apply_herbal_medicine`, which lets herbalists ease plagues where medicine grows
+//! - Tile descriptions in the explorer
+//! - Local chunks, which scatter the ground-cover species as `LocalFeature::Plant`
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use noise::{NoiseFn, Perlin};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::ExtendedBiome;
+#[cfg(feature = "history")]
+use crate::history::{EventType, WorldHistory};
+use crate::tilemap::Tilemap;
+
+/// The compiled-in species registry
+const BUILTIN_PLANTS: &str = include_str!("../flora/plants.json");
+
+/// What people gather a plant for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlantUse {
+    Food,
+    Fiber,
+    Medicine,
+    Poison,
+}
+
+impl PlantUse {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlantUse::Food => "food",
+            PlantUse::Fiber => "fiber",
+            PlantUse::Medicine => "medicine",
+            PlantUse::Poison => "poison",
+        }
+    }
+}
+
+/// Growth form, which decides how a plant shows on the local map
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlantForm {
+    Herb,
+    Grass,
+    Shrub,
+    Tree,
+    Vine,
+    Fungus,
+    Reed,
+}
+
+impl PlantForm {
+    /// Whether the plant grows low enough to be ground cover (trees are placed by the biome)
+    pub fn is_ground_cover(&self) -> bool {
+        !matches!(self, PlantForm::Tree)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub const ALL: [Season; 4] = [Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Season::Spring => "spring",
+            Season::Summer => "summer",
+            Season::Autumn => "autumn",
+            Season::Winter => "winter",
+        }
+    }
+}
+
+/// One plant species as listed in the registry
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct PlantSpecies {
+    pub name: String,
+    pub form: PlantForm,
+    /// Biomes the plant can grow in
+    pub biomes: Vec<ExtendedBiome>,
+    pub uses: Vec<PlantUse>,
+    /// Seasons it can be gathered
+    pub seasons: Vec<Season>,
+    /// How thickly it grows where it is found (0.0-1.0)
+    pub abundance: f32,
+    pub description: String,
+}
+
+impl PlantSpecies {
+    pub fn has_use(&self, plant_use: PlantUse) -> bool {
+        self.uses.contains(&plant_use)
+    }
+
+    pub fn in_season(&self, season: Season) -> bool {
+        self.seasons.contains(&season)
+    }
+
+    /// Fraction of the year it can be gathered
+    pub fn season_fraction(&self) -> f32 {
+        Season::ALL.iter().filter(|&&s| self.in_season(s)).count() as f32 / Season::ALL.len() as f32
+    }
+}
+
+/// All species a world may draw from
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct FloraRegistry {
+    pub species: Vec<PlantSpecies>,
+}
+
+impl Default for FloraRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl FloraRegistry {
+    pub fn builtin() -> FloraRegistry {
+        serde_json::from_str(BUILTIN_PLANTS).expect("built-in plant registry is valid JSON")
+    }
+
+    /// Load a registry from a JSON file
+    pub fn load(path: &str) -> Result<FloraRegistry, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        serde_json::from_str(&json).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// The built-in registry for "builtin", otherwise a registry file path
+    pub fn resolve(spec: &str) -> Result<FloraRegistry, String> {
+        if spec.eq_ignore_ascii_case("builtin") {
+            Ok(Self::builtin())
+        } else {
+            Self::load(spec)
+        }
+    }
+
+    /// Species that can grow in a biome
+    pub fn for_biome(&self, biome: ExtendedBiome) -> impl Iterator<Item = &PlantSpecies> {
+        self.species.iter().filter(move |s| s.biomes.contains(&biome))
+    }
+}
+
+/// How much food foragers find on a tile of a biome (0.0-1.0), from the
+/// built-in registry: every food plant counted by abundance and season length
+#[cfg_attr(not(feature = "history"), allow(dead_code))]
+pub(crate) fn forage_yield(biome: ExtendedBiome) -> f32 {
+    static REGISTRY: OnceLock<FloraRegistry> = OnceLock::new();
+    REGISTRY
+        .get_or_init(FloraRegistry::builtin)
+        .for_biome(biome)
+        .filter(|s| s.has_use(PlantUse::Food))
+        .map(|s| s.abundance * s.season_fraction())
+        .sum::<f32>()
+        .min(1.0)
+}
+
+/// Parameters for drawing a world's flora from the registry
+#[derive(Clone, Debug)]
+pub struct FloraConfig {
+    /// Most species drawn for any one biome
+    pub species_per_biome: usize,
+    /// Size in tiles of the patches a species grows in
+    pub patch_scale: f64,
+    /// Share of a biome (0.0-1.0) where a species is absent
+    pub patch_gap: f32,
+    /// Radius (tiles) herbalists gather medicine around a plague
+    pub herb_reach: i32,
+    /// Largest share of a plague's dead that medicine can save
+    pub max_relief: f32,
+}
+
+impl Default for FloraConfig {
+    fn default() -> Self {
+        Self {
+            species_per_biome: 4,
+            patch_scale: 24.0,
+            patch_gap: 0.35,
+            herb_reach: 3,
+            max_relief: 0.5,
+        }
+    }
+}
+
+/// The species growing in a world and their regional patches
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct FloraCatalog {
+    /// Species drawn for this world
+    pub species: Vec<PlantSpecies>,
+    /// Indices into `species` growing in each biome
+    pub by_biome: HashMap<ExtendedBiome, Vec<u16>>,
+    /// Biome of each tile when the flora was drawn
+    pub biomes: Tilemap<ExtendedBiome>,
+    pub patch_scale: f64,
+    pub patch_gap: f32,
+    /// Seed of the patch noise
+    pub seed: u64,
+}
+
+impl FloraCatalog {
+    /// How thickly a species grows on a tile (0.0 = absent)
+    pub fn presence(&self, species: u16, x: usize, y: usize) -> f32 {
+        let biome = *self.biomes.get(x, y);
+        if !self.by_biome.get(&biome).is_some_and(|ids| ids.contains(&species)) {
+            return 0.0;
+        }
+        self.patch(&Perlin::new(self.seed as u32), species, x, y)
+    }
+
+    fn patch(&self, noise: &Perlin, species: u16, x: usize, y: usize) -> f32 {
+        let n = noise.get([x as f64 / self.patch_scale, y as f64 / self.patch_scale, species as f64 * 7.31]);
+        let patch = ((n as f32 * 0.5 + 0.5 - self.patch_gap) / (1.0 - self.patch_gap)).clamp(0.0, 1.0);
+        self.species[species as usize].abundance * patch
+    }
+
+    /// Species growing on a tile with their presence, thickest first
+    pub fn plants_at(&self, x: usize, y: usize) -> Vec<(u16, f32)> {
+        let Some(ids) = self.by_biome.get(self.biomes.get(x, y)) else {
+            return Vec::new();
+        };
+        let noise = Perlin::new(self.seed as u32);
+        let mut plants: Vec<(u16, f32)> = ids
+            .iter()
+            .map(|&id| (id, self.patch(&noise, id, x, y)))
+            .filter(|&(_, presence)| presence > 0.0)
+            .collect();
+        plants.sort_by(|a, b| b.1.total_cmp(&a.1));
+        plants
+    }
+
+    /// Low-growing species on a tile, for the local map
+    pub fn ground_cover(&self, x: usize, y: usize) -> Vec<(u16, f32)> {
+        self.plants_at(x, y)
+            .into_iter()
+            .filter(|&(id, _)| self.species[id as usize].form.is_ground_cover())
+            .collect()
+    }
+
+    /// Total presence of plants with a use on a tile, optionally only those in season
+    pub fn harvest(&self, x: usize, y: usize, plant_use: PlantUse, season: Option<Season>) -> f32 {
+        self.plants_at(x, y)
+            .into_iter()
+            .map(|(id, presence)| (&self.species[id as usize], presence))
+            .filter(|(s, _)| s.has_use(plant_use) && season.is_none_or(|season| s.in_season(season)))
+            .map(|(_, presence)| presence)
+            .sum()
+    }
+
+    /// Describe the plants on a tile for tile info
+    pub fn describe(&self, x: usize, y: usize) -> Option<String> {
+        let plants: Vec<String> = self
+            .plants_at(x, y)
+            .into_iter()
+            .take(3)
+            .map(|(id, _)| {
+                let s = &self.species[id as usize];
+                let uses: Vec<&str> = s.uses.iter().map(|u| u.name()).collect();
+                let seasons: Vec<&str> = s.seasons.iter().map(|s| s.name()).collect();
+                let seasons = if seasons.len() == Season::ALL.len() { "all year".to_string() } else { seasons.join("/") };
+                format!("{} ({}, {})", s.name, uses.join("/"), seasons)
+            })
+            .collect();
+        (!plants.is_empty()).then(|| format!("Flora: {}", plants.join(", ")))
+    }
+}
+
+/// Draw up to `species_per_biome` species for every biome in the world,
+/// favouring the abundant ones
+pub fn generate_flora(
+    biomes: &Tilemap<ExtendedBiome>,
+    registry: &FloraRegistry,
+    config: &FloraConfig,
+    seed: u64,
+) -> FloraCatalog {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0xF10EA));
+
+    let mut present: Vec<ExtendedBiome> = Vec::new();
+    for (_, _, &biome) in biomes.iter() {
+        if !present.contains(&biome) {
+            present.push(biome);
+        }
+    }
+    present.sort_by_key(|b| format!("{:?}", b));
+
+    let mut species: Vec<PlantSpecies> = Vec::new();
+    let mut by_biome: HashMap<ExtendedBiome, Vec<u16>> = HashMap::new();
+    for biome in present {
+        let candidates: Vec<&PlantSpecies> = registry.for_biome(biome).collect();
+        let count = config.species_per_biome.min(candidates.len());
+        let chosen = candidates
+            .choose_multiple_weighted(&mut rng, count, |s| s.abundance.max(0.01))
+            .expect("abundance weights are positive");
+        let mut ids: Vec<u16> = chosen
+            .map(|&plant| match species.iter().position(|s| s.name == plant.name) {
+                Some(id) => id as u16,
+                None => {
+                    species.push(plant.clone());
+                    (species.len() - 1) as u16
+                }
+            })
+            .collect();
+        ids.sort();
+        if !ids.is_empty() {
+            by_biome.insert(biome, ids);
+        }
+    }
+
+    FloraCatalog {
+        species,
+        by_biome,
+        biomes: biomes.clone(),
+        patch_scale: config.patch_scale,
+        patch_gap: config.patch_gap,
+        seed,
+    }
+}
+
+/// Herbalists gather medicine plants growing near a plague and save part of
+/// its dead. Returns the number of plagues eased.
+#[cfg(feature = "history")]
+pub fn apply_herbal_medicine(history: &mut WorldHistory, flora: &FloraCatalog, config: &FloraConfig) -> usize {
+    let (width, height) = (flora.biomes.width as i32, flora.biomes.height as i32);
+    let mut ids: Vec<_> = history
+        .timeline
+        .events
+        .values()
+        .filter(|e| e.event_type == EventType::Plague && e.casualties > 0)
+        .map(|e| e.id)
+        .collect();
+    ids.sort_by_key(|id| id.0);
+
+    let mut eased = 0;
+    for id in ids {
+        let Some((ex, ey)) = history.timeline.events[&id].location else {
+            continue;
+        };
+        let reach = config.herb_reach;
+        let mut medicine = 0.0;
+        let mut best: Option<(u16, f32)> = None;
+        for dy in -reach..=reach {
+            for dx in -reach..=reach {
+                let y = ey as i32 + dy;
+                if dx * dx + dy * dy > reach * reach || y < 0 || y >= height {
+                    continue;
+                }
+                let x = (ex as i32 + dx).rem_euclid(width) as usize;
+                for (plant, presence) in flora.plants_at(x, y as usize) {
+                    if flora.species[plant as usize].has_use(PlantUse::Medicine) {
+                        medicine += presence;
+                        if best.is_none_or(|(_, p)| presence > p) {
+                            best = Some((plant, presence));
+                        }
+                    }
+                }
+            }
+        }
+        let Some((plant, _)) = best else {
+            continue;
+        };
+
+        let relief = (medicine / (reach * reach) as f32).min(1.0) * config.max_relief;
+        let event = history.timeline.events.get_mut(&id).expect("id was collected above");
+        let saved = (event.casualties as f32 * relief) as u32;
+        if saved == 0 {
+            continue;
+        }
+        event.casualties -= saved;
+        event.description.push_str(&format!(
+            " Herbalists brewing {} saved {} who would have died.",
+            flora.species[plant as usize].name.to_lowercase(),
+            saved
+        ));
+        eased += 1;
+    }
+    eased
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_draws_from_registry_by_biome() {
+        let registry = FloraRegistry::builtin();
+        assert!(registry.species.iter().all(|s| !s.biomes.is_empty() && !s.uses.is_empty() && !s.seasons.is_empty()));
+
+        let mut biomes = Tilemap::new_with(64, 32, ExtendedBiome::TemperateForest);
+        for y in 0..32 {
+            for x in 32..64 {
+                biomes.set(x, y, ExtendedBiome::Desert);
+            }
+        }
+        let config = FloraConfig::default();
+        let catalog = generate_flora(&biomes, &registry, &config, 42);
+
+        let forest = &catalog.by_biome[&ExtendedBiome::TemperateForest];
+        assert_eq!(forest.len(), config.species_per_biome);
+        for &id in forest {
+            assert!(catalog.species[id as usize].biomes.contains(&ExtendedBiome::TemperateForest));
+            assert_eq!(catalog.presence(id, 50, 10), 0.0, "forest plants do not grow in the desert");
+        }
+
+        // Regional patches: a species is thick in some places and absent in others
+        let id = forest[0];
+        let presences: Vec<f32> = (0..32).flat_map(|y| (0..32).map(move |x| (x, y))).map(|(x, y)| catalog.presence(id, x, y)).collect();
+        assert!(presences.iter().any(|&p| p > 0.0));
+        assert!(presences.iter().any(|&p| p == 0.0));
+
+        assert!(forage_yield(ExtendedBiome::TemperateForest) > forage_yield(ExtendedBiome::Desert));
+        assert_eq!(forage_yield(ExtendedBiome::DeepOcean), 0.0);
+        assert!((0..32).any(|x| catalog.describe(x, 8).is_some_and(|d| d.starts_with("Flora: "))));
+    }
+
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_herbalists_ease_plagues_where_medicine_grows() {
+        use crate::history::types::Year;
+        use crate::history::HistoricalEvent;
+
+        let biomes = Tilemap::new_with(32, 16, ExtendedBiome::TemperateForest);
+        let mut catalog = generate_flora(&biomes, &FloraRegistry::builtin(), &FloraConfig::default(), 7);
+        let mut medicine = catalog.species[0].clone();
+        medicine.uses = vec![PlantUse::Medicine];
+        medicine.abundance = 1.0;
+        catalog.species = vec![medicine];
+        catalog.by_biome = HashMap::from([(ExtendedBiome::TemperateForest, vec![0])]);
+        catalog.patch_gap = 0.0;
+
+        let mut history = WorldHistory::empty();
+        let id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id,
+            year: Year(-100),
+            event_type: EventType::Plague,
+            faction: None,
+            other_faction: None,
+            location: Some((8, 8)),
+            settlement: None,
+            name: "The Grey Plague".to_string(),
+            description: "A sickness swept the land.".to_string(),
+            casualties: 10_000,
+            has_evidence: false,
+        });
+
+        assert_eq!(apply_herbal_medicine(&mut history, &catalog, &FloraConfig::default()), 1);
+        let event = &history.timeline.events[&id];
+        assert!(event.casualties < 10_000 && event.casualties >= 5_000);
+        assert!(event.description.contains("Herbalists"));
+    }
+}
@@ -0,0 +1,440 @@
+//! Gameplay metadata layer
+//!
+//! Derives strategy-game data from the generated world: movement cost per
+//! locomotion type (foot, cavalry, boat), passability flags, defensive cover
+//! and supply attrition for every world tile. Costs come from the terrain
+//! class of the biome, slope, water, rivers and the road network carved by the
+//! structure and history passes.
+//!
+//! The layer exports as a compact binary grid plus a JSON schema describing
+//! its layout, so engines can load it without linking this crate.
+
+use std::io::Write;
+
+use crate::biomes::ExtendedBiome;
+use crate::erosion::river_geometry::rasterize_river_network;
+use crate::history::monsters::{categorize_biome, BiomeCategory};
+use crate::multiscale::is_water_biome;
+use crate::tilemap::Tilemap;
+use crate::water_bodies::WaterBodyId;
+use crate::world::WorldData;
+use crate::zlevel::SEA_LEVEL_Z;
+
+/// Binary file magic
+const MAGIC: &[u8; 4] = b"TDGP";
+
+/// Binary format version
+const FORMAT_VERSION: u8 = 1;
+
+/// Bytes per tile record
+const RECORD_SIZE: usize = 6;
+
+/// Encoded cost meaning "impassable"
+pub const IMPASSABLE: u8 = 255;
+
+/// Encoded cost units per 1.0 movement cost
+const COST_SCALE: f32 = 10.0;
+
+/// Tile flag bits
+pub const FLAG_FOOT: u8 = 1;
+pub const FLAG_CAVALRY: u8 = 1 << 1;
+pub const FLAG_BOAT: u8 = 1 << 2;
+pub const FLAG_ROAD: u8 = 1 << 3;
+pub const FLAG_RIVER: u8 = 1 << 4;
+pub const FLAG_WATER: u8 = 1 << 5;
+pub const FLAG_SETTLEMENT: u8 = 1 << 6;
+
+/// How a unit moves
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Locomotion {
+    Foot,
+    Cavalry,
+    Boat,
+}
+
+/// Gameplay data for one tile
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct TileGameplay {
+    /// Movement cost in tenths (10 = open plain on foot), `IMPASSABLE` if blocked
+    pub move_foot: u8,
+    pub move_cavalry: u8,
+    pub move_boat: u8,
+    /// `FLAG_*` bits
+    pub flags: u8,
+    /// Defensive cover (0-100)
+    pub cover: u8,
+    /// Supply lost per turn (percent)
+    pub attrition: u8,
+}
+
+impl TileGameplay {
+    pub fn cost(&self, locomotion: Locomotion) -> Option<f32> {
+        let raw = match locomotion {
+            Locomotion::Foot => self.move_foot,
+            Locomotion::Cavalry => self.move_cavalry,
+            Locomotion::Boat => self.move_boat,
+        };
+        (raw != IMPASSABLE).then(|| raw as f32 / COST_SCALE)
+    }
+
+    pub fn has(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+
+    fn to_bytes(self) -> [u8; RECORD_SIZE] {
+        [self.move_foot, self.move_cavalry, self.move_boat, self.flags, self.cover, self.attrition]
+    }
+}
+
+/// Gameplay layer for a whole world
+#[derive(Clone)]
+pub struct GameplayMap {
+    pub tiles: Tilemap<TileGameplay>,
+}
+
+/// Base (foot, cavalry) cost and cover for a terrain class; None = impassable for cavalry
+fn terrain_profile(category: BiomeCategory) -> (f32, Option<f32>, u8) {
+    match category {
+        BiomeCategory::Grassland => (1.0, Some(0.7), 10),
+        BiomeCategory::Coastal => (1.2, Some(1.0), 10),
+        BiomeCategory::Desert => (1.4, Some(1.2), 5),
+        BiomeCategory::Tundra => (1.4, Some(1.5), 10),
+        BiomeCategory::Hills => (1.5, Some(1.5), 40),
+        BiomeCategory::Ruins => (1.3, Some(1.5), 55),
+        BiomeCategory::Mystical => (1.5, Some(1.8), 35),
+        BiomeCategory::Forest => (1.6, Some(2.2), 60),
+        BiomeCategory::Cave => (2.0, Some(3.0), 80),
+        BiomeCategory::Volcanic => (2.2, Some(3.0), 30),
+        BiomeCategory::Swamp => (2.5, None, 45),
+        BiomeCategory::Mountain => (3.0, Some(5.0), 70),
+        BiomeCategory::Ocean => (1.0, Some(1.0), 0),
+    }
+}
+
+/// Base supply attrition (percent per turn) for a terrain class
+fn base_attrition(category: BiomeCategory) -> f32 {
+    match category {
+        BiomeCategory::Grassland | BiomeCategory::Coastal => 2.0,
+        BiomeCategory::Forest | BiomeCategory::Hills | BiomeCategory::Ruins => 5.0,
+        BiomeCategory::Mystical | BiomeCategory::Ocean => 8.0,
+        BiomeCategory::Cave => 15.0,
+        BiomeCategory::Swamp => 20.0,
+        BiomeCategory::Tundra | BiomeCategory::Mountain => 25.0,
+        BiomeCategory::Desert => 30.0,
+        BiomeCategory::Volcanic => 35.0,
+    }
+}
+
+fn encode_cost(cost: Option<f32>) -> u8 {
+    match cost {
+        Some(c) => (c * COST_SCALE).round().clamp(1.0, (IMPASSABLE - 1) as f32) as u8,
+        None => IMPASSABLE,
+    }
+}
+
+/// Derive the gameplay layer from a generated world
+pub fn generate_gameplay(world: &WorldData) -> GameplayMap {
+    let width = world.width;
+    let height = world.height;
+
+    let rivers = world
+        .river_network
+        .as_ref()
+        .map(|network| rasterize_river_network(network, width, height));
+    // Outlet rivers carry boats from lake to lake even where the traced network misses them
+    let outlets = world.lakes.as_ref().map(|graph| graph.outlet_map(width, height));
+
+    let mut settlements = Tilemap::new_with(width, height, false);
+    let mut trade_routes = Tilemap::new_with(width, height, false);
+    if let Some(history) = world.history.as_ref() {
+        for settlement in history.territories.settlements.values() {
+            if settlement.abandoned.is_none() {
+                settlements.set(settlement.x, settlement.y, true);
+            }
+        }
+        for route in history.trade.routes.values() {
+            for &(x, y) in &route.path {
+                trade_routes.set(x, y, true);
+            }
+        }
+    }
+
+    let mut tiles = Tilemap::new_with(width, height, TileGameplay::default());
+    for (x, y, &elevation) in world.heightmap.iter() {
+        let biome = *world.biomes.get(x, y);
+        let category = categorize_biome(biome);
+        let temperature = *world.temperature.get(x, y);
+        let moisture = *world.moisture.get(x, y);
+
+        let water = elevation <= 0.0
+            || is_water_biome(biome)
+            || *world.water_body_map.get(x, y) != WaterBodyId::NONE;
+        let road = *trade_routes.get(x, y) || has_road(world, x, y);
+        let river = !water
+            && (rivers.as_ref().is_some_and(|r| *r.get(x, y) > 0.0) || outlets.as_ref().is_some_and(|o| *o.get(x, y)));
+        let settlement = *settlements.get(x, y);
+
+        // Steepest drop to a 4-neighbour, in meters
+        let relief = world
+            .heightmap
+            .neighbors(x, y)
+            .iter()
+            .map(|&(nx, ny)| (world.heightmap.get(nx, ny).max(0.0) - elevation.max(0.0)).abs())
+            .fold(0.0f32, f32::max);
+
+        let (mut foot, mut cavalry, cover) = if water {
+            match biome {
+                // Frozen sea can be crossed on foot, slowly
+                ExtendedBiome::PackIce => (Some(3.0), Some(4.0), 5),
+                _ => (None, None, 0),
+            }
+        } else {
+            let (foot, cavalry, cover) = terrain_profile(category);
+            let foot = foot * (1.0 + relief / 1000.0);
+            let cavalry = cavalry.filter(|_| relief < 2500.0).map(|c| c * (1.0 + relief / 600.0));
+            let cover = (cover as f32 + (relief / 50.0).min(20.0)).min(100.0) as u8;
+            (Some(foot), cavalry, cover)
+        };
+
+        if road {
+            foot = foot.map(|c| (c * 0.4).max(0.5)).or(Some(0.5));
+            cavalry = cavalry.map(|c| (c * 0.3).max(0.4)).or(Some(0.4));
+        } else if river {
+            // Fording without a bridge
+            foot = foot.map(|c| c + 1.0);
+            cavalry = cavalry.map(|c| c + 1.5);
+        }
+
+        let boat = if water {
+            match biome {
+                ExtendedBiome::PackIce | ExtendedBiome::LavaLake | ExtendedBiome::AcidLake => None,
+                ExtendedBiome::Polynya => Some(1.5),
+                _ => Some(1.0),
+            }
+        } else if river {
+            Some(2.0)
+        } else {
+            None
+        };
+
+        let mut attrition = if water { base_attrition(BiomeCategory::Ocean) } else { base_attrition(category) };
+        if temperature < -10.0 {
+            attrition += 20.0;
+        } else if temperature > 35.0 {
+            attrition += 15.0;
+        }
+        if !water && moisture < 0.15 {
+            attrition += 10.0;
+        }
+        if river {
+            attrition -= 5.0;
+        }
+        if road {
+            attrition *= 0.5;
+        }
+        if settlement {
+            attrition = 0.0;
+        }
+
+        let mut flags = 0;
+        for (set, flag) in [
+            (foot.is_some(), FLAG_FOOT),
+            (cavalry.is_some(), FLAG_CAVALRY),
+            (boat.is_some(), FLAG_BOAT),
+            (road, FLAG_ROAD),
+            (river, FLAG_RIVER),
+            (water, FLAG_WATER),
+            (settlement, FLAG_SETTLEMENT),
+        ] {
+            if set {
+                flags |= flag;
+            }
+        }
+
+        tiles.set(x, y, TileGameplay {
+            move_foot: encode_cost(foot),
+            move_cavalry: encode_cost(cavalry),
+            move_boat: encode_cost(boat),
+            flags,
+            cover,
+            attrition: attrition.clamp(0.0, 100.0) as u8,
+        });
+    }
+
+    GameplayMap { tiles }
+}
+
+/// Whether the surface of a tile (or a bridge above water) is a carved road
+fn has_road(world: &WorldData, x: usize, y: usize) -> bool {
+    let surface = *world.surface_z.get(x, y);
+    (surface..=surface.max(SEA_LEVEL_Z)).any(|z| world.zlevels.get(x, y, z).is_road())
+}
+
+/// Layout description written next to the binary grid
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct GameplaySchema {
+    pub format: String,
+    pub version: u8,
+    pub width: usize,
+    pub height: usize,
+    /// Bytes before the first record
+    pub header_size: usize,
+    pub record_size: usize,
+    /// Records are stored row-major, north row first
+    pub order: String,
+    pub fields: Vec<SchemaField>,
+    pub flags: Vec<SchemaField>,
+    pub cost_scale: f32,
+    pub impassable: u8,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SchemaField {
+    pub name: String,
+    /// Byte offset within a record, or bit value for flags
+    pub offset: u32,
+    pub description: String,
+}
+
+impl GameplayMap {
+    pub fn get(&self, x: usize, y: usize) -> TileGameplay {
+        *self.tiles.get(x, y)
+    }
+
+    /// Header: magic, version, width and height (u32 little-endian)
+    fn header(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(13);
+        out.extend_from_slice(MAGIC);
+        out.push(FORMAT_VERSION);
+        out.extend_from_slice(&(self.tiles.width as u32).to_le_bytes());
+        out.extend_from_slice(&(self.tiles.height as u32).to_le_bytes());
+        out
+    }
+
+    /// Compact binary grid: header followed by one record per tile
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.header();
+        out.reserve(self.tiles.width * self.tiles.height * RECORD_SIZE);
+        for (_, _, tile) in self.tiles.iter() {
+            out.extend_from_slice(&tile.to_bytes());
+        }
+        out
+    }
+
+    /// Parse a grid written by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 13 || &bytes[0..4] != MAGIC {
+            return Err("not a gameplay layer file".to_string());
+        }
+        if bytes[4] != FORMAT_VERSION {
+            return Err(format!("unsupported gameplay layer version {}", bytes[4]));
+        }
+        let width = u32::from_le_bytes(bytes[5..9].try_into().unwrap()) as usize;
+        let height = u32::from_le_bytes(bytes[9..13].try_into().unwrap()) as usize;
+        let records = &bytes[13..];
+        if records.len() != width * height * RECORD_SIZE {
+            return Err("truncated gameplay layer".to_string());
+        }
+
+        let mut tiles = Tilemap::new_with(width, height, TileGameplay::default());
+        for (i, r) in records.chunks_exact(RECORD_SIZE).enumerate() {
+            tiles.set(i % width, i / width, TileGameplay {
+                move_foot: r[0],
+                move_cavalry: r[1],
+                move_boat: r[2],
+                flags: r[3],
+                cover: r[4],
+                attrition: r[5],
+            });
+        }
+        Ok(Self { tiles })
+    }
+
+    pub fn schema(&self) -> GameplaySchema {
+        let field = |name: &str, offset: u32, description: &str| SchemaField {
+            name: name.to_string(),
+            offset,
+            description: description.to_string(),
+        };
+        GameplaySchema {
+            format: "TDGP".to_string(),
+            version: FORMAT_VERSION,
+            width: self.tiles.width,
+            height: self.tiles.height,
+            header_size: self.header().len(),
+            record_size: RECORD_SIZE,
+            order: "row-major, y = 0 is the north edge, x wraps east-west".to_string(),
+            fields: vec![
+                field("move_foot", 0, "u8 movement cost on foot"),
+                field("move_cavalry", 1, "u8 movement cost for mounted units"),
+                field("move_boat", 2, "u8 movement cost by boat (sea, lakes and rivers)"),
+                field("flags", 3, "u8 bit set, see flags"),
+                field("cover", 4, "u8 defensive cover, 0-100"),
+                field("attrition", 5, "u8 supply lost per turn, percent"),
+            ],
+            flags: vec![
+                field("foot", FLAG_FOOT as u32, "passable on foot"),
+                field("cavalry", FLAG_CAVALRY as u32, "passable for cavalry"),
+                field("boat", FLAG_BOAT as u32, "navigable by boat"),
+                field("road", FLAG_ROAD as u32, "road or bridge"),
+                field("river", FLAG_RIVER as u32, "river crossing on land"),
+                field("water", FLAG_WATER as u32, "sea, lake or frozen sea"),
+                field("settlement", FLAG_SETTLEMENT as u32, "inhabited settlement"),
+            ],
+            cost_scale: COST_SCALE,
+            impassable: IMPASSABLE,
+        }
+    }
+
+    /// Write `PREFIX.bin` and `PREFIX.json` (schema). Returns the written paths.
+    pub fn export(&self, prefix: &str) -> std::io::Result<Vec<String>> {
+        let bin_path = format!("{}.bin", prefix);
+        std::fs::File::create(&bin_path)?.write_all(&self.to_bytes())?;
+
+        let schema_path = format!("{}.json", prefix);
+        let schema = serde_json::to_string_pretty(&self.schema()).expect("schema is always serializable");
+        std::fs::write(&schema_path, schema)?;
+
+        Ok(vec![bin_path, schema_path])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::generate_test_world;
+    use crate::zlevel::ZTile;
+
+    #[test]
+    fn test_costs_and_round_trip() {
+        let mut world = generate_test_world();
+        world.heightmap.set(3, 0, -200.0);
+        world.biomes.set(3, 0, ExtendedBiome::Ocean);
+        world.biomes.set(0, 3, ExtendedBiome::Swamp);
+        let z = *world.surface_z.get(1, 1);
+        world.zlevels.set(1, 1, z, ZTile::StoneRoad);
+
+        let map = generate_gameplay(&world);
+
+        let plain = map.get(2, 2);
+        assert_eq!(plain.cost(Locomotion::Foot), Some(1.0));
+        assert!(plain.cost(Locomotion::Cavalry).unwrap() < 1.0);
+        assert_eq!(plain.cost(Locomotion::Boat), None);
+
+        let sea = map.get(3, 0);
+        assert!(sea.has(FLAG_WATER) && sea.has(FLAG_BOAT) && !sea.has(FLAG_FOOT));
+
+        let swamp = map.get(0, 3);
+        assert_eq!(swamp.cost(Locomotion::Cavalry), None);
+        assert!(swamp.cover > plain.cover);
+
+        let road = map.get(1, 1);
+        assert!(road.has(FLAG_ROAD));
+        assert!(road.cost(Locomotion::Foot).unwrap() < 1.0);
+
+        let parsed = GameplayMap::from_bytes(&map.to_bytes()).unwrap();
+        assert_eq!(parsed.get(0, 3), swamp);
+        assert_eq!(parsed.get(1, 1), road);
+        assert!(GameplayMap::from_bytes(b"nope").is_err());
+    }
+}
@@ -0,0 +1,420 @@
+//! Water body naming and gazetteer export
+//!
+//! Gives every ocean, sea, major lake and river a proper name in the language of the
+//! nearest faction, stores the names on the water body structs and collects them into a
+//! gazetteer (name, type, size, coordinates) that can be exported as CSV or JSON.
+
+use std::collections::{HashSet, VecDeque};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::history::{NameGenerator, Species, WorldHistory};
+use crate::tilemap::Tilemap;
+use crate::water_bodies::{WaterBody, WaterBodyId, WaterBodyType};
+
+/// Ocean tiles shallower than this (meters) count as continental shelf
+const SHELF_DEPTH: f32 = -500.0;
+
+/// Minimum shelf region size (tiles) to be named as a sea
+const MIN_SEA_TILES: usize = 40;
+
+/// Minimum lake size (tiles) to be named
+const MIN_LAKE_TILES: usize = 4;
+
+/// Minimum river length (tiles) to be named
+const MIN_RIVER_TILES: usize = 12;
+
+/// Attempts at drawing a name that isn't already taken
+const NAME_ATTEMPTS: usize = 8;
+
+// =============================================================================
+// GAZETTEER TYPES
+// =============================================================================
+
+/// Kind of named water feature
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FeatureKind {
+    Ocean,
+    Sea,
+    Lake,
+    River,
+}
+
+impl FeatureKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeatureKind::Ocean => "ocean",
+            FeatureKind::Sea => "sea",
+            FeatureKind::Lake => "lake",
+            FeatureKind::River => "river",
+        }
+    }
+}
+
+/// A single named feature
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct GazetteerEntry {
+    pub name: String,
+    pub kind: FeatureKind,
+    /// Water body this feature belongs to
+    pub water_body: u16,
+    /// Surface area in tiles
+    pub area: usize,
+    /// Length in tiles (rivers only, 0 otherwise)
+    pub length: usize,
+    /// Representative tile (centre for bodies of water, mouth for rivers)
+    pub x: usize,
+    pub y: usize,
+    /// Faction whose language named the feature
+    pub named_by: Option<String>,
+}
+
+/// All named water features of a world
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct Gazetteer {
+    pub entries: Vec<GazetteerEntry>,
+}
+
+impl Gazetteer {
+    /// Entries of one kind
+    pub fn of_kind(&self, kind: FeatureKind) -> impl Iterator<Item = &GazetteerEntry> {
+        self.entries.iter().filter(move |e| e.kind == kind)
+    }
+
+    /// Render the gazetteer as CSV (one row per feature)
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("name,type,area_tiles,length_tiles,x,y,named_by\n");
+        for e in &self.entries {
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                csv_field(&e.name),
+                e.kind.name(),
+                e.area,
+                e.length,
+                e.x,
+                e.y,
+                csv_field(e.named_by.as_deref().unwrap_or("")),
+            ));
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Gazetteer is always serializable")
+    }
+
+    /// Write the gazetteer to disk; `.json` paths get JSON, anything else CSV
+    pub fn export(&self, path: &str) -> std::io::Result<()> {
+        if path.to_ascii_lowercase().ends_with(".json") {
+            std::fs::write(path, self.to_json())
+        } else {
+            std::fs::write(path, self.to_csv())
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// =============================================================================
+// NAMING
+// =============================================================================
+
+/// Picks names in the tongue of the faction settled nearest to a feature
+#[cfg_attr(not(feature = "history"), allow(dead_code))]
+struct Namer<'a> {
+    history: Option<&'a WorldHistory>,
+    name_gen: NameGenerator,
+    rng: ChaCha8Rng,
+    used: HashSet<String>,
+    width: usize,
+}
+
+impl Namer<'_> {
+    /// Species and faction name of the settlement closest to (x, y)
+    #[cfg(feature = "history")]
+    fn culture_at(&self, x: usize, y: usize) -> (Species, Option<String>) {
+        let Some(history) = self.history else {
+            return (Species::Human, None);
+        };
+        let nearest = history
+            .territories
+            .settlements
+            .values()
+            .min_by_key(|s| (wrapped_dist_sq(self.width, (s.x, s.y), (x, y)), s.id.0));
+        let faction = nearest.and_then(|s| {
+            history.factions.get(s.current_faction.unwrap_or(s.original_faction))
+        });
+        match faction {
+            Some(f) => (f.species, Some(f.name.clone())),
+            None => (Species::Human, None),
+        }
+    }
+
+    /// Without history every feature is named in the human tongue
+    #[cfg(not(feature = "history"))]
+    fn culture_at(&self, _: usize, _: usize) -> (Species, Option<String>) {
+        (Species::Human, None)
+    }
+
+    fn name(&mut self, kind: FeatureKind, x: usize, y: usize) -> (String, Option<String>) {
+        let (species, faction) = self.culture_at(x, y);
+        let mut name = self.name_gen.water_name(kind.name(), species, &mut self.rng);
+        for _ in 0..NAME_ATTEMPTS {
+            if !self.used.contains(&name) {
+                break;
+            }
+            name = self.name_gen.water_name(kind.name(), species, &mut self.rng);
+        }
+        if self.used.contains(&name) {
+            // Still taken: disambiguate with an ordinal
+            let base = name.clone();
+            let mut n = 2;
+            while self.used.contains(&name) {
+                name = format!("{} {}", base, roman(n));
+                n += 1;
+            }
+        }
+        self.used.insert(name.clone());
+        (name, faction)
+    }
+}
+
+fn roman(n: usize) -> String {
+    const NUMERALS: [(usize, &str); 4] = [(10, "X"), (9, "IX"), (5, "V"), (4, "IV")];
+    let mut n = n;
+    let mut out = String::new();
+    for &(value, numeral) in &NUMERALS {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out.push_str(&"I".repeat(n));
+    out
+}
+
+fn wrapped_dist_sq(width: usize, a: (usize, usize), b: (usize, usize)) -> usize {
+    let dx = a.0.abs_diff(b.0);
+    let dx = dx.min(width - dx);
+    let dy = a.1.abs_diff(b.1);
+    dx * dx + dy * dy
+}
+
+/// Tile of a region closest to its (horizontally wrapped) centroid
+fn region_center(width: usize, tiles: &[(usize, usize)]) -> (usize, usize) {
+    let n = tiles.len() as f32;
+    let (mut sin, mut cos, mut sy) = (0.0f32, 0.0f32, 0.0f32);
+    for &(x, y) in tiles {
+        let angle = x as f32 / width as f32 * std::f32::consts::TAU;
+        sin += angle.sin();
+        cos += angle.cos();
+        sy += y as f32;
+    }
+    let mean_x = (sin.atan2(cos).rem_euclid(std::f32::consts::TAU) / std::f32::consts::TAU * width as f32) as usize;
+    let mean = (mean_x.min(width - 1), (sy / n) as usize);
+    *tiles
+        .iter()
+        .min_by_key(|&&t| wrapped_dist_sq(width, t, mean))
+        .expect("region is never empty")
+}
+
+/// Connected components of tiles matching `keep`, largest first
+fn components(
+    width: usize,
+    height: usize,
+    keep: impl Fn(usize, usize) -> bool,
+    diagonal: bool,
+) -> Vec<Vec<(usize, usize)>> {
+    let mut seen = Tilemap::new_with(width, height, false);
+    let mut regions = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            if *seen.get(x, y) || !keep(x, y) {
+                continue;
+            }
+            let mut region = Vec::new();
+            let mut queue = VecDeque::from([(x, y)]);
+            seen.set(x, y, true);
+            while let Some((cx, cy)) = queue.pop_front() {
+                region.push((cx, cy));
+                let neighbors = if diagonal { seen.neighbors_8(cx, cy) } else { seen.neighbors(cx, cy) };
+                for (nx, ny) in neighbors {
+                    if !*seen.get(nx, ny) && keep(nx, ny) {
+                        seen.set(nx, ny, true);
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+            regions.push(region);
+        }
+    }
+    regions.sort_by_key(|r| std::cmp::Reverse(r.len()));
+    regions
+}
+
+/// Name every ocean, sea, major lake and river and build the gazetteer.
+///
+/// Names are written onto the matching `WaterBody` structs (the river body takes the
+/// name of its longest river). Seas are continental-shelf regions of the ocean and
+/// exist only in the gazetteer. Without a history every feature is named in the
+/// human tongue.
+pub fn name_water_bodies(
+    heightmap: &Tilemap<f32>,
+    water_map: &Tilemap<WaterBodyId>,
+    water_bodies: &mut [WaterBody],
+    history: Option<&WorldHistory>,
+    seed: u64,
+) -> Gazetteer {
+    let width = heightmap.width;
+    let height = heightmap.height;
+    let mut namer = Namer {
+        history,
+        name_gen: NameGenerator::new(seed),
+        rng: ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x6A2E77E5)),
+        used: HashSet::new(),
+        width,
+    };
+    let mut gazetteer = Gazetteer::default();
+
+    for body in water_bodies.iter_mut() {
+        let tiles: Vec<(usize, usize)> = water_map
+            .iter()
+            .filter(|&(_, _, id)| *id == body.id)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        if tiles.is_empty() {
+            continue;
+        }
+
+        match body.body_type {
+            WaterBodyType::Ocean => {
+                let (x, y) = region_center(width, &tiles);
+                let (name, named_by) = namer.name(FeatureKind::Ocean, x, y);
+                body.name = Some(name.clone());
+                gazetteer.entries.push(GazetteerEntry {
+                    name, kind: FeatureKind::Ocean, water_body: body.id.0,
+                    area: tiles.len(), length: 0, x, y, named_by,
+                });
+
+                // Seas: shelf regions of this ocean, unless the shelf is most of the ocean
+                let shelves = components(width, height, |sx, sy| {
+                    *water_map.get(sx, sy) == body.id && *heightmap.get(sx, sy) >= SHELF_DEPTH
+                }, false);
+                for shelf in shelves.iter().filter(|s| s.len() >= MIN_SEA_TILES && s.len() * 2 < tiles.len()) {
+                    let (x, y) = region_center(width, shelf);
+                    let (name, named_by) = namer.name(FeatureKind::Sea, x, y);
+                    gazetteer.entries.push(GazetteerEntry {
+                        name, kind: FeatureKind::Sea, water_body: body.id.0,
+                        area: shelf.len(), length: 0, x, y, named_by,
+                    });
+                }
+            }
+            WaterBodyType::Lake if tiles.len() >= MIN_LAKE_TILES => {
+                let (x, y) = region_center(width, &tiles);
+                let (name, named_by) = namer.name(FeatureKind::Lake, x, y);
+                body.name = Some(name.clone());
+                gazetteer.entries.push(GazetteerEntry {
+                    name, kind: FeatureKind::Lake, water_body: body.id.0,
+                    area: tiles.len(), length: 0, x, y, named_by,
+                });
+            }
+            WaterBodyType::River => {
+                // The river body holds every river tile; split it into individual rivers
+                let rivers = components(width, height, |rx, ry| *water_map.get(rx, ry) == body.id, true);
+                for river in rivers.iter().filter(|r| r.len() >= MIN_RIVER_TILES) {
+                    let &(x, y) = river
+                        .iter()
+                        .min_by(|a, b| heightmap.get(a.0, a.1).total_cmp(heightmap.get(b.0, b.1)))
+                        .expect("river is never empty");
+                    let (name, named_by) = namer.name(FeatureKind::River, x, y);
+                    if body.name.is_none() {
+                        body.name = Some(name.clone());
+                    }
+                    gazetteer.entries.push(GazetteerEntry {
+                        name, kind: FeatureKind::River, water_body: body.id.0,
+                        area: river.len(), length: river.len(), x, y, named_by,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    gazetteer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::water_bodies::detect_water_bodies_with_flow;
+
+    fn test_world() -> (Tilemap<f32>, Tilemap<f32>) {
+        let (w, h) = (64, 32);
+        let mut heightmap = Tilemap::new_with(w, h, -2000.0f32);
+        let mut flow = Tilemap::new_with(w, h, 0.0f32);
+        for y in 6..26 {
+            for x in 10..50 {
+                heightmap.set(x, y, 300.0);
+            }
+        }
+        // Shallow shelf west of the continent
+        for y in 4..28 {
+            for x in 2..10 {
+                heightmap.set(x, y, -100.0);
+            }
+        }
+        // Inland lake and a river running south from it
+        for y in 10..13 {
+            for x in 20..24 {
+                heightmap.set(x, y, -10.0);
+            }
+        }
+        for y in 13..26 {
+            heightmap.set(30, y, 300.0 - y as f32);
+            flow.set(30, y, 100.0);
+        }
+        (heightmap, flow)
+    }
+
+    #[test]
+    fn test_names_every_feature_kind() {
+        let (heightmap, flow) = test_world();
+        let (water_map, mut bodies) = detect_water_bodies_with_flow(&heightmap, Some(&flow));
+        let gazetteer = name_water_bodies(&heightmap, &water_map, &mut bodies, None, 7);
+
+        for kind in [FeatureKind::Ocean, FeatureKind::Sea, FeatureKind::Lake, FeatureKind::River] {
+            assert_eq!(gazetteer.of_kind(kind).count(), 1, "expected one {:?}", kind);
+        }
+        let river = gazetteer.of_kind(FeatureKind::River).next().unwrap();
+        assert_eq!(river.length, 13);
+        assert_eq!((river.x, river.y), (30, 25));
+
+        // Names are stored on the bodies and are unique
+        assert!(bodies.iter().all(|b| b.name.is_some()));
+        let names: HashSet<&String> = gazetteer.entries.iter().map(|e| &e.name).collect();
+        assert_eq!(names.len(), gazetteer.entries.len());
+    }
+
+    #[test]
+    fn test_gazetteer_export_formats() {
+        let (heightmap, flow) = test_world();
+        let (water_map, mut bodies) = detect_water_bodies_with_flow(&heightmap, Some(&flow));
+        let gazetteer = name_water_bodies(&heightmap, &water_map, &mut bodies, None, 7);
+
+        let csv = gazetteer.to_csv();
+        assert!(csv.starts_with("name,type,area_tiles,length_tiles,x,y,named_by"));
+        assert_eq!(csv.lines().count(), gazetteer.entries.len() + 1);
+
+        let parsed: Gazetteer = serde_json::from_str(&gazetteer.to_json()).unwrap();
+        assert_eq!(parsed.entries.len(), gazetteer.entries.len());
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(roman(4), "IV");
+    }
+}
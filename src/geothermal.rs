@@ -0,0 +1,272 @@
+//! Depth temperature model
+//!
+//! Temperature at any (x, y, z): the surface climate at the ground, cooling
+//! with altitude above it and warming along a geothermal gradient below it.
+//! The gradient is steeper under tectonically stressed crust and near magma,
+//! and the sea floor starts from cold bottom water instead of the air above.
+//!
+//! The model drives the underground: cave biomes read their heat from it, cave
+//! lakes freeze where the rock stays below zero, embark sites warn of magma
+//! close beneath them, and people living underground thrive only where the
+//! rock around their halls is comfortable.
+
+use crate::history::types::ArchitectureStyle;
+#[cfg(feature = "history")]
+use crate::history::types::SettlementState;
+#[cfg(feature = "history")]
+use crate::history::WorldHistory;
+use crate::tilemap::Tilemap;
+use crate::zlevel::{Tilemap3D, ZTile, FLOOR_HEIGHT, MAX_Z, MIN_Z};
+
+/// Z-levels below the surface where underground peoples build their halls
+pub const DWELLING_DEPTH: i32 = 2;
+
+/// Parameters of the depth temperature model
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct GeothermalConfig {
+    /// Rock warming (°C per km below the ground) on quiet crust
+    pub gradient: f32,
+    /// Extra gradient per unit of tectonic stress (either sign), as a fraction of `gradient`
+    pub stress_heating: f32,
+    /// Extra gradient (°C per km) right next to magma, fading out over `magma_radius`
+    pub magma_heating: f32,
+    /// Furthest (tiles) magma warms the rock around it
+    pub magma_radius: usize,
+    /// Air cooling (°C per km) above the ground
+    pub lapse_rate: f32,
+    /// Water temperature (°C) at the sea floor
+    pub sea_floor_temperature: f32,
+    /// Temperature range (°C) people live in comfortably
+    pub comfort_range: (f32, f32),
+    /// Degrees outside the comfort range at which comfort reaches zero
+    pub comfort_falloff: f32,
+}
+
+impl Default for GeothermalConfig {
+    fn default() -> Self {
+        Self {
+            gradient: 15.0,
+            stress_heating: 1.5,
+            magma_heating: 40.0,
+            magma_radius: 4,
+            lapse_rate: 6.5,
+            sea_floor_temperature: 4.0,
+            comfort_range: (10.0, 24.0),
+            comfort_falloff: 25.0,
+        }
+    }
+}
+
+/// Depth temperatures of a world
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct GeothermalMap {
+    /// Temperature (°C) at the ground: surface climate on land, bottom water at sea
+    pub ground: Tilemap<f32>,
+    /// Ground elevation (m)
+    pub elevation: Tilemap<f32>,
+    /// Geothermal gradient (°C per km)
+    pub gradient: Tilemap<f32>,
+    /// Shallowest magma z-level within `magma_radius` of each tile
+    pub magma_z: Tilemap<Option<i32>>,
+    pub config: GeothermalConfig,
+}
+
+impl GeothermalMap {
+    /// Temperature (°C) at the middle of a z-level
+    pub fn temperature_at(&self, x: usize, y: usize, z: i32) -> f32 {
+        let level = (z as f32 + 0.5) * FLOOR_HEIGHT;
+        let ground = *self.elevation.get(x, y);
+        if level >= ground {
+            // Above the ground: water keeps the bottom temperature, air cools with altitude
+            let above = (level - ground.max(0.0)).max(0.0);
+            self.ground.get(x, y) - above / 1000.0 * self.config.lapse_rate
+        } else {
+            self.ground.get(x, y) + (ground - level) / 1000.0 * self.gradient.get(x, y)
+        }
+    }
+
+    /// Whether water at a z-level stays frozen
+    pub fn is_frozen(&self, x: usize, y: usize, z: i32) -> bool {
+        self.temperature_at(x, y, z) <= 0.0
+    }
+
+    /// How comfortable (0-1) people find the temperature at a z-level
+    pub fn comfort_at(&self, x: usize, y: usize, z: i32) -> f32 {
+        comfort(self.temperature_at(x, y, z), &self.config)
+    }
+
+    /// Warning for embark sites with magma close beneath them
+    pub fn magma_warning(&self, x: usize, y: usize) -> Option<String> {
+        let magma_z = (*self.magma_z.get(x, y))?;
+        let depth = (self.elevation.get(x, y).max(0.0) - magma_z as f32 * FLOOR_HEIGHT).max(0.0);
+        Some(format!("Magma at z{} (~{:.0} m down)", magma_z, depth))
+    }
+
+    /// Short description for tile info panels
+    pub fn describe(&self, x: usize, y: usize, z: i32) -> String {
+        format!("{:.1}°C", self.temperature_at(x, y, z))
+    }
+}
+
+/// Comfort (0-1) of a temperature: 1 inside the comfort range, falling to 0
+/// `comfort_falloff` degrees outside it
+pub fn comfort(temperature: f32, config: &GeothermalConfig) -> f32 {
+    let (low, high) = config.comfort_range;
+    let outside = (low - temperature).max(temperature - high).max(0.0);
+    (1.0 - outside / config.comfort_falloff).clamp(0.0, 1.0)
+}
+
+/// Build the depth temperature model from the climate, crust stress and the
+/// magma the caves exposed
+pub fn generate_geothermal(
+    heightmap: &Tilemap<f32>,
+    temperature: &Tilemap<f32>,
+    stress_map: &Tilemap<f32>,
+    zlevels: &Tilemap3D<ZTile>,
+    config: &GeothermalConfig,
+) -> GeothermalMap {
+    let (width, height) = (heightmap.width, heightmap.height);
+
+    // Shallowest magma in each column
+    let mut column_magma = Tilemap::new_with(width, height, None);
+    for y in 0..height {
+        for x in 0..width {
+            let magma = (MIN_Z..=MAX_Z)
+                .rev()
+                .find(|&z| matches!(zlevels.get(x, y, z), ZTile::MagmaPool | ZTile::MagmaTube));
+            column_magma.set(x, y, magma);
+        }
+    }
+
+    let radius = config.magma_radius as i32;
+    let mut ground = Tilemap::new_with(width, height, 0.0f32);
+    let mut gradient = Tilemap::new_with(width, height, config.gradient);
+    let mut magma_z = Tilemap::new_with(width, height, None);
+    for y in 0..height {
+        for x in 0..width {
+            let elevation = *heightmap.get(x, y);
+            ground.set(x, y, if elevation < 0.0 { config.sea_floor_temperature } else { *temperature.get(x, y) });
+
+            // Nearest magma warms the rock most; the shallowest sets the warning
+            let mut nearest: Option<f32> = None;
+            let mut shallowest: Option<i32> = None;
+            for dy in -radius..=radius {
+                let ny = y as i32 + dy;
+                if ny < 0 || ny >= height as i32 {
+                    continue;
+                }
+                for dx in -radius..=radius {
+                    let (nx, ny) = heightmap.wrap_coords(x as i32 + dx, ny);
+                    if let Some(z) = *column_magma.get(nx, ny) {
+                        let distance = ((dx * dx + dy * dy) as f32).sqrt();
+                        if distance <= radius as f32 {
+                            nearest = Some(nearest.map_or(distance, |d| d.min(distance)));
+                            shallowest = Some(shallowest.map_or(z, |s| s.max(z)));
+                        }
+                    }
+                }
+            }
+            let magma_heat = nearest.map_or(0.0, |d| config.magma_heating * (1.0 - d / (radius as f32 + 1.0)));
+            let stress_heat = config.gradient * config.stress_heating * stress_map.get(x, y).abs();
+            gradient.set(x, y, config.gradient + stress_heat + magma_heat);
+            magma_z.set(x, y, shallowest);
+        }
+    }
+
+    GeothermalMap { ground, elevation: heightmap.clone(), gradient, magma_z, config: config.clone() }
+}
+
+/// Freeze cave lakes in rock that stays below zero; returns how many froze
+pub fn freeze_cave_lakes(zlevels: &mut Tilemap3D<ZTile>, geothermal: &GeothermalMap) -> usize {
+    let mut frozen = 0;
+    for z in MIN_Z..=MAX_Z {
+        for y in 0..zlevels.height {
+            for x in 0..zlevels.width {
+                if *zlevels.get(x, y, z) == ZTile::CaveLake && geothermal.is_frozen(x, y, z) {
+                    zlevels.set(x, y, z, ZTile::FrozenCaveLake);
+                    frozen += 1;
+                }
+            }
+        }
+    }
+    frozen
+}
+
+/// Whether a people with this architecture builds its halls underground
+pub fn dwells_underground(architecture: ArchitectureStyle) -> bool {
+    matches!(architecture, ArchitectureStyle::Underground | ArchitectureStyle::Dwarven)
+}
+
+/// Scale underground settlements by how comfortable the rock around their
+/// halls is: uncomfortable halls hold fewer people and the worst of them
+/// decline. Returns how many settlements were affected.
+#[cfg(feature = "history")]
+pub fn apply_underground_comfort(history: &mut WorldHistory, geothermal: &GeothermalMap, surface_z: &Tilemap<i32>) -> usize {
+    let mut affected = 0;
+    for settlement in history.territories.settlements.values_mut() {
+        if !dwells_underground(settlement.architecture) || !settlement.is_active() {
+            continue;
+        }
+        let (x, y) = (settlement.x, settlement.y);
+        let hall_z = (surface_z.get(x, y) - DWELLING_DEPTH).max(MIN_Z);
+        let comfort = geothermal.comfort_at(x, y, hall_z);
+        if comfort >= 1.0 {
+            continue;
+        }
+        settlement.peak_population = ((settlement.peak_population as f32) * (0.25 + 0.75 * comfort)).round() as u32;
+        if comfort < 0.25 && settlement.state == SettlementState::Thriving {
+            settlement.state = SettlementState::Declining;
+        }
+        affected += 1;
+    }
+    affected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temperature_grades_from_climate_to_depth() {
+        let mut heightmap = Tilemap::new_with(12, 6, 500.0f32);
+        heightmap.set(0, 0, -1000.0);
+        let temperature = Tilemap::new_with(12, 6, -20.0f32);
+        let stress = Tilemap::new_with(12, 6, 0.0f32);
+        let mut zlevels = Tilemap3D::new_with(12, 6, MIN_Z, MAX_Z, ZTile::Solid);
+        zlevels.set(10, 3, -12, ZTile::MagmaPool);
+
+        let config = GeothermalConfig::default();
+        let geo = generate_geothermal(&heightmap, &temperature, &stress, &zlevels, &config);
+
+        // Cold at the ground, frozen just below it, warm deep down
+        assert!((geo.temperature_at(3, 3, 1) - (-20.0 + 0.125 * config.gradient)).abs() < 1e-3);
+        assert!(geo.is_frozen(3, 3, -1));
+        assert!(!geo.is_frozen(3, 3, MIN_Z));
+        assert!(geo.temperature_at(3, 3, -8) > geo.temperature_at(3, 3, -4));
+        // Higher up the air is colder still
+        assert!(geo.temperature_at(3, 3, 8) < -20.0);
+        // The sea floor starts from bottom water
+        assert!((geo.temperature_at(0, 0, -4) - config.sea_floor_temperature).abs() < 1e-3);
+
+        // Magma steepens the gradient and warns nearby embarks only
+        assert!(geo.temperature_at(9, 3, -8) > geo.temperature_at(3, 3, -8));
+        assert_eq!(geo.magma_z.get(8, 3), &Some(-12));
+        assert!(geo.magma_warning(8, 3).is_some());
+        assert!(geo.magma_warning(4, 3).is_none());
+
+        // Cave lakes freeze only where the rock stays below zero
+        zlevels.set(3, 3, -1, ZTile::CaveLake);
+        zlevels.set(3, 3, -10, ZTile::CaveLake);
+        assert_eq!(freeze_cave_lakes(&mut zlevels, &geo), 1);
+        assert_eq!(*zlevels.get(3, 3, -1), ZTile::FrozenCaveLake);
+        assert_eq!(*zlevels.get(3, 3, -10), ZTile::CaveLake);
+    }
+
+    #[test]
+    fn test_comfort_falls_off_outside_range() {
+        let config = GeothermalConfig::default();
+        assert_eq!(comfort(18.0, &config), 1.0);
+        assert!((comfort(36.5, &config) - 0.5).abs() < 1e-3);
+        assert_eq!(comfort(-40.0, &config), 0.0);
+    }
+}
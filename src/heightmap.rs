@@ -1,6 +1,6 @@
 use noise::{NoiseFn, Perlin, Seedable};
 
-use crate::plates::{Plate, PlateId, PlateType};
+use crate::plates::{Plate, PlateId, PlateType, UpliftHistory};
 use crate::scale::{MapScale, scale_distance, scale_frequency, scale_elevation};
 use crate::tilemap::Tilemap;
 
@@ -423,6 +423,22 @@ const RIDGE_FREQUENCY: f64 = 0.012;      // Ridge spacing (lower = larger featur
 // Tectonic stress multiplier - dramatic boundary mountains
 const TECTONIC_SCALE: f32 = 2000.0;      // Increased for proper mountain ranges
 
+// Drift history relief (see plates::drift)
+const YOUNG_UPLIFT_HEIGHT: f32 = 1800.0; // Fresh collision belts
+const OLD_UPLIFT_HEIGHT: f32 = 600.0;    // Worn-down ancient ranges
+const RIFT_DEPTH: f32 = 500.0;           // Rift floors below their shoulders
+
+// Rift valleys at present divergent continental boundaries (in 512-wide reference tiles)
+const GRABEN_HALF_WIDTH: f32 = 3.0;      // Axis to the outer boundary fault
+const SHOULDER_WIDTH: f32 = 4.0;         // Raised flank beyond the boundary fault
+const GRABEN_DEPTH: f32 = 700.0;         // Floor below the surrounding land
+const SHOULDER_UPLIFT: f32 = 350.0;      // Flank uplift at its crest
+const FAULT_STEPS: f32 = 3.0;            // Fault blocks stepping down to the floor
+const RIFT_FLOOR_MIN: f32 = 10.0;        // Dry rift floors stay above sea level
+const RIFT_LAKE_THRESHOLD: f64 = 0.3;    // Along-axis noise above which a basin holds a lake
+const RIFT_LAKE_DEPTH: f32 = 400.0;      // Deepest rift lake floor (below sea level)
+const RIFT_LAKE_MIN_GROUND: f32 = 150.0; // Only carve lakes into land this high
+
 // Volcanic island parameters (oceanic convergence zones)
 const VOLCANIC_THRESHOLD: f32 = 0.02;    // Very low threshold to ensure islands appear
 const VOLCANIC_BASE: f32 = -500.0;       // Seamount base (underwater)
@@ -442,14 +458,19 @@ const COAST_FRACTAL_SCALE: f64 = 0.15;
 /// 2. Domain warping for natural-looking features
 /// 3. Procedural ridges for internal mountains
 /// 4. Tectonic stress for plate boundary mountains
-/// 5. Smooth blending with continental mask
+/// 5. Uplift history from `plates::simulate_drift`, if any: sharp young
+///    ranges, worn-down old highlands and sunken rifts
+/// 6. Smooth blending with continental mask
+///
+/// Rift valleys are carved separately, by `carve_rift_valleys`.
 pub fn generate_heightmap(
     plate_map: &Tilemap<PlateId>,
     plates: &[Plate],
     stress_map: &Tilemap<f32>,
+    uplift: Option<&UpliftHistory>,
     seed: u64,
 ) -> Tilemap<f32> {
-    generate_heightmap_scaled(plate_map, plates, stress_map, seed, &MapScale::default())
+    generate_heightmap_scaled(plate_map, plates, stress_map, uplift, seed, &MapScale::default())
 }
 
 /// Generate heightmap with explicit scale parameter
@@ -457,8 +478,55 @@ pub fn generate_heightmap_scaled(
     plate_map: &Tilemap<PlateId>,
     plates: &[Plate],
     stress_map: &Tilemap<f32>,
+    uplift: Option<&UpliftHistory>,
+    seed: u64,
+    map_scale: &MapScale,
+) -> Tilemap<f32> {
+    synthesize_heightmap(plate_map, plates, stress_map, uplift, seed, map_scale, 1.0)
+}
+
+/// Map width the heightmap distance thresholds are tuned for
+const INVARIANT_REFERENCE_WIDTH: f32 = 512.0;
+
+/// Generate a heightmap whose macro layout does not depend on resolution.
+///
+/// Tile distances (coastal gradients, smoothing) are measured in tiles of a
+/// 512-wide map, so a 128x64 preview and a 1024x512 render of the same plates
+/// share their coastlines and mountain belts; only fine detail differs.
+/// Pair with `plates::generate_plates_invariant`.
+pub fn generate_heightmap_invariant(
+    plate_map: &Tilemap<PlateId>,
+    plates: &[Plate],
+    stress_map: &Tilemap<f32>,
+    uplift: Option<&UpliftHistory>,
+    seed: u64,
+) -> Tilemap<f32> {
+    synthesize_heightmap(plate_map, plates, stress_map, uplift, seed, &MapScale::default(), invariant_tile_scale(plate_map.width))
+}
+
+/// Reference tiles per map tile for a scale-invariant world of this width
+pub fn invariant_tile_scale(width: usize) -> f32 {
+    INVARIANT_REFERENCE_WIDTH / width as f32
+}
+
+/// Map scale for the climate of a scale-invariant world: distance thresholds
+/// (coastal moisture bands, rain shadows) cover the same share of the map at
+/// any width, measured like `generate_heightmap_invariant` in tiles of a
+/// 512-wide map.
+pub fn invariant_scale(width: usize) -> MapScale {
+    MapScale { distance_scale: width as f32 / INVARIANT_REFERENCE_WIDTH, ..MapScale::default() }
+}
+
+/// Shared heightmap synthesis; `tile_scale` converts this map's tiles into
+/// reference tiles (1.0 for the classic resolution-dependent behaviour)
+fn synthesize_heightmap(
+    plate_map: &Tilemap<PlateId>,
+    plates: &[Plate],
+    stress_map: &Tilemap<f32>,
+    uplift: Option<&UpliftHistory>,
     seed: u64,
     map_scale: &MapScale,
+    tile_scale: f32,
 ) -> Tilemap<f32> {
     let width = plate_map.width;
     let height = plate_map.height;
@@ -489,8 +557,8 @@ pub fn generate_heightmap_scaled(
             
             let plate = &plates[plate_id.0 as usize];
             let stress = *stress_map.get(x, y);
-            let cont_dist = *continental_distance.get(x, y);
-            let raw_coast_dist = *coast_distance.get(x, y);
+            let cont_dist = *continental_distance.get(x, y) * tile_scale;
+            let raw_coast_dist = *coast_distance.get(x, y) * tile_scale;
             
             // Normalize coordinates for noise sampling
             let nx = x as f64 / width as f64;
@@ -548,7 +616,7 @@ pub fn generate_heightmap_scaled(
                     let stress_dy = if y > 0 && y < height - 1 {
                         *stress_map.get(x, y + 1) - *stress_map.get(x, y - 1)
                     } else { 0.0 };
-                    let stress_gradient = (stress_dx, stress_dy);
+                    let stress_gradient = (stress_dx / tile_scale, stress_dy / tile_scale);
 
                     // Use original coordinates for ocean/islands - no domain warping
                     generate_oceanic_elevation(
@@ -564,13 +632,142 @@ pub fn generate_heightmap_scaled(
                     )
                 }
             };
+            let elevation = match uplift {
+                Some(history) => elevation + uplift_relief(history, x, y, warped_x, warped_y, &ridge_noise),
+                None => elevation,
+            };
             
             heightmap.set(x, y, elevation);
         }
     }
     
     // Apply smoothing pass to reduce harsh transitions
-    smooth_heightmap(&heightmap, 2)
+    let smooth_radius = (2.0 / tile_scale).round().max(1.0) as usize;
+    smooth_heightmap(&heightmap, smooth_radius)
+}
+
+/// Relief (m) left by drift: young uplift stands as ridged ranges, old
+/// uplift as smooth rounded highlands, rifts sink below their shoulders
+fn uplift_relief(history: &UpliftHistory, x: usize, y: usize, nx: f64, ny: f64, ridge_noise: &Perlin) -> f32 {
+    let ridges = generate_ridges(nx, ny, ridge_noise, 2.0) as f32;
+    history.young(x, y) * YOUNG_UPLIFT_HEIGHT * (0.4 + 0.6 * ridges)
+        + history.old(x, y) * OLD_UPLIFT_HEIGHT
+        - *history.rift.get(x, y) * RIFT_DEPTH
+}
+
+// =============================================================================
+// RIFT VALLEYS
+// =============================================================================
+
+/// Carve grabens where two continental plates pull apart.
+///
+/// The valley follows the boundary itself: a floor dropped between boundary
+/// faults, stepped fault blocks down its walls and raised shoulders outside
+/// them. Along the axis the floor breaks into basins, and the deepest are
+/// sunk below sea level so `water_bodies` finds long, narrow rift lakes.
+/// `tile_scale` is 1.0 after `generate_heightmap` and `invariant_tile_scale`
+/// after `generate_heightmap_invariant`.
+pub fn carve_rift_valleys(
+    heightmap: &mut Tilemap<f32>,
+    plate_map: &Tilemap<PlateId>,
+    plates: &[Plate],
+    stress_map: &Tilemap<f32>,
+    seed: u64,
+    tile_scale: f32,
+) {
+    let width = heightmap.width;
+    let height = heightmap.height;
+    let continental = |id: PlateId| !id.is_none() && plates[id.0 as usize].plate_type == PlateType::Continental;
+
+    // Axis: continental tiles bordering another continental plate, diverging
+    let mut nearest: Tilemap<Option<(usize, usize)>> = Tilemap::new_with(width, height, None);
+    let mut distance = Tilemap::new_with(width, height, f32::MAX);
+    let mut queue = std::collections::VecDeque::new();
+    for y in 0..height {
+        for x in 0..width {
+            let id = *plate_map.get(x, y);
+            if !continental(id) || *stress_map.get(x, y) > -0.05 {
+                continue;
+            }
+            let on_boundary = plate_map.neighbors(x, y).into_iter().any(|(nx, ny)| {
+                let other = *plate_map.get(nx, ny);
+                other != id && continental(other)
+            });
+            if on_boundary {
+                nearest.set(x, y, Some((x, y)));
+                distance.set(x, y, 0.0);
+                queue.push_back((x, y));
+            }
+        }
+    }
+    if queue.is_empty() {
+        return;
+    }
+
+    // Distance to the nearest axis tile, out to the shoulders' edge
+    let half_width = (GRABEN_HALF_WIDTH / tile_scale).max(1.5);
+    let reach = half_width + (SHOULDER_WIDTH / tile_scale).max(2.0);
+    let wrapped = |x0: usize, y0: usize, x1: usize, y1: usize| {
+        let dx = (x0 as f32 - x1 as f32).abs();
+        let dx = dx.min(width as f32 - dx);
+        let dy = y0 as f32 - y1 as f32;
+        (dx * dx + dy * dy).sqrt()
+    };
+    while let Some((x, y)) = queue.pop_front() {
+        let Some((ax, ay)) = *nearest.get(x, y) else { continue };
+        for dy in -1i32..=1 {
+            for dx in -1i32..=1 {
+                let ny = y as i32 + dy;
+                if (dx, dy) == (0, 0) || ny < 0 || ny >= height as i32 {
+                    continue;
+                }
+                let (nx, ny) = ((x as i32 + dx).rem_euclid(width as i32) as usize, ny as usize);
+                let d = wrapped(nx, ny, ax, ay);
+                if d < reach && d < *distance.get(nx, ny) {
+                    distance.set(nx, ny, d);
+                    nearest.set(nx, ny, Some((ax, ay)));
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+    }
+
+    let basin_noise = Perlin::new(1).set_seed(seed as u32 + 5555);
+    let basin_freq = 0.05 * tile_scale as f64;
+    let original = heightmap.clone();
+    for y in 0..height {
+        for x in 0..width {
+            let Some((ax, ay)) = *nearest.get(x, y) else { continue };
+            let h = *original.get(x, y);
+            if h <= 0.0 {
+                continue;
+            }
+            let strength = ((-*stress_map.get(ax, ay) - 0.05) / 0.5).clamp(0.0, 1.0);
+            let u = *distance.get(x, y) / half_width;
+            if u >= 1.0 {
+                // Flank uplift, cresting just outside the boundary fault
+                let t = (u - 1.0) / (reach / half_width - 1.0);
+                heightmap.set(x, y, h + SHOULDER_UPLIFT * strength * (std::f32::consts::PI * t).sin());
+                continue;
+            }
+
+            // Fault blocks step down from the boundary fault to the floor
+            let block = (u * FAULT_STEPS).floor() / FAULT_STEPS;
+            let floor = (h - GRABEN_DEPTH * strength * (1.0 - block)).max(RIFT_FLOOR_MIN.min(h));
+
+            // Basins along the axis; the deepest hold lakes
+            let basin = basin_noise.get([ax as f64 * basin_freq, ay as f64 * basin_freq, 7.5]);
+            let dry = original.neighbors_8(x, y).into_iter().all(|(nx, ny)| *original.get(nx, ny) > 0.0);
+            let lake = basin > RIFT_LAKE_THRESHOLD && strength > 0.3 && u < 0.6 && h > RIFT_LAKE_MIN_GROUND && dry;
+            let elevation = if lake {
+                let depth = ((basin - RIFT_LAKE_THRESHOLD) / (1.0 - RIFT_LAKE_THRESHOLD)) as f32;
+                -20.0 - RIFT_LAKE_DEPTH * depth * strength
+            } else {
+                floor
+            };
+            heightmap.set(x, y, elevation);
+        }
+    }
 }
 
 // =============================================================================
@@ -1842,3 +2039,489 @@ fn fbm_simple(noise: &noise::Perlin, x: f64, y: f64, octaves: usize) -> f64 {
 
     total / max_val
 }
+
+// =============================================================================
+// DEM IMPORT
+// =============================================================================
+
+/// Source format of a digital elevation model file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DemFormat {
+    /// ESRI ASCII grid (`.asc`): header lines followed by rows of elevations in meters
+    AsciiGrid,
+    /// GeoTIFF (`.tif`): signed and float samples (SRTM Int16, Float32 DEMs) are read
+    /// as meters, unsigned ones are mapped like other integer rasters.
+    Tiff,
+    /// Raster image (16-bit PNG, ...). Float rasters are read as meters,
+    /// integer rasters are mapped linearly onto the configured elevation range.
+    Image,
+}
+
+impl DemFormat {
+    /// Guess the format from a file extension
+    pub fn from_path(path: &str) -> DemFormat {
+        let ext = std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        match ext.as_deref() {
+            Some("asc") | Some("grd") | Some("txt") => DemFormat::AsciiGrid,
+            Some("tif") | Some("tiff") => DemFormat::Tiff,
+            _ => DemFormat::Image,
+        }
+    }
+}
+
+/// Options controlling how a DEM is converted into a heightmap
+#[derive(Clone, Debug)]
+pub struct DemImportOptions {
+    /// Target map size; `None` keeps the DEM's native resolution
+    pub target_size: Option<(usize, usize)>,
+    /// Elevation (meters) of the lowest integer raster value
+    pub min_elevation: f32,
+    /// Elevation (meters) of the highest integer raster value
+    pub max_elevation: f32,
+    /// DEM elevation treated as sea level (subtracted from every sample)
+    pub sea_level: f32,
+    /// Multiplier applied after sea-level adjustment (vertical exaggeration)
+    pub vertical_scale: f32,
+    /// Elevation assigned to NODATA cells (typically open ocean)
+    pub nodata_elevation: f32,
+}
+
+impl Default for DemImportOptions {
+    fn default() -> Self {
+        Self {
+            target_size: None,
+            // Covers the Challenger Deep to Everest range for 16-bit Earth rasters
+            min_elevation: -11000.0,
+            max_elevation: 9000.0,
+            sea_level: 0.0,
+            vertical_scale: 1.0,
+            nodata_elevation: OCEAN_FLOOR,
+        }
+    }
+}
+
+/// Errors that can occur while importing a DEM
+#[derive(Debug)]
+pub enum DemError {
+    /// IO error reading the file
+    Io(std::io::Error),
+    /// Raster decoding error
+    Image(image::ImageError),
+    /// TIFF decoding error
+    Tiff(tiff::TiffError),
+    /// Malformed ASCII grid
+    Parse(String),
+}
+
+impl std::fmt::Display for DemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DemError::Io(e) => write!(f, "IO error: {}", e),
+            DemError::Image(e) => write!(f, "Image error: {}", e),
+            DemError::Tiff(e) => write!(f, "TIFF error: {}", e),
+            DemError::Parse(e) => write!(f, "Parse error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DemError {}
+
+impl From<std::io::Error> for DemError {
+    fn from(e: std::io::Error) -> Self {
+        DemError::Io(e)
+    }
+}
+
+impl From<image::ImageError> for DemError {
+    fn from(e: image::ImageError) -> Self {
+        DemError::Image(e)
+    }
+}
+
+impl From<tiff::TiffError> for DemError {
+    fn from(e: tiff::TiffError) -> Self {
+        DemError::Tiff(e)
+    }
+}
+
+/// Import a real digital elevation model as a heightmap in meters.
+///
+/// The format is detected from the file extension (see `DemFormat::from_path`).
+/// The result uses the crate's conventions (negative = underwater) so it can be fed
+/// to erosion, climate, biomes, water bodies and history like a generated heightmap.
+pub fn import_dem(path: &str, options: &DemImportOptions) -> Result<Tilemap<f32>, DemError> {
+    let raw = match DemFormat::from_path(path) {
+        DemFormat::AsciiGrid => {
+            let text = std::fs::read_to_string(path)?;
+            parse_ascii_grid(&text, options.nodata_elevation)?
+        }
+        DemFormat::Tiff => dem_from_tiff(path, options)?,
+        DemFormat::Image => {
+            let img = image::open(path)?;
+            dem_from_image(&img, options)
+        }
+    };
+
+    let mut heightmap = match options.target_size {
+        Some((w, h)) if (w, h) != (raw.width, raw.height) => resample_dem(&raw, w, h),
+        _ => raw,
+    };
+
+    for (_, _, h) in heightmap.iter_mut() {
+        *h = (*h - options.sea_level) * options.vertical_scale;
+    }
+
+    Ok(heightmap)
+}
+
+/// Parse an ESRI ASCII grid. NODATA cells become `nodata_elevation`.
+pub fn parse_ascii_grid(text: &str, nodata_elevation: f32) -> Result<Tilemap<f32>, DemError> {
+    let mut ncols: Option<usize> = None;
+    let mut nrows: Option<usize> = None;
+    let mut nodata: Option<f32> = None;
+    let mut values: Vec<f32> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let first = parts.next().unwrap_or("");
+        if values.is_empty() && first.chars().next().map(|c| c.is_ascii_alphabetic()).unwrap_or(false) {
+            let value = parts
+                .next()
+                .ok_or_else(|| DemError::Parse(format!("missing value for header '{}'", first)))?;
+            match first.to_ascii_lowercase().as_str() {
+                "ncols" => ncols = value.parse().ok(),
+                "nrows" => nrows = value.parse().ok(),
+                "nodata_value" => nodata = value.parse().ok(),
+                // Georeferencing (xllcorner, cellsize, ...) is not needed for a tile grid
+                _ => {}
+            }
+            continue;
+        }
+
+        for token in line.split_whitespace() {
+            let v: f32 = token
+                .parse()
+                .map_err(|_| DemError::Parse(format!("invalid elevation '{}'", token)))?;
+            values.push(v);
+        }
+    }
+
+    let ncols = ncols.ok_or_else(|| DemError::Parse("missing ncols header".to_string()))?;
+    let nrows = nrows.ok_or_else(|| DemError::Parse("missing nrows header".to_string()))?;
+    if ncols == 0 || nrows == 0 {
+        return Err(DemError::Parse("grid has zero size".to_string()));
+    }
+    if values.len() != ncols * nrows {
+        return Err(DemError::Parse(format!(
+            "expected {} values ({}x{}), found {}",
+            ncols * nrows, ncols, nrows, values.len()
+        )));
+    }
+
+    let mut heightmap = Tilemap::new_with(ncols, nrows, 0.0f32);
+    for (i, v) in values.into_iter().enumerate() {
+        let is_nodata = nodata.map(|nd| (v - nd).abs() < 1e-3).unwrap_or(false);
+        heightmap.set(i % ncols, i / ncols, if is_nodata { nodata_elevation } else { v });
+    }
+
+    Ok(heightmap)
+}
+
+/// Convert a decoded raster into elevations (meters)
+fn dem_from_image(img: &image::DynamicImage, options: &DemImportOptions) -> Tilemap<f32> {
+    use image::DynamicImage;
+
+    let width = img.width() as usize;
+    let height = img.height() as usize;
+    let mut heightmap = Tilemap::new_with(width, height, 0.0f32);
+
+    match img {
+        // Float rasters (typical GeoTIFF DEMs) already store meters
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => {
+            let luma = img.to_luma32f();
+            for (x, y, p) in luma.enumerate_pixels() {
+                let v = p.0[0];
+                let v = if v.is_finite() { v } else { options.nodata_elevation };
+                heightmap.set(x as usize, y as usize, v);
+            }
+        }
+        // Integer rasters are mapped onto [min_elevation, max_elevation]
+        _ => {
+            let luma = img.to_luma16();
+            let range = options.max_elevation - options.min_elevation;
+            for (x, y, p) in luma.enumerate_pixels() {
+                let t = p.0[0] as f32 / u16::MAX as f32;
+                heightmap.set(x as usize, y as usize, options.min_elevation + t * range);
+            }
+        }
+    }
+
+    heightmap
+}
+
+/// Decode a TIFF DEM. Signed and float samples are meters; unsigned samples are
+/// mapped onto [min_elevation, max_elevation]. Cells matching the GDAL NODATA tag
+/// become `nodata_elevation`, and only the first band of a multi-band file is read.
+fn dem_from_tiff(path: &str, options: &DemImportOptions) -> Result<Tilemap<f32>, DemError> {
+    use tiff::decoder::{Decoder, DecodingResult};
+    use tiff::tags::Tag;
+
+    let mut decoder = Decoder::new(std::io::BufReader::new(std::fs::File::open(path)?))?;
+    let (width, height) = decoder.dimensions()?;
+    let (width, height) = (width as usize, height as usize);
+    let nodata: Option<f64> = decoder.get_tag_ascii_string(Tag::GdalNodata).ok().and_then(|s| s.trim().parse().ok());
+
+    // Raw samples, and the full-scale value of unsigned ones
+    let (samples, full_scale): (Vec<f64>, Option<f64>) = match decoder.read_image()? {
+        DecodingResult::U8(v) => (v.into_iter().map(f64::from).collect(), Some(u8::MAX as f64)),
+        DecodingResult::U16(v) => (v.into_iter().map(f64::from).collect(), Some(u16::MAX as f64)),
+        DecodingResult::U32(v) => (v.into_iter().map(f64::from).collect(), Some(u32::MAX as f64)),
+        DecodingResult::U64(v) => (v.into_iter().map(|s| s as f64).collect(), Some(u64::MAX as f64)),
+        DecodingResult::I8(v) => (v.into_iter().map(f64::from).collect(), None),
+        DecodingResult::I16(v) => (v.into_iter().map(f64::from).collect(), None),
+        DecodingResult::I32(v) => (v.into_iter().map(f64::from).collect(), None),
+        DecodingResult::I64(v) => (v.into_iter().map(|s| s as f64).collect(), None),
+        DecodingResult::F16(v) => (v.into_iter().map(|s| s.to_f64()).collect(), None),
+        DecodingResult::F32(v) => (v.into_iter().map(f64::from).collect(), None),
+        DecodingResult::F64(v) => (v, None),
+    };
+    let bands = samples.len() / (width * height).max(1);
+    if bands == 0 {
+        return Err(DemError::Parse(format!("expected {}x{} samples, found {}", width, height, samples.len())));
+    }
+
+    let range = options.max_elevation - options.min_elevation;
+    let mut heightmap = Tilemap::new_with(width, height, 0.0f32);
+    for y in 0..height {
+        for x in 0..width {
+            let v = samples[(y * width + x) * bands];
+            let missing = !v.is_finite() || nodata.is_some_and(|nd| (v - nd).abs() < 1e-3);
+            let elevation = match full_scale {
+                _ if missing => options.nodata_elevation,
+                Some(max) => options.min_elevation + (v / max) as f32 * range,
+                None => v as f32,
+            };
+            heightmap.set(x, y, elevation);
+        }
+    }
+
+    Ok(heightmap)
+}
+
+/// Resample a DEM to a new size with bilinear interpolation (wrapping horizontally)
+fn resample_dem(source: &Tilemap<f32>, width: usize, height: usize) -> Tilemap<f32> {
+    let mut result = Tilemap::new_with(width, height, 0.0f32);
+    let sx = source.width as f32 / width as f32;
+    let sy = source.height as f32 / height as f32;
+
+    for y in 0..height {
+        for x in 0..width {
+            // Sample at cell centers so the map isn't shifted by half a pixel
+            let src_x = (x as f32 + 0.5) * sx - 0.5;
+            let src_y = ((y as f32 + 0.5) * sy - 0.5).max(0.0);
+            result.set(x, y, source.sample_bilinear(src_x, src_y));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const SAMPLE_GRID: &str = "ncols 3\nnrows 2\nxllcorner 0.0\nyllcorner 0.0\ncellsize 30\nNODATA_value -9999\n\
+10 20 30\n-9999 -5 100\n";
+
+    #[test]
+    fn test_parse_ascii_grid() {
+        let map = parse_ascii_grid(SAMPLE_GRID, -4000.0).unwrap();
+        assert_eq!((map.width, map.height), (3, 2));
+        assert_eq!(*map.get(0, 0), 10.0);
+        assert_eq!(*map.get(2, 0), 30.0);
+        assert_eq!(*map.get(0, 1), -4000.0);
+        assert_eq!(*map.get(2, 1), 100.0);
+    }
+
+    #[test]
+    fn test_parse_ascii_grid_errors() {
+        assert!(parse_ascii_grid("nrows 2\n1 2\n3 4\n", 0.0).is_err());
+        assert!(parse_ascii_grid("ncols 2\nnrows 2\n1 2 3\n", 0.0).is_err());
+        assert!(parse_ascii_grid("ncols 2\nnrows 1\n1 abc\n", 0.0).is_err());
+    }
+
+    #[test]
+    fn test_import_ascii_with_sea_level_and_resample() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("dem.asc");
+        std::fs::write(&path, SAMPLE_GRID).unwrap();
+
+        let options = DemImportOptions {
+            target_size: Some((6, 4)),
+            sea_level: 10.0,
+            vertical_scale: 2.0,
+            ..Default::default()
+        };
+        let map = import_dem(path.to_str().unwrap(), &options).unwrap();
+
+        assert_eq!((map.width, map.height), (6, 4));
+        // Cell (1, 0) samples a quarter of the way from 10m to 20m: (12.5 - 10) * 2 = 5m
+        assert!((*map.get(1, 0) - 5.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_import_png16_maps_elevation_range() {
+        let mut img = image::ImageBuffer::<image::Luma<u16>, Vec<u16>>::new(2, 1);
+        img.put_pixel(0, 0, image::Luma([0]));
+        img.put_pixel(1, 0, image::Luma([u16::MAX]));
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("dem.png");
+        img.save(&path).unwrap();
+
+        let options = DemImportOptions {
+            min_elevation: -100.0,
+            max_elevation: 500.0,
+            ..Default::default()
+        };
+        let map = import_dem(path.to_str().unwrap(), &options).unwrap();
+
+        assert!((*map.get(0, 0) + 100.0).abs() < 1e-3);
+        assert!((*map.get(1, 0) - 500.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_import_float_geotiff_reads_meters() {
+        use tiff::encoder::{colortype, TiffEncoder};
+        use tiff::tags::Tag;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("dem.tif");
+        let mut encoder = TiffEncoder::new(std::fs::File::create(&path).unwrap()).unwrap();
+        let mut image = encoder.new_image::<colortype::Gray32Float>(3, 2).unwrap();
+        image.encoder().write_tag(Tag::GdalNodata, "-9999").unwrap();
+        image.write_data(&[-120.5, 0.0, 8848.0, -9999.0, f32::NAN, 250.25]).unwrap();
+
+        let map = import_dem(path.to_str().unwrap(), &DemImportOptions::default()).unwrap();
+        assert_eq!((map.width, map.height), (3, 2));
+        assert_eq!(*map.get(0, 0), -120.5);
+        assert_eq!(*map.get(2, 0), 8848.0);
+        assert_eq!(*map.get(2, 1), 250.25);
+        assert_eq!(*map.get(0, 1), OCEAN_FLOOR);
+        assert_eq!(*map.get(1, 1), OCEAN_FLOOR);
+
+        // SRTM-style signed 16-bit samples are meters too, not stretched onto a range
+        let path = dir.path().join("srtm.tif");
+        let mut encoder = TiffEncoder::new(std::fs::File::create(&path).unwrap()).unwrap();
+        encoder.write_image::<colortype::GrayI16>(2, 1, &[-35, 1200]).unwrap();
+        let map = import_dem(path.to_str().unwrap(), &DemImportOptions::default()).unwrap();
+        assert_eq!((*map.get(0, 0), *map.get(1, 0)), (-35.0, 1200.0));
+    }
+
+    #[test]
+    fn test_invariant_heightmap_preview_matches_full_size() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        let build = |w: usize, h: usize| {
+            let mut rng = ChaCha8Rng::seed_from_u64(5);
+            let (plate_map, plates) = crate::plates::generate_plates_invariant(w, h, Some(8), crate::plates::WorldStyle::Random, None, &mut rng);
+            let stress = crate::plates::calculate_stress_invariant(&plate_map, &plates);
+            generate_heightmap_invariant(&plate_map, &plates, &stress, None, 5)
+        };
+        let preview = build(128, 64);
+        let full = build(512, 256);
+
+        let agree = preview
+            .iter()
+            .filter(|&(x, y, &h)| (h > 0.0) == (*full.get(x * 4, y * 4) > 0.0))
+            .count();
+        assert!(agree as f32 / (128.0 * 64.0) > 0.85, "only {} tiles agree", agree);
+
+        // Land stands as high in the preview as in the full render
+        let relief = |map: &Tilemap<f32>| {
+            let mut land: Vec<f32> = map.iter().map(|(_, _, &h)| h).filter(|&h| h > 0.0).collect();
+            land.sort_by(f32::total_cmp);
+            (land.iter().sum::<f32>() / land.len() as f32, land[land.len() * 9 / 10])
+        };
+        let ((preview_mean, preview_high), (full_mean, full_high)) = (relief(&preview), relief(&full));
+        assert!((preview_mean / full_mean - 1.0).abs() < 0.1, "mean land {} vs {}", preview_mean, full_mean);
+        assert!((preview_high / full_high - 1.0).abs() < 0.15, "90th percentile {} vs {}", preview_high, full_high);
+    }
+
+    #[test]
+    fn test_invariant_moisture_preview_matches_full_size() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        let build = |w: usize, h: usize, scaled: bool| {
+            let mut rng = ChaCha8Rng::seed_from_u64(5);
+            let (plate_map, plates) = crate::plates::generate_plates_invariant(w, h, Some(8), crate::plates::WorldStyle::Random, None, &mut rng);
+            let stress = crate::plates::calculate_stress_invariant(&plate_map, &plates);
+            let heightmap = generate_heightmap_invariant(&plate_map, &plates, &stress, None, 5);
+            let scale = if scaled { invariant_scale(w) } else { MapScale::default() };
+            crate::climate::generate_moisture_scaled(&heightmap, w, h, &scale)
+        };
+        // Mean difference between preview tiles and the 2x2 blocks they cover
+        let difference = |scaled: bool| {
+            let (preview, full) = (build(128, 64, scaled), build(256, 128, scaled));
+            let total: f32 = preview
+                .iter()
+                .map(|(x, y, &m)| {
+                    let block = (0..4).map(|i| *full.get(x * 2 + i % 2, y * 2 + i / 2)).sum::<f32>() / 4.0;
+                    (m - block).abs()
+                })
+                .sum();
+            total / (128.0 * 64.0)
+        };
+
+        let (scaled, fixed) = (difference(true), difference(false));
+        assert!(scaled < 0.05, "mean moisture difference {}", scaled);
+        assert!(scaled < fixed, "{} vs {} with tile distances", scaled, fixed);
+    }
+
+    #[test]
+    fn test_rift_valley_has_shoulders_floor_and_lakes() {
+        use crate::plates::Vec2;
+
+        // Two continental plates pulling apart along x = 64
+        let plate = |id: u8, vx: f32| Plate {
+            id: PlateId(id),
+            plate_type: PlateType::Continental,
+            velocity: Vec2::new(vx, 0.0),
+            base_elevation: 0.0,
+            color: [0, 0, 0],
+        };
+        let plates = [plate(0, -1.0), plate(1, 1.0)];
+        let mut plate_map = Tilemap::new_with(128, 128, PlateId(0));
+        for (x, _, id) in plate_map.iter_mut() {
+            if (64..120).contains(&x) {
+                *id = PlateId(1);
+            }
+        }
+        let stress = Tilemap::new_with(128, 128, -0.6f32);
+        let mut heightmap = Tilemap::new_with(128, 128, 600.0f32);
+        carve_rift_valleys(&mut heightmap, &plate_map, &plates, &stress, 11, 1.0);
+
+        let column = |x: usize| (0..128).map(|y| *heightmap.get(x, y)).fold(f32::MIN, f32::max);
+        assert!(column(64) < 600.0, "axis floor should drop");
+        assert!(column(68) > 600.0, "shoulder should rise");
+        assert_eq!(column(90), 600.0, "plate interior untouched");
+
+        // Lakes sit on the axis and run along it
+        let (_, bodies) = crate::water_bodies::detect_water_bodies_with_flow(&heightmap, None);
+        let lakes: Vec<_> = bodies.iter().filter(|b| b.body_type == crate::water_bodies::WaterBodyType::Lake).collect();
+        assert!(!lakes.is_empty(), "no rift lakes");
+        let largest = lakes.iter().max_by_key(|l| l.tile_count).unwrap();
+        let (x0, y0, x1, y1) = largest.bounds;
+        assert!(y1 - y0 > 2 * (x1 - x0), "lake should be long and narrow: {:?}", largest.bounds);
+    }
+}
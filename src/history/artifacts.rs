@@ -17,7 +17,7 @@ use super::heroes::{Hero, HeroRegistry, HeroRole};
 use super::monsters::{MonsterRegistry, BiomeCategory};
 
 /// Category of artifact
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum ArtifactCategory {
     Weapon,
     Armor,
@@ -43,7 +43,7 @@ impl ArtifactCategory {
 }
 
 /// Specific type of artifact
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum ArtifactType {
     // Weapons
     Sword,
@@ -157,7 +157,7 @@ impl ArtifactType {
 }
 
 /// Rarity of an artifact
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
 pub enum ArtifactRarity {
     Common,     // 60%
     Uncommon,   // 25%
@@ -190,7 +190,7 @@ impl ArtifactRarity {
 }
 
 /// An event in the artifact's history
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ArtifactEvent {
     pub year: Year,
     pub event_type: ArtifactEventType,
@@ -200,7 +200,7 @@ pub struct ArtifactEvent {
 }
 
 /// Type of artifact event
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ArtifactEventType {
     Created,
     Gifted,
@@ -234,7 +234,7 @@ impl ArtifactEventType {
 }
 
 /// Subject of a philosophy
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum PhilosophySubject {
     Ethics,
     Metaphysics,
@@ -262,7 +262,7 @@ impl PhilosophySubject {
 }
 
 /// The lore/information contained in an artifact
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub enum ArtifactLore {
     /// No special lore
     None,
@@ -343,7 +343,7 @@ impl ArtifactLore {
 }
 
 /// Where the artifact currently is
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub enum ArtifactLocation {
     /// Carried by a hero
     WithHero(HeroId),
@@ -397,7 +397,7 @@ impl ArtifactLocation {
 }
 
 /// Main artifact structure - a lore carrier
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Artifact {
     pub id: ArtifactId,
     pub name: String,
@@ -448,7 +448,7 @@ impl Artifact {
 }
 
 /// Registry of all artifacts
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct ArtifactRegistry {
     pub artifacts: HashMap<ArtifactId, Artifact>,
     pub artifacts_by_location: HashMap<(usize, usize, i32), Vec<ArtifactId>>,
@@ -0,0 +1,264 @@
+//! Object biographies: the life story of an artifact
+//!
+//! Walks an artifact's acquisition records (`Artifact::history`) to split its
+//! life into spans of ownership, then pulls from the chronicle every battle the
+//! object witnessed: the battles its holder fought while carrying it, plus those
+//! recorded in its battle lore. The result renders as Markdown for export and as
+//! a compact factual brief for text generators to expand into prose.
+
+use std::collections::HashSet;
+
+use super::artifacts::{Artifact, ArtifactEventType, ArtifactLore};
+use super::integration::WorldHistory;
+use super::timeline::EventType;
+use super::types::{ArtifactId, EventId, HeroId, Year};
+
+/// What happened in one chapter of an artifact's life
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChapterKind {
+    Created,
+    /// Passed to a new holder (gift, inheritance, spoils, discovery)
+    Acquired,
+    Stolen,
+    /// Present at a battle in its holder's hands or recorded in its lore
+    Battle,
+    /// Left the hands of men (lost, hidden, entombed, taken by a monster)
+    Lost,
+    Destroyed,
+}
+
+/// One dated entry of a biography
+#[derive(Clone, Debug)]
+pub struct Chapter {
+    pub year: Year,
+    pub kind: ChapterKind,
+    pub holder: Option<HeroId>,
+    pub event: Option<EventId>,
+    pub text: String,
+}
+
+/// The life story of one artifact
+#[derive(Clone, Debug)]
+pub struct ArtifactBiography {
+    pub artifact: ArtifactId,
+    pub title: String,
+    pub description: String,
+    pub origin: String,
+    /// Everyone who held it, in order
+    pub owners: Vec<String>,
+    pub chapters: Vec<Chapter>,
+    pub whereabouts: String,
+}
+
+impl ArtifactBiography {
+    /// Markdown document for export
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n*{}*\n\n{}\n\n", self.title, self.origin, self.description);
+        if !self.owners.is_empty() {
+            out.push_str("## Bearers\n\n");
+            for owner in &self.owners {
+                out.push_str(&format!("- {}\n", owner));
+            }
+            out.push('\n');
+        }
+        out.push_str("## Chronicle\n\n");
+        for chapter in &self.chapters {
+            out.push_str(&format!("- **{}**: {}\n", chapter.year, chapter.text));
+        }
+        out.push_str(&format!("\n## Whereabouts\n\n{}\n", self.whereabouts));
+        out
+    }
+
+    /// Plain factual brief asking a text generator to tell this object's story
+    pub fn prompt(&self) -> String {
+        let mut out = format!("Write the legend of {} ({}). Keep to these facts, in order:\n", self.title, self.origin);
+        for chapter in &self.chapters {
+            out.push_str(&format!("- {}: {}\n", chapter.year, chapter.text));
+        }
+        out.push_str(&format!("Today: {}.\n", self.whereabouts));
+        out
+    }
+}
+
+/// Whether a chronicle event is a fight an artifact could witness
+fn is_battle(event_type: EventType) -> bool {
+    matches!(event_type, EventType::Battle | EventType::Siege | EventType::Raid | EventType::Massacre)
+}
+
+fn hero_name(history: &WorldHistory, id: HeroId) -> String {
+    history.heroes.get(id).map_or_else(|| id.to_string(), |h| h.full_name())
+}
+
+/// Build the biography of `artifact` from the history registries
+pub fn artifact_biography(history: &WorldHistory, artifact: &Artifact) -> ArtifactBiography {
+    let mut records = artifact.history.clone();
+    records.sort_by_key(|r| r.year);
+
+    let mut chapters = Vec::new();
+    let mut owners: Vec<HeroId> = Vec::new();
+    // Ownership spans: (holder, from, until)
+    let mut spans: Vec<(HeroId, Year, Option<Year>)> = Vec::new();
+
+    for record in &records {
+        let kind = match record.event_type {
+            ArtifactEventType::Created => ChapterKind::Created,
+            ArtifactEventType::Gifted
+            | ArtifactEventType::Inherited
+            | ArtifactEventType::Won
+            | ArtifactEventType::Found => ChapterKind::Acquired,
+            ArtifactEventType::Stolen => ChapterKind::Stolen,
+            ArtifactEventType::Destroyed => ChapterKind::Destroyed,
+            ArtifactEventType::Lost
+            | ArtifactEventType::Hidden
+            | ArtifactEventType::Captured
+            | ArtifactEventType::Enshrined => ChapterKind::Lost,
+        };
+        // Whoever held it until now lets go
+        if let Some(open) = spans.last_mut().filter(|s| s.2.is_none()) {
+            open.2 = Some(record.year);
+        }
+        let holder = match kind {
+            ChapterKind::Created => artifact.created_for.or(record.person),
+            ChapterKind::Acquired | ChapterKind::Stolen => record.person,
+            _ => None,
+        };
+        if let Some(holder) = holder {
+            spans.push((holder, record.year, None));
+            if owners.last() != Some(&holder) {
+                owners.push(holder);
+            }
+        }
+        chapters.push(Chapter { year: record.year, kind, holder, event: None, text: record.description.clone() });
+    }
+
+    // Battles its holders fought while carrying it
+    let mut witnessed: HashSet<EventId> = HashSet::new();
+    for &(holder, from, until) in &spans {
+        let Some(hero) = history.heroes.get(holder) else { continue };
+        for &id in &hero.achievements {
+            let Some(event) = history.timeline.events.get(&id) else { continue };
+            if is_battle(event.event_type) && event.year >= from && until.is_none_or(|u| event.year <= u) && witnessed.insert(id) {
+                chapters.push(Chapter {
+                    year: event.year,
+                    kind: ChapterKind::Battle,
+                    holder: Some(holder),
+                    event: Some(id),
+                    text: format!("Carried by {} at the {}", hero.full_name(), event.name),
+                });
+            }
+        }
+    }
+    // Battles remembered in its lore
+    if let ArtifactLore::BattleHistory { battles_fought, .. } = &artifact.contained_lore {
+        for &id in battles_fought {
+            let Some(event) = history.timeline.events.get(&id) else { continue };
+            if is_battle(event.event_type) && event.year >= artifact.creation_year && witnessed.insert(id) {
+                chapters.push(Chapter {
+                    year: event.year,
+                    kind: ChapterKind::Battle,
+                    holder: None,
+                    event: Some(id),
+                    text: format!("Bloodied at the {}", event.name),
+                });
+            }
+        }
+    }
+    // Stable sort keeps same-year records in chronicle order
+    chapters.sort_by_key(|c| c.year);
+
+    let origin = match (artifact.creator, history.factions.get(artifact.faction_origin)) {
+        (Some(creator), Some(faction)) => format!("{} made by {} of {}", artifact.artifact_type.name(), hero_name(history, creator), faction.name),
+        (Some(creator), None) => format!("{} made by {}", artifact.artifact_type.name(), hero_name(history, creator)),
+        (None, Some(faction)) => format!("{} of {} make", artifact.artifact_type.name(), faction.name),
+        (None, None) => format!("{} of unknown make", artifact.artifact_type.name()),
+    };
+    let whereabouts = match artifact.current_owner {
+        Some(owner) => format!("Carried by {}", hero_name(history, owner)),
+        None => artifact.current_location.description(),
+    };
+
+    ArtifactBiography {
+        artifact: artifact.id,
+        title: artifact.name.clone(),
+        description: artifact.description.clone(),
+        origin,
+        owners: owners.into_iter().map(|id| hero_name(history, id)).collect(),
+        chapters,
+        whereabouts,
+    }
+}
+
+/// Biographies of every legendary artifact, oldest first
+pub fn legendary_biographies(history: &WorldHistory) -> Vec<ArtifactBiography> {
+    let mut legendary = history.artifacts.legendary_artifacts();
+    legendary.sort_by_key(|a| (a.creation_year, a.id.0));
+    legendary.into_iter().map(|a| artifact_biography(history, a)).collect()
+}
+
+/// File name for a biography: the artifact id plus a slug of its name
+pub fn biography_filename(biography: &ArtifactBiography) -> String {
+    let slug: String = biography
+        .title
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    format!("{:04}_{}.md", biography.artifact.0, slug.trim_matches('_'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::artifacts::ArtifactEvent;
+    use crate::history::timeline::HistoricalEvent;
+
+    fn battle(id: u32, year: i32, name: &str) -> HistoricalEvent {
+        HistoricalEvent {
+            id: EventId(id),
+            year: Year(year),
+            event_type: EventType::Battle,
+            faction: None,
+            other_faction: None,
+            location: None,
+            settlement: None,
+            name: name.to_string(),
+            description: String::new(),
+            casualties: 0,
+            has_evidence: true,
+        }
+    }
+
+    #[test]
+    fn test_biography_follows_owners_and_battles() {
+        let mut history = crate::world::generate_world(96, 48, 7).history.unwrap();
+        let (first, second) = {
+            let mut ids = history.heroes.heroes.keys().copied().collect::<Vec<_>>();
+            ids.sort_by_key(|id| id.0);
+            (ids[0], ids[1])
+        };
+        // One battle while the first owner held it, one after it was stolen
+        history.timeline.events.insert(EventId(9000), battle(9000, -300, "Battle of Ashford"));
+        history.timeline.events.insert(EventId(9001), battle(9001, -100, "Siege of Greyhold"));
+        history.heroes.heroes.get_mut(&first).unwrap().achievements = vec![EventId(9000), EventId(9001)];
+
+        let mut artifact = history.artifacts.all().next().unwrap().clone();
+        artifact.creation_year = Year(-400);
+        artifact.created_for = None;
+        artifact.contained_lore = ArtifactLore::None;
+        artifact.history = vec![
+            ArtifactEvent { year: Year(-200), event_type: ArtifactEventType::Stolen, location: None, person: Some(second), description: "Stolen".into() },
+            ArtifactEvent { year: Year(-400), event_type: ArtifactEventType::Created, location: None, person: Some(first), description: "Forged".into() },
+        ];
+
+        let bio = artifact_biography(&history, &artifact);
+        let kinds: Vec<_> = bio.chapters.iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, vec![ChapterKind::Created, ChapterKind::Battle, ChapterKind::Stolen]);
+        assert_eq!(bio.chapters[1].event, Some(EventId(9000)));
+        assert_eq!(bio.owners.len(), 2);
+
+        let markdown = bio.to_markdown();
+        assert!(markdown.starts_with(&format!("# {}", artifact.name)));
+        assert!(markdown.contains("Battle of Ashford") && !markdown.contains("Greyhold"));
+        assert!(bio.prompt().contains("Battle of Ashford"));
+        assert!(biography_filename(&bio).ends_with(".md"));
+    }
+}
@@ -0,0 +1,477 @@
+//! Signature foods and trade goods
+//!
+//! Every people is known abroad for something it makes from its own land: a
+//! cheese from hill pastures, a wine from terraced slopes, eel smoked over
+//! forest wood, cloudberry preserve from the bogs. Each faction takes the
+//! goods its best-placed settlements can make from their farmland, fisheries,
+//! game and wild plants, weighted by what its culture cares for. The goods are
+//! luxuries: they sell at a premium along the trade routes from where they
+//! are made, buy their makers food, and are celebrated in festivals.
+
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::ExtendedBiome;
+use crate::flora::{FloraCatalog, PlantForm, PlantUse};
+use crate::tilemap::Tilemap;
+use crate::water_bodies::WaterBodyId;
+
+use super::integration::WorldHistory;
+use super::supply::{biome_fertility, reach_tiles};
+use super::territories::Settlement;
+use super::timeline::{EventType, HistoricalEvent};
+use super::types::*;
+
+/// What a signature good is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum GoodKind {
+    Cheese,
+    Wine,
+    Ale,
+    Bread,
+    SmokedFish,
+    SaltFish,
+    CuredMeat,
+    Preserve,
+    Tea,
+    Bitters,
+    Linen,
+}
+
+impl GoodKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GoodKind::Cheese => "Cheese",
+            GoodKind::Wine => "Wine",
+            GoodKind::Ale => "Ale",
+            GoodKind::Bread => "Bread",
+            GoodKind::SmokedFish => "Smoked Fish",
+            GoodKind::SaltFish => "Salt Fish",
+            GoodKind::CuredMeat => "Cured Meat",
+            GoodKind::Preserve => "Preserve",
+            GoodKind::Tea => "Tea",
+            GoodKind::Bitters => "Bitters",
+            GoodKind::Linen => "Linen",
+        }
+    }
+
+    /// Trade value before any premium, on the scale of `ResourceType::value`
+    pub fn base_value(&self) -> f32 {
+        match self {
+            GoodKind::Wine | GoodKind::Bitters => 3.0,
+            GoodKind::Cheese | GoodKind::Tea | GoodKind::Linen => 2.5,
+            GoodKind::SmokedFish | GoodKind::CuredMeat | GoodKind::Preserve => 2.0,
+            GoodKind::Ale | GoodKind::SaltFish => 1.5,
+            GoodKind::Bread => 1.0,
+        }
+    }
+
+    /// How much a culture favours making this good
+    fn culture_affinity(&self, culture: CultureType) -> f32 {
+        match (culture, self) {
+            (CultureType::Mercantile, GoodKind::Wine | GoodKind::Cheese | GoodKind::Linen) => 1.5,
+            (CultureType::Religious, GoodKind::Wine | GoodKind::Bread) => 1.5,
+            (CultureType::Scholarly, GoodKind::Tea | GoodKind::Bitters) => 1.5,
+            (CultureType::Nomadic, GoodKind::CuredMeat | GoodKind::Cheese) => 1.5,
+            (CultureType::Industrial, GoodKind::Ale | GoodKind::SaltFish) => 1.5,
+            (CultureType::Militaristic, GoodKind::CuredMeat | GoodKind::Bread | GoodKind::Ale) => 1.3,
+            (CultureType::Isolationist, GoodKind::Preserve | GoodKind::Bitters) => 1.3,
+            (CultureType::Expansionist, GoodKind::SaltFish | GoodKind::Bread) => 1.3,
+            _ => 1.0,
+        }
+    }
+
+    /// Name patterns; `{place}` is the settlement and `{plant}` the ingredient
+    fn name_patterns(&self) -> &'static [&'static str] {
+        match self {
+            GoodKind::Cheese => &["{place} Blue", "Aged {place}", "{place} Hard Cheese"],
+            GoodKind::Wine => &["{place} Red", "{place} Gold", "Vintage of {place}"],
+            GoodKind::Ale => &["{place} Dark", "{place} Ale", "Old {place}"],
+            GoodKind::Bread => &["{place} Loaf", "{place} Honeycake", "{place} Hardtack"],
+            GoodKind::SmokedFish => &["Smoked {place} Eel", "{place} Kippers", "{place} Smokefish"],
+            GoodKind::SaltFish => &["{place} Saltfish", "Salted {place} Cod", "{place} Brinefish"],
+            GoodKind::CuredMeat => &["{place} Ham", "{place} Jerky", "{place} Sausage"],
+            GoodKind::Preserve => &["{place} {plant} Preserve", "{plant} Jam of {place}"],
+            GoodKind::Tea => &["{place} {plant} Tea", "{plant} Brew of {place}"],
+            GoodKind::Bitters => &["{place} {plant} Bitters", "{plant} Spirit of {place}"],
+            GoodKind::Linen => &["{place} {plant} Linen", "{place} Weave"],
+        }
+    }
+}
+
+/// A food or good a people is known for
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SignatureGood {
+    pub name: String,
+    pub kind: GoodKind,
+    /// What it is made from (a crop, a catch, game or a plant species)
+    pub ingredient: String,
+    pub faction: FactionId,
+    /// Settlement that makes it
+    pub origin: SettlementId,
+    pub location: (usize, usize),
+    /// Price multiplier over an ordinary good of its kind
+    pub premium: f32,
+    /// Trade routes it is carried on
+    pub routes: Vec<TradeRouteId>,
+    pub description: String,
+}
+
+impl SignatureGood {
+    /// Trade value, on the scale of `ResourceType::value`
+    pub fn price(&self) -> f32 {
+        self.kind.base_value() * self.premium
+    }
+}
+
+/// Parameters for signature goods
+#[derive(Clone, Debug)]
+pub struct CuisineConfig {
+    /// Most signature goods per faction
+    pub goods_per_faction: usize,
+    /// Premium of a good no one else makes
+    pub max_premium: f32,
+    /// People fed per route by selling a good at double price
+    pub luxury_import_per_route: f32,
+    /// Most festivals held for each good
+    pub max_festivals: u32,
+}
+
+impl Default for CuisineConfig {
+    fn default() -> Self {
+        Self {
+            goods_per_faction: 2,
+            max_premium: 3.0,
+            luxury_import_per_route: 600.0,
+            max_festivals: 3,
+        }
+    }
+}
+
+/// A good a settlement could make, with how well its land suits it
+struct Candidate {
+    kind: GoodKind,
+    ingredient: String,
+    score: f32,
+}
+
+/// What the land around a settlement can make into a signature good
+fn candidates(
+    settlement: &Settlement,
+    flora: Option<&FloraCatalog>,
+    heightmap: &Tilemap<f32>,
+    biomes: &Tilemap<ExtendedBiome>,
+    water_bodies: &Tilemap<WaterBodyId>,
+) -> Vec<Candidate> {
+    let (width, height) = (heightmap.width, heightmap.height);
+    let (mut tiles, mut water, mut grain, mut pasture, mut vineyard, mut forest, mut dry) = (0.0f32, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+    let mut plants: HashMap<u16, f32> = HashMap::new();
+    for (x, y) in reach_tiles(settlement, width, height) {
+        tiles += 1.0;
+        let biome = *biomes.get(x, y);
+        if *heightmap.get(x, y) < 0.0 || *water_bodies.get(x, y) != WaterBodyId::NONE {
+            water += 1.0;
+            continue;
+        }
+        grain += biome_fertility(biome);
+        match biome {
+            ExtendedBiome::TemperateGrassland | ExtendedBiome::AlpineTundra => pasture += 1.0,
+            ExtendedBiome::Foothills => {
+                pasture += 0.7;
+                vineyard += 1.0;
+            }
+            ExtendedBiome::Savanna => vineyard += 0.4,
+            ExtendedBiome::TemperateForest
+            | ExtendedBiome::BorealForest
+            | ExtendedBiome::TemperateRainforest
+            | ExtendedBiome::TropicalForest
+            | ExtendedBiome::TropicalRainforest => forest += 1.0,
+            ExtendedBiome::Desert | ExtendedBiome::SaltFlats | ExtendedBiome::Tundra => dry += 1.0,
+            _ => {}
+        }
+        for (id, presence) in flora.map(|f| f.plants_at(x, y)).unwrap_or_default() {
+            *plants.entry(id).or_default() += presence;
+        }
+    }
+    if tiles == 0.0 {
+        return Vec::new();
+    }
+
+    let mut out = vec![
+        Candidate { kind: GoodKind::Bread, ingredient: "grain".to_string(), score: grain / tiles },
+        Candidate { kind: GoodKind::Ale, ingredient: "barley".to_string(), score: grain / tiles * 0.9 },
+        Candidate { kind: GoodKind::Cheese, ingredient: "milk".to_string(), score: pasture / tiles },
+        Candidate { kind: GoodKind::Wine, ingredient: "grapes".to_string(), score: vineyard / tiles * 1.2 },
+        Candidate { kind: GoodKind::CuredMeat, ingredient: "game".to_string(), score: forest / tiles * 0.8 },
+    ];
+    // Fish are smoked over forest wood, and salted where there is none
+    let fish = water / tiles;
+    if forest > dry {
+        out.push(Candidate { kind: GoodKind::SmokedFish, ingredient: "fish".to_string(), score: fish });
+    } else {
+        out.push(Candidate { kind: GoodKind::SaltFish, ingredient: "fish".to_string(), score: fish });
+    }
+
+    if let Some(flora) = flora {
+        let mut plants: Vec<(u16, f32)> = plants.into_iter().collect();
+        plants.sort_by_key(|&(id, _)| id);
+        for (id, presence) in plants {
+            let species = &flora.species[id as usize];
+            let score = presence / tiles * 1.5;
+            let ingredient = species.name.clone();
+            if species.has_use(PlantUse::Food) {
+                out.push(Candidate { kind: GoodKind::Preserve, ingredient: ingredient.clone(), score });
+            }
+            if species.has_use(PlantUse::Medicine) {
+                let kind = if matches!(species.form, PlantForm::Herb | PlantForm::Shrub) { GoodKind::Tea } else { GoodKind::Bitters };
+                out.push(Candidate { kind, ingredient: ingredient.clone(), score });
+            }
+            if species.has_use(PlantUse::Fiber) {
+                out.push(Candidate { kind: GoodKind::Linen, ingredient, score });
+            }
+        }
+    }
+    out.retain(|c| c.score > 0.05);
+    out
+}
+
+fn describe_good(kind: GoodKind, ingredient: &str, place: &str, culture: CultureType) -> String {
+    let made = match kind {
+        GoodKind::Cheese => format!("A cheese of {} from the pastures around {}", ingredient, place),
+        GoodKind::Wine => format!("Wine pressed from the {} of the slopes above {}", ingredient, place),
+        GoodKind::Ale => format!("Ale brewed from {} grown about {}", ingredient, place),
+        GoodKind::Bread => format!("Bread baked from {} of the fields of {}", ingredient, place),
+        GoodKind::SmokedFish => format!("Fish caught off {} and smoked over forest wood", place),
+        GoodKind::SaltFish => format!("Fish caught off {} and packed in salt", place),
+        GoodKind::CuredMeat => format!("Meat of {} hunted in the woods of {}, cured for the road", ingredient, place),
+        GoodKind::Preserve => format!("{} gathered around {} and boiled down with honey", ingredient, place),
+        GoodKind::Tea => format!("{} dried and steeped as the people of {} drink it", ingredient, place),
+        GoodKind::Bitters => format!("A bitter spirit of {} distilled in {}", ingredient.to_lowercase(), place),
+        GoodKind::Linen => format!("Cloth woven in {} from {} fibre", place, ingredient.to_lowercase()),
+    };
+    let custom = match culture {
+        CultureType::Religious => "; first offered at the temple each year",
+        CultureType::Mercantile => "; sealed with the guild's mark",
+        CultureType::Nomadic => "; carried in every saddlebag",
+        CultureType::Militaristic => "; issued to every garrison",
+        CultureType::Scholarly => "; its making set down in treatises",
+        CultureType::Isolationist => "; its recipe never told to strangers",
+        CultureType::Industrial => "; made by the cartload in great works",
+        CultureType::Expansionist => "; shipped to every new colony",
+    };
+    format!("{}{}", made, custom)
+}
+
+/// Give every faction its signature goods, carry them on the trade routes
+/// from where they are made, add the food they buy to their makers' supply,
+/// and record the festivals held for them. Returns the number of goods.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_cuisine(
+    history: &mut WorldHistory,
+    flora: Option<&FloraCatalog>,
+    heightmap: &Tilemap<f32>,
+    biomes: &Tilemap<ExtendedBiome>,
+    water_bodies: &Tilemap<WaterBodyId>,
+    config: &CuisineConfig,
+    seed: u64,
+) -> usize {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0xC0151E));
+
+    let mut settlements: Vec<&Settlement> = history.territories.settlements.values().collect();
+    settlements.sort_by_key(|s| s.id.0);
+    let mut factions: Vec<_> = history.factions.all().map(|f| (f.id, f.culture)).collect();
+    factions.sort_by_key(|(id, _)| id.0);
+
+    // Each faction's goods, from the settlement best placed to make each kind
+    let mut chosen: Vec<(FactionId, CultureType, SettlementId, Candidate)> = Vec::new();
+    for &(faction, culture) in &factions {
+        let mut best: HashMap<GoodKind, (SettlementId, Candidate)> = HashMap::new();
+        for settlement in settlements.iter().filter(|s| s.original_faction == faction) {
+            for candidate in candidates(settlement, flora, heightmap, biomes, water_bodies) {
+                let score = candidate.score * candidate.kind.culture_affinity(culture) * rng.gen_range(0.8..1.2);
+                if best.get(&candidate.kind).is_none_or(|(_, b)| score > b.score) {
+                    best.insert(candidate.kind, (settlement.id, Candidate { score, ..candidate }));
+                }
+            }
+        }
+        let mut ranked: Vec<(SettlementId, Candidate)> = best.into_values().collect();
+        ranked.sort_by(|a, b| b.1.score.total_cmp(&a.1.score).then(a.0 .0.cmp(&b.0 .0)));
+        for (origin, candidate) in ranked.into_iter().take(config.goods_per_faction) {
+            chosen.push((faction, culture, origin, candidate));
+        }
+    }
+
+    // Goods few others make fetch the highest premiums
+    let mut makers: HashMap<GoodKind, usize> = HashMap::new();
+    for (_, _, _, c) in &chosen {
+        *makers.entry(c.kind).or_default() += 1;
+    }
+
+    let mut goods = Vec::new();
+    for (faction, culture, origin, candidate) in chosen {
+        let settlement = &history.territories.settlements[&origin];
+        let place = settlement.name.as_str();
+        let location = (settlement.x, settlement.y);
+        let pattern = candidate.kind.name_patterns().choose(&mut rng).expect("every kind has name patterns");
+        let name = pattern.replace("{place}", place).replace("{plant}", &candidate.ingredient);
+
+        let rarity = 1.0 / makers[&candidate.kind] as f32;
+        let mut premium = 1.0 + (config.max_premium - 1.0) * rarity * candidate.score.min(1.0).sqrt();
+        if culture == CultureType::Mercantile {
+            premium += 0.25;
+        }
+        let premium = premium.min(config.max_premium);
+
+        let mut routes: Vec<TradeRouteId> = history
+            .trade
+            .active_routes()
+            .filter(|r| r.start == location || r.end == location)
+            .map(|r| r.id)
+            .collect();
+        routes.sort_by_key(|id| id.0);
+
+        goods.push(SignatureGood {
+            description: describe_good(candidate.kind, &candidate.ingredient, place, culture),
+            name,
+            kind: candidate.kind,
+            ingredient: candidate.ingredient,
+            faction,
+            origin,
+            location,
+            premium,
+            routes,
+        });
+    }
+
+    for good in &goods {
+        // Selling abroad buys food at home
+        if let Some(supply) = history.territories.supply.get_mut(&good.origin) {
+            let bought = good.routes.len() as f32 * config.luxury_import_per_route * (good.premium - 1.0);
+            supply.luxury_trade += bought;
+            supply.capacity += bought as u32;
+        }
+
+        let settlement = &history.territories.settlements[&good.origin];
+        let (founded, end) = (settlement.founded.0, settlement.abandoned.unwrap_or(Year(0)).0);
+        if end <= founded {
+            continue;
+        }
+        let settlement_name = settlement.name.clone();
+        for _ in 0..rng.gen_range(1..=config.max_festivals.max(1)) {
+            let year = Year(rng.gen_range(founded..end));
+            let event_id = history.timeline.new_id();
+            let name = match rng.gen_range(0..3) {
+                0 => format!("The {} Fair", good.name),
+                1 => format!("Feast of the First {}", good.kind.name()),
+                _ => format!("Festival of {} at {}", good.name, settlement_name),
+            };
+            history.timeline.add_event(HistoricalEvent {
+                id: event_id,
+                year,
+                event_type: EventType::Festival,
+                faction: Some(good.faction),
+                other_faction: None,
+                location: Some(good.location),
+                settlement: Some(good.origin),
+                name,
+                description: format!(
+                    "Traders and pilgrims crowded into {} for the year's first {}",
+                    settlement_name, good.name
+                ),
+                casualties: 0,
+                has_evidence: EventType::Festival.leaves_evidence(),
+            });
+        }
+    }
+
+    let count = goods.len();
+    history.trade.luxuries = goods;
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::factions::Faction;
+    use crate::history::territories::TerritoryRegistry;
+    use crate::history::trade::TradeRoute;
+
+    #[test]
+    fn test_goods_come_from_the_land_and_sell_at_a_premium() {
+        let mut heightmap = Tilemap::new_with(64, 32, 100.0f32);
+        let biomes = Tilemap::new_with(64, 32, ExtendedBiome::Foothills);
+        for y in 0..32 {
+            for x in 40..64 {
+                heightmap.set(x, y, -50.0);
+            }
+        }
+        let water_bodies = Tilemap::new_with(64, 32, WaterBodyId::NONE);
+
+        let mut history = WorldHistory::empty();
+        history.territories = TerritoryRegistry::new(64, 32);
+        let faction_id = history.factions.new_id();
+        history.factions.add(Faction {
+            id: faction_id,
+            name: "Vellish League".to_string(),
+            species: Species::Human,
+            culture: CultureType::Mercantile,
+            architecture: ArchitectureStyle::Imperial,
+            founded: Year::years_ago(500),
+            collapsed: None,
+            collapse_reason: None,
+            color: (100, 100, 200),
+            capital: None,
+            peak_settlements: 1,
+            peak_population: 5000,
+        });
+
+        let id = history.territories.new_settlement_id();
+        history.territories.add_settlement(Settlement {
+            id,
+            name: "Vell".to_string(),
+            settlement_type: SettlementType::City,
+            original_faction: faction_id,
+            current_faction: Some(faction_id),
+            x: 12,
+            y: 16,
+            size: 5,
+            state: SettlementState::Thriving,
+            founded: Year::years_ago(400),
+            abandoned: None,
+            abandonment_reason: None,
+            peak_population: 5000,
+            architecture: ArchitectureStyle::Imperial,
+            occupations: Vec::new(),
+        });
+        history.territories.supply.insert(id, Default::default());
+        let route_id = history.trade.new_id();
+        history.trade.add_route(TradeRoute {
+            id: route_id,
+            start: (12, 16),
+            end: (30, 16),
+            path: (12..=30).map(|x| (x, 16)).collect(),
+            active: true,
+            established: Year::years_ago(300),
+            abandoned: None,
+            resources: Vec::new(),
+            waypoints: Vec::new(),
+        });
+
+        let count = apply_cuisine(&mut history, None, &heightmap, &biomes, &water_bodies, &CuisineConfig::default(), 3);
+        assert_eq!(count, 2);
+        for good in &history.trade.luxuries {
+            // Inland hills: wine, cheese or grain, never fish
+            assert!(!matches!(good.kind, GoodKind::SmokedFish | GoodKind::SaltFish));
+            assert!(good.name.contains("Vell"));
+            assert!(good.premium > 1.0 && good.price() > good.kind.base_value());
+            assert_eq!(good.routes, vec![route_id]);
+        }
+        assert!(history.territories.supply[&id].luxury_trade > 0.0);
+        assert!(history.timeline.events.values().any(|e| e.event_type == EventType::Festival && e.settlement == Some(id)));
+        assert!(history.tile_info(12, 16).summary().unwrap().contains("Known for"));
+    }
+}
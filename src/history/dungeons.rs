@@ -17,7 +17,7 @@ use super::territories::TerritoryRegistry;
 use super::monsters::categorize_biome;
 
 /// Origin type of a dungeon
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum DungeonOrigin {
     AncientTomb,
     CollapsedMine,
@@ -94,7 +94,7 @@ impl DungeonOrigin {
 }
 
 /// A dungeon or significant cave system
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Dungeon {
     pub id: DungeonId,
     pub name: String,
@@ -126,7 +126,7 @@ impl Dungeon {
 }
 
 /// Registry of all dungeons
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct DungeonRegistry {
     pub dungeons: HashMap<DungeonId, Dungeon>,
     pub dungeons_by_location: HashMap<(usize, usize), DungeonId>,
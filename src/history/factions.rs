@@ -17,7 +17,7 @@ use super::types::*;
 use super::monsters::BiomeCategory;
 
 /// A faction (civilization) in the world
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Faction {
     /// Unique identifier
     pub id: FactionId,
@@ -67,7 +67,7 @@ impl Faction {
 }
 
 /// Collection of all factions and their relationships
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct FactionRegistry {
     /// All factions by ID
     pub factions: HashMap<FactionId, Faction>,
@@ -0,0 +1,320 @@
+//! Natural frontiers between territories
+//!
+//! Major rivers, mountain crests and deserts slow a faction's expansion, so
+//! territories tend to stop at them the way real borders do. Once territories
+//! are drawn, each stretch of border is classified by the frontier it follows,
+//! and battles between neighbours are fought over its crossings and passes.
+
+use std::collections::{HashMap, HashSet};
+
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::ExtendedBiome;
+use crate::erosion::rivers::{compute_flow_accumulation, compute_flow_direction};
+use crate::tilemap::Tilemap;
+
+use super::territories::TerritoryRegistry;
+use super::timeline::{EventType, Timeline};
+use super::types::*;
+
+/// Parameters for natural frontier detection
+#[derive(Clone, Debug)]
+pub struct FrontierConfig {
+    /// Flow accumulation above which a river is wide enough to divide realms
+    pub major_river_flow: f32,
+    /// Elevation above which a ridge counts as a mountain crest
+    pub crest_elevation: f32,
+    /// Chance of expanding across a river or crest, relative to open land
+    pub line_crossing: f32,
+    /// Chance of expanding into desert, relative to open land
+    pub desert_crossing: f32,
+    /// How many unclaimed tiles may lie between two territories that still share a border
+    pub max_gap: usize,
+}
+
+impl Default for FrontierConfig {
+    fn default() -> Self {
+        Self {
+            major_river_flow: 200.0,
+            crest_elevation: 1500.0,
+            line_crossing: 0.15,
+            desert_crossing: 0.35,
+            max_gap: 3,
+        }
+    }
+}
+
+/// Kind of terrain a border follows
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum FrontierKind {
+    River,
+    MountainCrest,
+    Desert,
+    /// No natural line: the border is wherever expansion happened to stop
+    Open,
+}
+
+impl FrontierKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FrontierKind::River => "River",
+            FrontierKind::MountainCrest => "Mountain crest",
+            FrontierKind::Desert => "Desert",
+            FrontierKind::Open => "Open",
+        }
+    }
+
+    /// Whether the border follows a natural line
+    pub fn is_natural(&self) -> bool {
+        !matches!(self, FrontierKind::Open)
+    }
+
+    /// How a battle over this frontier is remembered
+    fn battle_site(&self) -> &'static str {
+        match self {
+            FrontierKind::River => "It was fought over a crossing of the river that marks the border.",
+            FrontierKind::MountainCrest => "It was fought for a pass over the mountains between the two realms.",
+            FrontierKind::Desert => "It was fought at the wells of the desert frontier.",
+            FrontierKind::Open => "It was fought on the open border between the two realms.",
+        }
+    }
+}
+
+/// Natural frontier tiles, found from rivers, ridges and biomes
+pub struct FrontierMap {
+    pub frontiers: Tilemap<Option<FrontierKind>>,
+}
+
+impl FrontierMap {
+    pub fn frontier_at(&self, x: usize, y: usize) -> Option<FrontierKind> {
+        *self.frontiers.get(x, y)
+    }
+}
+
+/// A connected stretch of border between a territory and its neighbour
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct BorderSegment {
+    /// Faction on this side of the border
+    pub faction: FactionId,
+    /// Faction on the other side, if the border faces another realm rather than wilderness
+    pub neighbour: Option<FactionId>,
+    /// Terrain the border follows
+    pub frontier: FrontierKind,
+    /// Border tiles on this faction's side
+    pub tiles: Vec<(usize, usize)>,
+}
+
+impl BorderSegment {
+    /// Whether this segment lies between the two factions, in either order
+    pub fn divides(&self, a: FactionId, b: FactionId) -> bool {
+        (self.faction == a && self.neighbour == Some(b)) || (self.faction == b && self.neighbour == Some(a))
+    }
+}
+
+fn is_desert(biome: ExtendedBiome) -> bool {
+    matches!(
+        biome,
+        ExtendedBiome::Desert | ExtendedBiome::SingingDunes | ExtendedBiome::SaltFlats | ExtendedBiome::Ashlands
+    )
+}
+
+/// Find the tiles that make natural frontiers: land tiles on a major river,
+/// high ridge lines (higher than both neighbours along an axis), and deserts
+pub fn detect_frontiers(heightmap: &Tilemap<f32>, biomes: &Tilemap<ExtendedBiome>, config: &FrontierConfig) -> FrontierMap {
+    let (width, height) = (heightmap.width, heightmap.height);
+    let flow_dir = compute_flow_direction(heightmap);
+    let flow_acc = compute_flow_accumulation(heightmap, &flow_dir);
+
+    let mut frontiers = Tilemap::new_with(width, height, None);
+    for y in 0..height {
+        for x in 0..width {
+            let h = *heightmap.get(x, y);
+            if h < 0.0 {
+                continue;
+            }
+            let kind = if *flow_acc.get(x, y) >= config.major_river_flow {
+                Some(FrontierKind::River)
+            } else if h >= config.crest_elevation && y > 0 && y + 1 < height && {
+                let east = *heightmap.get((x + 1) % width, y);
+                let west = *heightmap.get((x + width - 1) % width, y);
+                let north = *heightmap.get(x, y - 1);
+                let south = *heightmap.get(x, y + 1);
+                (h >= east && h >= west) || (h >= north && h >= south)
+            } {
+                Some(FrontierKind::MountainCrest)
+            } else if is_desert(*biomes.get(x, y)) {
+                Some(FrontierKind::Desert)
+            } else {
+                None
+            };
+            frontiers.set(x, y, kind);
+        }
+    }
+
+    FrontierMap { frontiers }
+}
+
+/// How much harder it is to expand onto a tile than onto open land
+pub fn crossing_factor(frontiers: &FrontierMap, x: usize, y: usize, config: &FrontierConfig) -> f32 {
+    match frontiers.frontier_at(x, y) {
+        Some(FrontierKind::River) | Some(FrontierKind::MountainCrest) => config.line_crossing,
+        Some(FrontierKind::Desert) => config.desert_crossing,
+        Some(FrontierKind::Open) | None => 1.0,
+    }
+}
+
+/// Split every territory's edge into segments by neighbour and by the
+/// frontier it follows. A border is natural where the edge tile, its outside
+/// neighbour or the unclaimed gap beyond lies on a frontier.
+pub fn trace_borders(
+    territory_map: &Tilemap<Option<FactionId>>,
+    frontiers: &FrontierMap,
+    config: &FrontierConfig,
+) -> Vec<BorderSegment> {
+    let (width, height) = (territory_map.width, territory_map.height);
+
+    let mut edges: HashMap<(usize, usize), (Option<FactionId>, FrontierKind)> = HashMap::new();
+    for (x, y, owner) in territory_map.iter() {
+        let Some(faction) = *owner else { continue };
+        let mut best: Option<(Option<FactionId>, FrontierKind)> = None;
+        for (dx, dy) in [(1i32, 0i32), (-1, 0), (0, 1), (0, -1)] {
+            let mut neighbour = None;
+            let mut frontier = frontiers.frontier_at(x, y);
+            let mut outside = false;
+            for step in 1..=config.max_gap as i32 + 1 {
+                let ny = y as i32 + dy * step;
+                if ny < 0 || ny >= height as i32 {
+                    break;
+                }
+                let nx = (x as i32 + dx * step).rem_euclid(width as i32) as usize;
+                let ny = ny as usize;
+                match *territory_map.get(nx, ny) {
+                    Some(other) if other == faction => break,
+                    Some(other) => {
+                        outside = true;
+                        neighbour = Some(other);
+                        break;
+                    }
+                    None => {
+                        outside = true;
+                        frontier = frontier.or(frontiers.frontier_at(nx, ny));
+                    }
+                }
+            }
+            if !outside {
+                continue;
+            }
+            let candidate = (neighbour, frontier.unwrap_or(FrontierKind::Open));
+            // A tile facing a rival realm belongs to that border first, then to a natural line
+            let rank = |(n, f): (Option<FactionId>, FrontierKind)| (n.is_some(), f.is_natural());
+            if best.is_none_or(|current| rank(candidate) > rank(current)) {
+                best = Some(candidate);
+            }
+        }
+        if let Some(edge) = best {
+            edges.insert((x, y), edge);
+        }
+    }
+
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    let mut keys: Vec<(usize, usize)> = edges.keys().copied().collect();
+    keys.sort_unstable_by_key(|&(x, y)| (y, x));
+
+    let mut segments = Vec::new();
+    for start in keys {
+        if !seen.insert(start) {
+            continue;
+        }
+        let faction = (*territory_map.get(start.0, start.1)).expect("border tiles are claimed");
+        let (neighbour, frontier) = edges[&start];
+        let mut tiles = vec![start];
+        let mut stack = vec![start];
+        while let Some((x, y)) = stack.pop() {
+            for (nx, ny) in territory_map.neighbors_8(x, y) {
+                if seen.contains(&(nx, ny)) || *territory_map.get(nx, ny) != Some(faction) {
+                    continue;
+                }
+                if edges.get(&(nx, ny)) == Some(&(neighbour, frontier)) {
+                    seen.insert((nx, ny));
+                    tiles.push((nx, ny));
+                    stack.push((nx, ny));
+                }
+            }
+        }
+        segments.push(BorderSegment { faction, neighbour, frontier, tiles });
+    }
+
+    segments
+}
+
+/// Move battles between neighbouring realms onto the border they share,
+/// preferring its river crossings, passes and desert wells over open ground.
+/// Returns the number of battles placed.
+pub fn place_frontier_battles(timeline: &mut Timeline, territories: &TerritoryRegistry, seed: u64) -> usize {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0xF120));
+
+    let mut battles: Vec<EventId> = timeline
+        .events
+        .values()
+        .filter(|e| e.event_type == EventType::Battle && e.faction.is_some() && e.other_faction.is_some())
+        .map(|e| e.id)
+        .collect();
+    battles.sort_by_key(|id| id.0);
+
+    let mut placed = 0;
+    for id in battles {
+        let event = &timeline.events[&id];
+        let (a, b) = (event.faction.unwrap(), event.other_faction.unwrap());
+        let shared: Vec<&BorderSegment> = territories.borders.iter().filter(|s| s.divides(a, b)).collect();
+        let natural: Vec<&BorderSegment> = shared.iter().copied().filter(|s| s.frontier.is_natural()).collect();
+        let candidates = if natural.is_empty() { shared } else { natural };
+        if candidates.is_empty() {
+            continue;
+        }
+
+        let segment = candidates[rng.gen_range(0..candidates.len())];
+        let site = segment.tiles[rng.gen_range(0..segment.tiles.len())];
+        timeline.relocate_event(id, site);
+        if let Some(event) = timeline.events.get_mut(&id) {
+            event.description = format!("{} {}", event.description, segment.frontier.battle_site());
+        }
+        placed += 1;
+    }
+
+    placed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_river_between_realms_is_a_natural_border() {
+        let heightmap = Tilemap::new_with(32, 16, 100.0f32);
+        let biomes = Tilemap::new_with(32, 16, ExtendedBiome::TemperateGrassland);
+        let mut frontiers = detect_frontiers(&heightmap, &biomes, &FrontierConfig::default());
+        let mut territory_map = Tilemap::new_with(32, 16, None);
+        for y in 0..16 {
+            frontiers.frontiers.set(16, y, Some(FrontierKind::River));
+            for x in 4..16 {
+                territory_map.set(x, y, Some(FactionId(0)));
+            }
+            for x in 17..28 {
+                territory_map.set(x, y, Some(FactionId(1)));
+            }
+        }
+
+        let borders = trace_borders(&territory_map, &frontiers, &FrontierConfig::default());
+
+        let river = borders
+            .iter()
+            .find(|s| s.faction == FactionId(0) && s.neighbour == Some(FactionId(1)))
+            .expect("the two realms share a border");
+        assert_eq!(river.frontier, FrontierKind::River);
+        assert!(river.tiles.iter().all(|&(x, _)| x == 15));
+        assert!(borders.iter().any(|s| s.divides(FactionId(1), FactionId(0)) && s.faction == FactionId(1)));
+        assert!(borders.iter().any(|s| s.faction == FactionId(0) && s.neighbour.is_none() && s.frontier == FrontierKind::Open));
+    }
+}
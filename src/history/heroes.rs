@@ -19,7 +19,7 @@ use super::monsters::{BiomeCategory, categorize_biome};
 use super::territories::TerritoryRegistry;
 
 /// Role of a notable figure in history
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum HeroRole {
     Warrior,
     Ruler,
@@ -74,7 +74,7 @@ impl HeroRole {
 }
 
 /// A notable historical figure
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Hero {
     pub id: HeroId,
     pub name: String,
@@ -130,7 +130,7 @@ impl Hero {
 }
 
 /// Registry of all heroes
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct HeroRegistry {
     pub heroes: HashMap<HeroId, Hero>,
     pub heroes_by_faction: HashMap<FactionId, Vec<HeroId>>,
@@ -11,7 +11,10 @@ use super::factions::{FactionRegistry, generate_factions};
 use super::timeline::{Timeline, generate_timeline};
 use super::territories::{TerritoryRegistry, generate_territories};
 use super::monsters::{MonsterRegistry, generate_monster_lairs};
+use super::lairs::build_lair_structures;
 use super::trade::{TradeRegistry, generate_trade_network};
+use super::supply::{SupplyConfig, apply_carrying_capacity};
+use super::frontiers::place_frontier_battles;
 use super::heroes::{HeroRegistry, generate_heroes_biome};
 use super::artifacts::{ArtifactRegistry, ArtifactLocation, generate_artifacts};
 use super::dungeons::{DungeonRegistry, generate_dungeons};
@@ -19,7 +22,7 @@ use super::evidence::generate_historical_evidence;
 use super::types::*;
 
 /// Complete world history data
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct WorldHistory {
     /// All factions and their relationships
     pub factions: FactionRegistry,
@@ -358,6 +361,20 @@ impl WorldHistory {
         Ok(())
     }
 
+    /// Export a Markdown biography of every legendary artifact into `dir`, each
+    /// with a `.prompt.txt` brief for text generation; returns the Markdown files
+    pub fn export_artifact_biographies(&self, dir: &str) -> std::io::Result<Vec<String>> {
+        std::fs::create_dir_all(dir)?;
+        let mut paths = Vec::new();
+        for biography in super::biographies::legendary_biographies(self) {
+            let path = std::path::Path::new(dir).join(super::biographies::biography_filename(&biography));
+            std::fs::write(&path, biography.to_markdown())?;
+            std::fs::write(path.with_extension("prompt.txt"), biography.prompt())?;
+            paths.push(path.display().to_string());
+        }
+        Ok(paths)
+    }
+
     /// Get faction controlling a tile
     pub fn faction_at(&self, x: usize, y: usize) -> Option<&super::factions::Faction> {
         self.territories.faction_at(x, y)
@@ -422,6 +439,9 @@ impl WorldHistory {
             faction: self.faction_at(x, y).map(|f| f.name.clone()),
             settlement: self.settlement_at(x, y).map(|s| (s.name.clone(), s.state)),
             lair: self.lair_at(x, y).map(|l| (l.name.clone(), l.species)),
+            lair_site: self.lair_at(x, y)
+                .and_then(|l| l.structure.as_ref())
+                .map(|s| s.describe()),
             events: self.events_at(x, y).iter()
                 .map(|e| (e.name.clone(), e.year))
                 .collect(),
@@ -430,6 +450,7 @@ impl WorldHistory {
             dungeon,
             artifacts,
             hero_buried,
+            specialties: self.trade.luxuries_at(x, y).map(|g| g.name.clone()).collect(),
         }
     }
 
@@ -457,17 +478,21 @@ impl WorldHistory {
 }
 
 /// Summary of historical information for a tile
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct TileHistoryInfo {
     pub faction: Option<String>,
     pub settlement: Option<(String, SettlementState)>,
     pub lair: Option<(String, super::monsters::MonsterSpecies)>,
+    pub lair_site: Option<String>,
     pub events: Vec<(String, Year)>,
     pub trade_route: bool,
     pub resource: Option<super::trade::ResourceType>,
     pub dungeon: Option<(String, super::dungeons::DungeonOrigin)>,
     pub artifacts: Vec<(String, String)>, // (name, rarity)
     pub hero_buried: Option<String>,
+    /// Signature goods made here
+    #[serde(default)]
+    pub specialties: Vec<String>,
 }
 
 impl TileHistoryInfo {
@@ -496,8 +521,15 @@ impl TileHistoryInfo {
             parts.push(format!("{} ({})", name, state.name()));
         }
 
+        if !self.specialties.is_empty() {
+            parts.push(format!("Known for {}", self.specialties.join(", ")));
+        }
+
         if let Some((ref name, species)) = self.lair {
-            parts.push(format!("{} - {}", name, species.name()));
+            match self.lair_site {
+                Some(ref site) => parts.push(format!("{} - {} ({})", name, species.name(), site)),
+                None => parts.push(format!("{} - {}", name, species.name())),
+            }
         }
 
         if let Some((ref name, origin)) = self.dungeon {
@@ -562,13 +594,17 @@ pub fn generate_world_history(
     println!("  {} factions created", factions.factions.len());
 
     // Phase 2: Generate timeline
-    let timeline = generate_timeline(&factions, width, height, seed);
+    let mut timeline = generate_timeline(&factions, width, height, seed);
     println!("  {} historical events recorded", timeline.events.len());
 
     // Phase 3: Generate territories and settlements (needed for hero biome assignment)
-    let territories = generate_territories(&factions, heightmap, biomes, water_bodies, seed);
+    let mut territories = generate_territories(&factions, heightmap, biomes, water_bodies, seed);
     println!("  {} settlements placed", territories.settlements.len());
 
+    // Phase 3.2: Fight border wars over the frontiers the realms share
+    let frontier_battles = place_frontier_battles(&mut timeline, &territories, seed);
+    println!("  {} battles fought on shared frontiers", frontier_battles);
+
     // Phase 3.5: Generate heroes with biome-aware features (now that we have territories)
     let heroes = generate_heroes_biome(&factions, &timeline, Some(&territories), Some(biomes), Some(heightmap), seed);
     println!("  {} notable heroes generated", heroes.heroes.len());
@@ -581,6 +617,19 @@ pub fn generate_world_history(
     let trade = generate_trade_network(&territories, heightmap, water_bodies, biomes, seed);
     println!("  {} trade routes established", trade.routes.len());
 
+    // Phase 5.5: Cap settlements at what their land and trade can feed
+    let supply_events = apply_carrying_capacity(
+        &mut territories,
+        &mut timeline,
+        &trade,
+        heightmap,
+        biomes,
+        water_bodies,
+        &SupplyConfig::default(),
+        seed,
+    );
+    println!("  {} settlements outgrew their land", supply_events);
+
     // Phase 6: Generate dungeons
     let mut dungeons = generate_dungeons(&territories, heightmap, biomes, seed);
     println!("  {} dungeons generated", dungeons.dungeons.len());
@@ -592,6 +641,10 @@ pub fn generate_world_history(
     // Phase 6.6: Link artifacts to monster hoards and dungeons
     link_artifacts_to_locations(&mut artifacts, &mut monsters, &mut dungeons, seed);
 
+    // Phase 6.7: Carve lair structures and place hoards
+    let lair_sites = build_lair_structures(zlevels, surface_z, &mut monsters, seed);
+    println!("  {} lair structures carved", lair_sites);
+
     // Phase 7: Place physical evidence in the world
     generate_historical_evidence(
         zlevels,
@@ -0,0 +1,412 @@
+//! Monster lair structures
+//!
+//! Carves a physical site into the z-level map for every monster lair:
+//! web-choked caves for spiders, halls under the mountain for dragons,
+//! bridges over water for trolls. Hoarded artifacts are placed in the
+//! deepest chamber so the lair's lore points at a real, lootable place.
+
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::tilemap::Tilemap;
+use crate::zlevel::{Tilemap3D, ZTile, MIN_Z};
+
+use super::monsters::{MonsterLair, MonsterRegistry, MonsterSpecies};
+
+/// Architectural style of a lair
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum LairStyle {
+    /// Web-choked cave
+    WebCave,
+    /// Pillared hall carved under high ground
+    MountainHall,
+    /// Bridge over water with a den beneath
+    TrollBridge,
+    /// Honeycomb of small cells
+    Hive,
+    /// Sealed tomb with an altar
+    Crypt,
+    /// Torch-lit warren of tunnels
+    Warren,
+    /// Long burrowed tunnel
+    Tunnel,
+    /// Simple bone-strewn den
+    Den,
+}
+
+impl LairStyle {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LairStyle::WebCave => "web cave",
+            LairStyle::MountainHall => "mountain hall",
+            LairStyle::TrollBridge => "troll bridge",
+            LairStyle::Hive => "hive",
+            LairStyle::Crypt => "crypt",
+            LairStyle::Warren => "warren",
+            LairStyle::Tunnel => "tunnel",
+            LairStyle::Den => "den",
+        }
+    }
+
+    /// Floor tile used for the lair's chambers
+    fn floor(&self) -> ZTile {
+        match self {
+            LairStyle::MountainHall | LairStyle::Crypt => ZTile::StoneFloor,
+            LairStyle::Warren | LairStyle::Hive | LairStyle::Den | LairStyle::TrollBridge => ZTile::DirtFloor,
+            LairStyle::WebCave | LairStyle::Tunnel => ZTile::CaveFloor,
+        }
+    }
+
+    /// Decoration scattered through the chambers
+    fn decoration(&self) -> ZTile {
+        match self {
+            LairStyle::WebCave => ZTile::WebCluster,
+            LairStyle::MountainHall => ZTile::Column,
+            LairStyle::TrollBridge | LairStyle::Den => ZTile::BoneNest,
+            LairStyle::Hive => ZTile::BeeHive,
+            LairStyle::Crypt => ZTile::CursedGround,
+            LairStyle::Warren => ZTile::Torch,
+            LairStyle::Tunnel => ZTile::SlimeTrail,
+        }
+    }
+
+    /// Tile marking the entrance at the surface
+    fn entrance(&self) -> ZTile {
+        match self {
+            LairStyle::Crypt => ZTile::Mausoleum,
+            LairStyle::Hive => ZTile::AntMound,
+            LairStyle::TrollBridge => ZTile::Bridge,
+            _ => ZTile::DungeonEntrance,
+        }
+    }
+
+    /// Number of z-levels the lair descends below its entrance
+    fn depth(&self) -> i32 {
+        match self {
+            LairStyle::MountainHall => 3,
+            LairStyle::Crypt | LairStyle::Warren | LairStyle::Hive => 2,
+            _ => 1,
+        }
+    }
+
+    /// Radius of each chamber in tiles
+    fn chamber_radius(&self) -> i32 {
+        match self {
+            LairStyle::MountainHall | LairStyle::Warren => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl MonsterSpecies {
+    /// Preferred lair style for this species
+    pub fn lair_style(&self) -> LairStyle {
+        match self {
+            MonsterSpecies::GiantSpider => LairStyle::WebCave,
+            MonsterSpecies::Dragon | MonsterSpecies::Wyvern => LairStyle::MountainHall,
+            MonsterSpecies::Troll => LairStyle::TrollBridge,
+            MonsterSpecies::GiantAnt | MonsterSpecies::GiantBee => LairStyle::Hive,
+            MonsterSpecies::Wraith | MonsterSpecies::Lich => LairStyle::Crypt,
+            MonsterSpecies::GoblinBand | MonsterSpecies::DarkElf => LairStyle::Warren,
+            MonsterSpecies::DeepWorm | MonsterSpecies::CaveCrawler => LairStyle::Tunnel,
+            MonsterSpecies::Ogre | MonsterSpecies::Werewolf |
+            MonsterSpecies::Harpy | MonsterSpecies::Elemental => LairStyle::Den,
+        }
+    }
+}
+
+/// Physical site of a lair in the z-level map
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LairStructure {
+    pub style: LairStyle,
+    /// Surface entrance (x, y, z)
+    pub entrance: (usize, usize, i32),
+    /// Chamber centres, shallowest first
+    pub chambers: Vec<(usize, usize, i32)>,
+    /// Hoard tile in the deepest chamber, if the monster owns artifacts
+    pub hoard: Option<(usize, usize, i32)>,
+}
+
+impl LairStructure {
+    /// Depth of the deepest chamber below the entrance
+    pub fn depth(&self) -> i32 {
+        self.chambers.last().map(|c| self.entrance.2 - c.2).unwrap_or(0)
+    }
+
+    /// Short description for lore and tile inspection
+    pub fn describe(&self) -> String {
+        let (x, y, _) = self.entrance;
+        let mut text = format!("{} at ({}, {})", self.style.name(), x, y);
+        if self.depth() > 0 {
+            text.push_str(&format!(", {} levels deep", self.depth()));
+        }
+        if self.hoard.is_some() {
+            text.push_str(", hoard in the deepest chamber");
+        }
+        text
+    }
+}
+
+impl MonsterLair {
+    /// A lair can be looted once its monster is gone and a hoard was placed
+    pub fn is_lootable(&self) -> bool {
+        !self.active && self.structure.as_ref().is_some_and(|s| s.hoard.is_some())
+    }
+}
+
+/// Carve a structure for every lair and record it on the lair.
+/// Returns the number of lairs that received a structure.
+pub fn build_lair_structures(
+    zlevels: &mut Tilemap3D<ZTile>,
+    surface_z: &Tilemap<i32>,
+    monsters: &mut MonsterRegistry,
+    seed: u64,
+) -> usize {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x1A125));
+
+    // Deterministic order regardless of HashMap iteration
+    let mut ids: Vec<_> = monsters.lairs.keys().copied().collect();
+    ids.sort_by_key(|id| id.0);
+
+    let mut built = 0;
+    for id in ids {
+        let lair = monsters.lairs.get_mut(&id).unwrap();
+        lair.structure = build_lair(zlevels, surface_z, lair, &mut rng);
+        if lair.structure.is_some() {
+            built += 1;
+        }
+    }
+    built
+}
+
+/// Carve a single lair
+fn build_lair(
+    zlevels: &mut Tilemap3D<ZTile>,
+    surface_z: &Tilemap<i32>,
+    lair: &MonsterLair,
+    rng: &mut ChaCha8Rng,
+) -> Option<LairStructure> {
+    let width = surface_z.width;
+    let height = surface_z.height;
+    if lair.x >= width || lair.y >= height {
+        return None;
+    }
+
+    let mut style = lair.species.lair_style();
+    let mut entrance = (lair.x, lair.y, *surface_z.get(lair.x, lair.y));
+
+    if style == LairStyle::TrollBridge {
+        let radius = lair.species.territory_radius() as i32;
+        match find_water(zlevels, surface_z, lair.x, lair.y, radius) {
+            Some((bx, by, bz)) => {
+                zlevels.set(bx, by, bz, style.entrance());
+                entrance = (bx, by, bz);
+            }
+            None => style = LairStyle::Den,
+        }
+    }
+
+    // Never dig a lair through open water
+    let (ex, ey, ez) = entrance;
+    if style != LairStyle::TrollBridge {
+        let flooded = zlevels.is_valid_z(ez + 1) && *zlevels.get(ex, ey, ez + 1) == ZTile::Water;
+        if flooded || *zlevels.get(ex, ey, ez) != ZTile::Surface {
+            return None;
+        }
+        zlevels.set(ex, ey, ez, style.entrance());
+    }
+
+    // Underground species live at their recorded depth, others just below the entrance
+    let bottom = if lair.species.is_underground() && lair.z < ez {
+        lair.z.max(MIN_Z + 1)
+    } else {
+        (ez - style.depth()).max(MIN_Z + 1)
+    };
+
+    // Chambers hang under the entrance; troll dens sit under the riverbank
+    let (cx, cy) = if style == LairStyle::TrollBridge { (lair.x, lair.y) } else { (ex, ey) };
+    let top = (*surface_z.get(cx, cy) - 1).min(ez - 1);
+
+    // Shaft down to the first chamber
+    for z in (bottom..=top).rev() {
+        carve(zlevels, cx, cy, z, ZTile::RampDown);
+    }
+
+    let mut chambers = Vec::new();
+    let levels = if lair.species.is_underground() { 1 } else { (top - bottom + 1).min(style.depth()) };
+    for i in 0..levels.max(1) {
+        let z = bottom + i;
+        carve_chamber(zlevels, cx, cy, z, style, rng);
+        chambers.push((cx, cy, z));
+    }
+    chambers.sort_by_key(|c| -c.2);
+
+    // Treasure or bones at the deepest point
+    let (hx, hy, hz) = *chambers.last().unwrap();
+    let hoard = if lair.hoard.is_empty() {
+        zlevels.set(hx, hy, hz, ZTile::BoneNest);
+        None
+    } else {
+        zlevels.set(hx, hy, hz, ZTile::TreasureHoard);
+        Some((hx, hy, hz))
+    };
+
+    Some(LairStructure {
+        style,
+        entrance,
+        chambers,
+        hoard,
+    })
+}
+
+/// Nearest surface water tile within `radius` of (cx, cy)
+fn find_water(
+    zlevels: &Tilemap3D<ZTile>,
+    surface_z: &Tilemap<i32>,
+    cx: usize,
+    cy: usize,
+    radius: i32,
+) -> Option<(usize, usize, i32)> {
+    let width = surface_z.width as i32;
+    let height = surface_z.height as i32;
+    let mut best: Option<(i32, (usize, usize, i32))> = None;
+
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            let ny = cy as i32 + dy;
+            if ny < 0 || ny >= height {
+                continue;
+            }
+            let nx = (cx as i32 + dx).rem_euclid(width) as usize;
+            let ny = ny as usize;
+            // Bridge sits on the topmost water tile above the bed
+            let mut z = *surface_z.get(nx, ny) + 1;
+            if !zlevels.is_valid_z(z) || *zlevels.get(nx, ny, z) != ZTile::Water {
+                continue;
+            }
+            while zlevels.is_valid_z(z + 1) && *zlevels.get(nx, ny, z + 1) == ZTile::Water {
+                z += 1;
+            }
+            let dist = dx * dx + dy * dy;
+            if best.map(|(d, _)| dist < d).unwrap_or(true) {
+                best = Some((dist, (nx, ny, z)));
+            }
+        }
+    }
+
+    best.map(|(_, loc)| loc)
+}
+
+/// Carve one chamber around (cx, cy) at z
+fn carve_chamber(
+    zlevels: &mut Tilemap3D<ZTile>,
+    cx: usize,
+    cy: usize,
+    z: i32,
+    style: LairStyle,
+    rng: &mut ChaCha8Rng,
+) {
+    let width = zlevels.width as i32;
+    let height = zlevels.height as i32;
+    let r = style.chamber_radius();
+
+    for dy in -r..=r {
+        for dx in -r..=r {
+            let ny = cy as i32 + dy;
+            if ny < 0 || ny >= height {
+                continue;
+            }
+            let nx = (cx as i32 + dx).rem_euclid(width) as usize;
+            let tile = if (dx != 0 || dy != 0) && rng.gen_bool(0.3) {
+                style.decoration()
+            } else {
+                style.floor()
+            };
+            carve(zlevels, nx, ny as usize, z, tile);
+        }
+    }
+
+    if style == LairStyle::Crypt {
+        carve(zlevels, cx, cy, z, ZTile::Altar);
+    }
+}
+
+/// Replace rock or bare cave floor, leaving water and other structures intact
+fn carve(zlevels: &mut Tilemap3D<ZTile>, x: usize, y: usize, z: i32, tile: ZTile) {
+    if !zlevels.is_valid_z(z) {
+        return;
+    }
+    let current = *zlevels.get(x, y, z);
+    if current == ZTile::Solid || current == ZTile::CaveFloor {
+        zlevels.set(x, y, z, tile);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::monsters::MonsterLair;
+    use crate::history::types::{ArtifactId, LairId};
+    use crate::zlevel::generate_zlevels;
+
+    fn lair(species: MonsterSpecies, x: usize, y: usize) -> MonsterLair {
+        MonsterLair {
+            id: LairId(0),
+            species,
+            x,
+            y,
+            z: -6,
+            name: "Test Lair".to_string(),
+            active: false,
+            danger: 5,
+            territory: Vec::new(),
+            attacks: Vec::new(),
+            hoard: Vec::new(),
+            hoard_sources: Vec::new(),
+            structure: None,
+        }
+    }
+
+    #[test]
+    fn test_lair_hoard_in_deepest_chamber() {
+        let heightmap = Tilemap::new_with(32, 16, 800.0f32);
+        let (mut zlevels, surface_z) = generate_zlevels(&heightmap);
+
+        let mut registry = MonsterRegistry::new();
+        let mut dragon = lair(MonsterSpecies::Dragon, 10, 8);
+        dragon.hoard.push(ArtifactId(1));
+        registry.add(dragon);
+
+        assert_eq!(build_lair_structures(&mut zlevels, &surface_z, &mut registry, 7), 1);
+
+        let lair = registry.lair_at(10, 8).unwrap();
+        let structure = lair.structure.as_ref().unwrap();
+        assert_eq!(structure.style, LairStyle::MountainHall);
+        assert_eq!(structure.depth(), 3);
+        let (hx, hy, hz) = structure.hoard.unwrap();
+        assert_eq!(*zlevels.get(hx, hy, hz), ZTile::TreasureHoard);
+        assert_eq!(*zlevels.get(10, 8, structure.entrance.2), ZTile::DungeonEntrance);
+        assert!(lair.is_lootable());
+    }
+
+    #[test]
+    fn test_troll_bridge_spans_water() {
+        let mut heightmap = Tilemap::new_with(32, 16, 300.0f32);
+        for y in 0..16 {
+            heightmap.set(14, y, -50.0);
+        }
+        let (mut zlevels, surface_z) = generate_zlevels(&heightmap);
+
+        let mut registry = MonsterRegistry::new();
+        registry.add(lair(MonsterSpecies::Troll, 12, 8));
+        build_lair_structures(&mut zlevels, &surface_z, &mut registry, 7);
+
+        let structure = registry.lair_at(12, 8).unwrap().structure.clone().unwrap();
+        assert_eq!(structure.style, LairStyle::TrollBridge);
+        let (bx, by, bz) = structure.entrance;
+        assert_eq!(bx, 14);
+        assert_eq!(*zlevels.get(bx, by, bz), ZTile::Bridge);
+        assert!(structure.hoard.is_none());
+    }
+}
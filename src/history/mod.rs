@@ -4,39 +4,92 @@
 //! - Factions (civilizations) with species, culture, and architecture
 //! - Historical timeline with eras and events
 //! - Territories and settlements with lifecycle states
+//! - Natural frontiers (rivers, mountain crests, deserts) that borders and border wars follow
+//! - Settlement food supply and carrying capacity from the surrounding terrain
+//! - Signature foods and goods of each people, sold as luxuries and celebrated in festivals
 //! - Monster ecology and lairs
+//! - Lair structures (caves, halls, bridges) with hoards
 //! - Trade routes and resource sites
 //! - Physical evidence (battlefields, monuments, graveyards)
 //! - Notable heroes with philosophies and beliefs
 //! - Artifacts as lore carriers that move through history
+//! - Object biographies of legendary artifacts (owners, thefts, battles witnessed)
+//! - Per-tile stories of everything that happened at a coordinate
 //! - Dungeons and cave systems with historical significance
 //!
 //! The goal is to make the procedurally generated world feel rich with past history,
 //! creating locations that appear to have been used by characters, monsters, and factions
 //! over centuries. Dwarf Fortress-style depth without real-time simulation.
+//!
+//! Species, cultures, the name generator and monster lairs are always built,
+//! since water-body naming and cave fauna use them; the rest of the simulation
+//! needs the `history` feature.
 
 pub mod types;
 pub mod naming;
+pub mod monsters;
+pub mod lairs;
+#[cfg(feature = "history")]
 pub mod factions;
+#[cfg(feature = "history")]
 pub mod timeline;
+#[cfg(feature = "history")]
 pub mod territories;
-pub mod monsters;
+#[cfg(feature = "history")]
+pub mod frontiers;
+#[cfg(feature = "history")]
+pub mod supply;
+#[cfg(feature = "history")]
+pub mod cuisine;
+#[cfg(feature = "history")]
 pub mod trade;
+#[cfg(feature = "history")]
 pub mod heroes;
+#[cfg(feature = "history")]
 pub mod artifacts;
+#[cfg(feature = "history")]
+pub mod biographies;
+#[cfg(feature = "history")]
 pub mod dungeons;
+#[cfg(feature = "history")]
 pub mod evidence;
+#[cfg(feature = "history")]
 pub mod integration;
+#[cfg(feature = "history")]
+pub mod tile_story;
 
 pub use types::*;
-pub use factions::{Faction, FactionRegistry, generate_factions};
 pub use naming::NameGenerator;
+pub use monsters::{MonsterLair, MonsterSpecies, generate_monster_lairs};
+#[cfg(feature = "history")]
+pub use factions::{Faction, FactionRegistry, generate_factions};
+#[cfg(feature = "history")]
 pub use timeline::{HistoricalEvent, EventType, Era, Timeline, generate_timeline};
+#[cfg(feature = "history")]
 pub use territories::{Territory, Settlement, generate_territories};
-pub use monsters::{MonsterLair, MonsterSpecies, generate_monster_lairs};
+#[cfg(feature = "history")]
+pub use frontiers::{BorderSegment, FrontierKind};
+#[cfg(feature = "history")]
+pub use supply::{SettlementSupply, SupplyConfig, apply_carrying_capacity};
+#[cfg(feature = "history")]
+pub use cuisine::{CuisineConfig, GoodKind, SignatureGood, apply_cuisine};
+pub use lairs::{LairStructure, LairStyle, build_lair_structures};
+#[cfg(feature = "history")]
 pub use trade::{TradeRoute, ResourceSite, generate_trade_network};
+#[cfg(feature = "history")]
 pub use heroes::{Hero, HeroRegistry, HeroRole, generate_heroes};
+#[cfg(feature = "history")]
 pub use artifacts::{Artifact, ArtifactRegistry, ArtifactLore, ArtifactLocation, generate_artifacts};
+#[cfg(feature = "history")]
 pub use dungeons::{Dungeon, DungeonRegistry, DungeonOrigin, generate_dungeons};
+#[cfg(feature = "history")]
 pub use evidence::generate_historical_evidence;
+#[cfg(feature = "history")]
 pub use integration::{WorldHistory, generate_world_history};
+
+/// Stands in for the history of a world built without the `history` feature,
+/// so terrain stages can still take an optional one. It has no values: such a
+/// world's history is always `None`.
+#[cfg(not(feature = "history"))]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub enum WorldHistory {}
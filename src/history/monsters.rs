@@ -15,7 +15,7 @@ use super::naming::NameGenerator;
 use super::types::*;
 
 /// Species of monsters that create lairs
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum MonsterSpecies {
     // Surface monsters
     GiantSpider,
@@ -167,7 +167,7 @@ impl MonsterSpecies {
 }
 
 /// Biome category for monster placement and naming
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum BiomeCategory {
     Forest,
     Mountain,
@@ -185,7 +185,7 @@ pub enum BiomeCategory {
 }
 
 /// A monster lair
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct MonsterLair {
     /// Unique identifier
     pub id: LairId,
@@ -210,10 +210,12 @@ pub struct MonsterLair {
     pub hoard: Vec<ArtifactId>,
     /// How the monster acquired artifacts (id, description)
     pub hoard_sources: Vec<(ArtifactId, String)>,
+    /// Physical site carved into the z-level map
+    pub structure: Option<super::lairs::LairStructure>,
 }
 
 /// Registry of monster lairs
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct MonsterRegistry {
     /// All lairs by ID
     pub lairs: HashMap<LairId, MonsterLair>,
@@ -361,6 +363,7 @@ pub fn generate_monster_lairs(
                 attacks,
                 hoard: Vec::new(),
                 hoard_sources: Vec::new(),
+                structure: None,
             };
 
             registry.add(lair);
@@ -510,6 +513,7 @@ pub fn categorize_biome(biome: ExtendedBiome) -> BiomeCategory {
 
         // === TUNDRA/ICE ===
         ExtendedBiome::Tundra | ExtendedBiome::Ice | ExtendedBiome::AuroraWastes |
+        ExtendedBiome::IceSheet | ExtendedBiome::PackIce | ExtendedBiome::Polynya |
         // Fantasy waters (frozen)
         ExtendedBiome::FrozenLake |
         // Ocean (frozen)
@@ -564,7 +568,7 @@ pub fn categorize_biome(biome: ExtendedBiome) -> BiomeCategory {
         // === RUINS ===
         ExtendedBiome::SunkenCity | ExtendedBiome::CyclopeanRuins |
         ExtendedBiome::BuriedTemple | ExtendedBiome::OvergrownCitadel |
-        ExtendedBiome::DarkTower |
+        ExtendedBiome::DarkTower | ExtendedBiome::TitanForge | ExtendedBiome::StormSpire |
         // Bone/Death
         ExtendedBiome::TitanBones | ExtendedBiome::BoneFields |
         // Deep ocean ruins
@@ -591,7 +595,7 @@ pub fn categorize_biome(biome: ExtendedBiome) -> BiomeCategory {
         // Alien/Corrupted (magical)
         ExtendedBiome::VoidScar | ExtendedBiome::BleedingStone |
         // Ancient (magical)
-        ExtendedBiome::CoralPlateau |
+        ExtendedBiome::CoralPlateau | ExtendedBiome::WorldTree |
         // Kelp towers
         ExtendedBiome::KelpTowers => BiomeCategory::Mystical,
 
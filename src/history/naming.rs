@@ -63,6 +63,34 @@ impl NameGenerator {
         }
     }
 
+    /// Generate a water body name ("ocean", "sea", "lake" or "river") in a species' tongue
+    pub fn water_name(&self, water_type: &str, species: Species, rng: &mut ChaCha8Rng) -> String {
+        let place = format!("{}{}", self.place_root(species, rng), self.place_suffix(species, rng));
+        match water_type {
+            "ocean" => if rng.gen_bool(0.5) {
+                format!("The {} Ocean", self.nature_adjective(species, rng))
+            } else {
+                format!("The Ocean of {}", place)
+            },
+            "sea" => if rng.gen_bool(0.5) {
+                format!("The {} Sea", self.nature_adjective(species, rng))
+            } else {
+                format!("Sea of {}", place)
+            },
+            "lake" => if rng.gen_bool(0.5) {
+                self.landmark_name("lake", species, rng)
+            } else {
+                format!("Lake {}", place)
+            },
+            "river" => if rng.gen_bool(0.5) {
+                self.landmark_name("river", species, rng)
+            } else {
+                format!("{} River", place)
+            },
+            _ => place,
+        }
+    }
+
     /// Generate a battle name
     pub fn battle_name(&self, location: &str, rng: &mut ChaCha8Rng) -> String {
         let prefix = pick(rng, &["Battle of", "Siege of", "Sack of", "Fall of", "Defense of"]);
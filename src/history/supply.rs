@@ -0,0 +1,414 @@
+//! Settlement supply and carrying capacity
+//!
+//! Counts the food a settlement can draw from the land within its reach
+//! (farmland, fisheries, hunting grounds, wild plants) and from the trade routes that end
+//! at its gates. Populations are capped at what the land feeds; the surplus
+//! left, or starved, and cities shrink to towns where the land is too poor.
+
+use std::collections::HashMap;
+
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::ExtendedBiome;
+use crate::flora::forage_yield;
+use crate::tilemap::Tilemap;
+use crate::water_bodies::WaterBodyId;
+
+use super::territories::{Settlement, TerritoryRegistry};
+use super::timeline::{EventType, HistoricalEvent, Timeline};
+use super::trade::TradeRegistry;
+use super::types::*;
+
+/// Parameters for the carrying-capacity model
+#[derive(Clone, Debug)]
+pub struct SupplyConfig {
+    /// People fed by one tile of the most fertile farmland
+    pub farm_yield: f32,
+    /// People fed by one tile of sheltered fishing water
+    pub fishery_yield: f32,
+    /// People fed by one tile of the richest hunting ground
+    pub hunting_yield: f32,
+    /// People fed by foragers gathering one tile of the richest wild food plants
+    pub forage_yield: f32,
+    /// People fed by each active trade route ending at the settlement
+    pub import_per_route: f32,
+    /// Slope (elevation difference to the steepest neighbour) above which land is not farmed
+    pub max_farm_slope: f32,
+    /// Elevation above which nothing grows well enough to farm
+    pub max_farm_elevation: f32,
+    /// A population this many times over capacity starves instead of leaving
+    pub famine_overshoot: f32,
+    /// Smallest surplus that is remembered as an emigration or famine
+    pub min_surplus: u32,
+}
+
+impl Default for SupplyConfig {
+    fn default() -> Self {
+        Self {
+            farm_yield: 250.0,
+            fishery_yield: 120.0,
+            hunting_yield: 30.0,
+            forage_yield: 15.0,
+            import_per_route: 1500.0,
+            max_farm_slope: 300.0,
+            max_farm_elevation: 2500.0,
+            famine_overshoot: 2.0,
+            min_surplus: 20,
+        }
+    }
+}
+
+/// What a settlement's surroundings provide, in people fed
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct SettlementSupply {
+    /// People fed by farmland within reach
+    pub farmland: f32,
+    /// People fed by fishing waters within reach
+    pub fisheries: f32,
+    /// People fed by game within reach
+    pub hunting: f32,
+    /// People fed by wild plants foragers gather within reach
+    #[serde(default)]
+    pub foraging: f32,
+    /// People fed by trade imports
+    pub imports: f32,
+    /// People fed by food bought with signature goods sold abroad
+    #[serde(default)]
+    pub luxury_trade: f32,
+    /// Population the settlement could sustain
+    pub capacity: u32,
+    /// People the settlement could not feed, who left or starved
+    pub shortfall: u32,
+}
+
+impl SettlementSupply {
+    /// Describe the settlement's food supply for tile info
+    pub fn describe(&self) -> String {
+        let mut text = format!(
+            "Feeds {} (farms {:.0}, fish {:.0}, game {:.0}, forage {:.0}, imports {:.0})",
+            self.capacity, self.farmland, self.fisheries, self.hunting, self.foraging, self.imports
+        );
+        if self.luxury_trade > 0.0 {
+            text.push_str(&format!(", luxuries {:.0}", self.luxury_trade));
+        }
+        if self.shortfall > 0 {
+            text.push_str(&format!(", {} could not be fed", self.shortfall));
+        }
+        text
+    }
+}
+
+impl SettlementType {
+    /// Radius in tiles of the land a settlement works for food
+    pub fn supply_reach(&self) -> usize {
+        match self {
+            SettlementType::Capital => 6,
+            SettlementType::City => 5,
+            SettlementType::Town => 4,
+            SettlementType::Village => 3,
+            SettlementType::Fortress | SettlementType::Temple | SettlementType::Mine | SettlementType::Outpost => 2,
+        }
+    }
+
+    /// The next smaller kind of settlement, for places the land cannot support
+    fn smaller(&self) -> Option<SettlementType> {
+        match self {
+            SettlementType::City => Some(SettlementType::Town),
+            SettlementType::Town => Some(SettlementType::Village),
+            _ => None,
+        }
+    }
+}
+
+/// How much of a tile's potential yield can be farmed, by biome
+pub(crate) fn biome_fertility(biome: ExtendedBiome) -> f32 {
+    match biome {
+        ExtendedBiome::TemperateGrassland => 1.0,
+        ExtendedBiome::Savanna => 0.7,
+        ExtendedBiome::Foothills => 0.6,
+        ExtendedBiome::TemperateForest => 0.6,
+        ExtendedBiome::MangroveSaltmarsh | ExtendedBiome::Marsh => 0.4,
+        ExtendedBiome::TropicalForest | ExtendedBiome::TemperateRainforest => 0.4,
+        ExtendedBiome::TropicalRainforest | ExtendedBiome::BorealForest => 0.3,
+        ExtendedBiome::AncientGrove | ExtendedBiome::MushroomForest => 0.3,
+        ExtendedBiome::Swamp | ExtendedBiome::Bog => 0.15,
+        ExtendedBiome::Tundra | ExtendedBiome::AlpineTundra => 0.1,
+        ExtendedBiome::Desert => 0.02,
+        _ => 0.0,
+    }
+}
+
+/// How much game a tile holds, by biome
+fn biome_game(biome: ExtendedBiome) -> f32 {
+    match biome {
+        ExtendedBiome::TemperateForest | ExtendedBiome::BorealForest | ExtendedBiome::TropicalForest => 1.0,
+        ExtendedBiome::TemperateRainforest | ExtendedBiome::TropicalRainforest | ExtendedBiome::AncientGrove => 0.9,
+        ExtendedBiome::Savanna | ExtendedBiome::TemperateGrassland => 0.8,
+        ExtendedBiome::Foothills | ExtendedBiome::Marsh | ExtendedBiome::Swamp | ExtendedBiome::MangroveSaltmarsh => 0.6,
+        ExtendedBiome::Tundra | ExtendedBiome::Bog => 0.4,
+        ExtendedBiome::AlpineTundra => 0.2,
+        ExtendedBiome::Desert => 0.05,
+        _ => 0.0,
+    }
+}
+
+/// How much fish a water tile yields, by biome
+fn biome_fishery(biome: ExtendedBiome) -> f32 {
+    match biome {
+        ExtendedBiome::CoastalWater | ExtendedBiome::Lagoon => 1.0,
+        ExtendedBiome::Ocean => 0.5,
+        ExtendedBiome::DeepOcean => 0.2,
+        ExtendedBiome::FrozenLake | ExtendedBiome::Polynya => 0.3,
+        ExtendedBiome::AcidLake | ExtendedBiome::LavaLake => 0.0,
+        ExtendedBiome::PackIce | ExtendedBiome::Ice | ExtendedBiome::IceSheet => 0.0,
+        // Lakes and rivers keep their land biome underneath
+        _ => 0.8,
+    }
+}
+
+/// Elevation difference to the steepest of the four neighbours
+fn local_slope(heightmap: &Tilemap<f32>, x: usize, y: usize) -> f32 {
+    let h = *heightmap.get(x, y);
+    let (width, height) = (heightmap.width, heightmap.height);
+    [
+        ((x + 1) % width, y),
+        ((x + width - 1) % width, y),
+        (x, (y + 1).min(height - 1)),
+        (x, y.saturating_sub(1)),
+    ]
+    .iter()
+    .map(|&(nx, ny)| (*heightmap.get(nx, ny) - h).abs())
+    .fold(0.0, f32::max)
+}
+
+/// Tiles within a settlement's reach, wrapping east-west
+pub(crate) fn reach_tiles(settlement: &Settlement, width: usize, height: usize) -> impl Iterator<Item = (usize, usize)> {
+    let reach = settlement.settlement_type.supply_reach() as i32;
+    let (sx, sy) = (settlement.x as i32, settlement.y as i32);
+    (-reach..=reach).flat_map(move |dy| {
+        (-reach..=reach).filter_map(move |dx| {
+            let y = sy + dy;
+            if dx * dx + dy * dy > reach * reach || y < 0 || y >= height as i32 {
+                return None;
+            }
+            Some(((sx + dx).rem_euclid(width as i32) as usize, y as usize))
+        })
+    })
+}
+
+/// Compute what the land around each settlement can feed. Tiles within reach
+/// of several settlements are shared between them.
+pub fn compute_supply(
+    territories: &TerritoryRegistry,
+    trade: &TradeRegistry,
+    heightmap: &Tilemap<f32>,
+    biomes: &Tilemap<ExtendedBiome>,
+    water_bodies: &Tilemap<WaterBodyId>,
+    config: &SupplyConfig,
+) -> HashMap<SettlementId, SettlementSupply> {
+    let (width, height) = (heightmap.width, heightmap.height);
+
+    let mut claims = Tilemap::new_with(width, height, 0u16);
+    for settlement in territories.settlements.values() {
+        for (x, y) in reach_tiles(settlement, width, height) {
+            let count = *claims.get(x, y);
+            claims.set(x, y, count.saturating_add(1));
+        }
+    }
+
+    let mut routes_ending_at: HashMap<(usize, usize), usize> = HashMap::new();
+    for route in trade.active_routes() {
+        *routes_ending_at.entry(route.start).or_default() += 1;
+        *routes_ending_at.entry(route.end).or_default() += 1;
+    }
+
+    territories
+        .settlements
+        .values()
+        .map(|settlement| {
+            let mut supply = SettlementSupply::default();
+            for (x, y) in reach_tiles(settlement, width, height) {
+                let share = 1.0 / (*claims.get(x, y)).max(1) as f32;
+                let biome = *biomes.get(x, y);
+                let elevation = *heightmap.get(x, y);
+                if elevation < 0.0 || *water_bodies.get(x, y) != WaterBodyId::NONE {
+                    supply.fisheries += biome_fishery(biome) * config.fishery_yield * share;
+                    continue;
+                }
+                supply.hunting += biome_game(biome) * config.hunting_yield * share;
+                supply.foraging += forage_yield(biome) * config.forage_yield * share;
+                if elevation < config.max_farm_elevation {
+                    let slope = local_slope(heightmap, x, y);
+                    let workable = (1.0 - slope / config.max_farm_slope).max(0.0);
+                    supply.farmland += biome_fertility(biome) * workable * config.farm_yield * share;
+                }
+            }
+            let routes = routes_ending_at.get(&(settlement.x, settlement.y)).copied().unwrap_or(0);
+            supply.imports = routes as f32 * config.import_per_route;
+            supply.capacity =
+                (supply.farmland + supply.fisheries + supply.hunting + supply.foraging + supply.imports) as u32;
+            (settlement.id, supply)
+        })
+        .collect()
+}
+
+/// Cap every settlement's population at what its surroundings can feed.
+/// Cities and towns the land cannot support shrink to the largest kind it
+/// can, and the people it could not feed leave as emigrants or, far over
+/// capacity, die in famine. Returns the number of events recorded.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_carrying_capacity(
+    territories: &mut TerritoryRegistry,
+    timeline: &mut Timeline,
+    trade: &TradeRegistry,
+    heightmap: &Tilemap<f32>,
+    biomes: &Tilemap<ExtendedBiome>,
+    water_bodies: &Tilemap<WaterBodyId>,
+    config: &SupplyConfig,
+    seed: u64,
+) -> usize {
+    let mut supplies = compute_supply(territories, trade, heightmap, biomes, water_bodies, config);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x5A9917));
+
+    let mut ids: Vec<SettlementId> = territories.settlements.keys().copied().collect();
+    ids.sort_by_key(|id| id.0);
+
+    let mut added = 0;
+    for id in ids {
+        let (Some(settlement), Some(supply)) = (territories.settlements.get_mut(&id), supplies.get_mut(&id)) else {
+            continue;
+        };
+        let capacity = supply.capacity.max(1);
+
+        while let Some(smaller) = settlement.settlement_type.smaller() {
+            if capacity >= settlement.settlement_type.population_range().0 {
+                break;
+            }
+            settlement.settlement_type = smaller;
+            settlement.size = settlement.size.min(smaller.size_range().1);
+        }
+
+        if settlement.peak_population <= capacity {
+            continue;
+        }
+        let surplus = settlement.peak_population - capacity;
+        let famine = settlement.peak_population as f32 > capacity as f32 * config.famine_overshoot;
+        settlement.peak_population = capacity;
+        supply.shortfall = surplus;
+        if surplus < config.min_surplus {
+            continue;
+        }
+
+        let end = settlement.abandoned.unwrap_or(Year(0)).0;
+        let year = if end > settlement.founded.0 { Year(rng.gen_range(settlement.founded.0..end)) } else { settlement.founded };
+        let (event_type, name, description, casualties) = if famine {
+            if settlement.state == SettlementState::Thriving {
+                settlement.state = SettlementState::Declining;
+            }
+            (
+                EventType::Famine,
+                format!("The Hunger of {}", settlement.name),
+                format!(
+                    "{} outgrew the land around it; {} starved before the fields could feed the rest",
+                    settlement.name, surplus
+                ),
+                surplus,
+            )
+        } else {
+            (
+                EventType::Emigration,
+                format!("The Leaving of {}", settlement.name),
+                format!("{} people left {} when its land could feed no more", surplus, settlement.name),
+                0,
+            )
+        };
+        let event_id = timeline.new_id();
+        timeline.add_event(HistoricalEvent {
+            id: event_id,
+            year,
+            event_type,
+            faction: Some(settlement.current_faction.unwrap_or(settlement.original_faction)),
+            other_faction: None,
+            location: Some((settlement.x, settlement.y)),
+            settlement: Some(id),
+            name,
+            description,
+            casualties,
+            has_evidence: event_type.leaves_evidence(),
+        });
+        added += 1;
+    }
+
+    territories.supply = supplies;
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settlement(territories: &mut TerritoryRegistry, settlement_type: SettlementType, x: usize) -> SettlementId {
+        let id = territories.new_settlement_id();
+        territories.add_settlement(Settlement {
+            id,
+            name: format!("Test {}", id.0),
+            settlement_type,
+            original_faction: FactionId(0),
+            current_faction: Some(FactionId(0)),
+            x,
+            y: 16,
+            size: settlement_type.size_range().1,
+            state: SettlementState::Thriving,
+            founded: Year::years_ago(300),
+            abandoned: None,
+            abandonment_reason: None,
+            peak_population: settlement_type.population_range().1,
+            architecture: ArchitectureStyle::Imperial,
+            occupations: Vec::new(),
+        });
+        id
+    }
+
+    #[test]
+    fn test_cities_only_where_the_land_feeds_them() {
+        let heightmap = Tilemap::new_with(64, 32, 100.0f32);
+        let mut biomes = Tilemap::new_with(64, 32, ExtendedBiome::TemperateGrassland);
+        for y in 0..32 {
+            for x in 32..64 {
+                biomes.set(x, y, ExtendedBiome::Desert);
+            }
+        }
+        let water_bodies = Tilemap::new_with(64, 32, WaterBodyId::NONE);
+
+        let mut territories = TerritoryRegistry::new(64, 32);
+        let fertile = settlement(&mut territories, SettlementType::City, 12);
+        let barren = settlement(&mut territories, SettlementType::City, 48);
+        let mut timeline = Timeline::new();
+
+        let events = apply_carrying_capacity(
+            &mut territories,
+            &mut timeline,
+            &TradeRegistry::new(),
+            &heightmap,
+            &biomes,
+            &water_bodies,
+            &SupplyConfig::default(),
+            42,
+        );
+
+        let fertile_supply = &territories.supply[&fertile];
+        let barren_supply = &territories.supply[&barren];
+        assert!(fertile_supply.capacity > barren_supply.capacity * 10);
+        assert_eq!(territories.settlements[&fertile].settlement_type, SettlementType::City);
+        assert_ne!(territories.settlements[&barren].settlement_type, SettlementType::City);
+
+        for (id, supply) in &territories.supply {
+            assert!(territories.settlements[id].peak_population <= supply.capacity.max(1));
+        }
+        assert!(events > 0);
+        assert!(timeline.events.values().any(|e| e.settlement == Some(barren)
+            && matches!(e.event_type, EventType::Famine | EventType::Emigration)));
+    }
+}
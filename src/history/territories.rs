@@ -13,11 +13,12 @@ use crate::tilemap::Tilemap;
 use crate::water_bodies::WaterBodyId;
 
 use super::factions::{Faction, FactionRegistry};
+use super::frontiers::{self, BorderSegment, FrontierConfig, FrontierMap};
 use super::naming::NameGenerator;
 use super::types::*;
 
 /// A faction's territory (claimed area of the map)
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Territory {
     /// Faction that claims this territory
     pub faction: FactionId,
@@ -44,7 +45,7 @@ impl Territory {
 }
 
 /// A settlement (city, town, village, etc.)
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Settlement {
     /// Unique identifier
     pub id: SettlementId,
@@ -94,7 +95,7 @@ impl Settlement {
 }
 
 /// Registry of all territories and settlements
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct TerritoryRegistry {
     /// All territories
     pub territories: Vec<Territory>,
@@ -104,6 +105,12 @@ pub struct TerritoryRegistry {
     pub settlements_by_location: HashMap<(usize, usize), SettlementId>,
     /// Territory map (which faction controls each tile)
     pub territory_map: Tilemap<Option<FactionId>>,
+    /// Food supply and carrying capacity of each settlement
+    #[serde(default)]
+    pub supply: HashMap<SettlementId, super::supply::SettlementSupply>,
+    /// Border segments of every territory, by neighbour and natural frontier
+    #[serde(default)]
+    pub borders: Vec<BorderSegment>,
     /// Next settlement ID
     next_settlement_id: u32,
 }
@@ -115,6 +122,8 @@ impl TerritoryRegistry {
             settlements: HashMap::new(),
             settlements_by_location: HashMap::new(),
             territory_map: Tilemap::new_with(width, height, None),
+            supply: HashMap::new(),
+            borders: Vec::new(),
             next_settlement_id: 0,
         }
     }
@@ -146,6 +155,12 @@ impl TerritoryRegistry {
     }
 
     /// Get all settlements for a faction
+    /// Get the border segment a tile lies on
+    pub fn border_at(&self, x: usize, y: usize) -> Option<&BorderSegment> {
+        self.faction_at(x, y)?;
+        self.borders.iter().find(|b| b.tiles.contains(&(x, y)))
+    }
+
     pub fn settlements_for_faction(&self, faction: FactionId) -> Vec<&Settlement> {
         self.settlements.values()
             .filter(|s| s.original_faction == faction)
@@ -170,6 +185,8 @@ pub fn generate_territories(
 
     // Compute terrain desirability for each tile
     let desirability = compute_terrain_desirability(heightmap, biomes, water_bodies);
+    let frontier_config = FrontierConfig::default();
+    let frontier_map = frontiers::detect_frontiers(heightmap, biomes, &frontier_config);
 
     // Place capital for each faction
     let mut used_locations: HashSet<(usize, usize)> = HashSet::new();
@@ -208,7 +225,8 @@ pub fn generate_territories(
                 cy,
                 &desirability,
                 heightmap,
-                water_bodies,
+                &frontier_map,
+                &frontier_config,
                 &registry.territory_map,
                 width,
                 height,
@@ -265,6 +283,8 @@ pub fn generate_territories(
         }
     }
 
+    registry.borders = frontiers::trace_borders(&registry.territory_map, &frontier_map, &frontier_config);
+
     println!("  Generated {} territories with {} settlements",
         registry.territories.len(),
         registry.settlements.len()
@@ -354,10 +374,12 @@ fn biome_desirability(biome: ExtendedBiome) -> f32 {
         ExtendedBiome::Swamp => 0.2,
         ExtendedBiome::VolcanicWasteland => 0.1,
         ExtendedBiome::Ice => 0.05,
+        ExtendedBiome::IceSheet => 0.02,
         ExtendedBiome::SnowyPeaks => 0.1,
 
         // Water - not suitable
         ExtendedBiome::DeepOcean | ExtendedBiome::Ocean | ExtendedBiome::CoastalWater => 0.0,
+        ExtendedBiome::PackIce | ExtendedBiome::Polynya => 0.0,
 
         // Default for other biomes
         _ => 0.5,
@@ -496,7 +518,7 @@ fn terrain_matches_preference(biome: ExtendedBiome, prefs: &[TerrainPreference])
             ),
             TerrainPreference::Tundra => matches!(biome,
                 ExtendedBiome::Tundra | ExtendedBiome::Ice |
-                ExtendedBiome::AuroraWastes
+                ExtendedBiome::AuroraWastes | ExtendedBiome::IceSheet
             ),
             TerrainPreference::Coastal => matches!(biome,
                 ExtendedBiome::Lagoon | ExtendedBiome::CoastalWater |
@@ -556,7 +578,8 @@ fn generate_territory(
     cy: usize,
     desirability: &Tilemap<f32>,
     heightmap: &Tilemap<f32>,
-    water_bodies: &Tilemap<WaterBodyId>,
+    frontier_map: &FrontierMap,
+    frontier_config: &FrontierConfig,
     existing: &Tilemap<Option<FactionId>>,
     width: usize,
     height: usize,
@@ -606,7 +629,9 @@ fn generate_territory(
                 continue;
             }
 
-            if rng.gen_bool((score * 0.8) as f64) {
+            // Rivers, crests and deserts hold expansion back, so borders settle on them
+            let crossing = frontiers::crossing_factor(frontier_map, nx, ny, frontier_config);
+            if rng.gen_bool((score * 0.8 * crossing).min(1.0) as f64) {
                 tiles.insert((nx, ny));
                 queue.push_back((nx, ny));
             }
@@ -0,0 +1,164 @@
+//! Tile stories: everything that ever happened at one coordinate
+//!
+//! `WorldHistory::tile_info` summarises what a tile is *now*; `tile_story`
+//! gathers what it has *been*: chronicle events (battles, disasters, burials
+//! under sand, discoveries), the founding, conquest and abandonment of the
+//! settlement there, dungeon and lair histories, tombs, artifacts that passed
+//! through and trade routes opened across it, in chronological order.
+
+use super::integration::WorldHistory;
+use super::timeline::EventType;
+use super::types::Year;
+
+/// Where a story entry came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorySource {
+    /// A chronicle event at the tile
+    Chronicle(EventType),
+    /// The settlement on the tile
+    Settlement,
+    /// A dungeon dug or abandoned here
+    Dungeon,
+    /// A monster lair's raids
+    Lair,
+    /// A hero's tomb
+    Tomb,
+    /// An artifact lost, hidden or found here
+    Artifact,
+    /// A trade route opened across the tile
+    Trade,
+}
+
+/// One dated line of a tile's story
+#[derive(Clone, Debug)]
+pub struct StoryEntry {
+    pub year: Year,
+    pub source: StorySource,
+    pub text: String,
+}
+
+/// Everything that happened at a tile, oldest first
+#[derive(Clone, Debug, Default)]
+pub struct TileStory {
+    pub x: usize,
+    pub y: usize,
+    pub entries: Vec<StoryEntry>,
+}
+
+impl TileStory {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// One line per entry, for inspector panels
+    pub fn lines(&self) -> Vec<String> {
+        self.entries.iter().map(|e| format!("{}: {}", e.year, e.text)).collect()
+    }
+
+    /// A sentence of flavor text: the latest battle or disaster if the tile saw
+    /// one, otherwise the latest thing that happened
+    pub fn flavor(&self) -> Option<String> {
+        let dramatic = self.entries.iter().rev().find(|e| match e.source {
+            StorySource::Chronicle(t) => t.leaves_evidence(),
+            _ => false,
+        });
+        dramatic
+            .or(self.entries.last())
+            .map(|e| format!("{}: {}", e.year, e.text))
+    }
+}
+
+impl WorldHistory {
+    /// Everything that ever happened at a tile, in chronological order
+    pub fn tile_story(&self, x: usize, y: usize) -> TileStory {
+        let mut entries = Vec::new();
+        let mut push = |year: Year, source: StorySource, text: String| entries.push(StoryEntry { year, source, text });
+        let faction_name = |id| self.factions.get(id).map_or("an unknown people", |f| f.name.as_str());
+
+        for event in self.timeline.events_at(x, y) {
+            let text = if event.description.is_empty() { event.name.clone() } else { format!("{}: {}", event.name, event.description) };
+            push(event.year, StorySource::Chronicle(event.event_type), text);
+        }
+
+        for s in self.territories.settlements.values().filter(|s| s.x == x && s.y == y) {
+            push(s.founded, StorySource::Settlement, format!(
+                "{} founded {} as a {}",
+                faction_name(s.original_faction), s.name, s.settlement_type.name().to_lowercase()
+            ));
+            for &(faction, from, until) in &s.occupations {
+                push(from, StorySource::Settlement, format!("{} taken by {}", s.name, faction_name(faction)));
+                if let Some(until) = until {
+                    push(until, StorySource::Settlement, format!("{} freed from {}", s.name, faction_name(faction)));
+                }
+            }
+            if let Some(year) = s.abandoned {
+                let reason = s.abandonment_reason.map(|r| format!(" ({:?})", r)).unwrap_or_default();
+                push(year, StorySource::Settlement, format!("{} abandoned{}", s.name, reason));
+            }
+        }
+
+        for d in self.dungeons.dungeons.values().filter(|d| d.location == (x, y)) {
+            push(d.founded_year, StorySource::Dungeon, format!("{} dug as a {}", d.name, d.original_purpose.name().to_lowercase()));
+            if let Some(year) = d.abandoned_year {
+                push(year, StorySource::Dungeon, format!("{} abandoned", d.name));
+            }
+        }
+
+        for lair in self.monsters.lairs.values().filter(|l| l.x == x && l.y == y) {
+            for (year, attack) in &lair.attacks {
+                push(*year, StorySource::Lair, format!("{} of {}: {}", lair.species.name(), lair.name, attack));
+            }
+        }
+
+        for hero in self.heroes.all() {
+            if let (Some((hx, hy, _)), Some(died)) = (hero.burial_site, hero.death_year) {
+                if (hx, hy) == (x, y) {
+                    push(died, StorySource::Tomb, format!("{} laid to rest", hero.full_name()));
+                }
+            }
+        }
+
+        for artifact in self.artifacts.all() {
+            for record in &artifact.history {
+                if record.location.is_some_and(|(ax, ay, _)| (ax, ay) == (x, y)) {
+                    push(record.year, StorySource::Artifact, format!("{}: {}", artifact.name, record.description));
+                }
+            }
+        }
+
+        for route in self.trade.routes.values().filter(|r| r.path.contains(&(x, y))) {
+            push(route.established, StorySource::Trade, "A trade road opened through here".to_string());
+            if let Some(year) = route.abandoned {
+                push(year, StorySource::Trade, "The trade road fell out of use".to_string());
+            }
+        }
+
+        // Stable: same-year entries keep the order above (chronicle first)
+        entries.sort_by_key(|e| e.year);
+        TileStory { x, y, entries }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tile_story_is_chronological_and_complete() {
+        let history = crate::world::generate_world(96, 48, 7).history.unwrap();
+        let settlement = history.territories.settlements.values().next().unwrap();
+        let story = history.tile_story(settlement.x, settlement.y);
+
+        assert!(story.entries.windows(2).all(|w| w[0].year <= w[1].year));
+        assert!(story.entries.iter().any(|e| e.source == StorySource::Settlement && e.year == settlement.founded));
+        for event in history.timeline.events_at(settlement.x, settlement.y) {
+            assert!(story.entries.iter().any(|e| e.year == event.year && e.text.starts_with(&event.name)));
+        }
+        assert_eq!(story.lines().len(), story.entries.len());
+        assert!(story.flavor().is_some());
+
+        // Somewhere out at sea nothing ever happened
+        let quiet = (0..96 * 48).map(|i| history.tile_story(i % 96, i / 96)).find(|s| s.is_empty()).unwrap();
+        assert!(quiet.flavor().is_none());
+    }
+}
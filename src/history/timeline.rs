@@ -14,7 +14,7 @@ use super::naming::NameGenerator;
 use super::types::*;
 
 /// A historical event that occurred in the world
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct HistoricalEvent {
     /// Unique identifier
     pub id: EventId,
@@ -41,7 +41,7 @@ pub struct HistoricalEvent {
 }
 
 /// Types of historical events
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum EventType {
     // Settlement events
     SettlementFounded,
@@ -70,12 +70,21 @@ pub enum EventType {
     MonsterInvasion,
     Flood,
     Famine,
+    Emigration,
+    SandBurial,
+    Landslide,
+    Avalanche,
+    CometStrike,
+    Shipwreck,
 
     // Cultural events
     MonumentBuilt,
     ReligionFounded,
     GreatDiscovery,
+    Expedition,
+    PlaceDiscovered,
     ArtifactCreated,
+    Festival,
     HeroBorn,
     HeroDeath,
 
@@ -84,6 +93,10 @@ pub enum EventType {
     FactionCollapsed,
     LeaderCrowned,
     CivilWar,
+
+    // Magical events
+    WizardTowerRaised,
+    MagicalCatastrophe,
 }
 
 impl EventType {
@@ -110,16 +123,27 @@ impl EventType {
             EventType::MonsterInvasion,
             EventType::Flood,
             EventType::Famine,
+            EventType::Emigration,
+            EventType::SandBurial,
+            EventType::Landslide,
+            EventType::Avalanche,
+            EventType::CometStrike,
+            EventType::Shipwreck,
             EventType::MonumentBuilt,
             EventType::ReligionFounded,
             EventType::GreatDiscovery,
+            EventType::Expedition,
+            EventType::PlaceDiscovered,
             EventType::ArtifactCreated,
+            EventType::Festival,
             EventType::HeroBorn,
             EventType::HeroDeath,
             EventType::FactionFounded,
             EventType::FactionCollapsed,
             EventType::LeaderCrowned,
             EventType::CivilWar,
+            EventType::WizardTowerRaised,
+            EventType::MagicalCatastrophe,
         ]
     }
 
@@ -129,7 +153,7 @@ impl EventType {
             EventType::Battle | EventType::Siege | EventType::Massacre |
             EventType::VolcanicEruption | EventType::Earthquake |
             EventType::DragonAttack | EventType::MonsterInvasion |
-            EventType::MonumentBuilt | EventType::SettlementDestroyed |
+            EventType::SandBurial | EventType::Landslide | EventType::CometStrike | EventType::Shipwreck | EventType::MonumentBuilt | EventType::SettlementDestroyed |
             EventType::SettlementAbandoned | EventType::SettlementConquered |
             EventType::ArtifactCreated
         )
@@ -158,22 +182,33 @@ impl EventType {
             EventType::MonsterInvasion => "Monster Invasion",
             EventType::Flood => "Flood",
             EventType::Famine => "Famine",
+            EventType::Emigration => "Emigration",
+            EventType::SandBurial => "Buried by Sand",
+            EventType::Landslide => "Landslide",
+            EventType::Avalanche => "Avalanche",
+            EventType::CometStrike => "Comet Strike",
+            EventType::Shipwreck => "Shipwreck",
             EventType::MonumentBuilt => "Monument Built",
             EventType::ReligionFounded => "Religion Founded",
             EventType::GreatDiscovery => "Great Discovery",
+            EventType::Expedition => "Expedition",
+            EventType::PlaceDiscovered => "Place Discovered",
             EventType::ArtifactCreated => "Artifact Created",
+            EventType::Festival => "Festival",
             EventType::HeroBorn => "Hero Born",
             EventType::HeroDeath => "Hero Death",
             EventType::FactionFounded => "Faction Founded",
             EventType::FactionCollapsed => "Faction Collapsed",
             EventType::LeaderCrowned => "Leader Crowned",
             EventType::CivilWar => "Civil War",
+            EventType::WizardTowerRaised => "Wizard Tower Raised",
+            EventType::MagicalCatastrophe => "Magical Catastrophe",
         }
     }
 }
 
 /// A historical era (period of time with a theme)
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Era {
     /// Name of the era
     pub name: String,
@@ -194,7 +229,7 @@ impl Era {
 }
 
 /// Complete timeline of world history
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Timeline {
     /// All eras in chronological order
     pub eras: Vec<Era>,
@@ -245,6 +280,17 @@ impl Timeline {
         self.events.insert(id, event);
     }
 
+    /// Move an event to a new location, keeping the location index in step
+    pub fn relocate_event(&mut self, id: EventId, location: (usize, usize)) {
+        let Some(event) = self.events.get_mut(&id) else { return };
+        if let Some(old) = event.location.replace(location) {
+            if let Some(ids) = self.events_by_location.get_mut(&old) {
+                ids.retain(|&other| other != id);
+            }
+        }
+        self.events_by_location.entry(location).or_default().push(id);
+    }
+
     /// Generate a new unique event ID
     pub fn new_id(&mut self) -> EventId {
         let id = EventId(self.next_id);
@@ -13,11 +13,12 @@ use crate::biomes::ExtendedBiome;
 use crate::tilemap::Tilemap;
 use crate::water_bodies::WaterBodyId;
 
+use super::cuisine::SignatureGood;
 use super::territories::{Settlement, TerritoryRegistry};
 use super::types::*;
 
 /// Type of resource
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum ResourceType {
     Iron,
     Gold,
@@ -102,7 +103,7 @@ impl ResourceType {
 }
 
 /// A resource site (mine, quarry, farm, etc.)
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ResourceSite {
     /// Location
     pub x: usize,
@@ -120,7 +121,7 @@ pub struct ResourceSite {
 }
 
 /// A trade route between two locations
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct TradeRoute {
     /// Unique identifier
     pub id: TradeRouteId,
@@ -143,7 +144,7 @@ pub struct TradeRoute {
 }
 
 /// Type of waypoint along a trade route
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum WaypointType {
     Inn,
     TradePost,
@@ -165,7 +166,7 @@ impl WaypointType {
 }
 
 /// Registry of trade routes and resources
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct TradeRegistry {
     /// All trade routes
     pub routes: HashMap<TradeRouteId, TradeRoute>,
@@ -173,6 +174,9 @@ pub struct TradeRegistry {
     pub resources: Vec<ResourceSite>,
     /// Resource sites by location
     pub resources_by_location: HashMap<(usize, usize), usize>,
+    /// Signature foods and goods sold at a premium
+    #[serde(default)]
+    pub luxuries: Vec<SignatureGood>,
     /// Next route ID
     next_id: u32,
 }
@@ -189,6 +193,7 @@ impl TradeRegistry {
             routes: HashMap::new(),
             resources: Vec::new(),
             resources_by_location: HashMap::new(),
+            luxuries: Vec::new(),
             next_id: 0,
         }
     }
@@ -224,6 +229,11 @@ impl TradeRegistry {
         self.routes.values().filter(|r| r.active)
     }
 
+    /// Signature goods made at a location
+    pub fn luxuries_at(&self, x: usize, y: usize) -> impl Iterator<Item = &SignatureGood> {
+        self.luxuries.iter().filter(move |g| g.location == (x, y))
+    }
+
     /// Check if a tile is on any trade route
     pub fn is_on_route(&self, x: usize, y: usize) -> bool {
         self.routes.values().any(|r| r.path.contains(&(x, y)))
@@ -432,7 +442,7 @@ fn categorize_terrain(biome: ExtendedBiome, elev: f32) -> &'static str {
 
         ExtendedBiome::Desert | ExtendedBiome::SingingDunes => "desert",
 
-        ExtendedBiome::Tundra | ExtendedBiome::AuroraWastes => "tundra",
+        ExtendedBiome::Tundra | ExtendedBiome::AuroraWastes | ExtendedBiome::IceSheet => "tundra",
 
         ExtendedBiome::Lagoon | ExtendedBiome::CoastalWater => "coastal",
 
@@ -539,7 +549,7 @@ fn find_path(
     width: usize,
     height: usize,
 ) -> Vec<(usize, usize)> {
-    #[derive(Clone, Eq, PartialEq)]
+    #[derive(Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
     struct Node {
         pos: (usize, usize),
         g: i32,
@@ -5,7 +5,7 @@
 use std::fmt;
 
 /// Unique identifier for a faction
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub struct FactionId(pub u32);
 
 impl fmt::Display for FactionId {
@@ -15,7 +15,7 @@ impl fmt::Display for FactionId {
 }
 
 /// Unique identifier for a settlement
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub struct SettlementId(pub u32);
 
 impl fmt::Display for SettlementId {
@@ -25,19 +25,19 @@ impl fmt::Display for SettlementId {
 }
 
 /// Unique identifier for a historical event
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub struct EventId(pub u32);
 
 /// Unique identifier for a monster lair
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub struct LairId(pub u32);
 
 /// Unique identifier for a trade route
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub struct TradeRouteId(pub u32);
 
 /// Unique identifier for a hero/notable figure
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub struct HeroId(pub u32);
 
 impl fmt::Display for HeroId {
@@ -47,7 +47,7 @@ impl fmt::Display for HeroId {
 }
 
 /// Unique identifier for an artifact
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub struct ArtifactId(pub u32);
 
 impl fmt::Display for ArtifactId {
@@ -57,7 +57,7 @@ impl fmt::Display for ArtifactId {
 }
 
 /// Unique identifier for a dungeon
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub struct DungeonId(pub u32);
 
 impl fmt::Display for DungeonId {
@@ -67,7 +67,7 @@ impl fmt::Display for DungeonId {
 }
 
 /// Species of intelligent beings that can form civilizations
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Species {
     Human,
     Dwarf,
@@ -158,7 +158,7 @@ impl Species {
 }
 
 /// Terrain preference categories for faction placement
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum TerrainPreference {
     Mountain,
     Forest,
@@ -175,7 +175,7 @@ pub enum TerrainPreference {
 }
 
 /// Cultural characteristics of a faction
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum CultureType {
     Militaristic,
     Mercantile,
@@ -230,7 +230,7 @@ impl CultureType {
 }
 
 /// Architectural style of a faction's buildings
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum ArchitectureStyle {
     Imperial,
     Rustic,
@@ -289,7 +289,7 @@ impl ArchitectureStyle {
 }
 
 /// Relationship between two factions
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum FactionRelation {
     /// Close allies, will defend each other
     Allied,
@@ -331,7 +331,7 @@ impl FactionRelation {
 }
 
 /// State of a settlement
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum SettlementState {
     /// Active, growing settlement
     Thriving,
@@ -369,7 +369,7 @@ impl SettlementState {
 }
 
 /// Type of settlement
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum SettlementType {
     /// Capital city of a faction
     Capital,
@@ -433,7 +433,7 @@ impl SettlementType {
 }
 
 /// Historical era type
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum EraType {
     /// Dawn of civilization
     Primordial,
@@ -475,7 +475,7 @@ impl EraType {
 }
 
 /// Reason for settlement abandonment
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum AbandonmentReason {
     /// Conquered by another faction
     Conquest,
@@ -511,7 +511,7 @@ impl AbandonmentReason {
 }
 
 /// A point in history (year)
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub struct Year(pub i32);
 
 impl Year {
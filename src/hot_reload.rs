@@ -0,0 +1,405 @@
+//! Hot-reloadable generation parameters
+//!
+//! While the explorer or viewer is open, a tuning file (erosion parameters,
+//! coastline complexity, fantasy intensity) can be watched for changes. Each
+//! edit re-runs only the pipeline stages it affects, starting from snapshots
+//! cached at the stage boundaries, so tuning becomes iterate-and-see instead
+//! of a full regeneration.
+//!
+//! Tectonics, climate and history are kept as generated. Every other generator
+//! setting (spectral shaping, unique biomes, landforms, chemistry, structures)
+//! is kept with the cache and applied again, so a reload only changes what the
+//! file changed.
+
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::WorldBiomeConfig;
+use crate::coast_character::CoastCharacterParams;
+use crate::erosion::ErosionParams;
+use crate::landforms::LandformMap;
+use crate::names::NameLayer;
+use crate::plates::{Plate, PlateId};
+use crate::tilemap::Tilemap;
+use crate::world::{Coasted, Progress, StageConfig, StageInputs, WorldData};
+
+// =============================================================================
+// PARAMETERS
+// =============================================================================
+
+/// Parameters that can be edited while a world is open
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TuningParams {
+    pub erosion: ErosionParams,
+    pub coast_complexity: f32,
+    pub fantasy_intensity: f32,
+}
+
+impl Default for TuningParams {
+    fn default() -> Self {
+        Self {
+            erosion: ErosionParams::default(),
+            coast_complexity: CoastCharacterParams::default().complexity,
+            fantasy_intensity: WorldBiomeConfig::default().fantasy_intensity,
+        }
+    }
+}
+
+impl TuningParams {
+    /// Parse a tuning file (missing fields take default values)
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("tuning params serialize")
+    }
+
+    /// Load a tuning file, or write `initial` to it if it does not exist yet
+    pub fn load_or_init(path: &str, initial: Self) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => Self::from_json(&json),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                std::fs::write(path, initial.to_json()).map_err(|e| e.to_string())?;
+                Ok(initial)
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// `config` with these parameters in place of the ones it was generated with
+    fn apply_to(&self, config: &StageConfig) -> StageConfig {
+        let mut config = config.clone();
+        config.erosion = self.erosion.clone();
+        config.budget.apply_to_erosion(&mut config.erosion);
+        config.coast.complexity = self.coast_complexity;
+        config.biomes.fantasy_intensity = self.fantasy_intensity;
+        config
+    }
+
+    /// Earliest stage whose inputs differ between `self` and `other`
+    pub fn first_changed_stage(&self, other: &Self) -> Option<Stage> {
+        if self.erosion != other.erosion {
+            Some(Stage::Erosion)
+        } else if self.coast_complexity != other.coast_complexity {
+            Some(Stage::Coast)
+        } else if self.fantasy_intensity != other.fantasy_intensity {
+            Some(Stage::Biomes)
+        } else {
+            None
+        }
+    }
+}
+
+/// Re-runnable pipeline stages, in pipeline order
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    /// Erosion, coastline jitter and regional terrain noise
+    Erosion,
+    /// Fjords, rias, barrier islands and wave erosion
+    Coast,
+    /// Water bodies, biomes and everything derived from them
+    Biomes,
+}
+
+impl Stage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Erosion => "erosion",
+            Stage::Coast => "coast",
+            Stage::Biomes => "biomes",
+        }
+    }
+}
+
+// =============================================================================
+// STAGE CACHE
+// =============================================================================
+
+/// Heightmap after erosion and terrain noise, with what erosion left behind
+struct ErodedSnapshot {
+    heightmap: Tilemap<f32>,
+    glacial_erosion: Option<Tilemap<f32>>,
+    hardness: Tilemap<f32>,
+    landforms: LandformMap,
+}
+
+/// Heightmap snapshots at each stage boundary, plus the fixed inputs and
+/// generator settings they depend on
+pub struct StageCache {
+    config: StageConfig,
+    seed: u64,
+    plate_map: Tilemap<PlateId>,
+    plates: Vec<Plate>,
+    stress_map: Tilemap<f32>,
+    temperature: Tilemap<f32>,
+    moisture: Tilemap<f32>,
+    /// Heightmap before erosion
+    uneroded: Tilemap<f32>,
+    /// Heightmap after erosion and terrain noise
+    eroded: Option<ErodedSnapshot>,
+    /// Heightmap after coastline character and wave erosion, with the coast
+    /// classification and wave exposure
+    coasted: Option<Coasted>,
+}
+
+impl StageCache {
+    /// Start a cache from the pre-erosion state of a world generated with `config`
+    pub(crate) fn new(config: &StageConfig, heightmap: &Tilemap<f32>, inputs: &StageInputs) -> Self {
+        Self {
+            config: config.clone(),
+            seed: inputs.seed,
+            plate_map: inputs.plate_map.clone(),
+            plates: inputs.plates.to_vec(),
+            stress_map: inputs.stress_map.clone(),
+            temperature: inputs.temperature.clone(),
+            moisture: inputs.moisture.clone(),
+            uneroded: heightmap.clone(),
+            eroded: None,
+            coasted: None,
+        }
+    }
+
+    /// Record the heightmap as it left the erosion stage
+    pub fn record_erosion(
+        &mut self,
+        heightmap: &Tilemap<f32>,
+        glacial_erosion: Option<&Tilemap<f32>>,
+        hardness: &Tilemap<f32>,
+        landforms: &LandformMap,
+    ) {
+        self.eroded = Some(ErodedSnapshot {
+            heightmap: heightmap.clone(),
+            glacial_erosion: glacial_erosion.cloned(),
+            hardness: hardness.clone(),
+            landforms: landforms.clone(),
+        });
+        self.coasted = None;
+    }
+
+    /// Record the heightmap as it left the coast stage
+    pub(crate) fn record_coast(&mut self, coasted: &Coasted) {
+        self.coasted = Some(coasted.clone());
+    }
+
+    /// Re-run the pipeline from `from` onwards and write the results into `world`.
+    /// Stages without a recorded snapshot are re-run too.
+    pub fn rerun(&mut self, params: &TuningParams, from: Stage, world: &mut WorldData) {
+        let from = match (&self.eroded, &self.coasted) {
+            (None, _) => Stage::Erosion,
+            (Some(_), None) => from.min(Stage::Coast),
+            _ => from,
+        };
+        let config = params.apply_to(&self.config);
+        let inputs = StageInputs {
+            seed: self.seed,
+            plate_map: &self.plate_map,
+            plates: &self.plates,
+            stress_map: &self.stress_map,
+            temperature: &self.temperature,
+            moisture: &self.moisture,
+        };
+        let report = &mut |_: Progress| {};
+
+        if from <= Stage::Erosion {
+            let mut heightmap = self.uneroded.clone();
+            let mut rng = ChaCha8Rng::seed_from_u64(inputs.seed);
+            let (stats, hardness) = config.erode(&mut heightmap, &inputs, &mut rng, report);
+            let landforms = config.shape_terrain(&mut heightmap, &inputs, report);
+            self.eroded = Some(ErodedSnapshot { heightmap, glacial_erosion: stats.glacial_erosion, hardness, landforms });
+        }
+
+        let eroded = self.eroded.as_ref().expect("erosion stage has run");
+        if from <= Stage::Coast {
+            self.coasted =
+                Some(config.shape_coast(eroded.heightmap.clone(), &inputs, eroded.glacial_erosion.as_ref(), report));
+        }
+        let coasted = self.coasted.clone().expect("coast stage has run");
+
+        // History is kept as generated, so its buildings are not re-stamped into the new z-levels
+        let mut surface =
+            config.build_surface(coasted, &inputs, eroded.glacial_erosion.as_ref(), world.seismic.as_ref(), report);
+        config.regrow(&mut surface, &inputs, world.history.as_ref(), report);
+        config.finish_surface(&mut surface, &inputs, world.history.as_ref(), report);
+
+        world.heightmap = surface.heightmap;
+        world.temperature = surface.temperature;
+        world.biomes = surface.biomes;
+        world.hardness_map = Some(eroded.hardness.clone());
+        world.landforms = Some(eroded.landforms.clone());
+        world.water_body_map = surface.water_body_map;
+        world.water_bodies = surface.water_bodies;
+        world.lakes = Some(surface.lakes);
+        world.zlevels = surface.zlevels;
+        world.surface_z = surface.surface_z;
+        world.river_network = surface.river_network;
+        world.biome_feather_map = surface.biome_feather_map;
+        world.polar = surface.polar;
+        world.dunes = Some(surface.dunes);
+        world.loess = Some(surface.loess);
+        world.cave_biomes = Some(surface.cave_biomes);
+        world.underground_rivers = Some(surface.underground_rivers);
+        world.geothermal = Some(surface.geothermal);
+        world.mass_wasting = Some(surface.mass_wasting);
+        world.waves = Some(surface.waves);
+        world.succession = surface.succession;
+        world.flora = surface.flora;
+        world.gazetteer = surface.gazetteer;
+        world.names = Some(NameLayer::of(world));
+    }
+}
+
+// =============================================================================
+// FILE WATCHER
+// =============================================================================
+
+/// Watches a tuning file and re-runs the affected stages when it changes
+pub struct HotReload {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    params: TuningParams,
+    cache: StageCache,
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl HotReload {
+    /// Watch `path`, whose current contents produced `params`
+    pub fn new(path: &str, params: TuningParams, cache: StageCache) -> Self {
+        let path = PathBuf::from(path);
+        Self { modified: modified_time(&path), path, params, cache }
+    }
+
+    /// Check the watched file. Returns `None` when it has not been touched,
+    /// otherwise a status line describing the reload (or why it failed).
+    pub fn poll(&mut self, world: &mut WorldData) -> Option<Result<String, String>> {
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+
+        let params = match std::fs::read_to_string(&self.path).map_err(|e| e.to_string()).and_then(|json| TuningParams::from_json(&json)) {
+            Ok(params) => params,
+            Err(e) => return Some(Err(format!("{}: {}", self.path.display(), e))),
+        };
+        let Some(stage) = self.params.first_changed_stage(&params) else {
+            return Some(Ok("No generation parameters changed".to_string()));
+        };
+
+        let start = Instant::now();
+        self.cache.rerun(&params, stage, world);
+        self.params = params;
+        Some(Ok(format!(
+            "Reloaded {}: re-ran from {} in {:.1}s",
+            self.path.display(),
+            stage.name(),
+            start.elapsed().as_secs_f32()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biome_constraints::PlacementSpec;
+    use crate::waves::{self, WaveConfig};
+    use crate::world::{generate_world, WorldGenerator};
+
+    #[test]
+    fn test_first_changed_stage_is_earliest() {
+        let base = TuningParams::default();
+        assert_eq!(base.first_changed_stage(&base.clone()), None);
+
+        let mut biomes_only = base.clone();
+        biomes_only.fantasy_intensity = 1.0;
+        assert_eq!(base.first_changed_stage(&biomes_only), Some(Stage::Biomes));
+
+        let mut coast_and_biomes = biomes_only.clone();
+        coast_and_biomes.coast_complexity = 0.9;
+        assert_eq!(base.first_changed_stage(&coast_and_biomes), Some(Stage::Coast));
+
+        let mut erosion = base.clone();
+        erosion.erosion.river_erosion_rate *= 2.0;
+        assert_eq!(base.first_changed_stage(&erosion), Some(Stage::Erosion));
+
+        // Partial files fill the rest from defaults
+        let partial = TuningParams::from_json(r#"{"coast_complexity": 0.9}"#).unwrap();
+        assert_eq!(partial.erosion, ErosionParams::default());
+        assert_eq!(partial.coast_complexity, 0.9);
+    }
+
+    #[test]
+    fn test_poll_reruns_changed_stages() {
+        let mut world = generate_world(64, 32, 5);
+        let inputs = StageInputs {
+            seed: world.seed,
+            plate_map: &world.plate_map,
+            plates: &world.plates,
+            stress_map: &world.stress_map,
+            temperature: &world.temperature,
+            moisture: &world.moisture,
+        };
+        let mut cache = StageCache::new(&StageConfig::default(), &world.heightmap, &inputs);
+        let flat = Tilemap::new_with(64, 32, 0.5f32);
+        let landforms = world.landforms.clone().unwrap();
+        cache.record_erosion(&world.heightmap, None, &flat, &landforms);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tuning.json");
+        let path_str = path.to_str().unwrap();
+        let params = TuningParams::load_or_init(path_str, TuningParams::default()).unwrap();
+        assert!(path.exists());
+
+        let mut reload = HotReload::new(path_str, params.clone(), cache);
+        assert!(reload.poll(&mut world).is_none());
+
+        // With no coastline complexity the coast stage only lets the waves cut the eroded
+        // heightmap; slope failure afterwards may still move material in the world itself
+        let mut before = world.heightmap.clone();
+        waves::apply_waves(&mut before, world.scale.km_per_tile, &WaveConfig::default());
+        let edited = TuningParams { coast_complexity: 0.0, ..params };
+        std::fs::write(&path, edited.to_json()).unwrap();
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+
+        let status = reload.poll(&mut world).unwrap().unwrap();
+        assert!(status.contains("coast"), "{}", status);
+        let coasted = &reload.cache.coasted.as_ref().unwrap().heightmap;
+        assert_eq!(coasted.iter().map(|(_, _, &h)| h).collect::<Vec<_>>(), before.iter().map(|(_, _, &h)| h).collect::<Vec<_>>());
+        assert!(reload.poll(&mut world).is_none());
+
+        // Plant species are drawn again for the new biome layout
+        let flora_biomes = &world.flora.as_ref().unwrap().biomes;
+        assert!(flora_biomes.iter().zip(world.biomes.iter()).all(|((_, _, a), (_, _, b))| a == b));
+
+        // Broken files report an error and keep the world as it is
+        std::fs::write(&path, "{ not json").unwrap();
+        let later = later + std::time::Duration::from_secs(5);
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+        assert!(reload.poll(&mut world).unwrap().is_err());
+    }
+
+    #[test]
+    fn test_rerun_keeps_generator_config() {
+        let erosion = ErosionParams { hydraulic_iterations: 2_000, glacial_timesteps: 20, ..Default::default() };
+        let (mut world, mut cache) = WorldGenerator::new()
+            .size(64, 32)
+            .seed(9)
+            .erosion(erosion.clone())
+            .unique_biomes(PlacementSpec::default())
+            .generate_with_stage_cache();
+        let biomes: Vec<_> = world.biomes.iter().map(|(_, _, &b)| b).collect();
+
+        // Re-running with unchanged tuning rebuilds the same world, without the default unique biomes
+        let params = TuningParams { erosion, ..Default::default() };
+        cache.rerun(&params, Stage::Coast, &mut world);
+        assert_eq!(world.biomes.iter().map(|(_, _, &b)| b).collect::<Vec<_>>(), biomes);
+    }
+}
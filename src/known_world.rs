@@ -0,0 +1,382 @@
+//! Faction knowledge maps ("as known by")
+//!
+//! Builds the world as a single faction knows it from its history: tiles it
+//! settled, claimed, traded across, fought over or charted (see `exploration`)
+//! are explored, a fringe around them is known only by rumour, and everything
+//! else is blank. Known places are
+//! labelled with the faction's own names: exonyms in its tongue for places
+//! founded or named by others, and rumoured places may sit in the wrong spot.
+//!
+//! Renders in any map theme with the unknown blanked to paper and hatched over
+//! ("hic sunt dracones"), plus a JSON list of the places and names the faction knows.
+
+use std::collections::VecDeque;
+
+use image::{Rgb, RgbImage};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::cartography::{self, HatchArea, MapLabel, MapTheme};
+use crate::history::{Faction, FactionId, NameGenerator, WorldHistory};
+use crate::tilemap::Tilemap;
+use crate::world::WorldData;
+
+/// Tiles a settlement overlooks
+const SETTLEMENT_SIGHT: i32 = 6;
+
+/// Tiles around a battle, raid or other located event that the faction saw
+const EVENT_SIGHT: i32 = 3;
+
+/// Tiles beyond the explored area known by rumour
+const RUMOR_RADIUS: usize = 10;
+
+/// Chance that a rumoured place is marked in the wrong spot
+const MISPLACE_CHANCE: f32 = 0.35;
+
+/// Pixels per world tile in the rendered map
+const PIXELS_PER_TILE: u32 = 4;
+
+/// How far rumoured tiles are faded into the paper
+const RUMOR_FADE: f32 = 0.6;
+
+/// Hatch spacing (pixels) over unknown areas when the theme doesn't set one
+const UNKNOWN_HATCH_SPACING: u32 = 6;
+
+/// How well a faction knows a tile
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Knowledge {
+    Unknown,
+    Rumored,
+    Explored,
+}
+
+/// A place on a faction's map, under the faction's name for it
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct KnownPlace {
+    /// What the faction calls it
+    pub name: String,
+    /// What it is called by the people who named it
+    pub true_name: String,
+    /// ocean, sea, lake, river, settlement, strait, cape or island
+    pub kind: String,
+    /// Where the faction believes it is
+    pub x: usize,
+    pub y: usize,
+    pub knowledge: Knowledge,
+    /// The faction has it in the wrong place
+    pub misplaced: bool,
+}
+
+/// The world as known by one faction
+#[derive(Clone)]
+pub struct KnownWorld {
+    pub faction: FactionId,
+    pub faction_name: String,
+    pub knowledge: Tilemap<Knowledge>,
+    pub places: Vec<KnownPlace>,
+}
+
+/// Summary written alongside the rendered map
+#[derive(serde::Serialize)]
+struct KnownWorldExport<'a> {
+    faction: &'a str,
+    explored_fraction: f32,
+    rumored_fraction: f32,
+    places: &'a [KnownPlace],
+}
+
+impl KnownWorld {
+    pub fn knowledge_at(&self, x: usize, y: usize) -> Knowledge {
+        *self.knowledge.get(x, y)
+    }
+
+    /// Fraction of the map at the given knowledge level
+    pub fn fraction(&self, level: Knowledge) -> f32 {
+        let count = self.knowledge.iter().filter(|(_, _, &k)| k == level).count();
+        count as f32 / (self.knowledge.width * self.knowledge.height) as f32
+    }
+
+    pub fn to_json(&self) -> String {
+        let export = KnownWorldExport {
+            faction: &self.faction_name,
+            explored_fraction: self.fraction(Knowledge::Explored),
+            rumored_fraction: self.fraction(Knowledge::Rumored),
+            places: &self.places,
+        };
+        serde_json::to_string_pretty(&export).expect("known world is always serializable")
+    }
+
+    /// Render the faction's map in the given theme: explored land as the theme
+    /// draws it, rumour faded into the paper, the unknown hatched over
+    pub fn render(&self, world: &WorldData, theme: &MapTheme, faction_color: (u8, u8, u8)) -> RgbImage {
+        let s = PIXELS_PER_TILE;
+        let mut img = cartography::render_base(world, theme, world.width * s as usize, world.height * s as usize);
+
+        // Themes that hatch the unknown set its linework; others get a plain ink hatch
+        let (spacing, hatch) = match &theme.hatching {
+            Some(h) if h.over == HatchArea::Unknown => (h.spacing, h.color),
+            _ => (UNKNOWN_HATCH_SPACING, theme.ink),
+        };
+        for (x, y, pixel) in img.enumerate_pixels_mut() {
+            let level = *self.knowledge.get((x / s) as usize, (y / s) as usize);
+            let color = (pixel.0[0], pixel.0[1], pixel.0[2]);
+            let color = match level {
+                Knowledge::Explored => continue,
+                Knowledge::Rumored => cartography::blend(color, theme.paper, RUMOR_FADE),
+                Knowledge::Unknown if cartography::on_hatch(x, y, spacing) => hatch,
+                Knowledge::Unknown => theme.paper,
+            };
+            *pixel = Rgb([color.0, color.1, color.2]);
+        }
+
+        for place in &self.places {
+            let color = if place.kind == "settlement" { faction_color } else { theme.ink };
+            let (cx, cy) = (place.x as u32 * s + s / 2, place.y as u32 * s + s / 2);
+            for dy in 0..3u32 {
+                for dx in 0..3u32 {
+                    let (px, py) = ((cx + dx).saturating_sub(1), (cy + dy).saturating_sub(1));
+                    if px < img.width() && py < img.height() {
+                        img.put_pixel(px, py, Rgb([color.0, color.1, color.2]));
+                    }
+                }
+            }
+        }
+
+        if let Some(style) = &theme.labels {
+            let labels: Vec<MapLabel> = self
+                .places
+                .iter()
+                .map(|p| MapLabel { text: p.name.clone(), x: p.x, y: p.y })
+                .collect();
+            cartography::draw_labels(&mut img, world, theme, style, &labels);
+        }
+
+        img
+    }
+
+    /// Write `PREFIX.png` and `PREFIX.json`. Returns the written paths.
+    pub fn export(
+        &self,
+        world: &WorldData,
+        theme: &MapTheme,
+        faction_color: (u8, u8, u8),
+        prefix: &str,
+    ) -> Result<Vec<String>, String> {
+        let png = format!("{}.png", prefix);
+        self.render(world, theme, faction_color).save(&png).map_err(|e| e.to_string())?;
+        let json = format!("{}.json", prefix);
+        std::fs::write(&json, self.to_json()).map_err(|e| e.to_string())?;
+        Ok(vec![png, json])
+    }
+}
+
+/// Mark every tile within `radius` of (cx, cy) as explored
+fn reveal(knowledge: &mut Tilemap<Knowledge>, cx: usize, cy: usize, radius: i32) {
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            if dx * dx + dy * dy > radius * radius {
+                continue;
+            }
+            let y = cy as i32 + dy;
+            if y < 0 || y >= knowledge.height as i32 {
+                continue;
+            }
+            let x = (cx as i32 + dx).rem_euclid(knowledge.width as i32) as usize;
+            knowledge.set(x, y as usize, Knowledge::Explored);
+        }
+    }
+}
+
+/// File-name-safe form of a faction name ("The Iron Pact" -> "the_iron_pact")
+pub fn slug(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Find a faction by name (case-insensitive) or numeric id
+pub fn find_faction<'a>(history: &'a WorldHistory, spec: &str) -> Option<&'a Faction> {
+    let spec = spec.trim();
+    history
+        .factions
+        .all()
+        .find(|f| f.name.eq_ignore_ascii_case(spec) || spec.parse::<u32>().is_ok_and(|id| id == f.id.0))
+}
+
+/// Build the world as known by `faction`
+pub fn known_world(world: &WorldData, history: &WorldHistory, faction: &Faction) -> KnownWorld {
+    let width = world.width;
+    let height = world.height;
+    let id = faction.id;
+    let mut knowledge = Tilemap::new_with(width, height, Knowledge::Unknown);
+
+    // Settlements founded or held by the faction, and the land it claimed
+    let owns = |s: &crate::history::Settlement| {
+        s.original_faction == id || s.current_faction == Some(id) || s.occupations.iter().any(|o| o.0 == id)
+    };
+    for settlement in history.territories.settlements.values().filter(|s| owns(s)) {
+        reveal(&mut knowledge, settlement.x, settlement.y, SETTLEMENT_SIGHT);
+    }
+    for territory in history.territories.territories.iter().filter(|t| t.faction == id) {
+        for &(x, y) in &territory.tiles {
+            knowledge.set(x, y, Knowledge::Explored);
+        }
+    }
+
+    // Trade routes from its settlements
+    let is_own_site = |(x, y): (usize, usize)| history.territories.settlement_at(x, y).is_some_and(owns);
+    for route in history.trade.routes.values() {
+        if is_own_site(route.start) || is_own_site(route.end) {
+            for &(x, y) in &route.path {
+                reveal(&mut knowledge, x, y, 1);
+            }
+        }
+    }
+
+    // Battles, raids and expeditions it took part in
+    for event in history.timeline.events.values() {
+        if event.faction == Some(id) || event.other_faction == Some(id) {
+            if let Some((x, y)) = event.location {
+                reveal(&mut knowledge, x, y, EVENT_SIGHT);
+            }
+        }
+    }
+
+    // Everything its expeditions charted, or it copied from others' charts
+    if let Some(chart) = world.exploration.as_ref().and_then(|r| r.chart(id)) {
+        for (x, y, charted) in chart.charted.iter() {
+            if charted.is_some() {
+                knowledge.set(x, y, Knowledge::Explored);
+            }
+        }
+    }
+
+    // Rumour: a fringe around everything explored
+    let mut distance = Tilemap::new_with(width, height, usize::MAX);
+    let mut queue = VecDeque::new();
+    for (x, y, &k) in knowledge.iter() {
+        if k == Knowledge::Explored {
+            distance.set(x, y, 0);
+            queue.push_back((x, y));
+        }
+    }
+    while let Some((x, y)) = queue.pop_front() {
+        let d = *distance.get(x, y);
+        if d >= RUMOR_RADIUS {
+            continue;
+        }
+        for (nx, ny) in distance.neighbors(x, y) {
+            if *distance.get(nx, ny) == usize::MAX {
+                distance.set(nx, ny, d + 1);
+                knowledge.set(nx, ny, Knowledge::Rumored);
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    let places = name_places(world, history, faction, &knowledge);
+
+    KnownWorld {
+        faction: id,
+        faction_name: faction.name.clone(),
+        knowledge,
+        places,
+    }
+}
+
+/// Places the faction knows of, under its own names
+fn name_places(world: &WorldData, history: &WorldHistory, faction: &Faction, knowledge: &Tilemap<Knowledge>) -> Vec<KnownPlace> {
+    let mut rng = ChaCha8Rng::seed_from_u64(world.seed ^ (0x6B0E_u64 << 20) ^ faction.id.0 as u64);
+    let names = NameGenerator::new(world.seed.wrapping_add(faction.id.0 as u64));
+    let mut places = Vec::new();
+
+    let mut place = |rng: &mut ChaCha8Rng, true_name: &str, own: bool, exonym: String, kind: &str, x: usize, y: usize| {
+        let level = *knowledge.get(x, y);
+        if level == Knowledge::Unknown {
+            return;
+        }
+        let name = if own { true_name.to_string() } else { exonym };
+        let misplaced = level == Knowledge::Rumored && rng.gen::<f32>() < MISPLACE_CHANCE;
+        let (x, y) = if misplaced {
+            let r = (RUMOR_RADIUS / 2) as i32;
+            knowledge.wrap_coords(x as i32 + rng.gen_range(-r..=r), y as i32 + rng.gen_range(-r..=r))
+        } else {
+            (x, y)
+        };
+        places.push(KnownPlace {
+            name,
+            true_name: true_name.to_string(),
+            kind: kind.to_string(),
+            x,
+            y,
+            knowledge: level,
+            misplaced,
+        });
+    };
+
+    let mut settlements: Vec<_> = history.territories.settlements.values().collect();
+    settlements.sort_by_key(|s| s.id.0);
+    for s in settlements {
+        let own = s.original_faction == faction.id;
+        let exonym = names.settlement_name(faction.species, &mut rng);
+        place(&mut rng, &s.name, own, exonym, "settlement", s.x, s.y);
+    }
+
+    if let Some(gazetteer) = world.gazetteer.as_ref() {
+        for entry in &gazetteer.entries {
+            let own = entry.named_by.as_deref() == Some(faction.name.as_str());
+            let exonym = names.water_name(entry.kind.name(), faction.species, &mut rng);
+            place(&mut rng, &entry.name, own, exonym, entry.kind.name(), entry.x, entry.y);
+        }
+    }
+
+    // Discoveries go by the names their discoverers gave them
+    if let Some(record) = world.exploration.as_ref() {
+        for d in &record.discoveries {
+            place(&mut rng, &d.name, true, d.name.clone(), d.kind.name(), d.x, d.y);
+        }
+    }
+
+    places
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reveal_and_rumor() {
+        let mut knowledge = Tilemap::new_with(40, 20, Knowledge::Unknown);
+        reveal(&mut knowledge, 0, 10, 2);
+        assert_eq!(*knowledge.get(0, 10), Knowledge::Explored);
+        // Sight wraps around the east-west seam
+        assert_eq!(*knowledge.get(39, 10), Knowledge::Explored);
+        assert_eq!(*knowledge.get(0, 13), Knowledge::Unknown);
+    }
+
+    #[test]
+    fn test_known_world_from_history() {
+        let world = crate::world::generate_world(96, 48, 42);
+        let history = world.history.as_ref().unwrap();
+        let faction = history.factions.all().min_by_key(|f| f.id.0).unwrap();
+
+        let known = known_world(&world, history, faction);
+        assert!(known.fraction(Knowledge::Explored) > 0.0);
+        assert!(known.fraction(Knowledge::Unknown) > 0.0);
+        assert!(known.places.iter().all(|p| p.knowledge != Knowledge::Unknown));
+        assert!(known
+            .places
+            .iter()
+            .filter(|p| !p.misplaced)
+            .all(|p| known.knowledge_at(p.x, p.y) != Knowledge::Unknown));
+
+        assert_eq!(slug("The Iron-Pact"), "the_iron_pact");
+        let by_name = find_faction(history, &faction.name.to_uppercase()).unwrap();
+        assert_eq!(by_name.id, faction.id);
+
+        let img = known.render(&world, &MapTheme::default(), faction.color);
+        assert_eq!(img.width(), 96 * PIXELS_PER_TILE);
+    }
+}
@@ -0,0 +1,496 @@
+//! Lake water balance and the lake graph
+//!
+//! Every lake gathers rain from its catchment and loses water to evaporation
+//! from its surface. A lake with water to spare overflows at the lowest pass
+//! on its rim and sends an outlet river on to the next lake or the sea, so
+//! upland lakes feed chains of lakes below them. A lake that evaporates all it
+//! receives has no outlet: it is terminal, and the salts its rivers bring stay
+//! behind (Great Salt Lake, Caspian, Aral).
+//!
+//! Depression filling keeps lakes as sinks, so the outlet rivers between them
+//! never appear in the flow routing. The graph records them explicitly, with
+//! each lake's inflow, outflow and residence time, so boat and trade routing
+//! can follow a chain of lakes down to the sea.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::erosion::rivers::{compute_flow_direction, fill_depressions_public, DX, DY, NO_FLOW};
+use crate::tilemap::Tilemap;
+use crate::water_bodies::{WaterBody, WaterBodyId, WaterBodyType};
+
+/// Rain at moisture 1.0 (m per year)
+const MAX_PRECIPITATION_M: f32 = 2.0;
+
+/// Lake surface evaporation (m per year) at 0°C, and per degree warmer
+const EVAPORATION_BASE_M: f32 = 0.25;
+const EVAPORATION_PER_DEGREE_M: f32 = 0.06;
+
+/// Shallowest depth a lake tile is counted at (m)
+const MIN_DEPTH_M: f32 = 1.0;
+
+const SECONDS_PER_YEAR: f32 = 31_557_600.0;
+
+/// Where a lake overflows
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Outlet {
+    /// Lake or ocean the outlet river runs into
+    pub into: WaterBodyId,
+    /// Highest ground the outlet river crosses (m)
+    pub sill: f32,
+    /// Tiles of the outlet river, from the lake shore to the receiving water
+    pub path: Vec<(usize, usize)>,
+}
+
+/// Water balance of one lake
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Lake {
+    pub id: WaterBodyId,
+    pub area_km2: f32,
+    pub volume_km3: f32,
+    /// Land tiles draining into the lake
+    pub catchment_tiles: usize,
+    /// Rain on the lake, runoff from its catchment and outflow of lakes above (m³/s)
+    pub inflow: f32,
+    /// Evaporation from the lake surface (m³/s)
+    pub evaporation: f32,
+    /// Water leaving through the outlet (m³/s); zero for terminal lakes
+    pub outflow: f32,
+    /// Years an average drop of water stays in the lake
+    pub residence_years: f32,
+    /// Lowest pass out of the basin, whether or not the lake reaches it
+    pub outlet: Option<Outlet>,
+    /// No outflow: evaporation concentrates the salts
+    pub saline: bool,
+}
+
+impl Lake {
+    /// Whether the lake overflows into its outlet
+    pub fn is_open(&self) -> bool {
+        self.outflow > 0.0 && self.outlet.is_some()
+    }
+}
+
+/// Lakes linked by their outlet rivers
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct LakeGraph {
+    pub lakes: Vec<Lake>,
+}
+
+impl LakeGraph {
+    pub fn get(&self, id: WaterBodyId) -> Option<&Lake> {
+        self.lakes.iter().find(|lake| lake.id == id)
+    }
+
+    /// Water body the lake overflows into (a lake or the ocean)
+    pub fn downstream(&self, id: WaterBodyId) -> Option<WaterBodyId> {
+        self.get(id).filter(|lake| lake.is_open()).and_then(|lake| lake.outlet.as_ref()).map(|o| o.into)
+    }
+
+    /// Lakes overflowing into `id`
+    pub fn upstream(&self, id: WaterBodyId) -> impl Iterator<Item = &Lake> {
+        self.lakes.iter().filter(move |lake| lake.is_open() && lake.outlet.as_ref().is_some_and(|o| o.into == id))
+    }
+
+    /// The lake and every water body below it, ending at the ocean or a terminal lake
+    pub fn chain(&self, id: WaterBodyId) -> Vec<WaterBodyId> {
+        let mut chain = vec![id];
+        while let Some(next) = self.downstream(*chain.last().unwrap()) {
+            if chain.contains(&next) {
+                break;
+            }
+            chain.push(next);
+        }
+        chain
+    }
+
+    /// Outlet rivers a boat takes from one water body to another, in travel
+    /// order (sailing across each lake between them). `None` when they are not
+    /// linked by flowing outlets; upstream legs are sailed against the current.
+    pub fn route(&self, from: WaterBodyId, to: WaterBodyId) -> Option<Vec<&Outlet>> {
+        // Each flowing outlet is an undirected edge between two water bodies
+        let edges: Vec<(WaterBodyId, WaterBodyId, &Outlet)> = self
+            .lakes
+            .iter()
+            .filter(|lake| lake.is_open())
+            .filter_map(|lake| lake.outlet.as_ref().map(|o| (lake.id, o.into, o)))
+            .collect();
+
+        let mut came_from: Vec<(WaterBodyId, usize)> = vec![(from, usize::MAX)];
+        let mut queue = VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut legs = Vec::new();
+                let mut at = current;
+                while let Some(&(_, edge)) = came_from.iter().find(|(id, _)| *id == at).filter(|(_, e)| *e != usize::MAX) {
+                    let (a, b, outlet) = edges[edge];
+                    legs.push(outlet);
+                    at = if a == at { b } else { a };
+                }
+                legs.reverse();
+                return Some(legs);
+            }
+            for (i, &(a, b, _)) in edges.iter().enumerate() {
+                let next = if a == current { b } else if b == current { a } else { continue };
+                if !came_from.iter().any(|(id, _)| *id == next) {
+                    came_from.push((next, i));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Tiles of every flowing outlet river
+    pub fn outlet_map(&self, width: usize, height: usize) -> Tilemap<bool> {
+        let mut map = Tilemap::new_with(width, height, false);
+        for outlet in self.lakes.iter().filter(|lake| lake.is_open()).filter_map(|lake| lake.outlet.as_ref()) {
+            for &(x, y) in &outlet.path {
+                map.set(x, y, true);
+            }
+        }
+        map
+    }
+
+    /// Lakes that overflow into another lake
+    pub fn chained(&self) -> usize {
+        self.lakes
+            .iter()
+            .filter(|lake| self.downstream(lake.id).is_some_and(|id| id.is_lake()))
+            .count()
+    }
+
+    pub fn saline(&self) -> usize {
+        self.lakes.iter().filter(|lake| lake.saline).count()
+    }
+}
+
+/// Balance every lake's water and link the lakes by their outlet rivers.
+/// Moisture stands in for rainfall and temperature drives evaporation.
+pub fn build_lake_graph(
+    heightmap: &Tilemap<f32>,
+    water_map: &Tilemap<WaterBodyId>,
+    water_bodies: &[WaterBody],
+    moisture: &Tilemap<f32>,
+    temperature: &Tilemap<f32>,
+    km_per_tile: f32,
+) -> LakeGraph {
+    let ids: Vec<WaterBodyId> =
+        water_bodies.iter().filter(|wb| wb.body_type == WaterBodyType::Lake).map(|wb| wb.id).collect();
+    if ids.is_empty() {
+        return LakeGraph::default();
+    }
+    let mut slots = vec![usize::MAX; ids.iter().map(|id| id.0 as usize + 1).max().unwrap_or(0)];
+    for (i, id) in ids.iter().enumerate() {
+        slots[id.0 as usize] = i;
+    }
+    let index = |id: WaterBodyId| slots.get(id.0 as usize).copied().filter(|&i| i != usize::MAX);
+    let is_water = |x: usize, y: usize| *heightmap.get(x, y) <= 0.0 && !water_map.get(x, y).is_none();
+    let tile_m2 = km_per_tile * km_per_tile * 1.0e6;
+    let per_second = tile_m2 / SECONDS_PER_YEAR;
+
+    // Which water body each land tile drains into, resolved from the sea upward
+    let surface = fill_depressions_public(heightmap);
+    let flow_dir = compute_flow_direction(&surface);
+    let mut cells: Vec<(usize, usize, f32)> =
+        surface.iter().filter(|&(x, y, _)| !is_water(x, y)).map(|(x, y, &h)| (x, y, h)).collect();
+    cells.sort_by(|a, b| a.2.total_cmp(&b.2));
+    let mut sink = Tilemap::new_with(heightmap.width, heightmap.height, WaterBodyId::NONE);
+    let mut runoff = vec![0.0f32; ids.len()];
+    let mut catchment = vec![0usize; ids.len()];
+    for (x, y, _) in cells {
+        let dir = *flow_dir.get(x, y);
+        if dir == NO_FLOW {
+            continue;
+        }
+        let nx = (x as i32 + DX[dir as usize]).rem_euclid(heightmap.width as i32) as usize;
+        let ny = (y as i32 + DY[dir as usize]) as usize;
+        let target = if is_water(nx, ny) { *water_map.get(nx, ny) } else { *sink.get(nx, ny) };
+        sink.set(x, y, target);
+        if let Some(i) = index(target) {
+            // Dry ground soaks up most of what falls on it
+            let m = moisture.get(x, y).clamp(0.0, 1.0);
+            runoff[i] += MAX_PRECIPITATION_M * m * m * per_second;
+            catchment[i] += 1;
+        }
+    }
+
+    // Rain on and evaporation from the lake surfaces
+    let mut rain = vec![0.0f32; ids.len()];
+    let mut evaporation = vec![0.0f32; ids.len()];
+    let mut volume_m3 = vec![0.0f32; ids.len()];
+    let mut shores: Vec<Vec<(usize, usize)>> = vec![Vec::new(); ids.len()];
+    for (x, y, id) in water_map.iter() {
+        let Some(i) = index(*id) else { continue };
+        if !is_water(x, y) {
+            continue;
+        }
+        shores[i].push((x, y));
+        rain[i] += MAX_PRECIPITATION_M * moisture.get(x, y).clamp(0.0, 1.0) * per_second;
+        evaporation[i] += lake_evaporation(*temperature.get(x, y)) * per_second;
+        volume_m3[i] += (-heightmap.get(x, y)).max(MIN_DEPTH_M) * tile_m2;
+    }
+
+    // Lowest pass out of each basin. Lakes sharing their lowest pass would
+    // spill into each other; they fill as one basin, whose deepest lake then
+    // looks past all of them.
+    let mut search = OutletSearch::new(heightmap.width, heightmap.height);
+    let mut outlets: Vec<Option<Outlet>> = ids
+        .iter()
+        .zip(&shores)
+        .map(|(&id, tiles)| search.find(heightmap, water_map, &is_water, tiles, &[id]))
+        .collect();
+    let mut next: Vec<Option<usize>> = outlets.iter().map(|o| o.as_ref().and_then(|o| index(o.into))).collect();
+    let mut basins: Vec<Vec<WaterBodyId>> = ids.iter().map(|&id| vec![id]).collect();
+    for _ in 0..ids.len() {
+        let Some(cycle) = find_cycle(&next) else { break };
+        let deepest = *cycle
+            .iter()
+            .min_by(|&&a, &&b| lake_floor(water_bodies, ids[a]).total_cmp(&lake_floor(water_bodies, ids[b])))
+            .unwrap();
+        let mut members: Vec<WaterBodyId> = cycle.iter().flat_map(|&i| basins[i].iter().copied()).collect();
+        members.sort_by_key(|id| id.0);
+        members.dedup();
+        for &i in &cycle {
+            basins[i] = members.clone();
+        }
+        outlets[deepest] = search.find(heightmap, water_map, &is_water, &shores[deepest], &members);
+        next[deepest] = outlets[deepest].as_ref().and_then(|o| index(o.into));
+    }
+
+    // Balance from the headwater lakes down, so each lake knows what the lakes above send it
+    let order = downstream_order(&next);
+    let mut inflow: Vec<f32> = (0..ids.len()).map(|i| rain[i] + runoff[i]).collect();
+    let mut outflow = vec![0.0f32; ids.len()];
+    for i in order {
+        let surplus = inflow[i] - evaporation[i];
+        if surplus <= 0.0 {
+            continue;
+        }
+        if outlets[i].is_some() {
+            outflow[i] = surplus;
+            if let Some(j) = next[i] {
+                inflow[j] += surplus;
+            }
+        }
+    }
+
+    let lakes = ids
+        .iter()
+        .enumerate()
+        .map(|(i, &id)| {
+            let loss = outflow[i] + evaporation[i].min(inflow[i]);
+            Lake {
+                id,
+                area_km2: shores[i].len() as f32 * km_per_tile * km_per_tile,
+                volume_km3: volume_m3[i] / 1.0e9,
+                catchment_tiles: catchment[i],
+                inflow: inflow[i],
+                evaporation: evaporation[i],
+                outflow: outflow[i],
+                residence_years: if loss > 0.0 { volume_m3[i] / (loss * SECONDS_PER_YEAR) } else { f32::INFINITY },
+                outlet: outlets[i].take(),
+                saline: outflow[i] <= 0.0,
+            }
+        })
+        .collect();
+    LakeGraph { lakes }
+}
+
+/// Lake surface evaporation (m per year) at a mean temperature (°C)
+fn lake_evaporation(temperature: f32) -> f32 {
+    (EVAPORATION_BASE_M + EVAPORATION_PER_DEGREE_M * temperature).clamp(0.05, 2.5)
+}
+
+fn lake_floor(water_bodies: &[WaterBody], id: WaterBodyId) -> f32 {
+    water_bodies.iter().find(|wb| wb.id == id).map_or(0.0, |wb| wb.min_elevation)
+}
+
+/// Path entry ordered by the highest ground crossed so far, then by length
+#[derive(PartialEq)]
+struct Step {
+    sill: f32,
+    length: usize,
+    x: usize,
+    y: usize,
+}
+
+impl Eq for Step {}
+
+impl Ord for Step {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.sill.total_cmp(&self.sill).then(other.length.cmp(&self.length))
+    }
+}
+
+impl PartialOrd for Step {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Reusable buffers for the outlet searches, one per lake
+struct OutletSearch {
+    came_from: Tilemap<(usize, usize)>,
+    /// Search that last reached each tile
+    visited: Tilemap<u32>,
+    round: u32,
+}
+
+impl OutletSearch {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            came_from: Tilemap::new_with(width, height, (0, 0)),
+            visited: Tilemap::new_with(width, height, 0),
+            round: 0,
+        }
+    }
+
+    /// Route from a lake (its water tiles) over the lowest pass to the nearest
+    /// water body that is not one of `exclude`
+    fn find(
+        &mut self,
+        heightmap: &Tilemap<f32>,
+        water_map: &Tilemap<WaterBodyId>,
+        is_water: &impl Fn(usize, usize) -> bool,
+        lake: &[(usize, usize)],
+        exclude: &[WaterBodyId],
+    ) -> Option<Outlet> {
+        self.round += 1;
+        let round = self.round;
+        let mut open = BinaryHeap::new();
+        for &(x, y) in lake {
+            self.visited.set(x, y, round);
+            self.came_from.set(x, y, (x, y));
+            open.push(Step { sill: f32::MIN, length: 0, x, y });
+        }
+
+        while let Some(Step { sill, length, x, y }) = open.pop() {
+            for (nx, ny) in heightmap.neighbors(x, y) {
+                if *self.visited.get(nx, ny) == round {
+                    continue;
+                }
+                self.visited.set(nx, ny, round);
+                self.came_from.set(nx, ny, (x, y));
+                let id = *water_map.get(nx, ny);
+                if is_water(nx, ny) && !exclude.contains(&id) {
+                    // Walk back to the lake shore, which points at itself
+                    let mut path = Vec::new();
+                    let mut at = (x, y);
+                    while *self.came_from.get(at.0, at.1) != at {
+                        path.push(at);
+                        at = *self.came_from.get(at.0, at.1);
+                    }
+                    path.reverse();
+                    return Some(Outlet { into: id, sill: sill.max(0.0), path });
+                }
+                let h = if is_water(nx, ny) { 0.0 } else { *heightmap.get(nx, ny) };
+                open.push(Step { sill: sill.max(h), length: length + 1, x: nx, y: ny });
+            }
+        }
+        None
+    }
+}
+
+/// Lakes (as indices) that overflow round in a loop, if any do. `next[i]` is
+/// the lake that lake `i` spills into.
+fn find_cycle(next: &[Option<usize>]) -> Option<Vec<usize>> {
+    // 0 = unvisited, 1 = on the current walk, 2 = known to end outside a loop
+    let mut state = vec![0u8; next.len()];
+    for start in 0..next.len() {
+        let mut walk = Vec::new();
+        let mut at = Some(start);
+        while let Some(i) = at.filter(|&i| state[i] != 2) {
+            if state[i] == 1 {
+                let pos = walk.iter().position(|&w| w == i).unwrap();
+                return Some(walk[pos..].to_vec());
+            }
+            state[i] = 1;
+            walk.push(i);
+            at = next[i];
+        }
+        for i in walk {
+            state[i] = 2;
+        }
+    }
+    None
+}
+
+/// Lakes (as indices) ordered so every lake comes after the lakes spilling into it
+fn downstream_order(next: &[Option<usize>]) -> Vec<usize> {
+    let mut feeders = vec![0usize; next.len()];
+    for n in next.iter().flatten() {
+        feeders[*n] += 1;
+    }
+    let mut ready: VecDeque<usize> = (0..next.len()).filter(|&i| feeders[i] == 0).collect();
+    let mut order = Vec::with_capacity(next.len());
+    while let Some(i) = ready.pop_front() {
+        order.push(i);
+        if let Some(n) = next[i] {
+            feeders[n] -= 1;
+            if feeders[n] == 0 {
+                ready.push_back(n);
+            }
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::water_bodies::detect_water_bodies;
+
+    #[test]
+    fn test_lake_chain_spills_to_sea_and_dry_basin_turns_saline() {
+        // A wet ridge: an upper lake spills over a low pass into a lower lake,
+        // which spills to the sea. A desert basin to the east has no surplus.
+        let mut heightmap = Tilemap::new_with(40, 20, 400.0f32);
+        for x in 0..40 {
+            heightmap.set(x, 19, -500.0);
+        }
+        let mut dig = |x0: usize, y0: usize, x1: usize, y1: usize, h: f32| {
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    heightmap.set(x, y, h);
+                }
+            }
+        };
+        dig(4, 3, 7, 6, -30.0); // upper lake
+        dig(6, 7, 6, 9, 50.0); // pass into the lower lake
+        dig(4, 10, 8, 13, -60.0); // lower lake
+        dig(6, 14, 6, 18, 40.0); // pass to the sea
+        dig(28, 6, 32, 10, -20.0); // desert basin
+        let (water_map, water_bodies) = detect_water_bodies(&heightmap);
+        let (mut moisture, mut temperature) = (Tilemap::new_with(40, 20, 0.9f32), Tilemap::new_with(40, 20, 10.0f32));
+        for (_, _, m) in moisture.iter_mut().filter(|(x, _, _)| *x >= 20) {
+            *m = 0.05;
+        }
+        for (_, _, t) in temperature.iter_mut().filter(|(x, _, _)| *x >= 20) {
+            *t = 30.0;
+        }
+        let graph = build_lake_graph(&heightmap, &water_map, &water_bodies, &moisture, &temperature, 10.0);
+
+        let upper = *water_map.get(5, 4);
+        let lower = *water_map.get(5, 11);
+        let basin = *water_map.get(30, 8);
+        assert_eq!(graph.lakes.len(), 3);
+        assert_eq!(graph.chain(upper), vec![upper, lower, WaterBodyId::OCEAN]);
+        assert_eq!(graph.chained(), 1);
+        // The lower lake passes on what the upper one sends it
+        assert!(graph.get(lower).unwrap().outflow > graph.get(upper).unwrap().outflow);
+        assert!(graph.get(upper).unwrap().residence_years.is_finite());
+        assert_eq!(graph.get(upper).unwrap().outlet.as_ref().unwrap().sill, 50.0);
+
+        let dry = graph.get(basin).unwrap();
+        assert!(dry.saline && dry.outflow == 0.0 && graph.downstream(basin).is_none());
+        assert_eq!(graph.saline(), 1);
+
+        // Boats follow the chain both ways, but cannot reach the desert basin
+        assert_eq!(graph.route(upper, WaterBodyId::OCEAN).unwrap().len(), 2);
+        let back = graph.route(WaterBodyId::OCEAN, upper).unwrap();
+        assert_eq!(back[0].into, WaterBodyId::OCEAN);
+        assert!(graph.route(upper, basin).is_none());
+        assert!(*graph.outlet_map(40, 20).get(6, 8));
+    }
+}
@@ -0,0 +1,426 @@
+//! Specialized landforms
+//!
+//! Post-erosion landforms the droplet model alone can't produce, chosen by rock
+//! type and climate or forced for hand-picked regions:
+//! - Mesas and buttes: flat-lying strata in arid uplands, each capped by a hard
+//!   layer over softer rock, weather back into flat benches bounded by cliffs
+//! - River terraces: stepped benches along major valleys, each an abandoned
+//!   floodplain left behind as the river cut down (level 1 is the youngest)
+//! - Badlands: soft arid sediments etched by dense downslope rills
+//!
+//! Ocean tiles are never touched, and no land is lowered below sea level.
+
+use std::collections::VecDeque;
+
+use noise::{NoiseFn, Perlin, Seedable};
+
+use crate::erosion::rivers::{compute_flow_accumulation, compute_flow_direction};
+use crate::erosion::RockType;
+use crate::tilemap::Tilemap;
+
+// =============================================================================
+// CONFIGURATION
+// =============================================================================
+
+/// Lowest elevation (m) badland rills may carve down to
+const MIN_LAND: f32 = 1.0;
+
+/// Slope (m per tile) below which soft ground is too flat to gully
+const BADLAND_MIN_SLOPE: f32 = 5.0;
+
+/// How far (fraction of a stratum) cliff lines wander with the noise
+const STRATUM_WANDER: f64 = 0.35;
+
+/// A kind of specialized landform
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Landform {
+    #[default]
+    None,
+    Mesa,
+    Terrace,
+    Badlands,
+}
+
+impl Landform {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Landform::None => "None",
+            Landform::Mesa => "Mesa",
+            Landform::Terrace => "River terrace",
+            Landform::Badlands => "Badlands",
+        }
+    }
+}
+
+/// A rectangle of the map forced to one landform (`Landform::None` suppresses them)
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct LandformRegion {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    pub landform: Landform,
+}
+
+impl LandformRegion {
+    fn contains(&self, x: usize, y: usize, map_width: usize) -> bool {
+        let dx = (x + map_width - self.x) % map_width;
+        dx < self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Parameters for specialized landforms
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LandformParams {
+    /// Blend between the eroded terrain (0) and the full landform (1)
+    pub strength: f32,
+    /// Moisture below which a region counts as arid
+    pub arid_moisture: f32,
+    /// Lowest elevation (m) of mesa country
+    pub mesa_min_elevation: f32,
+    /// Thickness (m) of one caprock-over-soft-rock stratum
+    pub stratum_thickness: f32,
+    /// How abruptly each stratum's cliff rises above its bench (higher = flatter benches)
+    pub caprock_sharpness: f32,
+    /// Flow accumulation of rivers big enough to leave terraces
+    pub terrace_flow: f32,
+    /// Distance (tiles) from the channel that terraces reach
+    pub terrace_radius: usize,
+    /// Height (m) of each terrace step above the one below
+    pub terrace_step: f32,
+    /// Number of terrace levels above the modern floodplain
+    pub terrace_levels: usize,
+    /// Depth (m) of badland rills
+    pub rill_depth: f32,
+    /// Rills per tile across the slope
+    pub rill_frequency: f32,
+    /// Regions forced to a landform regardless of rock and climate
+    pub regions: Vec<LandformRegion>,
+}
+
+impl Default for LandformParams {
+    fn default() -> Self {
+        Self {
+            strength: 1.0,
+            arid_moisture: 0.3,
+            mesa_min_elevation: 300.0,
+            stratum_thickness: 120.0,
+            caprock_sharpness: 4.0,
+            terrace_flow: 40.0,
+            terrace_radius: 4,
+            terrace_step: 25.0,
+            terrace_levels: 3,
+            rill_depth: 15.0,
+            rill_frequency: 0.35,
+            regions: Vec::new(),
+        }
+    }
+}
+
+impl LandformParams {
+    /// Load parameters (and forced regions) from a JSON file; missing fields take defaults
+    pub fn load(path: &str) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Where landforms were built
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct LandformMap {
+    pub landform: Tilemap<Landform>,
+    /// Terrace level per tile (0 = none, 1 = youngest, higher = older and higher)
+    pub terrace_level: Tilemap<u8>,
+}
+
+impl LandformMap {
+    /// Number of tiles with the given landform
+    pub fn count(&self, kind: Landform) -> usize {
+        self.landform.iter().filter(|(_, _, &l)| l == kind).count()
+    }
+
+    /// Short description for tile info panels
+    pub fn describe(&self, x: usize, y: usize) -> Option<String> {
+        match *self.landform.get(x, y) {
+            Landform::None => None,
+            Landform::Terrace => Some(format!("River terrace (level {})", self.terrace_level.get(x, y))),
+            kind => Some(kind.name().to_string()),
+        }
+    }
+}
+
+// =============================================================================
+// LANDFORMS
+// =============================================================================
+
+/// Choose and build landforms on an eroded heightmap
+pub fn apply_landforms(
+    heightmap: &mut Tilemap<f32>,
+    rocks: &Tilemap<RockType>,
+    moisture: &Tilemap<f32>,
+    params: &LandformParams,
+    seed: u64,
+) -> LandformMap {
+    let (width, height) = (heightmap.width, heightmap.height);
+    let mut map = LandformMap {
+        landform: Tilemap::new_with(width, height, Landform::None),
+        terrace_level: Tilemap::new_with(width, height, 0u8),
+    };
+    if params.strength <= 0.0 {
+        return map;
+    }
+
+    classify(heightmap, rocks, moisture, params, &mut map);
+    build_terraces(heightmap, params, &mut map);
+    build_mesas(heightmap, params, &map, seed);
+    build_badlands(heightmap, params, &map, seed);
+    map
+}
+
+/// Rock type and climate decide mesas and badlands; regions override both
+fn classify(
+    heightmap: &Tilemap<f32>,
+    rocks: &Tilemap<RockType>,
+    moisture: &Tilemap<f32>,
+    params: &LandformParams,
+    map: &mut LandformMap,
+) {
+    for y in 0..heightmap.height {
+        for x in 0..heightmap.width {
+            let h = *heightmap.get(x, y);
+            if h <= 0.0 {
+                continue;
+            }
+            let arid = *moisture.get(x, y) < params.arid_moisture;
+            let kind = match *rocks.get(x, y) {
+                // Hard caprock over softer beds
+                RockType::Sandstone | RockType::Limestone | RockType::Basalt
+                    if arid && h >= params.mesa_min_elevation =>
+                {
+                    Landform::Mesa
+                }
+                RockType::Shale | RockType::Sediment if arid => Landform::Badlands,
+                _ => Landform::None,
+            };
+            let kind = params
+                .regions
+                .iter()
+                .rev()
+                .find(|r| r.contains(x, y, heightmap.width))
+                .map_or(kind, |r| r.landform);
+            map.landform.set(x, y, kind);
+        }
+    }
+}
+
+/// Step the valley sides of major rivers into terraces
+fn build_terraces(heightmap: &mut Tilemap<f32>, params: &LandformParams, map: &mut LandformMap) {
+    if params.terrace_levels == 0 || params.terrace_step <= 0.0 {
+        return;
+    }
+    let flow_dir = compute_flow_direction(heightmap);
+    let flow_acc = compute_flow_accumulation(heightmap, &flow_dir);
+
+    // Multi-source BFS outwards from channel tiles, carrying the channel elevation
+    let (width, height) = (heightmap.width, heightmap.height);
+    let mut channel: Tilemap<Option<(f32, usize)>> = Tilemap::new_with(width, height, None);
+    let mut queue = VecDeque::new();
+    for (x, y, &acc) in flow_acc.iter() {
+        // Forced terrace regions also step the valleys of smaller rivers
+        let threshold = if *map.landform.get(x, y) == Landform::Terrace {
+            params.terrace_flow * 0.25
+        } else {
+            params.terrace_flow
+        };
+        if acc >= threshold && *heightmap.get(x, y) > 0.0 {
+            channel.set(x, y, Some((*heightmap.get(x, y), 0)));
+            queue.push_back((x, y));
+        }
+    }
+    while let Some((x, y)) = queue.pop_front() {
+        let (floor, dist) = channel.get(x, y).expect("queued tiles have a channel");
+        if dist >= params.terrace_radius {
+            continue;
+        }
+        for (nx, ny) in heightmap.neighbors(x, y) {
+            if channel.get(nx, ny).is_none() && *heightmap.get(nx, ny) > 0.0 {
+                channel.set(nx, ny, Some((floor, dist + 1)));
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    let top = params.terrace_step * params.terrace_levels as f32;
+    for y in 0..height {
+        for x in 0..width {
+            let Some((floor, dist)) = *channel.get(x, y) else {
+                continue;
+            };
+            let kind = *map.landform.get(x, y);
+            if dist == 0 || !matches!(kind, Landform::None | Landform::Terrace) {
+                continue;
+            }
+            let h = *heightmap.get(x, y);
+            let rise = h - floor;
+            if rise <= 0.0 || rise > top {
+                continue;
+            }
+            // Flat tread at the foot of each step, tilted gently towards the river
+            let level = (rise / params.terrace_step).ceil().max(1.0);
+            let tilt = 0.1 * params.terrace_step * dist as f32 / params.terrace_radius.max(1) as f32;
+            let tread = floor + (level - 1.0) * params.terrace_step + tilt;
+            heightmap.set(x, y, lerp(h, tread.min(h), params.strength));
+            map.landform.set(x, y, Landform::Terrace);
+            map.terrace_level.set(x, y, level as u8);
+        }
+    }
+}
+
+/// Weather mesa country into caprock benches and cliffs
+fn build_mesas(heightmap: &mut Tilemap<f32>, params: &LandformParams, map: &LandformMap, seed: u64) {
+    if params.stratum_thickness <= 0.0 {
+        return;
+    }
+    let noise = Perlin::new(1).set_seed(seed.wrapping_add(0x3E5A) as u32);
+    let s = params.stratum_thickness as f64;
+    let circumference = heightmap.width as f64;
+
+    for y in 0..heightmap.height {
+        for x in 0..heightmap.width {
+            if *map.landform.get(x, y) != Landform::Mesa {
+                continue;
+            }
+            let h = *heightmap.get(x, y);
+            // Wandering cliff lines: shift the strata by a smooth offset (seamless across the wrap)
+            let angle = x as f64 / circumference * std::f64::consts::TAU;
+            let r = circumference / std::f64::consts::TAU * 0.12;
+            let wander = noise.get([angle.cos() * r, angle.sin() * r, y as f64 * 0.12]) * STRATUM_WANDER * s;
+
+            let shifted = h as f64 + wander;
+            let base = (shifted / s).floor() * s;
+            let frac = (shifted - base) / s;
+            let stepped = base + s * frac.powf(params.caprock_sharpness as f64) - wander;
+            heightmap.set(x, y, lerp(h, (stepped as f32).max(MIN_LAND), params.strength));
+        }
+    }
+}
+
+/// Etch badlands with rills running straight down the slope
+fn build_badlands(heightmap: &mut Tilemap<f32>, params: &LandformParams, map: &LandformMap, seed: u64) {
+    let noise = Perlin::new(1).set_seed(seed.wrapping_add(0xBAD1) as u32);
+    let original = heightmap.clone();
+    let freq = params.rill_frequency as f64;
+
+    for y in 0..heightmap.height {
+        for x in 0..heightmap.width {
+            if *map.landform.get(x, y) != Landform::Badlands {
+                continue;
+            }
+            let (gx, gy) = gradient(&original, x, y);
+            let slope = (gx * gx + gy * gy).sqrt();
+            if slope < BADLAND_MIN_SLOPE {
+                continue;
+            }
+            let (ux, uy) = ((gx / slope) as f64, (gy / slope) as f64);
+            // Stretch the noise along the slope so its troughs become parallel rills
+            let along = x as f64 * ux + y as f64 * uy;
+            let across = -(x as f64) * uy + y as f64 * ux;
+            let n = noise.get([across * freq * 4.0, along * freq * 0.5]);
+            let rill = (1.0 - n.abs()).powi(6) as f32;
+
+            let h = *original.get(x, y);
+            let carved = (h - params.rill_depth * rill).max(MIN_LAND.min(h));
+            heightmap.set(x, y, lerp(h, carved, params.strength));
+        }
+    }
+}
+
+/// Central-difference elevation gradient (m per tile), wrapping east-west
+fn gradient(heightmap: &Tilemap<f32>, x: usize, y: usize) -> (f32, f32) {
+    let (w, h) = (heightmap.width, heightmap.height);
+    let east = *heightmap.get((x + 1) % w, y);
+    let west = *heightmap.get((x + w - 1) % w, y);
+    let south = *heightmap.get(x, (y + 1).min(h - 1));
+    let north = *heightmap.get(x, y.saturating_sub(1));
+    ((east - west) * 0.5, (south - north) * 0.5)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t.clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A plateau rising from 350 m in the west to 950 m in the east, cut by a
+    /// valley along row 16 that drains west to the sea
+    fn plateau() -> Tilemap<f32> {
+        let (w, h) = (64, 32);
+        let mut heightmap = Tilemap::new_with(w, h, 0.0f32);
+        for y in 0..h {
+            for x in 0..w {
+                let valley = (y as f32 - 16.0).abs() * 30.0;
+                heightmap.set(x, y, if x == 0 { -50.0 } else { 300.0 + x as f32 * 8.0 + valley });
+            }
+        }
+        heightmap
+    }
+
+    #[test]
+    fn test_rock_and_climate_choose_landforms() {
+        let mut heightmap = plateau();
+        let mut rocks = Tilemap::new_with(64, 32, RockType::Sandstone);
+        for y in 0..32 {
+            for x in 40..64 {
+                rocks.set(x, y, RockType::Shale);
+            }
+        }
+        let dry = Tilemap::new_with(64, 32, 0.1f32);
+        let params = LandformParams::default();
+        let map = apply_landforms(&mut heightmap, &rocks, &dry, &params, 7);
+
+        assert_eq!(*map.landform.get(20, 4), Landform::Mesa);
+        assert_eq!(*map.landform.get(50, 4), Landform::Badlands);
+        // Mesa benches: the steady 8 m/tile rise becomes flat treads between cliffs
+        let benches = (1..39)
+            .filter(|&x| (*heightmap.get(x + 1, 4) - *heightmap.get(x, 4)).abs() < 4.0)
+            .count();
+        assert!(benches > 10, "only {} bench tiles", benches);
+        assert_eq!(*heightmap.get(0, 4), -50.0);
+        let original = plateau();
+
+        // Humid country keeps its eroded form apart from the river terraces
+        let mut wet_map = plateau();
+        let wet = Tilemap::new_with(64, 32, 0.8f32);
+        let map = apply_landforms(&mut wet_map, &rocks, &wet, &params, 7);
+        assert_eq!(map.count(Landform::Mesa) + map.count(Landform::Badlands), 0);
+        assert!(map.count(Landform::Terrace) > 0);
+        // Terraces are never higher than the eroded slope they were cut from
+        for (x, y, &level) in map.terrace_level.iter() {
+            if level > 0 {
+                assert!(*wet_map.get(x, y) <= *original.get(x, y));
+                assert!(level as usize <= params.terrace_levels);
+            }
+        }
+        assert_eq!(map.describe(0, 0), None);
+    }
+
+    #[test]
+    fn test_regions_override_rock() {
+        let mut heightmap = plateau();
+        let rocks = Tilemap::new_with(64, 32, RockType::Granite);
+        let wet = Tilemap::new_with(64, 32, 0.8f32);
+        let params = LandformParams {
+            regions: vec![LandformRegion { x: 60, y: 0, width: 10, height: 8, landform: Landform::Mesa }],
+            ..Default::default()
+        };
+        let map = apply_landforms(&mut heightmap, &rocks, &wet, &params, 1);
+        // The region wraps across the east-west seam
+        assert_eq!(*map.landform.get(62, 3), Landform::Mesa);
+        assert_eq!(*map.landform.get(3, 3), Landform::Mesa);
+        assert_eq!(*map.landform.get(20, 3), Landform::None);
+        assert_eq!(*map.landform.get(3, 12), Landform::None);
+    }
+}
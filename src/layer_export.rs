@@ -0,0 +1,422 @@
+//! World layer export at arbitrary resolution
+//!
+//! Renders the world-scale layers (elevation, temperature, moisture, biomes,
+//! tectonic stress, seismic hazard, aurora, dune fields, river channels and floodways,
+//! coastal wave energy, plate boundary types) to PNG at an output resolution independent of the one the
+//! world was simulated at. Continuous layers are resampled bicubically and the
+//! elevation gains noise-based detail when upsampled, so a 512x256 world can
+//! produce print-quality 2048x1024 maps without simulating at that size.
+//! Alongside the raw layers, a composed map is drawn in a cartographic theme, and
+//! the classified plate boundaries are written as JSON polylines.
+//! Structure desirability fields can be exported as heatmaps for debugging placement.
+
+use image::{ImageBuffer, Luma, Rgb, RgbImage};
+
+use crate::aeolian::{self, DuneType};
+use crate::ascii;
+use crate::cartography::{self, MapTheme};
+use crate::erosion::{self, Waterway};
+use crate::plates;
+use crate::structures::placement::{self, DesirabilityBreakdown, DesirabilityLayers, DESIRABILITY_TYPES};
+use crate::tilemap::Tilemap;
+use crate::world::WorldData;
+
+/// Amount of synthesized elevation detail when upsampling (see `Tilemap::resample_with_detail`)
+const DETAIL_SCALE: f32 = 1.0;
+
+/// Frequency of synthesized detail in normalized map coordinates
+const DETAIL_FREQUENCY: f32 = 96.0;
+
+/// Output pixels between neighbouring longitudinal dunes (other types scale from it)
+const DUNE_SPACING: f64 = 6.0;
+
+/// Maps a layer value to an RGB colour
+type ColorFn = fn(f32) -> (u8, u8, u8);
+
+/// A world layer that can be exported
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportLayer {
+    Elevation,
+    Temperature,
+    Moisture,
+    Biomes,
+    Stress,
+    /// Seismic hazard with the fault traces drawn over it (only when the world has a seismic layer)
+    Seismic,
+    /// Polar aurora over the elevation map (only when the world has a polar layer)
+    Aurora,
+    /// Dune field patterns over the elevation map (only when the world has dunes)
+    Dunes,
+    /// River channels at their physical width, with flood-stage floodways, over the
+    /// elevation map (only when the world has a river network)
+    Rivers,
+    /// Wave energy along the coasts, with fine harbours and lee shores marked, over
+    /// the elevation map (only when the world has a wave layer)
+    Waves,
+    /// Plate boundaries coloured by type (convergent, divergent, transform) over the
+    /// elevation map
+    Boundaries,
+}
+
+impl ExportLayer {
+    pub fn all() -> &'static [ExportLayer] {
+        &[
+            ExportLayer::Elevation,
+            ExportLayer::Temperature,
+            ExportLayer::Moisture,
+            ExportLayer::Biomes,
+            ExportLayer::Stress,
+            ExportLayer::Seismic,
+            ExportLayer::Aurora,
+            ExportLayer::Dunes,
+            ExportLayer::Rivers,
+            ExportLayer::Waves,
+            ExportLayer::Boundaries,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportLayer::Elevation => "elevation",
+            ExportLayer::Temperature => "temperature",
+            ExportLayer::Moisture => "moisture",
+            ExportLayer::Biomes => "biomes",
+            ExportLayer::Stress => "stress",
+            ExportLayer::Seismic => "seismic",
+            ExportLayer::Aurora => "aurora",
+            ExportLayer::Dunes => "dunes",
+            ExportLayer::Rivers => "rivers",
+            ExportLayer::Waves => "waves",
+            ExportLayer::Boundaries => "boundaries",
+        }
+    }
+}
+
+/// Parse a `WIDTHxHEIGHT` resolution
+pub fn parse_resolution(spec: &str) -> Result<(usize, usize), String> {
+    let (w, h) = spec
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got '{}'", spec))?;
+    let w: usize = w.trim().parse().map_err(|_| format!("invalid width '{}'", w))?;
+    let h: usize = h.trim().parse().map_err(|_| format!("invalid height '{}'", h))?;
+    if w == 0 || h == 0 {
+        return Err("resolution must be non-zero".to_string());
+    }
+    Ok((w, h))
+}
+
+/// Elevation resampled to the output resolution, with synthesized detail
+pub fn resample_elevation(world: &WorldData, width: usize, height: usize) -> Tilemap<f32> {
+    world.heightmap.resample_with_detail(width, height, DETAIL_SCALE, DETAIL_FREQUENCY, world.seed)
+}
+
+/// Render one layer at the given output resolution
+pub fn render_layer(world: &WorldData, layer: ExportLayer, width: usize, height: usize) -> RgbImage {
+    let mut img = RgbImage::new(width as u32, height as u32);
+
+    match layer {
+        ExportLayer::Biomes => {
+            let biomes = world.biomes.resample_nearest(width, height);
+            for (x, y, biome) in biomes.iter() {
+                let (r, g, b) = biome.color();
+                img.put_pixel(x as u32, y as u32, Rgb([r, g, b]));
+            }
+        }
+        ExportLayer::Aurora => {
+            let elevation = resample_elevation(world, width, height);
+            let aurora = world
+                .polar
+                .as_ref()
+                .map(|p| p.aurora.resample(width, height))
+                .unwrap_or_else(|| Tilemap::new_with(width, height, 0.0));
+            for (x, y, &h) in elevation.iter() {
+                let (r, g, b) = ascii::height_color(h);
+                let a = aurora.get(x, y).clamp(0.0, 1.0);
+                let glow = |c: u8, target: f32| (c as f32 * (1.0 - a) + target * a) as u8;
+                img.put_pixel(x as u32, y as u32, Rgb([glow(r, 60.0), glow(g, 255.0), glow(b, 150.0)]));
+            }
+        }
+        ExportLayer::Seismic => {
+            let seismic = world.seismic.as_ref();
+            let hazard = seismic
+                .map(|s| s.hazard.resample(width, height))
+                .unwrap_or_else(|| Tilemap::new_with(width, height, 0.0));
+            for (x, y, &h) in hazard.iter() {
+                let (tx, ty) = (x * world.width / width, y * world.height / height);
+                let on_fault = seismic.is_some_and(|s| s.fault_map.get(tx, ty).is_some());
+                let (r, g, b) = if on_fault { (40, 0, 0) } else { heatmap_color(h) };
+                img.put_pixel(x as u32, y as u32, Rgb([r, g, b]));
+            }
+        }
+        ExportLayer::Dunes => {
+            let elevation = resample_elevation(world, width, height);
+            let dunes = world.dunes.as_ref();
+            for (x, y, &h) in elevation.iter() {
+                let (tx, ty) = (x * world.width / width, y * world.height / height);
+                let kind = dunes.map_or(DuneType::None, |d| *d.dune.get(tx, ty));
+                let (r, g, b) = match (dunes, kind) {
+                    (Some(dunes), kind) if kind != DuneType::None => {
+                        let spacing = DUNE_SPACING * kind.local_spacing() / DuneType::Longitudinal.local_spacing();
+                        let relief =
+                            aeolian::dune_relief(kind, dunes.wind(ty), x as f64 / spacing, y as f64 / spacing, world.seed);
+                        // Shadowed troughs to sunlit crests
+                        let shade = |lo: f32, hi: f32| (lo + (hi - lo) * relief) as u8;
+                        (shade(170.0, 245.0), shade(135.0, 220.0), shade(85.0, 160.0))
+                    }
+                    _ => ascii::height_color(h),
+                };
+                img.put_pixel(x as u32, y as u32, Rgb([r, g, b]));
+            }
+        }
+        ExportLayer::Rivers => {
+            let elevation = resample_elevation(world, width, height);
+            let waterways = world.river_network.as_ref().map(|network| {
+                erosion::rasterize_waterways(network, world.scale.km_per_tile, (world.width, world.height), width, height)
+            });
+            for (x, y, &h) in elevation.iter() {
+                let (r, g, b) = ascii::height_color(h);
+                let (r, g, b) = match waterways.as_ref().map_or(Waterway::None, |w| *w.get(x, y)) {
+                    Waterway::Channel => (30, 80, 200),
+                    Waterway::Floodway => ((r as u16 / 2 + 60) as u8, (g as u16 / 2 + 85) as u8, (b as u16 / 2 + 110) as u8),
+                    Waterway::None => (r, g, b),
+                };
+                img.put_pixel(x as u32, y as u32, Rgb([r, g, b]));
+            }
+        }
+        ExportLayer::Waves => {
+            let elevation = resample_elevation(world, width, height);
+            let waves = world.waves.as_ref();
+            for (x, y, &h) in elevation.iter() {
+                let (tx, ty) = (x * world.width / width, y * world.height / height);
+                let (energy, harbor, lee_shore) = waves.map_or((0.0, 0.0, 0.0), |w| {
+                    (*w.energy.get(tx, ty), *w.harbor.get(tx, ty), *w.lee_shore.get(tx, ty))
+                });
+                let (r, g, b) = if harbor >= 0.75 {
+                    (255, 255, 255)
+                } else if lee_shore >= 0.5 {
+                    (60, 0, 0)
+                } else if energy > 0.0 {
+                    heatmap_color(energy)
+                } else {
+                    // Dimmed so the coasts stand out
+                    let (r, g, b) = ascii::height_color(h);
+                    (r / 2, g / 2, b / 2)
+                };
+                img.put_pixel(x as u32, y as u32, Rgb([r, g, b]));
+            }
+        }
+        ExportLayer::Boundaries => {
+            let elevation = resample_elevation(world, width, height);
+            let boundaries = plates::boundary_map(&world.plate_map, &world.plates);
+            for (x, y, &h) in elevation.iter() {
+                let (tx, ty) = (x * world.width / width, y * world.height / height);
+                let (r, g, b) = match boundaries.kind_at(tx, ty) {
+                    Some(kind) => kind.color(),
+                    None => {
+                        let (r, g, b) = ascii::height_color(h);
+                        (r / 2, g / 2, b / 2)
+                    }
+                };
+                img.put_pixel(x as u32, y as u32, Rgb([r, g, b]));
+            }
+        }
+        _ => {
+            let (map, color): (Tilemap<f32>, ColorFn) = match layer {
+                ExportLayer::Elevation => (resample_elevation(world, width, height), ascii::height_color),
+                ExportLayer::Temperature => (world.temperature.resample(width, height), ascii::temperature_color),
+                ExportLayer::Moisture => (world.moisture.resample(width, height), ascii::moisture_color),
+                _ => (world.stress_map.resample(width, height), stress_color),
+            };
+            for (x, y, &v) in map.iter() {
+                let (r, g, b) = color(v);
+                img.put_pixel(x as u32, y as u32, Rgb([r, g, b]));
+            }
+        }
+    }
+
+    img
+}
+
+/// Blue for divergent, red for convergent boundaries
+fn stress_color(stress: f32) -> (u8, u8, u8) {
+    let s = stress.clamp(-1.0, 1.0);
+    if s >= 0.0 {
+        (128 + (s * 127.0) as u8, (128.0 * (1.0 - s)) as u8, (128.0 * (1.0 - s)) as u8)
+    } else {
+        ((128.0 * (1.0 + s)) as u8, (128.0 * (1.0 + s)) as u8, 128 + (-s * 127.0) as u8)
+    }
+}
+
+/// Blue (least desirable) through yellow to red (most) for t in 0-1
+fn heatmap_color(t: f32) -> (u8, u8, u8) {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        let s = t * 2.0;
+        ((s * 255.0) as u8, (s * 255.0) as u8, ((1.0 - s) * 255.0) as u8)
+    } else {
+        let s = (t - 0.5) * 2.0;
+        (255, ((1.0 - s) * 255.0) as u8, 0)
+    }
+}
+
+/// Render one scalar field as a heatmap stretched over its range (None = dark grey)
+fn render_heatmap(values: &Tilemap<Option<f32>>) -> RgbImage {
+    let (lo, hi) = values
+        .iter()
+        .filter_map(|(_, _, v)| *v)
+        .fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    let range = (hi - lo).max(f32::EPSILON);
+    let mut img = RgbImage::new(values.width as u32, values.height as u32);
+    for (x, y, v) in values.iter() {
+        let (r, g, b) = v.map_or((40, 40, 40), |v| heatmap_color((v - lo) / range));
+        img.put_pixel(x as u32, y as u32, Rgb([r, g, b]));
+    }
+    img
+}
+
+/// Export the castle, city and village desirability fields (before any structure is
+/// placed) as heatmaps `PREFIX_<type>.png`, plus `PREFIX_<type>_<factor>.png` for each
+/// factor that varies across the map. Unbuildable tiles are dark grey. Returns the
+/// written paths.
+pub fn export_desirability(world: &WorldData, prefix: &str) -> Result<Vec<String>, image::ImageError> {
+    let layers = DesirabilityLayers::of(world);
+    let mut written = Vec::new();
+
+    for &kind in &DESIRABILITY_TYPES {
+        let mut breakdowns = Tilemap::new_with(world.width, world.height, None);
+        for (x, y, _) in world.heightmap.iter() {
+            breakdowns.set(x, y, placement::explain_desirability(kind, &layers, &[], x, y));
+        }
+        let name = kind.display_name().to_lowercase();
+        let field = |f: &dyn Fn(&DesirabilityBreakdown) -> f32| {
+            let mut values = Tilemap::new_with(world.width, world.height, None);
+            for (x, y, b) in breakdowns.iter() {
+                values.set(x, y, b.as_ref().map(f));
+            }
+            values
+        };
+
+        let path = format!("{}_{}.png", prefix, name);
+        render_heatmap(&field(&|b| b.total())).save(&path)?;
+        written.push(path);
+
+        for (i, factor) in DesirabilityBreakdown::NAMES.iter().enumerate() {
+            let values = field(&|b| b.factors()[i]);
+            let mut present = values.iter().filter_map(|(_, _, v)| *v);
+            let first = present.next();
+            if present.all(|v| Some(v) == first) {
+                continue;
+            }
+            let path = format!("{}_{}_{}.png", prefix, name, factor);
+            render_heatmap(&values).save(&path)?;
+            written.push(path);
+        }
+    }
+
+    Ok(written)
+}
+
+/// Export every layer as `PREFIX_<layer>.png`, a 16-bit grayscale
+/// `PREFIX_elevation16.png` for GIS and print work (plus the elevation in
+/// meters as `PREFIX_elevation.exr` with the `export-exr` feature), the plate
+/// boundary polylines as `PREFIX_boundaries.json`, and the map drawn in `theme`
+/// as `PREFIX_map.png`. Returns the written paths.
+pub fn export_layers(
+    world: &WorldData,
+    prefix: &str,
+    width: usize,
+    height: usize,
+    theme: &MapTheme,
+) -> Result<Vec<String>, image::ImageError> {
+    let mut written = Vec::new();
+
+    for &layer in ExportLayer::all() {
+        if (layer == ExportLayer::Aurora && world.polar.is_none())
+            || (layer == ExportLayer::Dunes && world.dunes.is_none())
+            || (layer == ExportLayer::Seismic && world.seismic.is_none())
+            || (layer == ExportLayer::Rivers && world.river_network.is_none())
+            || (layer == ExportLayer::Waves && world.waves.is_none())
+        {
+            continue;
+        }
+        let path = format!("{}_{}.png", prefix, layer.name());
+        render_layer(world, layer, width, height).save(&path)?;
+        written.push(path);
+    }
+
+    let elevation = resample_elevation(world, width, height);
+    let (min_h, max_h) = elevation.iter().fold((f32::MAX, f32::MIN), |(lo, hi), (_, _, &h)| (lo.min(h), hi.max(h)));
+    let range = (max_h - min_h).max(1.0);
+    let raw: ImageBuffer<Luma<u16>, Vec<u16>> = ImageBuffer::from_fn(width as u32, height as u32, |x, y| {
+        let h = *elevation.get(x as usize, y as usize);
+        Luma([((h - min_h) / range * u16::MAX as f32) as u16])
+    });
+    let path = format!("{}_elevation16.png", prefix);
+    raw.save(&path)?;
+    written.push(path);
+
+    #[cfg(feature = "export-exr")]
+    {
+        let meters = image::Rgb32FImage::from_fn(width as u32, height as u32, |x, y| {
+            let h = *elevation.get(x as usize, y as usize);
+            Rgb([h, h, h])
+        });
+        let path = format!("{}_elevation.exr", prefix);
+        meters.save(&path)?;
+        written.push(path);
+    }
+
+    let path = format!("{}_boundaries.json", prefix);
+    std::fs::write(&path, plates::boundary_map(&world.plate_map, &world.plates).to_json())?;
+    written.push(path);
+
+    let path = format!("{}_map.png", prefix);
+    cartography::render_map(world, theme, width, height).save(&path)?;
+    written.push(path);
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resolution() {
+        assert_eq!(parse_resolution("2048x1024"), Ok((2048, 1024)));
+        assert_eq!(parse_resolution("300X200"), Ok((300, 200)));
+        assert!(parse_resolution("2048").is_err());
+        assert!(parse_resolution("0x10").is_err());
+    }
+
+    #[test]
+    fn test_resample_keeps_macro_layout() {
+        // Land in the west half, ocean in the east
+        let mut heightmap = Tilemap::new_with(32, 16, -2000.0f32);
+        for y in 0..16 {
+            for x in 0..16 {
+                heightmap.set(x, y, 1500.0);
+            }
+        }
+
+        let hires = heightmap.resample_with_detail(100, 50, DETAIL_SCALE, DETAIL_FREQUENCY, 3);
+        assert_eq!((hires.width, hires.height), (100, 50));
+        assert!(*hires.get(20, 25) > 0.0);
+        assert!(*hires.get(75, 25) < 0.0);
+
+        let lores = heightmap.resample_nearest(8, 4);
+        assert_eq!(*lores.get(1, 1), 1500.0);
+        assert_eq!(*lores.get(6, 1), -2000.0);
+    }
+
+    #[test]
+    fn test_heatmap_stretches_over_field() {
+        let mut values = Tilemap::new_with(3, 1, Some(5.0f32));
+        values.set(1, 0, Some(10.0));
+        values.set(2, 0, None);
+        let img = render_heatmap(&values);
+        assert_eq!(img.get_pixel(0, 0).0, [0, 0, 255]);
+        assert_eq!(img.get_pixel(1, 0).0, [255, 0, 0]);
+        assert_eq!(img.get_pixel(2, 0).0, [40, 40, 40]);
+    }
+}
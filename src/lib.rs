@@ -1,28 +1,107 @@
 //! Planet generation library
 //!
 //! A procedural world map generator featuring:
+//! - Builder-style world generation (`world::WorldGenerator`)
 //! - Tectonic plate simulation
 //! - Hydraulic and glacial erosion
 //! - Climate modeling (temperature, moisture)
 //! - 50+ biome types
 //! - Water body detection (oceans, lakes, rivers)
+//! - Underground biomes, rivers and geothermal heat
 //! - Human-made structures (castles, cities, villages, roads)
 //! - Historical world enrichment (factions, events, settlements, monsters, trade routes)
 //! - Multi-scale zoom system (world -> regional -> local)
+//! - Map, layer and vector exports
+//!
+//! # Features
+//!
+//! `terrain` is the world pipeline itself and is always required: plates,
+//! erosion, climate, water, biomes, the underground, structures, the multiscale
+//! zoom and every export of them. `history` adds the simulation on top of it
+//! (factions, events, settlements, trade, exploration, magic, gameplay layers,
+//! scenarios, campaigns and adventure packets) along with
+//! `WorldGenerator::with_history`; without it a world's `history` is always `None`. `climate` adds paleoclimate
+//! records when `history` is on too, and `simulation` world aging, sweeps and
+//! seed mining (it turns `history` on). `gpu`, `explorer`, `lore-llm` and
+//! `export-exr` pull in their heavier dependencies, `viewer` the desktop viewer,
+//! and `cli` is everything the command-line tool needs.
+//!
+//! For embedding, the minimal build is
+//! `default-features = false, features = ["terrain"]`: no history simulation
+//! and none of the GPU, terminal, HTTP or EXR dependencies.
+
+#[cfg(not(feature = "terrain"))]
+compile_error!("planet_generator needs the `terrain` feature");
 
+#[cfg(feature = "history")]
+pub mod adventures;
+pub mod aeolian;
+#[cfg(feature = "simulation")]
+pub mod aging;
 pub mod ascii;
+pub mod biome_constraints;
 pub mod biome_feathering;
 pub mod biomes;
+#[cfg(feature = "lore-llm")]
+pub mod brief;
+pub mod budget;
+#[cfg(feature = "history")]
+pub mod campaign;
+pub mod cartography;
+pub mod cave_biomes;
+pub mod chemistry;
 pub mod climate;
+pub mod coast_character;
 pub mod coastline;
+pub mod craters;
+pub mod editing;
 pub mod erosion;
+#[cfg(feature = "history")]
+pub mod exploration;
+pub mod flora;
+#[cfg(feature = "history")]
+pub mod gameplay;
+pub mod gazetteer;
+pub mod geothermal;
 pub mod heightmap;
 pub mod history;
+pub mod hot_reload;
+#[cfg(feature = "history")]
+pub mod known_world;
+pub mod lakes;
+pub mod landforms;
+pub mod layer_export;
+pub mod loess;
+#[cfg(feature = "history")]
+pub mod magic;
+pub mod mass_wasting;
 pub mod multiscale;
+pub mod names;
+#[cfg(all(feature = "climate", feature = "history"))]
+pub mod paleo;
+pub mod planes;
 pub mod plates;
+pub mod polar;
+pub mod preset;
 pub mod scale;
+#[cfg(feature = "history")]
+pub mod scenario;
+pub mod section_export;
+#[cfg(feature = "simulation")]
+pub mod seed_mining;
+pub mod seismic;
+pub mod sketch;
 pub mod structures;
+pub mod succession;
+#[cfg(feature = "simulation")]
+pub mod system;
+pub mod telemetry;
 pub mod tilemap;
+pub mod underground_rivers;
+pub mod vector_export;
+#[cfg(feature = "viewer")]
+pub mod viewer;
 pub mod water_bodies;
+pub mod waves;
 pub mod world;
 pub mod zlevel;
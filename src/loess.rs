@@ -0,0 +1,271 @@
+//! Loess: wind-blown silt
+//!
+//! Fine sediment is picked up where the ground is bare and loose and carried
+//! downwind on the prevailing winds (`aeolian::prevailing_wind`):
+//! - Deserts: dune fields deflate in proportion to their sand supply
+//! - Glacial outwash: part of the rock flour ground off by glaciers
+//!   (`ErosionStats::glacial_erosion`) blows out of the outwash plains
+//!
+//! Dust settles steadily along its path and rain washes it out faster, so the
+//! thickest loess lies on the humid margins downwind of deserts and ice. Dust over
+//! open water is lost to the sea and dust blown past the top or bottom row leaves
+//! the map; `LoessBudget` accounts for every metre, so emitted always equals
+//! deposited plus lost. Deposits deepen the local soil (as silt) and make humid
+//! land more fertile.
+
+use crate::aeolian::{self, DuneMap, DuneType};
+use crate::structures::types::DesirabilityMap;
+use crate::tilemap::Tilemap;
+
+/// Loess thickness (m) at which fertility reaches ~63% of its full bonus
+const FERTILE_THICKNESS: f32 = 3.0;
+
+/// Moisture below which loess is too dry to farm at full fertility
+const FARMABLE_MOISTURE: f32 = 0.4;
+
+/// Loess thickness (m) per local soil z-level
+const LOESS_PER_LEVEL: f32 = 2.0;
+
+/// Most local soil z-levels loess adds
+const MAX_LOESS_LEVELS: i16 = 6;
+
+/// Parameters for dust transport
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct LoessConfig {
+    /// Silt (m) a dune field tile with full sand supply gives up to the wind
+    pub desert_emission: f32,
+    /// Fraction of glacially eroded rock that blows away as silt
+    pub outwash_fraction: f32,
+    /// Fraction of the airborne load that settles out per tile travelled
+    pub settle_rate: f32,
+    /// Extra fraction rain washes out per tile, scaled by moisture
+    pub wet_settle_rate: f32,
+    /// Furthest (tiles) dust travels before the rest settles
+    pub max_distance: usize,
+}
+
+impl Default for LoessConfig {
+    fn default() -> Self {
+        Self {
+            desert_emission: 1.0,
+            outwash_fraction: 0.3,
+            settle_rate: 0.05,
+            wet_settle_rate: 0.15,
+            max_distance: 48,
+        }
+    }
+}
+
+/// Where the silt went, in metres summed over tiles
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct LoessBudget {
+    /// Picked up from deserts
+    pub from_deserts: f64,
+    /// Picked up from glacial outwash
+    pub from_outwash: f64,
+    /// Settled on land
+    pub deposited: f64,
+    /// Settled over open water
+    pub lost_at_sea: f64,
+    /// Blown past the top or bottom of the map
+    pub off_map: f64,
+}
+
+impl LoessBudget {
+    pub fn emitted(&self) -> f64 {
+        self.from_deserts + self.from_outwash
+    }
+
+    /// Emitted minus everything accounted for (zero up to rounding)
+    pub fn imbalance(&self) -> f64 {
+        self.emitted() - self.deposited - self.lost_at_sea - self.off_map
+    }
+}
+
+/// Loess deposits of a world
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct LoessMap {
+    /// Deposit thickness (m)
+    pub thickness: Tilemap<f32>,
+    /// Fertility bonus (0-1) the loess gives the soil
+    pub fertility: Tilemap<f32>,
+    pub budget: LoessBudget,
+}
+
+impl LoessMap {
+    /// Extra local soil z-levels of loess at a world tile
+    pub fn soil_levels(&self, x: usize, y: usize) -> i16 {
+        ((*self.thickness.get(x, y) / LOESS_PER_LEVEL) as i16).min(MAX_LOESS_LEVELS)
+    }
+
+    /// Short description for tile info panels
+    pub fn describe(&self, x: usize, y: usize) -> Option<String> {
+        let thickness = *self.thickness.get(x, y);
+        if thickness < 0.1 {
+            return None;
+        }
+        Some(format!("Loess {:.1} m (fertility +{:.0}%)", thickness, self.fertility.get(x, y) * 100.0))
+    }
+}
+
+/// Blow silt downwind from deserts and glacial outwash and settle it as loess
+pub fn generate_loess(
+    heightmap: &Tilemap<f32>,
+    moisture: &Tilemap<f32>,
+    dunes: Option<&DuneMap>,
+    glacial_erosion: Option<&Tilemap<f32>>,
+    config: &LoessConfig,
+) -> LoessMap {
+    let (width, height) = (heightmap.width, heightmap.height);
+    let mut thickness = Tilemap::new_with(width, height, 0.0f32);
+    let mut budget = LoessBudget::default();
+
+    for y in 0..height {
+        for x in 0..width {
+            if *heightmap.get(x, y) <= 0.0 {
+                continue;
+            }
+            let desert = dunes
+                .filter(|d| *d.dune.get(x, y) != DuneType::None)
+                .map_or(0.0, |d| config.desert_emission * *d.supply.get(x, y));
+            let outwash = glacial_erosion.map_or(0.0, |g| config.outwash_fraction * g.get(x, y).max(0.0));
+            if desert + outwash <= 0.0 {
+                continue;
+            }
+            budget.from_deserts += desert as f64;
+            budget.from_outwash += outwash as f64;
+            carry(heightmap, moisture, config, (x, y), (desert + outwash) as f64, &mut thickness, &mut budget);
+        }
+    }
+
+    debug_assert!(budget.imbalance().abs() <= 1e-6 * budget.emitted().max(1.0), "loess mass budget does not close");
+
+    let mut fertility = Tilemap::new_with(width, height, 0.0f32);
+    for (x, y, &t) in thickness.iter() {
+        let wet = (*moisture.get(x, y) / FARMABLE_MOISTURE).min(1.0);
+        fertility.set(x, y, (1.0 - (-t / FERTILE_THICKNESS).exp()) * wet);
+    }
+
+    LoessMap { thickness, fertility, budget }
+}
+
+/// Structure hook: raise desirability in proportion to loess fertility (e.g. for farming villages)
+pub fn add_fertility_desirability(desirability: &mut DesirabilityMap, loess: &LoessMap, weight: f32) {
+    for (x, y, &fertility) in loess.fertility.iter() {
+        if desirability.get(x, y) > f32::MIN {
+            desirability.add(x, y, fertility * weight);
+        }
+    }
+}
+
+/// Carry one source's silt downwind, settling it tile by tile
+fn carry(
+    heightmap: &Tilemap<f32>,
+    moisture: &Tilemap<f32>,
+    config: &LoessConfig,
+    source: (usize, usize),
+    mut load: f64,
+    thickness: &mut Tilemap<f32>,
+    budget: &mut LoessBudget,
+) {
+    let height = heightmap.height;
+    let (mut px, mut py) = (source.0 as f32, source.1 as f32);
+    let mut last = source;
+
+    for _ in 0..config.max_distance {
+        // The wind turns as the dust crosses the latitude belts
+        let (wx, wy) = aeolian::prevailing_wind(last.1, height);
+        px += wx;
+        py += wy;
+        let row = py.round();
+        if row < 0.0 || row >= height as f32 {
+            budget.off_map += load;
+            return;
+        }
+        let (x, y) = heightmap.wrap_coords(px.round() as i32, row as i32);
+        last = (x, y);
+
+        let over_land = *heightmap.get(x, y) > 0.0;
+        let rate = if over_land {
+            config.settle_rate + config.wet_settle_rate * moisture.get(x, y).clamp(0.0, 1.0)
+        } else {
+            config.settle_rate
+        };
+        let settled = load * rate.clamp(0.0, 1.0) as f64;
+        settle(heightmap, thickness, budget, last, settled);
+        load -= settled;
+    }
+
+    // Whatever is still airborne at the end of the trail comes down there
+    settle(heightmap, thickness, budget, last, load);
+}
+
+fn settle(heightmap: &Tilemap<f32>, thickness: &mut Tilemap<f32>, budget: &mut LoessBudget, (x, y): (usize, usize), amount: f64) {
+    if *heightmap.get(x, y) > 0.0 {
+        *thickness.get_mut(x, y) += amount as f32;
+        budget.deposited += amount;
+    } else {
+        budget.lost_at_sea += amount;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aeolian::{generate_dunes, DuneConfig};
+    use crate::biomes::ExtendedBiome;
+
+    #[test]
+    fn test_mass_budget_balances() {
+        // Land in the west half with a desert in it, ocean in the east; a glacier
+        // scoured the far north
+        let mut heightmap = Tilemap::new_with(64, 64, -100.0f32);
+        let mut biomes = Tilemap::new_with(64, 64, ExtendedBiome::Ocean);
+        for y in 0..64 {
+            for x in 0..32 {
+                heightmap.set(x, y, 300.0);
+                biomes.set(x, y, if (20..28).contains(&x) { ExtendedBiome::Desert } else { ExtendedBiome::TemperateGrassland });
+            }
+        }
+        let mut glacial = Tilemap::new_with(64, 64, 0.0f32);
+        for x in 0..32 {
+            glacial.set(x, 2, 40.0);
+        }
+        let moisture = Tilemap::new_with(64, 64, 0.6f32);
+        let dunes = generate_dunes(&heightmap, &biomes, &DuneConfig::default());
+
+        let config = LoessConfig { desert_emission: 5.0, ..Default::default() };
+        let loess = generate_loess(&heightmap, &moisture, Some(&dunes), Some(&glacial), &config);
+        let budget = &loess.budget;
+        assert!(budget.from_deserts > 0.0 && budget.from_outwash > 0.0);
+        assert!(budget.imbalance().abs() < 1e-6 * budget.emitted());
+        let on_map: f64 = loess.thickness.iter().map(|(_, _, &t)| t as f64).sum();
+        assert!((on_map - budget.deposited).abs() < 1e-3 * budget.deposited);
+        // Trade winds blow the desert's dust west onto the grassland, not east
+        assert!(*loess.thickness.get(19, 40) > *loess.thickness.get(30, 40));
+        assert!(loess.soil_levels(19, 40) >= 1);
+        assert!(*loess.fertility.get(19, 40) > 0.0);
+        assert_eq!(*loess.thickness.get(40, 40), 0.0);
+    }
+
+    #[test]
+    fn test_dry_loess_is_less_fertile() {
+        let heightmap = Tilemap::new_with(32, 32, 200.0f32);
+        let mut glacial = Tilemap::new_with(32, 32, 0.0f32);
+        glacial.set(16, 16, 100.0);
+        let config = LoessConfig { wet_settle_rate: 0.0, ..Default::default() };
+        let wet = generate_loess(&heightmap, &Tilemap::new_with(32, 32, 0.8f32), None, Some(&glacial), &config);
+        let dry = generate_loess(&heightmap, &Tilemap::new_with(32, 32, 0.1f32), None, Some(&glacial), &config);
+
+        // Without rain-out the same dust lands in the same places...
+        assert_eq!(wet.thickness.get(15, 16), dry.thickness.get(15, 16));
+        assert!(*wet.thickness.get(15, 16) > 0.0);
+        // ...but only humid loess is good farmland
+        assert!(*wet.fertility.get(15, 16) > 2.0 * *dry.fertility.get(15, 16));
+        assert!(wet.describe(15, 16).is_some() && wet.describe(0, 0).is_none());
+
+        let mut desirability = DesirabilityMap::new(32, 32);
+        add_fertility_desirability(&mut desirability, &wet, 10.0);
+        assert!(desirability.get(15, 16) > desirability.get(0, 0));
+    }
+}
@@ -0,0 +1,557 @@
+//! Ley lines and the magic field
+//!
+//! An optional magic geography shared by every system that cares about it:
+//! - Ley nodes at unique biomes, mystical landmarks and volcanic/tectonic hotspots
+//! - Ley lines: straight channels linking nodes (spanning tree plus a few extra links)
+//! - Mana intensity per tile, strongest along lines and around nodes
+//! - Anomaly sites: line confluences, wild surges on unstable ground, dead zones
+//!
+//! History (wizard towers, magical catastrophes), structure placement and
+//! descriptive text all read from the same `MagicMap`.
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::{BiomeCategory, ExtendedBiome};
+use crate::history::{EventType, HistoricalEvent, WorldHistory};
+use crate::history::types::Year;
+use crate::structures::types::DesirabilityMap;
+use crate::tilemap::Tilemap;
+
+/// Tiles over which a ley line's mana fades out
+const LINE_FALLOFF: f32 = 4.0;
+
+/// Radius (tiles) of a ley line's mana halo
+const LINE_REACH: i64 = 10;
+
+/// Radius (tiles) of a node's mana halo
+const NODE_REACH: i64 = 12;
+
+/// Stress magnitude at which ley lines become unstable
+const SURGE_STRESS: f32 = 0.5;
+
+/// Mana below which land counts as a dead zone
+const DEAD_ZONE_MANA: f32 = 0.02;
+
+/// What anchors a ley node
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum LeyNodeKind {
+    /// A one-per-map unique biome
+    Unique(ExtendedBiome),
+    /// A mystical or ultra-rare biome
+    Landmark(ExtendedBiome),
+    /// Volcanic ground or an active plate boundary
+    Hotspot,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct LeyNode {
+    pub x: usize,
+    pub y: usize,
+    pub kind: LeyNodeKind,
+    /// Relative power (0-1)
+    pub strength: f32,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct LeyLine {
+    /// Indices into `MagicMap::nodes`
+    pub from: usize,
+    pub to: usize,
+    /// Tiles the line passes through
+    pub path: Vec<(usize, usize)>,
+    pub strength: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum AnomalyKind {
+    /// Two or more ley lines cross
+    Confluence,
+    /// A ley line runs over unstable ground and discharges wildly
+    WildSurge,
+    /// Land starved of mana
+    DeadZone,
+}
+
+impl AnomalyKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnomalyKind::Confluence => "Ley Confluence",
+            AnomalyKind::WildSurge => "Wild Surge",
+            AnomalyKind::DeadZone => "Dead Zone",
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MagicAnomaly {
+    pub x: usize,
+    pub y: usize,
+    pub kind: AnomalyKind,
+    /// Mana intensity at the site
+    pub intensity: f32,
+}
+
+/// Options for magic generation
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MagicConfig {
+    /// Maximum ley nodes
+    pub max_nodes: usize,
+    /// Minimum distance (tiles) between nodes
+    pub node_spacing: usize,
+    /// Chance for each node to gain a link beyond the spanning tree
+    pub extra_link_chance: f64,
+    /// Maximum anomalies of each kind
+    pub max_anomalies: usize,
+}
+
+impl Default for MagicConfig {
+    fn default() -> Self {
+        Self {
+            max_nodes: 24,
+            node_spacing: 20,
+            extra_link_chance: 0.3,
+            max_anomalies: 8,
+        }
+    }
+}
+
+/// The world's magic geography
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct MagicMap {
+    pub nodes: Vec<LeyNode>,
+    pub lines: Vec<LeyLine>,
+    /// Mana intensity per tile (0-1)
+    pub mana: Tilemap<f32>,
+    pub anomalies: Vec<MagicAnomaly>,
+}
+
+fn wrapped_dist_sq(width: usize, a: (usize, usize), b: (usize, usize)) -> usize {
+    let dx = a.0.abs_diff(b.0);
+    let dx = dx.min(width - dx);
+    let dy = a.1.abs_diff(b.1);
+    dx * dx + dy * dy
+}
+
+/// Straight line between two tiles, taking the short way around the wrapped x axis
+fn line_path(width: usize, a: (usize, usize), b: (usize, usize)) -> Vec<(usize, usize)> {
+    let w = width as i64;
+    let (x0, y0) = (a.0 as i64, a.1 as i64);
+    let mut dx = b.0 as i64 - x0;
+    if dx > w / 2 {
+        dx -= w;
+    } else if dx < -w / 2 {
+        dx += w;
+    }
+    let dy = b.1 as i64 - y0;
+    let steps = dx.abs().max(dy.abs()).max(1);
+
+    (0..=steps)
+        .map(|i| {
+            let x = x0 + (dx * i + steps / 2 * dx.signum()) / steps;
+            let y = y0 + (dy * i + steps / 2 * dy.signum()) / steps;
+            (x.rem_euclid(w) as usize, y as usize)
+        })
+        .collect()
+}
+
+fn is_hotspot(biome: ExtendedBiome, stress: f32) -> bool {
+    stress.abs() > 0.6
+        || matches!(
+            biome,
+            ExtendedBiome::VolcanicCone | ExtendedBiome::Caldera | ExtendedBiome::HotSpot | ExtendedBiome::LavaLake
+        )
+}
+
+fn find_nodes(
+    heightmap: &Tilemap<f32>,
+    biomes: &Tilemap<ExtendedBiome>,
+    stress_map: &Tilemap<f32>,
+    config: &MagicConfig,
+    rng: &mut ChaCha8Rng,
+) -> Vec<LeyNode> {
+    let mut uniques = Vec::new();
+    let mut landmarks = Vec::new();
+    let mut hotspots = Vec::new();
+
+    for (x, y, &biome) in biomes.iter() {
+        if *heightmap.get(x, y) < 0.0 && !biome.is_unique() {
+            continue;
+        }
+        if biome.is_unique() {
+            uniques.push(LeyNode { x, y, kind: LeyNodeKind::Unique(biome), strength: 1.0 });
+        } else if biome.category() == BiomeCategory::Mystical || biome.is_ultra_rare() {
+            landmarks.push(LeyNode { x, y, kind: LeyNodeKind::Landmark(biome), strength: 0.7 });
+        } else if is_hotspot(biome, *stress_map.get(x, y)) {
+            hotspots.push(LeyNode { x, y, kind: LeyNodeKind::Hotspot, strength: 0.5 });
+        }
+    }
+    landmarks.shuffle(rng);
+    hotspots.shuffle(rng);
+
+    // Strongest anchors claim their spot first; weaker ones fill the gaps
+    let spacing_sq = config.node_spacing * config.node_spacing;
+    let mut nodes: Vec<LeyNode> = Vec::new();
+    for candidate in uniques.into_iter().chain(landmarks).chain(hotspots) {
+        if nodes.len() >= config.max_nodes {
+            break;
+        }
+        let clear = nodes
+            .iter()
+            .all(|n| wrapped_dist_sq(biomes.width, (n.x, n.y), (candidate.x, candidate.y)) >= spacing_sq);
+        if clear {
+            nodes.push(candidate);
+        }
+    }
+    nodes
+}
+
+/// Link nodes with a minimum spanning tree, then add a few extra links for loops
+fn link_nodes(width: usize, nodes: &[LeyNode], config: &MagicConfig, rng: &mut ChaCha8Rng) -> Vec<(usize, usize)> {
+    let n = nodes.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    let dist = |a: usize, b: usize| wrapped_dist_sq(width, (nodes[a].x, nodes[a].y), (nodes[b].x, nodes[b].y));
+
+    // Prim's algorithm
+    let mut in_tree = vec![false; n];
+    let mut best: Vec<(usize, usize)> = (0..n).map(|i| (dist(0, i), 0)).collect();
+    in_tree[0] = true;
+    let mut edges = Vec::with_capacity(n);
+    for _ in 1..n {
+        let next = (0..n).filter(|&i| !in_tree[i]).min_by_key(|&i| best[i].0).unwrap();
+        in_tree[next] = true;
+        edges.push((best[next].1, next));
+        for i in 0..n {
+            if !in_tree[i] && dist(next, i) < best[i].0 {
+                best[i] = (dist(next, i), next);
+            }
+        }
+    }
+
+    for a in 0..n {
+        if !rng.gen_bool(config.extra_link_chance) {
+            continue;
+        }
+        let mut others: Vec<usize> = (0..n).filter(|&b| b != a).collect();
+        others.sort_by_key(|&b| dist(a, b));
+        if let Some(&b) = others
+            .iter()
+            .find(|&&b| !edges.contains(&(a, b)) && !edges.contains(&(b, a)))
+        {
+            edges.push((a, b));
+        }
+    }
+
+    edges
+}
+
+fn compute_mana(width: usize, height: usize, nodes: &[LeyNode], lines: &[LeyLine]) -> Tilemap<f32> {
+    let mut mana = Tilemap::new_with(width, height, 0.0f32);
+
+    let mut splat = |cx: usize, cy: usize, reach: i64, falloff: f32, strength: f32| {
+        for dy in -reach..=reach {
+            let y = cy as i64 + dy;
+            if y < 0 || y >= height as i64 {
+                continue;
+            }
+            for dx in -reach..=reach {
+                let d = ((dx * dx + dy * dy) as f32).sqrt();
+                if d > reach as f32 {
+                    continue;
+                }
+                let x = (cx as i64 + dx).rem_euclid(width as i64) as usize;
+                let value = strength * (-d / falloff).exp();
+                let cell = mana.get_mut(x, y as usize);
+                // Take the strongest source rather than summing so dense lines don't saturate
+                *cell = cell.max(value);
+            }
+        }
+    };
+
+    for line in lines {
+        for &(x, y) in &line.path {
+            splat(x, y, LINE_REACH, LINE_FALLOFF, line.strength * 0.8);
+        }
+    }
+    for node in nodes {
+        splat(node.x, node.y, NODE_REACH, LINE_FALLOFF * 1.5, node.strength);
+    }
+
+    mana
+}
+
+fn find_anomalies(
+    heightmap: &Tilemap<f32>,
+    stress_map: &Tilemap<f32>,
+    nodes: &[LeyNode],
+    lines: &[LeyLine],
+    mana: &Tilemap<f32>,
+    config: &MagicConfig,
+    rng: &mut ChaCha8Rng,
+) -> Vec<MagicAnomaly> {
+    let width = mana.width;
+    let mut anomalies: Vec<MagicAnomaly> = Vec::new();
+    let spacing_sq = 16;
+    let near_node = |x: usize, y: usize| nodes.iter().any(|n| wrapped_dist_sq(width, (n.x, n.y), (x, y)) <= 9);
+
+    let push = |anomalies: &mut Vec<MagicAnomaly>, x: usize, y: usize, kind: AnomalyKind| {
+        let count = anomalies.iter().filter(|a| a.kind == kind).count();
+        let clear = anomalies.iter().all(|a| wrapped_dist_sq(width, (a.x, a.y), (x, y)) >= spacing_sq);
+        if count < config.max_anomalies && clear {
+            anomalies.push(MagicAnomaly { x, y, kind, intensity: *mana.get(x, y) });
+        }
+    };
+
+    // Confluences: tiles shared by two different lines away from their nodes
+    let mut line_owner: Tilemap<Option<usize>> = Tilemap::new_with(width, mana.height, None);
+    for (i, line) in lines.iter().enumerate() {
+        for &(x, y) in &line.path {
+            match *line_owner.get(x, y) {
+                Some(other) if other != i && !near_node(x, y) => push(&mut anomalies, x, y, AnomalyKind::Confluence),
+                _ => line_owner.set(x, y, Some(i)),
+            }
+        }
+    }
+
+    // Wild surges: lines over strongly stressed ground
+    for line in lines {
+        for &(x, y) in &line.path {
+            if stress_map.get(x, y).abs() > SURGE_STRESS && *heightmap.get(x, y) >= 0.0 {
+                push(&mut anomalies, x, y, AnomalyKind::WildSurge);
+            }
+        }
+    }
+
+    // Dead zones: random mana-starved land
+    let mut dead: Vec<(usize, usize)> = mana
+        .iter()
+        .filter(|&(x, y, &m)| m < DEAD_ZONE_MANA && *heightmap.get(x, y) >= 0.0)
+        .map(|(x, y, _)| (x, y))
+        .collect();
+    dead.shuffle(rng);
+    for (x, y) in dead.into_iter().take(config.max_anomalies * 8) {
+        push(&mut anomalies, x, y, AnomalyKind::DeadZone);
+    }
+
+    anomalies
+}
+
+/// Generate the ley-line network, mana field and anomaly sites
+pub fn generate_magic(
+    heightmap: &Tilemap<f32>,
+    biomes: &Tilemap<ExtendedBiome>,
+    stress_map: &Tilemap<f32>,
+    config: &MagicConfig,
+    seed: u64,
+) -> MagicMap {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x1E7_11E5));
+    let width = heightmap.width;
+
+    let nodes = find_nodes(heightmap, biomes, stress_map, config, &mut rng);
+    let lines: Vec<LeyLine> = link_nodes(width, &nodes, config, &mut rng)
+        .into_iter()
+        .map(|(from, to)| LeyLine {
+            from,
+            to,
+            path: line_path(width, (nodes[from].x, nodes[from].y), (nodes[to].x, nodes[to].y)),
+            strength: (nodes[from].strength + nodes[to].strength) * 0.5,
+        })
+        .collect();
+
+    let mana = compute_mana(width, heightmap.height, &nodes, &lines);
+    let anomalies = find_anomalies(heightmap, stress_map, &nodes, &lines, &mana, config, &mut rng);
+
+    MagicMap { nodes, lines, mana, anomalies }
+}
+
+impl MagicMap {
+    pub fn mana_at(&self, x: usize, y: usize) -> f32 {
+        *self.mana.get(x, y)
+    }
+
+    /// Whether a ley line passes through the tile
+    pub fn on_ley_line(&self, x: usize, y: usize) -> bool {
+        self.lines.iter().any(|l| l.path.contains(&(x, y)))
+    }
+
+    pub fn anomaly_at(&self, x: usize, y: usize) -> Option<&MagicAnomaly> {
+        self.anomalies.iter().find(|a| a.x == x && a.y == y)
+    }
+
+    /// Short description of the tile's magic, for lore and tile info
+    pub fn describe(&self, x: usize, y: usize) -> Option<String> {
+        if let Some(a) = self.anomaly_at(x, y) {
+            return Some(format!("{} (mana {:.0}%)", a.kind.name(), a.intensity * 100.0));
+        }
+        if let Some(node) = self.nodes.iter().find(|n| n.x == x && n.y == y) {
+            let what = match node.kind {
+                LeyNodeKind::Unique(b) | LeyNodeKind::Landmark(b) => b.display_name(),
+                LeyNodeKind::Hotspot => "earthfire",
+            };
+            return Some(format!("Ley node of the {}", what));
+        }
+        let mana = self.mana_at(x, y);
+        if self.on_ley_line(x, y) {
+            Some(format!("Ley line (mana {:.0}%)", mana * 100.0))
+        } else if mana > 0.5 {
+            Some(format!("Strong mana ({:.0}%)", mana * 100.0))
+        } else {
+            None
+        }
+    }
+
+    /// Best sites for wizard towers: confluences first, then landmark nodes
+    pub fn tower_sites(&self) -> Vec<(usize, usize)> {
+        let confluences = self
+            .anomalies
+            .iter()
+            .filter(|a| a.kind == AnomalyKind::Confluence)
+            .map(|a| (a.x, a.y));
+        let nodes = self
+            .nodes
+            .iter()
+            .filter(|n| matches!(n.kind, LeyNodeKind::Landmark(_)))
+            .map(|n| (n.x, n.y));
+        confluences.chain(nodes).collect()
+    }
+}
+
+/// Structure hook: raise desirability in proportion to mana (e.g. for towers and temples)
+pub fn add_mana_desirability(desirability: &mut DesirabilityMap, magic: &MagicMap, weight: f32) {
+    for (x, y, &mana) in magic.mana.iter() {
+        if desirability.get(x, y) > f32::MIN {
+            desirability.add(x, y, mana * weight);
+        }
+    }
+}
+
+/// History hook: factions raise wizard towers on tower sites inside their territory,
+/// and wild surges become magical catastrophes. Returns the number of events added.
+pub fn apply_magic_history(history: &mut WorldHistory, magic: &MagicMap, seed: u64) -> usize {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x3A61C));
+    let (start, end) = match (history.timeline.eras.first(), history.timeline.eras.last()) {
+        (Some(first), Some(last)) => (first.start.0, last.end.0),
+        _ => (-1000, 0),
+    };
+    let random_year = |rng: &mut ChaCha8Rng| Year(rng.gen_range(start.min(end)..=end.max(start)));
+    let mut added = 0;
+
+    for (x, y) in magic.tower_sites() {
+        let Some(faction) = history.faction_at(x, y) else {
+            continue;
+        };
+        let (faction_id, faction_name) = (faction.id, faction.name.clone());
+        let id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id,
+            year: random_year(&mut rng),
+            event_type: EventType::WizardTowerRaised,
+            faction: Some(faction_id),
+            other_faction: None,
+            location: Some((x, y)),
+            settlement: None,
+            name: format!("Raising of the {} Spire", faction_name),
+            description: format!("Mages of {} raised a tower to tap the ley lines", faction_name),
+            casualties: 0,
+            has_evidence: EventType::WizardTowerRaised.leaves_evidence(),
+        });
+        added += 1;
+    }
+
+    for anomaly in magic.anomalies.iter().filter(|a| a.kind == AnomalyKind::WildSurge) {
+        let faction = history.faction_at(anomaly.x, anomaly.y).map(|f| f.id);
+        let id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id,
+            year: random_year(&mut rng),
+            event_type: EventType::MagicalCatastrophe,
+            faction,
+            other_faction: None,
+            location: Some((anomaly.x, anomaly.y)),
+            settlement: None,
+            name: "The Unbinding".to_string(),
+            description: "A ley line tore loose from the shifting earth and scoured the land".to_string(),
+            casualties: rng.gen_range(10..2000),
+            has_evidence: EventType::MagicalCatastrophe.leaves_evidence(),
+        });
+        added += 1;
+    }
+
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat_world(w: usize, h: usize) -> (Tilemap<f32>, Tilemap<ExtendedBiome>, Tilemap<f32>) {
+        (
+            Tilemap::new_with(w, h, 100.0),
+            Tilemap::new_with(w, h, ExtendedBiome::TemperateGrassland),
+            Tilemap::new_with(w, h, 0.0),
+        )
+    }
+
+    #[test]
+    fn test_line_path_wraps() {
+        let path = line_path(100, (98, 5), (2, 5));
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.first(), Some(&(98, 5)));
+        assert_eq!(path.last(), Some(&(2, 5)));
+        assert!(path.contains(&(0, 5)));
+    }
+
+    #[test]
+    fn test_network_connects_all_nodes() {
+        let (heightmap, mut biomes, stress) = flat_world(120, 60);
+        biomes.set(10, 10, ExtendedBiome::DarkTower);
+        biomes.set(60, 30, ExtendedBiome::LeyNexus);
+        biomes.set(100, 50, ExtendedBiome::WhisperingStones);
+        biomes.set(30, 50, ExtendedBiome::EtherealMist);
+
+        let magic = generate_magic(&heightmap, &biomes, &stress, &MagicConfig::default(), 1);
+        assert_eq!(magic.nodes.len(), 4);
+        assert_eq!(magic.nodes[0].kind, LeyNodeKind::Unique(ExtendedBiome::DarkTower));
+        assert!(magic.lines.len() >= 3);
+
+        // Union-find over the lines must join every node
+        let mut parent: Vec<usize> = (0..4).collect();
+        fn root(p: &mut Vec<usize>, i: usize) -> usize {
+            if p[i] != i {
+                let r = root(p, p[i]);
+                p[i] = r;
+            }
+            p[i]
+        }
+        for line in &magic.lines {
+            let (a, b) = (root(&mut parent, line.from), root(&mut parent, line.to));
+            parent[a] = b;
+        }
+        let r = root(&mut parent, 0);
+        assert!((0..4).all(|i| root(&mut parent, i) == r));
+
+        // Mana peaks at nodes, is present on lines and fades away from them
+        assert!(magic.mana_at(10, 10) > 0.9);
+        let (lx, ly) = magic.lines[0].path[magic.lines[0].path.len() / 2];
+        assert!(magic.mana_at(lx, ly) > 0.4);
+        assert!(magic.on_ley_line(lx, ly));
+        assert!(magic.describe(10, 10).unwrap().contains("Dark Tower"));
+    }
+
+    #[test]
+    fn test_wild_surge_on_stressed_ground() {
+        let (heightmap, mut biomes, mut stress) = flat_world(80, 40);
+        biomes.set(25, 20, ExtendedBiome::LeyNexus);
+        biomes.set(55, 20, ExtendedBiome::WhisperingStones);
+        for y in 0..40 {
+            stress.set(40, y, 0.55);
+        }
+        let config = MagicConfig { extra_link_chance: 0.0, ..MagicConfig::default() };
+        let magic = generate_magic(&heightmap, &biomes, &stress, &config, 2);
+        assert!(magic.anomalies.iter().any(|a| a.kind == AnomalyKind::WildSurge && a.x == 40));
+        assert!(magic.anomalies.iter().any(|a| a.kind == AnomalyKind::DeadZone));
+    }
+}
@@ -1,29 +1,102 @@
-use clap::Parser;
-use rand::SeedableRng;
-use rand_chacha::ChaCha8Rng;
+use clap::{Args, Parser, Subcommand};
 
+mod adventures;
+mod aeolian;
+mod aging;
 mod ascii;
+mod biome_constraints;
 mod biome_feathering;
 mod biomes;
+#[cfg(feature = "lore-llm")]
+mod brief;
+mod budget;
+mod campaign;
+mod cartography;
+mod cave_biomes;
+mod chemistry;
 mod climate;
+mod coast_character;
 mod coastline;
+mod craters;
 mod erosion;
+mod exploration;
 mod explorer;
+mod flora;
+mod gameplay;
+mod gazetteer;
+mod geothermal;
 mod heightmap;
 mod history;
+mod hot_reload;
+mod known_world;
+mod lakes;
+mod landforms;
+mod layer_export;
+mod loess;
+mod magic;
+mod mass_wasting;
 mod multiscale;
+mod names;
+mod paleo;
 mod plates;
+mod polar;
+mod preset;
 mod scale;
+mod scenario;
+mod section_export;
+mod seed_mining;
+mod seismic;
+mod sketch;
 mod structures;
+mod succession;
+mod system;
+mod telemetry;
 mod tilemap;
+mod underground_rivers;
+mod vector_export;
+#[cfg(feature = "viewer")]
+mod viewer;
 mod water_bodies;
+mod waves;
 mod world;
 mod zlevel;
 
 #[derive(Parser, Debug)]
 #[command(name = "planet_generator")]
 #[command(about = "Generate procedural planet maps with tectonic plates")]
-struct Args {
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Without a subcommand, generate a world and explore it
+    #[command(flatten)]
+    explore: ExploreArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate a world and save it to a world file
+    Generate(GenerateArgs),
+    /// Explore a world in the terminal explorer (or the desktop viewer)
+    Explore(ExploreArgs),
+    /// Run erosion sweeps, autotuning, seed mining or a solar system
+    #[command(subcommand)]
+    Simulate(SimulateCommand),
+    /// Summarize a world's history and export its timeline and gazetteer
+    History(HistoryArgs),
+    /// Export layers, themed maps, GeoJSON, the gameplay layer, known-world maps or a terminal preview
+    Export(ExportArgs),
+    /// Export local (embark-scale) maps or their debug dump
+    Local(LocalArgs),
+    /// Manage a campaign of linked worlds and generate sequels
+    #[command(subcommand)]
+    Campaign(CampaignCommand),
+}
+
+/// Size, seed and plate count shared by everything that builds a planet
+#[derive(Args, Clone, Debug)]
+struct PlanetArgs {
     /// Width of the tilemap in pixels
     #[arg(short = 'W', long, default_value = "512")]
     width: usize,
@@ -39,315 +112,1316 @@ struct Args {
     /// Number of tectonic plates (random 6-15 if not specified)
     #[arg(short = 'p', long)]
     plates: Option<usize>,
+}
 
-    /// Export timeline to a text file (e.g., "chronicle.txt")
+/// Options for generating a world
+#[derive(Args, Clone, Debug)]
+struct GenerationArgs {
+    #[command(flatten)]
+    planet: PlanetArgs,
+
+    /// Coastline complexity: how much of the coast becomes fjords, rias and barrier islands (0-1)
+    #[arg(long, default_value = "0.5")]
+    coast_complexity: f32,
+
+    /// Erosion preset: a built-in look (alpine-young, old-rolling-hills, desert-mesas,
+    /// tropical-dissected, glaciated-shield) or a JSON preset file
     #[arg(long)]
-    export_timeline: Option<String>,
+    erosion_preset: Option<String>,
 
-    /// Export local maps to PNG (specify output path)
+    /// Seed the heightmap from a real DEM (ESRI .asc grid, 16-bit PNG or GeoTIFF),
+    /// resampled to --width x --height
+    #[arg(long)]
+    dem: Option<String>,
+
+    /// DEM elevation (meters) treated as sea level
+    #[arg(long, default_value = "0")]
+    dem_sea_level: f32,
+
+    /// Vertical exaggeration applied to the DEM
+    #[arg(long, default_value = "1")]
+    dem_vertical_scale: f32,
+
+    /// Fit the world to a hand-drawn sketch: blue ocean, green land, brown mountains,
+    /// yellow desert, white left to the generator
+    #[arg(long)]
+    sketch: Option<String>,
+
+    /// Continent layout: random, pangaea, dual-continents, archipelago,
+    /// equatorial-band or polar-continents
+    #[arg(long)]
+    world_style: Option<String>,
+
+    /// Grayscale image (white land, black ocean) the continents are seeded on;
+    /// the generator still draws the coastlines
+    #[arg(long)]
+    continent_mask: Option<String>,
+
+    /// Drift the plates through this many earlier epochs, leaving worn-down old
+    /// ranges and rifts beside the young mountain belts
+    #[arg(long)]
+    drift_epochs: Option<usize>,
+
+    /// Nudge the eroded terrain toward this power-spectrum slope (real terrain
+    /// sits near -3; steeper is smoother), leaving carved rivers in place
+    #[arg(long, allow_negative_numbers = true)]
+    spectral_slope: Option<f32>,
+
+    /// Raise hotspot island chains trailing behind moving plates, and flood
+    /// basalt plateaus where plumes break through continents
+    #[arg(long)]
+    hotspots: bool,
+
+    /// Carve rift valleys and rift lakes where continental plates pull apart
+    #[arg(long)]
+    rift_valleys: bool,
+
+    /// Generate an airless moon or dead planet: impact craters and regolith,
+    /// no oceans, rivers, climate or biomes
+    #[arg(long)]
+    airless: bool,
+
+    /// Crater density for --airless (craters per 10,000 tiles)
+    #[arg(long, default_value = "40")]
+    crater_density: f32,
+
+    /// Alien climate chemistry: a preset (titan, sulfur, fungal) or a JSON chemistry file
+    #[arg(long)]
+    chemistry: Option<String>,
+
+    /// JSON placement spec for unique biomes (replaces the built-in rules)
+    #[arg(long)]
+    unique_biomes: Option<String>,
+
+    /// JSON landform parameters: mesa, terrace and badland settings and regions forced to a landform
+    #[arg(long)]
+    landforms: Option<String>,
+
+    /// Generate the ley-line magic layer (mana field, anomalies, wizard towers in history)
+    #[arg(long)]
+    magic: bool,
+
+    /// Degrees C added to the whole temperature map (negative = a colder world)
+    #[arg(long, default_value = "0", allow_hyphen_values = true)]
+    temperature_offset: f32,
+
+    /// JSON world preset: size, plates, erosion look, climate, chemistry, magic and a
+    /// layout of landmasses; the settings it gives replace the matching options
+    #[arg(long)]
+    preset: Option<String>,
+
+    /// Describe the world in plain words and let the LLM write the preset
+    #[cfg(feature = "lore-llm")]
+    #[arg(long)]
+    brief: Option<String>,
+
+    /// Ask the LLM to change the preset from --preset or --brief ("make it colder", ...)
+    #[cfg(feature = "lore-llm")]
+    #[arg(long)]
+    refine: Option<String>,
+
+    /// Write the preset this run used, to inspect, edit or reuse without the LLM
+    #[arg(long)]
+    save_preset: Option<String>,
+
+    /// JSON scenario of authored events (comet strikes, prophesied heroes, plagues) to
+    /// schedule into the history and into later aging
+    #[arg(long)]
+    scenario: Option<String>,
+
+    /// Keep the macro layout (plates, coastlines, mountain belts, moisture) independent of
+    /// resolution, so a small preview and a large render show the same planet
+    #[arg(long)]
+    scale_invariant: bool,
+
+    /// Write generation metrics (stage timings, peak memory, entity counts);
+    /// ".prom" for Prometheus text, otherwise appended as CSV rows
+    #[arg(long)]
+    metrics: Option<String>,
+
+    /// Worker threads for erosion, aging and the other parallel stages (default: all cores)
+    #[arg(long)]
+    max_threads: Option<usize>,
+
+    /// Refuse to generate worlds estimated to need more memory than this (MB);
+    /// also caps the local chunk cache
     #[arg(long)]
-    export_local: Option<String>,
+    max_memory_mb: Option<usize>,
 
-    /// Center X coordinate for local export (default: center of map)
+    /// Keep erosion on the CPU even when the GPU is available
     #[arg(long)]
-    export_local_x: Option<usize>,
+    no_gpu: bool,
+}
+
+/// Where a command gets its world: a saved world file, or a fresh generation
+#[derive(Args, Debug)]
+struct WorldArgs {
+    /// Load a world saved by `generate` instead of generating one
+    #[arg(long)]
+    world: Option<String>,
+
+    #[command(flatten)]
+    generation: GenerationArgs,
+}
+
+#[derive(Args, Debug)]
+struct GenerateArgs {
+    #[command(flatten)]
+    generation: GenerationArgs,
+
+    /// World file to write
+    #[arg(short, long, default_value = "world.bin")]
+    out: String,
+
+    /// Age the finished world by this many years (erosion, sea-level drift,
+    /// ruin decay, abandonment) before saving
+    #[arg(long, value_name = "YEARS")]
+    age: Option<u32>,
+}
+
+#[derive(Args, Debug)]
+struct ExploreArgs {
+    #[command(flatten)]
+    world: WorldArgs,
+
+    /// Watch a tuning file (erosion, coast_complexity, fantasy_intensity) while exploring and
+    /// re-run the stages its edits affect; created from the current parameters if missing
+    #[arg(long, conflicts_with = "world")]
+    watch: Option<String>,
+
+    /// Open the desktop viewer instead of the terminal explorer
+    #[cfg(feature = "viewer")]
+    #[arg(long)]
+    viewer: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum SimulateCommand {
+    /// Run an erosion parameter sweep and write its metrics CSV and contact sheet
+    Sweep(SweepArgs),
+    /// Search erosion parameters and save the best set as a preset
+    Autotune(AutotuneArgs),
+    /// Screen a range of seeds at low resolution and report the best
+    MineSeeds(MineSeedsArgs),
+    /// Generate a solar system (a star and its planets) and write its JSON summary
+    System(SystemArgs),
+}
+
+#[derive(Args, Debug)]
+struct SweepArgs {
+    #[command(flatten)]
+    planet: PlanetArgs,
+
+    /// Base erosion parameters: a built-in look or a JSON preset file
+    #[arg(long)]
+    erosion_preset: Option<String>,
+
+    /// Sweep axis for the grid columns (NAME=MIN,MAX,STEPS)
+    #[arg(short = 'x', long = "x")]
+    x_axis: String,
+
+    /// Optional second sweep axis for the grid rows (NAME=MIN,MAX,STEPS)
+    #[arg(short = 'y', long = "y")]
+    y_axis: Option<String>,
+
+    /// Output prefix for sweep results (writes PREFIX.csv and PREFIX.png)
+    #[arg(long, default_value = "sweep")]
+    out: String,
+}
+
+#[derive(Args, Debug)]
+struct AutotuneArgs {
+    #[command(flatten)]
+    planet: PlanetArgs,
 
-    /// Center Y coordinate for local export (default: center of map)
+    /// Base erosion parameters: a built-in look or a JSON preset file
     #[arg(long)]
-    export_local_y: Option<usize>,
+    erosion_preset: Option<String>,
 
-    /// Radius in chunks for local export (default: 5)
+    /// Number of erosion runs to evaluate
+    #[arg(long)]
+    evaluations: usize,
+
+    /// Metric targets (e.g. "drainage_density=0.3,hypsometric_integral=0.45");
+    /// maximizes the realism score if omitted
+    #[arg(long)]
+    target: Option<String>,
+
+    /// Output path for the autotuned erosion preset
+    #[arg(long, default_value = "erosion_preset.json")]
+    out: String,
+}
+
+#[derive(Args, Debug)]
+struct MineSeedsArgs {
+    /// Seed range to screen (START..END)
+    range: String,
+
+    /// Criteria (e.g. "land=0.3..0.45,continents=3..5,inland_sea=0.01,realism=40");
+    /// ranks by realism score if omitted
+    #[arg(long)]
+    criteria: Option<String>,
+
+    /// Number of best seeds to report
     #[arg(long, default_value = "5")]
-    export_local_radius: usize,
+    top: usize,
 
-    /// Scale factor for local export (1-4, default: 1)
-    #[arg(long, default_value = "1")]
-    export_local_scale: u32,
+    /// Screening resolution (WIDTHxHEIGHT)
+    #[arg(long, default_value = "128x64")]
+    resolution: String,
+
+    /// Number of tectonic plates (random 6-15 if not specified)
+    #[arg(short = 'p', long)]
+    plates: Option<usize>,
+
+    /// Output prefix for results (writes PREFIX.csv and PREFIX.png)
+    #[arg(long, default_value = "seeds")]
+    out: String,
+}
+
+#[derive(Args, Debug)]
+struct SystemArgs {
+    #[command(flatten)]
+    planet: PlanetArgs,
+
+    /// Number of planets (random 3-8 if not specified)
+    #[arg(long)]
+    planets: Option<usize>,
+
+    /// JSON summary to write
+    #[arg(short, long, default_value = "system.json")]
+    out: String,
+}
+
+#[derive(Subcommand, Debug)]
+enum CampaignCommand {
+    /// List a campaign's worlds and cross-world references
+    Show(CampaignShowArgs),
+    /// Add a saved world to a campaign (creating the campaign file if needed)
+    Add(CampaignAddArgs),
+    /// Generate a later age or a colony of a campaign world
+    Sequel(CampaignSequelArgs),
+}
+
+#[derive(Args, Debug)]
+struct CampaignShowArgs {
+    /// Campaign file (JSON)
+    #[arg(long)]
+    campaign: String,
+}
 
-    /// Show chunk grid in local export
+#[derive(Args, Debug)]
+struct CampaignAddArgs {
+    /// Campaign file (JSON)
     #[arg(long)]
-    export_local_grid: bool,
+    campaign: String,
 
-    /// Export debug info for local maps (text file for analysis)
+    /// World file saved by `generate`
     #[arg(long)]
-    debug_local: Option<String>,
+    world: String,
 
-    /// X coordinate for debug export (default: center of map)
+    /// Name of the world within the campaign
     #[arg(long)]
-    debug_local_x: Option<usize>,
+    label: String,
+}
+
+#[derive(Args, Debug)]
+struct CampaignSequelArgs {
+    /// Campaign file (JSON)
+    #[arg(long)]
+    campaign: String,
+
+    /// Label of the world to follow on from
+    #[arg(long)]
+    parent: String,
+
+    /// Name of the new world within the campaign
+    #[arg(long)]
+    label: String,
+
+    /// "age" (same planet, later) or "colony" (a new world settled from the parent)
+    #[arg(long, default_value = "age")]
+    relation: String,
+
+    /// Years between the parent's present and the later age
+    #[arg(long, default_value = "500")]
+    years: i32,
+
+    /// World file to write
+    #[arg(short, long)]
+    out: String,
+}
+
+#[derive(Args, Debug)]
+struct HistoryArgs {
+    #[command(flatten)]
+    world: WorldArgs,
+
+    /// Export timeline to a text file (e.g., "chronicle.txt")
+    #[arg(long)]
+    timeline: Option<String>,
+
+    /// Export named oceans, seas, lakes and rivers (".json" for JSON, otherwise CSV)
+    #[arg(long)]
+    gazetteer: Option<String>,
+
+    /// Export a Markdown biography (plus a text-generation prompt) of each legendary artifact into DIR
+    #[arg(long)]
+    biographies: Option<String>,
+
+    /// Export an adventure packet into DIR: Markdown handouts for ruins, lairs and dungeons
+    /// with maps, hooks, nearby settlements and rumor tables
+    #[arg(long)]
+    adventures: Option<String>,
+
+    /// Number of sites in the --adventures packet
+    #[arg(long, default_value = "6")]
+    adventure_sites: usize,
+
+    /// Export a synthetic paleoclimate record as PREFIX.csv, PREFIX_markers.csv and a PREFIX_<n>.png plot per site
+    #[arg(long)]
+    paleo: Option<String>,
+
+    /// Site for --paleo as X,Y (repeatable; default: capitals and the oldest settlements)
+    #[arg(long)]
+    paleo_site: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+struct ExportArgs {
+    #[command(flatten)]
+    world: WorldArgs,
+
+    /// Export world layers as PREFIX_<layer>.png (elevation, temperature, moisture, biomes, stress) plus a themed PREFIX_map.png and plate boundary polylines as PREFIX_boundaries.json
+    #[arg(long)]
+    layers: Option<String>,
+
+    /// Output resolution for --layers (WIDTHxHEIGHT, default: the world size)
+    #[arg(long)]
+    resolution: Option<String>,
+
+    /// Map theme for rendered exports: parchment, satellite, retro, political, or a theme JSON file
+    #[arg(long, default_value = "parchment")]
+    theme: String,
+
+    /// Export the gameplay layer (movement costs, passability, cover, attrition) as PREFIX.bin + PREFIX.json
+    #[arg(long)]
+    gameplay: Option<String>,
+
+    /// Export rivers, floodways, lakes, coastlines, borders, roads, settlements and place names as PREFIX_<layer>.geojson
+    #[arg(long)]
+    geojson: Option<String>,
+
+    /// Export per-faction known-world maps as PREFIX_<faction>.png + .json
+    #[arg(long)]
+    known: Option<String>,
+
+    /// Faction (name or id) for --known (default: every active faction)
+    #[arg(long)]
+    known_faction: Option<String>,
+
+    /// Export castle, city and village desirability heatmaps (and their factors) as PREFIX_<type>[_<factor>].png
+    #[arg(long)]
+    desirability: Option<String>,
+
+    /// Print the per-factor desirability breakdown of tile X,Y for castles, cities and villages
+    #[arg(long)]
+    explain: Option<String>,
+
+    /// Export the heightmap's radially averaged power spectrum as CSV and print its slope
+    #[arg(long)]
+    spectrum: Option<String>,
+
+    /// Export a horizontal slice of z-level Z as PREFIX_z<Z>.txt + .png (repeatable; PREFIX is --section-prefix)
+    #[arg(long, allow_negative_numbers = true)]
+    zslice: Vec<i32>,
+
+    /// Export a vertical cross-section along row:N or col:N as PREFIX_<row|col>N.txt + .png (repeatable)
+    #[arg(long)]
+    section: Vec<String>,
+
+    /// File prefix for --zslice and --section
+    #[arg(long, default_value = "section")]
+    section_prefix: String,
+
+    /// Print a high-density terminal preview of a layer (biome, height, temperature, moisture, plates, stress)
+    #[arg(long)]
+    preview: Option<String>,
+
+    /// Preview cell mode: half (1x2 half blocks) or braille (2x4 dots)
+    #[arg(long, default_value = "half")]
+    preview_cells: String,
 
-    /// Y coordinate for debug export (default: center of map)
+    /// Preview width in terminal columns (default: the terminal width)
     #[arg(long)]
-    debug_local_y: Option<usize>,
+    preview_width: Option<usize>,
+}
+
+#[derive(Args, Debug)]
+struct LocalArgs {
+    #[command(flatten)]
+    world: WorldArgs,
+
+    /// Export local maps to PNG (specify output path)
+    #[arg(long)]
+    out: Option<String>,
+
+    /// Center X coordinate (default: center of map)
+    #[arg(long)]
+    x: Option<usize>,
+
+    /// Center Y coordinate (default: center of map)
+    #[arg(long)]
+    y: Option<usize>,
+
+    /// Radius in chunks for the local export
+    #[arg(long, default_value = "5")]
+    radius: usize,
+
+    /// Scale factor for the local export (1-4)
+    #[arg(long, default_value = "1")]
+    scale: u32,
+
+    /// Show chunk grid in the local export
+    #[arg(long)]
+    grid: bool,
+
+    /// Export debug info for the local maps (text file for analysis)
+    #[arg(long)]
+    debug: Option<String>,
 }
 
 fn main() {
-    let args = Args::parse();
+    let cli = Cli::parse();
+    match cli.command.unwrap_or(Command::Explore(cli.explore)) {
+        Command::Generate(args) => run_generate(&args),
+        Command::Explore(args) => run_explore(args),
+        Command::Simulate(SimulateCommand::Sweep(args)) => run_sweep_mode(&args),
+        Command::Simulate(SimulateCommand::Autotune(args)) => run_autotune_mode(&args),
+        Command::Simulate(SimulateCommand::MineSeeds(args)) => run_mine_seeds_mode(&args),
+        Command::Simulate(SimulateCommand::System(args)) => run_system_mode(&args),
+        Command::History(args) => run_history(&args),
+        Command::Export(args) => run_export(&args),
+        Command::Local(args) => run_local(&args),
+        Command::Campaign(command) => run_campaign(&command),
+    }
+}
 
-    // Initialize RNG
-    let seed = args.seed.unwrap_or_else(|| rand::random());
-    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+// =============================================================================
+// WORLD SOURCES
+// =============================================================================
+
+/// Load erosion parameters from an optional built-in preset or preset file
+fn load_erosion_preset(spec: Option<&str>) -> Option<erosion::ErosionParams> {
+    match spec {
+        Some(spec) => match erosion::ErosionParams::load(spec) {
+            Ok(params) => {
+                match erosion::ErosionPreset::from_name(spec) {
+                    Some(preset) => println!("Erosion preset: {} ({})", preset.name(), preset.description()),
+                    None => println!("Loaded erosion preset: {}", spec),
+                }
+                Some(params)
+            }
+            Err(e) => {
+                eprintln!("Failed to load erosion preset {}: {}", spec, e);
+                None
+            }
+        },
+        None => Some(erosion::ErosionParams::default()),
+    }
+}
+
+/// Read --preset (and, with the LLM, --brief and --refine) and fold the preset
+/// into the options. --save-preset writes out the settings the run will use.
+fn resolve_preset(args: &GenerationArgs) -> Option<(GenerationArgs, Option<preset::WorldPreset>)> {
+    #[allow(unused_mut)]
+    let mut world_preset = match args.preset {
+        Some(ref path) => match preset::WorldPreset::load(path) {
+            Ok(preset) => Some(preset),
+            Err(e) => {
+                eprintln!("Failed to load world preset {}: {}", path, e);
+                return None;
+            }
+        },
+        None => None,
+    };
+
+    #[cfg(feature = "lore-llm")]
+    {
+        let config = brief::LlmConfig::default();
+        if let Some(ref text) = args.brief {
+            println!("Asking {} to write a preset for the brief...", config.model);
+            match brief::preset_from_brief(text, &config) {
+                Ok(preset) => world_preset = Some(preset),
+                Err(e) => {
+                    eprintln!("Failed to turn the brief into a preset: {}", e);
+                    return None;
+                }
+            }
+        }
+        if let Some(ref feedback) = args.refine {
+            let Some(ref current) = world_preset else {
+                eprintln!("--refine needs a preset from --preset or --brief");
+                return None;
+            };
+            match brief::refine_preset(current, feedback, &config) {
+                Ok(preset) => world_preset = Some(preset),
+                Err(e) => {
+                    eprintln!("Failed to refine the preset: {}", e);
+                    return None;
+                }
+            }
+        }
+    }
+
+    let mut resolved = args.clone();
+    if let Some(ref preset) = world_preset {
+        println!("World preset: {}", preset.summary());
+        resolved.planet.width = preset.width.unwrap_or(resolved.planet.width);
+        resolved.planet.height = preset.height.unwrap_or(resolved.planet.height);
+        resolved.planet.seed = preset.seed.or(resolved.planet.seed);
+        resolved.planet.plates = preset.plates.or(resolved.planet.plates);
+        resolved.erosion_preset = preset.erosion_preset.clone().or(resolved.erosion_preset);
+        resolved.coast_complexity = preset.coast_complexity.unwrap_or(resolved.coast_complexity);
+        resolved.chemistry = preset.chemistry.clone().or(resolved.chemistry);
+        resolved.magic |= preset.magic;
+        if preset.temperature_offset != 0.0 {
+            resolved.temperature_offset = preset.temperature_offset;
+        }
+    }
+
+    if let Some(ref path) = args.save_preset {
+        // Fix the seed now, so the saved preset regenerates this exact world
+        resolved.planet.seed = Some(resolved.planet.seed.unwrap_or_else(rand::random));
+        let saved = preset::WorldPreset {
+            width: Some(resolved.planet.width),
+            height: Some(resolved.planet.height),
+            seed: resolved.planet.seed,
+            plates: resolved.planet.plates,
+            erosion_preset: resolved.erosion_preset.clone(),
+            coast_complexity: Some(resolved.coast_complexity),
+            temperature_offset: resolved.temperature_offset,
+            chemistry: resolved.chemistry.clone(),
+            magic: resolved.magic,
+            ..world_preset.clone().unwrap_or_default()
+        };
+        match saved.save(path) {
+            Ok(()) => println!("Saved world preset: {}", path),
+            Err(e) => eprintln!("Failed to save world preset {}: {}", path, e),
+        }
+    }
+    Some((resolved, world_preset))
+}
+
+/// Thread, memory and GPU limits from the command-line options
+fn resource_budget(args: &GenerationArgs) -> budget::ResourceBudget {
+    budget::ResourceBudget {
+        max_threads: args.max_threads,
+        max_memory_bytes: args.max_memory_mb.map(|mb| mb * 1024 * 1024),
+        allow_gpu: !args.no_gpu,
+    }
+}
+
+/// Load a saved world, or generate one from the command-line options
+fn obtain_world(args: &WorldArgs) -> Option<world::WorldData> {
+    match args.world {
+        Some(ref path) => match world::WorldData::load(path) {
+            Ok(world) => {
+                println!("Loaded world: {} (seed {}, {}x{})", path, world.seed, world.width, world.height);
+                Some(world)
+            }
+            Err(e) => {
+                eprintln!("Failed to load world {}: {}", path, e);
+                None
+            }
+        },
+        None => generate(&args.generation, None).map(|(world, _)| world),
+    }
+}
+
+/// Run the generation pipeline. With `watch`, tuning parameters come from
+/// (and are later hot-reloaded from) that file.
+fn generate(args: &GenerationArgs, watch: Option<&str>) -> Option<(world::WorldData, Option<hot_reload::HotReload>)> {
+    let (args, world_preset) = resolve_preset(args)?;
+    let args = &args;
+    let seed = args.planet.seed.unwrap_or_else(rand::random);
+    let (width, height) = (args.planet.width, args.planet.height);
 
     println!("Generating planet with seed: {}", seed);
-    println!("Map size: {}x{}", args.width, args.height);
-
-    // Generate tectonic plates
-    println!("Generating tectonic plates...");
-    let (plate_map, plates) = plates::generate_plates(args.width, args.height, args.plates, &mut rng);
-    let continental_count = plates.iter().filter(|p| p.plate_type == plates::PlateType::Continental).count();
-    let oceanic_count = plates.iter().filter(|p| p.plate_type == plates::PlateType::Oceanic).count();
-    println!("Created {} plates ({} continental, {} oceanic)", plates.len(), continental_count, oceanic_count);
-
-    // Calculate stress at plate boundaries
-    println!("Calculating plate stress...");
-    let stress_map = plates::calculate_stress(&plate_map, &plates);
-
-    // Generate heightmap
-    println!("Generating heightmap...");
-    let land_mask = heightmap::generate_land_mask(&plate_map, &plates, seed);
-    let land_count = (0..args.height).flat_map(|y| (0..args.width).map(move |x| (x, y)))
-        .filter(|&(x, y)| *land_mask.get(x, y)).count();
-    println!("Land mask: {} cells are land ({:.1}%)", land_count, 100.0 * land_count as f64 / (args.width * args.height) as f64);
-    let mut heightmap = heightmap::generate_heightmap(&plate_map, &plates, &stress_map, seed);
-    let mut min_h = f32::MAX;
-    let mut max_h = f32::MIN;
-    for (_, _, &h) in heightmap.iter() {
-        if h < min_h { min_h = h; }
-        if h > max_h { max_h = h; }
-    }
-    let above_sea = (0..args.height).flat_map(|y| (0..args.width).map(move |x| (x, y)))
-        .filter(|&(x, y)| *heightmap.get(x, y) > 0.0).count();
-    println!("Heightmap range: {:.1}m to {:.1}m ({:.1}% above sea level)", min_h, max_h,
-        100.0 * above_sea as f64 / (args.width * args.height) as f64);
-
-    // Generate climate (needed for glacial erosion temperature zones)
-    println!("Generating climate...");
-    let temperature = climate::generate_temperature(&heightmap, args.width, args.height);
-    let moisture = climate::generate_moisture(&heightmap, args.width, args.height);
-
-    // Report climate stats
-    let mut min_temp = f32::MAX;
-    let mut max_temp = f32::MIN;
-    for (_, _, &t) in temperature.iter() {
-        if t < min_temp { min_temp = t; }
-        if t > max_temp { max_temp = t; }
-    }
-    println!("Temperature range: {:.1}°C to {:.1}°C", min_temp, max_temp);
-
-    // Hardness map (defaults to 0.5 if erosion is disabled/not run)
-    let mut hardness_map = tilemap::Tilemap::new_with(args.width, args.height, 0.5f32);
-
-    // Apply erosion
-    println!("Simulating erosion...");
-    let erosion_params = erosion::ErosionParams::default();
-
-    let (stats, h_map) = erosion::simulate_erosion(
-        &mut heightmap,
-        &plate_map,
-        &plates,
-        &stress_map,
-        &temperature,
-        &erosion_params,
-        &mut rng,
-        seed,
-    );
-    hardness_map = h_map;
-
-    println!("Erosion complete:");
-    println!("  Total eroded: {:.1} units", stats.total_eroded);
-    println!("  Total deposited: {:.1} units", stats.total_deposited);
-    println!("  Max erosion: {:.2} units", stats.max_erosion);
-    println!("  Max deposition: {:.2} units", stats.max_deposition);
-
-    // Update heightmap stats after erosion
-    min_h = f32::MAX;
-    max_h = f32::MIN;
-    for (_, _, &h) in heightmap.iter() {
-        if h < min_h { min_h = h; }
-        if h > max_h { max_h = h; }
-    }
-    println!("Post-erosion heightmap range: {:.1}m to {:.1}m", min_h, max_h);
-
-    // Apply coastline jittering for more organic shorelines
-    println!("Applying coastline jittering...");
-    let coastline_params = coastline::CoastlineParams::default();
-    let coastline_network = coastline::generate_coastline_network(&heightmap, &coastline_params, seed);
-    coastline::apply_coastline_to_heightmap(&coastline_network, &mut heightmap, coastline_params.blend_width);
-
-    // Apply terrain noise layers based on region type
-    println!("Applying terrain noise layers...");
-    heightmap::apply_regional_noise_stacks(&mut heightmap, &stress_map, seed);
-
-    // Detect water bodies (lakes, rivers, ocean)
-    println!("Detecting water bodies...");
-    let (water_body_map, water_bodies_list) = water_bodies::detect_water_bodies(&heightmap);
-    let lake_count = water_bodies::count_lakes(&water_bodies_list);
-    let stats = water_bodies::water_body_stats(&water_bodies_list);
-    println!("Found {} lakes, {} river tiles, {} ocean tiles",
-        lake_count, stats.river_tiles, stats.ocean_tiles);
-
-    // Generate extended biomes for explorer
-    let biome_config = biomes::WorldBiomeConfig::default();
-    let mut extended_biomes = biomes::generate_extended_biomes(
-        &heightmap,
-        &temperature,
-        &moisture,
-        &stress_map,
-        &biome_config,
-        seed,
-    );
-
-    // Apply biome replacement rules (rare biomes replace common ones)
-    println!("Applying rare biome replacements...");
-    let rare_biome_clusters = biomes::apply_biome_replacements(
-        &mut extended_biomes,
-        &heightmap,
-        &temperature,
-        &moisture,
-        &stress_map,
-        seed,
-    );
-    println!("Created {} rare biome clusters", rare_biome_clusters);
-
-    // Apply fantasy lake conversions (transform entire lakes to LavaLake, FrozenLake, etc.)
-    let fantasy_lakes_converted = water_bodies::apply_fantasy_lake_conversions(
-        &mut extended_biomes,
-        &water_bodies_list,
-        &water_body_map,
-        &temperature,
-        &stress_map,
-        seed,
-    );
-    if fantasy_lakes_converted > 0 {
-        println!("Converted {} lakes to fantasy biomes", fantasy_lakes_converted);
+    println!("Map size: {}x{}", width, height);
+
+    // Airless mode: cratered moon or dead planet with no hydrosphere
+    if args.airless {
+        let params = craters::CraterParams {
+            density: args.crater_density,
+            ..craters::CraterParams::default()
+        };
+        println!("Generating airless world...");
+        let (world_data, airless) = craters::generate_airless_world(width, height, seed, &params);
+        let complex = airless.craters.iter().filter(|c| c.is_complex).count();
+        let maria = airless.maria_mask.iter().filter(|(_, _, &m)| m).count();
+        println!("  Craters: {} ({} complex)", airless.craters.len(), complex);
+        let tiles = (width * height) as f32;
+        let mean_regolith = airless.regolith_depth.iter().map(|(_, _, &d)| d).sum::<f32>() / tiles;
+        println!("  Maria coverage: {:.1}%", maria as f32 / tiles * 100.0);
+        println!("  Mean regolith depth: {:.1}m", mean_regolith);
+        return Some((world_data, None));
     }
 
-    // Place unique biomes (exactly one per map)
-    let unique_biomes_placed = biomes::place_unique_biomes(
-        &mut extended_biomes,
-        &heightmap,
-        seed,
-    );
-    if unique_biomes_placed > 0 {
-        println!("Placed {} unique biomes", unique_biomes_placed);
+    let mut erosion_params = load_erosion_preset(args.erosion_preset.as_deref())?;
+    erosion_params.enable_hotspots |= args.hotspots;
+
+    let world_style = match args.world_style {
+        Some(ref name) => match plates::WorldStyle::from_name(name) {
+            Some(style) => {
+                println!("World style: {} ({})", style.name(), style.description());
+                style
+            }
+            None => {
+                let names: Vec<&str> = plates::WorldStyle::all().iter().map(|s| s.name()).collect();
+                eprintln!("Unknown world style {} (expected one of: {})", name, names.join(", "));
+                return None;
+            }
+        },
+        None => plates::WorldStyle::Random,
+    };
+
+    let chemistry = match args.chemistry {
+        Some(ref spec) => match chemistry::ClimateChemistry::load(spec) {
+            Ok(chem) => {
+                println!("Climate chemistry: {} ({} hydrosphere)", chem.name, chem.solvent.display_name());
+                Some(chem)
+            }
+            Err(e) => {
+                eprintln!("Failed to load climate chemistry {}: {}", spec, e);
+                return None;
+            }
+        },
+        None => None,
+    };
+
+    // Tunable parameters, taken from the watched file when hot reloading
+    let tuning = hot_reload::TuningParams {
+        erosion: erosion_params,
+        coast_complexity: args.coast_complexity,
+        ..Default::default()
+    };
+    let tuning = match watch {
+        Some(path) => match hot_reload::TuningParams::load_or_init(path, tuning) {
+            Ok(tuning) => {
+                println!("Watching tuning file: {}", path);
+                tuning
+            }
+            Err(e) => {
+                eprintln!("Failed to load tuning file {}: {}", path, e);
+                return None;
+            }
+        },
+        None => tuning,
+    };
+
+    // Seed the heightmap from a real DEM, resampled to the map size
+    let dem = match args.dem {
+        Some(ref path) => {
+            let options = heightmap::DemImportOptions {
+                target_size: Some((width, height)),
+                sea_level: args.dem_sea_level,
+                vertical_scale: args.dem_vertical_scale,
+                ..Default::default()
+            };
+            match heightmap::import_dem(path, &options) {
+                Ok(map) => {
+                    println!("Imported DEM: {}", path);
+                    Some(map)
+                }
+                Err(e) => {
+                    eprintln!("Failed to import DEM {}: {}", path, e);
+                    return None;
+                }
+            }
+        }
+        None => None,
+    };
+
+    let sketch = match args.sketch {
+        Some(ref path) => match sketch::Sketch::load(path, width, height) {
+            Ok(sketch) => {
+                println!("Loaded sketch: {}", path);
+                Some(sketch)
+            }
+            Err(e) => {
+                eprintln!("Failed to load sketch {}: {}", path, e);
+                return None;
+            }
+        },
+        None => world_preset.as_ref().and_then(|p| p.sketch(width, height)),
+    };
+
+    // Unique biomes (exactly one per map)
+    let placement_spec = match args.unique_biomes {
+        Some(ref path) => match biome_constraints::PlacementSpec::load(path) {
+            Ok(spec) => spec,
+            Err(e) => {
+                eprintln!("Failed to load unique biome spec {}: {}", path, e);
+                return None;
+            }
+        },
+        None => biome_constraints::default_spec(),
+    };
+
+    let landform_params = match args.landforms {
+        Some(ref path) => match landforms::LandformParams::load(path) {
+            Ok(params) => params,
+            Err(e) => {
+                eprintln!("Failed to load landform parameters {}: {}", path, e);
+                return None;
+            }
+        },
+        None => landforms::LandformParams::default(),
+    };
+
+    let scenario = match args.scenario {
+        Some(ref path) => match scenario::Scenario::load(path).and_then(|s| s.check_bounds(width, height).map(|_| s)) {
+            Ok(scenario) => {
+                println!("Loaded scenario: {} ({} events)", scenario.name, scenario.events.len());
+                Some(scenario)
+            }
+            Err(e) => {
+                eprintln!("Failed to load scenario {}: {}", path, e);
+                return None;
+            }
+        },
+        None => None,
+    };
+
+    let mut telemetry = telemetry::Telemetry::new(seed);
+    let mut generator = world::WorldGenerator::new()
+        .size(width, height)
+        .seed(seed)
+        .budget(resource_budget(args))
+        .erosion(tuning.erosion.clone())
+        .coast(coast_character::CoastCharacterParams {
+            complexity: tuning.coast_complexity,
+            ..Default::default()
+        })
+        .landforms(landform_params)
+        .biomes(biomes::WorldBiomeConfig {
+            fantasy_intensity: tuning.fantasy_intensity,
+            ..Default::default()
+        })
+        .unique_biomes(placement_spec)
+        .temperature_offset(args.temperature_offset)
+        .with_structures()
+        .with_history(world::HistoryConfig {
+            magic: args.magic.then(magic::MagicConfig::default),
+        })
+        .on_progress(|progress| match progress {
+            world::Progress::Stage(name, description) => {
+                telemetry.stage(name);
+                println!("{}...", description);
+            }
+            world::Progress::Detail(line) => println!("{}", line),
+        });
+    if let Some(count) = args.planet.plates {
+        generator = generator.plates(count);
+    }
+    generator = generator.world_style(world_style);
+    if let Some(ref path) = args.continent_mask {
+        match plates::ContinentMask::load(path) {
+            Ok(mask) => {
+                println!("Loaded continent mask: {} ({:.0}% land)", path, mask.land_fraction() * 100.0);
+                generator = generator.continent_mask(mask);
+            }
+            Err(e) => {
+                eprintln!("Failed to load continent mask {}: {}", path, e);
+                return None;
+            }
+        }
+    }
+    if args.scale_invariant {
+        generator = generator.scale_invariant();
+    }
+    if let Some(map) = dem {
+        generator = generator.heightmap(map);
+    }
+    if let Some(ref chem) = chemistry {
+        generator = generator.chemistry(chem.clone());
+    }
+    if let Some(sketch) = sketch {
+        generator = generator.sketch(sketch);
+    }
+    if let Some(epochs) = args.drift_epochs {
+        generator = generator.drift(plates::DriftParams { epochs, ..Default::default() });
+    }
+    if args.rift_valleys {
+        generator = generator.rift_valleys();
+    }
+    if let Some(slope) = args.spectral_slope {
+        generator = generator.spectral_shaping(erosion::SpectralShaping { slope, ..Default::default() });
+    }
+    if let Some(scenario) = scenario {
+        generator = generator.scenario(scenario);
     }
 
-    // Compute biome feathering map for smooth transitions
-    println!("Computing biome feathering map...");
-    let feather_config = biome_feathering::FeatherConfig::default();
-    let biome_feather_map = biome_feathering::compute_biome_feathering(
-        &extended_biomes,
-        &feather_config,
-        seed,
-    );
-
-    // Generate Z-level data
-    println!("Generating Z-level data...");
-    let (mut zlevels, surface_z) = zlevel::generate_zlevels(&heightmap);
-    println!("Z-levels: {} to {} ({} levels)", zlevel::MIN_Z, zlevel::MAX_Z, zlevel::Z_LEVEL_COUNT);
-
-    // Generate underground water system
-    println!("Generating underground water...");
-    zlevel::generate_underground_water(
-        &mut zlevels,
-        &surface_z,
-        &heightmap,
-        &moisture,
-        seed,
-    );
-
-    // Generate Dwarf Fortress-style cave system
-    println!("Generating cave system...");
-    zlevel::generate_caves(
-        &mut zlevels,
-        &surface_z,
-        &heightmap,
-        &moisture,
-        &stress_map,
-        seed,
-    );
-
-    // Generate human-made structures (castles, cities, villages, roads)
-    println!("Generating structures...");
-    let _placed_structures = structures::generate_structures(
-        &mut zlevels,
-        &surface_z,
-        &heightmap,
-        &moisture,
-        &temperature,
-        &extended_biomes,
-        &stress_map,
-        &water_body_map,
-        seed,
-    );
-
-    // Generate world history (factions, events, settlements, monsters, trade)
-    println!("Generating world history...");
-    let world_history = history::generate_world_history(
-        &mut zlevels,
-        &surface_z,
-        &heightmap,
-        &extended_biomes,
-        &water_body_map,
-        &stress_map,
-        seed,
-    );
+    let estimate = generator.estimate_memory();
+    if let Err(e) = resource_budget(args).check(&estimate) {
+        eprintln!("{} ({})", e, estimate.summary());
+        return None;
+    }
+
+    // Hot reloading keeps snapshots at the stage boundaries, so edits re-run only the stages they affect
+    let (world_data, hot_reload) = match watch {
+        Some(path) => {
+            let (world, cache) = generator.generate_with_stage_cache();
+            (world, Some(hot_reload::HotReload::new(path, tuning, cache)))
+        }
+        None => (generator.generate(), None),
+    };
+
+    telemetry.count("plates", world_data.plates.len());
+    telemetry.count("lakes", water_bodies::count_lakes(&world_data.water_bodies));
+    telemetry.count("river_tiles", water_bodies::water_body_stats(&world_data.water_bodies).river_tiles);
+    if let Some(ref history) = world_data.history {
+        telemetry.count("factions", history.factions.factions.len());
+        telemetry.count("settlements", history.territories.settlements.len());
+        telemetry.count("monster_lairs", history.monsters.lairs.len());
+        telemetry.count("artifacts", history.artifacts.artifacts.len());
+    }
 
-    // Export timeline if requested
-    if let Some(ref filename) = args.export_timeline {
-        if let Err(e) = world_history.export_timeline(filename) {
-            eprintln!("Failed to export timeline: {}", e);
+    // Write generation metrics
+    telemetry.finish_stage();
+    if let Some(ref path) = args.metrics {
+        match telemetry.export(path) {
+            Ok(()) => println!("Metrics written to: {} ({:.1}s total)", path, telemetry.total_seconds()),
+            Err(e) => eprintln!("Failed to write metrics: {}", e),
         }
     }
 
-    // Launch explorer
+    Some((world_data, hot_reload))
+}
+
+// =============================================================================
+// COMMANDS
+// =============================================================================
+
+/// Generate a world and save it for later commands
+fn run_generate(args: &GenerateArgs) {
+    let Some((mut world_data, _)) = generate(&args.generation, None) else {
+        return;
+    };
+    if let Some(years) = args.age {
+        println!("Aging the world by {} years...", years);
+        let config = aging::AgingConfig { budget: resource_budget(&args.generation), ..Default::default() };
+        println!("  {}", aging::advance_ages_with(&mut world_data, years, &config).summary());
+    }
+    match world_data.save(&args.out) {
+        Ok(()) => println!("World saved to: {}", args.out),
+        Err(e) => eprintln!("Failed to save world: {}", e),
+    }
+}
+
+/// Open a world in the explorer or viewer
+fn run_explore(args: ExploreArgs) {
+    let loaded = match args.watch {
+        Some(ref path) => generate(&args.world.generation, Some(path)),
+        None => obtain_world(&args.world).map(|world| (world, None)),
+    };
+    let Some((world_data, hot_reload)) = loaded else {
+        return;
+    };
+    let budget = resource_budget(&args.world.generation);
+
+    #[cfg(feature = "viewer")]
+    if args.viewer {
+        if let Err(e) = viewer::run_viewer(world_data, hot_reload, budget) {
+            eprintln!("Viewer error: {}", e);
+        }
+        return;
+    }
+
     println!("Launching terminal explorer...");
-    let map_scale = scale::MapScale::default();
-    // Generate Bezier river network (Phase 1)
-    let river_network = crate::erosion::trace_bezier_rivers(&heightmap, None, seed);
+    if let Err(e) = explorer::run_explorer_watching(world_data, hot_reload, &budget) {
+        eprintln!("Explorer error: {}", e);
+    }
+}
 
-    let world_data = world::WorldData::new(
-        seed,
-        map_scale,
-        heightmap,
-        temperature,
-        moisture,
-        extended_biomes,
-        stress_map,
-        plate_map,
-        plates,
-        Some(hardness_map),
-        water_body_map,
-        water_bodies_list,
-        zlevels,
-        surface_z,
-        Some(world_history),
-        Some(river_network),
-        Some(biome_feather_map),
-    );
-
-    // Export local maps if requested
-    if let Some(ref export_path) = args.export_local {
-        use multiscale::{export_local_area, ExportOptions};
-
-        let center_x = args.export_local_x.unwrap_or(args.width / 2);
-        let center_y = args.export_local_y.unwrap_or(args.height / 2);
-        let radius = args.export_local_radius;
+/// Summarize a world's history and export its timeline and gazetteer
+fn run_history(args: &HistoryArgs) {
+    let Some(world_data) = obtain_world(&args.world) else {
+        return;
+    };
 
+    match world_data.history {
+        Some(ref history) => {
+            println!("History:");
+            println!("  {} factions ({} active)", history.factions.factions.len(), history.factions.active().count());
+            println!("  {} historical events", history.timeline.events.len());
+            println!("  {} settlements", history.territories.settlements.len());
+            println!("  {} heroes, {} artifacts", history.heroes.heroes.len(), history.artifacts.artifacts.len());
+            println!("  {} monster lairs, {} dungeons", history.monsters.lairs.len(), history.dungeons.dungeons.len());
+            println!("  {} trade routes", history.trade.routes.len());
+            if let Some(ref record) = world_data.exploration {
+                println!("  {} expeditions, {} named discoveries", record.expeditions.len(), record.discoveries.len());
+            }
+
+            if let Some(ref filename) = args.timeline {
+                if let Err(e) = history.export_timeline(filename) {
+                    eprintln!("Failed to export timeline: {}", e);
+                }
+            }
+
+            if let Some(ref dir) = args.biographies {
+                match history.export_artifact_biographies(dir) {
+                    Ok(paths) => println!("Exported {} artifact biographies to: {}", paths.len(), dir),
+                    Err(e) => eprintln!("Failed to export artifact biographies: {}", e),
+                }
+            }
+
+            if let Some(ref dir) = args.adventures {
+                let packet = adventures::build_packet(&world_data, args.adventure_sites).expect("the world has a history");
+                match packet.export(dir) {
+                    Ok(_) => println!("Exported an adventure packet of {} sites to: {}", packet.sites.len(), dir),
+                    Err(e) => eprintln!("Failed to export the adventure packet: {}", e),
+                }
+            }
+        }
+        None => {
+            println!("This world has no history");
+            if args.timeline.is_some() {
+                eprintln!("No timeline to export");
+            }
+            if args.biographies.is_some() {
+                eprintln!("No artifacts to write biographies for");
+            }
+            if args.adventures.is_some() {
+                eprintln!("No history to build adventure sites from");
+            }
+        }
+    }
+
+    if let Some(ref prefix) = args.paleo {
+        export_paleo(&world_data, &args.paleo_site, prefix);
+    }
+
+    if let Some(ref path) = args.gazetteer {
+        match world_data.gazetteer.as_ref().map(|g| g.export(path)) {
+            Some(Ok(())) => println!("Exported gazetteer to: {}", path),
+            Some(Err(e)) => eprintln!("Failed to export gazetteer: {}", e),
+            None => eprintln!("This world has no gazetteer"),
+        }
+    }
+}
+
+/// Sample the paleoclimate record at the given X,Y sites (or the default ones) and export it
+fn export_paleo(world_data: &world::WorldData, site_specs: &[String], prefix: &str) {
+    let config = paleo::PaleoConfig::default();
+    let mut sites = Vec::new();
+    for spec in site_specs {
+        let coords = spec.split_once(',').and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)));
+        match coords {
+            Some((x, y)) if x < world_data.width && y < world_data.height => {
+                sites.push(paleo::PaleoSite { name: format!("Core at {},{}", x, y), x, y });
+            }
+            _ => eprintln!("Invalid --paleo-site '{}': expected X,Y inside the world", spec),
+        }
+    }
+    if sites.is_empty() {
+        sites = paleo::default_sites(world_data, &config);
+    }
+
+    let record = paleo::paleo_record(world_data, &sites, &config);
+    println!("Exporting paleoclimate record ({} sites, {} layers)...", record.sites.len(), record.markers.len());
+    match paleo::export_paleo(&record, prefix) {
+        Ok(paths) => {
+            for path in paths {
+                println!("  {}", path);
+            }
+        }
+        Err(e) => eprintln!("Failed to export paleoclimate record: {}", e),
+    }
+}
+
+/// Show a campaign, add a world to it or generate a sequel world
+fn run_campaign(command: &CampaignCommand) {
+    let path = match command {
+        CampaignCommand::Show(args) => &args.campaign,
+        CampaignCommand::Add(args) => &args.campaign,
+        CampaignCommand::Sequel(args) => &args.campaign,
+    };
+    let mut campaign = match campaign::Campaign::load_or_new(path) {
+        Ok(campaign) => campaign,
+        Err(e) => {
+            eprintln!("Failed to load campaign {}: {}", path, e);
+            return;
+        }
+    };
+
+    let changed = match command {
+        CampaignCommand::Show(_) => Ok(false),
+        CampaignCommand::Add(args) => world::WorldData::load(&args.world).and_then(|world| {
+            campaign.add_world(&args.label, &args.world, &world, campaign::WorldRelation::Origin, None)?;
+            println!("Added {} as '{}'", args.world, args.label);
+            Ok(true)
+        }),
+        CampaignCommand::Sequel(args) => {
+            let relation = match args.relation.as_str() {
+                "age" => campaign::WorldRelation::LaterAge { years_after: args.years },
+                "colony" => campaign::WorldRelation::Colony,
+                other => {
+                    eprintln!("Unknown relation '{}' (expected age or colony)", other);
+                    return;
+                }
+            };
+            let config = campaign::SequelConfig::default();
+            campaign.create_sequel(&args.parent, &args.label, &args.out, relation, &config).map(|_| {
+                println!("Generated '{}' ({}) from '{}': {}", args.label, relation.describe(), args.parent, args.out);
+                true
+            })
+        }
+    };
+    match changed {
+        Ok(true) => {
+            if let Err(e) = campaign.save(path) {
+                eprintln!("Failed to save campaign: {}", e);
+            }
+        }
+        Ok(false) => {}
+        Err(e) => {
+            eprintln!("Campaign update failed: {}", e);
+            return;
+        }
+    }
+
+    println!("Campaign: {}", campaign.name);
+    for w in &campaign.worlds {
+        let lineage: Vec<_> = campaign.lineage(&w.label).iter().map(|l| l.label.as_str()).collect();
+        println!("  {} ({}, seed {}): {}", lineage.join(" > "), w.relation.describe(), w.seed, w.path);
+        for r in campaign.references_of(&w.label).filter(|r| r.to_world == w.label) {
+            println!("    {:?} {} (from {} #{}, now #{})", r.kind, r.name, r.from_world, r.from_id, r.to_id);
+        }
+    }
+}
+
+/// Export map layers, the gameplay layer, known-world maps or a terminal preview
+fn run_export(args: &ExportArgs) {
+    let theme = match cartography::MapTheme::resolve(&args.theme) {
+        Ok(theme) => theme,
+        Err(e) => {
+            eprintln!("Failed to load map theme: {}", e);
+            return;
+        }
+    };
+    let Some(world_data) = obtain_world(&args.world) else {
+        return;
+    };
+
+    // Terminal preview for quick inspection (e.g. over SSH)
+    if let Some(ref layer) = args.preview {
+        let Some(mode) = ascii::AsciiMode::from_name(layer) else {
+            eprintln!("Unknown preview layer '{}'", layer);
+            return;
+        };
+        let Some(cells) = ascii::CellMode::from_name(&args.preview_cells) else {
+            eprintln!("Unknown preview cell mode '{}' (half or braille)", args.preview_cells);
+            return;
+        };
+        let columns = args
+            .preview_width
+            .or_else(|| crossterm::terminal::size().ok().map(|(w, _)| w as usize))
+            .unwrap_or(100);
+        let options = ascii::DenseOptions { cells, depth: ascii::ColorDepth::detect(), columns };
+        print!("{}", ascii::render_dense_map(&ascii::MapLayers::of(&world_data), mode, &options));
+    }
+
+    // Export world layers at the requested output resolution
+    if let Some(ref prefix) = args.layers {
+        let resolution = match args.resolution.as_deref().map(layer_export::parse_resolution) {
+            Some(Ok(size)) => size,
+            Some(Err(e)) => {
+                eprintln!("Invalid --resolution: {}", e);
+                return;
+            }
+            None => (world_data.width, world_data.height),
+        };
+
+        println!("Exporting world layers at {}x{}...", resolution.0, resolution.1);
+        match layer_export::export_layers(&world_data, prefix, resolution.0, resolution.1, &theme) {
+            Ok(paths) => {
+                for path in paths {
+                    println!("  {}", path);
+                }
+            }
+            Err(e) => eprintln!("Failed to export layers: {}", e),
+        }
+    }
+
+    // Desirability heatmaps, to debug where structures end up
+    if let Some(ref prefix) = args.desirability {
+        println!("Exporting desirability heatmaps...");
+        match layer_export::export_desirability(&world_data, prefix) {
+            Ok(paths) => {
+                for path in paths {
+                    println!("  {}", path);
+                }
+            }
+            Err(e) => eprintln!("Failed to export desirability heatmaps: {}", e),
+        }
+    }
+
+    // Why a tile is (or isn't) a good site
+    if let Some(ref spec) = args.explain {
+        let tile = spec
+            .split_once(',')
+            .and_then(|(x, y)| Some((x.trim().parse::<usize>().ok()?, y.trim().parse::<usize>().ok()?)))
+            .filter(|&(x, y)| x < world_data.width && y < world_data.height);
+        match tile {
+            Some((x, y)) => {
+                let layers = structures::placement::DesirabilityLayers::of(&world_data);
+                println!("Desirability at ({}, {}):", x, y);
+                for kind in structures::placement::DESIRABILITY_TYPES {
+                    let explained = structures::placement::explain_desirability(kind, &layers, &[], x, y)
+                        .map_or_else(|| "unbuildable".to_string(), |b| b.describe());
+                    println!("  {:<8} {}", kind.display_name(), explained);
+                }
+            }
+            None => eprintln!("Invalid --explain '{}': expected X,Y inside the world", spec),
+        }
+    }
+
+    // How the terrain's roughness rolls off with scale
+    if let Some(ref path) = args.spectrum {
+        let spectrum = erosion::power_spectrum(&world_data.heightmap);
+        match std::fs::write(path, spectrum.to_csv()) {
+            Ok(()) => println!("Power spectrum ({} bins, slope {:.2}) written to {}", spectrum.bins.len(), spectrum.slope(), path),
+            Err(e) => eprintln!("Failed to write power spectrum: {}", e),
+        }
+    }
+
+    // Export the gameplay layer for strategy games
+    if let Some(ref prefix) = args.gameplay {
+        println!("Exporting gameplay layer...");
+        match gameplay::generate_gameplay(&world_data).export(prefix) {
+            Ok(paths) => {
+                for path in paths {
+                    println!("  {}", path);
+                }
+            }
+            Err(e) => eprintln!("Failed to export gameplay layer: {}", e),
+        }
+    }
+
+    // Export vector features for GIS tools and web maps
+    if let Some(ref prefix) = args.geojson {
+        println!("Exporting GeoJSON layers...");
+        match vector_export::export_geojson(&world_data, prefix) {
+            Ok(paths) => {
+                for path in paths {
+                    println!("  {}", path);
+                }
+            }
+            Err(e) => eprintln!("Failed to export GeoJSON: {}", e),
+        }
+    }
+
+    // Slices and cross-sections through the z-levels
+    for &z in &args.zslice {
+        if !(zlevel::MIN_Z..=zlevel::MAX_Z).contains(&z) {
+            eprintln!("Invalid --zslice {}: z-levels run from {} to {}", z, zlevel::MIN_Z, zlevel::MAX_Z);
+            continue;
+        }
+        println!("Exporting z-level {} slice...", z);
+        match section_export::export_slice(&world_data, z, &args.section_prefix) {
+            Ok(paths) => {
+                for path in paths {
+                    println!("  {}", path);
+                }
+            }
+            Err(e) => eprintln!("Failed to export slice: {}", e),
+        }
+    }
+    for spec in &args.section {
+        let line = match section_export::SectionLine::parse(spec) {
+            Ok(line) if line.tiles(&world_data).is_some() => line,
+            Ok(_) => {
+                eprintln!("Invalid --section '{}': outside the world", spec);
+                continue;
+            }
+            Err(e) => {
+                eprintln!("Invalid --section: {}", e);
+                continue;
+            }
+        };
+        println!("Exporting cross-section along {}...", line.label());
+        match section_export::export_section(&world_data, line, &args.section_prefix) {
+            Ok(paths) => {
+                for path in paths {
+                    println!("  {}", path);
+                }
+            }
+            Err(e) => eprintln!("Failed to export cross-section: {}", e),
+        }
+    }
+
+    // Export the world as each faction knows it
+    if let Some(ref prefix) = args.known {
+        println!("Exporting known-world maps...");
+        match world_data.history.as_ref() {
+            Some(history) => {
+                let factions: Vec<_> = match args.known_faction.as_deref() {
+                    Some(spec) => known_world::find_faction(history, spec).into_iter().collect(),
+                    None => history.factions.active().collect(),
+                };
+                if factions.is_empty() {
+                    eprintln!("No matching faction for --known-faction");
+                }
+                for faction in factions {
+                    let known = known_world::known_world(&world_data, history, faction);
+                    let path = format!("{}_{}", prefix, known_world::slug(&faction.name));
+                    match known.export(&world_data, &theme, faction.color, &path) {
+                        Ok(paths) => {
+                            for path in paths {
+                                println!("  {}", path);
+                            }
+                        }
+                        Err(e) => eprintln!("Failed to export known world for {}: {}", faction.name, e),
+                    }
+                }
+            }
+            None => eprintln!("Known-world maps need a world history"),
+        }
+    }
+}
+
+/// Export local maps (and optionally their debug dump) around a world tile
+fn run_local(args: &LocalArgs) {
+    use multiscale::{export_debug_local_maps, export_local_area, ExportOptions};
+
+    if args.out.is_none() && args.debug.is_none() {
+        eprintln!("Nothing to export: pass --out and/or --debug");
+        return;
+    }
+    let Some(world_data) = obtain_world(&args.world) else {
+        return;
+    };
+    let center_x = args.x.unwrap_or(world_data.width / 2);
+    let center_y = args.y.unwrap_or(world_data.height / 2);
+
+    if let Some(ref export_path) = args.out {
         println!("Exporting local maps...");
         println!("  Center: ({}, {})", center_x, center_y);
-        println!("  Radius: {} chunks", radius);
-        println!("  Scale: {}x", args.export_local_scale);
+        println!("  Radius: {} chunks", args.radius);
+        println!("  Scale: {}x", args.scale);
 
         let options = ExportOptions {
             z_level: None,
             auto_surface: true,
             show_features: true,
-            scale: args.export_local_scale.clamp(1, 4),
-            show_chunk_grid: args.export_local_grid,
+            scale: args.scale.clamp(1, 4),
+            show_chunk_grid: args.grid,
+            budget: resource_budget(&args.world.generation),
         };
 
-        match export_local_area(&world_data, center_x, center_y, radius, export_path, &options) {
+        match export_local_area(&world_data, center_x, center_y, args.radius, export_path, &options) {
             Ok((width, height)) => {
                 println!("Exported local maps to: {}", export_path);
                 println!("  Image size: {}x{} pixels", width, height);
@@ -358,13 +1432,7 @@ fn main() {
         }
     }
 
-    // Export debug info for local maps if requested
-    if let Some(ref debug_path) = args.debug_local {
-        use multiscale::export_debug_local_maps;
-
-        let center_x = args.debug_local_x.unwrap_or(args.width / 2);
-        let center_y = args.debug_local_y.unwrap_or(args.height / 2);
-
+    if let Some(ref debug_path) = args.debug {
         println!("Exporting debug local map info...");
         println!("  Center: ({}, {})", center_x, center_y);
 
@@ -376,17 +1444,184 @@ fn main() {
                 eprintln!("Failed to export debug info: {}", e);
             }
         }
+    }
+}
+
+/// Generate a star and its planets and export the system as JSON
+fn run_system_mode(args: &SystemArgs) {
+    let seed = args.planet.seed.unwrap_or_else(rand::random);
+    let config = system::SystemConfig {
+        planet_count: args.planets,
+        world_width: args.planet.width,
+        world_height: args.planet.height,
+        generate_worlds: true,
+    };
+    println!("Generating solar system...");
+    let solar_system = system::generate_system(seed, &config);
+    println!("Star: {} ({:?}, {:.2} L☉)", solar_system.star.name, solar_system.star.spectral_class, solar_system.star.luminosity);
+    for planet in &solar_system.planets {
+        let p = &planet.params;
+        println!("  {}: {:?}, {:.2} AU, {:.1}°C, {:.2} R⊕", p.name, p.kind, p.orbit_au, p.surface_temp, p.radius);
+    }
+    match solar_system.export_json(&args.out) {
+        Ok(()) => println!("Exported system to: {}", args.out),
+        Err(e) => eprintln!("Failed to export system: {}", e),
+    }
+}
 
-        // Exit without launching explorer when debug exporting
+/// Run an erosion parameter sweep from the CLI and write its CSV and contact sheet
+fn run_sweep_mode(args: &SweepArgs) {
+    let seed = args.planet.seed.unwrap_or_else(rand::random);
+    let Some(base_params) = load_erosion_preset(args.erosion_preset.as_deref()) else {
         return;
+    };
+    let x_axis = match erosion::SweepAxis::parse(&args.x_axis) {
+        Ok(axis) => axis,
+        Err(e) => {
+            eprintln!("Invalid --x: {}", e);
+            return;
+        }
+    };
+    let y_axis = match args.y_axis.as_deref().map(erosion::SweepAxis::parse) {
+        Some(Ok(axis)) => Some(axis),
+        Some(Err(e)) => {
+            eprintln!("Invalid --y: {}", e);
+            return;
+        }
+        None => None,
+    };
+
+    let config = erosion::SweepConfig {
+        width: args.planet.width,
+        height: args.planet.height,
+        seed,
+        num_plates: args.planet.plates,
+        base_params,
+        x_axis,
+        y_axis,
+    };
+
+    println!("Running parameter sweep...");
+    let result = erosion::run_sweep(&config);
+
+    let csv_path = format!("{}.csv", args.out);
+    let png_path = format!("{}.png", args.out);
+    match result.write_csv(&csv_path) {
+        Ok(()) => println!("Sweep metrics saved to: {}", csv_path),
+        Err(e) => eprintln!("Failed to write sweep CSV: {}", e),
+    }
+    match result.export_contact_sheet(&png_path) {
+        Ok(()) => println!("Sweep contact sheet saved to: {}", png_path),
+        Err(e) => eprintln!("Failed to write contact sheet: {}", e),
     }
 
-    // Export local maps exits early too
-    if args.export_local.is_some() {
+    if let Some(best) = result.best_cell() {
+        println!(
+            "Best cell ({}, {}): realism score {:.1}/100",
+            best.column, best.row, best.realism_score
+        );
+    }
+}
+
+/// Autotune erosion parameters from the CLI and save the best set as a preset
+fn run_autotune_mode(args: &AutotuneArgs) {
+    let seed = args.planet.seed.unwrap_or_else(rand::random);
+    let Some(base_params) = load_erosion_preset(args.erosion_preset.as_deref()) else {
         return;
+    };
+    let objective = match args.target.as_deref().map(erosion::TuneTargets::parse) {
+        Some(Ok(targets)) => erosion::TuneObjective::MatchTargets(targets),
+        Some(Err(e)) => {
+            eprintln!("Invalid --target: {}", e);
+            return;
+        }
+        None => erosion::TuneObjective::MaximizeRealism,
+    };
+
+    let config = erosion::AutotuneConfig {
+        width: args.planet.width,
+        height: args.planet.height,
+        seed,
+        num_plates: args.planet.plates,
+        base_params,
+        bounds: erosion::autotune::default_bounds(),
+        objective,
+        evaluations: args.evaluations,
+        search_seed: seed,
+    };
+
+    println!("Autotuning erosion parameters ({} evaluations)...", args.evaluations);
+    let result = erosion::autotune(&config);
+
+    println!("Best fitness: {:.3}", result.best_fitness);
+    println!("Best realism score: {:.1}/100", result.best_metrics.realism_score());
+    match result.best_params.save_preset(&args.out) {
+        Ok(()) => println!("Erosion preset saved to: {}", args.out),
+        Err(e) => eprintln!("Failed to save erosion preset: {}", e),
     }
+}
 
-    if let Err(e) = explorer::run_explorer(world_data) {
-        eprintln!("Explorer error: {}", e);
+/// Screen a seed range from the CLI and write the ranking and thumbnails
+fn run_mine_seeds_mode(args: &MineSeedsArgs) {
+    let range = &args.range;
+    let (start, end) = match range.split_once("..").map(|(a, b)| (a.parse::<u64>(), b.parse::<u64>())) {
+        Some((Ok(start), Ok(end))) if end > start => (start, end),
+        _ => {
+            eprintln!("Invalid seed range: expected START..END, got '{}'", range);
+            return;
+        }
+    };
+    let (width, height) = match args.resolution.split_once('x').map(|(w, h)| (w.parse::<usize>(), h.parse::<usize>())) {
+        Some((Ok(w), Ok(h))) if w > 0 && h > 0 => (w, h),
+        _ => {
+            eprintln!("Invalid --resolution: expected WIDTHxHEIGHT, got '{}'", args.resolution);
+            return;
+        }
+    };
+    let criteria = match args.criteria.as_deref().map(seed_mining::SeedCriteria::parse) {
+        Some(Ok(criteria)) => criteria,
+        Some(Err(e)) => {
+            eprintln!("Invalid --criteria: {}", e);
+            return;
+        }
+        None => seed_mining::SeedCriteria::default(),
+    };
+
+    let config = seed_mining::MiningConfig {
+        start,
+        count: end - start,
+        width,
+        height,
+        num_plates: args.plates,
+        criteria,
+        keep: args.top,
+    };
+
+    println!("Mining seeds {}..{} at {}x{}...", start, end, width, height);
+    let result = seed_mining::mine_seeds(&config);
+
+    println!("Best of {} seeds:", result.screened);
+    for (rank, c) in result.candidates.iter().enumerate() {
+        println!(
+            "  {}. seed {} - score {:.3} (land {:.1}%, {} continents, inland sea {:.2}%, realism {:.1})",
+            rank + 1,
+            c.seed,
+            c.score,
+            c.stats.land_fraction * 100.0,
+            c.stats.continents,
+            c.stats.inland_sea * 100.0,
+            c.stats.realism,
+        );
+    }
+
+    let csv_path = format!("{}.csv", args.out);
+    let png_path = format!("{}.png", args.out);
+    match result.write_csv(&csv_path) {
+        Ok(()) => println!("Seed ranking saved to: {}", csv_path),
+        Err(e) => eprintln!("Failed to write seed ranking: {}", e),
+    }
+    match result.export_thumbnails(&png_path) {
+        Ok(()) => println!("Seed thumbnails saved to: {}", png_path),
+        Err(e) => eprintln!("Failed to write thumbnails: {}", e),
     }
 }
@@ -0,0 +1,525 @@
+//! Mass wasting: landslides and avalanches
+//!
+//! A slope holds while the drop to its lowest neighbour stays within what its
+//! ground can bear. Stability falls on wet ground and on seismically active
+//! crust and rises under forest, whose roots bind the soil. Slopes beyond it
+//! fail: material slides to the neighbour below, pass after pass, so slides
+//! cascade down the mountainside as an erosion process.
+//!
+//! What stays steep after the slides is a hazard, to the slope itself and to
+//! everything along the runout below it. Cold slopes carry snow that
+//! avalanches at gentler drops. In the history, settlements and roads beneath
+//! the hazard are struck: some by slides that earthquakes shook loose, some by
+//! storms, more often where settlers cleared the forest that held the slope.
+
+#[cfg(feature = "history")]
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "history")]
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::ExtendedBiome;
+#[cfg(feature = "history")]
+use crate::history::types::{AbandonmentReason, SettlementState, Year};
+#[cfg(feature = "history")]
+use crate::history::{EventType, HistoricalEvent, WorldHistory};
+use crate::seismic::SeismicMap;
+use crate::tilemap::Tilemap;
+#[cfg(feature = "history")]
+use crate::zlevel::{Tilemap3D, ZTile, MAX_Z};
+
+/// Parameters for slope failure and its hazard
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MassWastingConfig {
+    /// Height drop (m) to the lowest neighbour that dry, bare, quiet ground holds
+    pub stable_drop: f32,
+    /// Share of stability lost on saturated ground
+    pub moisture_weakening: f32,
+    /// Extra stability under forest
+    pub forest_anchoring: f32,
+    /// Share of stability lost at full seismic hazard
+    pub seismic_weakening: f32,
+    /// Share of a slope's excess that slides in one failure
+    pub failure_fraction: f32,
+    /// Failure passes; each lets slides cascade one tile further
+    pub passes: usize,
+    /// Furthest (tiles) a slide or avalanche runs out below its source
+    pub runout_length: usize,
+    /// Temperature (°C) below which slopes carry snow
+    pub snow_temperature: f32,
+    /// Height drop (m) that a bare snow slope holds before it avalanches
+    pub avalanche_drop: f32,
+    /// Chance over a settlement's life that a slide at full hazard strikes it
+    pub strike_chance: f32,
+    /// Hazard at which a landslide buries a settlement rather than damaging it
+    pub ruin_hazard: f32,
+    /// Hazard along a road at which slides can cut it
+    pub road_hazard: f32,
+}
+
+impl Default for MassWastingConfig {
+    fn default() -> Self {
+        Self {
+            stable_drop: 600.0,
+            moisture_weakening: 0.5,
+            forest_anchoring: 0.3,
+            seismic_weakening: 0.4,
+            failure_fraction: 0.8,
+            passes: 4,
+            runout_length: 6,
+            snow_temperature: -2.0,
+            avalanche_drop: 250.0,
+            strike_chance: 0.6,
+            ruin_hazard: 0.75,
+            road_hazard: 0.6,
+        }
+    }
+}
+
+/// Slide scars, debris and the hazard they leave
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MassWastingMap {
+    /// Ground (m) stripped from each tile by slides
+    pub scar: Tilemap<f32>,
+    /// Debris (m) piled on each tile by slides from above
+    pub debris: Tilemap<f32>,
+    /// Landslide hazard (0-1) on unstable slopes and the runout below them
+    pub landslide: Tilemap<f32>,
+    /// Avalanche hazard (0-1) on snowy slopes and the runout below them
+    pub avalanche: Tilemap<f32>,
+    /// Stability multiplier from forest cover (1 where bare)
+    pub anchoring: Tilemap<f32>,
+    /// Material (m, summed over tiles) moved downslope
+    pub moved: f64,
+}
+
+impl MassWastingMap {
+    /// Landslide hazard once the forest around a tile is cleared
+    pub fn cleared_hazard(&self, x: usize, y: usize) -> f32 {
+        (self.landslide.get(x, y) * self.anchoring.get(x, y)).min(1.0)
+    }
+
+    /// Short description for tile info panels
+    pub fn describe(&self, x: usize, y: usize) -> Option<String> {
+        let mut parts = Vec::new();
+        let (landslide, avalanche) = (*self.landslide.get(x, y), *self.avalanche.get(x, y));
+        if landslide >= 0.1 {
+            parts.push(format!("Landslide hazard {:.0}%", landslide * 100.0));
+        }
+        if avalanche >= 0.1 {
+            parts.push(format!("Avalanche hazard {:.0}%", avalanche * 100.0));
+        }
+        if *self.scar.get(x, y) >= 1.0 {
+            parts.push(format!("slide scar {:.0} m", self.scar.get(x, y)));
+        } else if *self.debris.get(x, y) >= 1.0 {
+            parts.push(format!("slide debris {:.0} m", self.debris.get(x, y)));
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+fn is_forest(biome: ExtendedBiome) -> bool {
+    matches!(
+        biome,
+        ExtendedBiome::BorealForest
+            | ExtendedBiome::TemperateForest
+            | ExtendedBiome::TemperateRainforest
+            | ExtendedBiome::TropicalForest
+            | ExtendedBiome::TropicalRainforest
+    )
+}
+
+/// Lowest neighbour of a tile and the drop to it
+fn steepest_drop(heightmap: &Tilemap<f32>, x: usize, y: usize) -> Option<((usize, usize), f32)> {
+    heightmap
+        .neighbors(x, y)
+        .into_iter()
+        .min_by(|&a, &b| heightmap.get(a.0, a.1).total_cmp(heightmap.get(b.0, b.1)))
+        .map(|(nx, ny)| ((nx, ny), heightmap.get(x, y) - heightmap.get(nx, ny)))
+}
+
+/// Fail the slopes beyond their stability, moving material downslope, and map
+/// the landslide and avalanche hazard left behind
+pub fn apply_mass_wasting(
+    heightmap: &mut Tilemap<f32>,
+    moisture: &Tilemap<f32>,
+    temperature: &Tilemap<f32>,
+    biomes: &Tilemap<ExtendedBiome>,
+    seismic: Option<&SeismicMap>,
+    config: &MassWastingConfig,
+) -> MassWastingMap {
+    let (width, height) = (heightmap.width, heightmap.height);
+    let mut anchoring = Tilemap::new_with(width, height, 1.0f32);
+    let mut stability = Tilemap::new_with(width, height, config.stable_drop);
+    for y in 0..height {
+        for x in 0..width {
+            let anchor = if is_forest(*biomes.get(x, y)) { 1.0 + config.forest_anchoring } else { 1.0 };
+            let wet = 1.0 - config.moisture_weakening * moisture.get(x, y).clamp(0.0, 1.0);
+            let shaken = 1.0 - config.seismic_weakening * seismic.map_or(0.0, |s| *s.hazard.get(x, y));
+            anchoring.set(x, y, anchor);
+            stability.set(x, y, config.stable_drop * wet * shaken * anchor);
+        }
+    }
+
+    // Slides cascade: what lands below may overload the next slope down
+    let mut scar = Tilemap::new_with(width, height, 0.0f32);
+    let mut debris = Tilemap::new_with(width, height, 0.0f32);
+    let mut moved = 0.0f64;
+    for _ in 0..config.passes {
+        let mut failed = false;
+        for y in 0..height {
+            for x in 0..width {
+                if *heightmap.get(x, y) <= 0.0 {
+                    continue;
+                }
+                let Some(((lx, ly), drop)) = steepest_drop(heightmap, x, y) else {
+                    continue;
+                };
+                let excess = drop - stability.get(x, y);
+                if excess <= 0.0 {
+                    continue;
+                }
+                // Moving half the excess levels the pair; a failure moves part of that
+                let depth = excess / 2.0 * config.failure_fraction;
+                *heightmap.get_mut(x, y) -= depth;
+                *heightmap.get_mut(lx, ly) += depth;
+                *scar.get_mut(x, y) += depth;
+                *debris.get_mut(lx, ly) += depth;
+                moved += depth as f64;
+                failed = true;
+            }
+        }
+        if !failed {
+            break;
+        }
+    }
+
+    // Hazard rises as a slope nears its stability, and runs out downhill
+    let mut landslide = Tilemap::new_with(width, height, 0.0f32);
+    let mut avalanche = Tilemap::new_with(width, height, 0.0f32);
+    for y in 0..height {
+        for x in 0..width {
+            if *heightmap.get(x, y) <= 0.0 {
+                continue;
+            }
+            let Some((_, drop)) = steepest_drop(heightmap, x, y) else {
+                continue;
+            };
+            let slide = ((drop / stability.get(x, y) - 0.5) * 2.0).clamp(0.0, 1.0);
+            runout(heightmap, &mut landslide, (x, y), slide, config.runout_length);
+            if *temperature.get(x, y) < config.snow_temperature {
+                let snow = ((drop / (config.avalanche_drop * anchoring.get(x, y)) - 0.5) * 2.0).clamp(0.0, 1.0);
+                runout(heightmap, &mut avalanche, (x, y), snow, config.runout_length);
+            }
+        }
+    }
+
+    MassWastingMap { scar, debris, landslide, avalanche, anchoring, moved }
+}
+
+/// Spread a source's hazard down the steepest path below it, fading with distance
+fn runout(heightmap: &Tilemap<f32>, hazard: &mut Tilemap<f32>, source: (usize, usize), value: f32, length: usize) {
+    if value <= 0.0 {
+        return;
+    }
+    let (mut x, mut y) = source;
+    for step in 0..=length {
+        let fading = value * (1.0 - step as f32 / (length + 1) as f32);
+        if fading > *hazard.get(x, y) {
+            hazard.set(x, y, fading);
+        }
+        match steepest_drop(heightmap, x, y) {
+            Some((next, drop)) if drop > 0.0 => (x, y) = next,
+            _ => break,
+        }
+    }
+}
+
+/// Bury the buildings and roads on a tile under debris; returns tiles buried
+#[cfg(feature = "history")]
+fn bury(zlevels: &mut Tilemap3D<ZTile>, surface_z: &Tilemap<i32>, x: usize, y: usize) -> usize {
+    let surface = *surface_z.get(x, y);
+    let mut buried = 0;
+    for z in surface..=(surface + 3).min(MAX_Z) {
+        if matches!(
+            zlevels.get(x, y, z),
+            ZTile::StoneWall
+                | ZTile::BrickWall
+                | ZTile::WoodWall
+                | ZTile::Door
+                | ZTile::Window
+                | ZTile::Column
+                | ZTile::StoneFloor
+                | ZTile::WoodFloor
+                | ZTile::CobblestoneFloor
+                | ZTile::DirtFloor
+                | ZTile::DirtRoad
+                | ZTile::StoneRoad
+        ) {
+            zlevels.set(x, y, z, ZTile::Rubble);
+            buried += 1;
+        }
+    }
+    buried
+}
+
+/// Strike the settlements and roads beneath unstable slopes. Earthquakes shake
+/// slides loose on the spot; otherwise a slide strikes at some point in a
+/// settlement's life with a chance that grows with the hazard, and settlers
+/// who cleared a forested slope face it without the trees. The worst slides
+/// bury a settlement, leaving rubble in the z-levels. Returns how many events
+/// were added.
+#[cfg(feature = "history")]
+pub fn apply_mass_wasting_history(
+    history: &mut WorldHistory,
+    map: &MassWastingMap,
+    seismic: Option<&SeismicMap>,
+    zlevels: &mut Tilemap3D<ZTile>,
+    surface_z: &Tilemap<i32>,
+    config: &MassWastingConfig,
+    seed: u64,
+) -> usize {
+    let (start, end) = match (history.timeline.eras.first(), history.timeline.eras.last()) {
+        (Some(first), Some(last)) => (first.start, last.end),
+        _ => return 0,
+    };
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x51DE));
+    let width = map.landslide.width;
+    let mut added = 0;
+
+    let mut settlement_ids: Vec<_> = history.territories.settlements.keys().copied().collect();
+    settlement_ids.sort_by_key(|id| id.0);
+    for id in settlement_ids {
+        let settlement = &history.territories.settlements[&id];
+        let (x, y) = (settlement.x, settlement.y);
+        let (founded, last) = (settlement.founded.max(start), settlement.abandoned.unwrap_or(end));
+        if last <= founded {
+            continue;
+        }
+        let landslide = map.cleared_hazard(x, y);
+        let avalanche = *map.avalanche.get(x, y);
+        let (kind, hazard) =
+            if avalanche > landslide { (EventType::Avalanche, avalanche) } else { (EventType::Landslide, landslide) };
+        if hazard <= 0.0 {
+            continue;
+        }
+
+        let quake = seismic.and_then(|s| {
+            s.earthquakes.iter().find(|q| {
+                let (dx, dy) = (q.epicenter.0.abs_diff(x), q.epicenter.1.abs_diff(y));
+                let distance = (dx.min(width - dx) as f32).hypot(dy as f32);
+                q.year > founded && q.year <= last && distance <= q.damage_radius()
+            })
+        });
+        let year = match quake {
+            Some(q) if hazard >= 0.2 => q.year,
+            _ if rng.gen::<f32>() < hazard * config.strike_chance => Year(rng.gen_range(founded.0 + 1..=last.0)),
+            _ => continue,
+        };
+
+        let name = settlement.name.clone();
+        let peak = settlement.peak_population;
+        let faction = settlement.current_faction.or(Some(settlement.original_faction));
+        let ruined = kind == EventType::Landslide && hazard >= config.ruin_hazard;
+        if ruined {
+            let settlement = history.territories.settlements.get_mut(&id).expect("id was collected above");
+            settlement.state = SettlementState::Ruined;
+            settlement.abandoned = Some(year);
+            settlement.abandonment_reason = Some(AbandonmentReason::NaturalDisaster);
+            if let Some(occupation) = settlement.occupations.last_mut().filter(|o| o.2.is_none()) {
+                occupation.2 = Some(year);
+            }
+            bury(zlevels, surface_z, x, y);
+        }
+
+        let cause = match quake {
+            Some(q) if q.year == year => format!("shaken loose by a magnitude {:.1} earthquake", q.magnitude),
+            _ if kind == EventType::Avalanche => "after heavy snows".to_string(),
+            _ if *map.anchoring.get(x, y) > 1.0 => "from the slopes its people had cleared of forest".to_string(),
+            _ => "after days of rain".to_string(),
+        };
+        let what = if kind == EventType::Avalanche { "An avalanche" } else { "A landslide" };
+        let description = if ruined {
+            format!("{} came down on {} {}, burying it", what, name, cause)
+        } else {
+            format!("{} struck {} {}", what, name, cause)
+        };
+
+        let event_id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id: event_id,
+            year,
+            event_type: kind,
+            faction,
+            other_faction: None,
+            location: Some((x, y)),
+            settlement: Some(id),
+            name: format!("The {} of {}", kind.name(), name),
+            description,
+            casualties: if ruined { peak / 3 } else { (peak as f32 * hazard * 0.05) as u32 },
+            has_evidence: kind.leaves_evidence(),
+        });
+        added += 1;
+    }
+
+    // Roads across a runout are cut where a slide comes down on them
+    let mut route_ids: Vec<_> = history.trade.routes.keys().copied().collect();
+    route_ids.sort_by_key(|id| id.0);
+    for id in route_ids {
+        let route = &history.trade.routes[&id];
+        if !route.active {
+            continue;
+        }
+        let worst = route.path.iter().copied().max_by(|a, b| {
+            let hazard = |&(x, y): &(usize, usize)| map.landslide.get(x, y).max(*map.avalanche.get(x, y));
+            hazard(a).total_cmp(&hazard(b))
+        });
+        let Some((x, y)) = worst else {
+            continue;
+        };
+        let hazard = map.landslide.get(x, y).max(*map.avalanche.get(x, y));
+        if hazard < config.road_hazard || rng.gen::<f32>() >= hazard || route.established >= end {
+            continue;
+        }
+        let year = Year(rng.gen_range(route.established.0.max(start.0) + 1..=end.0));
+        let route = history.trade.routes.get_mut(&id).expect("id was collected above");
+        route.active = false;
+        route.abandoned = Some(year);
+        bury(zlevels, surface_z, x, y);
+
+        let event_id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id: event_id,
+            year,
+            event_type: EventType::Landslide,
+            faction: history.faction_at(x, y).map(|f| f.id),
+            other_faction: None,
+            location: Some((x, y)),
+            settlement: None,
+            name: "The Road Swallowed".to_string(),
+            description: "A slide carried away a stretch of road, and the traders never cleared it".to_string(),
+            casualties: 0,
+            has_evidence: EventType::Landslide.leaves_evidence(),
+        });
+        added += 1;
+    }
+
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "history")]
+    use crate::history::territories::{Settlement, TerritoryRegistry};
+    #[cfg(feature = "history")]
+    use crate::history::timeline::Era;
+    #[cfg(feature = "history")]
+    use crate::history::types::*;
+
+    /// A 32x16 valley floor with a cliff rising to the east of x = 16
+    fn cliff(top: f32) -> Tilemap<f32> {
+        let mut heightmap = Tilemap::new_with(32, 16, 100.0f32);
+        for y in 0..16 {
+            for x in 16..28 {
+                heightmap.set(x, y, top);
+            }
+        }
+        heightmap
+    }
+
+    #[test]
+    fn test_wet_bare_slopes_fail_before_forested_ones() {
+        let config = MassWastingConfig::default();
+        let temperature = Tilemap::new_with(32, 16, 15.0f32);
+        let run = |moisture: f32, biome: ExtendedBiome| {
+            let mut heightmap = cliff(700.0);
+            let before: f64 = heightmap.iter().map(|(_, _, &h)| h as f64).sum();
+            let map = apply_mass_wasting(
+                &mut heightmap,
+                &Tilemap::new_with(32, 16, moisture),
+                &temperature,
+                &Tilemap::new_with(32, 16, biome),
+                None,
+                &config,
+            );
+            let after: f64 = heightmap.iter().map(|(_, _, &h)| h as f64).sum();
+            assert!((before - after).abs() < 1e-2 * before.abs().max(1.0), "slides only move material");
+            (map, heightmap)
+        };
+
+        // A 600 m cliff holds when dry but fails when soaked...
+        let (dry, _) = run(0.0, ExtendedBiome::TemperateGrassland);
+        assert_eq!(dry.moved, 0.0);
+        let (wet, heightmap) = run(1.0, ExtendedBiome::TemperateGrassland);
+        assert!(wet.moved > 0.0);
+        assert!(*wet.scar.get(16, 8) > 0.0 && *wet.debris.get(15, 8) > 0.0);
+        assert!(*heightmap.get(16, 8) < 700.0);
+        // ...unless forest roots hold it
+        let (forest, _) = run(0.5, ExtendedBiome::TemperateForest);
+        let (bare, _) = run(0.5, ExtendedBiome::TemperateGrassland);
+        assert!(forest.moved < bare.moved);
+        // The hazard runs out onto the valley floor below the cliff
+        assert!(*bare.landslide.get(14, 8) > 0.0);
+        assert!(*bare.landslide.get(14, 8) < *bare.landslide.get(16, 8));
+        assert_eq!(*bare.landslide.get(4, 8), 0.0);
+        assert!(bare.cleared_hazard(16, 8) >= *bare.landslide.get(16, 8));
+    }
+
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_slides_strike_settlements_beneath_them() {
+        let mut heightmap = cliff(1400.0);
+        let moisture = Tilemap::new_with(32, 16, 0.8f32);
+        let mut temperature = Tilemap::new_with(32, 16, 10.0f32);
+        for x in 0..8 {
+            for y in 0..16 {
+                temperature.set(x, y, -10.0);
+            }
+        }
+        let biomes = Tilemap::new_with(32, 16, ExtendedBiome::TemperateGrassland);
+        let config = MassWastingConfig { strike_chance: 1.0, ..Default::default() };
+        let map = apply_mass_wasting(&mut heightmap, &moisture, &temperature, &biomes, None, &config);
+        assert_eq!(*map.avalanche.get(20, 8), 0.0, "warm slopes carry no snow");
+
+        let mut history = WorldHistory::empty();
+        history.timeline.eras.push(Era {
+            name: "Age of Rockfall".to_string(),
+            era_type: EraType::GoldenAge,
+            start: Year(-1000),
+            end: Year(0),
+            events: Vec::new(),
+        });
+        history.territories = TerritoryRegistry::new(32, 16);
+        for (id, x) in [(0u32, 16usize), (1, 4)] {
+            history.territories.add_settlement(Settlement {
+                id: SettlementId(id),
+                name: format!("Town{}", id),
+                settlement_type: SettlementType::Village,
+                original_faction: FactionId(0),
+                current_faction: Some(FactionId(0)),
+                x,
+                y: 8,
+                size: 1,
+                state: SettlementState::Thriving,
+                founded: Year(-900),
+                abandoned: None,
+                abandonment_reason: None,
+                peak_population: 300,
+                architecture: ArchitectureStyle::Rustic,
+                occupations: vec![(FactionId(0), Year(-900), None)],
+            });
+        }
+        let (mut zlevels, surface_z) = crate::zlevel::generate_zlevels(&heightmap);
+        let surface = *surface_z.get(16, 8);
+        zlevels.set(16, 8, surface + 1, ZTile::WoodWall);
+
+        let added = apply_mass_wasting_history(&mut history, &map, None, &mut zlevels, &surface_z, &config, 5);
+        assert_eq!(added, 1);
+        let town = &history.territories.settlements[&SettlementId(0)];
+        assert_eq!(town.state, SettlementState::Ruined);
+        assert_eq!(town.abandonment_reason, Some(AbandonmentReason::NaturalDisaster));
+        assert_eq!(*zlevels.get(16, 8, surface + 1), ZTile::Rubble);
+        assert!(history.territories.settlements[&SettlementId(1)].is_active());
+        assert!(history.timeline.events_at(16, 8).iter().any(|e| e.event_type == EventType::Landslide));
+    }
+}
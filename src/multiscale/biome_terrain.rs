@@ -15,6 +15,9 @@ use super::geology::{GeologyParams, CornerHeights, interpolate_surface_z, calcul
 use super::coords::{world_noise_coord, feature_seed, should_place_feature, position_random_range, position_random};
 use super::LOCAL_SIZE;
 
+/// Share of tiles a species growing at full presence covers on the local map
+const GROUND_COVER_DENSITY: f32 = 0.08;
+
 // =============================================================================
 // FEATURE POOL SYSTEM (Phase 2b)
 // =============================================================================
@@ -425,6 +428,19 @@ pub fn get_biome_config(biome: ExtendedBiome) -> BiomeTerrainConfig {
             ..Default::default()
         },
 
+        ExtendedBiome::IceSheet | ExtendedBiome::PackIce => BiomeTerrainConfig {
+            surface_terrain: LocalTerrain::Ice,
+            surface_material: Material::Ice,
+            tree_density: 0.0,
+            bush_density: 0.0,
+            boulder_density: 0.01,
+            water_chance: 0.0,
+            terrain_variation: 1,
+            soil_type: SoilType::Permafrost,
+            stone_type: StoneType::Granite,
+            ..Default::default()
+        },
+
         ExtendedBiome::Tundra => BiomeTerrainConfig {
             surface_terrain: LocalTerrain::Snow,
             surface_material: Material::Snow,
@@ -1722,7 +1738,9 @@ pub fn generate_biome_surface(
             // Set underground tiles
             for z in chunk.z_min..local_z {
                 let depth = local_z - z;
-                let tile = if depth <= geology.soil_depth as i16 {
+                let tile = if geology.is_loess(depth) {
+                    LocalTile::soil(SoilType::Silt)
+                } else if depth <= geology.soil_depth {
                     LocalTile::soil(config.soil_type)
                 } else {
                     LocalTile::stone(config.stone_type)
@@ -2457,7 +2475,9 @@ pub fn generate_blended_biome_surface(
             // Set underground tiles
             for z in chunk.z_min..local_z {
                 let depth = local_z - z;
-                let tile = if depth <= geology.soil_depth as i16 {
+                let tile = if geology.is_loess(depth) {
+                    LocalTile::soil(SoilType::Silt)
+                } else if depth <= geology.soil_depth {
                     LocalTile::soil(primary_config.soil_type)
                 } else {
                     LocalTile::stone(primary_config.stone_type)
@@ -2470,8 +2490,8 @@ pub fn generate_blended_biome_surface(
 
 /// Add blended biome features considering adjacent biomes
 ///
-/// If a `feather_map` is provided along with world coordinates, feature densities
-/// will be scaled based on precomputed biome boundary data.
+/// If a `feather_map` is provided, tree and bush densities thin out toward
+/// boundaries with unlike biomes (see `BiomeFeatherMap::edge_thinning`).
 ///
 /// Uses position-based deterministic placement for seamless features across chunk boundaries.
 pub fn add_blended_biome_features(
@@ -2480,7 +2500,7 @@ pub fn add_blended_biome_features(
     primary_config: &BiomeTerrainConfig,
     _adjacent: &AdjacentBiomes,  // Kept for API compatibility
     _rng: &mut ChaCha8Rng,
-    _feather_map: Option<&BiomeFeatherMap>,  // Kept for API compatibility
+    feather_map: Option<&BiomeFeatherMap>,
     world_coords: Option<(usize, usize)>,
     world_seed: u64,
     corner_biomes: Option<&[[ExtendedBiome; 2]; 2]>,  // Added for radial blending
@@ -2513,7 +2533,7 @@ pub fn add_blended_biome_features(
             }
 
             // Get interpolated feature densities using radial blending
-            let (tree_density, bush_density, boulder_density) = if let Some(corners) = corner_biomes {
+            let (mut tree_density, mut bush_density, boulder_density) = if let Some(corners) = corner_biomes {
                 // Use position-based noise offset for variation
                 let noise_offset = position_random(pos_seed, 50) * 0.2 - 0.1;
                 let interp = get_interpolated_config(primary_config, corners, x, y, LOCAL_SIZE, noise_offset);
@@ -2522,6 +2542,14 @@ pub fn add_blended_biome_features(
                 (primary_config.tree_density, primary_config.bush_density, primary_config.boulder_density)
             };
 
+            // Thin vegetation approaching an unlike biome
+            if let Some(feather) = feather_map {
+                let (u, v) = (x as f32 / LOCAL_SIZE as f32, y as f32 / LOCAL_SIZE as f32);
+                let thinning = feather.edge_thinning(world_x, world_y, u, v);
+                tree_density *= thinning;
+                bush_density *= thinning;
+            }
+
             // Place features using position-based deterministic placement
             // This ensures the same position always generates the same feature
             // across chunk boundaries
@@ -2545,6 +2573,16 @@ pub fn add_blended_biome_features(
                 continue;
             }
 
+            // Wild plants from the world's flora, in proportion to how thickly they grow here
+            let plant = geology.ground_cover.iter().enumerate().find(|&(i, &(_, presence))| {
+                let plant_seed = feature_seed(world_seed.wrapping_add(4 + i as u64), abs_x, abs_y);
+                should_place_feature(plant_seed, presence * GROUND_COVER_DENSITY)
+            });
+            if let Some((_, &(species, _))) = plant {
+                chunk.get_mut(x, y, local_surface_z).feature = LocalFeature::Plant { species };
+                continue;
+            }
+
             // Special features (use position-based placement)
             if primary_config.special_feature_chance > 0.0 {
                 let special_seed = feature_seed(world_seed.wrapping_add(3), abs_x, abs_y);
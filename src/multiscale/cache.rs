@@ -7,6 +7,7 @@ use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::path::Path;
 
+use crate::budget::ResourceBudget;
 use crate::world::WorldData;
 use super::local::{LocalChunk, BoundaryConditions, ChunkEdge, EdgeDirection, generate_local_chunk_with_boundaries};
 use super::storage::ChunkStorage;
@@ -170,6 +171,11 @@ impl ChunkCache {
         }
     }
 
+    /// Create a new chunk cache holding as many chunks as fit `budget` (no persistence)
+    pub fn with_budget(budget: &ResourceBudget) -> Self {
+        Self::with_size(budget.local_cache_size())
+    }
+
     /// Create a new chunk cache with disk persistence enabled.
     ///
     /// Chunks will be saved to disk when generated and loaded from disk
@@ -253,6 +259,15 @@ impl ChunkCache {
         self.stats = CacheStats::default();
     }
 
+    /// Clear all caches, including chunks persisted to disk for this world
+    pub fn clear_persisted(&mut self) -> std::io::Result<()> {
+        self.clear();
+        match self.storage {
+            Some(ref storage) => storage.clear(),
+            None => Ok(()),
+        }
+    }
+
     /// Update statistics
     fn update_stats(&mut self) {
         self.stats.local_count = self.local.len();
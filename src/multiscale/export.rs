@@ -5,6 +5,7 @@
 use image::{ImageBuffer, Rgb, RgbImage};
 use std::path::Path;
 
+use crate::budget::ResourceBudget;
 use crate::world::WorldData;
 use super::cache::ChunkCache;
 use super::local::{LocalChunk, LocalTerrain, LocalFeature, Material};
@@ -23,6 +24,8 @@ pub struct ExportOptions {
     pub scale: u32,
     /// Whether to show grid lines between chunks
     pub show_chunk_grid: bool,
+    /// Memory the chunk cache may hold while exporting
+    pub budget: ResourceBudget,
 }
 
 impl Default for ExportOptions {
@@ -33,6 +36,7 @@ impl Default for ExportOptions {
             show_features: true,
             scale: 1,
             show_chunk_grid: false,
+            budget: ResourceBudget::default(),
         }
     }
 }
@@ -101,6 +105,7 @@ fn feature_color(feature: &LocalFeature) -> Option<Rgb<u8>> {
         LocalFeature::Stalactite | LocalFeature::Stalagmite => Some(Rgb([169, 169, 169])),
         LocalFeature::Crystal => Some(Rgb([138, 43, 226])),      // Blue violet
         LocalFeature::OreVein => Some(Rgb([255, 215, 0])),       // Gold
+        LocalFeature::Plant { .. } => Some(Rgb([107, 142, 35])), // Olive drab
         LocalFeature::StairsUp | LocalFeature::StairsDown => Some(Rgb([160, 160, 160])),
         LocalFeature::RampUp | LocalFeature::RampDown => Some(Rgb([140, 140, 140])),
         LocalFeature::Ladder => Some(Rgb([139, 90, 43])),        // Brown
@@ -154,6 +159,14 @@ fn get_tile_color(chunk: &LocalChunk, x: usize, y: usize, z: i16, options: &Expo
     color
 }
 
+/// Render a single chunk to its own image (for previews and viewers)
+pub fn render_chunk_image(chunk: &LocalChunk, options: &ExportOptions) -> RgbImage {
+    let size = LOCAL_SIZE as u32 * options.scale.max(1);
+    let mut img = ImageBuffer::new(size, size);
+    render_chunk_to_buffer(chunk, &mut img, 0, 0, options);
+    img
+}
+
 /// Render a single chunk to a section of an image buffer
 fn render_chunk_to_buffer(
     chunk: &LocalChunk,
@@ -250,7 +263,7 @@ pub fn export_local_region<P: AsRef<Path>>(
     }
 
     let mut img: RgbImage = ImageBuffer::new(img_width, img_height);
-    let mut cache = ChunkCache::new();
+    let mut cache = ChunkCache::with_budget(&options.budget);
 
     // Track progress
     let total_chunks = width * height;
@@ -4,6 +4,7 @@
 //! into detailed local geology with proper z-level structure.
 
 use crate::biomes::ExtendedBiome;
+use crate::cave_biomes::CaveBiome;
 use crate::zlevel::{self, CAVERN_1_MIN, CAVERN_2_MIN, CAVERN_3_MIN};
 use crate::world::WorldData;
 use crate::water_bodies::WaterBodyType;
@@ -29,16 +30,24 @@ pub struct GeologyParams {
     pub water_body_type: WaterBodyType,
     /// Soil depth in z-levels
     pub soil_depth: i16,
+    /// Z-levels of wind-blown loess on top of the soil (included in soil_depth)
+    pub loess_depth: i16,
     /// Primary stone type
     pub primary_stone: StoneType,
     /// Secondary stone type (for variety)
     pub secondary_stone: StoneType,
     /// Cavern presence flags [cavern1, cavern2, cavern3]
     pub has_caverns: [bool; 3],
+    /// Underground biome of each cavern layer under this tile
+    pub cave_biomes: [Option<CaveBiome>; 3],
     /// Whether magma sea is present at deep levels
     pub has_magma: bool,
     /// Aquifer depth (z-level where aquifer starts, or None)
     pub aquifer_z: Option<i16>,
+    /// Low-growing plant species on this tile and how thickly they grow
+    /// (indices into the world's `FloraCatalog`)
+    #[serde(default)]
+    pub ground_cover: Vec<(u16, f32)>,
 }
 
 impl GeologyParams {
@@ -52,6 +61,11 @@ impl GeologyParams {
         z < self.surface_z
     }
 
+    /// Check if a depth below the surface is in the loess cover
+    pub fn is_loess(&self, depth: i16) -> bool {
+        depth > 0 && depth <= self.loess_depth
+    }
+
     /// Check if a z-level is in the soil layer
     pub fn is_soil_layer(&self, z: i16) -> bool {
         z >= self.rock_surface_z() && z < self.surface_z
@@ -93,8 +107,9 @@ pub fn derive_geology(world: &WorldData, world_x: usize, world_y: usize) -> Geol
         .map(|wb| wb.body_type)
         .unwrap_or(WaterBodyType::None);
 
-    // Derive soil depth from biome and moisture
-    let soil_depth = derive_soil_depth(biome, moisture);
+    // Derive soil depth from biome and moisture, deepened by any loess cover
+    let loess_depth = world.loess.as_ref().map_or(0, |l| l.soil_levels(world_x, world_y));
+    let soil_depth = derive_soil_depth(biome, moisture) + loess_depth;
 
     // Derive stone types from stress and temperature
     let (primary_stone, secondary_stone) = derive_stone_types(stress, temperature, biome);
@@ -109,6 +124,8 @@ pub fn derive_geology(world: &WorldData, world_x: usize, world_y: usize) -> Geol
 
     // Check cavern presence from world zlevel data
     let has_caverns = check_cavern_presence(world, world_x, world_y, surface_z);
+    let cave_biomes = world.cave_biomes.as_ref()
+        .map_or([None; 3], |c| [0, 1, 2].map(|layer| c.layer_biome(world_x, world_y, layer)));
 
     // Magma is present in volcanic areas or very deep with high stress
     let has_magma = is_volcanic || stress > 0.5;
@@ -116,6 +133,8 @@ pub fn derive_geology(world: &WorldData, world_x: usize, world_y: usize) -> Geol
     // Aquifer presence based on moisture and surface type
     let aquifer_z = derive_aquifer_depth(surface_z, moisture, biome);
 
+    let ground_cover = world.flora.as_ref().map_or_else(Vec::new, |f| f.ground_cover(world_x, world_y));
+
     GeologyParams {
         surface_z,
         biome,
@@ -125,11 +144,14 @@ pub fn derive_geology(world: &WorldData, world_x: usize, world_y: usize) -> Geol
         is_volcanic,
         water_body_type,
         soil_depth,
+        loess_depth,
         primary_stone,
         secondary_stone,
         has_caverns,
+        cave_biomes,
         has_magma,
         aquifer_z,
+        ground_cover,
     }
 }
 
@@ -183,6 +205,8 @@ fn derive_soil_depth(biome: ExtendedBiome, moisture: f32) -> i16 {
         ExtendedBiome::Ocean |
         ExtendedBiome::CoastalWater |
         ExtendedBiome::Lagoon |
+        ExtendedBiome::PackIce |
+        ExtendedBiome::Polynya |
         ExtendedBiome::FrozenLake => 0,
 
         // Default moderate soil
@@ -267,6 +291,7 @@ fn derive_aquifer_depth(surface_z: i16, moisture: f32, biome: ExtendedBiome) ->
         ExtendedBiome::VolcanicWasteland |
         ExtendedBiome::ObsidianFields |
         ExtendedBiome::Ice |
+        ExtendedBiome::IceSheet |
         ExtendedBiome::AuroraWastes |
         ExtendedBiome::FrozenLake
     ) {
@@ -471,6 +496,8 @@ pub fn is_water_biome(biome: ExtendedBiome) -> bool {
         ExtendedBiome::OceanicTrench |
         ExtendedBiome::MidOceanRidge |
         ExtendedBiome::FrozenLake |
+        ExtendedBiome::PackIce |
+        ExtendedBiome::Polynya |
         ExtendedBiome::SeagrassMeadow |
         ExtendedBiome::KelpForest |
         ExtendedBiome::CoralReef |
@@ -1007,6 +1034,8 @@ pub fn biome_surface_material(biome: ExtendedBiome, is_water: bool) -> Material
     match biome {
         // Ice/frozen
         ExtendedBiome::Ice |
+        ExtendedBiome::IceSheet |
+        ExtendedBiome::PackIce |
         ExtendedBiome::FrozenLake |
         ExtendedBiome::SnowyPeaks => Material::Ice,
 
@@ -1075,11 +1104,14 @@ mod tests {
             is_volcanic: false,
             water_body_type: WaterBodyType::None,
             soil_depth: 4,
+            loess_depth: 0,
             primary_stone: StoneType::Limestone,
             secondary_stone: StoneType::Sandstone,
             has_caverns: [true, false, false],
+            cave_biomes: [None; 3],
             has_magma: false,
             aquifer_z: Some(0),
+            ground_cover: Vec::new(),
         };
 
         // Surface
@@ -8,7 +8,11 @@ use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use noise::{NoiseFn, Perlin};
 
+use crate::aeolian::{self, DuneType};
 use crate::biomes::ExtendedBiome;
+use crate::cave_biomes::CaveBiome;
+#[cfg(feature = "history")]
+use crate::history::EventType;
 use crate::world::WorldData;
 use crate::zlevel::{self, ZTile};
 
@@ -120,6 +124,8 @@ pub enum LocalFeature {
     Crystal,
     /// Ore vein
     OreVein,
+    /// Wild plant (index into the world's `FloraCatalog`)
+    Plant { species: u16 },
 }
 
 impl LocalFeature {
@@ -370,11 +376,14 @@ impl LocalChunk {
                 is_volcanic: false,
                 water_body_type: crate::water_bodies::WaterBodyType::None,
                 soil_depth: 4,
+                loess_depth: 0,
                 primary_stone: StoneType::Limestone,
                 secondary_stone: StoneType::Sandstone,
                 has_caverns: [false; 3],
+                cave_biomes: [None; 3],
                 has_magma: false,
                 aquifer_z: None,
+                ground_cover: Vec::new(),
             },
             generated: false,
         }
@@ -422,6 +431,12 @@ impl LocalChunk {
             + self.tiles.len() * std::mem::size_of::<LocalTile>()
     }
 
+    /// Memory of a chunk spanning every z-level, before it is allocated
+    pub fn estimated_size() -> usize {
+        let z_count = (zlevel::MAX_Z - zlevel::MIN_Z + 1) as usize;
+        std::mem::size_of::<Self>() + LOCAL_SIZE * LOCAL_SIZE * z_count * std::mem::size_of::<LocalTile>()
+    }
+
     /// Get the coordinate for a position in this chunk
     pub fn coord_at(&self, x: usize, y: usize, z: i16) -> LocalCoord {
         LocalCoord::new(self.world_x, self.world_y, x as u8, y as u8, z)
@@ -699,6 +714,7 @@ fn detect_all_structures(world: &WorldData, world_x: usize, world_y: usize) -> V
     let mut structures = Vec::new();
 
     // Check WorldHistory for structures at this location
+    #[cfg(feature = "history")]
     if let Some(ref history) = world.history {
         // Check for dungeons
         if let Some(_dungeon_id) = history.dungeons.dungeons_by_location.get(&(world_x, world_y)) {
@@ -807,7 +823,7 @@ fn detect_all_structures(world: &WorldData, world_x: usize, world_y: usize) -> V
                     found_waterfall = true;
                 }
             }
-            ZTile::CaveLake | ZTile::WaterCave => {
+            ZTile::CaveLake | ZTile::FrozenCaveLake | ZTile::WaterCave => {
                 if !found_cave_lake {
                     structures.push((StructureType::UndergroundLake, z16));
                     found_cave_lake = true;
@@ -1118,6 +1134,18 @@ pub fn generate_local_chunk_with_boundaries(
         world.seed,
     );
 
+    // Pile wind-blown sand into dunes; where the dunes overran a settlement or road,
+    // the sand goes on after the structures so it buries them
+    #[cfg(feature = "history")]
+    let buried = world.history.as_ref().is_some_and(|h| {
+        h.timeline.events_at(world_x, world_y).iter().any(|e| e.event_type == EventType::SandBurial)
+    });
+    #[cfg(not(feature = "history"))]
+    let buried = false;
+    if !buried {
+        raise_dunes(&mut chunk, world, world_x, world_y, false);
+    }
+
     // Apply boundary conditions from neighboring chunks
     // This overwrites edge tiles to ensure perfect matching with neighbors
     if boundaries.has_any() {
@@ -1315,6 +1343,10 @@ pub fn generate_local_chunk_with_boundaries(
         );
     }
 
+    if buried {
+        raise_dunes(&mut chunk, world, world_x, world_y, true);
+    }
+
     // Add cave features (stalactites, crystals, etc.)
     add_cave_features(&mut chunk, &geology, &mut rng);
 
@@ -1322,6 +1354,47 @@ pub fn generate_local_chunk_with_boundaries(
     chunk
 }
 
+/// Raise the world tile's dune pattern in sand on top of the surface.
+/// Normally only bare sand is built on; `bury` covers everything, structures included.
+fn raise_dunes(chunk: &mut LocalChunk, world: &WorldData, world_x: usize, world_y: usize, bury: bool) {
+    let Some(dunes) = world.dunes.as_ref() else { return };
+    let kind = *dunes.dune.get(world_x, world_y);
+    if kind == DuneType::None {
+        return;
+    }
+    let wind = dunes.wind(world_y);
+    let spacing = kind.local_spacing();
+
+    for y in 0..LOCAL_SIZE {
+        for x in 0..LOCAL_SIZE {
+            // World-continuous coordinates keep the pattern seamless across chunks
+            let u = (world_x * LOCAL_SIZE + x) as f64 / spacing;
+            let v = (world_y * LOCAL_SIZE + y) as f64 / spacing;
+            let mut levels = (aeolian::dune_relief(kind, wind, u, v, world.seed) * kind.local_height()).round() as i16;
+            if bury {
+                levels = levels.max(1);
+            }
+            if levels == 0 {
+                continue;
+            }
+
+            let Some(top) = (chunk.z_min..=chunk.z_max).rev().find(|&z| chunk.get(x, y, z).terrain != LocalTerrain::Air)
+            else {
+                continue;
+            };
+            let terrain = chunk.get(x, y, top).terrain;
+            if terrain.is_water() || (!bury && terrain != LocalTerrain::Sand) {
+                continue;
+            }
+            let crest = (top + levels).min(chunk.z_max);
+            for z in top..crest {
+                chunk.set(x, y, z, LocalTile::soil(SoilType::Sand));
+            }
+            chunk.set(x, y, crest, LocalTile::surface(Material::Sand));
+        }
+    }
+}
+
 /// Sample 3D cave noise for cave generation.
 /// Uses world-coordinate noise for seamless caves across chunk boundaries.
 fn sample_3d_cave(
@@ -1661,7 +1734,11 @@ fn generate_underground_tile(
 
     // Soil layers (near surface)
     if depth <= geology.soil_depth as i16 {
-        let soil_type = biome_soil_type(geology.biome, depth, geology.moisture);
+        let soil_type = if geology.is_loess(depth) {
+            SoilType::Silt
+        } else {
+            biome_soil_type(geology.biome, depth, geology.moisture)
+        };
         let mut tile = LocalTile::soil(soil_type);
         tile.temperature = geology.temperature - depth as f32 * 0.3;
         return tile;
@@ -1786,11 +1863,13 @@ fn add_cave_features(
                     continue;
                 }
 
-                // Determine cavern layer for feature types
+                // Cavern layer and its biome pick the feature palette
                 let cavern = geology.cavern_layer(z);
+                let cave_biome = cavern.and_then(|layer| geology.cave_biomes[layer]);
+                let (formation_chance, mushroom_chance, crystal_chance) = cave_feature_chances(cavern, cave_biome);
 
                 // Stalactites/stalagmites (all caves)
-                if rng.gen_bool(0.05) {
+                if rng.gen_bool(formation_chance) {
                     chunk.get_mut(x, y, z).feature = if rng.gen_bool(0.5) {
                         LocalFeature::Stalactite
                     } else {
@@ -1799,8 +1878,8 @@ fn add_cave_features(
                     continue;
                 }
 
-                // Mushrooms (cavern 1 and 2)
-                if matches!(cavern, Some(0) | Some(1)) && rng.gen_bool(0.03) {
+                // Mushrooms
+                if mushroom_chance > 0.0 && rng.gen_bool(mushroom_chance) {
                     chunk.get_mut(x, y, z).feature = if rng.gen_bool(0.2) {
                         LocalFeature::GiantMushroom
                     } else {
@@ -1809,8 +1888,8 @@ fn add_cave_features(
                     continue;
                 }
 
-                // Crystals (cavern 2 and 3)
-                if matches!(cavern, Some(1) | Some(2)) && rng.gen_bool(0.02) {
+                // Crystals
+                if crystal_chance > 0.0 && rng.gen_bool(crystal_chance) {
                     chunk.get_mut(x, y, z).feature = LocalFeature::Crystal;
                 }
             }
@@ -1818,6 +1897,23 @@ fn add_cave_features(
     }
 }
 
+/// Cave feature chances (formation, mushroom, crystal) for a cavern layer and its biome
+fn cave_feature_chances(cavern: Option<usize>, biome: Option<CaveBiome>) -> (f64, f64, f64) {
+    match biome {
+        Some(CaveBiome::MushroomCavern) => (0.02, 0.15, 0.0),
+        Some(CaveBiome::CrystalGallery) => (0.04, 0.0, 0.12),
+        Some(CaveBiome::SunlessSea) => (0.06, 0.01, 0.0),
+        Some(CaveBiome::Barren) => (0.06, 0.0, 0.0),
+        // Caves without a biome fall back to the layer: mushrooms in caverns 1
+        // and 2, crystals in caverns 2 and 3
+        None => (
+            0.05,
+            if matches!(cavern, Some(0) | Some(1)) { 0.03 } else { 0.0 },
+            if matches!(cavern, Some(1) | Some(2)) { 0.02 } else { 0.0 },
+        ),
+    }
+}
+
 /// Copy cave system from world zlevels to local chunk with expansion
 ///
 /// The world has a single cave marker per tile; we expand it to fill
@@ -1874,6 +1970,9 @@ fn copy_cave_from_world(
                         ZTile::CaveLake | ZTile::WaterCave => {
                             LocalTile::new(LocalTerrain::DeepWater, Material::Water)
                         }
+                        ZTile::FrozenCaveLake => {
+                            LocalTile::new(LocalTerrain::Ice, Material::Ice)
+                        }
                         ZTile::Waterfall => {
                             LocalTile::new(LocalTerrain::FlowingWater, Material::Water)
                         }
@@ -2100,6 +2199,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "history")]
     fn test_integration_dungeon_accessibility() {
         use crate::world::generate_world;
 
@@ -12,6 +12,9 @@
 //!
 //! Conversion: 1 world tile = 48×48 local tiles (2304 tiles per world tile)
 //!
+//! Between the two scales, `WorldData::sample` gives interpolated values at any
+//! fractional tile or latitude/longitude, consistent with local generation.
+//!
 //! # Z-Level Structure
 //!
 //! Local maps emphasize vertical depth (z-levels). The z-level range comes from
@@ -32,6 +35,7 @@ pub mod debug_export;
 pub mod export;
 pub mod geology;
 pub mod local;
+pub mod sample;
 pub mod storage;
 pub mod structures;
 pub mod terrain;
@@ -53,6 +57,7 @@ pub use local::{
     BoundaryConditions, ChunkEdge, EdgeColumn, EdgeDirection,
     generate_local_chunk_with_boundaries,
 };
+pub use sample::{SamplePoint, WorldSample, lat_lon_to_tile};
 pub use verify::{
     VerifyResult, VerifyCategory, Severity, VerificationStatus, VerificationReport,
     verify_chunk, verify_world_sample, verify_world_quick, verify_world_thorough,
@@ -60,7 +65,7 @@ pub use verify::{
     verify_boundary_conditions, generate_and_verify, is_chunk_valid, get_verification_summary,
 };
 pub use export::{
-    ExportOptions, ExportError,
+    ExportOptions, ExportError, render_chunk_image,
     export_local_region, export_full_world, export_local_area, quick_export,
 };
 pub use debug_export::export_debug_local_maps;
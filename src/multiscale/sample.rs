@@ -0,0 +1,205 @@
+//! Continuous sub-tile queries.
+//!
+//! `WorldData::sample` answers "what is the world like at this exact point?"
+//! for positions between tile centres. It uses the same corner convention as
+//! local chunk generation (a world tile's values sit at u = v = 0 and blend
+//! toward the east and south neighbours), so engines rendering between tiles
+//! agree with the local maps instead of inventing their own interpolation.
+//!
+//! Sub-tile detail comes from world-seeded Perlin noise sampled at whole-tile
+//! frequencies. Perlin noise vanishes on its integer lattice, so a sample taken
+//! exactly on a tile returns that tile's world values.
+
+use noise::{NoiseFn, Perlin};
+
+use crate::biomes::ExtendedBiome;
+use crate::tilemap::Tilemap;
+use crate::world::WorldData;
+
+/// Elevation detail amplitude as a fraction of the local relief between corners
+const DETAIL_RELIEF: f32 = 0.15;
+
+/// How far (in tile units) noise may push biome boundaries off the straight blend
+const BIOME_JITTER: f64 = 0.3;
+
+/// Where to sample the world
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SamplePoint {
+    /// Fractional world tile coordinates (x wraps, y is clamped)
+    Tile { x: f64, y: f64 },
+    /// Latitude/longitude in degrees (north and east positive)
+    LatLon { lat: f64, lon: f64 },
+}
+
+/// Interpolated world values at a continuous position
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorldSample {
+    /// World tile containing the point
+    pub world_x: usize,
+    pub world_y: usize,
+    /// Position within the tile (0-1), matching local chunk coordinates
+    pub u: f32,
+    pub v: f32,
+    pub elevation: f32,
+    pub temperature: f32,
+    pub moisture: f32,
+    /// Blended biomes with weights summing to 1, strongest first
+    pub biome_weights: Vec<(ExtendedBiome, f32)>,
+}
+
+impl WorldSample {
+    /// The biome with the largest weight
+    pub fn dominant_biome(&self) -> ExtendedBiome {
+        self.biome_weights[0].0
+    }
+
+    /// Weight of a given biome at this point (0 if absent)
+    pub fn biome_weight(&self, biome: ExtendedBiome) -> f32 {
+        self.biome_weights
+            .iter()
+            .find(|(b, _)| *b == biome)
+            .map(|&(_, w)| w)
+            .unwrap_or(0.0)
+    }
+}
+
+impl WorldData {
+    /// Sample elevation, climate and blended biomes at a continuous position
+    pub fn sample(&self, at: SamplePoint) -> WorldSample {
+        let (x, y) = match at {
+            SamplePoint::Tile { x, y } => (x, y),
+            SamplePoint::LatLon { lat, lon } => lat_lon_to_tile(lat, lon, self.width, self.height),
+        };
+        let x = x.rem_euclid(self.width as f64);
+        let y = y.clamp(0.0, (self.height - 1) as f64);
+
+        let world_x = (x.floor() as usize).min(self.width - 1);
+        let world_y = (y.floor() as usize).min(self.height - 1);
+        let u = (x - world_x as f64) as f32;
+        let v = (y - world_y as f64) as f32;
+
+        let noise = Perlin::new(self.seed as u32);
+        let elevation_corners = corners(&self.heightmap, world_x, world_y);
+        let relief = elevation_corners
+            .iter()
+            .flatten()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), &h| (lo.min(h), hi.max(h)));
+        let detail = fbm(&noise, x, y) as f32 * (relief.1 - relief.0) * DETAIL_RELIEF;
+        let elevation = bilerp(&elevation_corners, u, v) + detail;
+
+        let temperature = bilerp(&corners(&self.temperature, world_x, world_y), u, v);
+        let moisture = bilerp(&corners(&self.moisture, world_x, world_y), u, v);
+
+        // Jitter the blend position so biome edges meander instead of running straight
+        let ju = (u as f64 + noise.get([x, y, 17.5]) * BIOME_JITTER).clamp(0.0, 1.0) as f32;
+        let jv = (v as f64 + noise.get([x, y, 42.5]) * BIOME_JITTER).clamp(0.0, 1.0) as f32;
+        let biomes = corners(&self.biomes, world_x, world_y);
+        let weights = [
+            (biomes[0][0], (1.0 - ju) * (1.0 - jv)),
+            (biomes[0][1], ju * (1.0 - jv)),
+            (biomes[1][0], (1.0 - ju) * jv),
+            (biomes[1][1], ju * jv),
+        ];
+        let mut biome_weights: Vec<(ExtendedBiome, f32)> = Vec::with_capacity(4);
+        for (biome, w) in weights {
+            match biome_weights.iter_mut().find(|(b, _)| *b == biome) {
+                Some(entry) => entry.1 += w,
+                None => biome_weights.push((biome, w)),
+            }
+        }
+        biome_weights.retain(|&(_, w)| w > 0.0);
+        biome_weights.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        WorldSample {
+            world_x,
+            world_y,
+            u,
+            v,
+            elevation,
+            temperature,
+            moisture,
+            biome_weights,
+        }
+    }
+}
+
+/// Convert latitude/longitude to fractional tile coordinates (tile values sit at row/column centres)
+pub fn lat_lon_to_tile(lat: f64, lon: f64, width: usize, height: usize) -> (f64, f64) {
+    let x = (lon + 180.0) / 360.0 * width as f64 - 0.5;
+    let y = (90.0 - lat.clamp(-90.0, 90.0)) / 180.0 * height as f64 - 0.5;
+    (x, y)
+}
+
+/// Values at a tile and its east, south and south-east neighbours (east wraps, south clamps)
+fn corners<T: Clone>(map: &Tilemap<T>, x: usize, y: usize) -> [[T; 2]; 2] {
+    let east_x = (x + 1) % map.width;
+    let south_y = (y + 1).min(map.height - 1);
+    [
+        [map.get(x, y).clone(), map.get(east_x, y).clone()],
+        [map.get(x, south_y).clone(), map.get(east_x, south_y).clone()],
+    ]
+}
+
+fn bilerp(c: &[[f32; 2]; 2], u: f32, v: f32) -> f32 {
+    let top = c[0][0] * (1.0 - u) + c[0][1] * u;
+    let bottom = c[1][0] * (1.0 - u) + c[1][1] * u;
+    top * (1.0 - v) + bottom * v
+}
+
+/// Three octaves at whole-tile frequencies, so the detail is zero on tile corners
+fn fbm(noise: &Perlin, x: f64, y: f64) -> f64 {
+    let mut sum = 0.0;
+    let mut amplitude = 0.5;
+    let mut frequency = 1.0;
+    for _ in 0..3 {
+        sum += noise.get([x * frequency, y * frequency]) * amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::generate_test_world;
+
+    #[test]
+    fn test_sample_matches_tiles_and_blends() {
+        let mut world = generate_test_world();
+        for (x, y, h) in [(1, 2, 800.0), (2, 2, -300.0), (1, 1, 250.0), (2, 1, 40.0)] {
+            world.heightmap.set(x, y, h);
+            world.temperature.set(x, y, h / 100.0);
+        }
+        world.biomes.set(2, 1, ExtendedBiome::Desert);
+        world.biomes.set(2, 2, ExtendedBiome::Ocean);
+
+        // Exactly on a tile: the world values, no invented detail
+        let s = world.sample(SamplePoint::Tile { x: 1.0, y: 2.0 });
+        assert_eq!((s.world_x, s.world_y), (1, 2));
+        assert!((s.elevation - *world.heightmap.get(1, 2)).abs() < 1e-3);
+        assert!((s.temperature - *world.temperature.get(1, 2)).abs() < 1e-4);
+
+        // Between tiles: weights sum to one and are deterministic
+        let point = SamplePoint::Tile { x: 1.37, y: 0.81 };
+        let a = world.sample(point);
+        let total: f32 = a.biome_weights.iter().map(|&(_, w)| w).sum();
+        assert!((total - 1.0).abs() < 1e-4);
+        assert!(a.biome_weights.len() > 1);
+        assert!(a.elevation > -300.0 && a.elevation < 800.0);
+        assert_eq!(a, world.sample(point));
+
+        // Longitude wraps around the map
+        let east = world.sample(SamplePoint::Tile { x: world.width as f64 + 0.5, y: 1.0 });
+        let west = world.sample(SamplePoint::Tile { x: 0.5, y: 1.0 });
+        assert_eq!(east, west);
+    }
+
+    #[test]
+    fn test_lat_lon_to_tile() {
+        let (x, y) = lat_lon_to_tile(0.0, 0.0, 360, 180);
+        assert!((x - 179.5).abs() < 1e-9 && (y - 89.5).abs() < 1e-9);
+        let (x, y) = lat_lon_to_tile(89.5, -179.5, 360, 180);
+        assert!(x.abs() < 1e-9 && y.abs() < 1e-9);
+    }
+}
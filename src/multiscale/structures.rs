@@ -111,7 +111,7 @@ impl BspNode {
 
         if split_horizontal {
             // Split horizontally
-            let split_y = rng.gen_range(min_size..self.height - min_size);
+            let split_y = rng.gen_range(min_size..=self.height - min_size);
             self.left = Some(Box::new(BspNode::new(
                 self.x,
                 self.y,
@@ -126,7 +126,7 @@ impl BspNode {
             )));
         } else {
             // Split vertically
-            let split_x = rng.gen_range(min_size..self.width - min_size);
+            let split_x = rng.gen_range(min_size..=self.width - min_size);
             self.left = Some(Box::new(BspNode::new(
                 self.x,
                 self.y,
@@ -333,6 +333,7 @@ impl VerificationReport {
 // =============================================================================
 
 /// Verify that structures from WorldHistory appear in local maps
+#[cfg(feature = "history")]
 pub fn verify_structure_presence(
     world: &WorldData,
     chunk: &LocalChunk,
@@ -450,6 +451,7 @@ pub fn verify_structure_presence(
 }
 
 /// Check if chunk has any feature matching the predicate
+#[cfg(feature = "history")]
 fn chunk_has_feature<F>(chunk: &LocalChunk, predicate: F) -> bool
 where
     F: Fn(&LocalFeature) -> bool,
@@ -468,6 +470,7 @@ where
 }
 
 /// Check if chunk has any terrain matching the predicate
+#[cfg(feature = "history")]
 fn chunk_has_terrain<F>(chunk: &LocalChunk, predicate: F) -> bool
 where
     F: Fn(&LocalTerrain) -> bool,
@@ -853,6 +856,7 @@ pub fn verify_chunk(
     let mut results = Vec::new();
 
     // Structure presence
+    #[cfg(feature = "history")]
     results.extend(verify_structure_presence(world, chunk, world_x, world_y));
 
     // Z-level reachability
@@ -938,6 +942,7 @@ fn get_verification_samples(world: &WorldData, count: usize) -> Vec<(usize, usiz
     }
 
     // Add locations with structures from history
+    #[cfg(feature = "history")]
     if let Some(ref history) = world.history {
         // Add dungeon locations
         for (loc, _) in history.dungeons.dungeons_by_location.iter().take(5) {
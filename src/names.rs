@@ -0,0 +1,236 @@
+//! Place name registry
+//!
+//! Settlement, landmark and water names live in the history, the exploration
+//! record and the gazetteer. The name layer gathers them into one registry
+//! keyed by the tile each name is anchored on, with a label priority (which
+//! name wins where labels crowd) and an extent (how far from its anchor the
+//! name applies), so the atlas, the ASCII export, the explorer and the GeoJSON
+//! export all call a place by the same name.
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "history")]
+use crate::history::SettlementType;
+use crate::world::WorldData;
+
+/// What a named place is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum PlaceKind {
+    Ocean,
+    Sea,
+    Lake,
+    River,
+    Capital,
+    City,
+    Town,
+    Village,
+    Ruin,
+    Landmark,
+    Dungeon,
+    Lair,
+}
+
+impl PlaceKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlaceKind::Ocean => "ocean",
+            PlaceKind::Sea => "sea",
+            PlaceKind::Lake => "lake",
+            PlaceKind::River => "river",
+            PlaceKind::Capital => "capital",
+            PlaceKind::City => "city",
+            PlaceKind::Town => "town",
+            PlaceKind::Village => "village",
+            PlaceKind::Ruin => "ruin",
+            PlaceKind::Landmark => "landmark",
+            PlaceKind::Dungeon => "dungeon",
+            PlaceKind::Lair => "lair",
+        }
+    }
+
+    /// Label priority before the size bonus; kinds never outrank the one above
+    pub fn base_priority(&self) -> u32 {
+        match self {
+            PlaceKind::Ocean => 1100,
+            PlaceKind::Capital => 1000,
+            PlaceKind::Sea => 900,
+            PlaceKind::City => 800,
+            PlaceKind::Lake => 700,
+            PlaceKind::River => 600,
+            PlaceKind::Town => 500,
+            PlaceKind::Landmark => 400,
+            PlaceKind::Village => 300,
+            PlaceKind::Ruin => 200,
+            PlaceKind::Dungeon => 100,
+            PlaceKind::Lair => 0,
+        }
+    }
+
+    pub fn is_water(&self) -> bool {
+        matches!(self, PlaceKind::Ocean | PlaceKind::Sea | PlaceKind::Lake | PlaceKind::River)
+    }
+}
+
+/// A name anchored on one tile
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PlaceName {
+    pub name: String,
+    pub kind: PlaceKind,
+    /// Higher priorities are labelled first and win shared anchors
+    pub priority: u32,
+    /// Radius in tiles around the anchor the name applies to
+    pub extent: usize,
+    /// Water body the name covers entirely (water features only)
+    pub water_body: Option<u16>,
+}
+
+impl PlaceName {
+    /// A name whose priority grows with the place's size in tiles
+    pub fn new(name: &str, kind: PlaceKind, size: usize) -> Self {
+        // Equal-area disc around the anchor
+        let extent = (size as f64 / std::f64::consts::PI).sqrt().round() as usize;
+        let bonus = ((size as f64).sqrt() as u32).min(99);
+        PlaceName { name: name.to_string(), kind, priority: kind.base_priority() + bonus, extent, water_body: None }
+    }
+}
+
+/// Every named place of a world, keyed by anchor tile
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct NameLayer {
+    pub width: usize,
+    pub height: usize,
+    pub places: BTreeMap<(usize, usize), PlaceName>,
+}
+
+impl NameLayer {
+    pub fn new(width: usize, height: usize) -> Self {
+        NameLayer { width, height, places: BTreeMap::new() }
+    }
+
+    /// Gather the names of the world's water features, settlements, discoveries,
+    /// dungeons and lairs
+    pub fn of(world: &WorldData) -> Self {
+        let mut layer = NameLayer::new(world.width, world.height);
+
+        if let Some(gazetteer) = &world.gazetteer {
+            for entry in &gazetteer.entries {
+                let kind = match entry.kind {
+                    crate::gazetteer::FeatureKind::Ocean => PlaceKind::Ocean,
+                    crate::gazetteer::FeatureKind::Sea => PlaceKind::Sea,
+                    crate::gazetteer::FeatureKind::Lake => PlaceKind::Lake,
+                    crate::gazetteer::FeatureKind::River => PlaceKind::River,
+                };
+                let size = entry.area.max(entry.length);
+                let place = PlaceName { water_body: Some(entry.water_body), ..PlaceName::new(&entry.name, kind, size) };
+                layer.insert(entry.x, entry.y, place);
+            }
+        }
+
+        #[cfg(feature = "history")]
+        if let Some(history) = &world.history {
+            for s in history.territories.settlements.values() {
+                let kind = match s.settlement_type {
+                    _ if !s.is_active() => PlaceKind::Ruin,
+                    SettlementType::Capital => PlaceKind::Capital,
+                    SettlementType::City => PlaceKind::City,
+                    SettlementType::Town | SettlementType::Fortress | SettlementType::Temple => PlaceKind::Town,
+                    SettlementType::Village | SettlementType::Mine | SettlementType::Outpost => PlaceKind::Village,
+                };
+                layer.insert(s.x, s.y, PlaceName::new(&s.name, kind, s.size));
+            }
+            for dungeon in history.dungeons.all() {
+                let (x, y) = dungeon.location;
+                layer.insert(x, y, PlaceName::new(&dungeon.name, PlaceKind::Dungeon, dungeon.size));
+            }
+            for lair in history.monsters.active_lairs() {
+                layer.insert(lair.x, lair.y, PlaceName::new(&lair.name, PlaceKind::Lair, lair.territory.len()));
+            }
+        }
+
+        #[cfg(feature = "history")]
+        if let Some(record) = &world.exploration {
+            for d in &record.discoveries {
+                layer.insert(d.x, d.y, PlaceName::new(&d.name, PlaceKind::Landmark, 1));
+            }
+        }
+        layer
+    }
+
+    /// Anchor a name on a tile, unless a higher-priority name already holds it
+    pub fn insert(&mut self, x: usize, y: usize, place: PlaceName) {
+        match self.places.get(&(x, y)) {
+            Some(held) if held.priority >= place.priority => {}
+            _ => {
+                self.places.insert((x, y), place);
+            }
+        }
+    }
+
+    /// The name anchored on a tile
+    pub fn get(&self, x: usize, y: usize) -> Option<&PlaceName> {
+        self.places.get(&(x, y))
+    }
+
+    /// All names, highest priority first, with their anchors
+    pub fn by_priority(&self) -> Vec<((usize, usize), &PlaceName)> {
+        let mut places: Vec<_> = self.places.iter().map(|(&at, place)| (at, place)).collect();
+        places.sort_by(|a, b| b.1.priority.cmp(&a.1.priority).then_with(|| a.0.cmp(&b.0)));
+        places
+    }
+
+    /// The highest-priority name that applies to a tile: a land name whose extent
+    /// reaches it, or the water feature of the body the tile lies in
+    pub fn name_at(&self, x: usize, y: usize, water_body: u16) -> Option<((usize, usize), &PlaceName)> {
+        self.places
+            .iter()
+            .filter(|(&(ax, ay), place)| match place.water_body {
+                Some(body) => body == water_body,
+                None => {
+                    let dx = ax.abs_diff(x);
+                    let dx = dx.min(self.width.saturating_sub(dx));
+                    let d2 = dx * dx + ay.abs_diff(y).pow(2);
+                    d2 <= place.extent * place.extent
+                }
+            })
+            .max_by(|a, b| a.1.priority.cmp(&b.1.priority).then_with(|| b.0.cmp(a.0)))
+            .map(|(&at, place)| (at, place))
+    }
+
+    /// Legend listing every name by priority, for text exports
+    pub fn legend(&self) -> String {
+        let mut legend = String::new();
+        legend.push_str("=== PLACE NAMES ===\n");
+        for ((x, y), place) in self.by_priority() {
+            legend.push_str(&format!("  {:28} {:9} ({}, {})\n", place.name, place.kind.name(), x, y));
+        }
+        legend
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_and_extent() {
+        let mut layer = NameLayer::new(40, 20);
+        layer.insert(5, 5, PlaceName::new("Ashford", PlaceKind::Village, 4));
+        layer.insert(5, 5, PlaceName::new("The Deep Halls", PlaceKind::Dungeon, 30));
+        layer.insert(38, 10, PlaceName::new("Kharam", PlaceKind::Capital, 13));
+        layer.insert(20, 10, PlaceName { water_body: Some(3), ..PlaceName::new("Mirrormere", PlaceKind::Lake, 50) });
+
+        // The village keeps its anchor over the dungeon beneath it
+        assert_eq!(layer.get(5, 5).unwrap().name, "Ashford");
+        assert_eq!(layer.places.len(), 3);
+
+        let order: Vec<_> = layer.by_priority().into_iter().map(|(_, p)| p.name.as_str()).collect();
+        assert_eq!(order, ["Kharam", "Mirrormere", "Ashford"]);
+
+        // The capital's extent wraps across the east-west seam; the lake covers its body
+        assert_eq!(layer.name_at(0, 10, 0).unwrap().1.name, "Kharam");
+        assert_eq!(layer.name_at(0, 10, 0).unwrap().0, (38, 10));
+        assert_eq!(layer.name_at(12, 2, 3).unwrap().1.name, "Mirrormere");
+        assert!(layer.name_at(12, 2, 0).is_none());
+        assert!(layer.legend().lines().nth(1).unwrap().contains("Kharam"));
+    }
+}
@@ -0,0 +1,528 @@
+//! Synthetic paleoclimate record
+//!
+//! Reads the world's environmental history back the way a pollen core or an
+//! ice core would: one sample per century at a handful of sites, from the
+//! start of recorded history to the present.
+//! - Temperature follows slow orbital cycles, with sharp cooling after
+//!   volcanic eruptions and catastrophes
+//! - Precipitation rises and falls with temperature
+//! - Forest cover around the site drops while settlements farm the land and
+//!   after fires, and recovers as the fields are abandoned
+//!
+//! Eruptions and impacts are marked as layers in the record. History has no
+//! meteor strikes of its own, so magical catastrophes stand in for them.
+//! The record is exported as CSV and as a plotted PNG per site.
+
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+
+use image::{Rgb, RgbImage};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::ExtendedBiome;
+use crate::history::supply::{biome_fertility, reach_tiles};
+use crate::history::{EventType, SettlementType, WorldHistory};
+use crate::succession::{is_forest_biome, SuccessionConfig};
+use crate::world::WorldData;
+
+/// Parameters for the paleoclimate record
+#[derive(Clone, Debug)]
+pub struct PaleoConfig {
+    /// Years per sample
+    pub century: i32,
+    /// Years covered when the world has no history to date it
+    pub default_span: i32,
+    /// Sites sampled when none are given
+    pub max_sites: usize,
+    /// Radius (tiles) of the catchment whose forest a site records
+    pub forest_radius: i32,
+    /// Swing of the orbital temperature cycles (°C)
+    pub orbital_amplitude: f32,
+    /// Year-to-year scatter in the temperature proxy (°C)
+    pub noise: f32,
+    /// Cooling in the century of an eruption or impact (°C)
+    pub eruption_cooling: f32,
+    /// Years for that cooling to fade to about a third
+    pub cooling_decay_years: f32,
+    /// Fractional change in precipitation per °C of warming
+    pub precipitation_per_degree: f32,
+    /// Regrowth times and burn radius, shared with biome succession
+    pub succession: SuccessionConfig,
+}
+
+impl Default for PaleoConfig {
+    fn default() -> Self {
+        Self {
+            century: 100,
+            default_span: 2000,
+            max_sites: 4,
+            forest_radius: 4,
+            orbital_amplitude: 1.2,
+            noise: 0.15,
+            eruption_cooling: 0.8,
+            cooling_decay_years: 80.0,
+            precipitation_per_degree: 0.07,
+            succession: SuccessionConfig::default(),
+        }
+    }
+}
+
+/// Where a core was drilled
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaleoSite {
+    pub name: String,
+    pub x: usize,
+    pub y: usize,
+}
+
+/// One century of a site's record
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PaleoSample {
+    /// First year of the century
+    pub year: i32,
+    /// Mean temperature (°C)
+    pub temperature: f32,
+    /// Precipitation (0.0-1.0, on the moisture scale)
+    pub precipitation: f32,
+    /// Share of the land around the site under forest (0.0-1.0)
+    pub forest_cover: f32,
+}
+
+/// Kind of layer left in every core
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarkerKind {
+    /// Volcanic ash and sulphate
+    Eruption,
+    /// Impact debris; left by magical catastrophes
+    Impact,
+}
+
+impl MarkerKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarkerKind::Eruption => "eruption",
+            MarkerKind::Impact => "impact",
+        }
+    }
+
+    fn color(&self) -> (u8, u8, u8) {
+        match self {
+            MarkerKind::Eruption => (200, 60, 40),
+            MarkerKind::Impact => (130, 60, 170),
+        }
+    }
+}
+
+/// A dated event layer
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaleoMarker {
+    pub year: i32,
+    pub kind: MarkerKind,
+    pub name: String,
+}
+
+/// The sampled record of every site
+#[derive(Clone, Debug)]
+pub struct PaleoRecord {
+    pub sites: Vec<PaleoSite>,
+    /// Samples of each site, oldest first, in the order of `sites`
+    pub samples: Vec<Vec<PaleoSample>>,
+    /// Event layers, oldest first
+    pub markers: Vec<PaleoMarker>,
+}
+
+/// Capitals first, then the oldest settlements; evenly spaced land tiles if
+/// the world has none
+pub fn default_sites(world: &WorldData, config: &PaleoConfig) -> Vec<PaleoSite> {
+    if let Some(ref history) = world.history {
+        let mut settlements: Vec<_> = history.territories.settlements.values().collect();
+        settlements.sort_by_key(|s| (s.settlement_type != SettlementType::Capital, s.founded.0, s.id.0));
+        let sites: Vec<PaleoSite> = settlements
+            .into_iter()
+            .take(config.max_sites)
+            .map(|s| PaleoSite { name: s.name.clone(), x: s.x, y: s.y })
+            .collect();
+        if !sites.is_empty() {
+            return sites;
+        }
+    }
+
+    let land: Vec<(usize, usize)> =
+        world.heightmap.iter().filter(|(_, _, &h)| h >= 0.0).map(|(x, y, _)| (x, y)).collect();
+    let count = config.max_sites.min(land.len());
+    (0..count)
+        .map(|i| {
+            let (x, y) = land[(2 * i + 1) * land.len() / (2 * count)];
+            PaleoSite { name: format!("Site {}", i + 1), x, y }
+        })
+        .collect()
+}
+
+/// Event layers from the history, oldest first
+fn markers(history: Option<&WorldHistory>) -> Vec<PaleoMarker> {
+    let mut markers: Vec<PaleoMarker> = history
+        .into_iter()
+        .flat_map(|h| h.timeline.events.values())
+        .filter_map(|e| {
+            let kind = match e.event_type {
+                EventType::VolcanicEruption => MarkerKind::Eruption,
+                EventType::MagicalCatastrophe => MarkerKind::Impact,
+                _ => return None,
+            };
+            (e.year.0 <= 0).then(|| PaleoMarker { year: e.year.0, kind, name: e.name.clone() })
+        })
+        .collect();
+    markers.sort_by(|a, b| a.year.cmp(&b.year).then_with(|| a.name.cmp(&b.name)));
+    markers
+}
+
+/// Orbital cycles of the world: (period in years, share of the amplitude, phase)
+fn orbital_cycles(seed: u64) -> [(f32, f32, f32); 3] {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x9A1E0));
+    [
+        (rng.gen_range(1800.0..2600.0), 0.5, rng.gen_range(0.0..TAU)),
+        (rng.gen_range(900.0..1300.0), 0.3, rng.gen_range(0.0..TAU)),
+        (rng.gen_range(350.0..500.0), 0.2, rng.gen_range(0.0..TAU)),
+    ]
+}
+
+/// Global temperature anomaly of the century starting at `year`, relative to the present
+fn temperature_anomaly(year: i32, seed: u64, markers: &[PaleoMarker], config: &PaleoConfig) -> f32 {
+    let cycles = orbital_cycles(seed);
+    let orbital = |t: f32| -> f32 { cycles.iter().map(|&(period, share, phase)| share * (TAU * t / period + phase).sin()).sum() };
+    let mid = year as f32 + config.century as f32 / 2.0;
+    let mut anomaly = config.orbital_amplitude * (orbital(mid) - orbital(0.0));
+
+    let end = year + config.century;
+    for marker in markers.iter().filter(|m| m.year < end) {
+        let elapsed = (year - marker.year).max(0) as f32;
+        anomaly -= config.eruption_cooling * (-elapsed / config.cooling_decay_years).exp();
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x9A1E1).wrapping_add(year as i64 as u64));
+    anomaly + rng.gen_range(-config.noise..=config.noise)
+}
+
+/// Years in which each tile around the sites was cleared: fields while a
+/// settlement farms it, the regrowth after, and burns
+fn clearings(world: &WorldData, config: &PaleoConfig) -> HashMap<(usize, usize), Vec<(i32, i32)>> {
+    let mut clearings: HashMap<(usize, usize), Vec<(i32, i32)>> = HashMap::new();
+    let Some(ref history) = world.history else { return clearings };
+    let (width, height) = (world.width, world.height);
+    let regrowth = config.succession.young_forest_years as i32;
+
+    for settlement in history.territories.settlements.values() {
+        let end = settlement.abandoned.map_or(i32::MAX, |y| y.0.saturating_add(regrowth));
+        for tile in reach_tiles(settlement, width, height) {
+            if biome_fertility(climax(world, tile.0, tile.1)) >= 0.3 {
+                clearings.entry(tile).or_default().push((settlement.founded.0, end));
+            }
+        }
+    }
+
+    let r = config.succession.burn_radius;
+    for event in history.timeline.events.values() {
+        if !matches!(event.event_type, EventType::DragonAttack | EventType::VolcanicEruption) {
+            continue;
+        }
+        let Some((cx, cy)) = event.location else { continue };
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let y = cy as i32 + dy;
+                if dx * dx + dy * dy > r * r || y < 0 || y >= height as i32 {
+                    continue;
+                }
+                let x = (cx as i32 + dx).rem_euclid(width as i32) as usize;
+                clearings.entry((x, y as usize)).or_default().push((event.year.0, event.year.0 + regrowth));
+            }
+        }
+    }
+
+    clearings
+}
+
+/// Biome the tile returns to when left alone
+fn climax(world: &WorldData, x: usize, y: usize) -> ExtendedBiome {
+    world
+        .succession
+        .as_ref()
+        .and_then(|map| *map.climax.get(x, y))
+        .unwrap_or(*world.biomes.get(x, y))
+}
+
+/// Share of the land around a site under forest in the given year
+fn forest_cover(
+    world: &WorldData,
+    site: &PaleoSite,
+    year: i32,
+    clearings: &HashMap<(usize, usize), Vec<(i32, i32)>>,
+    config: &PaleoConfig,
+) -> f32 {
+    let r = config.forest_radius;
+    let (mut land, mut forest) = (0, 0);
+    for dy in -r..=r {
+        for dx in -r..=r {
+            let y = site.y as i32 + dy;
+            if dx * dx + dy * dy > r * r || y < 0 || y >= world.height as i32 {
+                continue;
+            }
+            let (x, y) = ((site.x as i32 + dx).rem_euclid(world.width as i32) as usize, y as usize);
+            if *world.heightmap.get(x, y) < 0.0 {
+                continue;
+            }
+            land += 1;
+            let cleared = clearings.get(&(x, y)).is_some_and(|spans| spans.iter().any(|&(from, to)| from <= year && year < to));
+            if is_forest_biome(climax(world, x, y)) && !cleared {
+                forest += 1;
+            }
+        }
+    }
+    if land == 0 {
+        0.0
+    } else {
+        forest as f32 / land as f32
+    }
+}
+
+/// Sample every site once per century, from the first era to the present
+pub fn paleo_record(world: &WorldData, sites: &[PaleoSite], config: &PaleoConfig) -> PaleoRecord {
+    let history = world.history.as_ref();
+    let first = history
+        .and_then(|h| h.timeline.eras.first())
+        .map_or(-config.default_span, |era| era.start.0.min(0));
+    let start = first.div_euclid(config.century) * config.century;
+    let years: Vec<i32> = (start..0).step_by(config.century as usize).collect();
+
+    let markers = markers(history);
+    let anomalies: Vec<f32> = years.iter().map(|&y| temperature_anomaly(y, world.seed, &markers, config)).collect();
+    let clearings = clearings(world, config);
+
+    let samples = sites
+        .iter()
+        .map(|site| {
+            let temperature = *world.temperature.get(site.x, site.y);
+            let moisture = *world.moisture.get(site.x, site.y);
+            years
+                .iter()
+                .zip(&anomalies)
+                .map(|(&year, &anomaly)| PaleoSample {
+                    year,
+                    temperature: temperature + anomaly,
+                    precipitation: (moisture * (1.0 + config.precipitation_per_degree * anomaly)).clamp(0.0, 1.0),
+                    forest_cover: forest_cover(world, site, year, &clearings, config),
+                })
+                .collect()
+        })
+        .collect();
+
+    PaleoRecord { sites: sites.to_vec(), samples, markers }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl PaleoRecord {
+    /// One row per site and century
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("site,x,y,year,temperature_c,precipitation,forest_cover\n");
+        for (site, samples) in self.sites.iter().zip(&self.samples) {
+            for s in samples {
+                out.push_str(&format!(
+                    "{},{},{},{},{:.2},{:.3},{:.3}\n",
+                    csv_field(&site.name),
+                    site.x,
+                    site.y,
+                    s.year,
+                    s.temperature,
+                    s.precipitation,
+                    s.forest_cover
+                ));
+            }
+        }
+        out
+    }
+
+    /// One row per event layer
+    pub fn markers_csv(&self) -> String {
+        let mut out = String::from("year,kind,name\n");
+        for m in &self.markers {
+            out.push_str(&format!("{},{},{}\n", m.year, m.kind.name(), csv_field(&m.name)));
+        }
+        out
+    }
+}
+
+/// Plot width in pixels
+const PLOT_WIDTH: u32 = 600;
+/// Height of each curve's panel in pixels
+const PANEL_HEIGHT: u32 = 110;
+/// Margin around and between panels in pixels
+const MARGIN: u32 = 16;
+
+const BACKGROUND: (u8, u8, u8) = (250, 248, 240);
+const FRAME: (u8, u8, u8) = (170, 170, 170);
+const INK: (u8, u8, u8) = (30, 30, 30);
+
+fn put(img: &mut RgbImage, x: i32, y: i32, color: (u8, u8, u8)) {
+    if x >= 0 && y >= 0 && (x as u32) < img.width() && (y as u32) < img.height() {
+        img.put_pixel(x as u32, y as u32, Rgb([color.0, color.1, color.2]));
+    }
+}
+
+fn line(img: &mut RgbImage, (x0, y0): (i32, i32), (x1, y1): (i32, i32), color: (u8, u8, u8)) {
+    let steps = (x1 - x0).abs().max((y1 - y0).abs()).max(1);
+    for i in 0..=steps {
+        let x = x0 + (x1 - x0) * i / steps;
+        let y = y0 + (y1 - y0) * i / steps;
+        put(img, x, y, color);
+    }
+}
+
+fn text(img: &mut RgbImage, font: &HashMap<char, [u8; 7]>, x: i32, y: i32, label: &str, color: (u8, u8, u8)) {
+    for (i, ch) in label.chars().enumerate() {
+        let Some(rows) = font.get(&ch) else { continue };
+        for (row, bits) in rows.iter().enumerate() {
+            for col in 0..5 {
+                if bits & (0b10000 >> col) != 0 {
+                    put(img, x + i as i32 * 6 + col, y + row as i32, color);
+                }
+            }
+        }
+    }
+}
+
+/// A plotted curve: label, unit, value of a sample and line colour
+type Curve = (&'static str, &'static str, fn(&PaleoSample) -> f32, (u8, u8, u8));
+
+/// Plot one site's curves in stacked panels, with event layers as vertical lines
+pub fn render_site_png(record: &PaleoRecord, site: usize) -> RgbImage {
+    let font = crate::ascii::create_bitmap_font();
+    let samples = &record.samples[site];
+    let panels: [Curve; 3] = [
+        ("Temperature", "C", |s| s.temperature, (190, 70, 50)),
+        ("Precipitation", "", |s| s.precipitation, (50, 90, 190)),
+        ("Forest cover", "", |s| s.forest_cover, (40, 130, 60)),
+    ];
+    let header = 12;
+    let footer = 12;
+    let width = PLOT_WIDTH + 2 * MARGIN;
+    let height = header + MARGIN + panels.len() as u32 * (PANEL_HEIGHT + MARGIN) + footer;
+    let mut img = RgbImage::from_pixel(width, height, Rgb([BACKGROUND.0, BACKGROUND.1, BACKGROUND.2]));
+
+    let s = &record.sites[site];
+    text(&mut img, &font, MARGIN as i32, 4, &format!("{} ({}, {})", s.name, s.x, s.y), INK);
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else { return img };
+    let span = (last.year - first.year).max(1) as f32;
+    let px = |year: i32| MARGIN as i32 + ((year - first.year) as f32 / span * (PLOT_WIDTH - 1) as f32).round() as i32;
+
+    for (i, (name, unit, value, color)) in panels.iter().enumerate() {
+        let top = (header + MARGIN + i as u32 * (PANEL_HEIGHT + MARGIN)) as i32;
+        let bottom = top + PANEL_HEIGHT as i32 - 1;
+        let (left, right) = (MARGIN as i32, (MARGIN + PLOT_WIDTH) as i32 - 1);
+        for x in left..=right {
+            put(&mut img, x, top, FRAME);
+            put(&mut img, x, bottom, FRAME);
+        }
+        for y in top..=bottom {
+            put(&mut img, left, y, FRAME);
+            put(&mut img, right, y, FRAME);
+        }
+        for marker in record.markers.iter().filter(|m| m.year >= first.year) {
+            let x = px(marker.year.min(last.year));
+            for y in (top + 1..bottom).step_by(2) {
+                put(&mut img, x, y, marker.kind.color());
+            }
+        }
+
+        let values: Vec<f32> = samples.iter().map(value).collect();
+        let lo = values.iter().copied().fold(f32::INFINITY, f32::min);
+        let hi = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let range = (hi - lo).max(1e-3);
+        let py = |v: f32| bottom - 4 - ((v - lo) / range * (PANEL_HEIGHT - 9) as f32).round() as i32;
+        let points: Vec<(i32, i32)> = samples.iter().zip(&values).map(|(s, &v)| (px(s.year), py(v))).collect();
+        for pair in points.windows(2) {
+            line(&mut img, pair[0], pair[1], *color);
+        }
+        if let [only] = points[..] {
+            put(&mut img, only.0, only.1, *color);
+        }
+
+        text(&mut img, &font, left + 4, top + 4, &format!("{} {:.2}-{:.2} {}", name, lo, hi, unit), INK);
+    }
+
+    let base = height as i32 - footer as i32;
+    text(&mut img, &font, MARGIN as i32, base, &format!("{}", first.year), INK);
+    let now = format!("{}", last.year);
+    text(&mut img, &font, (MARGIN + PLOT_WIDTH) as i32 - now.len() as i32 * 6, base, &now, INK);
+    img
+}
+
+/// Write `PREFIX.csv`, `PREFIX_markers.csv` and a `PREFIX_<n>.png` plot per site
+pub fn export_paleo(record: &PaleoRecord, prefix: &str) -> Result<Vec<String>, image::ImageError> {
+    let (csv, markers) = (format!("{}.csv", prefix), format!("{}_markers.csv", prefix));
+    std::fs::write(&csv, record.to_csv())?;
+    std::fs::write(&markers, record.markers_csv())?;
+    let mut paths = vec![csv, markers];
+    for site in 0..record.sites.len() {
+        let png = format!("{}_{}.png", prefix, site + 1);
+        render_site_png(record, site).save(&png)?;
+        paths.push(png);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{HistoricalEvent, Timeline};
+    use crate::history::types::Year;
+
+    #[test]
+    fn test_eruption_cools_the_record_and_leaves_a_layer() {
+        let mut world = crate::world::generate_test_world();
+        let mut history = WorldHistory::empty();
+        let mut timeline = Timeline::new();
+        let id = timeline.new_id();
+        timeline.add_event(HistoricalEvent {
+            id,
+            year: Year(-850),
+            event_type: EventType::VolcanicEruption,
+            faction: None,
+            other_faction: None,
+            location: Some((1, 1)),
+            settlement: None,
+            name: "Eruption of Ashpeak".to_string(),
+            description: String::new(),
+            casualties: 0,
+            has_evidence: true,
+        });
+        history.timeline = timeline;
+        world.history = Some(history);
+
+        let config = PaleoConfig { noise: 0.0, eruption_cooling: 5.0, ..Default::default() };
+        let sites = default_sites(&world, &config);
+        assert!(!sites.is_empty());
+        let record = paleo_record(&world, &sites, &config);
+
+        let samples = &record.samples[0];
+        assert_eq!(samples.len(), (config.default_span / config.century) as usize);
+        assert_eq!(samples.first().unwrap().year, -config.default_span);
+        let at = |year: i32| samples.iter().find(|s| s.year == year).unwrap().temperature;
+        assert!(at(-900) < at(-1000) - 3.0, "the eruption century is colder than the one before");
+        assert!(at(-600) > at(-900), "the cooling fades");
+
+        assert_eq!(record.markers.len(), 1);
+        assert_eq!(record.markers[0].kind, MarkerKind::Eruption);
+        let csv = record.to_csv();
+        assert_eq!(csv.lines().count(), 1 + sites.len() * samples.len());
+        assert!(record.markers_csv().contains("-850,eruption,Eruption of Ashpeak"));
+        let img = render_site_png(&record, 0);
+        assert_eq!(img.width(), PLOT_WIDTH + 2 * MARGIN);
+    }
+}
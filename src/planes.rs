@@ -0,0 +1,506 @@
+//! Planar layers and portals
+//!
+//! Generates mirror planes (an Underworld and a Feywild) that overlay the surface
+//! world in a shared coordinate system: tile (x, y) on one plane corresponds to
+//! tile (x, y) on every other plane. Coastlines are shared so the planes read as
+//! reflections of each other, while relief, climate and biomes are transformed.
+//!
+//! Portals anchor at unique biomes, ultra-rare biomes and mystical landmarks.
+//! Optionally, history is extended so monsters and factions near a portal cross
+//! between planes, with the crossings recorded in the surface timeline.
+
+use rand::seq::SliceRandom;
+#[cfg(feature = "history")]
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::{BiomeCategory, ExtendedBiome};
+#[cfg(feature = "history")]
+use crate::history::{EventType, HistoricalEvent, WorldHistory};
+#[cfg(feature = "history")]
+use crate::history::types::Year;
+use crate::history::types::{FactionId, LairId};
+use crate::water_bodies;
+use crate::world::WorldData;
+use crate::zlevel;
+
+/// Tiles around a portal from which monsters may cross
+#[cfg(feature = "history")]
+const CROSSING_RADIUS: usize = 12;
+
+/// Chance that a monster lair near a portal crosses into the other plane
+#[cfg(feature = "history")]
+const LAIR_CROSSING_CHANCE: f64 = 0.5;
+
+/// A world layer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PlaneKind {
+    /// The ordinary generated world
+    Surface,
+    /// Dark mirror: inverted relief, scorched climate, dead biomes
+    Underworld,
+    /// Bright mirror: exaggerated relief, lush enchanted biomes
+    Feywild,
+}
+
+impl PlaneKind {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PlaneKind::Surface => "Surface",
+            PlaneKind::Underworld => "Underworld",
+            PlaneKind::Feywild => "Feywild",
+        }
+    }
+}
+
+/// A portal linking the same coordinates on two planes
+#[derive(Clone, Debug)]
+pub struct Portal {
+    pub x: usize,
+    pub y: usize,
+    pub from: PlaneKind,
+    pub to: PlaneKind,
+    /// Surface biome the portal is anchored to
+    pub anchor: ExtendedBiome,
+    pub name: String,
+}
+
+/// Something that crossed a portal during history
+#[derive(Clone, Debug)]
+pub struct PortalCrossing {
+    /// Index into `PlanarWorld::portals`
+    pub portal: usize,
+    /// Faction that discovered or used the portal
+    pub faction: Option<FactionId>,
+    /// Surface lair that moved through the portal
+    pub lair: Option<LairId>,
+    pub description: String,
+}
+
+/// One plane and its world data
+pub struct PlaneLayer {
+    pub kind: PlaneKind,
+    pub world: WorldData,
+}
+
+/// Options for planar generation
+#[derive(Clone, Debug)]
+pub struct PlanarConfig {
+    /// Mirror planes to generate alongside the surface
+    pub planes: Vec<PlaneKind>,
+    /// Maximum portals linking the surface to each mirror plane
+    pub portals_per_plane: usize,
+    /// Minimum distance (tiles) between portals
+    pub min_spacing: usize,
+    /// Let monsters and factions cross at portals during history
+    pub history_crossings: bool,
+}
+
+impl Default for PlanarConfig {
+    fn default() -> Self {
+        Self {
+            planes: vec![PlaneKind::Underworld, PlaneKind::Feywild],
+            portals_per_plane: 6,
+            min_spacing: 16,
+            history_crossings: true,
+        }
+    }
+}
+
+/// A stack of overlapping world layers connected by portals
+pub struct PlanarWorld {
+    /// Surface first, then mirror planes in config order
+    pub layers: Vec<PlaneLayer>,
+    pub portals: Vec<Portal>,
+    pub crossings: Vec<PortalCrossing>,
+}
+
+impl PlanarWorld {
+    pub fn layer(&self, kind: PlaneKind) -> Option<&PlaneLayer> {
+        self.layers.iter().find(|l| l.kind == kind)
+    }
+
+    pub fn surface(&self) -> &WorldData {
+        &self.layers[0].world
+    }
+
+    /// Portals at a tile (on any plane, since coordinates are shared)
+    pub fn portals_at(&self, x: usize, y: usize) -> Vec<&Portal> {
+        self.portals.iter().filter(|p| p.x == x && p.y == y).collect()
+    }
+
+    /// Planes reachable from `plane` by stepping through a portal at (x, y)
+    pub fn destinations(&self, plane: PlaneKind, x: usize, y: usize) -> Vec<PlaneKind> {
+        self.portals_at(x, y)
+            .into_iter()
+            .filter_map(|p| {
+                if p.from == plane {
+                    Some(p.to)
+                } else if p.to == plane {
+                    Some(p.from)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Transform a surface biome into its counterpart on a mirror plane
+pub fn mirror_biome(plane: PlaneKind, biome: ExtendedBiome, is_water: bool) -> ExtendedBiome {
+    use ExtendedBiome as B;
+
+    if biome.is_unique() || plane == PlaneKind::Surface {
+        return biome;
+    }
+
+    match plane {
+        PlaneKind::Underworld => {
+            if is_water {
+                return match biome {
+                    B::CoastalWater | B::Lagoon => B::LavaLake,
+                    _ => B::InkSea,
+                };
+            }
+            match biome {
+                B::TropicalRainforest | B::TemperateRainforest => B::PetrifiedForest,
+                B::BorealForest | B::TemperateForest | B::TropicalForest => B::DeadForest,
+                B::Desert => B::VolcanicWasteland,
+                B::Ice | B::Tundra => B::BoneFields,
+                B::AlpineTundra | B::SnowyPeaks => B::ObsidianFields,
+                B::Foothills => B::BasaltColumns,
+                _ if biome.category() == BiomeCategory::Wetlands => B::Shadowfen,
+                _ => B::Ashlands,
+            }
+        }
+        PlaneKind::Feywild => {
+            if is_water {
+                return match biome {
+                    B::CoastalWater | B::Lagoon => B::BioluminescentWater,
+                    _ => B::PhosphorShallows,
+                };
+            }
+            match biome {
+                B::TropicalRainforest | B::TemperateRainforest => B::AncientGrove,
+                B::BorealForest | B::TemperateForest | B::TropicalForest => B::BioluminescentForest,
+                B::TemperateGrassland | B::Savanna => B::EtherealMist,
+                B::Desert => B::SingingDunes,
+                B::Ice | B::Tundra => B::AuroraWastes,
+                B::AlpineTundra | B::SnowyPeaks => B::CrystalWasteland,
+                B::Foothills => B::FloatingStones,
+                _ if biome.category() == BiomeCategory::Wetlands => B::SpiritMarsh,
+                _ => B::MushroomForest,
+            }
+        }
+        PlaneKind::Surface => biome,
+    }
+}
+
+/// Build a mirror plane from the surface world (coastlines are preserved)
+pub fn generate_mirror_plane(surface: &WorldData, plane: PlaneKind) -> WorldData {
+    let max_land = surface.heightmap.iter().map(|(_, _, &h)| h).fold(0.0f32, f32::max);
+
+    let mut heightmap = surface.heightmap.clone();
+    for (_, _, h) in heightmap.iter_mut() {
+        if *h < 0.0 {
+            continue;
+        }
+        *h = match plane {
+            // Peaks become sunken lowlands, plains rise into broken highlands
+            PlaneKind::Underworld => (max_land - *h) * 0.6 + 10.0,
+            PlaneKind::Feywild => *h * 1.3,
+            PlaneKind::Surface => *h,
+        };
+    }
+
+    let mut temperature = surface.temperature.clone();
+    let mut moisture = surface.moisture.clone();
+    for (x, y, t) in temperature.iter_mut() {
+        let m = moisture.get_mut(x, y);
+        match plane {
+            PlaneKind::Underworld => {
+                *t += 25.0;
+                *m *= 0.3;
+            }
+            PlaneKind::Feywild => {
+                *t = *t * 0.5 + 10.0;
+                *m = m.max(0.6);
+            }
+            PlaneKind::Surface => {}
+        }
+    }
+
+    let mut biomes = surface.biomes.clone();
+    for (x, y, b) in biomes.iter_mut() {
+        *b = mirror_biome(plane, *b, *surface.heightmap.get(x, y) < 0.0);
+    }
+
+    let (water_body_map, water_bodies_list) = water_bodies::detect_water_bodies(&heightmap);
+    let (zlevels, surface_z) = zlevel::generate_zlevels(&heightmap);
+
+    WorldData::new(
+        surface.seed,
+        surface.scale,
+        heightmap,
+        temperature,
+        moisture,
+        biomes,
+        surface.stress_map.clone(),
+        surface.plate_map.clone(),
+        surface.plates.clone(),
+        None,
+        water_body_map,
+        water_bodies_list,
+        zlevels,
+        surface_z,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Whether a surface biome is notable enough to anchor a portal
+fn is_portal_anchor(biome: ExtendedBiome) -> bool {
+    biome.is_unique() || biome.is_ultra_rare() || biome.category() == BiomeCategory::Mystical
+}
+
+/// Place portals from the surface to each mirror plane at notable landmarks
+pub fn place_portals(surface: &WorldData, config: &PlanarConfig, seed: u64) -> Vec<Portal> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x9047A15));
+
+    let mut candidates: Vec<(usize, usize, ExtendedBiome)> = surface
+        .biomes
+        .iter()
+        .filter(|&(x, y, &b)| is_portal_anchor(b) && *surface.heightmap.get(x, y) >= 0.0)
+        .map(|(x, y, &b)| (x, y, b))
+        .collect();
+    candidates.shuffle(&mut rng);
+
+    // Unique biomes always get first pick
+    candidates.sort_by_key(|&(_, _, b)| !b.is_unique());
+
+    let spacing_sq = (config.min_spacing * config.min_spacing) as i64;
+    let mut portals: Vec<Portal> = Vec::new();
+
+    for &plane in config.planes.iter().filter(|&&p| p != PlaneKind::Surface) {
+        let mut placed = 0;
+        for &(x, y, anchor) in &candidates {
+            if placed >= config.portals_per_plane {
+                break;
+            }
+            let too_close = portals.iter().any(|p| {
+                let dx = (p.x as i64 - x as i64).abs();
+                let dx = dx.min(surface.width as i64 - dx);
+                let dy = p.y as i64 - y as i64;
+                dx * dx + dy * dy < spacing_sq
+            });
+            if too_close {
+                continue;
+            }
+            portals.push(Portal {
+                x,
+                y,
+                from: PlaneKind::Surface,
+                to: plane,
+                anchor,
+                name: format!("{} Gate of the {}", anchor.display_name(), plane.display_name()),
+            });
+            placed += 1;
+        }
+    }
+
+    portals
+}
+
+/// Let monsters and factions cross at portals, recording events in the surface
+/// timeline and moving crossing lairs into the destination plane's history
+#[cfg(feature = "history")]
+pub fn apply_portal_crossings(
+    surface_history: &mut WorldHistory,
+    plane_histories: &mut [(PlaneKind, WorldHistory)],
+    portals: &[Portal],
+    seed: u64,
+) -> Vec<PortalCrossing> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0xC405_5100));
+    let year = surface_history.timeline.current_era().map(|e| e.end).unwrap_or(Year(0));
+    let mut crossings = Vec::new();
+    let radius_sq = (CROSSING_RADIUS * CROSSING_RADIUS) as i64;
+
+    for (index, portal) in portals.iter().enumerate() {
+        let Some((_, dest)) = plane_histories.iter_mut().find(|(k, _)| *k == portal.to) else {
+            continue;
+        };
+
+        // Factions whose territory holds the portal discover it
+        if let Some(faction) = surface_history.faction_at(portal.x, portal.y) {
+            let faction_id = faction.id;
+            let description = format!("{} opened the {} and walked into the {}", faction.name, portal.name, portal.to.display_name());
+            let id = surface_history.timeline.new_id();
+            surface_history.timeline.add_event(HistoricalEvent {
+                id,
+                year,
+                event_type: EventType::GreatDiscovery,
+                faction: Some(faction_id),
+                other_faction: None,
+                location: Some((portal.x, portal.y)),
+                settlement: None,
+                name: format!("Opening of the {}", portal.name),
+                description: description.clone(),
+                casualties: 0,
+                has_evidence: false,
+            });
+            crossings.push(PortalCrossing { portal: index, faction: Some(faction_id), lair: None, description });
+        }
+
+        // Nearby monsters may slip through into the other plane
+        let mut nearby: Vec<LairId> = surface_history
+            .monsters
+            .active_lairs()
+            .filter(|l| {
+                let dx = l.x as i64 - portal.x as i64;
+                let dy = l.y as i64 - portal.y as i64;
+                dx * dx + dy * dy <= radius_sq
+            })
+            .map(|l| l.id)
+            .collect();
+        nearby.sort_by_key(|id| id.0);
+
+        for lair_id in nearby {
+            if !rng.gen_bool(LAIR_CROSSING_CHANCE) {
+                continue;
+            }
+            let Some(lair) = surface_history.monsters.lairs.get_mut(&lair_id) else {
+                continue;
+            };
+            lair.active = false;
+
+            let mut crossed = lair.clone();
+            crossed.id = dest.monsters.new_id();
+            crossed.hoard.clear();
+            crossed.hoard_sources.clear();
+            dest.monsters.add(crossed);
+
+            let description = format!(
+                "The {} of {} fled through the {} into the {}",
+                lair.species.name(),
+                lair.name,
+                portal.name,
+                portal.to.display_name()
+            );
+            let id = surface_history.timeline.new_id();
+            surface_history.timeline.add_event(HistoricalEvent {
+                id,
+                year,
+                event_type: EventType::MonsterInvasion,
+                faction: None,
+                other_faction: None,
+                location: Some((lair.x, lair.y)),
+                settlement: None,
+                name: format!("Crossing of {}", lair.name),
+                description: description.clone(),
+                casualties: 0,
+                has_evidence: false,
+            });
+            crossings.push(PortalCrossing { portal: index, faction: None, lair: Some(lair_id), description });
+        }
+    }
+
+    crossings
+}
+
+/// Generate mirror planes and portals for a surface world
+#[cfg_attr(not(feature = "history"), allow(unused_mut))]
+pub fn generate_planar_world(surface: WorldData, config: &PlanarConfig) -> PlanarWorld {
+    let seed = surface.seed;
+    let portals = place_portals(&surface, config, seed);
+
+    let mut mirrors: Vec<PlaneLayer> = config
+        .planes
+        .iter()
+        .filter(|&&k| k != PlaneKind::Surface)
+        .map(|&kind| PlaneLayer { kind, world: generate_mirror_plane(&surface, kind) })
+        .collect();
+
+    let mut surface = surface;
+    let mut crossings = Vec::new();
+    #[cfg(feature = "history")]
+    if config.history_crossings {
+        if let Some(ref mut history) = surface.history {
+            let mut plane_histories: Vec<(PlaneKind, WorldHistory)> = mirrors
+                .iter()
+                .map(|l| {
+                    let mut h = WorldHistory::empty();
+                    h.seed = seed;
+                    (l.kind, h)
+                })
+                .collect();
+            crossings = apply_portal_crossings(history, &mut plane_histories, &portals, seed);
+            for (layer, (_, history)) in mirrors.iter_mut().zip(plane_histories) {
+                layer.world.history = Some(history);
+            }
+        }
+    }
+
+    let mut layers = vec![PlaneLayer { kind: PlaneKind::Surface, world: surface }];
+    layers.append(&mut mirrors);
+
+    PlanarWorld { layers, portals, crossings }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::craters::{self, CraterParams};
+
+    #[test]
+    fn test_mirror_biomes() {
+        assert_eq!(mirror_biome(PlaneKind::Underworld, ExtendedBiome::TemperateForest, false), ExtendedBiome::DeadForest);
+        assert_eq!(mirror_biome(PlaneKind::Feywild, ExtendedBiome::TemperateForest, false), ExtendedBiome::BioluminescentForest);
+        assert_eq!(mirror_biome(PlaneKind::Underworld, ExtendedBiome::Ocean, true), ExtendedBiome::InkSea);
+        assert_eq!(mirror_biome(PlaneKind::Feywild, ExtendedBiome::DarkTower, false), ExtendedBiome::DarkTower);
+    }
+
+    #[test]
+    fn test_mirror_plane_shares_coastline() {
+        // Airless worlds are cheap to generate; carve some sea to test the coastline
+        let (mut surface, _) = craters::generate_airless_world(48, 24, 3, &CraterParams::default());
+        for x in 0..48 {
+            surface.heightmap.set(x, 0, -500.0);
+        }
+        let under = generate_mirror_plane(&surface, PlaneKind::Underworld);
+        assert_eq!((under.width, under.height), (surface.width, surface.height));
+        for (x, y, &h) in surface.heightmap.iter() {
+            assert_eq!(h < 0.0, *under.heightmap.get(x, y) < 0.0);
+        }
+    }
+
+    #[test]
+    fn test_portals_respect_spacing() {
+        let (mut surface, _) = craters::generate_airless_world(64, 32, 9, &CraterParams::default());
+        for (x, y, b) in surface.biomes.iter_mut() {
+            if (x + y) % 5 == 0 {
+                *b = ExtendedBiome::LeyNexus;
+            }
+        }
+        let config = PlanarConfig { portals_per_plane: 4, min_spacing: 10, ..PlanarConfig::default() };
+        let portals = place_portals(&surface, &config, 9);
+        assert!(!portals.is_empty());
+        assert!(portals.iter().any(|p| p.to == PlaneKind::Underworld));
+        assert!(portals.iter().any(|p| p.to == PlaneKind::Feywild));
+        for (i, a) in portals.iter().enumerate() {
+            for b in &portals[i + 1..] {
+                let dx = (a.x as i64 - b.x as i64).abs();
+                let dx = dx.min(64 - dx);
+                let dy = a.y as i64 - b.y as i64;
+                assert!(dx * dx + dy * dy >= 100);
+            }
+        }
+
+        let planar = generate_planar_world(surface, &config);
+        assert_eq!(planar.layers.len(), 3);
+        let p = &planar.portals[0];
+        assert_eq!(planar.destinations(PlaneKind::Surface, p.x, p.y), vec![p.to]);
+        assert_eq!(planar.destinations(p.to, p.x, p.y), vec![PlaneKind::Surface]);
+    }
+}
@@ -0,0 +1,297 @@
+//! Plate boundary classification
+//!
+//! Every tile where two plates meet is classified by how the plates move across
+//! it: converging (closing on each other), diverging (pulling apart) or sliding
+//! past (transform). The plates' relative velocity is resolved onto the
+//! boundary's local normal, so one long boundary can change character along its
+//! length as it bends.
+//!
+//! Boundary tiles are chained into polylines, one or more per connected stretch
+//! of the same type between the same two plates, for export and for placing
+//! earthquakes, volcanoes and the disasters history remembers.
+
+use std::collections::HashSet;
+
+use crate::tilemap::Tilemap;
+
+use super::types::{Plate, PlateId};
+
+/// Relative speed below which two plates are treated as locked together
+const LOCKED_SPEED: f32 = 1e-3;
+
+/// How two plates move across their boundary
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum BoundaryType {
+    Convergent,
+    Divergent,
+    Transform,
+}
+
+impl BoundaryType {
+    pub fn all() -> &'static [BoundaryType] {
+        &[BoundaryType::Convergent, BoundaryType::Divergent, BoundaryType::Transform]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BoundaryType::Convergent => "convergent",
+            BoundaryType::Divergent => "divergent",
+            BoundaryType::Transform => "transform",
+        }
+    }
+
+    /// Map colour: red where plates collide, blue where they part, yellow where they slide
+    pub fn color(&self) -> (u8, u8, u8) {
+        match self {
+            BoundaryType::Convergent => (220, 40, 30),
+            BoundaryType::Divergent => (40, 110, 230),
+            BoundaryType::Transform => (235, 200, 40),
+        }
+    }
+}
+
+/// A boundary tile, on the lower-numbered of the two plates
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BoundaryCell {
+    pub kind: BoundaryType,
+    /// The plate across the boundary
+    pub across: PlateId,
+    /// Closing speed across the boundary (negative where the plates part)
+    pub closing: f32,
+    /// Speed of the plates sliding past each other along it
+    pub sliding: f32,
+}
+
+/// A connected run of boundary of one type between the same two plates
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct BoundaryLine {
+    pub kind: BoundaryType,
+    pub plates: (PlateId, PlateId),
+    /// Mean relative speed of the plates along the line
+    pub rate: f32,
+    /// Tiles in order along the line (x wraps)
+    pub points: Vec<(usize, usize)>,
+}
+
+/// Classified plate boundaries
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct BoundaryMap {
+    pub cells: Tilemap<Option<BoundaryCell>>,
+    pub lines: Vec<BoundaryLine>,
+}
+
+impl BoundaryMap {
+    pub fn kind_at(&self, x: usize, y: usize) -> Option<BoundaryType> {
+        self.cells.get(x, y).map(|c| c.kind)
+    }
+
+    /// Boundary tiles of one type
+    pub fn count(&self, kind: BoundaryType) -> usize {
+        self.cells.iter().filter(|(_, _, c)| c.is_some_and(|c| c.kind == kind)).count()
+    }
+
+    /// The polylines as JSON: `{"lines": [{"kind", "plates", "rate", "points"}]}`
+    pub fn to_json(&self) -> String {
+        let lines: Vec<serde_json::Value> = self
+            .lines
+            .iter()
+            .map(|line| {
+                serde_json::json!({
+                    "kind": line.kind.name(),
+                    "plates": [line.plates.0 .0, line.plates.1 .0],
+                    "rate": line.rate,
+                    "points": line.points.iter().map(|&(x, y)| [x, y]).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        serde_json::to_string_pretty(&serde_json::json!({ "lines": lines })).unwrap_or_default()
+    }
+}
+
+/// Classify every plate boundary tile and trace the boundaries as polylines
+pub fn boundary_map(plate_map: &Tilemap<PlateId>, plates: &[Plate]) -> BoundaryMap {
+    let (width, height) = (plate_map.width, plate_map.height);
+    let velocity = |id: PlateId| plates.get(id.0 as usize).map_or((0.0, 0.0), |p| (p.velocity.x, p.velocity.y));
+
+    let mut cells = Tilemap::new_with(width, height, None);
+    for (x, y, &plate) in plate_map.iter() {
+        if plate.is_none() {
+            continue;
+        }
+        // Each boundary tile lies on the lower-numbered plate, so boundaries are one tile wide
+        let Some(across) = plate_map
+            .neighbors(x, y)
+            .into_iter()
+            .map(|(nx, ny)| *plate_map.get(nx, ny))
+            .filter(|other| !other.is_none() && other.0 > plate.0)
+            .min_by_key(|other| other.0)
+        else {
+            continue;
+        };
+
+        // Normal pointing across the boundary, from the neighbours on the far plate.
+        // Rows without a mirror on the map are left out so its edges don't tilt the
+        // normal, unless the far plate lies only there.
+        let normal = |mirrored: bool| {
+            let (mut nx, mut ny) = (0.0f32, 0.0f32);
+            for dy in -1i64..=1 {
+                let (row, mirror) = (y as i64 + dy, y as i64 - dy);
+                if row < 0 || row >= height as i64 || (mirrored && (mirror < 0 || mirror >= height as i64)) {
+                    continue;
+                }
+                for dx in -1i64..=1 {
+                    let ox = (x as i64 + dx).rem_euclid(width as i64) as usize;
+                    if *plate_map.get(ox, row as usize) == across {
+                        nx += dx as f32;
+                        ny += dy as f32;
+                    }
+                }
+            }
+            (nx, ny)
+        };
+        let (nx, ny) = match normal(true) {
+            n if n != (0.0, 0.0) => n,
+            _ => normal(false),
+        };
+        let length = nx.hypot(ny).max(f32::EPSILON);
+        let (nx, ny) = (nx / length, ny / length);
+
+        let ((ax, ay), (bx, by)) = (velocity(plate), velocity(across));
+        let (rx, ry) = (ax - bx, ay - by);
+        if rx.hypot(ry) < LOCKED_SPEED {
+            continue;
+        }
+        let closing = rx * nx + ry * ny;
+        let sliding = (rx * ny - ry * nx).abs();
+        let kind = if closing.abs() <= sliding {
+            BoundaryType::Transform
+        } else if closing > 0.0 {
+            BoundaryType::Convergent
+        } else {
+            BoundaryType::Divergent
+        };
+        cells.set(x, y, Some(BoundaryCell { kind, across, closing, sliding }));
+    }
+
+    let lines = trace_lines(plate_map, &cells);
+    BoundaryMap { cells, lines }
+}
+
+/// Chain boundary tiles into polylines. Each walk starts from the end of a run
+/// (the tile with the fewest unvisited neighbours of the same run) and steps to
+/// the next such tile until it runs out; branches left behind start lines of
+/// their own.
+fn trace_lines(plate_map: &Tilemap<PlateId>, cells: &Tilemap<Option<BoundaryCell>>) -> Vec<BoundaryLine> {
+    let mut visited = Tilemap::new_with(cells.width, cells.height, false);
+    let mut lines = Vec::new();
+
+    for y in 0..cells.height {
+        for x in 0..cells.width {
+            let Some(cell) = *cells.get(x, y) else {
+                continue;
+            };
+            if *visited.get(x, y) {
+                continue;
+            }
+            let plate = *plate_map.get(x, y);
+            let same_run = |(nx, ny): (usize, usize)| {
+                *plate_map.get(nx, ny) == plate
+                    && cells.get(nx, ny).is_some_and(|c| c.kind == cell.kind && c.across == cell.across)
+            };
+
+            // Gather the run, then walk it from its ends
+            let mut run = vec![(x, y)];
+            let mut stack = vec![(x, y)];
+            let mut seen = HashSet::from([(x, y)]);
+            while let Some(p) = stack.pop() {
+                for n in cells.neighbors_8(p.0, p.1) {
+                    if same_run(n) && seen.insert(n) {
+                        run.push(n);
+                        stack.push(n);
+                    }
+                }
+            }
+            let unvisited_degree = |visited: &Tilemap<bool>, (px, py): (usize, usize)| {
+                cells.neighbors_8(px, py).into_iter().filter(|&n| seen.contains(&n) && !*visited.get(n.0, n.1)).count()
+            };
+
+            loop {
+                let start = run
+                    .iter()
+                    .copied()
+                    .filter(|&(px, py)| !*visited.get(px, py))
+                    .min_by_key(|&p| unvisited_degree(&visited, p));
+                let Some(mut at) = start else {
+                    break;
+                };
+                let mut points = vec![at];
+                visited.set(at.0, at.1, true);
+                while let Some(next) = cells
+                    .neighbors_8(at.0, at.1)
+                    .into_iter()
+                    .filter(|&n| seen.contains(&n) && !*visited.get(n.0, n.1))
+                    .min_by_key(|&n| (unvisited_degree(&visited, n), n.0.abs_diff(at.0) + n.1.abs_diff(at.1)))
+                {
+                    visited.set(next.0, next.1, true);
+                    points.push(next);
+                    at = next;
+                }
+                let rate = points
+                    .iter()
+                    .filter_map(|&(px, py)| *cells.get(px, py))
+                    .map(|c| c.closing.hypot(c.sliding))
+                    .sum::<f32>()
+                    / points.len() as f32;
+                lines.push(BoundaryLine { kind: cell.kind, plates: (plate, cell.across), rate, points });
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plates::{PlateType, Vec2};
+
+    fn plate(id: u8, vx: f32, vy: f32) -> Plate {
+        Plate {
+            id: PlateId(id),
+            plate_type: PlateType::Continental,
+            velocity: Vec2::new(vx, vy),
+            base_elevation: 0.0,
+            color: [0, 0, 0],
+        }
+    }
+
+    #[test]
+    fn test_boundaries_classified_by_relative_motion() {
+        // Three vertical strips: plate 0 closes on plate 1 from the west, plate 2 slides
+        // north past plate 1, and plate 2 pulls away from plate 0 across the seam
+        let mut plate_map = Tilemap::new_with(30, 12, PlateId(0));
+        for y in 0..12 {
+            for x in 10..30 {
+                plate_map.set(x, y, PlateId(if x < 20 { 1 } else { 2 }));
+            }
+        }
+        let plates = [plate(0, 1.0, 0.0), plate(1, 0.0, 0.0), plate(2, -0.3, -1.0)];
+        let map = boundary_map(&plate_map, &plates);
+
+        assert_eq!(map.kind_at(9, 5), Some(BoundaryType::Convergent));
+        assert_eq!(map.kind_at(19, 5), Some(BoundaryType::Transform));
+        assert_eq!(map.kind_at(0, 5), Some(BoundaryType::Divergent));
+        assert_eq!(map.kind_at(5, 5), None);
+        assert_eq!(map.count(BoundaryType::Convergent), 12);
+
+        // One straight line per boundary, walked end to end
+        assert_eq!(map.lines.len(), 3);
+        let collision = map.lines.iter().find(|l| l.kind == BoundaryType::Convergent).unwrap();
+        assert_eq!(collision.plates, (PlateId(0), PlateId(1)));
+        assert_eq!(collision.points.len(), 12);
+        assert!(collision.points.windows(2).all(|w| w[0].1.abs_diff(w[1].1) == 1));
+
+        let json: serde_json::Value = serde_json::from_str(&map.to_json()).unwrap();
+        assert_eq!(json["lines"].as_array().unwrap().len(), 3);
+        assert!(json["lines"].as_array().unwrap().iter().any(|l| l["kind"] == "transform"));
+    }
+}
@@ -0,0 +1,334 @@
+//! Plate drift over geological time
+//!
+//! The generated plates are a snapshot. Drift runs them backwards through
+//! their history: each epoch every plate slides along its velocity and turns
+//! a little about its centre. Where plates pile onto the same ground crust is
+//! raised, where they pull apart a rift opens, and both travel with the plate
+//! afterwards. Older belts are worn down each epoch, so a range raised early
+//! ends up as a low, broad highland far from the present boundaries while a
+//! young one still stands sharp along them.
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::tilemap::Tilemap;
+
+use super::stress::smooth_stress;
+use super::types::{Plate, PlateId, PlateType};
+
+/// Uplift added by one epoch of continent-continent collision
+const COLLISION_UPLIFT: f32 = 1.0;
+
+/// Uplift added where oceanic crust dives under a continent
+const SUBDUCTION_UPLIFT: f32 = 0.6;
+
+/// Uplift added where two oceanic plates meet (island arcs)
+const ARC_UPLIFT: f32 = 0.3;
+
+/// Stress laid down by fully young uplift
+const YOUNG_UPLIFT_STRESS: f32 = 0.8;
+
+/// Divergent stress laid down by a fully open rift
+const RIFT_STRESS: f32 = 0.5;
+
+/// How far and how long the plates drift
+#[derive(Clone, Debug)]
+pub struct DriftParams {
+    /// Geological timesteps simulated
+    pub epochs: usize,
+    /// Distance moved per epoch at unit velocity, as a share of the map width
+    pub step: f32,
+    /// Largest turn (radians) of a plate per epoch
+    pub max_rotation: f32,
+    /// Share of old uplift and rift relief worn away each epoch
+    pub erosion_per_epoch: f32,
+}
+
+impl Default for DriftParams {
+    fn default() -> Self {
+        Self { epochs: 8, step: 0.012, max_rotation: 0.01, erosion_per_epoch: 0.2 }
+    }
+}
+
+/// What drift left behind on each tile
+#[derive(Clone, Debug)]
+pub struct UpliftHistory {
+    /// Accumulated uplift (0-1)
+    pub uplift: Tilemap<f32>,
+    /// Accumulated rifting (0-1)
+    pub rift: Tilemap<f32>,
+    /// Age of the uplift: 0 = raised in the last epoch, near 1 = the first
+    pub age: Tilemap<f32>,
+    pub epochs: usize,
+}
+
+impl UpliftHistory {
+    /// Uplift still standing as a sharp range
+    pub fn young(&self, x: usize, y: usize) -> f32 {
+        *self.uplift.get(x, y) * (1.0 - *self.age.get(x, y))
+    }
+
+    /// Uplift worn down into old highlands
+    pub fn old(&self, x: usize, y: usize) -> f32 {
+        *self.uplift.get(x, y) * *self.age.get(x, y)
+    }
+
+    /// Add young belts as convergent stress and rifts as divergent stress,
+    /// keeping whichever magnitude is larger on each tile
+    pub fn apply_to_stress(&self, stress_map: &mut Tilemap<f32>) {
+        for (x, y, stress) in stress_map.iter_mut() {
+            let lift = self.young(x, y) * YOUNG_UPLIFT_STRESS;
+            let pull = -*self.rift.get(x, y) * RIFT_STRESS;
+            if lift > *stress {
+                *stress = lift;
+            }
+            if pull < *stress && -pull > lift {
+                *stress = pull;
+            }
+        }
+    }
+
+    /// Share of tiles carrying noticeable uplift and rifting
+    pub fn coverage(&self) -> (f32, f32) {
+        let tiles = (self.uplift.width * self.uplift.height).max(1) as f32;
+        let uplifted = self.uplift.iter().filter(|(_, _, &u)| u > 0.1).count() as f32;
+        let rifted = self.rift.iter().filter(|(_, _, &r)| r > 0.1).count() as f32;
+        (uplifted / tiles, rifted / tiles)
+    }
+}
+
+/// Crust carried along with a tile as its plate moves
+#[derive(Clone, Copy, Default)]
+struct Crust {
+    uplift: f32,
+    /// Uplift weighted by the epoch it was raised in
+    raised_at: f32,
+    rift: f32,
+}
+
+/// Drift the plates over `params.epochs` epochs. Returns the final plate map
+/// and the uplift history; plates keep their type and velocity.
+pub fn simulate_drift(
+    plate_map: &Tilemap<PlateId>,
+    plates: &[Plate],
+    params: &DriftParams,
+    rng: &mut ChaCha8Rng,
+) -> (Tilemap<PlateId>, UpliftHistory) {
+    let (width, height) = (plate_map.width, plate_map.height);
+    let spin: Vec<f32> = plates.iter().map(|_| rng.gen_range(-1.0..=1.0) * params.max_rotation).collect();
+    let step = params.step * width as f32;
+    let reach = plates.iter().map(|p| p.velocity.length()).fold(0.0f32, f32::max) * step;
+    let search = (reach.ceil() as usize + 1).max(2);
+    let keep = 1.0 - params.erosion_per_epoch.clamp(0.0, 1.0);
+
+    let mut owner = plate_map.clone();
+    let mut crust = Tilemap::new_with(width, height, Crust::default());
+
+    for epoch in 0..params.epochs {
+        let centres = plate_centres(&owner, plates.len());
+        let mut claims: Tilemap<Vec<(PlateId, usize, usize)>> = Tilemap::new_with(width, height, Vec::new());
+
+        // Each plate claims the tiles its moved footprint covers
+        for (x, y, claimants) in claims.iter_mut() {
+            for (plate, centre) in plates.iter().zip(&centres) {
+                let Some((cx, cy)) = *centre else { continue };
+                let (dx, dy) = (plate.velocity.x * step, plate.velocity.y * step);
+                // Undo this epoch's turn and slide to find where the tile came from
+                let rx = wrapped_offset(x as f32 - dx - cx, width as f32);
+                let ry = y as f32 - dy - cy;
+                let (sin, cos) = (-spin[plate.id.0 as usize]).sin_cos();
+                let sx = (cx + rx * cos - ry * sin).round() as i32;
+                let sy = (cy + rx * sin + ry * cos).round() as i32;
+                if sy < 0 || sy >= height as i32 {
+                    continue;
+                }
+                let sx = sx.rem_euclid(width as i32) as usize;
+                if *owner.get(sx, sy as usize) == plate.id {
+                    claimants.push((plate.id, sx, sy as usize));
+                }
+            }
+        }
+
+        let mut next_owner = owner.clone();
+        let mut next_crust = Tilemap::new_with(width, height, Crust::default());
+        for (x, y, claimants) in claims.iter() {
+            let Some(&(winner, sx, sy)) = claimants.iter().min_by_key(|(id, _, _)| {
+                let continental = plates[id.0 as usize].plate_type == PlateType::Continental;
+                (!continental, *id != *owner.get(x, y), id.0)
+            }) else {
+                continue;
+            };
+            let mut carried = *crust.get(sx, sy);
+            if claimants.len() > 1 {
+                let continents = claimants
+                    .iter()
+                    .filter(|(id, _, _)| plates[id.0 as usize].plate_type == PlateType::Continental)
+                    .count();
+                let lift = match continents {
+                    0 => ARC_UPLIFT,
+                    1 => SUBDUCTION_UPLIFT,
+                    _ => COLLISION_UPLIFT,
+                };
+                carried.uplift += lift;
+                carried.raised_at += lift * epoch as f32;
+            }
+            next_owner.set(x, y, winner);
+            next_crust.set(x, y, carried);
+        }
+
+        // Unclaimed ground is new crust: it joins the nearest plate, and is a
+        // rift if more than one plate is pulling away around it
+        for y in 0..height {
+            for x in 0..width {
+                if !claims.get(x, y).is_empty() {
+                    continue;
+                }
+                let (nearest, plates_around) = nearest_claim(&claims, x, y, search);
+                if let Some(id) = nearest {
+                    next_owner.set(x, y, id);
+                }
+                if plates_around > 1 {
+                    next_crust.get_mut(x, y).rift += 1.0;
+                }
+            }
+        }
+
+        for (_, _, c) in next_crust.iter_mut() {
+            c.uplift *= keep;
+            c.raised_at *= keep;
+            c.rift *= keep;
+        }
+        owner = next_owner;
+        crust = next_crust;
+    }
+
+    let mut uplift = Tilemap::new_with(width, height, 0.0f32);
+    let mut rift = Tilemap::new_with(width, height, 0.0f32);
+    let mut age = Tilemap::new_with(width, height, 0.0f32);
+    let last = params.epochs.max(1) as f32;
+    for (x, y, c) in crust.iter() {
+        uplift.set(x, y, 1.0 - (-c.uplift).exp());
+        rift.set(x, y, 1.0 - (-c.rift).exp());
+        if c.uplift > 0.0 {
+            age.set(x, y, (1.0 - (c.raised_at / c.uplift + 1.0) / last).clamp(0.0, 1.0));
+        }
+    }
+
+    // Belts are a few tiles wide, not single lines of collided tiles
+    let radius = (width / 128).max(2);
+    let soften = |map: &Tilemap<f32>, gain: f32| {
+        let mut soft = smooth_stress(map, radius, radius as f32 * 0.6);
+        for (_, _, v) in soft.iter_mut() {
+            *v = (*v * gain).min(1.0);
+        }
+        soft
+    };
+    let history = UpliftHistory {
+        uplift: soften(&uplift, 1.5),
+        rift: soften(&rift, 1.5),
+        age: soften(&age, 1.0),
+        epochs: params.epochs,
+    };
+    (owner, history)
+}
+
+/// Centre of each plate, averaging x around the wrapping seam (None for
+/// plates with no tiles left)
+fn plate_centres(plate_map: &Tilemap<PlateId>, count: usize) -> Vec<Option<(f32, f32)>> {
+    let width = plate_map.width as f32;
+    let mut sums = vec![(0.0f32, 0.0f32, 0.0f32, 0usize); count];
+    for (x, y, id) in plate_map.iter() {
+        if let Some(sum) = sums.get_mut(id.0 as usize) {
+            let angle = x as f32 / width * std::f32::consts::TAU;
+            sum.0 += angle.cos();
+            sum.1 += angle.sin();
+            sum.2 += y as f32;
+            sum.3 += 1;
+        }
+    }
+    sums.into_iter()
+        .map(|(cos, sin, y, n)| {
+            (n > 0).then(|| {
+                let angle = sin.atan2(cos).rem_euclid(std::f32::consts::TAU);
+                (angle / std::f32::consts::TAU * width, y / n as f32)
+            })
+        })
+        .collect()
+}
+
+/// Offset folded into the nearest copy across the wrapping seam
+fn wrapped_offset(offset: f32, width: f32) -> f32 {
+    (offset + width / 2.0).rem_euclid(width) - width / 2.0
+}
+
+/// Closest claiming plate within `radius` tiles, and how many plates claim around the tile
+fn nearest_claim(
+    claims: &Tilemap<Vec<(PlateId, usize, usize)>>,
+    x: usize,
+    y: usize,
+    radius: usize,
+) -> (Option<PlateId>, usize) {
+    let r = radius as i32;
+    let mut nearest: Option<(i32, PlateId)> = None;
+    let mut seen: Vec<PlateId> = Vec::new();
+    for dy in -r..=r {
+        let ny = y as i32 + dy;
+        if ny < 0 || ny >= claims.height as i32 {
+            continue;
+        }
+        for dx in -r..=r {
+            let nx = (x as i32 + dx).rem_euclid(claims.width as i32) as usize;
+            for &(id, _, _) in claims.get(nx, ny as usize) {
+                let distance = dx * dx + dy * dy;
+                if nearest.is_none_or(|(d, _)| distance < d) {
+                    nearest = Some((distance, id));
+                }
+                if !seen.contains(&id) {
+                    seen.push(id);
+                }
+            }
+        }
+    }
+    (nearest.map(|(_, id)| id), seen.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plates::Vec2;
+    use rand::SeedableRng;
+
+    fn plate(id: u8, continental: bool, vx: f32) -> Plate {
+        let mut rng = ChaCha8Rng::seed_from_u64(id as u64);
+        let mut plate = Plate::new_with_type(PlateId(id), &mut rng, continental);
+        plate.velocity = Vec2::new(vx, 0.0);
+        plate
+    }
+
+    #[test]
+    fn test_drift_raises_collisions_and_opens_rifts() {
+        // Three bands: the middle continent is rammed from the west and
+        // abandoned by the eastern plate moving away
+        let mut plate_map = Tilemap::new_with(96, 48, PlateId(0));
+        for (x, _, id) in plate_map.iter_mut() {
+            *id = PlateId(match x {
+                0..=31 => 0,
+                32..=63 => 1,
+                _ => 2,
+            });
+        }
+        let plates = vec![plate(0, true, 0.5), plate(1, true, 0.0), plate(2, false, 0.5)];
+        let params = DriftParams { step: 0.05, max_rotation: 0.0, ..DriftParams::default() };
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let (drifted, history) = simulate_drift(&plate_map, &plates, &params, &mut rng);
+
+        assert!(history.uplift.get(32, 24) > history.uplift.get(48, 24));
+        assert!(*history.rift.get(66, 24) > 0.1);
+        assert!(history.age.iter().all(|(_, _, &a)| (0.0..=1.0).contains(&a)));
+        assert!(drifted.iter().any(|(x, y, id)| id != plate_map.get(x, y)));
+
+        let mut stress = Tilemap::new_with(96, 48, 0.0f32);
+        history.apply_to_stress(&mut stress);
+        assert!(*stress.get(32, 24) > 0.0 && *stress.get(66, 24) < 0.0);
+    }
+}
@@ -7,6 +7,8 @@ use rand_chacha::ChaCha8Rng;
 
 use crate::tilemap::Tilemap;
 
+use super::mask::ContinentMask;
+use super::style::WorldStyle;
 use super::types::{Plate, PlateId};
 
 /// Entry in the priority queue for plate expansion.
@@ -80,6 +82,10 @@ fn domain_warp(
     (x + warp_x * warp_strength, y + warp_y * warp_strength)
 }
 
+/// How strongly a styled layout holds plates to it (extra expansion cost, as a
+/// multiple of the normal cost, for a plate growing into the wrong zone)
+const LAYOUT_PULL: f32 = 2.0;
+
 /// Plate size distribution modes for variety in generation
 #[derive(Clone, Copy, Debug)]
 enum PlateSizeMode {
@@ -96,11 +102,15 @@ enum PlateSizeMode {
 /// Generate tectonic plates using fractal noise-modulated flood-fill.
 /// Uses fBm and domain warping for realistic, self-similar coastlines.
 /// Includes 4 border plates (oceanic) at map edges to create natural coastlines.
-/// Randomly varies plate sizes for natural variety.
+/// Randomly varies plate sizes for natural variety; a `WorldStyle` other than
+/// `Random` fixes the size mix and gathers the continents into its layout; a
+/// continent mask seeds the continents on its land and sizes them to it.
 pub fn generate_plates(
     width: usize,
     height: usize,
     num_plates: Option<usize>,
+    style: WorldStyle,
+    mask: Option<&ContinentMask>,
     rng: &mut ChaCha8Rng,
 ) -> (Tilemap<PlateId>, Vec<Plate>) {
     // Border margin for edge plates
@@ -108,16 +118,22 @@ pub fn generate_plates(
 
     // 4 border plates (always oceanic) + interior plates
     let num_border_plates: usize = 4;
-    let num_interior_plates: usize = num_plates.unwrap_or_else(|| rng.gen_range(6..=15));
+    let num_interior_plates: usize = num_plates.unwrap_or_else(|| style.plate_count(rng));
     let total_plates = num_border_plates + num_interior_plates;
 
     // Randomly select a size distribution mode
-    let size_mode = match rng.gen_range(0..100) {
+    let random_mode = match rng.gen_range(0..100) {
         0..=20 => PlateSizeMode::Supercontinent,  // 20% chance
         21..=45 => PlateSizeMode::MajorMinor,     // 25% chance
         46..=70 => PlateSizeMode::Chaotic,        // 25% chance
         _ => PlateSizeMode::Balanced,             // 30% chance
     };
+    let size_mode = match style {
+        WorldStyle::Random => random_mode,
+        WorldStyle::Pangaea => PlateSizeMode::Supercontinent,
+        WorldStyle::DualContinents => PlateSizeMode::MajorMinor,
+        WorldStyle::Archipelago | WorldStyle::EquatorialBand | WorldStyle::PolarContinents => PlateSizeMode::Balanced,
+    };
 
     // Boundary noise for fBm - creates fractal coastlines
     let boundary_noise = Perlin::new(1).set_seed(rng.gen());
@@ -164,7 +180,11 @@ pub fn generate_plates(
         }
         PlateSizeMode::MajorMinor => {
             // 2-3 major plates, rest small
-            let num_major = rng.gen_range(2..=3.min(num_interior_plates));
+            let num_major = if style == WorldStyle::DualContinents {
+                2.min(num_interior_plates)
+            } else {
+                rng.gen_range(2..=3.min(num_interior_plates))
+            };
             let mut major_indices: Vec<usize> = (0..num_interior_plates).collect();
             // Shuffle and take first num_major as major plates
             for i in 0..num_major {
@@ -244,15 +264,67 @@ pub fn generate_plates(
         heap.push(ExpansionCell { x: width - 1, y, plate_id: PlateId(3), priority: 0.0 });
     }
 
+    // A styled layout seeds its would-be continents around the style's anchors
+    // (the largest plates, or for an archipelago the smallest) and keeps the
+    // rest away from them; a continent mask does the same with its land
+    let target_land_fraction = mask.map_or_else(|| style.land_fraction(), |m| m.land_fraction().clamp(0.05, 0.8));
+    let anchored = ((num_interior_plates as f64 * target_land_fraction * 1.4).round() as usize).clamp(1, num_interior_plates);
+    let anchors = style.anchors(anchored, rng);
+    let laid_out = !anchors.is_empty() || mask.is_some();
+    let mut by_size: Vec<usize> = (0..num_interior_plates).collect();
+    by_size.sort_by(|&a, &b| plate_bias[num_border_plates + a].total_cmp(&plate_bias[num_border_plates + b]));
+    if style == WorldStyle::Archipelago {
+        by_size.reverse();
+    }
+    let mut is_anchored = vec![false; total_plates];
+    for &i in by_size.iter().take(anchored) {
+        is_anchored[num_border_plates + i] = laid_out;
+    }
+    let land_weight = |u: f64, v: f64| match mask {
+        Some(mask) => mask.weight(u, v),
+        None => anchors.iter().map(|a| a.weight(u, v)).fold(0.0, f64::max),
+    };
+    let to_tile = |u: f64, v: f64| {
+        (
+            ((u * width as f64) as usize).clamp(border_margin, width - border_margin - 1),
+            ((v * height as f64) as usize).clamp(border_margin, height - border_margin - 1),
+        )
+    };
+
     // Seed interior plates (starting at index 4) - away from edges
     // Plates with lower bias get extra seed points for faster coverage
     for i in 0..num_interior_plates {
         let id = PlateId((num_border_plates + i) as u8);
         let num_seeds = 1 + extra_seeds[i];
+        let rank = by_size.iter().position(|&p| p == i).unwrap_or(0);
 
         for _ in 0..num_seeds {
-            let x = rng.gen_range(border_margin..width - border_margin);
-            let y = rng.gen_range(border_margin..height - border_margin);
+            let (x, y) = if !laid_out {
+                (rng.gen_range(border_margin..width - border_margin), rng.gen_range(border_margin..height - border_margin))
+            } else if rank < anchored && mask.is_some() {
+                // Masked land: a few tries for a spot the mask paints bright
+                let mut spot = (0.0, 0.0);
+                for _ in 0..20 {
+                    spot = (rng.gen::<f64>(), rng.gen::<f64>());
+                    if rng.gen::<f64>() < land_weight(spot.0, spot.1) {
+                        break;
+                    }
+                }
+                to_tile(spot.0, spot.1)
+            } else if rank < anchored {
+                let (u, v) = anchors[rank % anchors.len()].sample(rng);
+                to_tile(u, v)
+            } else {
+                // Open ocean: a few tries for a spot far from every anchor
+                let mut spot = (0.0, 0.0);
+                for _ in 0..20 {
+                    spot = (rng.gen::<f64>(), rng.gen::<f64>());
+                    if rng.gen::<f64>() > land_weight(spot.0, spot.1) {
+                        break;
+                    }
+                }
+                to_tile(spot.0, spot.1)
+            };
 
             // Only set if not already claimed
             if plate_map.get(x, y).is_none() {
@@ -296,7 +368,14 @@ pub fn generate_plates(
 
                 // Priority: distance + fractal noise (stronger noise influence)
                 let noise_cost = (boundary_fbm + local_fbm + 1.0) as f32 * 2.5;
-                let priority = cell.priority + (0.5 + noise_cost + jitter) * bias;
+                let mut priority = cell.priority + (0.5 + noise_cost + jitter) * bias;
+
+                // Styled layouts: continents grow slowly out of their anchors, oceans into them
+                if laid_out {
+                    let w = land_weight(fx, fy) as f32;
+                    let shape = if is_anchored[plate_idx] { 1.0 - w } else { w };
+                    priority += (0.5 + noise_cost) * bias * LAYOUT_PULL * shape;
+                }
 
                 heap.push(ExpansionCell {
                     x: nx,
@@ -318,7 +397,6 @@ pub fn generate_plates(
 
     // Target land percentage (~35% like Earth + margin for erosion/sea level)
     let total_cells = width * height;
-    let target_land_fraction = 0.35;
     let target_land_cells = (total_cells as f64 * target_land_fraction) as usize;
 
     // Sort interior plates by actual area (largest first)
@@ -330,6 +408,18 @@ pub fn generate_plates(
         .collect();
     interior_areas.sort_by(|a, b| b.1.cmp(&a.1));
 
+    // A styled layout offers the plates lying closest to its anchors first
+    if laid_out {
+        let mut weight_sums = vec![0.0f64; total_plates];
+        for (x, y, &id) in plate_map.iter() {
+            if !id.is_none() {
+                weight_sums[id.0 as usize] += land_weight(x as f64 / width as f64, y as f64 / height as f64);
+            }
+        }
+        let mean_weight = |&(idx, area): &(usize, usize)| weight_sums[idx] / area.max(1) as f64;
+        interior_areas.sort_by(|a, b| mean_weight(b).total_cmp(&mean_weight(a)));
+    }
+
     // Select plates to be continental, trying to match target land coverage
     let mut continental_set: std::collections::HashSet<usize> = std::collections::HashSet::new();
     let mut current_land = 0usize;
@@ -367,3 +457,125 @@ pub fn generate_plates(
 
     (plate_map, plates)
 }
+
+/// Resolution of the reference grid used by `generate_plates_invariant`
+pub const REFERENCE_WIDTH: usize = 256;
+pub const REFERENCE_HEIGHT: usize = 128;
+
+/// Boundary jitter when resampling the reference grid, in reference tiles
+const RESAMPLE_JITTER: f64 = 0.75;
+
+/// Generate tectonic plates whose layout does not depend on the output resolution.
+///
+/// Plates are grown on a fixed `REFERENCE_WIDTH` x `REFERENCE_HEIGHT` grid and then
+/// resampled in normalized world space, so the same seed yields the same continents
+/// whether the map is 128x64 or 2048x1024. Boundaries are jittered with noise defined
+/// in world space to avoid blocky edges when upsampling.
+pub fn generate_plates_invariant(
+    width: usize,
+    height: usize,
+    num_plates: Option<usize>,
+    style: WorldStyle,
+    mask: Option<&ContinentMask>,
+    rng: &mut ChaCha8Rng,
+) -> (Tilemap<PlateId>, Vec<Plate>) {
+    let (reference, plates) = generate_plates(REFERENCE_WIDTH, REFERENCE_HEIGHT, num_plates, style, mask, rng);
+    if width == REFERENCE_WIDTH && height == REFERENCE_HEIGHT {
+        return (reference, plates);
+    }
+
+    let jitter_noise = Perlin::new(3).set_seed(rng.gen());
+    let mut plate_map = Tilemap::new_with(width, height, PlateId::NONE);
+
+    for y in 0..height {
+        for x in 0..width {
+            let u = (x as f64 + 0.5) / width as f64;
+            let v = (y as f64 + 0.5) / height as f64;
+
+            let jx = fbm(&jitter_noise, u * 24.0, v * 12.0, 4, 0.5, 2.0) * RESAMPLE_JITTER;
+            let jy = fbm(&jitter_noise, u * 24.0 + 57.0, v * 12.0 + 31.0, 4, 0.5, 2.0) * RESAMPLE_JITTER;
+
+            let rx = (u * REFERENCE_WIDTH as f64 + jx).floor() as i64;
+            let ry = (v * REFERENCE_HEIGHT as f64 + jy).floor() as i64;
+            let rx = rx.rem_euclid(REFERENCE_WIDTH as i64) as usize;
+            let ry = ry.clamp(0, REFERENCE_HEIGHT as i64 - 1) as usize;
+
+            plate_map.set(x, y, *reference.get(rx, ry));
+        }
+    }
+
+    (plate_map, plates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plates::PlateType;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_invariant_plates_match_across_resolutions() {
+        let (small, small_plates) = generate_plates_invariant(128, 64, Some(8), WorldStyle::Random, None, &mut ChaCha8Rng::seed_from_u64(9));
+        let (large, large_plates) = generate_plates_invariant(512, 256, Some(8), WorldStyle::Random, None, &mut ChaCha8Rng::seed_from_u64(9));
+
+        assert_eq!(small_plates.len(), large_plates.len());
+        for (a, b) in small_plates.iter().zip(&large_plates) {
+            assert_eq!(a.plate_type, b.plate_type);
+        }
+
+        // Same plate at the same world position away from boundaries
+        let agree = (0..64)
+            .flat_map(|y| (0..128).map(move |x| (x, y)))
+            .filter(|&(x, y)| small.get(x, y) == large.get(x * 4 + 2, y * 4 + 2))
+            .count();
+        assert!(agree as f32 / (128.0 * 64.0) > 0.9, "only {} tiles agree", agree);
+
+        // Mountain belts cover the same share of the planet at both sizes
+        let belts = |map: &Tilemap<PlateId>, plates: &[Plate]| {
+            let stress = crate::plates::calculate_stress_invariant(map, plates);
+            stress.iter().filter(|(_, _, &s)| s > 0.1).count() as f32 / (map.width * map.height) as f32
+        };
+        let (small_belts, large_belts) = (belts(&small, &small_plates), belts(&large, &large_plates));
+        assert!(small_belts < large_belts * 1.3 && large_belts < small_belts * 1.3, "belts cover {} vs {}", small_belts, large_belts);
+    }
+
+    #[test]
+    fn test_world_styles_place_continents() {
+        // Mean distance of continental tiles from the equator, as a fraction of the map height
+        let continental_latitude = |style: WorldStyle| {
+            let (map, plates) = generate_plates(256, 128, Some(10), style, None, &mut ChaCha8Rng::seed_from_u64(21));
+            let land: Vec<usize> = map
+                .iter()
+                .filter(|(_, _, id)| !id.is_none() && plates[id.0 as usize].plate_type == PlateType::Continental)
+                .map(|(_, y, _)| y)
+                .collect();
+            assert!(!land.is_empty(), "{:?} has no land", style);
+            land.iter().map(|&y| (y as f32 / 128.0 - 0.5).abs()).sum::<f32>() / land.len() as f32
+        };
+        let equatorial = continental_latitude(WorldStyle::EquatorialBand);
+        let polar = continental_latitude(WorldStyle::PolarContinents);
+        assert!(equatorial < 0.15 && polar > equatorial + 0.05, "equatorial {} polar {}", equatorial, polar);
+
+        // An archipelago spreads less land over more plates
+        let (_, islands) = generate_plates(256, 128, None, WorldStyle::Archipelago, None, &mut ChaCha8Rng::seed_from_u64(21));
+        assert!(islands.len() >= 4 + 18);
+        assert_eq!(WorldStyle::from_name("Dual_Continents"), Some(WorldStyle::DualContinents));
+    }
+
+    #[test]
+    fn test_continent_mask_places_land() {
+        // Land painted only on the western half of the map
+        let img = image::GrayImage::from_fn(64, 32, |x, y| image::Luma([if x < 32 && (6..26).contains(&y) { 255 } else { 0 }]));
+        let mask = ContinentMask::from_image(&image::DynamicImage::ImageLuma8(img));
+        assert!((mask.land_fraction() - 0.31).abs() < 0.05, "land fraction {}", mask.land_fraction());
+
+        let (map, plates) = generate_plates(256, 128, Some(12), WorldStyle::Random, Some(&mask), &mut ChaCha8Rng::seed_from_u64(5));
+        let land: Vec<usize> = map
+            .iter()
+            .filter(|(_, _, id)| !id.is_none() && plates[id.0 as usize].plate_type == PlateType::Continental)
+            .map(|(x, _, _)| x)
+            .collect();
+        let west = land.iter().filter(|&&x| x < 128).count();
+        assert!(!land.is_empty() && west as f32 / land.len() as f32 > 0.75, "{} of {} land tiles west", west, land.len());
+    }
+}
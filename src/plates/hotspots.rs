@@ -0,0 +1,242 @@
+//! Hotspot volcanism: mantle plumes fixed beneath moving plates
+//!
+//! A plume stays put while the plate slides over it, so every volcano it
+//! builds is carried off downstream and a new one grows over the plume. The
+//! result is a chain that gets older, lower and more worn with distance from
+//! the plume: a tall young island over it, older islands behind, then atolls
+//! and drowned seamounts (Hawaii-Emperor). Some plumes instead erupt in one
+//! vast outpouring and bury the land under a stepped basalt plateau (Deccan,
+//! Columbia River, Ontong Java).
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::tilemap::Tilemap;
+
+use super::types::{Plate, PlateId, PlateType};
+
+/// Summit elevation of the volcano standing over the plume
+const YOUNG_SUMMIT: f32 = 2400.0;
+
+/// Summit elevation of the oldest volcano in a chain (a drowned seamount)
+const OLD_SUMMIT: f32 = -900.0;
+
+/// Broad rise of the crust around an active plume
+const SWELL_HEIGHT: f32 = 350.0;
+
+/// Height a flood basalt stands above the ground it buried
+const PLATEAU_LIFT: f32 = 600.0;
+
+/// Thickness of one lava flow in a basalt plateau's stepped edges
+const TRAP_STEP: f32 = 80.0;
+
+/// Map tiles per plume when the count is left to the generator
+const TILES_PER_PLUME: usize = 8000;
+
+/// Where and how hotspots erupt
+#[derive(Clone, Debug)]
+pub struct HotspotParams {
+    /// Mantle plumes (0 = one per 8000 tiles, at least 2)
+    pub count: usize,
+    /// Volcanoes in a chain, oldest included
+    pub chain_length: usize,
+    /// Chance a plume under a continent floods it with basalt instead of
+    /// building a chain (oceanic plumes get a quarter of this)
+    pub plateau_chance: f32,
+}
+
+impl Default for HotspotParams {
+    fn default() -> Self {
+        Self { count: 0, chain_length: 8, plateau_chance: 0.6 }
+    }
+}
+
+/// What a plume built
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HotspotKind {
+    IslandChain,
+    BasaltPlateau,
+}
+
+/// A mantle plume and what it left on the surface
+#[derive(Clone, Debug)]
+pub struct Hotspot {
+    /// Tile over the plume today
+    pub x: usize,
+    pub y: usize,
+    pub kind: HotspotKind,
+    /// Volcano centres, youngest (over the plume) first; empty for plateaus
+    pub chain: Vec<(usize, usize)>,
+}
+
+/// Place the plumes. Chains trail downstream of the plume along its plate's
+/// motion and stop where the plate does; plates that don't move get none.
+pub fn place_hotspots(
+    plate_map: &Tilemap<PlateId>,
+    plates: &[Plate],
+    params: &HotspotParams,
+    rng: &mut ChaCha8Rng,
+) -> Vec<Hotspot> {
+    let (width, height) = (plate_map.width, plate_map.height);
+    let count = if params.count > 0 { params.count } else { (width * height / TILES_PER_PLUME).max(2) };
+    let spacing = volcano_radius(width) * 1.6;
+
+    let mut hotspots: Vec<Hotspot> = Vec::new();
+    for _ in 0..count * 20 {
+        if hotspots.len() >= count {
+            break;
+        }
+        let x = rng.gen_range(0..width);
+        let y = rng.gen_range(height / 10..height - height / 10);
+        let Some(plate) = plates.get(plate_map.get(x, y).0 as usize) else { continue };
+        let direction = plate.velocity.normalize();
+        if direction.length() == 0.0 {
+            continue;
+        }
+        // Plumes far enough apart that their chains don't merge
+        if hotspots.iter().any(|h| wrapped_distance(h.x, h.y, x, y, width) < spacing * 4.0) {
+            continue;
+        }
+        let chance = match plate.plate_type {
+            PlateType::Continental => params.plateau_chance,
+            PlateType::Oceanic => params.plateau_chance * 0.25,
+        };
+        if rng.gen::<f32>() < chance {
+            hotspots.push(Hotspot { x, y, kind: HotspotKind::BasaltPlateau, chain: Vec::new() });
+            continue;
+        }
+
+        let mut chain = Vec::new();
+        for k in 0..params.chain_length.max(1) {
+            // A little wander, as real plate motion is never perfectly steady
+            let along = k as f32 * spacing;
+            let drift = rng.gen_range(-0.3..0.3) * spacing;
+            let fx = x as f32 + direction.x * along - direction.y * drift;
+            let fy = y as f32 + direction.y * along + direction.x * drift;
+            if fy < 0.0 || fy >= height as f32 {
+                break;
+            }
+            let (cx, cy) = (fx.rem_euclid(width as f32) as usize % width, fy as usize);
+            if plate_map.get(cx, cy) != plate_map.get(x, y) {
+                break;
+            }
+            chain.push((cx, cy));
+        }
+        hotspots.push(Hotspot { x, y, kind: HotspotKind::IslandChain, chain });
+    }
+    hotspots
+}
+
+/// Raise the plumes' volcanoes and plateaus on the heightmap
+pub fn apply_hotspots(heightmap: &mut Tilemap<f32>, hotspots: &[Hotspot]) {
+    let width = heightmap.width;
+    let radius = volcano_radius(width);
+    for hotspot in hotspots {
+        match hotspot.kind {
+            HotspotKind::IslandChain => {
+                // The swell under the active end of the chain
+                raise(heightmap, hotspot.x, hotspot.y, radius * 3.0, |h, t| h + SWELL_HEIGHT * t * t);
+                let last = hotspot.chain.len().saturating_sub(1).max(1) as f32;
+                for (k, &(cx, cy)) in hotspot.chain.iter().enumerate() {
+                    let age = k as f32 / last;
+                    let summit = YOUNG_SUMMIT + (OLD_SUMMIT - YOUNG_SUMMIT) * age.powf(0.7);
+                    // Old volcanoes slump into broad, low shields
+                    let r = radius * (1.0 + age * 0.5);
+                    let base = *heightmap.get(cx, cy);
+                    if summit <= base {
+                        continue;
+                    }
+                    raise(heightmap, cx, cy, r, |h, t| h.max(base + (summit - base) * t.powf(1.6)));
+                }
+            }
+            HotspotKind::BasaltPlateau => {
+                let r = radius * 6.0;
+                let (mut ground, mut n) = (0.0, 0);
+                raise(heightmap, hotspot.x, hotspot.y, r, |h, _| {
+                    ground += h;
+                    n += 1;
+                    h
+                });
+                let ground = ground / n.max(1) as f32;
+                // Ocean floor needs far more lava to build a plateau (and it stays drowned)
+                let level = ground + if ground < 0.0 { PLATEAU_LIFT * 4.0 } else { PLATEAU_LIFT };
+                raise(heightmap, hotspot.x, hotspot.y, r, |h, t| {
+                    // Flat top, stepped flanks where successive flows end
+                    let cover = (t * 2.5).min(1.0);
+                    let flow = h + (level - h) * cover;
+                    let flow = level - ((level - flow) / TRAP_STEP).round() * TRAP_STEP;
+                    h.max(flow)
+                });
+            }
+        }
+    }
+}
+
+/// Radius of one volcano, in tiles
+fn volcano_radius(width: usize) -> f32 {
+    (width as f32 / 160.0).max(2.0)
+}
+
+fn wrapped_distance(x0: usize, y0: usize, x1: usize, y1: usize, width: usize) -> f32 {
+    let dx = (x0 as f32 - x1 as f32).abs();
+    let dx = dx.min(width as f32 - dx);
+    let dy = y0 as f32 - y1 as f32;
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Apply `f(height, t)` within `radius` of a tile, where t falls from 1 at
+/// the centre to 0 at the rim (x wraps)
+fn raise(heightmap: &mut Tilemap<f32>, cx: usize, cy: usize, radius: f32, mut f: impl FnMut(f32, f32) -> f32) {
+    let (width, height) = (heightmap.width as i32, heightmap.height as i32);
+    let reach = radius.ceil() as i32;
+    for dy in -reach..=reach {
+        let y = cy as i32 + dy;
+        if y < 0 || y >= height {
+            continue;
+        }
+        for dx in -reach..=reach {
+            let d = ((dx * dx + dy * dy) as f32).sqrt();
+            if d >= radius {
+                continue;
+            }
+            let x = (cx as i32 + dx).rem_euclid(width) as usize;
+            let h = heightmap.get_mut(x, y as usize);
+            *h = f(*h, 1.0 - d / radius);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plates::Vec2;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_hotspot_chains_age_downstream() {
+        // One eastward-moving ocean plate
+        let plate = Plate {
+            id: PlateId(0),
+            plate_type: PlateType::Oceanic,
+            velocity: Vec2::new(1.0, 0.0),
+            base_elevation: -4000.0,
+            color: [0, 0, 0],
+        };
+        let plate_map = Tilemap::new_with(320, 160, PlateId(0));
+        let params = HotspotParams { count: 3, chain_length: 6, plateau_chance: 0.0 };
+        let hotspots = place_hotspots(&plate_map, &[plate], &params, &mut ChaCha8Rng::seed_from_u64(5));
+        assert_eq!(hotspots.len(), 3);
+
+        let mut heightmap = Tilemap::new_with(320, 160, -4000.0f32);
+        apply_hotspots(&mut heightmap, &hotspots);
+        for hotspot in &hotspots {
+            assert_eq!(hotspot.kind, HotspotKind::IslandChain);
+            assert_eq!(hotspot.chain.len(), 6);
+            let summits: Vec<f32> = hotspot.chain.iter().map(|&(x, y)| *heightmap.get(x, y)).collect();
+            // A young island over the plume, a drowned seamount at the far end
+            assert!(summits[0] > 1000.0, "{:?}", summits);
+            assert!(summits[5] < 0.0 && summits[5] > -4000.0, "{:?}", summits);
+            assert!(summits.windows(2).all(|w| w[0] >= w[1] - 1.0), "{:?}", summits);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Hand-drawn continent masks for plate seeding
+//!
+//! A grayscale image marks where the continents should be: white for land,
+//! black for ocean, greys in between. The mask is resampled to a fixed grid
+//! in map fractions, so one drawing fits any world size, and it only biases
+//! the plates: continental plates are seeded on the bright areas, oceanic
+//! ones on the dark, and the flood-fill still draws its own coastlines.
+
+use image::imageops::FilterType;
+use image::DynamicImage;
+
+/// Resolution the mask is resampled to (x wraps, y = 0 north)
+const MASK_WIDTH: u32 = 256;
+const MASK_HEIGHT: u32 = 128;
+
+/// Brightness above which the mask counts as land
+const LAND_THRESHOLD: f32 = 0.5;
+
+/// Land weight of every point on the map, 1 for land and 0 for ocean
+#[derive(Clone, Debug)]
+pub struct ContinentMask {
+    values: Vec<f32>,
+}
+
+impl ContinentMask {
+    /// Resample an image's brightness into a mask
+    pub fn from_image(img: &DynamicImage) -> Self {
+        let luma = img.resize_exact(MASK_WIDTH, MASK_HEIGHT, FilterType::Triangle).to_luma8();
+        Self { values: luma.pixels().map(|p| p.0[0] as f32 / 255.0).collect() }
+    }
+
+    /// Load a mask image (any format the image crate reads, normally a grayscale PNG)
+    pub fn load(path: &str) -> Result<Self, String> {
+        let img = image::open(path).map_err(|e| e.to_string())?;
+        Ok(Self::from_image(&img))
+    }
+
+    /// Land weight at a map point given in fractions, interpolated between mask cells
+    pub fn weight(&self, u: f64, v: f64) -> f64 {
+        let (w, h) = (MASK_WIDTH as usize, MASK_HEIGHT as usize);
+        let fx = u.rem_euclid(1.0) * w as f64 - 0.5;
+        let fy = (v * h as f64 - 0.5).clamp(0.0, (h - 1) as f64);
+        let (x0, y0) = (fx.floor(), fy.floor());
+        let (tx, ty) = (fx - x0, fy - y0);
+        let x0 = (x0 as i64).rem_euclid(w as i64) as usize;
+        let x1 = (x0 + 1) % w;
+        let y0 = y0 as usize;
+        let y1 = (y0 + 1).min(h - 1);
+        let at = |x: usize, y: usize| self.values[y * w + x] as f64;
+        let top = at(x0, y0) * (1.0 - tx) + at(x1, y0) * tx;
+        let bottom = at(x0, y1) * (1.0 - tx) + at(x1, y1) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+
+    /// Share of the map the mask marks as land
+    pub fn land_fraction(&self) -> f64 {
+        self.values.iter().filter(|&&v| v > LAND_THRESHOLD).count() as f64 / self.values.len() as f64
+    }
+}
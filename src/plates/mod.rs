@@ -1,7 +1,17 @@
+pub mod boundaries;
+pub mod drift;
 pub mod generation;
+pub mod hotspots;
+pub mod mask;
 pub mod stress;
+pub mod style;
 pub mod types;
 
-pub use generation::generate_plates;
-pub use stress::{add_wiggle, calculate_stress, enhance_stress, smooth_stress, spread_stress};
+pub use boundaries::{boundary_map, BoundaryLine, BoundaryMap, BoundaryType};
+pub use drift::{simulate_drift, DriftParams, UpliftHistory};
+pub use generation::{generate_plates, generate_plates_invariant};
+pub use hotspots::{apply_hotspots, place_hotspots, Hotspot, HotspotKind, HotspotParams};
+pub use mask::ContinentMask;
+pub use stress::{add_wiggle, calculate_stress, calculate_stress_invariant, enhance_stress, smooth_stress, spread_stress};
+pub use style::WorldStyle;
 pub use types::{Plate, PlateId, PlateType, Vec2};
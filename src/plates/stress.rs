@@ -2,15 +2,31 @@ use noise::{NoiseFn, Perlin, Seedable};
 
 use crate::tilemap::Tilemap;
 
+use super::generation::REFERENCE_WIDTH;
 use super::types::{Plate, PlateId};
 
 /// Calculate the tectonic stress at each cell based on plate velocities.
 /// Stress width is proportional to relative velocity of converging plates.
 pub fn calculate_stress(plate_map: &Tilemap<PlateId>, plates: &[Plate]) -> Tilemap<f32> {
+    stress_field(plate_map, plates, plate_map.width)
+}
+
+/// Calculate stress for a scale-invariant world: spread radii are chosen in
+/// tiles of the `REFERENCE_WIDTH` plate grid and scaled to the output width,
+/// so mountain belts cover the same share of the planet at any resolution.
+/// Pair with `generate_plates_invariant`.
+pub fn calculate_stress_invariant(plate_map: &Tilemap<PlateId>, plates: &[Plate]) -> Tilemap<f32> {
+    stress_field(plate_map, plates, REFERENCE_WIDTH)
+}
+
+/// Shared stress spreading; radii are measured in tiles of a map
+/// `reference_width` wide (this map's own width for the classic behaviour)
+fn stress_field(plate_map: &Tilemap<PlateId>, plates: &[Plate], reference_width: usize) -> Tilemap<f32> {
     use std::collections::HashMap;
 
     let width = plate_map.width;
     let height = plate_map.height;
+    let tiles_per_reference = width as f32 / reference_width as f32;
 
     // Calculate stress and spread width between each pair of continental plates
     // (stress_value, spread_radius)
@@ -43,9 +59,9 @@ pub fn calculate_stress(plate_map: &Tilemap<PlateId>, plates: &[Plate]) -> Tilem
 
             // Spread radius based on relative velocity (faster = wider mountains)
             // Scale: velocity 1.0 -> ~8 pixels, velocity 3.0 -> ~24 pixels
-            let base_spread = (width / 64).max(4);
+            let base_spread = (reference_width / 64).max(4);
             let spread_radius = (base_spread as f32 * rel_speed * 0.8) as usize;
-            let spread_radius = spread_radius.clamp(2, width / 16);
+            let spread_radius = spread_radius.clamp(2, reference_width / 16);
 
             plate_pair_info.insert((i as u8, j as u8), (stress, spread_radius));
         }
@@ -92,19 +108,20 @@ pub fn calculate_stress(plate_map: &Tilemap<PlateId>, plates: &[Plate]) -> Tilem
 
     for (bx, by, stress, spread) in boundary_cells {
         // Add random variation to spread based on position
-        // Simple hash from position for deterministic randomness
-        let hash = ((bx * 73856093) ^ (by * 19349663)) as f32;
+        // Simple hash from (reference grid) position for deterministic randomness
+        let (rx, ry) = (bx * reference_width / width, by * reference_width / width);
+        let hash = ((rx * 73856093) ^ (ry * 19349663)) as f32;
         let variation = ((hash % 1000.0) / 1000.0) * 0.6 + 0.7; // Range: 0.7 to 1.3
         let spread = ((spread as f32) * variation) as usize;
-        let spread = spread.max(2);
+        let spread = spread.max(2) as f32 * tiles_per_reference;
 
         // Apply stress in a radius around the boundary cell
-        let spread_i = spread as i32;
+        let spread_i = spread.ceil() as i32;
 
         for dy in -spread_i..=spread_i {
             for dx in -spread_i..=spread_i {
                 let dist = ((dx * dx + dy * dy) as f32).sqrt();
-                if dist > spread as f32 {
+                if dist > spread {
                     continue;
                 }
 
@@ -113,7 +130,7 @@ pub fn calculate_stress(plate_map: &Tilemap<PlateId>, plates: &[Plate]) -> Tilem
 
                 // Gaussian falloff for sharp peak, smooth descent to ocean level
                 // exp(-k * t^2) gives a bell curve shape
-                let t = dist / spread as f32;
+                let t = dist / spread;
                 let k = 3.0; // Controls steepness - higher = sharper peak
                 let falloff = (-k * t * t).exp();
 
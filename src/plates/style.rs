@@ -0,0 +1,150 @@
+//! Continent layouts for plate seeding
+//!
+//! A world style steers where the large plates are seeded and which plates
+//! become continental, so a whole family of worlds shares a recognisable
+//! layout while every seed still draws its own coastlines:
+//!
+//! - **random**: the generator's own mix of plate sizes and land placement.
+//! - **pangaea**: one dominant plate carrying a single supercontinent.
+//! - **dual-continents**: two major continents on opposite sides of the globe.
+//! - **archipelago**: many small plates, little land, scattered island groups.
+//! - **equatorial-band**: continents strung along the equator, open polar seas.
+//! - **polar-continents**: land gathered in high latitudes around an equatorial ocean.
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+/// A continent layout
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum WorldStyle {
+    #[default]
+    Random,
+    Pangaea,
+    DualContinents,
+    Archipelago,
+    EquatorialBand,
+    PolarContinents,
+}
+
+/// Where a group of continental plates is seeded, in map fractions
+/// (x wraps, y = 0 north), with its spread along each axis
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Anchor {
+    pub u: f64,
+    pub v: f64,
+    pub spread_u: f64,
+    pub spread_v: f64,
+}
+
+impl Anchor {
+    /// Closeness of a map point to the anchor, 1 at its centre
+    pub fn weight(&self, u: f64, v: f64) -> f64 {
+        let du = (u - self.u).rem_euclid(1.0);
+        let du = du.min(1.0 - du) / self.spread_u;
+        let dv = (v - self.v) / self.spread_v;
+        (-(du * du + dv * dv)).exp()
+    }
+
+    /// A point drawn around the anchor
+    pub fn sample(&self, rng: &mut ChaCha8Rng) -> (f64, f64) {
+        // Sum of uniforms: a cheap bell around the centre
+        let mut bell = || (0..3).map(|_| rng.gen_range(-1.0..1.0)).sum::<f64>() / 3.0;
+        let u = (self.u + bell() * self.spread_u * 1.5).rem_euclid(1.0);
+        let v = (self.v + bell() * self.spread_v * 1.5).clamp(0.0, 1.0);
+        (u, v)
+    }
+}
+
+impl WorldStyle {
+    /// All layouts
+    pub fn all() -> &'static [WorldStyle] {
+        &[
+            WorldStyle::Random,
+            WorldStyle::Pangaea,
+            WorldStyle::DualContinents,
+            WorldStyle::Archipelago,
+            WorldStyle::EquatorialBand,
+            WorldStyle::PolarContinents,
+        ]
+    }
+
+    /// Name used on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            WorldStyle::Random => "random",
+            WorldStyle::Pangaea => "pangaea",
+            WorldStyle::DualContinents => "dual-continents",
+            WorldStyle::Archipelago => "archipelago",
+            WorldStyle::EquatorialBand => "equatorial-band",
+            WorldStyle::PolarContinents => "polar-continents",
+        }
+    }
+
+    /// One-line description of the layout
+    pub fn description(&self) -> &'static str {
+        match self {
+            WorldStyle::Random => "the generator's own mix of continents",
+            WorldStyle::Pangaea => "a single supercontinent in a world ocean",
+            WorldStyle::DualContinents => "two major continents on opposite sides of the globe",
+            WorldStyle::Archipelago => "scattered island groups in a wide ocean",
+            WorldStyle::EquatorialBand => "a chain of continents along the equator",
+            WorldStyle::PolarContinents => "land in the high latitudes around an equatorial ocean",
+        }
+    }
+
+    /// Look up a layout by name, ignoring case and `-`/`_`
+    pub fn from_name(name: &str) -> Option<WorldStyle> {
+        let key = |s: &str| s.chars().filter(|c| *c != '-' && *c != '_').collect::<String>().to_ascii_lowercase();
+        let wanted = key(name);
+        Self::all().iter().copied().find(|s| key(s.name()) == wanted)
+    }
+
+    /// Share of the map the continental plates should cover
+    pub fn land_fraction(&self) -> f64 {
+        match self {
+            WorldStyle::Random | WorldStyle::Pangaea | WorldStyle::DualContinents => 0.35,
+            WorldStyle::Archipelago => 0.2,
+            WorldStyle::EquatorialBand | WorldStyle::PolarContinents => 0.3,
+        }
+    }
+
+    /// Interior plates when the count is left to the generator
+    pub(crate) fn plate_count(&self, rng: &mut ChaCha8Rng) -> usize {
+        match self {
+            WorldStyle::Archipelago => rng.gen_range(18..=28),
+            _ => rng.gen_range(6..=15),
+        }
+    }
+
+    /// Seeding centres for `count` continental plates (none for a random layout).
+    /// Plate `i` is seeded around anchor `i % anchors.len()`.
+    pub(crate) fn anchors(&self, count: usize, rng: &mut ChaCha8Rng) -> Vec<Anchor> {
+        if *self == WorldStyle::Random {
+            return Vec::new();
+        }
+        let count = count.max(1);
+        let start: f64 = rng.gen();
+        let spaced = |i: usize, n: usize| (start + i as f64 / n as f64).rem_euclid(1.0);
+        match self {
+            WorldStyle::Random => unreachable!(),
+            WorldStyle::Pangaea => vec![Anchor { u: start, v: 0.5, spread_u: 0.1, spread_v: 0.16 }],
+            WorldStyle::DualContinents => (0..2)
+                .map(|i| Anchor { u: spaced(i, 2), v: 0.5, spread_u: 0.07, spread_v: 0.16 })
+                .collect(),
+            WorldStyle::Archipelago => (0..count)
+                .map(|_| Anchor { u: rng.gen(), v: rng.gen_range(0.2..0.8), spread_u: 0.03, spread_v: 0.06 })
+                .collect(),
+            WorldStyle::EquatorialBand => (0..count)
+                .map(|i| Anchor { u: spaced(i, count), v: 0.5, spread_u: 0.35 / count as f64, spread_v: 0.07 })
+                .collect(),
+            WorldStyle::PolarContinents => (0..count)
+                .map(|i| Anchor {
+                    u: spaced(i / 2, count.div_ceil(2)),
+                    v: if i % 2 == 0 { 0.24 } else { 0.76 },
+                    spread_u: 0.35 / count.div_ceil(2) as f64,
+                    spread_v: 0.05,
+                })
+                .collect(),
+        }
+    }
+}
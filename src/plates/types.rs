@@ -2,7 +2,7 @@ use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 
 /// Unique identifier for a tectonic plate.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub struct PlateId(pub u8);
 
 impl PlateId {
@@ -14,7 +14,7 @@ impl PlateId {
 }
 
 /// Type of tectonic plate.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum PlateType {
     /// Oceanic plates are denser and sit lower.
     Oceanic,
@@ -23,7 +23,7 @@ pub enum PlateType {
 }
 
 /// A 2D velocity vector.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32,
@@ -56,7 +56,7 @@ impl Vec2 {
 }
 
 /// A tectonic plate with its properties.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Plate {
     pub id: PlateId,
     pub plate_type: PlateType,
@@ -0,0 +1,283 @@
+//! Polar regions
+//!
+//! Gives the poles explicit structure instead of leaving them as the coldest rows:
+//! - Ice sheets burying cold high-latitude land
+//! - Pack ice over frozen polar seas
+//! - Polynyas: persistent open water in the pack, off coasts where winds drive the ice away
+//! - An aurora band around each pole, a cosmetic layer for renders and descriptive text
+
+use noise::{NoiseFn, Perlin, Seedable};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::ExtendedBiome;
+use crate::tilemap::Tilemap;
+
+/// Frequency of the aurora curtain noise (cycles across the map width)
+const AURORA_CURTAIN_FREQUENCY: f64 = 24.0;
+
+/// Aurora intensity above which a tile counts as under the aurora
+const AURORA_VISIBLE: f32 = 0.35;
+
+/// What the polar ice is doing on a tile
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PolarIce {
+    None,
+    /// Frozen sea
+    SeaIce,
+    /// Land buried under a continental ice sheet
+    IceSheet,
+    /// Open water enclosed by sea ice
+    Polynya,
+}
+
+impl PolarIce {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PolarIce::None => "none",
+            PolarIce::SeaIce => "pack ice",
+            PolarIce::IceSheet => "ice sheet",
+            PolarIce::Polynya => "polynya",
+        }
+    }
+}
+
+/// Thresholds for polar cap generation
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct PolarConfig {
+    /// Minimum absolute latitude (degrees) for polar ice
+    pub min_latitude: f32,
+    /// Sea freezes over below this temperature (°C)
+    pub sea_ice_temp: f32,
+    /// Land is buried under an ice sheet below this temperature (°C)
+    pub ice_sheet_temp: f32,
+    /// Chance that a coastal pack-ice tile opens a polynya
+    pub polynya_chance: f32,
+    /// Maximum polynya radius (tiles)
+    pub polynya_radius: i32,
+    /// Centre latitude of the aurora band (degrees)
+    pub aurora_latitude: f32,
+    /// Half-width of the aurora band (degrees)
+    pub aurora_width: f32,
+}
+
+impl Default for PolarConfig {
+    fn default() -> Self {
+        Self {
+            min_latitude: 55.0,
+            sea_ice_temp: -6.0,
+            ice_sheet_temp: -14.0,
+            polynya_chance: 0.03,
+            polynya_radius: 3,
+            aurora_latitude: 67.0,
+            aurora_width: 7.0,
+        }
+    }
+}
+
+/// Polar ice and aurora layers for a world
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct PolarMap {
+    pub ice: Tilemap<PolarIce>,
+    /// Aurora intensity (0-1)
+    pub aurora: Tilemap<f32>,
+}
+
+impl PolarMap {
+    pub fn ice_at(&self, x: usize, y: usize) -> PolarIce {
+        *self.ice.get(x, y)
+    }
+
+    pub fn aurora_at(&self, x: usize, y: usize) -> f32 {
+        *self.aurora.get(x, y)
+    }
+
+    /// Number of tiles with the given ice state
+    pub fn count(&self, kind: PolarIce) -> usize {
+        self.ice.iter().filter(|(_, _, &i)| i == kind).count()
+    }
+
+    /// Short description for tile info panels
+    pub fn describe(&self, x: usize, y: usize) -> Option<String> {
+        let ice = self.ice_at(x, y);
+        let aurora = self.aurora_at(x, y);
+        let under_aurora = aurora >= AURORA_VISIBLE;
+        match (ice, under_aurora) {
+            (PolarIce::None, false) => None,
+            (PolarIce::None, true) => Some(format!("Aurora ({:.0}%)", aurora * 100.0)),
+            (ice, false) => Some(capitalize(ice.name())),
+            (ice, true) => Some(format!("{} under the aurora ({:.0}%)", capitalize(ice.name()), aurora * 100.0)),
+        }
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().collect::<String>() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// Latitude in degrees at the centre of row `y` (north positive)
+pub fn latitude(y: usize, height: usize) -> f32 {
+    (0.5 - (y as f32 + 0.5) / height as f32) * 180.0
+}
+
+/// Generate polar ice caps, polynyas and the aurora band
+pub fn generate_polar(
+    heightmap: &Tilemap<f32>,
+    temperature: &Tilemap<f32>,
+    config: &PolarConfig,
+    seed: u64,
+) -> PolarMap {
+    let width = heightmap.width;
+    let height = heightmap.height;
+    let mut ice = Tilemap::new_with(width, height, PolarIce::None);
+
+    for (x, y, &h) in heightmap.iter() {
+        if latitude(y, height).abs() < config.min_latitude {
+            continue;
+        }
+        let temp = *temperature.get(x, y);
+        if h > 0.0 && temp < config.ice_sheet_temp {
+            ice.set(x, y, PolarIce::IceSheet);
+        } else if h <= 0.0 && temp < config.sea_ice_temp {
+            ice.set(x, y, PolarIce::SeaIce);
+        }
+    }
+
+    // Coastal polynyas: seed on pack ice touching land, grow a ragged blob over the pack
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x901a));
+    let mut seeds = Vec::new();
+    for (x, y, &i) in ice.iter() {
+        if i != PolarIce::SeaIce {
+            continue;
+        }
+        let coastal = heightmap.neighbors_8(x, y).iter().any(|&(nx, ny)| *heightmap.get(nx, ny) > 0.0);
+        if coastal && rng.gen::<f32>() < config.polynya_chance {
+            seeds.push((x, y));
+        }
+    }
+    for (cx, cy) in seeds {
+        let radius = rng.gen_range(1..=config.polynya_radius.max(1));
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let dist = ((dx * dx + dy * dy) as f32).sqrt();
+                if dist > radius as f32 + rng.gen_range(-0.5..0.5) {
+                    continue;
+                }
+                let (nx, ny) = ice.wrap_coords(cx as i32 + dx, cy as i32 + dy);
+                if *ice.get(nx, ny) == PolarIce::SeaIce {
+                    ice.set(nx, ny, PolarIce::Polynya);
+                }
+            }
+        }
+    }
+
+    // Aurora: a Gaussian band around each pole, broken into curtains by noise
+    let noise = Perlin::new(1).set_seed(seed.wrapping_add(0xa0a0) as u32);
+    let mut aurora = Tilemap::new_with(width, height, 0.0f32);
+    for y in 0..height {
+        let offset = (latitude(y, height).abs() - config.aurora_latitude) / config.aurora_width.max(0.1);
+        let band = (-offset * offset).exp();
+        if band < 0.01 {
+            continue;
+        }
+        for x in 0..width {
+            // Sample on a cylinder so the curtains wrap seamlessly in x
+            let angle = x as f64 / width as f64 * std::f64::consts::TAU;
+            let r = AURORA_CURTAIN_FREQUENCY / std::f64::consts::TAU;
+            let v = y as f64 / height as f64 * AURORA_CURTAIN_FREQUENCY * 0.25;
+            let curtain = noise.get([angle.cos() * r, angle.sin() * r, v]) as f32;
+            aurora.set(x, y, (band * (0.6 + curtain * 0.8)).clamp(0.0, 1.0));
+        }
+    }
+
+    PolarMap { ice, aurora }
+}
+
+/// Replace ordinary land and open-ocean biomes under polar ice with the polar biomes.
+/// Trenches, vents and rare, fantasy or unique biomes are left alone. Returns the number of tiles changed.
+pub fn apply_polar_biomes(biomes: &mut Tilemap<ExtendedBiome>, polar: &PolarMap) -> usize {
+    let mut changed = 0;
+    for (x, y, &ice) in polar.ice.iter() {
+        let current = *biomes.get(x, y);
+        let replacement = match ice {
+            PolarIce::None => continue,
+            PolarIce::IceSheet => matches!(
+                current,
+                ExtendedBiome::Ice | ExtendedBiome::Tundra | ExtendedBiome::AlpineTundra
+                    | ExtendedBiome::BorealForest | ExtendedBiome::Foothills
+            )
+            .then_some(ExtendedBiome::IceSheet),
+            PolarIce::SeaIce | PolarIce::Polynya => matches!(
+                current,
+                ExtendedBiome::DeepOcean | ExtendedBiome::Ocean | ExtendedBiome::CoastalWater | ExtendedBiome::Lagoon
+                    | ExtendedBiome::ContinentalShelf | ExtendedBiome::AbyssalPlain | ExtendedBiome::Seamount
+                    | ExtendedBiome::MidOceanRidge | ExtendedBiome::KelpForest | ExtendedBiome::SeagrassMeadow
+            )
+            .then_some(if ice == PolarIce::Polynya { ExtendedBiome::Polynya } else { ExtendedBiome::PackIce }),
+        };
+        if let Some(biome) = replacement {
+            biomes.set(x, y, biome);
+            changed += 1;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Land in the western half, ocean in the east, temperature falling toward the poles
+    fn polar_world() -> (Tilemap<f32>, Tilemap<f32>) {
+        let (w, h) = (64, 32);
+        let mut heightmap = Tilemap::new_with(w, h, -500.0f32);
+        let mut temperature = Tilemap::new_with(w, h, 0.0f32);
+        for y in 0..h {
+            let lat = latitude(y, h).abs() / 90.0;
+            for x in 0..w {
+                if x < w / 2 {
+                    heightmap.set(x, y, 300.0);
+                }
+                temperature.set(x, y, 30.0 - 60.0 * lat);
+            }
+        }
+        (heightmap, temperature)
+    }
+
+    #[test]
+    fn test_polar_caps() {
+        let (heightmap, temperature) = polar_world();
+        let config = PolarConfig { polynya_chance: 1.0, ..Default::default() };
+        let polar = generate_polar(&heightmap, &temperature, &config, 7);
+
+        // Top row: ice sheet on land, frozen sea offshore; equator ice-free
+        assert_eq!(polar.ice_at(5, 0), PolarIce::IceSheet);
+        assert_eq!(polar.ice_at(50, 0), PolarIce::SeaIce);
+        assert_eq!(polar.ice_at(5, 16), PolarIce::None);
+        assert_eq!(polar.ice_at(50, 16), PolarIce::None);
+        assert!(polar.count(PolarIce::Polynya) > 0);
+
+        // The aurora sits at high latitude, not over the equator
+        assert!(polar.aurora.iter().any(|(_, _, &a)| a > AURORA_VISIBLE));
+        assert_eq!(polar.aurora_at(10, 16), 0.0);
+
+        let mut biomes = Tilemap::new_with(64, 32, ExtendedBiome::Ocean);
+        biomes.set(5, 0, ExtendedBiome::Ice);
+        let changed = apply_polar_biomes(&mut biomes, &polar);
+        assert!(changed > 0);
+        assert_eq!(*biomes.get(5, 0), ExtendedBiome::IceSheet);
+        assert!(matches!(*biomes.get(50, 0), ExtendedBiome::PackIce | ExtendedBiome::Polynya));
+    }
+
+    #[test]
+    fn test_wrap_coords_crosses_pole() {
+        let map = Tilemap::new_with(8, 4, 0u8);
+        assert_eq!(map.wrap_coords(1, -1), (5, 0));
+        assert_eq!(map.wrap_coords(6, 4), (2, 3));
+        assert_eq!(map.wrap_coords(-1, 2), (7, 2));
+    }
+}
@@ -0,0 +1,231 @@
+//! World presets: a whole generation setup in one JSON file
+//!
+//! A preset records the choices behind a world (size, plates, erosion look,
+//! coast, climate, chemistry, magic and a rough layout of its landmasses) so
+//! it can be inspected, edited by hand and generated again. Presets are what
+//! `--brief` asks the LLM to write, and they are checked against the same
+//! rules whether a person or a model wrote them.
+//!
+//! Landmasses are painted into a `Sketch`, so the layout steers the plates,
+//! relief and climate the way a hand-drawn sketch does.
+
+use crate::chemistry::ClimateChemistry;
+use crate::erosion::ErosionParams;
+use crate::sketch::{Sketch, SketchHint, SketchParams};
+use crate::tilemap::Tilemap;
+
+/// The preset format with the meaning of every field, as given to the LLM
+pub const SCHEMA: &str = r#"{
+  "name": string,                      // short title for the world
+  "brief": string | null,              // the description the preset was written from
+  "width": integer | null,             // map width in tiles (16-8192), null = keep the command line's
+  "height": integer | null,            // map height in tiles (16-8192)
+  "seed": integer | null,              // random seed, null = random
+  "plates": integer | null,            // tectonic plates (2-40), null = random 6-15
+  "erosion_preset": string | null,     // "alpine-young", "old-rolling-hills", "desert-mesas",
+                                       // "tropical-dissected" or "glaciated-shield"
+  "coast_complexity": number | null,   // 0-1: how much coast becomes fjords, rias and barrier islands
+  "temperature_offset": number,        // degrees C added everywhere (-30 to 30); negative = colder world
+  "chemistry": string | null,          // alien chemistry: "titan", "sulfur" or "fungal"; null = Earthlike
+  "magic": boolean,                    // generate ley lines and a magic-shaped history
+  "landmasses": [                      // empty = the generator decides where land goes
+    { "x": number, "y": number,        // centre as a fraction of the map (0-1; y = 0 north, 1 south)
+      "radius": number,                // radius as a fraction of the map height (0.01-1)
+      "terrain": "land" | "mountain" | "desert" }
+  ]
+}"#;
+
+/// Largest map side a preset may ask for
+const MAX_SIDE: usize = 8192;
+
+/// What a landmass is painted as
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LandmassTerrain {
+    #[default]
+    Land,
+    Mountain,
+    Desert,
+}
+
+/// A round patch of land in the layout
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Landmass {
+    /// Centre, as fractions of the map width and height
+    pub x: f32,
+    pub y: f32,
+    /// Radius as a fraction of the map height
+    pub radius: f32,
+    #[serde(default)]
+    pub terrain: LandmassTerrain,
+}
+
+/// Everything needed to generate a world again
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WorldPreset {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub brief: Option<String>,
+    #[serde(default)]
+    pub width: Option<usize>,
+    #[serde(default)]
+    pub height: Option<usize>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub plates: Option<usize>,
+    #[serde(default)]
+    pub erosion_preset: Option<String>,
+    #[serde(default)]
+    pub coast_complexity: Option<f32>,
+    #[serde(default)]
+    pub temperature_offset: f32,
+    #[serde(default)]
+    pub chemistry: Option<String>,
+    #[serde(default)]
+    pub magic: bool,
+    #[serde(default)]
+    pub landmasses: Vec<Landmass>,
+}
+
+impl WorldPreset {
+    /// Parse a preset and check it against the schema's ranges
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let preset: WorldPreset = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        preset.validate()?;
+        Ok(preset)
+    }
+
+    /// Load a preset file
+    pub fn load(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::from_json(&json)
+    }
+
+    /// Write the preset as pretty-printed JSON
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, self.to_json()).map_err(|e| e.to_string())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("presets always serialize")
+    }
+
+    /// Err describing the first field outside the schema's ranges
+    pub fn validate(&self) -> Result<(), String> {
+        for (field, side) in [("width", self.width), ("height", self.height)] {
+            if side.is_some_and(|s| !(16..=MAX_SIDE).contains(&s)) {
+                return Err(format!("{} must be between 16 and {}", field, MAX_SIDE));
+            }
+        }
+        if self.plates.is_some_and(|p| !(2..=40).contains(&p)) {
+            return Err("plates must be between 2 and 40".to_string());
+        }
+        if self.coast_complexity.is_some_and(|c| !(0.0..=1.0).contains(&c)) {
+            return Err("coast_complexity must be between 0 and 1".to_string());
+        }
+        if !(-30.0..=30.0).contains(&self.temperature_offset) {
+            return Err("temperature_offset must be between -30 and 30".to_string());
+        }
+        if let Some(ref spec) = self.erosion_preset {
+            ErosionParams::load(spec).map_err(|e| format!("erosion_preset {:?}: {}", spec, e))?;
+        }
+        if let Some(ref spec) = self.chemistry {
+            ClimateChemistry::load(spec).map_err(|e| format!("chemistry {:?}: {}", spec, e))?;
+        }
+        for (i, landmass) in self.landmasses.iter().enumerate() {
+            let inside = (0.0..=1.0).contains(&landmass.x) && (0.0..=1.0).contains(&landmass.y);
+            if !inside || !(0.01..=1.0).contains(&landmass.radius) {
+                return Err(format!("landmass {} must have x and y in 0-1 and radius in 0.01-1", i));
+            }
+        }
+        Ok(())
+    }
+
+    /// One-line description for the CLI
+    pub fn summary(&self) -> String {
+        let mut parts = vec![if self.name.is_empty() { "unnamed preset".to_string() } else { self.name.clone() }];
+        if let Some(ref erosion) = self.erosion_preset {
+            parts.push(format!("{} erosion", erosion));
+        }
+        if self.temperature_offset != 0.0 {
+            parts.push(format!("{:+.0}°C", self.temperature_offset));
+        }
+        if let Some(ref chemistry) = self.chemistry {
+            parts.push(format!("{} chemistry", chemistry));
+        }
+        if self.magic {
+            parts.push("magic".to_string());
+        }
+        if !self.landmasses.is_empty() {
+            parts.push(format!("{} landmasses", self.landmasses.len()));
+        }
+        parts.join(", ")
+    }
+
+    /// Paint the landmasses onto an ocean sketch of the map (None without a layout)
+    pub fn sketch(&self, width: usize, height: usize) -> Option<Sketch> {
+        if self.landmasses.is_empty() {
+            return None;
+        }
+        let mut hints = Tilemap::new_with(width, height, SketchHint::Ocean);
+        // Plain land first, so mountains and deserts painted inside it survive
+        let mut order: Vec<&Landmass> = self.landmasses.iter().collect();
+        order.sort_by_key(|l| l.terrain != LandmassTerrain::Land);
+        for landmass in order {
+            let hint = match landmass.terrain {
+                LandmassTerrain::Land => SketchHint::Land,
+                LandmassTerrain::Mountain => SketchHint::Mountain,
+                LandmassTerrain::Desert => SketchHint::Desert,
+            };
+            let (cx, cy) = (landmass.x * width as f32, landmass.y * height as f32);
+            let radius = landmass.radius * height as f32;
+            for (x, y, tile) in hints.iter_mut() {
+                let dx = (x as f32 + 0.5 - cx).abs();
+                let dx = dx.min(width as f32 - dx);
+                let dy = y as f32 + 0.5 - cy;
+                if dx * dx + dy * dy <= radius * radius {
+                    *tile = hint;
+                }
+            }
+        }
+        Some(Sketch { hints, params: SketchParams::default() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_round_trip_validation_and_layout() {
+        let json = r#"{
+            "name": "Cold archipelago",
+            "temperature_offset": -12,
+            "erosion_preset": "glaciated-shield",
+            "landmasses": [
+                { "x": 0.5, "y": 0.75, "radius": 0.2 },
+                { "x": 0.5, "y": 0.75, "radius": 0.05, "terrain": "mountain" },
+                { "x": 0.2, "y": 0.3, "radius": 0.04 }
+            ]
+        }"#;
+        let preset = WorldPreset::from_json(json).expect("valid preset");
+        assert_eq!(WorldPreset::from_json(&preset.to_json()), Ok(preset.clone()));
+        assert!(preset.summary().contains("3 landmasses"));
+
+        let sketch = preset.sketch(64, 32).expect("has a layout");
+        assert_eq!(*sketch.hints.get(32, 24), SketchHint::Mountain);
+        assert_eq!(*sketch.hints.get(32, 19), SketchHint::Land);
+        assert_eq!(*sketch.hints.get(32, 4), SketchHint::Ocean);
+        assert!(WorldPreset::default().sketch(64, 32).is_none());
+
+        for bad in [
+            r#"{ "plates": 100 }"#,
+            r#"{ "erosion_preset": "volcanic" }"#,
+            r#"{ "landmasses": [ { "x": 1.5, "y": 0.5, "radius": 0.1 } ] }"#,
+        ] {
+            assert!(WorldPreset::from_json(bad).is_err(), "{} should be rejected", bad);
+        }
+    }
+}
@@ -3,7 +3,7 @@
 //! Supports scales from local (1 km/tile) to planetary (50 km/tile).
 
 /// Map scale configuration
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 pub struct MapScale {
     /// Physical distance one tile represents (in kilometers)
     pub km_per_tile: f32,
@@ -101,7 +101,7 @@ impl Default for MapScale {
 }
 
 /// Scale presets for UI selection
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ScalePreset {
     Planetary,
     Continental,
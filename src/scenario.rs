@@ -0,0 +1,388 @@
+//! Scenario files: authored events scheduled into a generated world
+//!
+//! A scenario is a JSON list of events pinned to a year and, where it
+//! matters, a tile:
+//! - Comet strikes carve a crater, leave starfall biome on its floor and
+//!   destroy the settlements inside the rim
+//! - Prophesied heroes are born into the faction holding the tile
+//! - Plagues strike the settlements in reach with fixed casualties
+//! - Any other event type can be written into the chronicle as-is
+//!
+//! Years use the history's calendar: 0 is the present of the generated
+//! world, negative years are its past and positive years lie ahead. Past
+//! events are applied when the world is generated; later ones wait in the
+//! world until aging carries it past their year.
+//!
+//! ```json
+//! {
+//!   "name": "The Falling Star",
+//!   "events": [
+//!     { "year": 312, "at": [40, 20], "kind": { "comet_strike": { "radius": 5.0 } } }
+//!   ]
+//! }
+//! ```
+
+use crate::biomes::ExtendedBiome;
+use crate::craters::{self, Crater, CraterParams};
+use crate::history::{
+    AbandonmentReason, EventType, FactionId, Hero, HeroRole, HistoricalEvent, SettlementId, SettlementState,
+    WorldHistory, Year,
+};
+use crate::tilemap::Tilemap;
+
+/// Fame of a hero whose birth was foretold
+const PROPHESIED_FAME: u32 = 90;
+
+/// What a scripted event does
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptedKind {
+    /// An impact at `at`: a crater of `radius` tiles, `depth` meters deep
+    /// (None = the usual depth for its size)
+    CometStrike {
+        radius: f32,
+        #[serde(default)]
+        depth: Option<f32>,
+    },
+    /// A foretold hero, born into the faction holding `at` (or the oldest faction)
+    HeroBirth {
+        name: String,
+        #[serde(default)]
+        epithet: Option<String>,
+        role: HeroRole,
+    },
+    /// A plague killing exactly `casualties` in the settlements within `radius` tiles of `at`
+    Plague { casualties: u32, radius: usize },
+    /// Any other event, recorded as written
+    Event {
+        event_type: EventType,
+        #[serde(default)]
+        casualties: u32,
+    },
+}
+
+/// One authored event
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ScriptedEvent {
+    /// Year it happens (0 = the present, negative = the past)
+    pub year: i32,
+    /// Tile it happens at; comet strikes and plagues need one
+    #[serde(default)]
+    pub at: Option<(usize, usize)>,
+    /// Chronicle name (None = one made up from the kind)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Chronicle description (None = one made up from what happened)
+    #[serde(default)]
+    pub description: Option<String>,
+    pub kind: ScriptedKind,
+}
+
+/// A named list of authored events
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub events: Vec<ScriptedEvent>,
+}
+
+/// A scenario carried by a world, and how far aging has taken it
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ScenarioState {
+    pub scenario: Scenario,
+    /// Years the world has aged since generation; events up to this year have happened
+    pub elapsed: i32,
+}
+
+impl Scenario {
+    /// Parse a scenario and check every event is complete
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let scenario: Scenario = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        for event in &scenario.events {
+            let needs_tile = matches!(event.kind, ScriptedKind::CometStrike { .. } | ScriptedKind::Plague { .. });
+            if needs_tile && event.at.is_none() {
+                return Err(format!("event in year {} needs a tile (\"at\")", event.year));
+            }
+            if let ScriptedKind::CometStrike { radius, .. } = event.kind {
+                if radius <= 0.0 {
+                    return Err(format!("comet strike in year {} needs a positive radius", event.year));
+                }
+            }
+        }
+        Ok(scenario)
+    }
+
+    /// Load a scenario file
+    pub fn load(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::from_json(&json)
+    }
+
+    /// Err if an event's tile lies off a `width` x `height` map
+    pub fn check_bounds(&self, width: usize, height: usize) -> Result<(), String> {
+        match self.events.iter().find(|e| e.at.is_some_and(|(x, y)| x >= width || y >= height)) {
+            Some(event) => Err(format!(
+                "event in year {} at {:?} lies off the {}x{} map",
+                event.year,
+                event.at.expect("found by its tile"),
+                width,
+                height
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Events after year `after` up to and including `until`, earliest first
+    pub fn between(&self, after: i32, until: i32) -> Vec<ScriptedEvent> {
+        let mut events: Vec<_> = self.events.iter().filter(|e| e.year > after && e.year <= until).cloned().collect();
+        events.sort_by_key(|e| e.year);
+        events
+    }
+
+    /// Events up to the present, applied when the world is generated
+    pub fn past(&self) -> Vec<ScriptedEvent> {
+        self.between(i32::MIN, 0)
+    }
+}
+
+/// Carve the craters of the comet strikes among `events`. Returns the crater
+/// coverage map as `craters::apply_craters` does (indices count comet strikes only).
+pub fn carve_comet_strikes(heightmap: &mut Tilemap<f32>, events: &[ScriptedEvent]) -> Tilemap<Option<(usize, bool)>> {
+    let params = CraterParams::default();
+    let strikes: Vec<Crater> = events
+        .iter()
+        .filter_map(|event| match (event.kind.clone(), event.at) {
+            (ScriptedKind::CometStrike { radius, depth }, Some((x, y))) => Some(Crater {
+                x,
+                y,
+                radius,
+                depth: depth.unwrap_or_else(|| craters::crater_depth(radius, &params)),
+                is_complex: radius >= params.complex_radius,
+                age: 1.0,
+            }),
+            _ => None,
+        })
+        .collect();
+    craters::apply_craters(heightmap, &strikes, &params)
+}
+
+/// Turn crater floors above the sea into starfall craters. Returns the tiles changed.
+pub fn mark_crater_biomes(
+    biomes: &mut Tilemap<ExtendedBiome>,
+    heightmap: &Tilemap<f32>,
+    coverage: &Tilemap<Option<(usize, bool)>>,
+) -> usize {
+    let mut marked = 0;
+    for (x, y, &covered) in coverage.iter() {
+        if matches!(covered, Some((_, true))) && *heightmap.get(x, y) >= 0.0 {
+            biomes.set(x, y, ExtendedBiome::StarfallCrater);
+            marked += 1;
+        }
+    }
+    marked
+}
+
+/// Write `events` into the history, each dated `offset` years earlier than
+/// its scenario year (the years the world has already aged). Returns the
+/// events recorded.
+pub fn inject_events(history: &mut WorldHistory, events: &[ScriptedEvent], offset: i32) -> usize {
+    events.iter().map(|event| inject_event(history, event, Year(event.year - offset))).sum()
+}
+
+fn inject_event(history: &mut WorldHistory, event: &ScriptedEvent, year: Year) -> usize {
+    let map = &history.territories.territory_map;
+    let on_map = event.at.filter(|&(x, y)| x < map.width && y < map.height);
+    let owner = on_map.and_then(|(x, y)| history.territories.faction_at(x, y));
+    let mut faction = owner;
+    let mut settlement = None;
+    let mut casualties = 0;
+    let (name, description) = match event.kind {
+        ScriptedKind::CometStrike { radius, .. } => {
+            let (x, y) = event.at.expect("checked when the scenario was loaded");
+            let struck = settlements_within(history, x, y, radius, year);
+            let mut names = Vec::new();
+            for &id in &struck {
+                let site = history.territories.settlements.get_mut(&id).expect("id was collected above");
+                casualties += site.peak_population;
+                site.state = SettlementState::Destroyed;
+                site.abandoned = Some(year);
+                site.abandonment_reason = Some(AbandonmentReason::NaturalDisaster);
+                if let Some(occupation) = site.occupations.last_mut().filter(|o| o.2.is_none()) {
+                    occupation.2 = Some(year);
+                }
+                names.push(site.name.clone());
+            }
+            settlement = struck.first().copied();
+            let fate = if names.is_empty() {
+                "No one lived where it fell.".to_string()
+            } else {
+                format!("It wiped {} from the face of the world.", names.join(", "))
+            };
+            (
+                "The Falling Star".to_string(),
+                format!("A comet struck the earth and left a great crater where it fell. {}", fate),
+            )
+        }
+        ScriptedKind::HeroBirth { ref name, ref epithet, role } => {
+            faction = owner.or_else(|| {
+                let mut factions: Vec<_> = history.factions.all().collect();
+                factions.sort_by_key(|f| (f.founded, f.id.0));
+                factions.first().map(|f| f.id)
+            });
+            let Some(faction_id) = faction else {
+                return 0;
+            };
+            add_prophesied_hero(history, faction_id, name, epithet.clone(), role, year);
+            let people = history.factions.get(faction_id).map_or("", |f| f.name.as_str()).to_string();
+            (
+                format!("Birth of {}", name),
+                format!("{} was born among the {}, as the prophecy foretold.", name, people),
+            )
+        }
+        ScriptedKind::Plague { casualties: dead, radius } => {
+            let (x, y) = event.at.expect("checked when the scenario was loaded");
+            let stricken = settlements_within(history, x, y, radius as f32, year);
+            let names: Vec<_> = stricken.iter().map(|id| history.territories.settlements[id].name.clone()).collect();
+            settlement = stricken.first().copied();
+            faction = faction.or_else(|| {
+                settlement.map(|id| {
+                    let site = &history.territories.settlements[&id];
+                    site.current_faction.unwrap_or(site.original_faction)
+                })
+            });
+            casualties = dead;
+            let reach = if names.is_empty() { "the countryside".to_string() } else { names.join(", ") };
+            ("The Scripted Plague".to_string(), format!("A plague swept {} and claimed {} lives.", reach, dead))
+        }
+        ScriptedKind::Event { event_type, casualties: dead } => {
+            settlement = on_map.and_then(|(x, y)| history.territories.settlement_at(x, y)).map(|s| s.id);
+            casualties = dead;
+            (event_type.name().to_string(), format!("{} recorded in the scenario.", event_type.name()))
+        }
+    };
+
+    let event_type = match event.kind {
+        ScriptedKind::CometStrike { .. } => EventType::CometStrike,
+        ScriptedKind::HeroBirth { .. } => EventType::HeroBorn,
+        ScriptedKind::Plague { .. } => EventType::Plague,
+        ScriptedKind::Event { event_type, .. } => event_type,
+    };
+    let id = history.timeline.new_id();
+    history.timeline.add_event(HistoricalEvent {
+        id,
+        year,
+        event_type,
+        faction,
+        other_faction: None,
+        location: event.at,
+        settlement,
+        name: event.name.clone().unwrap_or(name),
+        description: event.description.clone().unwrap_or(description),
+        casualties,
+        has_evidence: event_type.leaves_evidence(),
+    });
+    1
+}
+
+/// Settlements standing in `year` within `radius` tiles of (x, y), nearest first
+fn settlements_within(history: &WorldHistory, x: usize, y: usize, radius: f32, year: Year) -> Vec<SettlementId> {
+    let width = history.territories.territory_map.width as i32;
+    let mut found: Vec<(f32, SettlementId)> = history
+        .territories
+        .settlements
+        .values()
+        .filter(|s| s.founded <= year && s.abandoned.is_none_or(|a| a > year))
+        .filter_map(|s| {
+            let dx = (s.x as i32 - x as i32).rem_euclid(width);
+            let dx = dx.min(width - dx) as f32;
+            let dy = s.y as f32 - y as f32;
+            let dist = (dx * dx + dy * dy).sqrt();
+            (dist <= radius).then_some((dist, s.id))
+        })
+        .collect();
+    found.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1 .0.cmp(&b.1 .0)));
+    found.into_iter().map(|(_, id)| id).collect()
+}
+
+fn add_prophesied_hero(
+    history: &mut WorldHistory,
+    faction: FactionId,
+    name: &str,
+    epithet: Option<String>,
+    role: HeroRole,
+    year: Year,
+) {
+    let Some(species) = history.factions.get(faction).map(|f| f.species) else {
+        return;
+    };
+    let id = history.heroes.new_id();
+    history.heroes.add(Hero {
+        id,
+        name: name.to_string(),
+        epithet,
+        species,
+        faction,
+        role,
+        birth_year: year,
+        death_year: None,
+        death_location: None,
+        titles: vec!["Child of Prophecy".to_string()],
+        achievements: Vec::new(),
+        artifacts_created: Vec::new(),
+        burial_site: None,
+        fame: PROPHESIED_FAME,
+        homeland_biome: None,
+        philosophy: None,
+        military_doctrine: None,
+        religious_beliefs: None,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../scenarios/falling_star.json");
+
+    #[test]
+    fn test_scenario_parses_and_schedules() {
+        let scenario = Scenario::from_json(EXAMPLE).expect("example scenario parses");
+        assert!(scenario.check_bounds(64, 32).is_ok());
+        assert!(scenario.check_bounds(16, 8).is_err());
+        let past = scenario.past();
+        assert!(past.iter().all(|e| e.year <= 0));
+        assert!(past.windows(2).all(|w| w[0].year <= w[1].year));
+        assert_eq!(past.len() + scenario.between(0, i32::MAX).len(), scenario.events.len());
+
+        let missing_tile = r#"{ "name": "x", "events": [ { "year": 5, "kind": { "plague": { "casualties": 10, "radius": 2 } } } ] }"#;
+        assert!(Scenario::from_json(missing_tile).is_err());
+    }
+
+    #[test]
+    fn test_comet_strike_carves_and_enters_history() {
+        let mut heightmap = Tilemap::new_with(32, 16, 200.0f32);
+        let strike = ScriptedEvent {
+            year: 12,
+            at: Some((16, 8)),
+            name: None,
+            description: None,
+            kind: ScriptedKind::CometStrike { radius: 3.0, depth: Some(400.0) },
+        };
+        let coverage = carve_comet_strikes(&mut heightmap, std::slice::from_ref(&strike));
+        assert!(*heightmap.get(16, 8) < 0.0, "the crater floor is below the old surface");
+        assert_eq!(*coverage.get(16, 8), Some((0, true)));
+
+        let mut biomes = Tilemap::new_with(32, 16, ExtendedBiome::TemperateGrassland);
+        heightmap.set(16, 8, 10.0);
+        assert!(mark_crater_biomes(&mut biomes, &heightmap, &coverage) > 0);
+        assert_eq!(*biomes.get(16, 8), ExtendedBiome::StarfallCrater);
+
+        let mut history = WorldHistory::empty();
+        assert_eq!(inject_events(&mut history, &[strike], 10), 1);
+        let event = history.timeline.events.values().next().expect("one event");
+        assert_eq!(event.event_type, EventType::CometStrike);
+        assert_eq!(event.year, Year(2));
+        assert_eq!(event.location, Some((16, 8)));
+    }
+}
@@ -0,0 +1,351 @@
+//! Z-level slice and cross-section export
+//!
+//! Draws what the 3D generation actually produced as diagrams, in plain ASCII
+//! and as PNG:
+//! - A horizontal slice shows one z-level across the whole map
+//! - A vertical cross-section cuts along one row or column and stacks every
+//!   z-level under it, with the exact terrain profile drawn over the PNG
+//!
+//! Each tile is reduced to the material it is made of (soil, stone, caves,
+//! the water table, structures...) so the layering reads at a glance.
+
+use image::{Rgb, RgbImage};
+
+use crate::world::WorldData;
+use crate::zlevel::{ZTile, FLOOR_HEIGHT, MAX_Z, MIN_Z};
+
+/// PNG pixels per map tile in a horizontal slice
+const SLICE_CELL: u32 = 4;
+
+/// PNG pixels per tile along a cross-section
+const SECTION_CELL_WIDTH: u32 = 4;
+
+/// PNG pixels per z-level in a cross-section
+const SECTION_CELL_HEIGHT: u32 = 12;
+
+/// Colour of the terrain profile line
+const PROFILE_COLOR: (u8, u8, u8) = (20, 20, 20);
+
+/// What a z-level tile is made of, for diagrams
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Material {
+    /// Open air above the ground
+    Sky,
+    /// Sea or lake water above the ground
+    Water,
+    /// The ground surface: soil and whatever grows on it
+    Soil,
+    /// Bedrock
+    Stone,
+    /// Open cave space, dry
+    Cave,
+    /// Underground rivers, cave lakes and springs
+    CaveWater,
+    /// Water-bearing rock (aquifers): the water table
+    WaterTable,
+    /// Magma pools, lava tubes and their obsidian
+    Magma,
+    /// Ore veins
+    Ore,
+    /// Walls, floors, roads, mines and fortresses
+    Structure,
+    /// Historical evidence, tombs, artifact containers, dungeon entrances
+    Feature,
+}
+
+impl Material {
+    pub fn of(tile: ZTile) -> Self {
+        match tile {
+            ZTile::Air => Material::Sky,
+            ZTile::Water => Material::Water,
+            ZTile::Surface => Material::Soil,
+            ZTile::Solid | ZTile::CaveWall => Material::Stone,
+            ZTile::Aquifer => Material::WaterTable,
+            ZTile::MagmaPool | ZTile::MagmaTube | ZTile::ObsidianFloor => Material::Magma,
+            ZTile::OreVein | ZTile::RichOreVein => Material::Ore,
+            ZTile::FrozenCaveLake => Material::CaveWater,
+            t if t.is_underground_water() || t == ZTile::Spring => Material::CaveWater,
+            t if t.is_cave() => Material::Cave,
+            t if t.is_structure() => Material::Structure,
+            _ => Material::Feature,
+        }
+    }
+
+    /// All materials, in legend order
+    pub fn all() -> &'static [Material] {
+        &[
+            Material::Sky,
+            Material::Water,
+            Material::Soil,
+            Material::Stone,
+            Material::Cave,
+            Material::CaveWater,
+            Material::WaterTable,
+            Material::Magma,
+            Material::Ore,
+            Material::Structure,
+            Material::Feature,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Material::Sky => "sky",
+            Material::Water => "water",
+            Material::Soil => "soil",
+            Material::Stone => "stone",
+            Material::Cave => "cave",
+            Material::CaveWater => "cave water",
+            Material::WaterTable => "water table",
+            Material::Magma => "magma",
+            Material::Ore => "ore",
+            Material::Structure => "structure",
+            Material::Feature => "feature",
+        }
+    }
+
+    /// ASCII character for text diagrams
+    pub fn glyph(&self) -> char {
+        match self {
+            Material::Sky => ' ',
+            Material::Water => '~',
+            Material::Soil => '"',
+            Material::Stone => '#',
+            Material::Cave => '.',
+            Material::CaveWater => '=',
+            Material::WaterTable => '-',
+            Material::Magma => '*',
+            Material::Ore => '$',
+            Material::Structure => 'H',
+            Material::Feature => '+',
+        }
+    }
+
+    pub fn color(&self) -> (u8, u8, u8) {
+        match self {
+            Material::Sky => (205, 225, 245),
+            Material::Water => (40, 90, 180),
+            Material::Soil => (120, 90, 50),
+            Material::Stone => (115, 112, 108),
+            Material::Cave => (45, 38, 32),
+            Material::CaveWater => (60, 130, 205),
+            Material::WaterTable => (120, 165, 200),
+            Material::Magma => (230, 90, 20),
+            Material::Ore => (205, 175, 60),
+            Material::Structure => (170, 60, 55),
+            Material::Feature => (225, 205, 120),
+        }
+    }
+}
+
+/// One-line legend of glyphs
+pub fn legend() -> String {
+    let entries: Vec<String> = Material::all().iter().map(|m| format!("'{}' {}", m.glyph(), m.name())).collect();
+    format!("Legend: {}", entries.join("  "))
+}
+
+/// The line a vertical cross-section cuts along
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SectionLine {
+    /// West to east along a row (y)
+    Row(usize),
+    /// North to south along a column (x)
+    Column(usize),
+}
+
+impl SectionLine {
+    /// Parse `row:N` or `col:N`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (axis, index) = spec.split_once(':').ok_or_else(|| format!("expected row:N or col:N, got '{}'", spec))?;
+        let index = index.trim().parse::<usize>().map_err(|e| format!("bad index in '{}': {}", spec, e))?;
+        match axis.trim() {
+            "row" | "y" => Ok(SectionLine::Row(index)),
+            "col" | "column" | "x" => Ok(SectionLine::Column(index)),
+            other => Err(format!("unknown axis '{}' (row or col)", other)),
+        }
+    }
+
+    /// Short name for file names: `row12`, `col40`
+    pub fn label(&self) -> String {
+        match self {
+            SectionLine::Row(y) => format!("row{}", y),
+            SectionLine::Column(x) => format!("col{}", x),
+        }
+    }
+
+    /// Tiles along the line, or None if it falls outside the world
+    pub fn tiles(&self, world: &WorldData) -> Option<Vec<(usize, usize)>> {
+        match *self {
+            SectionLine::Row(y) if y < world.height => Some((0..world.width).map(|x| (x, y)).collect()),
+            SectionLine::Column(x) if x < world.width => Some((0..world.height).map(|y| (x, y)).collect()),
+            _ => None,
+        }
+    }
+}
+
+/// Z-levels a cross-section shows: from the bottom of the map to one level
+/// above the highest tile that isn't sky
+fn section_range(world: &WorldData, tiles: &[(usize, usize)]) -> (i32, i32) {
+    let top = tiles
+        .iter()
+        .flat_map(|&(x, y)| (MIN_Z..=MAX_Z).rev().find(|&z| Material::of(*world.zlevels.get(x, y, z)) != Material::Sky))
+        .max()
+        .unwrap_or(MIN_Z);
+    (MIN_Z, (top + 1).min(MAX_Z))
+}
+
+/// ASCII map of one z-level
+pub fn render_slice_ascii(world: &WorldData, z: i32) -> String {
+    let mut out = format!("Z-level {} ({:.0} m to {:.0} m)\n", z, z as f32 * FLOOR_HEIGHT, (z + 1) as f32 * FLOOR_HEIGHT);
+    for y in 0..world.height {
+        let row: String = (0..world.width).map(|x| Material::of(*world.zlevels.get(x, y, z)).glyph()).collect();
+        out.push_str(row.trim_end());
+        out.push('\n');
+    }
+    out.push_str(&legend());
+    out.push('\n');
+    out
+}
+
+/// ASCII cross-section: one text row per z-level, highest first, labelled
+/// with its z; returns None if the line falls outside the world
+pub fn render_section_ascii(world: &WorldData, line: SectionLine) -> Option<String> {
+    let tiles = line.tiles(world)?;
+    let (bottom, top) = section_range(world, &tiles);
+    let mut out = format!("Cross-section along {}\n", line.label());
+    for z in (bottom..=top).rev() {
+        let row: String = tiles.iter().map(|&(x, y)| Material::of(*world.zlevels.get(x, y, z)).glyph()).collect();
+        out.push_str(&format!("{:+4} |{}\n", z, row));
+    }
+    out.push_str(&legend());
+    out.push('\n');
+    Some(out)
+}
+
+fn fill(img: &mut RgbImage, x0: u32, y0: u32, w: u32, h: u32, color: (u8, u8, u8)) {
+    for py in y0..(y0 + h).min(img.height()) {
+        for px in x0..(x0 + w).min(img.width()) {
+            img.put_pixel(px, py, Rgb([color.0, color.1, color.2]));
+        }
+    }
+}
+
+/// PNG map of one z-level
+pub fn render_slice_png(world: &WorldData, z: i32) -> RgbImage {
+    let mut img = RgbImage::new(world.width as u32 * SLICE_CELL, world.height as u32 * SLICE_CELL);
+    for y in 0..world.height {
+        for x in 0..world.width {
+            let color = Material::of(*world.zlevels.get(x, y, z)).color();
+            fill(&mut img, x as u32 * SLICE_CELL, y as u32 * SLICE_CELL, SLICE_CELL, SLICE_CELL, color);
+        }
+    }
+    img
+}
+
+/// PNG cross-section with the exact terrain profile drawn over the z-level
+/// cells; returns None if the line falls outside the world
+pub fn render_section_png(world: &WorldData, line: SectionLine) -> Option<RgbImage> {
+    let tiles = line.tiles(world)?;
+    let (bottom, top) = section_range(world, &tiles);
+    let levels = (top - bottom + 1) as u32;
+    let mut img = RgbImage::new(tiles.len() as u32 * SECTION_CELL_WIDTH, levels * SECTION_CELL_HEIGHT);
+    let bottom_pixel = img.height() - 1;
+    let cell_top = |z: i32| (top - z) as u32 * SECTION_CELL_HEIGHT;
+    // Pixel row of an elevation, measured from the top of the diagram
+    let profile_y = |elevation: f32| {
+        let levels_down = (top + 1) as f32 - elevation / FLOOR_HEIGHT;
+        ((levels_down * SECTION_CELL_HEIGHT as f32) as u32).min(bottom_pixel)
+    };
+
+    let mut previous = None;
+    for (i, &(x, y)) in tiles.iter().enumerate() {
+        let x0 = i as u32 * SECTION_CELL_WIDTH;
+        for z in bottom..=top {
+            let color = Material::of(*world.zlevels.get(x, y, z)).color();
+            fill(&mut img, x0, cell_top(z), SECTION_CELL_WIDTH, SECTION_CELL_HEIGHT, color);
+        }
+
+        // Within the surface level, only what lies below the true elevation is ground
+        let elevation = *world.heightmap.get(x, y);
+        let surface = *world.surface_z.get(x, y);
+        let ground = profile_y(elevation);
+        if (bottom..=top).contains(&surface) && *world.zlevels.get(x, y, surface) == ZTile::Surface {
+            let above = if elevation < 0.0 { Material::Water } else { Material::Sky };
+            let start = cell_top(surface);
+            fill(&mut img, x0, start, SECTION_CELL_WIDTH, ground.saturating_sub(start), above.color());
+        }
+
+        // Join the profile to the previous column so steep slopes stay connected
+        let (from, to) = match previous {
+            Some(p) => (ground.min(p), ground.max(p)),
+            None => (ground, ground),
+        };
+        fill(&mut img, x0, from, 1, to - from + 1, PROFILE_COLOR);
+        fill(&mut img, x0, ground, SECTION_CELL_WIDTH, 1, PROFILE_COLOR);
+        previous = Some(ground);
+    }
+    Some(img)
+}
+
+/// Write `PREFIX_z<Z>.txt` and `.png` for one z-level
+pub fn export_slice(world: &WorldData, z: i32, prefix: &str) -> Result<Vec<String>, image::ImageError> {
+    let base = format!("{}_z{}", prefix, z);
+    let (txt, png) = (format!("{}.txt", base), format!("{}.png", base));
+    std::fs::write(&txt, render_slice_ascii(world, z))?;
+    render_slice_png(world, z).save(&png)?;
+    Ok(vec![txt, png])
+}
+
+/// Write `PREFIX_<row|col>N.txt` and `.png` for one cross-section; nothing is
+/// written if the line falls outside the world
+pub fn export_section(world: &WorldData, line: SectionLine, prefix: &str) -> Result<Vec<String>, image::ImageError> {
+    let (Some(text), Some(img)) = (render_section_ascii(world, line), render_section_png(world, line)) else {
+        return Ok(Vec::new());
+    };
+    let base = format!("{}_{}", prefix, line.label());
+    let (txt, png) = (format!("{}.txt", base), format!("{}.png", base));
+    std::fs::write(&txt, text)?;
+    img.save(&png)?;
+    Ok(vec![txt, png])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cross_section_shows_each_layer() {
+        let mut world = crate::world::generate_test_world();
+        // One column gets a cave, an aquifer and a wall on the surface
+        world.zlevels.set(1, 2, -3, ZTile::CaveFloor);
+        world.zlevels.set(1, 2, -5, ZTile::Aquifer);
+        world.zlevels.set(1, 2, 1, ZTile::StoneWall);
+
+        let text = render_section_ascii(&world, SectionLine::Row(2)).unwrap();
+        let rows: Vec<&str> = text.lines().collect();
+        // Header, z = 2 (sky above the wall) down to MIN_Z, legend
+        assert_eq!(rows.len(), 1 + (2 - MIN_Z + 1) as usize + 1);
+        let at = |z: i32| rows[1 + (2 - z) as usize];
+        assert_eq!(at(1), "  +1 | H  ");
+        assert_eq!(at(0), "  +0 |\"\"\"\"");
+        assert_eq!(at(-3), "  -3 |#.##");
+        assert_eq!(at(-5), "  -5 |#-##");
+        assert!(render_section_ascii(&world, SectionLine::Column(9)).is_none());
+
+        let img = render_section_png(&world, SectionLine::Row(2)).unwrap();
+        assert_eq!(img.width(), 4 * SECTION_CELL_WIDTH);
+        assert_eq!(img.height(), (2 - MIN_Z + 1) as u32 * SECTION_CELL_HEIGHT);
+        assert_eq!(SectionLine::parse("col:7"), Ok(SectionLine::Column(7)));
+        assert!(SectionLine::parse("diagonal:3").is_err());
+    }
+
+    #[test]
+    fn test_slice_matches_zlevels() {
+        let world = crate::world::generate_test_world();
+        let surface = render_slice_ascii(&world, 0);
+        assert_eq!(surface.lines().nth(1), Some("\"\"\"\""));
+        let deep = render_slice_ascii(&world, MIN_Z);
+        assert!(deep.lines().skip(1).take(4).all(|l| l == "####"));
+        assert_eq!(render_slice_png(&world, 0).width(), 4 * SLICE_CELL);
+    }
+}
@@ -0,0 +1,330 @@
+//! Seed mining
+//!
+//! Screens a range of seeds for worlds matching user criteria (land fraction,
+//! continent count, a large inland sea, realism score). Candidates are generated
+//! at low resolution with a fast approximate pipeline — plates and the base
+//! heightmap only, no erosion — so thousands of seeds can be scored quickly.
+//! The best seeds are reported with thumbnails and can then be generated at
+//! full resolution with `--seed`.
+
+use crate::erosion::geomorphometry;
+use crate::erosion::sweep::BaseTerrain;
+use crate::tilemap::Tilemap;
+
+/// Flow accumulation threshold used for the realism analysis
+const ANALYSIS_THRESHOLD: f32 = 5.0;
+
+/// Land components smaller than this fraction of the map are islands, not continents
+const CONTINENT_MIN_FRACTION: f32 = 0.02;
+
+/// Padding between thumbnails on the contact sheet
+const SHEET_PADDING: u32 = 4;
+
+/// Inclusive range for a criterion
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Range {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Range {
+    /// Parse `MIN..MAX`, `MIN..`, `..MAX` or a single exact value
+    fn parse(spec: &str) -> Result<Self, String> {
+        let parse = |s: &str, default: f32| -> Result<f32, String> {
+            if s.is_empty() {
+                Ok(default)
+            } else {
+                s.parse().map_err(|_| format!("invalid number '{}'", s))
+            }
+        };
+        match spec.split_once("..") {
+            Some((lo, hi)) => Ok(Range { min: parse(lo, f32::MIN)?, max: parse(hi, f32::MAX)? }),
+            None => {
+                let v = parse(spec, 0.0)?;
+                Ok(Range { min: v, max: v })
+            }
+        }
+    }
+
+    /// 1.0 inside the range, falling off linearly with distance relative to `scale`
+    fn score(&self, value: f32, scale: f32) -> f32 {
+        let dist = if value < self.min {
+            self.min - value
+        } else if value > self.max {
+            value - self.max
+        } else {
+            0.0
+        };
+        (1.0 - dist / scale).max(0.0)
+    }
+}
+
+/// What the user is looking for (unset criteria are ignored)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SeedCriteria {
+    /// Fraction of the map above sea level
+    pub land_fraction: Option<Range>,
+    /// Number of continents
+    pub continents: Option<Range>,
+    /// Minimum area of the largest inland sea, as a fraction of the map
+    pub inland_sea: Option<f32>,
+    /// Minimum geomorphometry realism score (0-100)
+    pub realism: Option<f32>,
+}
+
+impl SeedCriteria {
+    /// Parse criteria from `name=value` pairs separated by commas
+    /// (e.g. `land=0.3..0.45,continents=3..5,inland_sea=0.01,realism=40`)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut criteria = SeedCriteria::default();
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected NAME=VALUE, got '{}'", pair))?;
+            match name.trim() {
+                "land" | "land_fraction" => criteria.land_fraction = Some(Range::parse(value.trim())?),
+                "continents" => criteria.continents = Some(Range::parse(value.trim())?),
+                "inland_sea" => {
+                    criteria.inland_sea = Some(value.trim().parse().map_err(|_| format!("invalid number '{}'", value))?)
+                }
+                "realism" => {
+                    criteria.realism = Some(value.trim().parse().map_err(|_| format!("invalid number '{}'", value))?)
+                }
+                other => return Err(format!("unknown criterion '{}'", other)),
+            }
+        }
+        Ok(criteria)
+    }
+
+    /// Score a candidate in [0, 1]; with no criteria the realism score decides
+    pub fn score(&self, stats: &WorldStats) -> f32 {
+        let mut scores = Vec::new();
+        if let Some(range) = self.land_fraction {
+            scores.push(range.score(stats.land_fraction, 0.2));
+        }
+        if let Some(range) = self.continents {
+            scores.push(range.score(stats.continents as f32, 3.0));
+        }
+        if let Some(min) = self.inland_sea {
+            scores.push(if min <= 0.0 { 1.0 } else { (stats.inland_sea / min).min(1.0) });
+        }
+        if let Some(min) = self.realism {
+            scores.push(if min <= 0.0 { 1.0 } else { (stats.realism / min).min(1.0) });
+        }
+
+        let realism = stats.realism / 100.0;
+        if scores.is_empty() {
+            realism
+        } else {
+            // Realism breaks ties between equally matching seeds
+            (scores.iter().sum::<f32>() / scores.len() as f32) * 0.95 + realism * 0.05
+        }
+    }
+}
+
+/// Summary statistics of a candidate world
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorldStats {
+    pub land_fraction: f32,
+    pub continents: usize,
+    /// Largest below-sea-level region not connected to the main ocean, as a fraction of the map
+    pub inland_sea: f32,
+    pub realism: f32,
+}
+
+impl WorldStats {
+    pub fn measure(heightmap: &Tilemap<f32>) -> Self {
+        let area = (heightmap.width * heightmap.height) as f32;
+        let land = heightmap.iter().filter(|(_, _, &h)| h > 0.0).count();
+
+        let land_regions = region_sizes(heightmap, |h| h > 0.0);
+        let continents = land_regions
+            .iter()
+            .filter(|&&size| size as f32 >= area * CONTINENT_MIN_FRACTION)
+            .count();
+
+        // The largest water region is the world ocean; the next largest is an inland sea
+        let mut water_regions = region_sizes(heightmap, |h| h <= 0.0);
+        water_regions.sort_unstable_by(|a, b| b.cmp(a));
+        let inland_sea = water_regions.get(1).copied().unwrap_or(0) as f32 / area;
+
+        let realism = geomorphometry::analyze(heightmap, ANALYSIS_THRESHOLD).realism_score();
+
+        Self {
+            land_fraction: land as f32 / area,
+            continents,
+            inland_sea,
+            realism,
+        }
+    }
+}
+
+/// Sizes of the 4-connected regions whose elevation satisfies `predicate`
+fn region_sizes(heightmap: &Tilemap<f32>, predicate: impl Fn(f32) -> bool) -> Vec<usize> {
+    let mut seen = Tilemap::new_with(heightmap.width, heightmap.height, false);
+    let mut sizes = Vec::new();
+
+    for (x, y, &h) in heightmap.iter() {
+        if *seen.get(x, y) || !predicate(h) {
+            continue;
+        }
+        seen.set(x, y, true);
+        let mut stack = vec![(x, y)];
+        let mut size = 0;
+        while let Some((cx, cy)) = stack.pop() {
+            size += 1;
+            for (nx, ny) in heightmap.neighbors(cx, cy) {
+                if !*seen.get(nx, ny) && predicate(*heightmap.get(nx, ny)) {
+                    seen.set(nx, ny, true);
+                    stack.push((nx, ny));
+                }
+            }
+        }
+        sizes.push(size);
+    }
+
+    sizes
+}
+
+/// Settings for a seed mining run
+#[derive(Clone, Debug)]
+pub struct MiningConfig {
+    /// First seed to try
+    pub start: u64,
+    /// Number of consecutive seeds to try
+    pub count: u64,
+    /// Screening resolution
+    pub width: usize,
+    pub height: usize,
+    /// Number of tectonic plates (random per seed if None)
+    pub num_plates: Option<usize>,
+    pub criteria: SeedCriteria,
+    /// How many of the best candidates to keep
+    pub keep: usize,
+}
+
+/// A scored seed
+#[derive(Clone)]
+pub struct Candidate {
+    pub seed: u64,
+    pub score: f32,
+    pub stats: WorldStats,
+    /// Low-resolution pre-erosion heightmap used for the thumbnail
+    pub heightmap: Tilemap<f32>,
+}
+
+/// Best candidates of a mining run, highest score first
+pub struct MiningResult {
+    pub candidates: Vec<Candidate>,
+    pub screened: u64,
+}
+
+/// Screen `config.count` seeds and keep the best `config.keep`
+pub fn mine_seeds(config: &MiningConfig) -> MiningResult {
+    let mut candidates: Vec<Candidate> = Vec::new();
+
+    for seed in config.start..config.start.saturating_add(config.count) {
+        let base = BaseTerrain::generate(config.width, config.height, seed, config.num_plates);
+        let stats = WorldStats::measure(&base.heightmap);
+        let score = config.criteria.score(&stats);
+
+        candidates.push(Candidate { seed, score, stats, heightmap: base.heightmap });
+        candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        candidates.truncate(config.keep.max(1));
+    }
+
+    MiningResult {
+        candidates,
+        screened: config.count,
+    }
+}
+
+impl MiningResult {
+    /// Render the ranking as CSV (one row per kept seed)
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("rank,seed,score,land_fraction,continents,inland_sea,realism_score\n");
+        for (rank, c) in self.candidates.iter().enumerate() {
+            out.push_str(&format!(
+                "{},{},{:.4},{:.4},{},{:.4},{:.2}\n",
+                rank + 1,
+                c.seed,
+                c.score,
+                c.stats.land_fraction,
+                c.stats.continents,
+                c.stats.inland_sea,
+                c.stats.realism,
+            ));
+        }
+        out
+    }
+
+    /// Write the ranking to a CSV file
+    pub fn write_csv(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.to_csv())
+    }
+
+    /// Export thumbnails of the kept seeds side by side, best first
+    pub fn export_thumbnails(&self, path: &str) -> Result<(), image::ImageError> {
+        use image::{Rgb, RgbImage};
+
+        let (cell_w, cell_h) = self
+            .candidates
+            .first()
+            .map(|c| (c.heightmap.width as u32, c.heightmap.height as u32))
+            .unwrap_or((1, 1));
+
+        let sheet_w = self.candidates.len().max(1) as u32 * (cell_w + SHEET_PADDING) + SHEET_PADDING;
+        let sheet_h = cell_h + 2 * SHEET_PADDING;
+        let mut img = RgbImage::from_pixel(sheet_w, sheet_h, Rgb([32, 32, 32]));
+
+        for (i, c) in self.candidates.iter().enumerate() {
+            let ox = SHEET_PADDING + i as u32 * (cell_w + SHEET_PADDING);
+            for (x, y, &h) in c.heightmap.iter() {
+                let (r, g, b) = crate::ascii::height_color(h);
+                img.put_pixel(ox + x as u32, SHEET_PADDING + y as u32, Rgb([r, g, b]));
+            }
+        }
+
+        img.save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_criteria_parse() {
+        let c = SeedCriteria::parse("land=0.3..0.45, continents=3.., inland_sea=0.01").unwrap();
+        assert_eq!(c.land_fraction, Some(Range { min: 0.3, max: 0.45 }));
+        assert_eq!(c.continents.unwrap().min, 3.0);
+        assert_eq!(c.inland_sea, Some(0.01));
+        assert_eq!(c.realism, None);
+
+        assert!(SeedCriteria::parse("bogus=1").is_err());
+        assert!(SeedCriteria::parse("land").is_err());
+    }
+
+    #[test]
+    fn test_measure_inland_sea_and_continents() {
+        // One continent in a connected ocean, holding a landlocked basin
+        let mut heightmap = Tilemap::new_with(40, 20, -1000.0f32);
+        for y in 4..16 {
+            for x in 5..35 {
+                heightmap.set(x, y, 500.0);
+            }
+        }
+        for y in 8..12 {
+            for x in 10..20 {
+                heightmap.set(x, y, -200.0);
+            }
+        }
+
+        let stats = WorldStats::measure(&heightmap);
+        assert_eq!(stats.continents, 1);
+        assert!((stats.inland_sea - 40.0 / 800.0).abs() < 1e-6);
+
+        let criteria = SeedCriteria::parse("continents=1,inland_sea=0.05").unwrap();
+        assert!(criteria.score(&stats) > 0.9);
+    }
+}
@@ -0,0 +1,688 @@
+//! Earthquakes: fault lines and seismic hazard
+//!
+//! Fault traces are drawn along the plate boundaries, one fault per connected
+//! stretch of boundary between the same two plates. The stress there sets the
+//! kind of fault (thrust where plates converge, normal where they pull apart,
+//! strike-slip where they slide past) and the plates' relative speed sets how
+//! fast it slips. Long, fast faults carry the largest quakes and the highest
+//! hazard, which fades with distance from the trace.
+//!
+//! Earthquakes strike faults in proportion to how active they are, with an
+//! occasional smaller quake away from any fault. Magnitudes follow a truncated
+//! Gutenberg-Richter law up to each fault's largest quake. A quake ruptures
+//! part of its fault, lifting or dropping the ground along it by a few metres,
+//! shakes loose landslides on steep slopes within its damage radius, and ruins
+//! or thins out the settlements it reaches.
+
+use std::collections::VecDeque;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::history::types::Year;
+#[cfg(feature = "history")]
+use crate::history::types::{AbandonmentReason, SettlementState};
+#[cfg(feature = "history")]
+use crate::history::{EventType, HistoricalEvent, WorldHistory};
+use crate::plates::{Plate, PlateId};
+use crate::tilemap::Tilemap;
+
+/// Magnitude at and above which a quake can level a settlement
+#[cfg(feature = "history")]
+const RUINOUS_MAGNITUDE: f32 = 7.0;
+
+/// Parameters for faults, hazard and earthquakes
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SeismicConfig {
+    /// Shortest stretch of plate boundary (tiles) that becomes a fault
+    pub min_fault_length: usize,
+    /// Mean stress along a trace beyond which it is thrust (or, negative, normal)
+    pub dip_slip_stress: f32,
+    /// Furthest (tiles) a fault raises the seismic hazard
+    pub hazard_radius: usize,
+    /// Damaging earthquakes per thousand years of history
+    pub quakes_per_millennium: f32,
+    /// Cap on the number of earthquakes in one span
+    pub max_quakes: usize,
+    /// Smallest magnitude worth recording
+    pub min_magnitude: f32,
+    /// Gutenberg-Richter b-value: higher means relatively fewer large quakes
+    pub b_value: f32,
+    /// Share of quakes that strike away from any fault
+    pub background_share: f32,
+    /// Largest magnitude of a quake away from any fault
+    pub background_max_magnitude: f32,
+    /// Height drop (m) to the lowest neighbour beyond which shaking can trigger a landslide
+    pub landslide_slope: f32,
+    /// Share of the drop beyond `landslide_slope` that slides down
+    pub landslide_fraction: f32,
+    /// Deepest (m) a single landslide strips from its scar
+    pub max_landslide_depth: f32,
+    /// Shaking intensity (0-1) at which a ruinous quake levels a settlement
+    pub ruin_intensity: f32,
+}
+
+impl Default for SeismicConfig {
+    fn default() -> Self {
+        Self {
+            min_fault_length: 6,
+            dip_slip_stress: 0.15,
+            hazard_radius: 12,
+            quakes_per_millennium: 4.0,
+            max_quakes: 60,
+            min_magnitude: 5.5,
+            b_value: 1.0,
+            background_share: 0.1,
+            background_max_magnitude: 6.0,
+            landslide_slope: 300.0,
+            landslide_fraction: 0.3,
+            max_landslide_depth: 20.0,
+            ruin_intensity: 0.6,
+        }
+    }
+}
+
+/// How the two sides of a fault move
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum FaultKind {
+    /// Converging plates: one side is pushed up over the other
+    Thrust,
+    /// Diverging plates: one side drops down
+    Normal,
+    /// Plates sliding past each other
+    StrikeSlip,
+}
+
+impl FaultKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FaultKind::Thrust => "Thrust fault",
+            FaultKind::Normal => "Normal fault",
+            FaultKind::StrikeSlip => "Strike-slip fault",
+        }
+    }
+
+    /// Extra magnitude this kind of fault can reach for its length
+    fn magnitude_bonus(&self) -> f32 {
+        match self {
+            FaultKind::Thrust => 0.7,
+            FaultKind::Normal => 0.0,
+            FaultKind::StrikeSlip => 0.3,
+        }
+    }
+
+    /// Vertical throw (sign only): thrusts lift their side, normal faults drop it
+    fn throw(&self) -> f32 {
+        match self {
+            FaultKind::Thrust => 1.0,
+            FaultKind::Normal => -1.0,
+            FaultKind::StrikeSlip => 0.0,
+        }
+    }
+}
+
+/// A fault trace along a plate boundary
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Fault {
+    pub kind: FaultKind,
+    /// The plate the trace lies on, and the plate across the boundary
+    pub plates: (PlateId, PlateId),
+    /// Tiles along the trace
+    pub trace: Vec<(usize, usize)>,
+    /// Relative speed of the two plates
+    pub slip_rate: f32,
+    /// Largest quake the fault can produce
+    pub max_magnitude: f32,
+}
+
+/// An earthquake and what it did to the land
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Earthquake {
+    pub year: Year,
+    pub epicenter: (usize, usize),
+    /// Index into `SeismicMap::faults`, or None for a quake away from any fault
+    pub fault: Option<usize>,
+    pub magnitude: f32,
+    /// Fault tiles that broke the surface
+    pub rupture: Vec<(usize, usize)>,
+    /// Tiles whose slopes slid away
+    pub landslides: Vec<(usize, usize)>,
+}
+
+impl Earthquake {
+    /// Radius (tiles) within which the quake does damage
+    pub fn damage_radius(&self) -> f32 {
+        damage_radius(self.magnitude)
+    }
+}
+
+/// Radius (tiles) within which a quake of this magnitude does damage
+pub fn damage_radius(magnitude: f32) -> f32 {
+    10f32.powf(0.5 * magnitude - 2.5)
+}
+
+/// Length (tiles) of fault a quake of this magnitude breaks
+fn rupture_length(magnitude: f32) -> usize {
+    (10f32.powf(0.5 * magnitude - 2.8).round() as usize).max(1)
+}
+
+/// Vertical surface offset (m) along the rupture
+fn surface_offset(magnitude: f32) -> f32 {
+    10f32.powf(0.5 * magnitude - 3.2)
+}
+
+/// Faults, seismic hazard and the earthquakes that have struck
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SeismicMap {
+    /// Index into `faults` of the fault through each tile
+    pub fault_map: Tilemap<Option<u16>>,
+    pub faults: Vec<Fault>,
+    /// Seismic hazard (0-1)
+    pub hazard: Tilemap<f32>,
+    /// Earthquakes in the order they struck
+    pub earthquakes: Vec<Earthquake>,
+}
+
+impl SeismicMap {
+    pub fn fault_at(&self, x: usize, y: usize) -> Option<&Fault> {
+        self.fault_map.get(x, y).map(|i| &self.faults[i as usize])
+    }
+
+    /// Short description for tile info panels
+    pub fn describe(&self, x: usize, y: usize) -> Option<String> {
+        let hazard = *self.hazard.get(x, y);
+        match self.fault_at(x, y) {
+            Some(fault) => Some(format!(
+                "{} (up to M{:.1}), seismic hazard {:.0}%",
+                fault.kind.name(),
+                fault.max_magnitude,
+                hazard * 100.0
+            )),
+            None if hazard >= 0.05 => Some(format!("Seismic hazard {:.0}%", hazard * 100.0)),
+            None => None,
+        }
+    }
+}
+
+/// Trace faults along the plate boundaries and map the seismic hazard
+pub fn generate_seismic(
+    plate_map: &Tilemap<PlateId>,
+    plates: &[Plate],
+    stress_map: &Tilemap<f32>,
+    config: &SeismicConfig,
+) -> SeismicMap {
+    let (width, height) = (plate_map.width, plate_map.height);
+
+    // Boundary tiles, each on the lower-numbered plate so a trace is one tile wide
+    let mut boundary = Tilemap::new_with(width, height, None);
+    for (x, y, &plate) in plate_map.iter() {
+        if plate.is_none() {
+            continue;
+        }
+        let across = plate_map
+            .neighbors(x, y)
+            .into_iter()
+            .map(|(nx, ny)| *plate_map.get(nx, ny))
+            .filter(|other| !other.is_none() && other.0 > plate.0)
+            .min_by_key(|other| other.0);
+        boundary.set(x, y, across);
+    }
+
+    // One fault per connected stretch of boundary between the same two plates
+    let mut fault_map = Tilemap::new_with(width, height, None);
+    let mut faults = Vec::new();
+    let mut seen = Tilemap::new_with(width, height, false);
+    for y in 0..height {
+        for x in 0..width {
+            let Some(across) = *boundary.get(x, y) else {
+                continue;
+            };
+            if *seen.get(x, y) {
+                continue;
+            }
+            let plate = *plate_map.get(x, y);
+            let mut trace = Vec::new();
+            let mut queue = VecDeque::from([(x, y)]);
+            seen.set(x, y, true);
+            while let Some((cx, cy)) = queue.pop_front() {
+                trace.push((cx, cy));
+                for (nx, ny) in plate_map.neighbors_8(cx, cy) {
+                    if !*seen.get(nx, ny) && *plate_map.get(nx, ny) == plate && *boundary.get(nx, ny) == Some(across) {
+                        seen.set(nx, ny, true);
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+            if trace.len() < config.min_fault_length || faults.len() >= u16::MAX as usize {
+                continue;
+            }
+
+            let mean_stress = trace.iter().map(|&(tx, ty)| *stress_map.get(tx, ty)).sum::<f32>() / trace.len() as f32;
+            let kind = if mean_stress > config.dip_slip_stress {
+                FaultKind::Thrust
+            } else if mean_stress < -config.dip_slip_stress {
+                FaultKind::Normal
+            } else {
+                FaultKind::StrikeSlip
+            };
+            let slip_rate = match (plates.get(plate.0 as usize), plates.get(across.0 as usize)) {
+                (Some(a), Some(b)) => (a.velocity.x - b.velocity.x).hypot(a.velocity.y - b.velocity.y),
+                _ => 0.0,
+            };
+            let max_magnitude = (5.5 + (trace.len() as f32).log10() + kind.magnitude_bonus()).min(9.5);
+
+            let index = faults.len() as u16;
+            for &(tx, ty) in &trace {
+                fault_map.set(tx, ty, Some(index));
+            }
+            faults.push(Fault { kind, plates: (plate, across), trace, slip_rate, max_magnitude });
+        }
+    }
+
+    let hazard = hazard_map(&faults, stress_map, config);
+    SeismicMap { fault_map, faults, hazard, earthquakes: Vec::new() }
+}
+
+/// Hazard from the most threatening fault in reach, fading with distance, over
+/// a background from the crustal stress
+fn hazard_map(faults: &[Fault], stress_map: &Tilemap<f32>, config: &SeismicConfig) -> Tilemap<f32> {
+    let (width, height) = (stress_map.width, stress_map.height);
+    let mut hazard = Tilemap::new_with(width, height, 0.0f32);
+    for (x, y, &stress) in stress_map.iter() {
+        hazard.set(x, y, (0.3 * stress.abs()).min(0.3));
+    }
+
+    let max_slip = faults.iter().map(|f| f.slip_rate).fold(0.0f32, f32::max).max(f32::EPSILON);
+    let decay = (config.hazard_radius as f32 / 3.0).max(1.0);
+    let mut distance = Tilemap::new_with(width, height, usize::MAX);
+    for fault in faults {
+        let activity = (fault.slip_rate / max_slip).sqrt() * (fault.max_magnitude / 9.5);
+        let mut queue: VecDeque<(usize, usize)> = fault.trace.iter().copied().collect();
+        let mut touched = fault.trace.clone();
+        for &(x, y) in &fault.trace {
+            distance.set(x, y, 0);
+        }
+        while let Some((x, y)) = queue.pop_front() {
+            let d = *distance.get(x, y);
+            let value = activity * (-(d as f32) / decay).exp();
+            if value > *hazard.get(x, y) {
+                hazard.set(x, y, value);
+            }
+            if d >= config.hazard_radius {
+                continue;
+            }
+            for (nx, ny) in stress_map.neighbors(x, y) {
+                if *distance.get(nx, ny) == usize::MAX {
+                    distance.set(nx, ny, d + 1);
+                    touched.push((nx, ny));
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        for (x, y) in touched {
+            distance.set(x, y, usize::MAX);
+        }
+    }
+    hazard
+}
+
+/// Distance in tiles, wrapping east-west
+fn tile_distance(width: usize, a: (usize, usize), b: (usize, usize)) -> f32 {
+    let dx = a.0.abs_diff(b.0);
+    let dx = dx.min(width - dx) as f32;
+    let dy = a.1.abs_diff(b.1) as f32;
+    dx.hypot(dy)
+}
+
+/// Strike the world with earthquakes between `start` and `end`: faults rupture
+/// the heightmap and shaking sets off landslides. The quakes are appended to
+/// `seismic.earthquakes` and returned.
+pub fn trigger_earthquakes(
+    seismic: &mut SeismicMap,
+    heightmap: &mut Tilemap<f32>,
+    plate_map: &Tilemap<PlateId>,
+    (start, end): (Year, Year),
+    config: &SeismicConfig,
+    seed: u64,
+) -> Vec<Earthquake> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x5E153C));
+    let span = (end.0 - start.0).max(0);
+    let count = ((span as f32 / 1000.0 * config.quakes_per_millennium).round() as usize).min(config.max_quakes);
+    let weights: Vec<f32> = seismic.faults.iter().map(|f| f.slip_rate * f.trace.len() as f32).collect();
+    let total_weight: f32 = weights.iter().sum();
+
+    let mut years: Vec<Year> = (0..count).map(|_| Year(rng.gen_range(start.0..=end.0))).collect();
+    years.sort();
+
+    let mut quakes = Vec::with_capacity(count);
+    for year in years {
+        let on_fault = total_weight > 0.0 && rng.gen::<f32>() >= config.background_share;
+        let mut quake = if on_fault {
+            let mut roll = rng.gen::<f32>() * total_weight;
+            let index = weights.iter().position(|&w| {
+                roll -= w;
+                roll < 0.0
+            });
+            let index = index.unwrap_or(weights.len() - 1);
+            let fault = &seismic.faults[index];
+            let epicenter = fault.trace[rng.gen_range(0..fault.trace.len())];
+            let magnitude = gutenberg_richter(config.min_magnitude, fault.max_magnitude, config.b_value, &mut rng);
+
+            // The rupture runs along the fault either side of the epicenter
+            let reach = rupture_length(magnitude) as f32 / 2.0;
+            let rupture: Vec<_> = fault
+                .trace
+                .iter()
+                .copied()
+                .filter(|&t| tile_distance(heightmap.width, t, epicenter) <= reach)
+                .collect();
+            let offset = surface_offset(magnitude) * fault.kind.throw();
+            if offset != 0.0 {
+                for &(x, y) in &rupture {
+                    *heightmap.get_mut(x, y) += offset;
+                    for (nx, ny) in heightmap.neighbors(x, y) {
+                        if *plate_map.get(nx, ny) == fault.plates.0 && seismic.fault_map.get(nx, ny).is_none() {
+                            *heightmap.get_mut(nx, ny) += offset;
+                        }
+                    }
+                }
+            }
+            Earthquake { year, epicenter, fault: Some(index), magnitude, rupture, landslides: Vec::new() }
+        } else {
+            let epicenter = (rng.gen_range(0..heightmap.width), rng.gen_range(0..heightmap.height));
+            let max = config.background_max_magnitude.max(config.min_magnitude);
+            let magnitude = gutenberg_richter(config.min_magnitude, max, config.b_value, &mut rng);
+            Earthquake { year, epicenter, fault: None, magnitude, rupture: Vec::new(), landslides: Vec::new() }
+        };
+
+        quake.landslides = shake_slopes(heightmap, &quake, config, &mut rng);
+        seismic.earthquakes.push(quake.clone());
+        quakes.push(quake);
+    }
+    quakes
+}
+
+/// Magnitude from a Gutenberg-Richter law truncated to `[min, max]`
+fn gutenberg_richter(min: f32, max: f32, b: f32, rng: &mut impl Rng) -> f32 {
+    if max <= min {
+        return max;
+    }
+    let tail = 10f32.powf(-b * (max - min));
+    let u: f32 = rng.gen();
+    (min - (1.0 - u * (1.0 - tail)).log10() / b).min(max)
+}
+
+/// Slide steep slopes loose within the damage radius: the harder the shaking,
+/// the likelier a slope fails. Returns the landslide scars.
+fn shake_slopes(
+    heightmap: &mut Tilemap<f32>,
+    quake: &Earthquake,
+    config: &SeismicConfig,
+    rng: &mut ChaCha8Rng,
+) -> Vec<(usize, usize)> {
+    let radius = quake.damage_radius();
+    let r = radius.ceil() as i32;
+    let (ex, ey) = quake.epicenter;
+    let mut scars = Vec::new();
+    for dy in -r..=r {
+        let y = ey as i32 + dy;
+        if y < 0 || y >= heightmap.height as i32 {
+            continue;
+        }
+        for dx in -r..=r {
+            let (x, y) = heightmap.wrap_coords(ex as i32 + dx, y);
+            let intensity = 1.0 - tile_distance(heightmap.width, (x, y), quake.epicenter) / radius.max(1.0);
+            let here = *heightmap.get(x, y);
+            if intensity <= 0.0 || here <= 0.0 {
+                continue;
+            }
+            let Some((lx, ly)) = heightmap
+                .neighbors(x, y)
+                .into_iter()
+                .min_by(|&a, &b| heightmap.get(a.0, a.1).total_cmp(heightmap.get(b.0, b.1)))
+            else {
+                continue;
+            };
+            let drop = here - *heightmap.get(lx, ly);
+            if drop <= config.landslide_slope || rng.gen::<f32>() >= intensity {
+                continue;
+            }
+            let depth = ((drop - config.landslide_slope) * config.landslide_fraction).min(config.max_landslide_depth);
+            *heightmap.get_mut(x, y) -= depth;
+            *heightmap.get_mut(lx, ly) += depth;
+            scars.push((x, y));
+        }
+    }
+    scars
+}
+
+/// Record earthquakes in the history: settlements near the epicenter lose
+/// people, and the strongest quakes level the settlements closest to them.
+/// Returns how many events were added.
+#[cfg(feature = "history")]
+pub fn record_earthquakes(history: &mut WorldHistory, quakes: &[Earthquake], seismic: &SeismicMap, config: &SeismicConfig) -> usize {
+    let width = history.territories.territory_map.width;
+    let mut settlement_ids: Vec<_> = history.territories.settlements.keys().copied().collect();
+    settlement_ids.sort_by_key(|id| id.0);
+
+    for quake in quakes {
+        let radius = quake.damage_radius();
+        let mut casualties = 0u32;
+        let mut ruined = Vec::new();
+        for &id in &settlement_ids {
+            let settlement = history.territories.settlements.get_mut(&id).expect("id was collected above");
+            let standing = settlement.founded <= quake.year && settlement.abandoned.is_none_or(|a| a > quake.year);
+            let intensity = 1.0 - tile_distance(width, (settlement.x, settlement.y), quake.epicenter) / radius.max(1.0);
+            if !standing || intensity <= 0.0 {
+                continue;
+            }
+            if quake.magnitude >= RUINOUS_MAGNITUDE && intensity >= config.ruin_intensity {
+                casualties += settlement.peak_population / 2;
+                settlement.state = SettlementState::Ruined;
+                settlement.abandoned = Some(quake.year);
+                settlement.abandonment_reason = Some(AbandonmentReason::NaturalDisaster);
+                if let Some(occupation) = settlement.occupations.last_mut().filter(|o| o.2.is_none()) {
+                    occupation.2 = Some(quake.year);
+                }
+                ruined.push((id, settlement.name.clone()));
+            } else {
+                casualties += (settlement.peak_population as f32 * intensity * 0.1) as u32;
+            }
+        }
+
+        let (x, y) = quake.epicenter;
+        let faction = history.faction_at(x, y).map(|f| f.id);
+        let kind = quake.fault.map_or("deep", |i| seismic.faults[i].kind.name());
+        let mut description = format!("A magnitude {:.1} earthquake shook the land ({})", quake.magnitude, kind.to_lowercase());
+        if !quake.rupture.is_empty() {
+            description += ", tearing open the ground along the fault";
+        }
+        if !quake.landslides.is_empty() {
+            description += &format!(" and bringing down {} landslides", quake.landslides.len());
+        }
+        if !ruined.is_empty() {
+            let names: Vec<_> = ruined.iter().map(|(_, name)| name.as_str()).collect();
+            description += &format!("; {} fell to ruin", names.join(", "));
+        }
+        let name = match ruined.first() {
+            Some((_, town)) => format!("The Great Quake of {}", town),
+            None if quake.magnitude >= RUINOUS_MAGNITUDE => "The Great Quake".to_string(),
+            None => "The Trembling".to_string(),
+        };
+
+        let id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id,
+            year: quake.year,
+            event_type: EventType::Earthquake,
+            faction,
+            other_faction: None,
+            location: Some((x, y)),
+            settlement: ruined.first().map(|(id, _)| *id),
+            name,
+            description,
+            casualties,
+            has_evidence: EventType::Earthquake.leaves_evidence(),
+        });
+    }
+    quakes.len()
+}
+
+/// Strike the world with earthquakes over the span of its history and record
+/// them. Returns how many earthquakes struck.
+#[cfg(feature = "history")]
+pub fn apply_earthquake_history(
+    history: &mut WorldHistory,
+    seismic: &mut SeismicMap,
+    heightmap: &mut Tilemap<f32>,
+    plate_map: &Tilemap<PlateId>,
+    config: &SeismicConfig,
+    seed: u64,
+) -> usize {
+    let (start, end) = match (history.timeline.eras.first(), history.timeline.eras.last()) {
+        (Some(first), Some(last)) => (first.start, last.end),
+        _ => return 0,
+    };
+    let quakes = trigger_earthquakes(seismic, heightmap, plate_map, (start, end), config, seed);
+    record_earthquakes(history, &quakes, seismic, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "history")]
+    use crate::history::territories::{Settlement, TerritoryRegistry};
+    #[cfg(feature = "history")]
+    use crate::history::timeline::Era;
+    #[cfg(feature = "history")]
+    use crate::history::types::*;
+    use crate::plates::{PlateType, Vec2};
+
+    /// A 48x24 world split into two plates at x = 24 (and again where x wraps)
+    fn two_plates(velocity: (f32, f32), stress: f32) -> (Tilemap<PlateId>, Vec<Plate>, Tilemap<f32>) {
+        let mut plate_map = Tilemap::new_with(48, 24, PlateId(0));
+        let mut stress_map = Tilemap::new_with(48, 24, 0.0f32);
+        for y in 0..24 {
+            for x in 24..48 {
+                plate_map.set(x, y, PlateId(1));
+            }
+            for x in 22..26 {
+                stress_map.set(x, y, stress);
+            }
+        }
+        let plate = |id: u8, vx: f32, vy: f32| Plate {
+            id: PlateId(id),
+            plate_type: PlateType::Continental,
+            velocity: Vec2::new(vx, vy),
+            base_elevation: 0.1,
+            color: [0, 0, 0],
+        };
+        let plates = vec![plate(0, velocity.0, velocity.1), plate(1, -velocity.0, -velocity.1)];
+        (plate_map, plates, stress_map)
+    }
+
+    #[test]
+    fn test_faults_follow_plate_boundaries() {
+        let (plate_map, plates, stress) = two_plates((0.5, 0.0), 0.8);
+        let seismic = generate_seismic(&plate_map, &plates, &stress, &SeismicConfig::default());
+
+        let fault = seismic.fault_at(23, 10).expect("boundary tile is on a fault");
+        assert_eq!(fault.kind, FaultKind::Thrust);
+        assert_eq!(fault.plates, (PlateId(0), PlateId(1)));
+        assert!((fault.slip_rate - 1.0).abs() < 1e-5);
+        assert!(seismic.fault_at(10, 10).is_none());
+        // The wrap-around boundary has no stress: plates slide past there
+        assert_eq!(seismic.fault_at(0, 10).map(|f| f.kind), Some(FaultKind::StrikeSlip));
+
+        // Hazard is highest on the active fault and fades away from it
+        assert!(*seismic.hazard.get(23, 10) > *seismic.hazard.get(18, 10));
+        assert!(*seismic.hazard.get(18, 10) > *seismic.hazard.get(12, 10));
+        assert!(seismic.describe(23, 10).unwrap().starts_with("Thrust fault"));
+    }
+
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_earthquakes_rupture_faults_and_ruin_towns() {
+        let (plate_map, plates, stress) = two_plates((0.5, 0.0), 0.8);
+        let config = SeismicConfig {
+            quakes_per_millennium: 10.0,
+            background_share: 0.0,
+            min_magnitude: 7.5,
+            landslide_slope: 100.0,
+            ..Default::default()
+        };
+        let mut seismic = generate_seismic(&plate_map, &plates, &stress, &config);
+        // A steep cliff right on the fault
+        let mut heightmap = Tilemap::new_with(48, 24, 200.0f32);
+        for y in 0..24 {
+            heightmap.set(22, y, 1500.0);
+            heightmap.set(0, y, 1500.0);
+        }
+        let before = heightmap.clone();
+
+        let mut history = WorldHistory::empty();
+        history.timeline.eras.push(Era {
+            name: "Age of Tremors".to_string(),
+            era_type: EraType::GoldenAge,
+            start: Year(-1000),
+            end: Year(0),
+            events: Vec::new(),
+        });
+        history.territories = TerritoryRegistry::new(48, 24);
+        for (id, x) in [(0u32, 23usize), (1, 1)] {
+            history.territories.add_settlement(Settlement {
+                id: SettlementId(id),
+                name: format!("Town{}", id),
+                settlement_type: SettlementType::Town,
+                original_faction: FactionId(0),
+                current_faction: Some(FactionId(0)),
+                x,
+                y: 12,
+                size: 1,
+                state: SettlementState::Thriving,
+                founded: Year(-2000),
+                abandoned: None,
+                abandonment_reason: None,
+                peak_population: 1000,
+                architecture: ArchitectureStyle::Rustic,
+                occupations: vec![(FactionId(0), Year(-2000), None)],
+            });
+        }
+
+        let struck = apply_earthquake_history(&mut history, &mut seismic, &mut heightmap, &plate_map, &config, 9);
+        assert_eq!(struck, 10);
+        assert_eq!(seismic.earthquakes.len(), 10);
+        for quake in &seismic.earthquakes {
+            // A fault too short for the threshold still breaks at its largest quake
+            let max = seismic.faults[quake.fault.expect("no background quakes")].max_magnitude;
+            assert!(quake.magnitude <= max && (quake.magnitude >= 7.5 || quake.magnitude == max));
+        }
+        assert!(seismic.earthquakes.windows(2).all(|w| w[0].year <= w[1].year));
+
+        // Thrust ruptures lift the ground; shaking brings the cliffs down
+        let thrust = seismic.earthquakes.iter().find(|q| seismic.faults[q.fault.unwrap()].kind == FaultKind::Thrust);
+        if let Some(quake) = thrust {
+            let &(x, y) = quake.rupture.first().expect("a quake breaks its fault");
+            assert!(*heightmap.get(x, y) > *before.get(x, y));
+        }
+        assert!(seismic.earthquakes.iter().any(|q| !q.landslides.is_empty()));
+
+        // Every quake is in the chronicle; great quakes on the fault level the towns on it
+        let quakes = history.timeline.events.values().filter(|e| e.event_type == EventType::Earthquake).count();
+        assert_eq!(quakes, 10);
+        let ruined = history.territories.settlements.values().filter(|s| s.state == SettlementState::Ruined).count();
+        assert!(ruined >= 1);
+    }
+
+    #[test]
+    fn test_magnitudes_stay_within_bounds() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let samples: Vec<f32> = (0..2000).map(|_| gutenberg_richter(5.0, 8.0, 1.0, &mut rng)).collect();
+        assert!(samples.iter().all(|m| (5.0..=8.0).contains(m)));
+        // Large quakes are rarer than small ones
+        let small = samples.iter().filter(|&&m| m < 6.0).count();
+        let large = samples.iter().filter(|&&m| m >= 7.0).count();
+        assert!(small > 5 * large);
+        assert!(damage_radius(8.0) > damage_radius(6.0));
+    }
+}
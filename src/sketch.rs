@@ -0,0 +1,252 @@
+//! Worlds from a hand-drawn sketch
+//!
+//! A rough painted image says where the land, sea, mountains and deserts
+//! should be. The full pipeline still runs; the sketch only nudges it:
+//! - Plates mostly painted as land become continental, the rest oceanic
+//! - Painted mountains raise the plate stress so ranges grow there
+//! - The heightmap is pulled toward sketch elevations, keeping its own
+//!   relief, with the coast following the blurred outline of the paint
+//! - Painted deserts are dried out before biomes are classified
+//!
+//! Colours are matched to the nearest of blue (ocean), green (land), brown
+//! (mountain), yellow (desert) and white (unpainted, left to the generator).
+
+use image::DynamicImage;
+use rand_chacha::ChaCha8Rng;
+
+use crate::erosion::utils::smooth_tilemap;
+use crate::plates::{Plate, PlateId, PlateType};
+use crate::tilemap::Tilemap;
+
+/// Depth (m) the sketch pulls open ocean toward
+const OCEAN_DEPTH: f32 = 2000.0;
+
+/// Elevation (m) the sketch pulls inland plains toward
+const LAND_ELEVATION: f32 = 400.0;
+
+/// Elevation (m) the sketch pulls painted mountains toward
+const MOUNTAIN_ELEVATION: f32 = 2800.0;
+
+/// What a painted colour asks for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
+pub enum SketchHint {
+    /// Left to the generator
+    #[default]
+    Unpainted,
+    Ocean,
+    Land,
+    Mountain,
+    Desert,
+}
+
+/// Reference colour of each hint
+const PALETTE: [(SketchHint, [u8; 3]); 5] = [
+    (SketchHint::Unpainted, [255, 255, 255]),
+    (SketchHint::Ocean, [40, 80, 200]),
+    (SketchHint::Land, [60, 160, 60]),
+    (SketchHint::Mountain, [130, 90, 60]),
+    (SketchHint::Desert, [230, 200, 100]),
+];
+
+impl SketchHint {
+    /// Hint of the nearest palette colour
+    pub fn classify(rgb: [u8; 3]) -> Self {
+        let distance = |reference: &[u8; 3]| -> i32 {
+            (0..3).map(|i| (rgb[i] as i32 - reference[i] as i32).pow(2)).sum()
+        };
+        PALETTE.iter().min_by_key(|(_, reference)| distance(reference)).map_or(SketchHint::Unpainted, |(hint, _)| *hint)
+    }
+
+    /// Whether the hint asks for land (None = unpainted)
+    pub fn is_land(&self) -> Option<bool> {
+        match self {
+            SketchHint::Unpainted => None,
+            SketchHint::Ocean => Some(false),
+            SketchHint::Land | SketchHint::Mountain | SketchHint::Desert => Some(true),
+        }
+    }
+}
+
+/// How firmly the pipeline is held to the sketch
+#[derive(Clone, Debug)]
+pub struct SketchParams {
+    /// Pull of painted tiles toward the sketch elevation (0 = none, 1 = all the way)
+    pub strength: f32,
+    /// Blur (tiles) softening the painted outlines into coasts and foothills
+    pub blur_radius: usize,
+    /// Share of a plate's painted tiles that must be land for it to be continental
+    pub continental_share: f32,
+    /// Convergent stress laid under painted mountains
+    pub mountain_stress: f32,
+    /// Share of moisture removed from painted deserts
+    pub desert_dryness: f32,
+}
+
+impl Default for SketchParams {
+    fn default() -> Self {
+        Self { strength: 0.7, blur_radius: 3, continental_share: 0.5, mountain_stress: 0.6, desert_dryness: 0.7 }
+    }
+}
+
+/// A sketch resampled to the map size
+#[derive(Clone, Debug)]
+pub struct Sketch {
+    pub hints: Tilemap<SketchHint>,
+    pub params: SketchParams,
+}
+
+impl Sketch {
+    /// Classify every pixel of `img`, sampled onto a `width` x `height` map
+    pub fn from_image(img: &DynamicImage, width: usize, height: usize) -> Self {
+        let rgb = img.to_rgb8();
+        let (iw, ih) = (rgb.width() as usize, rgb.height() as usize);
+        let mut hints = Tilemap::new_with(width, height, SketchHint::Unpainted);
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = rgb.get_pixel((x * iw / width) as u32, (y * ih / height) as u32);
+                hints.set(x, y, SketchHint::classify(pixel.0));
+            }
+        }
+        Self { hints, params: SketchParams::default() }
+    }
+
+    /// Load a sketch image (any format the image crate reads)
+    pub fn load(path: &str, width: usize, height: usize) -> Result<Self, String> {
+        let img = image::open(path).map_err(|e| e.to_string())?;
+        Ok(Self::from_image(&img, width, height))
+    }
+
+    /// Share of the painted tiles that ask for land
+    pub fn land_fraction(&self) -> f32 {
+        let painted: Vec<bool> = self.hints.iter().filter_map(|(_, _, h)| h.is_land()).collect();
+        painted.iter().filter(|&&land| land).count() as f32 / painted.len().max(1) as f32
+    }
+
+    /// Make plates continental where they are mostly painted as land and
+    /// oceanic where mostly sea. Stationary border plates stay oceanic.
+    /// Returns the number of continental plates.
+    pub fn fit_plates(&self, plate_map: &Tilemap<PlateId>, plates: &mut [Plate], rng: &mut ChaCha8Rng) -> usize {
+        let mut counts = vec![(0usize, 0usize); plates.len()];
+        for (x, y, &id) in plate_map.iter() {
+            if let (Some(land), Some(count)) = (self.hints.get(x, y).is_land(), counts.get_mut(id.0 as usize)) {
+                count.0 += land as usize;
+                count.1 += 1;
+            }
+        }
+
+        let mut continental = 0;
+        for (plate, &(land, painted)) in plates.iter_mut().zip(&counts) {
+            if plate.velocity.length() == 0.0 || painted == 0 {
+                continental += (plate.plate_type == PlateType::Continental) as usize;
+                continue;
+            }
+            let is_continental = land as f32 / painted as f32 >= self.params.continental_share;
+            let fitted = Plate::new_with_type(plate.id, rng, is_continental);
+            plate.plate_type = fitted.plate_type;
+            plate.base_elevation = fitted.base_elevation;
+            plate.color = fitted.color;
+            continental += is_continental as usize;
+        }
+        continental
+    }
+
+    /// Lay convergent stress under painted mountains
+    pub fn bias_stress(&self, stress_map: &mut Tilemap<f32>) {
+        let mountains = self.blurred(|h| (h == SketchHint::Mountain) as u8 as f32);
+        for (x, y, stress) in stress_map.iter_mut() {
+            let lift = *mountains.get(x, y) * self.params.mountain_stress;
+            *stress = stress.max(lift);
+        }
+    }
+
+    /// Pull the heightmap toward the sketch, keeping its local relief. Where
+    /// the paint is clear the coast follows its blurred outline.
+    pub fn fit_heightmap(&self, heightmap: &mut Tilemap<f32>) {
+        let painted = self.blurred(|h| h.is_land().is_some() as u8 as f32);
+        let mountains = self.blurred(|h| (h == SketchHint::Mountain) as u8 as f32);
+        // Unpainted tiles vote with the generated terrain, so outlines fade into it
+        let mut land_votes = Tilemap::new_with(heightmap.width, heightmap.height, 0.0f32);
+        for (x, y, vote) in land_votes.iter_mut() {
+            let land = self.hints.get(x, y).is_land().unwrap_or(*heightmap.get(x, y) > 0.0);
+            *vote = land as u8 as f32;
+        }
+        let land = smooth_tilemap(&land_votes, self.params.blur_radius);
+        let regional = smooth_tilemap(heightmap, self.params.blur_radius);
+
+        for (x, y, h) in heightmap.iter_mut() {
+            let share = *land.get(x, y);
+            let target = if share >= 0.5 {
+                LAND_ELEVATION * (share - 0.5) * 2.0 + *mountains.get(x, y) * (MOUNTAIN_ELEVATION - LAND_ELEVATION)
+            } else {
+                -OCEAN_DEPTH * (0.5 - share) * 2.0
+            };
+            let relief = *h - *regional.get(x, y);
+            let pull = (*painted.get(x, y) * self.params.strength).clamp(0.0, 1.0);
+            *h += (target + relief - *h) * pull;
+
+            if *painted.get(x, y) >= 0.5 {
+                *h = if share >= 0.5 { h.max(1.0) } else { h.min(-1.0) };
+            }
+        }
+    }
+
+    /// Dry out painted deserts
+    pub fn bias_moisture(&self, moisture: &mut Tilemap<f32>) {
+        let deserts = self.blurred(|h| (h == SketchHint::Desert) as u8 as f32);
+        for (x, y, m) in moisture.iter_mut() {
+            *m *= 1.0 - *deserts.get(x, y) * self.params.desert_dryness;
+        }
+    }
+
+    /// Share of the painted tiles whose land or sea matches the heightmap
+    pub fn agreement(&self, heightmap: &Tilemap<f32>) -> f32 {
+        let (mut matched, mut painted) = (0usize, 0usize);
+        for (x, y, hint) in self.hints.iter() {
+            if let Some(land) = hint.is_land() {
+                matched += (land == (*heightmap.get(x, y) > 0.0)) as usize;
+                painted += 1;
+            }
+        }
+        matched as f32 / painted.max(1) as f32
+    }
+
+    /// Blurred 0-1 mask of the hints `select` picks out
+    fn blurred(&self, select: impl Fn(SketchHint) -> f32) -> Tilemap<f32> {
+        let mut mask = Tilemap::new_with(self.hints.width, self.hints.height, 0.0f32);
+        for (x, y, value) in mask.iter_mut() {
+            *value = select(*self.hints.get(x, y));
+        }
+        smooth_tilemap(&mask, self.params.blur_radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_sketch_fits_heightmap_and_climate() {
+        // Sea on the left, a continent on the right with a desert inside it
+        let img = RgbImage::from_fn(64, 32, |x, y| match (x, y) {
+            (0..=27, _) => Rgb([30, 70, 210]),
+            (44..=55, 10..=21) => Rgb([240, 210, 90]),
+            _ => Rgb([70, 150, 50]),
+        });
+        let sketch = Sketch::from_image(&DynamicImage::ImageRgb8(img), 32, 16);
+        assert_eq!(*sketch.hints.get(2, 8), SketchHint::Ocean);
+        assert_eq!(*sketch.hints.get(24, 8), SketchHint::Desert);
+        assert_eq!(SketchHint::classify([250, 250, 245]), SketchHint::Unpainted);
+        assert!((sketch.land_fraction() - 0.5625).abs() < 0.01);
+
+        // A flat world just above sea level everywhere
+        let mut heightmap = Tilemap::new_with(32, 16, 50.0f32);
+        sketch.fit_heightmap(&mut heightmap);
+        assert!(sketch.agreement(&heightmap) > 0.9);
+        assert!(*heightmap.get(2, 8) < 0.0 && *heightmap.get(28, 2) > 0.0);
+
+        let mut moisture = Tilemap::new_with(32, 16, 0.6f32);
+        sketch.bias_moisture(&mut moisture);
+        assert!(*moisture.get(24, 8) < *moisture.get(18, 2));
+    }
+}
@@ -68,6 +68,7 @@ pub fn generate_structures(
     biomes: &Tilemap<ExtendedBiome>,
     stress_map: &Tilemap<f32>,
     water_bodies: &Tilemap<WaterBodyId>,
+    harbor: Option<&Tilemap<f32>>,
     seed: u64,
 ) -> Vec<PlacedStructure> {
     let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x5701C7));
@@ -112,6 +113,7 @@ pub fn generate_structures(
         temperature,
         water_bodies,
         biomes,
+        harbor,
         &all_structures,
     );
 
@@ -2,12 +2,172 @@
 //!
 //! Computes desirability maps for different structure types based on terrain,
 //! climate, and existing features. Uses these maps to intelligently place structures.
+//!
+//! Every score is the sum of a `DesirabilityBreakdown`, so `explain_desirability`
+//! can say which factors put (or kept) a structure at any tile.
 
 use crate::biomes::ExtendedBiome;
 use crate::tilemap::Tilemap;
 use crate::water_bodies::WaterBodyId;
+use crate::world::WorldData;
 use super::types::{DesirabilityMap, PlacedStructure, StructureType};
 
+/// Structure types that are placed from a desirability map
+pub const DESIRABILITY_TYPES: [StructureType; 3] = [StructureType::Castle, StructureType::City, StructureType::Village];
+
+/// Per-factor contributions to a tile's desirability (they sum to its score)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DesirabilityBreakdown {
+    /// Proximity to fresh water
+    pub water_access: f32,
+    /// Sheltered, deep water for a port
+    pub harbor: f32,
+    /// Moisture and fertile biomes
+    pub fertility: f32,
+    /// High ground, peaks and distance from water
+    pub defensibility: f32,
+    /// Comfortable temperatures
+    pub climate: f32,
+    /// Flatness and elevation band
+    pub terrain: f32,
+    /// Lying along probable trade routes between major structures
+    pub trade: f32,
+    /// Spacing from existing structures (mostly penalties)
+    pub distance: f32,
+}
+
+impl DesirabilityBreakdown {
+    /// Factor names, in `factors()` order
+    pub const NAMES: [&'static str; 8] =
+        ["water_access", "harbor", "fertility", "defensibility", "climate", "terrain", "trade", "distance"];
+
+    /// Factor values, in `NAMES` order
+    pub fn factors(&self) -> [f32; 8] {
+        [
+            self.water_access,
+            self.harbor,
+            self.fertility,
+            self.defensibility,
+            self.climate,
+            self.terrain,
+            self.trade,
+            self.distance,
+        ]
+    }
+
+    /// The desirability score
+    pub fn total(&self) -> f32 {
+        self.factors().iter().sum()
+    }
+
+    /// "water_access +6.0, terrain +4.0, distance -2.5 = 7.5" (zero factors omitted)
+    pub fn describe(&self) -> String {
+        let parts: Vec<String> = Self::NAMES
+            .iter()
+            .zip(self.factors())
+            .filter(|(_, v)| *v != 0.0)
+            .map(|(name, v)| format!("{} {:+.1}", name, v))
+            .collect();
+        format!("{} = {:.1}", parts.join(", "), self.total())
+    }
+}
+
+/// Borrowed world layers the desirability factors read
+pub struct DesirabilityLayers<'a> {
+    pub heightmap: &'a Tilemap<f32>,
+    pub moisture: &'a Tilemap<f32>,
+    pub temperature: &'a Tilemap<f32>,
+    pub stress_map: &'a Tilemap<f32>,
+    pub water_bodies: &'a Tilemap<WaterBodyId>,
+    pub biomes: &'a Tilemap<ExtendedBiome>,
+    /// Harbour quality along the coasts (None when the world has no wave layer)
+    pub harbor: Option<&'a Tilemap<f32>>,
+}
+
+impl<'a> DesirabilityLayers<'a> {
+    pub fn of(world: &'a WorldData) -> Self {
+        Self {
+            heightmap: &world.heightmap,
+            moisture: &world.moisture,
+            temperature: &world.temperature,
+            stress_map: &world.stress_map,
+            water_bodies: &world.water_body_map,
+            biomes: &world.biomes,
+            harbor: world.waves.as_ref().map(|w| &w.harbor),
+        }
+    }
+}
+
+/// Explain a tile's desirability for a structure type, factor by factor.
+///
+/// Returns None where the structure can't be built (water, unbuildable biomes) and for
+/// types not placed from a desirability map (cave dwellings, dungeons).
+pub fn explain_desirability(
+    structure_type: StructureType,
+    layers: &DesirabilityLayers,
+    existing_structures: &[PlacedStructure],
+    x: usize,
+    y: usize,
+) -> Option<DesirabilityBreakdown> {
+    match structure_type {
+        StructureType::Castle => castle_factors(
+            layers.heightmap,
+            layers.stress_map,
+            layers.water_bodies,
+            layers.biomes,
+            existing_structures,
+            x,
+            y,
+        ),
+        StructureType::City => city_factors(
+            layers.heightmap,
+            layers.moisture,
+            layers.temperature,
+            layers.water_bodies,
+            layers.biomes,
+            layers.harbor,
+            existing_structures,
+            x,
+            y,
+        ),
+        StructureType::Village => village_factors(
+            layers.heightmap,
+            layers.moisture,
+            layers.water_bodies,
+            layers.biomes,
+            existing_structures,
+            x,
+            y,
+        ),
+        StructureType::CaveDwelling | StructureType::Dungeon => None,
+    }
+}
+
+/// Desirability map holding each tile's total, f32::MIN where `factors` rules it out
+fn desirability_from(
+    heightmap: &Tilemap<f32>,
+    factors: impl Fn(usize, usize) -> Option<DesirabilityBreakdown>,
+) -> DesirabilityMap {
+    let mut desirability = DesirabilityMap::new(heightmap.width, heightmap.height);
+    for y in 0..heightmap.height {
+        for x in 0..heightmap.width {
+            desirability.set(x, y, factors(x, y).map_or(f32::MIN, |b| b.total()));
+        }
+    }
+    desirability
+}
+
+/// Whether a structure can stand on this tile at all
+fn is_buildable_tile(
+    heightmap: &Tilemap<f32>,
+    water_bodies: &Tilemap<WaterBodyId>,
+    biomes: &Tilemap<ExtendedBiome>,
+    x: usize,
+    y: usize,
+) -> bool {
+    *heightmap.get(x, y) > 0.0 && water_bodies.get(x, y).is_none() && is_buildable_biome(biomes.get(x, y))
+}
+
 /// Compute desirability map for castle placement
 ///
 /// Castles prefer:
@@ -23,73 +183,66 @@ pub fn compute_castle_desirability(
     biomes: &Tilemap<ExtendedBiome>,
     existing_structures: &[PlacedStructure],
 ) -> DesirabilityMap {
-    let width = heightmap.width;
-    let height = heightmap.height;
-    let mut desirability = DesirabilityMap::new(width, height);
-
-    for y in 0..height {
-        for x in 0..width {
-            let elev = *heightmap.get(x, y);
-            let stress = stress_map.get(x, y).abs();
-            let water_id = *water_bodies.get(x, y);
-            let biome = *biomes.get(x, y);
-
-            // Skip underwater tiles
-            if elev <= 0.0 || !water_id.is_none() {
-                desirability.set(x, y, f32::MIN);
-                continue;
-            }
-
-            // Skip unsuitable biomes
-            if !is_buildable_biome(&biome) {
-                desirability.set(x, y, f32::MIN);
-                continue;
-            }
-
-            let mut score = 0.0;
+    desirability_from(heightmap, |x, y| {
+        castle_factors(heightmap, stress_map, water_bodies, biomes, existing_structures, x, y)
+    })
+}
 
-            // Prefer high elevation (hills, mountains) - major factor
-            score += (elev / 500.0).clamp(0.0, 2.0) * 3.0;
+fn castle_factors(
+    heightmap: &Tilemap<f32>,
+    stress_map: &Tilemap<f32>,
+    water_bodies: &Tilemap<WaterBodyId>,
+    biomes: &Tilemap<ExtendedBiome>,
+    existing_structures: &[PlacedStructure],
+    x: usize,
+    y: usize,
+) -> Option<DesirabilityBreakdown> {
+    if !is_buildable_tile(heightmap, water_bodies, biomes, x, y) {
+        return None;
+    }
+    let elev = *heightmap.get(x, y);
+    let stress = stress_map.get(x, y).abs();
+    let mut factors = DesirabilityBreakdown::default();
 
-            // Bonus for being a local peak
-            let local_peak_bonus = compute_local_peak_bonus(heightmap, x, y);
-            score += local_peak_bonus * 5.0;
+    // Prefer high elevation (hills, mountains) - major factor
+    factors.defensibility += (elev / 500.0).clamp(0.0, 2.0) * 3.0;
 
-            // Moderate preference for tectonic stress (dramatic terrain)
-            score += stress * 1.5;
+    // Bonus for being a local peak
+    let local_peak_bonus = compute_local_peak_bonus(heightmap, x, y);
+    factors.defensibility += local_peak_bonus * 5.0;
 
-            // Penalize if too close to water (less defensible)
-            let water_dist = compute_water_distance(water_bodies, x, y, 10);
-            if water_dist < 3.0 {
-                score -= (3.0 - water_dist) * 0.5;
-            }
+    // Moderate preference for tectonic stress (dramatic terrain)
+    factors.defensibility += stress * 1.5;
 
-            // Penalize if too close to existing structures
-            for structure in existing_structures {
-                let (cx, cy) = structure.center();
-                let dist = ((x as f32 - cx as f32).powi(2) + (y as f32 - cy as f32).powi(2)).sqrt();
-                if dist < 50.0 {
-                    score -= (50.0 - dist) * 0.1;
-                }
-            }
-
-            // Compute slope penalty (need flat foundation)
-            let slope = compute_slope(heightmap, x, y);
-            if slope > 0.5 {
-                score -= (slope - 0.5) * 2.0;
-            }
+    // Penalize if too close to water (less defensible)
+    let water_dist = compute_water_distance(water_bodies, x, y, 10);
+    if water_dist < 3.0 {
+        factors.defensibility -= (3.0 - water_dist) * 0.5;
+    }
 
-            desirability.set(x, y, score);
+    // Penalize if too close to existing structures
+    for structure in existing_structures {
+        let (cx, cy) = structure.center();
+        let dist = ((x as f32 - cx as f32).powi(2) + (y as f32 - cy as f32).powi(2)).sqrt();
+        if dist < 50.0 {
+            factors.distance -= (50.0 - dist) * 0.1;
         }
     }
 
-    desirability
+    // Compute slope penalty (need flat foundation)
+    let slope = compute_slope(heightmap, x, y);
+    if slope > 0.5 {
+        factors.terrain -= (slope - 0.5) * 2.0;
+    }
+
+    Some(factors)
 }
 
 /// Compute desirability map for city placement
 ///
 /// Cities prefer:
 /// - Near fresh water (rivers, lakes)
+/// - Natural harbours on the coast
 /// - Flat terrain
 /// - Fertile biomes (grassland, forest edge)
 /// - Moderate temperature
@@ -100,95 +253,97 @@ pub fn compute_city_desirability(
     temperature: &Tilemap<f32>,
     water_bodies: &Tilemap<WaterBodyId>,
     biomes: &Tilemap<ExtendedBiome>,
+    harbor: Option<&Tilemap<f32>>,
     existing_structures: &[PlacedStructure],
 ) -> DesirabilityMap {
-    let width = heightmap.width;
-    let height = heightmap.height;
-    let mut desirability = DesirabilityMap::new(width, height);
-
-    for y in 0..height {
-        for x in 0..width {
-            let elev = *heightmap.get(x, y);
-            let moist = *moisture.get(x, y);
-            let temp = *temperature.get(x, y);
-            let water_id = *water_bodies.get(x, y);
-            let biome = *biomes.get(x, y);
-
-            // Skip underwater tiles
-            if elev <= 0.0 || !water_id.is_none() {
-                desirability.set(x, y, f32::MIN);
-                continue;
-            }
-
-            // Skip unsuitable biomes
-            if !is_buildable_biome(&biome) {
-                desirability.set(x, y, f32::MIN);
-                continue;
-            }
-
-            let mut score = 0.0;
+    desirability_from(heightmap, |x, y| {
+        city_factors(heightmap, moisture, temperature, water_bodies, biomes, harbor, existing_structures, x, y)
+    })
+}
 
-            // Strong preference for proximity to fresh water
-            let water_dist = compute_water_distance(water_bodies, x, y, 20);
-            if water_dist <= 5.0 {
-                score += (5.0 - water_dist) * 2.0;
-            } else if water_dist <= 15.0 {
-                score += (15.0 - water_dist) * 0.3;
-            }
+#[allow(clippy::too_many_arguments)]
+fn city_factors(
+    heightmap: &Tilemap<f32>,
+    moisture: &Tilemap<f32>,
+    temperature: &Tilemap<f32>,
+    water_bodies: &Tilemap<WaterBodyId>,
+    biomes: &Tilemap<ExtendedBiome>,
+    harbor: Option<&Tilemap<f32>>,
+    existing_structures: &[PlacedStructure],
+    x: usize,
+    y: usize,
+) -> Option<DesirabilityBreakdown> {
+    if !is_buildable_tile(heightmap, water_bodies, biomes, x, y) {
+        return None;
+    }
+    let elev = *heightmap.get(x, y);
+    let moist = *moisture.get(x, y);
+    let temp = *temperature.get(x, y);
+    let biome = *biomes.get(x, y);
+    let mut factors = DesirabilityBreakdown::default();
+
+    // Strong preference for proximity to fresh water
+    let water_dist = compute_water_distance(water_bodies, x, y, 20);
+    if water_dist <= 5.0 {
+        factors.water_access += (5.0 - water_dist) * 2.0;
+    } else if water_dist <= 15.0 {
+        factors.water_access += (15.0 - water_dist) * 0.3;
+    }
 
-            // Prefer flat terrain - major factor for cities
-            let slope = compute_slope(heightmap, x, y);
-            score += (1.0 - slope.min(1.0)) * 4.0;
-
-            // Prefer low to moderate elevation (not mountains)
-            if elev > 0.0 && elev < 500.0 {
-                score += 2.0;
-            } else if elev >= 500.0 && elev < 1000.0 {
-                score += 1.0;
-            } else if elev >= 1000.0 {
-                score -= (elev - 1000.0) / 500.0;
-            }
+    // A good natural harbour makes a port
+    if let Some(harbor) = harbor {
+        factors.harbor += harbor.get(x, y) * 4.0;
+    }
 
-            // Prefer moderate moisture (fertile land)
-            if moist > 0.3 && moist < 0.8 {
-                score += 2.0;
-            }
+    // Prefer flat terrain - major factor for cities
+    let slope = compute_slope(heightmap, x, y);
+    factors.terrain += (1.0 - slope.min(1.0)) * 4.0;
+
+    // Prefer low to moderate elevation (not mountains)
+    if elev > 0.0 && elev < 500.0 {
+        factors.terrain += 2.0;
+    } else if (500.0..1000.0).contains(&elev) {
+        factors.terrain += 1.0;
+    } else if elev >= 1000.0 {
+        factors.terrain -= (elev - 1000.0) / 500.0;
+    }
 
-            // Prefer moderate temperature
-            if temp > 5.0 && temp < 25.0 {
-                score += 1.5;
-            } else if temp <= 5.0 {
-                score -= (5.0 - temp) * 0.1;
-            } else if temp >= 25.0 {
-                score -= (temp - 25.0) * 0.1;
-            }
+    // Prefer moderate moisture (fertile land)
+    if moist > 0.3 && moist < 0.8 {
+        factors.fertility += 2.0;
+    }
 
-            // Bonus for fertile biomes
-            match biome {
-                ExtendedBiome::TemperateGrassland | ExtendedBiome::TemperateRainforest |
-                ExtendedBiome::TemperateForest | ExtendedBiome::Foothills => {
-                    score += 2.0;
-                }
-                ExtendedBiome::Savanna | ExtendedBiome::TropicalForest => {
-                    score += 1.5;
-                }
-                _ => {}
-            }
+    // Prefer moderate temperature
+    if temp > 5.0 && temp < 25.0 {
+        factors.climate += 1.5;
+    } else if temp <= 5.0 {
+        factors.climate -= (5.0 - temp) * 0.1;
+    } else if temp >= 25.0 {
+        factors.climate -= (temp - 25.0) * 0.1;
+    }
 
-            // Penalize if too close to existing structures
-            for structure in existing_structures {
-                let (cx, cy) = structure.center();
-                let dist = ((x as f32 - cx as f32).powi(2) + (y as f32 - cy as f32).powi(2)).sqrt();
-                if dist < 80.0 {
-                    score -= (80.0 - dist) * 0.1;
-                }
-            }
+    // Bonus for fertile biomes
+    match biome {
+        ExtendedBiome::TemperateGrassland | ExtendedBiome::TemperateRainforest |
+        ExtendedBiome::TemperateForest | ExtendedBiome::Foothills => {
+            factors.fertility += 2.0;
+        }
+        ExtendedBiome::Savanna | ExtendedBiome::TropicalForest => {
+            factors.fertility += 1.5;
+        }
+        _ => {}
+    }
 
-            desirability.set(x, y, score);
+    // Penalize if too close to existing structures
+    for structure in existing_structures {
+        let (cx, cy) = structure.center();
+        let dist = ((x as f32 - cx as f32).powi(2) + (y as f32 - cy as f32).powi(2)).sqrt();
+        if dist < 80.0 {
+            factors.distance -= (80.0 - dist) * 0.1;
         }
     }
 
-    desirability
+    Some(factors)
 }
 
 /// Compute desirability map for village placement
@@ -205,98 +360,89 @@ pub fn compute_village_desirability(
     biomes: &Tilemap<ExtendedBiome>,
     existing_structures: &[PlacedStructure],
 ) -> DesirabilityMap {
-    let width = heightmap.width;
-    let height = heightmap.height;
-    let mut desirability = DesirabilityMap::new(width, height);
-
-    for y in 0..height {
-        for x in 0..width {
-            let elev = *heightmap.get(x, y);
-            let moist = *moisture.get(x, y);
-            let water_id = *water_bodies.get(x, y);
-            let biome = *biomes.get(x, y);
-
-            // Skip underwater tiles
-            if elev <= 0.0 || !water_id.is_none() {
-                desirability.set(x, y, f32::MIN);
-                continue;
-            }
-
-            // Skip unsuitable biomes
-            if !is_buildable_biome(&biome) {
-                desirability.set(x, y, f32::MIN);
-                continue;
-            }
+    desirability_from(heightmap, |x, y| {
+        village_factors(heightmap, moisture, water_bodies, biomes, existing_structures, x, y)
+    })
+}
 
-            let mut score = 0.0;
+fn village_factors(
+    heightmap: &Tilemap<f32>,
+    moisture: &Tilemap<f32>,
+    water_bodies: &Tilemap<WaterBodyId>,
+    biomes: &Tilemap<ExtendedBiome>,
+    existing_structures: &[PlacedStructure],
+    x: usize,
+    y: usize,
+) -> Option<DesirabilityBreakdown> {
+    if !is_buildable_tile(heightmap, water_bodies, biomes, x, y) {
+        return None;
+    }
+    let moist = *moisture.get(x, y);
+    let mut factors = DesirabilityBreakdown::default();
 
-            // Prefer flat terrain
-            let slope = compute_slope(heightmap, x, y);
-            score += (1.0 - slope.min(1.0)) * 2.0;
+    // Prefer flat terrain
+    let slope = compute_slope(heightmap, x, y);
+    factors.terrain += (1.0 - slope.min(1.0)) * 2.0;
 
-            // Prefer proximity to fresh water
-            let water_dist = compute_water_distance(water_bodies, x, y, 15);
-            if water_dist <= 5.0 {
-                score += (5.0 - water_dist) * 1.0;
-            }
+    // Prefer proximity to fresh water
+    let water_dist = compute_water_distance(water_bodies, x, y, 15);
+    if water_dist <= 5.0 {
+        factors.water_access += (5.0 - water_dist) * 1.0;
+    }
 
-            // Prefer moderate moisture
-            if moist > 0.2 && moist < 0.7 {
-                score += 1.0;
-            }
+    // Prefer moderate moisture
+    if moist > 0.2 && moist < 0.7 {
+        factors.fertility += 1.0;
+    }
 
-            // Prefer being near (but not too close to) major structures
-            let mut near_major = false;
-            for structure in existing_structures {
-                if matches!(structure.structure_type, StructureType::Castle | StructureType::City) {
-                    let (cx, cy) = structure.center();
-                    let dist = ((x as f32 - cx as f32).powi(2) + (y as f32 - cy as f32).powi(2)).sqrt();
-
-                    if dist > 30.0 && dist < 100.0 {
-                        score += 2.0; // Sweet spot distance
-                        near_major = true;
-                    } else if dist <= 30.0 {
-                        score -= 3.0; // Too close to major structure
-                    }
-                }
+    // Prefer being near (but not too close to) major structures
+    let mut near_major = false;
+    for structure in existing_structures {
+        if matches!(structure.structure_type, StructureType::Castle | StructureType::City) {
+            let (cx, cy) = structure.center();
+            let dist = ((x as f32 - cx as f32).powi(2) + (y as f32 - cy as f32).powi(2)).sqrt();
+
+            if dist > 30.0 && dist < 100.0 {
+                factors.distance += 2.0; // Sweet spot distance
+                near_major = true;
+            } else if dist <= 30.0 {
+                factors.distance -= 3.0; // Too close to major structure
             }
+        }
+    }
 
-            // Bonus for areas along probable trade routes (between major structures)
-            if !near_major && existing_structures.len() >= 2 {
-                let mut min_path_dist = f32::MAX;
-                for i in 0..existing_structures.len() {
-                    for j in (i + 1)..existing_structures.len() {
-                        let (ax, ay) = existing_structures[i].center();
-                        let (bx, by) = existing_structures[j].center();
-                        let path_dist = point_to_line_distance(
-                            x as f32, y as f32,
-                            ax as f32, ay as f32,
-                            bx as f32, by as f32,
-                        );
-                        min_path_dist = min_path_dist.min(path_dist);
-                    }
-                }
-                if min_path_dist < 20.0 {
-                    score += (20.0 - min_path_dist) * 0.2;
-                }
+    // Bonus for areas along probable trade routes (between major structures)
+    if !near_major && existing_structures.len() >= 2 {
+        let mut min_path_dist = f32::MAX;
+        for i in 0..existing_structures.len() {
+            for j in (i + 1)..existing_structures.len() {
+                let (ax, ay) = existing_structures[i].center();
+                let (bx, by) = existing_structures[j].center();
+                let path_dist = point_to_line_distance(
+                    x as f32, y as f32,
+                    ax as f32, ay as f32,
+                    bx as f32, by as f32,
+                );
+                min_path_dist = min_path_dist.min(path_dist);
             }
+        }
+        if min_path_dist < 20.0 {
+            factors.trade += (20.0 - min_path_dist) * 0.2;
+        }
+    }
 
-            // Penalize if too close to existing villages
-            for structure in existing_structures {
-                if structure.structure_type == StructureType::Village {
-                    let (cx, cy) = structure.center();
-                    let dist = ((x as f32 - cx as f32).powi(2) + (y as f32 - cy as f32).powi(2)).sqrt();
-                    if dist < 25.0 {
-                        score -= (25.0 - dist) * 0.3;
-                    }
-                }
+    // Penalize if too close to existing villages
+    for structure in existing_structures {
+        if structure.structure_type == StructureType::Village {
+            let (cx, cy) = structure.center();
+            let dist = ((x as f32 - cx as f32).powi(2) + (y as f32 - cy as f32).powi(2)).sqrt();
+            if dist < 25.0 {
+                factors.distance -= (25.0 - dist) * 0.3;
             }
-
-            desirability.set(x, y, score);
         }
     }
 
-    desirability
+    Some(factors)
 }
 
 /// Check if a biome is suitable for building structures
@@ -306,6 +452,7 @@ fn is_buildable_biome(biome: &ExtendedBiome) -> bool {
         ExtendedBiome::DeepOcean | ExtendedBiome::Ocean | ExtendedBiome::CoastalWater |
         ExtendedBiome::Lagoon | ExtendedBiome::FrozenLake | ExtendedBiome::LavaLake |
         ExtendedBiome::AcidLake | ExtendedBiome::BioluminescentWater | ExtendedBiome::Ice |
+        ExtendedBiome::IceSheet | ExtendedBiome::PackIce | ExtendedBiome::Polynya |
         ExtendedBiome::VolcanicWasteland | ExtendedBiome::Ashlands | ExtendedBiome::SulfurVents
     )
 }
@@ -450,3 +597,93 @@ pub fn place_structures_from_desirability(
 
     structures
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flat temperate land at 200 m with a one-tile lake in the middle
+    struct Lakeside {
+        heightmap: Tilemap<f32>,
+        moisture: Tilemap<f32>,
+        temperature: Tilemap<f32>,
+        stress: Tilemap<f32>,
+        water: Tilemap<WaterBodyId>,
+        biomes: Tilemap<ExtendedBiome>,
+    }
+
+    impl Lakeside {
+        fn new(size: usize) -> Self {
+            let mut water = Tilemap::new_with(size, size, WaterBodyId::NONE);
+            water.set(size / 2, size / 2, WaterBodyId(2));
+            Self {
+                heightmap: Tilemap::new_with(size, size, 200.0),
+                moisture: Tilemap::new_with(size, size, 0.5),
+                temperature: Tilemap::new_with(size, size, 15.0),
+                stress: Tilemap::new_with(size, size, 0.0),
+                water,
+                biomes: Tilemap::new_with(size, size, ExtendedBiome::TemperateGrassland),
+            }
+        }
+
+        fn layers(&self) -> DesirabilityLayers<'_> {
+            DesirabilityLayers {
+                heightmap: &self.heightmap,
+                moisture: &self.moisture,
+                temperature: &self.temperature,
+                stress_map: &self.stress,
+                water_bodies: &self.water,
+                biomes: &self.biomes,
+                harbor: None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_breakdown_sums_to_desirability() {
+        let world = Lakeside::new(24);
+        let layers = world.layers();
+        let maps = [
+            compute_castle_desirability(&world.heightmap, &world.stress, &world.water, &world.biomes, &[]),
+            compute_city_desirability(
+                &world.heightmap,
+                &world.moisture,
+                &world.temperature,
+                &world.water,
+                &world.biomes,
+                None,
+                &[],
+            ),
+            compute_village_desirability(&world.heightmap, &world.moisture, &world.water, &world.biomes, &[]),
+        ];
+        for (kind, map) in DESIRABILITY_TYPES.iter().zip(&maps) {
+            for (x, y, _) in world.heightmap.iter() {
+                let score = explain_desirability(*kind, &layers, &[], x, y).map_or(f32::MIN, |b| b.total());
+                assert_eq!(map.get(x, y), score);
+            }
+        }
+
+        // The lake itself can't be built on; next to it, water access drives the city score
+        assert!(explain_desirability(StructureType::City, &layers, &[], 12, 12).is_none());
+        let shore = explain_desirability(StructureType::City, &layers, &[], 13, 12).unwrap();
+        let inland = explain_desirability(StructureType::City, &layers, &[], 2, 2).unwrap();
+        assert!(shore.water_access > inland.water_access);
+        assert_eq!((shore.fertility, shore.climate), (inland.fertility, inland.climate));
+        assert!(explain_desirability(StructureType::Dungeon, &layers, &[], 2, 2).is_none());
+    }
+
+    #[test]
+    fn test_crowding_shows_as_distance_penalty() {
+        let world = Lakeside::new(24);
+        let layers = world.layers();
+        let castle = PlacedStructure::new(0, 0, 0, 6, 6, StructureType::Castle);
+        let alone = explain_desirability(StructureType::City, &layers, &[], 4, 4).unwrap();
+        let crowded = explain_desirability(StructureType::City, &layers, &[castle], 4, 4).unwrap();
+
+        assert_eq!(alone.distance, 0.0);
+        assert!(crowded.distance < 0.0);
+        assert!((crowded.total() - crowded.distance - alone.total()).abs() < 1e-4);
+        assert!(crowded.describe().contains("distance -"));
+        assert!(!alone.describe().contains("distance"));
+    }
+}
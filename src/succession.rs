@@ -0,0 +1,339 @@
+//! Biome succession: land recovering after disturbance
+//!
+//! Forest does not spring back the year a field is abandoned or a fire goes
+//! out. Each vegetated tile keeps the years since it was last cleared, and
+//! passes through bare ground, grassland, shrubland and young forest before it
+//! is old growth again; how far it can go is set by its climax biome, so
+//! grassland climates stop at grass. Disturbances come from the history:
+//! - Fields worked around every settlement, left to regrow when it was abandoned
+//! - Forest burned by dragons and volcanic eruptions
+//! - Land newly raised from the sea as the world ages
+//!
+//! `apply_succession_biomes` then shows forest climates that have not yet
+//! recovered as open country.
+
+use crate::biomes::ExtendedBiome;
+#[cfg(feature = "history")]
+use crate::history::supply::{biome_fertility, reach_tiles};
+#[cfg(feature = "history")]
+use crate::history::types::Year;
+#[cfg(feature = "history")]
+use crate::history::EventType;
+use crate::history::WorldHistory;
+use crate::tilemap::Tilemap;
+
+/// Years each stage of succession lasts
+#[derive(Clone, Debug)]
+pub struct SuccessionConfig {
+    /// Years bare ground takes to grass over
+    pub grass_years: u32,
+    /// Years since disturbance at which shrubs take over the grass
+    pub shrub_years: u32,
+    /// Years since disturbance at which young trees close over the shrubs
+    pub young_forest_years: u32,
+    /// Years since disturbance after which a forest counts as old growth
+    pub old_growth_years: u32,
+    /// Radius (tiles) of forest burned by a dragon attack or an eruption
+    pub burn_radius: i32,
+}
+
+impl Default for SuccessionConfig {
+    fn default() -> Self {
+        Self {
+            grass_years: 3,
+            shrub_years: 20,
+            young_forest_years: 60,
+            old_growth_years: 250,
+            burn_radius: 3,
+        }
+    }
+}
+
+/// Stage of recovery after the land was last cleared
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub enum SuccessionStage {
+    /// Fields under cultivation
+    Farmland,
+    /// Freshly burned or abandoned ground
+    Bare,
+    Grassland,
+    Shrubland,
+    YoungForest,
+    OldGrowth,
+}
+
+impl SuccessionStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SuccessionStage::Farmland => "Farmland",
+            SuccessionStage::Bare => "Bare ground",
+            SuccessionStage::Grassland => "Grassland",
+            SuccessionStage::Shrubland => "Shrubland",
+            SuccessionStage::YoungForest => "Young forest",
+            SuccessionStage::OldGrowth => "Old-growth forest",
+        }
+    }
+
+    /// Whether the stage is closed forest
+    pub fn is_forest(&self) -> bool {
+        matches!(self, SuccessionStage::YoungForest | SuccessionStage::OldGrowth)
+    }
+}
+
+/// Forest biomes that succession can clear and regrow
+pub(crate) fn is_forest_biome(biome: ExtendedBiome) -> bool {
+    matches!(
+        biome,
+        ExtendedBiome::TemperateForest
+            | ExtendedBiome::BorealForest
+            | ExtendedBiome::TemperateRainforest
+            | ExtendedBiome::TropicalForest
+            | ExtendedBiome::TropicalRainforest
+    )
+}
+
+/// Open biomes whose climax is grass
+fn is_grass_biome(biome: ExtendedBiome) -> bool {
+    matches!(biome, ExtendedBiome::TemperateGrassland | ExtendedBiome::Savanna | ExtendedBiome::Foothills)
+}
+
+/// The open country a forest climate shows before the trees return
+fn open_biome(climax: ExtendedBiome) -> ExtendedBiome {
+    match climax {
+        ExtendedBiome::TropicalForest | ExtendedBiome::TropicalRainforest => ExtendedBiome::Savanna,
+        _ => ExtendedBiome::TemperateGrassland,
+    }
+}
+
+/// Per-tile succession state
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SuccessionMap {
+    /// Biome the tile returns to when left alone; `None` where nothing succeeds
+    pub climax: Tilemap<Option<ExtendedBiome>>,
+    /// Years since the tile was last cleared
+    pub since: Tilemap<u32>,
+    /// Tiles being farmed, cleared again every year
+    pub cultivated: Tilemap<bool>,
+}
+
+impl SuccessionMap {
+    /// Stage of the tile at (x, y), if it takes part in succession
+    pub fn stage_at(&self, x: usize, y: usize, config: &SuccessionConfig) -> Option<SuccessionStage> {
+        let climax = (*self.climax.get(x, y))?;
+        if *self.cultivated.get(x, y) {
+            return Some(SuccessionStage::Farmland);
+        }
+        let since = *self.since.get(x, y);
+        let stage = if since < config.grass_years {
+            SuccessionStage::Bare
+        } else if since < config.shrub_years {
+            SuccessionStage::Grassland
+        } else if since < config.young_forest_years {
+            SuccessionStage::Shrubland
+        } else if since < config.old_growth_years {
+            SuccessionStage::YoungForest
+        } else {
+            SuccessionStage::OldGrowth
+        };
+        let ceiling = if is_forest_biome(climax) { SuccessionStage::OldGrowth } else { SuccessionStage::Grassland };
+        Some(stage.min(ceiling))
+    }
+
+    /// Clear a tile `years_ago`, unless it was cleared more recently
+    pub fn disturb(&mut self, x: usize, y: usize, years_ago: u32) {
+        if self.climax.get(x, y).is_some() && years_ago < *self.since.get(x, y) {
+            self.since.set(x, y, years_ago);
+        }
+    }
+
+    /// Advance every tile by one simulated year
+    pub fn step_year(&mut self) {
+        self.advance(1);
+    }
+
+    /// Advance every tile by `years`: the same as `years` calls to `step_year`,
+    /// since a tile's stage depends only on the years since it was cleared
+    pub fn advance(&mut self, years: u32) {
+        let width = self.since.width;
+        for y in 0..self.since.height {
+            for x in 0..width {
+                let since = if *self.cultivated.get(x, y) { 0 } else { self.since.get(x, y).saturating_add(years) };
+                self.since.set(x, y, since);
+            }
+        }
+    }
+
+    /// Start succession again `since` years ago on land that has changed
+    /// climate or risen from the sea; `None` takes the tile out of succession
+    pub fn reset(&mut self, x: usize, y: usize, climax: Option<ExtendedBiome>, since: u32) {
+        let climax = climax.filter(|&b| is_forest_biome(b) || is_grass_biome(b));
+        self.climax.set(x, y, climax);
+        self.since.set(x, y, since);
+        self.cultivated.set(x, y, false);
+    }
+
+    /// Short description for tile info panels; tiles at their climax have nothing to add
+    pub fn describe(&self, x: usize, y: usize, config: &SuccessionConfig) -> Option<String> {
+        let stage = self.stage_at(x, y, config)?;
+        let since = *self.since.get(x, y);
+        match stage {
+            SuccessionStage::Farmland => Some("Farmland".to_string()),
+            SuccessionStage::OldGrowth if since < config.old_growth_years * 4 => {
+                Some(format!("Old-growth forest, cleared {} years ago", since))
+            }
+            SuccessionStage::OldGrowth => None,
+            SuccessionStage::Grassland if !self.climax.get(x, y).is_some_and(is_forest_biome) => None,
+            stage => Some(format!("{}, regrowing for {} years", stage.name(), since)),
+        }
+    }
+}
+
+/// Start every forest and grassland tile at its climax, long undisturbed
+pub fn initial_succession(biomes: &Tilemap<ExtendedBiome>) -> SuccessionMap {
+    let (width, height) = (biomes.width, biomes.height);
+    let mut map = SuccessionMap {
+        climax: Tilemap::new_with(width, height, None),
+        since: Tilemap::new_with(width, height, u32::MAX),
+        cultivated: Tilemap::new_with(width, height, false),
+    };
+    for (x, y, &biome) in biomes.iter() {
+        if is_forest_biome(biome) || is_grass_biome(biome) {
+            map.climax.set(x, y, Some(biome));
+        }
+    }
+    map
+}
+
+/// Years before the present (year 0) of a historical year
+#[cfg(feature = "history")]
+fn years_ago(year: Year) -> u32 {
+    (-year.0).max(0) as u32
+}
+
+/// Record the history's disturbances: fields around every settlement,
+/// worked while it stands and regrowing since it was abandoned, and forest
+/// burned by dragons and eruptions. Returns the number of tiles disturbed.
+#[cfg(feature = "history")]
+pub fn apply_history_disturbances(map: &mut SuccessionMap, history: &WorldHistory, config: &SuccessionConfig) -> usize {
+    let (width, height) = (map.since.width, map.since.height);
+    let mut disturbed = 0;
+
+    // Abandoned fields first, so land shared with a living settlement stays farmed
+    let mut settlements: Vec<_> = history.territories.settlements.values().collect();
+    settlements.sort_by_key(|s| (s.is_active(), s.id.0));
+    for settlement in settlements {
+        let abandoned = (!settlement.is_active()).then(|| settlement.abandoned.map_or(0, years_ago));
+        for (x, y) in reach_tiles(settlement, width, height) {
+            let Some(climax) = *map.climax.get(x, y) else { continue };
+            if biome_fertility(climax) < 0.3 {
+                continue;
+            }
+            match abandoned {
+                // Fields worked until the end regrow from the year they were left
+                Some(years) if *map.cultivated.get(x, y) => {
+                    map.cultivated.set(x, y, false);
+                    map.since.set(x, y, years);
+                }
+                Some(years) => map.disturb(x, y, years),
+                None => map.cultivated.set(x, y, true),
+            }
+            disturbed += 1;
+        }
+    }
+
+    let r = config.burn_radius;
+    for event in history.timeline.events.values() {
+        if !matches!(event.event_type, EventType::DragonAttack | EventType::VolcanicEruption) || event.year.0 > 0 {
+            continue;
+        }
+        let Some((cx, cy)) = event.location else { continue };
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let y = cy as i32 + dy;
+                if dx * dx + dy * dy > r * r || y < 0 || y >= height as i32 {
+                    continue;
+                }
+                let x = (cx as i32 + dx).rem_euclid(width as i32) as usize;
+                if map.climax.get(x, y as usize).is_some_and(is_forest_biome) && !*map.cultivated.get(x, y as usize) {
+                    map.disturb(x, y as usize, years_ago(event.year));
+                    disturbed += 1;
+                }
+            }
+        }
+    }
+
+    disturbed
+}
+
+/// Succession state for a freshly generated world
+#[cfg_attr(not(feature = "history"), allow(unused_variables, unused_mut))]
+pub fn generate_succession(
+    biomes: &Tilemap<ExtendedBiome>,
+    history: Option<&WorldHistory>,
+    config: &SuccessionConfig,
+) -> SuccessionMap {
+    let mut map = initial_succession(biomes);
+    #[cfg(feature = "history")]
+    if let Some(history) = history {
+        apply_history_disturbances(&mut map, history, config);
+    }
+    map
+}
+
+/// Show forest climates that have not recovered as open country, and
+/// recovered ones as forest again. Returns the number of tiles that became forest.
+pub fn apply_succession_biomes(biomes: &mut Tilemap<ExtendedBiome>, map: &SuccessionMap, config: &SuccessionConfig) -> usize {
+    let mut regrown = 0;
+    for y in 0..biomes.height {
+        for x in 0..biomes.width {
+            let Some(climax) = *map.climax.get(x, y) else { continue };
+            if !is_forest_biome(climax) {
+                continue;
+            }
+            let forest = map.stage_at(x, y, config).is_some_and(|s| s.is_forest());
+            let biome = if forest { climax } else { open_biome(climax) };
+            if forest && *biomes.get(x, y) != climax {
+                regrown += 1;
+            }
+            biomes.set(x, y, biome);
+        }
+    }
+    regrown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cleared_forest_regrows_through_each_stage() {
+        let config = SuccessionConfig::default();
+        let mut biomes = Tilemap::new_with(8, 8, ExtendedBiome::TemperateForest);
+        biomes.set(7, 7, ExtendedBiome::TemperateGrassland);
+        let mut map = initial_succession(&biomes);
+        map.disturb(2, 2, 0);
+        map.disturb(7, 7, 0);
+        assert_eq!(apply_succession_biomes(&mut biomes, &map, &config), 0);
+        assert_eq!(*biomes.get(2, 2), ExtendedBiome::TemperateGrassland);
+
+        let mut stages = vec![map.stage_at(2, 2, &config).unwrap()];
+        for _ in 0..config.old_growth_years {
+            map.step_year();
+            let stage = map.stage_at(2, 2, &config).unwrap();
+            if stages.last() != Some(&stage) {
+                stages.push(stage);
+            }
+        }
+        use SuccessionStage::*;
+        assert_eq!(stages, vec![Bare, Grassland, Shrubland, YoungForest, OldGrowth]);
+
+        // Grassland climates stop at grass, and cultivated fields never regrow
+        assert_eq!(map.stage_at(7, 7, &config), Some(Grassland));
+        map.cultivated.set(3, 3, true);
+        map.advance(1000);
+        assert_eq!(map.stage_at(3, 3, &config), Some(Farmland));
+        assert_eq!(apply_succession_biomes(&mut biomes, &map, &config), 1);
+        assert_eq!(*biomes.get(2, 2), ExtendedBiome::TemperateForest);
+        assert_eq!(*biomes.get(3, 3), ExtendedBiome::TemperateGrassland);
+    }
+}
@@ -0,0 +1,445 @@
+//! Solar system generation
+//!
+//! Generates a star and a family of planets from a single seed. Each planet's
+//! `PlanetParams` follow from its orbit (equilibrium temperature, frost line, size),
+//! and rocky planets get a full `WorldData` built with the matching generator:
+//! - Temperate planets use the standard Earthlike pipeline
+//! - Hot, cold and frozen planets are re-paletted with an alien climate chemistry
+//! - Small planets become airless cratered bodies
+//! - Gas giants are described by parameters only
+//!
+//! Every planet seed is derived from the system seed, so the same seed always
+//! produces the same planet family.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::biomes::ExtendedBiome;
+use crate::chemistry::{self, ClimateChemistry};
+use crate::craters::{self, CraterParams};
+use crate::world::{self, WorldData};
+
+/// Mixing constant for deriving planet seeds from the system seed
+const SEED_MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Equilibrium temperature (K) of a body at 1 AU from a 1 L☉ star
+const EQUILIBRIUM_TEMP_1AU: f32 = 278.6;
+
+/// Greenhouse warming for planets with an atmosphere (°C)
+const GREENHOUSE_WARMING: f32 = 33.0;
+
+/// Radius (Earth radii) below which a planet cannot hold an atmosphere
+const AIRLESS_RADIUS: f32 = 0.45;
+
+const STAR_PREFIXES: &[&str] = &["Ar", "Bel", "Cor", "Dra", "El", "Fen", "Gal", "Hel", "Ix", "Kor", "Lum", "Mor", "Nar", "Or", "Sol", "Tor", "Vel", "Zan"];
+const STAR_SUFFIXES: &[&str] = &["ax", "ion", "is", "ara", "eth", "un", "os", "ira", "ul", "ane"];
+const NUMERALS: &[&str] = &["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X", "XI", "XII"];
+
+/// Main-sequence spectral class
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SpectralClass {
+    M,
+    K,
+    G,
+    F,
+    A,
+}
+
+impl SpectralClass {
+    /// Stellar mass range (solar masses)
+    fn mass_range(&self) -> (f32, f32) {
+        match self {
+            SpectralClass::M => (0.08, 0.45),
+            SpectralClass::K => (0.45, 0.8),
+            SpectralClass::G => (0.8, 1.04),
+            SpectralClass::F => (1.04, 1.4),
+            SpectralClass::A => (1.4, 2.1),
+        }
+    }
+}
+
+/// The system's star
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Star {
+    pub name: String,
+    pub spectral_class: SpectralClass,
+    /// Mass in solar masses
+    pub mass: f32,
+    /// Luminosity in solar luminosities
+    pub luminosity: f32,
+}
+
+impl Star {
+    /// Orbital distance (AU) beyond which volatiles freeze
+    pub fn frost_line(&self) -> f32 {
+        2.7 * self.luminosity.sqrt()
+    }
+
+    /// Surface equilibrium temperature (°C) at the given orbit, before greenhouse warming
+    pub fn equilibrium_temp(&self, orbit_au: f32) -> f32 {
+        EQUILIBRIUM_TEMP_1AU * self.luminosity.powf(0.25) / orbit_au.max(0.01).sqrt() - 273.15
+    }
+}
+
+/// Broad planet class, chosen from orbit and size
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PlanetKind {
+    /// Liquid water world using the standard pipeline
+    Temperate,
+    /// Scorching world with sulfuric acid lakes
+    Hot,
+    /// Cold ammonia world
+    Cold,
+    /// Frozen methane world
+    Frozen,
+    /// Too small to hold an atmosphere: craters and regolith
+    Airless,
+    /// Gas giant (no surface map)
+    GasGiant,
+}
+
+impl PlanetKind {
+    /// Alien chemistry preset used to re-palette this planet, if any
+    pub fn chemistry(&self) -> Option<ClimateChemistry> {
+        match self {
+            PlanetKind::Hot => Some(ClimateChemistry::sulfur()),
+            PlanetKind::Cold => Some(ClimateChemistry::fungal()),
+            PlanetKind::Frozen => Some(ClimateChemistry::titan()),
+            _ => None,
+        }
+    }
+}
+
+/// Physical and generation parameters of one planet, derived from its orbit
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct PlanetParams {
+    pub name: String,
+    /// Orbit index from the star (0 = innermost)
+    pub index: usize,
+    /// Seed used for this planet's world (derived from the system seed)
+    pub seed: u64,
+    /// Orbital semi-major axis (AU)
+    pub orbit_au: f32,
+    /// Orbital period (Earth years)
+    pub period_years: f32,
+    /// Radius (Earth radii)
+    pub radius: f32,
+    /// Mean surface temperature (°C), including greenhouse warming
+    pub surface_temp: f32,
+    pub kind: PlanetKind,
+    /// Map size used for the planet's world
+    pub width: usize,
+    pub height: usize,
+}
+
+/// A generated planet
+pub struct Planet {
+    pub params: PlanetParams,
+    /// Surface world (None for gas giants or when worlds were not requested)
+    pub world: Option<WorldData>,
+}
+
+/// Options for system generation
+#[derive(Clone, Debug)]
+pub struct SystemConfig {
+    /// Number of planets (random 3-8 if None)
+    pub planet_count: Option<usize>,
+    /// Map width of an Earth-sized planet; other planets scale with radius
+    pub world_width: usize,
+    /// Map height of an Earth-sized planet
+    pub world_height: usize,
+    /// Build a `WorldData` for every planet with a surface
+    pub generate_worlds: bool,
+}
+
+impl Default for SystemConfig {
+    fn default() -> Self {
+        Self {
+            planet_count: None,
+            world_width: 256,
+            world_height: 128,
+            generate_worlds: true,
+        }
+    }
+}
+
+/// A star and its planets
+pub struct SolarSystem {
+    pub seed: u64,
+    pub star: Star,
+    pub planets: Vec<Planet>,
+}
+
+/// Per-planet summary for JSON export
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct PlanetSummary {
+    #[serde(flatten)]
+    pub params: PlanetParams,
+    /// Fraction of tiles above sea level (None without a world)
+    pub land_fraction: Option<f32>,
+    /// Most common biome (None without a world)
+    pub dominant_biome: Option<ExtendedBiome>,
+}
+
+/// System-level JSON export
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SystemSummary {
+    pub seed: u64,
+    pub star: Star,
+    pub frost_line_au: f32,
+    pub planets: Vec<PlanetSummary>,
+}
+
+/// Derive a planet's seed from the system seed
+pub fn planet_seed(system_seed: u64, index: usize) -> u64 {
+    system_seed ^ (index as u64 + 1).wrapping_mul(SEED_MIX)
+}
+
+fn generate_star(rng: &mut ChaCha8Rng) -> Star {
+    let roll: f32 = rng.gen();
+    let spectral_class = match roll {
+        r if r < 0.5 => SpectralClass::M,
+        r if r < 0.75 => SpectralClass::K,
+        r if r < 0.9 => SpectralClass::G,
+        r if r < 0.97 => SpectralClass::F,
+        _ => SpectralClass::A,
+    };
+    let (min_mass, max_mass) = spectral_class.mass_range();
+    let mass = rng.gen_range(min_mass..max_mass);
+
+    // Main-sequence mass-luminosity relation
+    let luminosity = if mass < 0.43 { 0.23 * mass.powf(2.3) } else { mass.powi(4) };
+
+    let name = format!(
+        "{}{}",
+        STAR_PREFIXES[rng.gen_range(0..STAR_PREFIXES.len())],
+        STAR_SUFFIXES[rng.gen_range(0..STAR_SUFFIXES.len())]
+    );
+
+    Star { name, spectral_class, mass, luminosity }
+}
+
+fn classify_planet(star: &Star, orbit_au: f32, radius: f32, surface_temp: f32) -> PlanetKind {
+    if radius >= 3.0 {
+        PlanetKind::GasGiant
+    } else if radius < AIRLESS_RADIUS || orbit_au < 0.1 * star.luminosity.sqrt() {
+        // Small bodies and those skimming the star are stripped of their atmosphere
+        PlanetKind::Airless
+    } else if surface_temp > 80.0 {
+        PlanetKind::Hot
+    } else if surface_temp >= -25.0 {
+        PlanetKind::Temperate
+    } else if surface_temp >= -110.0 {
+        PlanetKind::Cold
+    } else {
+        PlanetKind::Frozen
+    }
+}
+
+/// Derive the parameters of every planet in the system from its orbit
+pub fn generate_planet_params(seed: u64, star: &Star, config: &SystemConfig, rng: &mut ChaCha8Rng) -> Vec<PlanetParams> {
+    let count = config.planet_count.unwrap_or_else(|| rng.gen_range(3..=8)).min(NUMERALS.len());
+    let frost_line = star.frost_line();
+
+    // Roughly geometric orbit spacing, scaled to the star's habitable distance
+    let mut orbit_au = rng.gen_range(0.2..0.5) * star.luminosity.sqrt().max(0.05);
+    let mut params = Vec::with_capacity(count);
+
+    for (index, numeral) in NUMERALS.iter().enumerate().take(count) {
+        let beyond_frost = orbit_au > frost_line;
+        let radius = if beyond_frost && rng.gen_bool(0.6) {
+            rng.gen_range(3.5..11.0)
+        } else {
+            rng.gen_range(0.25..1.8)
+        };
+
+        let equilibrium = star.equilibrium_temp(orbit_au);
+        let has_atmosphere = radius >= AIRLESS_RADIUS;
+        let surface_temp = equilibrium + if has_atmosphere { GREENHOUSE_WARMING } else { 0.0 };
+        let kind = classify_planet(star, orbit_au, radius, surface_temp);
+
+        // Larger planets get proportionally larger maps (kept even and non-degenerate)
+        let size_scale = radius.clamp(0.25, 2.0);
+        let width = (((config.world_width as f32 * size_scale) as usize).max(32) / 2) * 2;
+        let height = (((config.world_height as f32 * size_scale) as usize).max(16) / 2) * 2;
+
+        params.push(PlanetParams {
+            name: format!("{} {}", star.name, numeral),
+            index,
+            seed: planet_seed(seed, index),
+            orbit_au,
+            period_years: (orbit_au.powi(3) / star.mass).sqrt(),
+            radius,
+            surface_temp,
+            kind,
+            width,
+            height,
+        });
+
+        orbit_au *= rng.gen_range(1.4..2.1);
+    }
+
+    params
+}
+
+/// Build the surface world for a planet
+pub fn generate_planet_world(params: &PlanetParams) -> Option<WorldData> {
+    match params.kind {
+        PlanetKind::GasGiant => None,
+        PlanetKind::Airless => {
+            let (world, _) = craters::generate_airless_world(params.width, params.height, params.seed, &CraterParams::default());
+            Some(world)
+        }
+        kind => {
+            let mut world = world::generate_world(params.width, params.height, params.seed);
+            if let Some(chem) = kind.chemistry() {
+                world.temperature = chem.remap_temperature(&world.temperature);
+                world.biomes = chemistry::generate_exotic_biomes(&world.heightmap, &world.temperature, &world.moisture, &chem);
+                world.biome_feather_map = None;
+                // Earthlike civilizations don't belong on alien chemistry worlds
+                world.history = None;
+            }
+            Some(world)
+        }
+    }
+}
+
+/// Generate a star and its planets from one seed
+pub fn generate_system(seed: u64, config: &SystemConfig) -> SolarSystem {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let star = generate_star(&mut rng);
+    let planets = generate_planet_params(seed, &star, config, &mut rng)
+        .into_iter()
+        .map(|params| {
+            let world = if config.generate_worlds { generate_planet_world(&params) } else { None };
+            Planet { params, world }
+        })
+        .collect();
+
+    SolarSystem { seed, star, planets }
+}
+
+fn dominant_biome(world: &WorldData) -> ExtendedBiome {
+    let mut counts: std::collections::HashMap<ExtendedBiome, usize> = std::collections::HashMap::new();
+    for (_, _, &biome) in world.biomes.iter() {
+        *counts.entry(biome).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(biome, count)| (count, biome as usize))
+        .map(|(biome, _)| biome)
+        .unwrap_or(ExtendedBiome::Ocean)
+}
+
+impl SolarSystem {
+    /// Summarize the system for export
+    pub fn summary(&self) -> SystemSummary {
+        let planets = self
+            .planets
+            .iter()
+            .map(|planet| {
+                let land_fraction = planet.world.as_ref().map(|w| {
+                    w.heightmap.iter().filter(|(_, _, &h)| h >= 0.0).count() as f32 / (w.width * w.height) as f32
+                });
+                PlanetSummary {
+                    params: planet.params.clone(),
+                    land_fraction,
+                    dominant_biome: planet.world.as_ref().map(dominant_biome),
+                }
+            })
+            .collect();
+
+        SystemSummary {
+            seed: self.seed,
+            star: self.star.clone(),
+            frost_line_au: self.star.frost_line(),
+            planets,
+        }
+    }
+
+    /// Serialize the system summary as JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.summary()).expect("SystemSummary is always serializable")
+    }
+
+    /// Write the system summary to a JSON file
+    pub fn export_json(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params_only(planet_count: Option<usize>) -> SystemConfig {
+        SystemConfig {
+            planet_count,
+            generate_worlds: false,
+            ..SystemConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_system_is_deterministic() {
+        let a = generate_system(42, &params_only(None)).summary();
+        let b = generate_system(42, &params_only(None)).summary();
+        assert_eq!(serde_json::to_string(&a).unwrap(), serde_json::to_string(&b).unwrap());
+
+        let c = generate_system(43, &params_only(None)).summary();
+        assert_ne!(serde_json::to_string(&a).unwrap(), serde_json::to_string(&c).unwrap());
+    }
+
+    #[test]
+    fn test_orbits_increase_and_cool() {
+        let system = generate_system(7, &params_only(Some(6)));
+        assert_eq!(system.planets.len(), 6);
+        for pair in system.planets.windows(2) {
+            let (inner, outer) = (&pair[0].params, &pair[1].params);
+            assert!(outer.orbit_au > inner.orbit_au);
+            assert!(outer.period_years > inner.period_years);
+            assert!(system.star.equilibrium_temp(outer.orbit_au) < system.star.equilibrium_temp(inner.orbit_au));
+            assert_ne!(inner.seed, outer.seed);
+        }
+        assert!(system.planets[0].params.name.ends_with(" I"));
+    }
+
+    #[test]
+    fn test_classification() {
+        let star = Star { name: "Sol".to_string(), spectral_class: SpectralClass::G, mass: 1.0, luminosity: 1.0 };
+        assert_eq!(classify_planet(&star, 1.0, 1.0, 15.0), PlanetKind::Temperate);
+        assert_eq!(classify_planet(&star, 0.7, 1.0, 460.0), PlanetKind::Hot);
+        assert_eq!(classify_planet(&star, 9.5, 1.0, -180.0), PlanetKind::Frozen);
+        assert_eq!(classify_planet(&star, 1.5, 0.3, -60.0), PlanetKind::Airless);
+        assert_eq!(classify_planet(&star, 5.2, 11.0, -150.0), PlanetKind::GasGiant);
+        assert!((star.equilibrium_temp(1.0) - 5.45).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_airless_planet_world_and_export() {
+        let params = PlanetParams {
+            name: "Test I".to_string(),
+            index: 0,
+            seed: planet_seed(1, 0),
+            orbit_au: 0.4,
+            period_years: 0.25,
+            radius: 0.3,
+            surface_temp: 150.0,
+            kind: PlanetKind::Airless,
+            width: 32,
+            height: 16,
+        };
+        let world = generate_planet_world(&params).unwrap();
+        assert_eq!((world.width, world.height), (32, 16));
+
+        let system = SolarSystem {
+            seed: 1,
+            star: Star { name: "Test".to_string(), spectral_class: SpectralClass::K, mass: 0.7, luminosity: 0.24 },
+            planets: vec![Planet { params, world: Some(world) }],
+        };
+        let summary: SystemSummary = serde_json::from_str(&system.to_json()).unwrap();
+        assert_eq!(summary.planets.len(), 1);
+        assert_eq!(summary.planets[0].land_fraction, Some(1.0));
+        assert!(summary.planets[0].dominant_biome.is_some());
+    }
+}
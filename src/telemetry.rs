@@ -0,0 +1,190 @@
+//! Generation telemetry
+//!
+//! Records per-stage wall-clock timings, the process memory high-water mark
+//! and entity counts for a generation run, and writes them as Prometheus
+//! text exposition or as a CSV time series that successive runs append to.
+//! Useful when generating many worlds (seed sweeps, batch jobs) and comparing
+//! their cost.
+
+use std::io::Write;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Timing and memory for one pipeline stage
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct StageMetric {
+    pub stage: String,
+    pub seconds: f64,
+    /// Peak resident set size (kB) when the stage finished, if the platform reports it
+    pub peak_rss_kb: Option<u64>,
+}
+
+/// Metrics collected over one generation run
+#[derive(Clone, Debug)]
+pub struct Telemetry {
+    pub seed: u64,
+    pub stages: Vec<StageMetric>,
+    /// Named entity counts (plates, lakes, settlements, ...)
+    pub counts: Vec<(String, u64)>,
+    /// Unix time the run started
+    pub started_at: u64,
+    current: Option<(String, Instant)>,
+    run_start: Instant,
+}
+
+impl Telemetry {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            stages: Vec::new(),
+            counts: Vec::new(),
+            started_at: unix_time(),
+            current: None,
+            run_start: Instant::now(),
+        }
+    }
+
+    /// Start timing a stage, closing the previous one
+    pub fn stage(&mut self, name: &str) {
+        self.finish_stage();
+        self.current = Some((name.to_string(), Instant::now()));
+    }
+
+    /// Close the running stage, if any
+    pub fn finish_stage(&mut self) {
+        if let Some((stage, start)) = self.current.take() {
+            self.stages.push(StageMetric {
+                stage,
+                seconds: start.elapsed().as_secs_f64(),
+                peak_rss_kb: peak_rss_kb(),
+            });
+        }
+    }
+
+    /// Record an entity count
+    pub fn count(&mut self, name: &str, value: usize) {
+        self.counts.push((name.to_string(), value as u64));
+    }
+
+    /// Total wall-clock time since the run started
+    pub fn total_seconds(&self) -> f64 {
+        self.run_start.elapsed().as_secs_f64()
+    }
+
+    /// Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let seed = self.seed;
+        let mut out = String::new();
+
+        out.push_str("# HELP worldgen_stage_seconds Wall-clock time spent in a generation stage.\n");
+        out.push_str("# TYPE worldgen_stage_seconds gauge\n");
+        for s in &self.stages {
+            out.push_str(&format!(
+                "worldgen_stage_seconds{{seed=\"{}\",stage=\"{}\"}} {:.6}\n",
+                seed, s.stage, s.seconds
+            ));
+        }
+
+        out.push_str("# HELP worldgen_peak_rss_kilobytes Process memory high-water mark after a stage.\n");
+        out.push_str("# TYPE worldgen_peak_rss_kilobytes gauge\n");
+        for s in &self.stages {
+            if let Some(kb) = s.peak_rss_kb {
+                out.push_str(&format!(
+                    "worldgen_peak_rss_kilobytes{{seed=\"{}\",stage=\"{}\"}} {}\n",
+                    seed, s.stage, kb
+                ));
+            }
+        }
+
+        out.push_str("# HELP worldgen_entities Number of generated entities by kind.\n");
+        out.push_str("# TYPE worldgen_entities gauge\n");
+        for (name, value) in &self.counts {
+            out.push_str(&format!(
+                "worldgen_entities{{seed=\"{}\",kind=\"{}\"}} {}\n",
+                seed, name, value
+            ));
+        }
+
+        out.push_str("# HELP worldgen_total_seconds Wall-clock time for the whole run.\n");
+        out.push_str("# TYPE worldgen_total_seconds gauge\n");
+        out.push_str(&format!("worldgen_total_seconds{{seed=\"{}\"}} {:.6}\n", seed, self.total_seconds()));
+        out
+    }
+
+    /// CSV rows (`timestamp,seed,metric,label,value`), without header
+    pub fn to_csv_rows(&self) -> String {
+        let mut out = String::new();
+        let mut row = |metric: &str, label: &str, value: String| {
+            out.push_str(&format!("{},{},{},{},{}\n", self.started_at, self.seed, metric, label, value));
+        };
+        for s in &self.stages {
+            row("stage_seconds", &s.stage, format!("{:.6}", s.seconds));
+            if let Some(kb) = s.peak_rss_kb {
+                row("peak_rss_kb", &s.stage, kb.to_string());
+            }
+        }
+        for (name, value) in &self.counts {
+            row("entities", name, value.to_string());
+        }
+        row("total_seconds", "", format!("{:.6}", self.total_seconds()));
+        out
+    }
+
+    /// Write metrics to disk. `.prom` paths are overwritten with Prometheus text;
+    /// anything else is treated as a CSV time series and appended to.
+    pub fn export(&self, path: &str) -> std::io::Result<()> {
+        if path.to_ascii_lowercase().ends_with(".prom") {
+            return std::fs::write(path, self.to_prometheus());
+        }
+
+        let is_new = std::fs::metadata(path).map(|m| m.len() == 0).unwrap_or(true);
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        if is_new {
+            file.write_all(b"timestamp,seed,metric,label,value\n")?;
+        }
+        file.write_all(self.to_csv_rows().as_bytes())
+    }
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Peak resident set size of this process in kB (Linux only)
+fn peak_rss_kb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find(|line| line.starts_with("VmHWM:"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|kb| kb.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stages_and_exports() {
+        let mut telemetry = Telemetry::new(42);
+        telemetry.stage("plates");
+        telemetry.stage("erosion");
+        telemetry.finish_stage();
+        telemetry.count("lakes", 7);
+
+        assert_eq!(telemetry.stages.len(), 2);
+        assert_eq!(telemetry.stages[1].stage, "erosion");
+
+        let prom = telemetry.to_prometheus();
+        assert!(prom.contains("worldgen_stage_seconds{seed=\"42\",stage=\"plates\"}"));
+        assert!(prom.contains("worldgen_entities{seed=\"42\",kind=\"lakes\"} 7"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.csv");
+        let path = path.to_str().unwrap();
+        telemetry.export(path).unwrap();
+        telemetry.export(path).unwrap();
+        let csv = std::fs::read_to_string(path).unwrap();
+        assert_eq!(csv.matches("timestamp,seed").count(), 1);
+        assert_eq!(csv.matches(",lakes,7").count(), 2);
+    }
+}
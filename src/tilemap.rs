@@ -1,11 +1,20 @@
 /// A 2D tilemap grid with equirectangular projection (wraps horizontally).
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct Tilemap<T> {
     pub width: usize,
     pub height: usize,
     data: Vec<T>,
 }
 
+impl<T> std::fmt::Debug for Tilemap<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tilemap")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
 impl<T: Clone + Default> Tilemap<T> {
     pub fn new(width: usize, height: usize) -> Self {
         Self {
@@ -45,6 +54,36 @@ impl<T: Clone> Tilemap<T> {
         self.data[idx] = value;
     }
 
+    /// Map signed coordinates onto the globe. X wraps around; stepping past the
+    /// top or bottom row crosses the pole and comes back down on the opposite
+    /// meridian (half a map width away), so paths never pile up on the edge rows.
+    pub fn wrap_coords(&self, x: i32, y: i32) -> (usize, usize) {
+        let w = self.width as i32;
+        let h = self.height as i32;
+        let (x, y) = if y < 0 {
+            (x + w / 2, -y - 1)
+        } else if y >= h {
+            (x + w / 2, 2 * h - y - 1)
+        } else {
+            (x, y)
+        };
+        (x.rem_euclid(w) as usize, y.clamp(0, h - 1) as usize)
+    }
+
+    /// Resample to an arbitrary size with nearest-neighbour lookup.
+    /// Suitable for categorical layers (biomes, plate IDs).
+    pub fn resample_nearest(&self, width: usize, height: usize) -> Self {
+        let mut data = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let sy = (y * self.height / height).min(self.height - 1);
+            for x in 0..width {
+                let sx = (x * self.width / width).min(self.width - 1);
+                data.push(self.get(sx, sy).clone());
+            }
+        }
+        Self { width, height, data }
+    }
+
     /// Fill the entire map with a value.
     pub fn fill(&mut self, value: T) where T: Clone {
         self.data.fill(value);
@@ -214,6 +253,63 @@ impl Tilemap<f32> {
         result
     }
 
+    /// Resample to an arbitrary size using bicubic interpolation.
+    /// Unlike `upscale`, the target size need not be a multiple of the source.
+    pub fn resample(&self, width: usize, height: usize) -> Self {
+        self.resample_with_detail(width, height, 0.0, 0.0, 0)
+    }
+
+    /// Resample to an arbitrary size, synthesizing fractal detail where the
+    /// target is finer than the source. Detail noise is defined in normalized
+    /// map coordinates, so every output resolution gets the same features.
+    pub fn resample_with_detail(
+        &self,
+        width: usize,
+        height: usize,
+        detail_scale: f32,
+        detail_frequency: f32,
+        seed: u64,
+    ) -> Self {
+        use noise::{Perlin, Seedable};
+
+        let noise = Perlin::new(1).set_seed(seed as u32);
+        let sx_ratio = self.width as f32 / width as f32;
+        let sy_ratio = self.height as f32 / height as f32;
+
+        // Only add detail when upsampling, fading in as the source pixels grow
+        let upsample = (1.0 / sx_ratio.max(sy_ratio) - 1.0).clamp(0.0, 1.0);
+        let detail_scale = detail_scale * upsample;
+
+        let range = if detail_scale > 0.0 {
+            let (min_h, max_h) = self.iter().fold((f32::MAX, f32::MIN), |(lo, hi), (_, _, &h)| (lo.min(h), hi.max(h)));
+            (max_h - min_h).max(1.0)
+        } else {
+            0.0
+        };
+
+        let mut result = Tilemap::new_with(width, height, 0.0f32);
+        for y in 0..height {
+            for x in 0..width {
+                // Pixel centres line up between source and target
+                let src_x = (x as f32 + 0.5) * sx_ratio - 0.5;
+                let src_y = ((y as f32 + 0.5) * sy_ratio - 0.5).max(0.0);
+                let mut value = self.sample_bicubic(src_x, src_y);
+
+                if detail_scale > 0.0 {
+                    let nx = (x as f64 + 0.5) / width as f64 * detail_frequency as f64;
+                    let ny = (y as f64 + 0.5) / height as f64 * detail_frequency as f64;
+                    let detail = fbm_noise(&noise, nx, ny, 4, 0.5, 2.0) as f32;
+                    let gradient_factor = (self.get_local_gradient(src_x, src_y) / 100.0).clamp(0.1, 1.0);
+                    value += detail * detail_scale * range * 0.02 * gradient_factor;
+                }
+
+                result.set(x, y, value);
+            }
+        }
+
+        result
+    }
+
     /// Sample the tilemap at fractional coordinates using bicubic interpolation.
     fn sample_bicubic(&self, x: f32, y: f32) -> f32 {
         let x0 = x.floor() as i32;
@@ -0,0 +1,383 @@
+//! Sinking rivers, underground rivers, resurgences and cave waterfalls
+//!
+//! A river crossing soluble karst, or running over a cavern close beneath its
+//! bed, loses part of its flow down a swallet. The captured water pours down a
+//! shaft to a conduit and follows the valley underground, dropping into any
+//! cavern it passes over in a cave waterfall. Where the valley floor comes down
+//! to the conduit the water wells up again at a resurgence spring; a conduit
+//! reaching the coast or a lake resurfaces under water, and one that never finds
+//! a way out ends in a cave lake.
+//!
+//! The surface keeps its water budget: the captured discharge is taken from
+//! every surface tile between the swallet and the resurgence and carried by the
+//! conduit instead, so surface plus underground discharge is the same all the
+//! way down the valley. Water that ends in a cave lake is retained underground.
+
+use crate::biomes::ExtendedBiome;
+use crate::erosion::hydraulic_geometry::discharge;
+use crate::erosion::rivers::{compute_flow_accumulation, compute_flow_direction, fill_depressions_public, DX, DY, NO_FLOW};
+use crate::tilemap::Tilemap;
+use crate::zlevel::{Tilemap3D, ZTile, MIN_Z};
+
+/// Parameters for sinking rivers and their conduits
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct UndergroundRiverConfig {
+    /// Upstream area (tiles) a river drains before it can sink
+    pub min_flow: f32,
+    /// Share of the discharge a swallet in karst takes
+    pub karst_capture: f32,
+    /// Share of the discharge lost to a cavern beneath the river bed
+    pub cave_capture: f32,
+    /// Z-levels below a river bed searched for a cavern
+    pub max_sink_depth: i32,
+    /// Z-levels a conduit can fall into a cavern in one step
+    pub max_plunge: i32,
+    /// Longest conduit (tiles) before its water is left in a cave lake
+    pub max_length: usize,
+}
+
+impl Default for UndergroundRiverConfig {
+    fn default() -> Self {
+        Self {
+            min_flow: 40.0,
+            karst_capture: 0.7,
+            cave_capture: 0.35,
+            max_sink_depth: 8,
+            max_plunge: 6,
+            max_length: 120,
+        }
+    }
+}
+
+/// A drop in a conduit: water falling from `top` down `drop` z-levels
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CaveWaterfall {
+    pub x: usize,
+    pub y: usize,
+    pub top: i32,
+    pub drop: i32,
+}
+
+/// One river's underground course from swallet to resurgence
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct UndergroundRiver {
+    /// Surface tile where the water sinks
+    pub sink: (usize, usize),
+    /// Surface tile where it comes back up (None when left in a cave lake)
+    pub resurgence: Option<(usize, usize)>,
+    /// Whether the resurgence is under the sea or a lake rather than a spring
+    pub submerged: bool,
+    /// Conduit tiles in flow order, with their z-levels
+    pub path: Vec<(usize, usize, i32)>,
+    /// Discharge (m³/s) carried underground
+    pub discharge: f32,
+    /// The swallet shaft and every plunge into a cavern
+    pub falls: Vec<CaveWaterfall>,
+}
+
+/// Underground rivers and the surface discharge they leave behind
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct UndergroundRiverMap {
+    pub rivers: Vec<UndergroundRiver>,
+    /// Discharge (m³/s) still flowing on the surface
+    pub surface_discharge: Tilemap<f32>,
+    /// Discharge (m³/s) flowing in conduits beneath each tile
+    pub underground_discharge: Tilemap<f32>,
+    /// Total discharge (m³/s) sunk, resurfaced and left in cave lakes
+    pub diverted: f32,
+    pub resurfaced: f32,
+    pub retained: f32,
+}
+
+impl UndergroundRiverMap {
+    /// Resurgences that come up as springs on land
+    pub fn springs(&self) -> usize {
+        self.rivers.iter().filter(|r| r.resurgence.is_some() && !r.submerged).count()
+    }
+
+    pub fn waterfalls(&self) -> usize {
+        self.rivers.iter().map(|r| r.falls.len()).sum()
+    }
+
+    pub fn describe(&self, x: usize, y: usize) -> Option<String> {
+        let mut parts = Vec::new();
+        for river in &self.rivers {
+            if river.sink == (x, y) {
+                parts.push(format!("Swallet: {:.0} m³/s sinks underground", river.discharge));
+            }
+            if river.resurgence == Some((x, y)) {
+                parts.push(format!("Resurgence: {:.0} m³/s wells up", river.discharge));
+            }
+            for fall in river.falls.iter().filter(|f| (f.x, f.y) == (x, y)) {
+                parts.push(format!("cave waterfall of {} levels below z={}", fall.drop, fall.top));
+            }
+        }
+        let underground = *self.underground_discharge.get(x, y);
+        if parts.is_empty() && underground > 0.0 {
+            parts.push(format!("Underground river {:.0} m³/s", underground));
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+fn is_karst(biome: ExtendedBiome) -> bool {
+    matches!(
+        biome,
+        ExtendedBiome::KarstPlains
+            | ExtendedBiome::TowerKarst
+            | ExtendedBiome::Sinkhole
+            | ExtendedBiome::CockpitKarst
+            | ExtendedBiome::CaveEntrance
+    )
+}
+
+/// Open cave ground a conduit can run along
+fn is_cave_floor(tile: ZTile) -> bool {
+    matches!(tile, ZTile::CaveFloor | ZTile::CaveLake | ZTile::Flowstone | ZTile::Stalagmite)
+}
+
+/// Rock and cave the conduit may cut through (not magma or anything built)
+fn is_carvable(tile: ZTile) -> bool {
+    tile == ZTile::Solid
+        || tile.is_underground_water()
+        || is_cave_floor(tile)
+        || matches!(tile, ZTile::CaveWall | ZTile::Stalactite | ZTile::Pillar)
+}
+
+/// Highest open cave floor in a column between two z-levels
+fn highest_floor(zlevels: &Tilemap3D<ZTile>, x: usize, y: usize, low: i32, high: i32) -> Option<i32> {
+    (low.max(MIN_Z)..=high).rev().find(|&z| is_cave_floor(*zlevels.get(x, y, z)))
+}
+
+/// Sink rivers into karst and caverns, carve their conduits, waterfalls and
+/// resurgences into the z-levels, and balance the surface discharge against them
+pub fn generate_underground_rivers(
+    zlevels: &mut Tilemap3D<ZTile>,
+    surface_z: &Tilemap<i32>,
+    heightmap: &Tilemap<f32>,
+    biomes: &Tilemap<ExtendedBiome>,
+    km_per_tile: f32,
+    config: &UndergroundRiverConfig,
+) -> UndergroundRiverMap {
+    let (width, height) = (heightmap.width, heightmap.height);
+    let filled = fill_depressions_public(heightmap);
+    let flow_dir = compute_flow_direction(&filled);
+    let accumulation = compute_flow_accumulation(&filled, &flow_dir);
+    let downstream = |x: usize, y: usize| {
+        let dir = *flow_dir.get(x, y);
+        if dir == NO_FLOW {
+            return None;
+        }
+        let ny = y as i32 + DY[dir as usize];
+        (0..height as i32)
+            .contains(&ny)
+            .then(|| ((x as i32 + DX[dir as usize]).rem_euclid(width as i32) as usize, ny as usize))
+    };
+
+    let mut surface_discharge = Tilemap::new_with(width, height, 0.0f32);
+    for (x, y, &area) in accumulation.iter() {
+        surface_discharge.set(x, y, discharge(area, km_per_tile));
+    }
+    let mut underground_discharge = Tilemap::new_with(width, height, 0.0f32);
+
+    // Swallet candidates, upstream first so a river sinks at the first chance it gets
+    let cave_below = |zlevels: &Tilemap3D<ZTile>, x: usize, y: usize| {
+        let surf = *surface_z.get(x, y);
+        highest_floor(zlevels, x, y, surf - config.max_sink_depth, surf - 1)
+    };
+    let mut candidates: Vec<(usize, usize)> = accumulation
+        .iter()
+        .filter(|&(x, y, &area)| {
+            area >= config.min_flow
+                && *heightmap.get(x, y) > 0.0
+                && *surface_z.get(x, y) > MIN_Z + 1
+                && (is_karst(*biomes.get(x, y)) || cave_below(zlevels, x, y).is_some())
+        })
+        .map(|(x, y, _)| (x, y))
+        .collect();
+    candidates.sort_by(|a, b| filled.get(b.0, b.1).total_cmp(filled.get(a.0, a.1)).then(a.cmp(b)));
+
+    let mut bypassed = Tilemap::new_with(width, height, false);
+    let mut rivers = Vec::new();
+    for (x, y) in candidates {
+        if *bypassed.get(x, y) {
+            continue;
+        }
+        let surf = *surface_z.get(x, y);
+        let (start_z, capture) = match cave_below(zlevels, x, y) {
+            Some(floor) if !is_karst(*biomes.get(x, y)) => (floor, config.cave_capture),
+            Some(floor) => (floor, config.karst_capture),
+            None => (surf - 1, config.karst_capture),
+        };
+        let flow = *surface_discharge.get(x, y) * capture;
+
+        // The swallet shaft from the river bed down to the conduit
+        let mut falls = Vec::new();
+        if surf - start_z >= 2 {
+            falls.push(CaveWaterfall { x, y, top: surf - 1, drop: surf - 1 - start_z });
+        }
+        let mut path = vec![(x, y, start_z)];
+        let (mut resurgence, mut submerged) = (None, false);
+        bypassed.set(x, y, true);
+
+        let (mut cx, mut cy, mut z) = (x, y, start_z);
+        while path.len() < config.max_length {
+            let Some((nx, ny)) = downstream(cx, cy) else {
+                break;
+            };
+            if *heightmap.get(nx, ny) <= 0.0 {
+                (resurgence, submerged) = (Some((nx, ny)), true);
+                break;
+            }
+            // The valley floor has come down to the conduit
+            if *surface_z.get(nx, ny) <= z {
+                resurgence = Some((nx, ny));
+                break;
+            }
+            let floor = highest_floor(zlevels, nx, ny, z - config.max_plunge, z).unwrap_or(z);
+            if z - floor >= 2 {
+                falls.push(CaveWaterfall { x: nx, y: ny, top: z, drop: z - floor });
+            }
+            z = floor;
+            path.push((nx, ny, z));
+            bypassed.set(nx, ny, true);
+            (cx, cy) = (nx, ny);
+        }
+
+        // Carve the conduit, its falls, and where it ends
+        for &(px, py, pz) in &path {
+            if is_carvable(*zlevels.get(px, py, pz)) {
+                zlevels.set(px, py, pz, ZTile::UndergroundRiver);
+            }
+            *underground_discharge.get_mut(px, py) += flow;
+        }
+        for fall in &falls {
+            for fz in (fall.top - fall.drop + 1)..=fall.top {
+                if is_carvable(*zlevels.get(fall.x, fall.y, fz)) {
+                    zlevels.set(fall.x, fall.y, fz, ZTile::Waterfall);
+                }
+            }
+        }
+        match resurgence {
+            Some((rx, ry)) if !submerged => zlevels.set(rx, ry, *surface_z.get(rx, ry), ZTile::Spring),
+            Some(_) => {}
+            None => {
+                let &(px, py, pz) = path.last().unwrap();
+                zlevels.set(px, py, pz, ZTile::CaveLake);
+            }
+        }
+
+        // The surface river runs short of the captured water until it resurfaces
+        let mut at = Some((x, y));
+        while let Some((sx, sy)) = at {
+            if resurgence == Some((sx, sy)) || *heightmap.get(sx, sy) <= 0.0 {
+                break;
+            }
+            let left = surface_discharge.get_mut(sx, sy);
+            *left = (*left - flow).max(0.0);
+            at = downstream(sx, sy);
+        }
+
+        rivers.push(UndergroundRiver { sink: (x, y), resurgence, submerged, path, discharge: flow, falls });
+    }
+
+    let diverted = rivers.iter().map(|r| r.discharge).sum();
+    let resurfaced = rivers.iter().filter(|r| r.resurgence.is_some()).map(|r| r.discharge).sum();
+    UndergroundRiverMap {
+        rivers,
+        surface_discharge,
+        underground_discharge,
+        diverted,
+        resurfaced,
+        retained: diverted - resurfaced,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zlevel::generate_zlevels;
+
+    #[test]
+    fn test_river_sinks_in_karst_and_resurfaces() {
+        // A valley running east from a ridge to the sea, stepping down two z-levels at
+        // x = 14; karst upstream
+        let (width, height) = (24, 9);
+        let mut heightmap = Tilemap::new_with(width, height, -100.0f32);
+        for y in 0..height {
+            heightmap.set(0, y, 2000.0);
+            for x in 1..width - 2 {
+                let valley = 120.0 * (y as f32 - 4.0).abs();
+                let floor = if x < 14 { 800.0 - x as f32 } else { 400.0 - x as f32 };
+                heightmap.set(x, y, floor + valley);
+            }
+        }
+        let mut biomes = Tilemap::new_with(width, height, ExtendedBiome::TemperateGrassland);
+        for x in 0..6 {
+            biomes.set(x, 4, ExtendedBiome::KarstPlains);
+        }
+        let (mut zlevels, surface_z) = generate_zlevels(&heightmap);
+        let config = UndergroundRiverConfig { min_flow: 3.0, ..Default::default() };
+        let before = generate_underground_rivers(
+            &mut generate_zlevels(&heightmap).0,
+            &surface_z,
+            &heightmap,
+            &Tilemap::new_with(width, height, ExtendedBiome::TemperateGrassland),
+            10.0,
+            &config,
+        );
+        assert!(before.rivers.is_empty());
+
+        let map = generate_underground_rivers(&mut zlevels, &surface_z, &heightmap, &biomes, 10.0, &config);
+        let river = map.rivers.iter().find(|r| r.sink.1 == 4).expect("the valley river sinks");
+        let (rx, ry) = river.resurgence.expect("and comes back up");
+        assert_eq!((rx, ry), (14, 4));
+        assert!(!river.submerged);
+        assert_eq!(*zlevels.get(rx, ry, *surface_z.get(rx, ry)), ZTile::Spring);
+        let (px, py, pz) = river.path[2];
+        assert_eq!(*zlevels.get(px, py, pz), ZTile::UndergroundRiver);
+
+        // Surface and underground together carry the whole river all the way down
+        for x in river.sink.0..16 {
+            let total = map.surface_discharge.get(x, 4) + map.underground_discharge.get(x, 4);
+            let whole = before.surface_discharge.get(x, 4);
+            assert!((total - whole).abs() < 1e-3 * whole, "x={}: {} vs {}", x, total, whole);
+        }
+        let lost = before.surface_discharge.get(10, 4) - map.surface_discharge.get(10, 4);
+        assert!((lost - river.discharge).abs() < 1e-3 * river.discharge);
+        assert_eq!(map.surface_discharge.get(15, 4), before.surface_discharge.get(15, 4));
+        assert!((map.diverted - map.resurfaced - map.retained).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_conduit_falls_into_cavern() {
+        let (width, height) = (20, 7);
+        let mut heightmap = Tilemap::new_with(width, height, -100.0f32);
+        for y in 0..height {
+            heightmap.set(0, y, 2000.0);
+            for x in 1..width - 2 {
+                heightmap.set(x, y, 700.0 - x as f32 + 150.0 * (y as f32 - 3.0).abs());
+            }
+        }
+        let mut biomes = Tilemap::new_with(width, height, ExtendedBiome::TemperateGrassland);
+        for x in 0..width {
+            biomes.set(x, 3, ExtendedBiome::KarstPlains);
+        }
+        let (mut zlevels, surface_z) = generate_zlevels(&heightmap);
+        // A cavern four levels below the conduit, under the middle of the valley
+        for x in 8..12 {
+            zlevels.set(x, 3, -3, ZTile::CaveFloor);
+        }
+        let config = UndergroundRiverConfig { min_flow: 3.0, ..Default::default() };
+        let map = generate_underground_rivers(&mut zlevels, &surface_z, &heightmap, &biomes, 10.0, &config);
+
+        let river = map.rivers.iter().find(|r| r.sink.1 == 3).unwrap();
+        let fall = river.falls.iter().find(|f| f.x == 8).expect("a waterfall into the cavern");
+        assert_eq!(fall.top - fall.drop, -3);
+        assert_eq!(*zlevels.get(8, 3, -2), ZTile::Waterfall);
+        assert_eq!(*zlevels.get(10, 3, -3), ZTile::UndergroundRiver);
+        // Below sea level now, it runs out under the sea
+        assert!(river.submerged);
+        assert_eq!(map.retained, 0.0);
+    }
+}
@@ -0,0 +1,690 @@
+//! GeoJSON vector export
+//!
+//! Exports the political and hydrological features of a world as GeoJSON feature
+//! collections: rivers, their flood-stage floodways, lakes, coastlines, faction
+//! borders, trade roads, settlements and the place name registry, each with
+//! attributes (discharge, channel width and depth, lake outflow and salinity,
+//! population, founding year, label priority, ...). Borders, roads and
+//! settlements need the `history` feature.
+//! The map is treated as an equirectangular projection, so tile (0, 0) is the
+//! north-west corner at (-180°, 90°), and the files load directly into QGIS,
+//! Leaflet or D3. Shapefiles can be produced from them with `ogr2ogr`.
+//!
+//! Area features are traced along tile edges, so polygons follow the map's tiles
+//! exactly; exterior rings run counter-clockwise and holes clockwise (RFC 7946).
+//! Lines that cross the east-west seam are split there.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde_json::{json, Value};
+
+use crate::erosion::{self, Waterway};
+use crate::tilemap::Tilemap;
+use crate::water_bodies::{WaterBodyId, WaterBodyType};
+use crate::world::WorldData;
+
+/// Decimal places kept in coordinates (about 1 m at the equator)
+const COORD_DECIMALS: i32 = 5;
+
+/// Samples per river Bezier segment when the network doesn't specify one
+const DEFAULT_RIVER_SAMPLES: usize = 8;
+
+/// A ring or line of tile-corner coordinates
+type Path = Vec<(i32, i32)>;
+
+/// Tile bounds (min x, min y, max x, max y)
+type Bounds = (usize, usize, usize, usize);
+
+// =============================================================================
+// EXPORT
+// =============================================================================
+
+/// Write every layer as PREFIX_<layer>.geojson and return the paths written
+pub fn export_geojson(world: &WorldData, prefix: &str) -> std::io::Result<Vec<String>> {
+    let mut layers = vec![
+        ("rivers", rivers(world)),
+        ("floodways", floodways(world)),
+        ("lakes", lakes(world)),
+        ("coastlines", coastlines(world)),
+    ];
+    #[cfg(feature = "history")]
+    layers.extend([("borders", borders(world)), ("roads", roads(world)), ("settlements", settlements(world))]);
+    layers.push(("places", places(world)));
+
+    let mut paths = Vec::new();
+    for (name, collection) in layers {
+        let path = format!("{}_{}.geojson", prefix, name);
+        let text = serde_json::to_string(&collection).expect("GeoJSON is always serializable");
+        std::fs::write(&path, text)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// River network as LineStrings, one per Bezier segment, with the hydraulic
+/// geometry of its downstream end
+pub fn rivers(world: &WorldData) -> Value {
+    let Some(ref network) = world.river_network else {
+        return feature_collection(Vec::new());
+    };
+    let reaches = erosion::reach_geometry(network, world.scale.km_per_tile);
+    let samples = if network.params.points_per_segment > 1 {
+        network.params.points_per_segment
+    } else {
+        DEFAULT_RIVER_SAMPLES
+    };
+
+    let features = network
+        .segments
+        .iter()
+        .zip(&reaches)
+        .map(|(segment, reach)| {
+            let points: Vec<(f64, f64)> = (0..=samples)
+                .map(|i| {
+                    let p = segment.evaluate(i as f32 / samples as f32);
+                    (p.world_x as f64 + 0.5, p.world_y as f64 + 0.5)
+                })
+                .collect();
+
+            // Named after the river water body under the segment's midpoint
+            let mid = segment.evaluate(0.5);
+            let (mx, my) = world.water_body_map.wrap_coords(mid.world_x.round() as i32, mid.world_y.round() as i32);
+            let body = *world.water_body_map.get(mx, my);
+            let name = world
+                .water_bodies
+                .iter()
+                .find(|wb| wb.id == body && wb.body_type == WaterBodyType::River)
+                .and_then(|wb| wb.name.clone());
+
+            feature(
+                Projection::of(world).line(&points),
+                json!({
+                    "id": segment.id,
+                    "name": name,
+                    "discharge": segment.p0.flow_accumulation.max(segment.p3.flow_accumulation),
+                    "width": segment.p0.width.max(segment.p3.width),
+                    "tributaries": segment.tributaries.len(),
+                    "discharge_m3s": reach.discharge,
+                    "width_m": reach.width_m,
+                    "depth_m": reach.depth_m,
+                    "flood_width_m": reach.flood_width_m,
+                    "gradient_m_per_km": reach.gradient,
+                }),
+            )
+        })
+        .collect();
+    feature_collection(features)
+}
+
+/// Ground under water at flood stage, one (Multi)Polygon per connected floodway
+pub fn floodways(world: &WorldData) -> Value {
+    let Some(ref network) = world.river_network else {
+        return feature_collection(Vec::new());
+    };
+    let km = world.scale.km_per_tile;
+    let waterways = erosion::rasterize_waterways(network, km, (world.width, world.height), world.width, world.height);
+
+    // Connected regions: (bounds, tiles, channel tiles)
+    let mut region_of = Tilemap::new_with(world.width, world.height, usize::MAX);
+    let mut regions: Vec<(Bounds, usize, usize)> = Vec::new();
+    for (x, y, &waterway) in waterways.iter() {
+        if waterway == Waterway::None || *region_of.get(x, y) != usize::MAX {
+            continue;
+        }
+        let id = regions.len();
+        let mut region = ((x, y, x, y), 0, 0);
+        let mut queue = VecDeque::from([(x, y)]);
+        region_of.set(x, y, id);
+        while let Some((cx, cy)) = queue.pop_front() {
+            let b = &mut region.0;
+            *b = (b.0.min(cx), b.1.min(cy), b.2.max(cx), b.3.max(cy));
+            region.1 += 1;
+            region.2 += (*waterways.get(cx, cy) == Waterway::Channel) as usize;
+            for (nx, ny) in waterways.neighbors(cx, cy) {
+                if *waterways.get(nx, ny) != Waterway::None && *region_of.get(nx, ny) == usize::MAX {
+                    region_of.set(nx, ny, id);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        regions.push(region);
+    }
+
+    // Peak discharge of the reaches ending in each region
+    let mut peak = vec![0.0f32; regions.len()];
+    for reach in erosion::reach_geometry(network, km) {
+        let end = &network.segments[reach.segment].p3;
+        let (x, y) = region_of.wrap_coords(end.world_x.round() as i32, end.world_y.round() as i32);
+        if let Some(p) = peak.get_mut(*region_of.get(x, y)) {
+            *p = p.max(reach.discharge);
+        }
+    }
+
+    let features = regions
+        .iter()
+        .enumerate()
+        .map(|(id, &(bounds, tiles, channel))| {
+            let rings = trace_rings((world.width, world.height), bounds, |x, y| *region_of.get(x, y) == id);
+            feature(
+                Projection::of(world).polygon(rings),
+                json!({
+                    "id": id,
+                    "area_tiles": tiles,
+                    "area_km2": tiles as f32 * km * km,
+                    "channel_tiles": channel,
+                    "peak_discharge_m3s": peak[id],
+                }),
+            )
+        })
+        .collect();
+    feature_collection(features)
+}
+
+/// Lakes as Polygons traced from the water body map
+pub fn lakes(world: &WorldData) -> Value {
+    let features = world
+        .water_bodies
+        .iter()
+        .filter(|wb| wb.body_type == WaterBodyType::Lake)
+        .map(|wb| {
+            let rings = trace_rings((world.width, world.height), wb.bounds, |x, y| *world.water_body_map.get(x, y) == wb.id);
+            let lake = world.lakes.as_ref().and_then(|graph| graph.get(wb.id));
+            feature(
+                Projection::of(world).polygon(rings),
+                json!({
+                    "id": wb.id.0,
+                    "name": wb.name,
+                    "area_tiles": wb.tile_count,
+                    "area_km2": wb.tile_count as f32 * world.scale.km_per_tile * world.scale.km_per_tile,
+                    "elevation": wb.avg_elevation,
+                    "inflow_m3s": lake.map(|l| l.inflow),
+                    "outflow_m3s": lake.map(|l| l.outflow),
+                    "residence_years": lake.map(|l| l.residence_years).filter(|r| r.is_finite()),
+                    "saline": lake.map(|l| l.saline),
+                    "outlet_into": lake.filter(|l| l.is_open()).and_then(|l| l.outlet.as_ref()).map(|o| o.into.0),
+                }),
+            )
+        })
+        .collect();
+    feature_collection(features)
+}
+
+/// Ocean shorelines as LineStrings (the map frame is not part of the coast)
+pub fn coastlines(world: &WorldData) -> Value {
+    let ocean: Vec<WaterBodyId> = world
+        .water_bodies
+        .iter()
+        .filter(|wb| wb.body_type == WaterBodyType::Ocean)
+        .map(|wb| wb.id)
+        .collect();
+    let bounds = (0, 0, world.width - 1, world.height - 1);
+    let rings = trace_rings((world.width, world.height), bounds, |x, y| !ocean.contains(world.water_body_map.get(x, y)));
+
+    let features = rings
+        .iter()
+        .flat_map(|ring| split_at_frame(ring, world.width as i32, world.height as i32))
+        .filter(|line| line.len() > 1)
+        .map(|line| {
+            let length = line.windows(2).map(|w| (w[1].0 - w[0].0).abs() + (w[1].1 - w[0].1).abs()).sum::<i32>();
+            let points: Vec<(f64, f64)> = line.iter().map(|&(x, y)| (x as f64, y as f64)).collect();
+            feature(
+                Projection::of(world).line(&points),
+                json!({
+                    "length_tiles": length,
+                    "length_km": length as f32 * world.scale.km_per_tile,
+                }),
+            )
+        })
+        .collect();
+    feature_collection(features)
+}
+
+/// Faction territories as (Multi)Polygons
+#[cfg(feature = "history")]
+pub fn borders(world: &WorldData) -> Value {
+    let Some(ref history) = world.history else {
+        return feature_collection(Vec::new());
+    };
+    let territory = &history.territories.territory_map;
+    let bounds = (0, 0, world.width - 1, world.height - 1);
+
+    let mut factions: Vec<_> = history.factions.all().collect();
+    factions.sort_by_key(|f| f.id.0);
+    let features = factions
+        .into_iter()
+        .filter_map(|faction| {
+            let rings = trace_rings((world.width, world.height), bounds, |x, y| *territory.get(x, y) == Some(faction.id));
+            if rings.is_empty() {
+                return None;
+            }
+            let area = territory.iter().filter(|(_, _, owner)| **owner == Some(faction.id)).count();
+            let (r, g, b) = faction.color;
+            Some(feature(
+                Projection::of(world).polygon(rings),
+                json!({
+                    "id": faction.id.0,
+                    "name": faction.name,
+                    "species": faction.species.name(),
+                    "culture": faction.culture.name(),
+                    "founded": faction.founded.0,
+                    "collapsed": faction.collapsed.map(|y| y.0),
+                    "population": faction.peak_population,
+                    "area_tiles": area,
+                    "color": format!("#{:02x}{:02x}{:02x}", r, g, b),
+                }),
+            ))
+        })
+        .collect();
+    feature_collection(features)
+}
+
+/// Trade routes as LineStrings
+#[cfg(feature = "history")]
+pub fn roads(world: &WorldData) -> Value {
+    let Some(ref history) = world.history else {
+        return feature_collection(Vec::new());
+    };
+
+    let mut routes: Vec<_> = history.trade.routes.values().collect();
+    routes.sort_by_key(|r| r.id.0);
+    let features = routes
+        .into_iter()
+        .filter(|route| route.path.len() > 1)
+        .map(|route| {
+            let points: Vec<(f64, f64)> = route.path.iter().map(|&(x, y)| (x as f64 + 0.5, y as f64 + 0.5)).collect();
+            let resources: Vec<&str> = route.resources.iter().map(|r| r.name()).collect();
+            feature(
+                Projection::of(world).line(&points),
+                json!({
+                    "id": route.id.0,
+                    "kind": "trade_route",
+                    "active": route.active,
+                    "established": route.established.0,
+                    "abandoned": route.abandoned.map(|y| y.0),
+                    "resources": resources,
+                    "waypoints": route.waypoints.len(),
+                }),
+            )
+        })
+        .collect();
+    feature_collection(features)
+}
+
+/// Settlements as Points
+#[cfg(feature = "history")]
+pub fn settlements(world: &WorldData) -> Value {
+    let Some(ref history) = world.history else {
+        return feature_collection(Vec::new());
+    };
+    let faction_name = |id| history.factions.get(id).map(|f| f.name.clone());
+
+    let mut settlements: Vec<_> = history.territories.settlements.values().collect();
+    settlements.sort_by_key(|s| s.id.0);
+    let features = settlements
+        .into_iter()
+        .map(|s| {
+            feature(
+                json!({
+                    "type": "Point",
+                    "coordinates": Projection::of(world).lon_lat(s.x as f64 + 0.5, s.y as f64 + 0.5),
+                }),
+                json!({
+                    "id": s.id.0,
+                    "name": s.name,
+                    "type": s.settlement_type.name(),
+                    "state": s.state.name(),
+                    "founded_by": faction_name(s.original_faction),
+                    "faction": s.current_faction.and_then(faction_name),
+                    "founded": s.founded.0,
+                    "abandoned": s.abandoned.map(|y| y.0),
+                    "population": s.peak_population,
+                    "size_tiles": s.size,
+                }),
+            )
+        })
+        .collect();
+    feature_collection(features)
+}
+
+/// Every entry of the name layer as a Point at its anchor, for map labels
+pub fn places(world: &WorldData) -> Value {
+    let Some(ref names) = world.names else {
+        return feature_collection(Vec::new());
+    };
+    let features = names
+        .by_priority()
+        .into_iter()
+        .map(|((x, y), place)| {
+            feature(
+                json!({
+                    "type": "Point",
+                    "coordinates": Projection::of(world).lon_lat(x as f64 + 0.5, y as f64 + 0.5),
+                }),
+                json!({
+                    "name": place.name,
+                    "kind": place.kind.name(),
+                    "priority": place.priority,
+                    "extent_tiles": place.extent,
+                    "extent_km": place.extent as f32 * world.scale.km_per_tile,
+                }),
+            )
+        })
+        .collect();
+    feature_collection(features)
+}
+
+// =============================================================================
+// GEOJSON BUILDING
+// =============================================================================
+
+fn feature_collection(features: Vec<Value>) -> Value {
+    json!({ "type": "FeatureCollection", "features": features })
+}
+
+fn feature(geometry: Value, properties: Value) -> Value {
+    json!({ "type": "Feature", "geometry": geometry, "properties": properties })
+}
+
+/// Equirectangular projection of a map onto longitude and latitude
+#[derive(Clone, Copy)]
+struct Projection {
+    width: usize,
+    height: usize,
+}
+
+impl Projection {
+    fn of(world: &WorldData) -> Self {
+        Self { width: world.width, height: world.height }
+    }
+
+    /// Map coordinates (tiles, fractional) to [longitude, latitude]
+    fn lon_lat(&self, x: f64, y: f64) -> [f64; 2] {
+        let scale = 10f64.powi(COORD_DECIMALS);
+        let round = |v: f64| (v * scale).round() / scale;
+        [
+            round(x / self.width as f64 * 360.0 - 180.0),
+            round(90.0 - y / self.height as f64 * 180.0),
+        ]
+    }
+
+    /// LineString, or MultiLineString when the line crosses the east-west seam
+    fn line(&self, points: &[(f64, f64)]) -> Value {
+        let width = self.width as f64;
+        let mut parts: Vec<Vec<[f64; 2]>> = vec![Vec::new()];
+        for (i, &(x, y)) in points.iter().enumerate() {
+            if i > 0 && (x - points[i - 1].0).abs() > width / 2.0 {
+                parts.push(Vec::new());
+            }
+            let x = if (0.0..=width).contains(&x) { x } else { x.rem_euclid(width) };
+            parts.last_mut().unwrap().push(self.lon_lat(x, y));
+        }
+        parts.retain(|part| part.len() > 1);
+
+        if parts.len() == 1 {
+            json!({ "type": "LineString", "coordinates": parts.pop().unwrap() })
+        } else {
+            json!({ "type": "MultiLineString", "coordinates": parts })
+        }
+    }
+
+    /// Polygon, or MultiPolygon for several exteriors, with holes assigned to the exterior enclosing them
+    fn polygon(&self, rings: Vec<Path>) -> Value {
+        let (exteriors, holes): (Vec<Path>, Vec<Path>) = rings.into_iter().partition(|r| signed_area(r) > 0);
+
+        let mut polygons: Vec<Vec<&Path>> = exteriors.iter().map(|e| vec![e]).collect();
+        for hole in &holes {
+            // Test the middle of the hole's first edge, which lies on no other ring
+            let (a, b) = (hole[0], hole[1]);
+            let probe = ((a.0 + b.0) as f64 / 2.0, (a.1 + b.1) as f64 / 2.0);
+            let owner = exteriors
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| contains(e, probe))
+                .min_by_key(|(_, e)| signed_area(e))
+                .map(|(i, _)| i);
+            if let Some(i) = owner {
+                polygons[i].push(hole);
+            }
+        }
+
+        let coords = |ring: &Path| -> Vec<[f64; 2]> {
+            ring.iter()
+                .chain(std::iter::once(&ring[0]))
+                .map(|&(x, y)| self.lon_lat(x as f64, y as f64))
+                .collect()
+        };
+        let polygons: Vec<Vec<Vec<[f64; 2]>>> = polygons
+            .into_iter()
+            .map(|rings| rings.into_iter().map(coords).collect())
+            .collect();
+
+        if polygons.len() == 1 {
+            json!({ "type": "Polygon", "coordinates": polygons[0] })
+        } else {
+            json!({ "type": "MultiPolygon", "coordinates": polygons })
+        }
+    }
+}
+
+// =============================================================================
+// RING TRACING
+// =============================================================================
+
+/// Trace the outlines of the tiles within `bounds` (inclusive min/max) for which
+/// `inside` holds. Rings are in tile-corner coordinates with collinear corners
+/// dropped; exteriors have positive signed area (clockwise on screen, so
+/// counter-clockwise once y is flipped to latitude) and holes negative.
+fn trace_rings(
+    (width, height): (usize, usize),
+    bounds: (usize, usize, usize, usize),
+    inside: impl Fn(usize, usize) -> bool,
+) -> Vec<Path> {
+    let (min_x, min_y, max_x, max_y) = bounds;
+    let is_inside = |x: i32, y: i32| {
+        x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height && inside(x as usize, y as usize)
+    };
+
+    // Directed boundary edges with the region on the right (y down)
+    let mut edges: HashMap<(i32, i32), Vec<(i32, i32)>> = HashMap::new();
+    for y in min_y as i32..=max_y as i32 {
+        for x in min_x as i32..=max_x as i32 {
+            if !is_inside(x, y) {
+                continue;
+            }
+            if !is_inside(x, y - 1) {
+                edges.entry((x, y)).or_default().push((x + 1, y));
+            }
+            if !is_inside(x + 1, y) {
+                edges.entry((x + 1, y)).or_default().push((x + 1, y + 1));
+            }
+            if !is_inside(x, y + 1) {
+                edges.entry((x + 1, y + 1)).or_default().push((x, y + 1));
+            }
+            if !is_inside(x - 1, y) {
+                edges.entry((x, y + 1)).or_default().push((x, y));
+            }
+        }
+    }
+
+    // Chain edges into closed rings, walking from the smallest corner for stable output.
+    // Where two tiles touch only at a corner, turning right keeps following the same
+    // tile, so diagonal neighbours become separate rings.
+    let mut starts: Vec<(i32, i32)> = edges.keys().copied().collect();
+    starts.sort_by_key(|&(x, y)| (y, x));
+    let mut rings = Vec::new();
+    for start in starts {
+        while edges.get(&start).is_some_and(|out| !out.is_empty()) {
+            let mut ring = vec![start];
+            let mut at = start;
+            let mut heading = None;
+            loop {
+                let out = edges.get_mut(&at).expect("boundary edges form closed rings");
+                let right = heading.map(|(dx, dy): (i32, i32)| (at.0 - dy, at.1 + dx));
+                let i = out.iter().position(|&n| Some(n) == right).unwrap_or(out.len() - 1);
+                let next = out.swap_remove(i);
+                if next == start {
+                    break;
+                }
+                heading = Some((next.0 - at.0, next.1 - at.1));
+                ring.push(next);
+                at = next;
+            }
+            rings.push(drop_collinear(ring));
+        }
+    }
+    rings
+}
+
+/// Remove corners where the ring runs straight on
+fn drop_collinear(ring: Path) -> Path {
+    let n = ring.len();
+    (0..n)
+        .filter(|&i| {
+            let (a, b, c) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+            (b.0 - a.0) * (c.1 - b.1) != (b.1 - a.1) * (c.0 - b.0)
+        })
+        .map(|i| ring[i])
+        .collect()
+}
+
+/// Twice the signed area (positive = clockwise on screen)
+fn signed_area(ring: &Path) -> i64 {
+    let n = ring.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (ring[i], ring[(i + 1) % n]);
+            a.0 as i64 * b.1 as i64 - b.0 as i64 * a.1 as i64
+        })
+        .sum()
+}
+
+/// Even-odd point-in-polygon test
+fn contains(ring: &Path, (px, py): (f64, f64)) -> bool {
+    let n = ring.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (ring[i], ring[(i + 1) % n]);
+        let (ax, ay, bx, by) = (a.0 as f64, a.1 as f64, b.0 as f64, b.1 as f64);
+        if (ay > py) != (by > py) && px < ax + (py - ay) / (by - ay) * (bx - ax) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Cut a closed ring into open lines wherever it runs along the map frame
+fn split_at_frame(ring: &Path, width: i32, height: i32) -> Vec<Path> {
+    let on_frame = |a: (i32, i32), b: (i32, i32)| {
+        (a.0 == b.0 && (a.0 == 0 || a.0 == width)) || (a.1 == b.1 && (a.1 == 0 || a.1 == height))
+    };
+    let n = ring.len();
+    let Some(first_cut) = (0..n).find(|&i| on_frame(ring[i], ring[(i + 1) % n])) else {
+        let mut closed = ring.clone();
+        closed.push(ring[0]);
+        return vec![closed];
+    };
+
+    // Walk once around the ring starting just after a frame edge
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    for k in 1..=n {
+        let i = (first_cut + k) % n;
+        let j = (i + 1) % n;
+        line.push(ring[i]);
+        if on_frame(ring[i], ring[j]) {
+            if line.len() > 1 {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.clear();
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tilemap::Tilemap;
+
+    #[test]
+    fn test_rings_and_holes() {
+        // A 5x5 block with a hole in the middle, plus a lone tile
+        let mut mask = Tilemap::new_with(6, 6, false);
+        for y in 0..5 {
+            for x in 0..5 {
+                mask.set(x, y, !(x == 2 && y == 2));
+            }
+        }
+        mask.set(5, 5, true);
+
+        let rings = trace_rings((6, 6), (0, 0, 5, 5), |x, y| *mask.get(x, y));
+        assert_eq!(rings.len(), 3);
+        let areas: Vec<i64> = rings.iter().map(signed_area).collect();
+        assert!(areas.contains(&50) && areas.contains(&2) && areas.contains(&-2));
+        // Straight runs collapse to their corners
+        assert!(rings.iter().all(|r| r.len() == 4));
+
+        let geometry = Projection { width: 6, height: 6 }.polygon(rings.clone());
+        assert_eq!(geometry["type"], "MultiPolygon");
+        let polygons = geometry["coordinates"].as_array().unwrap();
+        let ring_counts: Vec<usize> = polygons.iter().map(|p| p.as_array().unwrap().len()).collect();
+        assert!(ring_counts.contains(&2) && ring_counts.contains(&1));
+
+        // The block touches the west and north edges; only its inner sides are coast
+        let block = rings.iter().find(|r| signed_area(r) == 50).unwrap();
+        let lines = split_at_frame(block, 6, 6);
+        assert_eq!(lines, vec![vec![(5, 0), (5, 5), (0, 5)]]);
+    }
+
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_export_world_layers() {
+        let world = crate::world::generate_world(64, 32, 42);
+        let dir = tempfile::tempdir().unwrap();
+        let prefix = dir.path().join("world");
+        let paths = export_geojson(&world, prefix.to_str().unwrap()).unwrap();
+        assert_eq!(paths.len(), 8);
+
+        for path in &paths {
+            let value: Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+            assert_eq!(value["type"], "FeatureCollection");
+            for feature in value["features"].as_array().unwrap() {
+                let text = feature["geometry"]["coordinates"].to_string();
+                let numbers = text.split(|c: char| c == '[' || c == ']' || c == ',').filter_map(|s| s.parse::<f64>().ok());
+                assert!(numbers.into_iter().all(|v| (-180.0..=180.0).contains(&v)), "{}", path);
+            }
+        }
+
+        let towns = settlements(&world);
+        let history = world.history.as_ref().unwrap();
+        assert_eq!(towns["features"].as_array().unwrap().len(), history.territories.settlements.len());
+        assert!(towns["features"][0]["properties"]["founded"].is_i64());
+
+        // Settlements and ruins go by the same name in both layers
+        let places = places(&world);
+        let settled: Vec<_> = places["features"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|p| ["capital", "city", "town", "village", "ruin"].iter().any(|k| p["properties"]["kind"] == *k))
+            .map(|p| p["properties"]["name"].clone())
+            .collect();
+        assert!(!settled.is_empty());
+        for name in settled {
+            assert!(towns["features"].as_array().unwrap().iter().any(|t| t["properties"]["name"] == name));
+        }
+
+        assert!(!coastlines(&world)["features"].as_array().unwrap().is_empty());
+
+        // Every river reach carries its hydraulic geometry, and floods somewhere
+        let rivers = rivers(&world);
+        for river in rivers["features"].as_array().unwrap() {
+            let props = &river["properties"];
+            assert!(props["flood_width_m"].as_f64() >= props["width_m"].as_f64());
+            assert!(props["depth_m"].as_f64().unwrap() > 0.0);
+        }
+        if !rivers["features"].as_array().unwrap().is_empty() {
+            assert!(!floodways(&world)["features"].as_array().unwrap().is_empty());
+        }
+    }
+}
@@ -0,0 +1,625 @@
+//! Native desktop viewer (egui), enabled with the `viewer` feature
+//!
+//! A windowed frontend for generated worlds: layer toggles, smooth pan/zoom that
+//! streams local chunks in through the multiscale cache once zoomed in far enough,
+//! a tile inspector, a history timeline scrubber and export buttons.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use eframe::egui::{
+    self, Align2, Color32, ColorImage, FontId, Pos2, Rect, Sense, Stroke, StrokeKind,
+    TextureHandle, TextureOptions, Vec2,
+};
+
+use crate::ascii::{height_color, moisture_color, stress_color, temperature_color};
+use crate::history::Year;
+use crate::hot_reload::HotReload;
+use crate::budget::ResourceBudget;
+use crate::multiscale::{self, ChunkCache, ExportOptions, LOCAL_SIZE};
+use crate::world::WorldData;
+
+/// Pixels per world tile at which local chunks replace the world texture
+const LOCAL_ZOOM_THRESHOLD: f32 = 24.0;
+
+/// Zoom limits (pixels per world tile)
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 4.0 * LOCAL_SIZE as f32;
+
+/// New local chunks generated per frame (keeps panning responsive)
+const CHUNKS_PER_FRAME: usize = 2;
+
+/// Chunk textures kept before the texture cache is flushed
+const MAX_CHUNK_TEXTURES: usize = 256;
+
+/// Years of history shown around the scrubber position
+const EVENT_WINDOW: i32 = 50;
+
+/// How often the watched tuning file is checked
+const RELOAD_POLL: Duration = Duration::from_millis(500);
+
+// =============================================================================
+// LAYERS
+// =============================================================================
+
+/// Base layer drawn as the world texture
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layer {
+    Biome,
+    Height,
+    Temperature,
+    Moisture,
+    Stress,
+    Plates,
+    Factions,
+}
+
+impl Layer {
+    pub fn all() -> &'static [Layer] {
+        &[
+            Layer::Biome,
+            Layer::Height,
+            Layer::Temperature,
+            Layer::Moisture,
+            Layer::Stress,
+            Layer::Plates,
+            Layer::Factions,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Layer::Biome => "Biome",
+            Layer::Height => "Height",
+            Layer::Temperature => "Temperature",
+            Layer::Moisture => "Moisture",
+            Layer::Stress => "Stress",
+            Layer::Plates => "Plates",
+            Layer::Factions => "Factions",
+        }
+    }
+}
+
+/// Color of one world tile in a layer
+pub fn layer_color(world: &WorldData, layer: Layer, x: usize, y: usize) -> (u8, u8, u8) {
+    let water = (40, 70, 140);
+    match layer {
+        Layer::Biome => world.biomes.get(x, y).color(),
+        Layer::Height => height_color(*world.heightmap.get(x, y)),
+        Layer::Temperature => temperature_color(*world.temperature.get(x, y)),
+        Layer::Moisture => moisture_color(*world.moisture.get(x, y)),
+        Layer::Stress => stress_color(*world.stress_map.get(x, y)),
+        Layer::Plates => {
+            let id = *world.plate_map.get(x, y);
+            world.plates.iter()
+                .find(|p| p.id == id)
+                .map(|p| (p.color[0], p.color[1], p.color[2]))
+                .unwrap_or((128, 128, 128))
+        }
+        Layer::Factions => {
+            if *world.heightmap.get(x, y) < 0.0 {
+                return water;
+            }
+            world.history.as_ref()
+                .and_then(|h| h.faction_at(x, y))
+                .map(|f| f.color)
+                .unwrap_or((80, 80, 80))
+        }
+    }
+}
+
+/// Render a whole layer as RGB bytes (one pixel per tile)
+pub fn layer_rgb(world: &WorldData, layer: Layer) -> Vec<u8> {
+    let mut rgb = Vec::with_capacity(world.width * world.height * 3);
+    for y in 0..world.height {
+        for x in 0..world.width {
+            let (r, g, b) = layer_color(world, layer, x, y);
+            rgb.extend_from_slice(&[r, g, b]);
+        }
+    }
+    rgb
+}
+
+/// Map overlays drawn on top of the base layer
+#[derive(Clone, Debug)]
+pub struct Overlays {
+    pub settlements: bool,
+    pub events: bool,
+    pub ley_lines: bool,
+    pub aurora: bool,
+    pub water_names: bool,
+    pub chunk_grid: bool,
+}
+
+impl Default for Overlays {
+    fn default() -> Self {
+        Self {
+            settlements: true,
+            events: true,
+            ley_lines: true,
+            aurora: false,
+            water_names: true,
+            chunk_grid: false,
+        }
+    }
+}
+
+// =============================================================================
+// HISTORY SCRUBBING
+// =============================================================================
+
+/// Earliest and latest recorded years (present day if there is no history)
+pub fn history_span(world: &WorldData) -> (i32, i32) {
+    let Some(history) = world.history.as_ref() else {
+        return (0, 0);
+    };
+    let start = history.timeline.eras.iter().map(|e| e.start.0)
+        .chain(history.timeline.events.values().map(|e| e.year.0))
+        .min()
+        .unwrap_or(0);
+    let end = history.timeline.eras.iter().map(|e| e.end.0)
+        .chain(history.timeline.events.values().map(|e| e.year.0))
+        .max()
+        .unwrap_or(0);
+    (start, end.max(start))
+}
+
+/// A historical event as listed by the scrubber
+pub struct ScrubEvent {
+    pub year: Year,
+    pub name: String,
+    pub location: Option<(usize, usize)>,
+}
+
+/// Events in the window leading up to `year`, oldest first
+pub fn events_near(world: &WorldData, year: i32) -> Vec<ScrubEvent> {
+    let Some(history) = world.history.as_ref() else {
+        return Vec::new();
+    };
+    let mut events: Vec<_> = history.timeline.events.values()
+        .filter(|e| e.year.0 <= year && e.year.0 > year - EVENT_WINDOW)
+        .map(|e| ScrubEvent { year: e.year, name: e.name.clone(), location: e.location })
+        .collect();
+    events.sort_by_key(|e| e.year);
+    events
+}
+
+// =============================================================================
+// APP
+// =============================================================================
+
+struct ViewerApp {
+    world: WorldData,
+    layer: Layer,
+    overlays: Overlays,
+    world_texture: Option<(Layer, TextureHandle)>,
+    /// View centre in world tile coordinates
+    center: Vec2,
+    /// Pixels per world tile
+    zoom: f32,
+    chunk_cache: ChunkCache,
+    chunk_textures: HashMap<(usize, usize), TextureHandle>,
+    selected: Option<(usize, usize)>,
+    year: i32,
+    status: Option<String>,
+    hot_reload: Option<HotReload>,
+    budget: ResourceBudget,
+}
+
+impl ViewerApp {
+    fn new(world: WorldData, hot_reload: Option<HotReload>, budget: ResourceBudget) -> Self {
+        let chunk_cache = ChunkCache::with_persistence("saves/chunks", world.seed, budget.local_cache_size());
+        let year = history_span(&world).1;
+        Self {
+            center: Vec2::new(world.width as f32 / 2.0, world.height as f32 / 2.0),
+            zoom: 2.0,
+            layer: Layer::Biome,
+            overlays: Overlays::default(),
+            world_texture: None,
+            chunk_cache,
+            chunk_textures: HashMap::new(),
+            selected: None,
+            year,
+            status: None,
+            hot_reload,
+            budget,
+            world,
+        }
+    }
+
+    /// Re-run changed generation stages and drop every texture built from the old world
+    fn poll_reload(&mut self, ctx: &egui::Context) {
+        let Some(reload) = self.hot_reload.as_mut() else {
+            return;
+        };
+        if let Some(result) = reload.poll(&mut self.world) {
+            self.status = Some(match result {
+                Ok(status) => {
+                    self.world_texture = None;
+                    self.chunk_textures.clear();
+                    match self.chunk_cache.clear_persisted() {
+                        Ok(()) => status,
+                        Err(e) => format!("{} (failed to clear saved chunks: {})", status, e),
+                    }
+                }
+                Err(e) => format!("Reload failed: {}", e),
+            });
+        }
+        ctx.request_repaint_after(RELOAD_POLL);
+    }
+
+    /// World texture for the current layer (rebuilt when the layer changes)
+    fn world_texture(&mut self, ctx: &egui::Context) -> egui::TextureId {
+        if self.world_texture.as_ref().map(|(l, _)| *l) != Some(self.layer) {
+            let image = ColorImage::from_rgb([self.world.width, self.world.height], &layer_rgb(&self.world, self.layer));
+            let texture = ctx.load_texture("world", image, TextureOptions::NEAREST);
+            self.world_texture = Some((self.layer, texture));
+        }
+        self.world_texture.as_ref().expect("texture was just loaded").1.id()
+    }
+
+    fn screen_to_tile(&self, rect: Rect, pos: Pos2) -> Vec2 {
+        self.center + (pos - rect.center()) / self.zoom
+    }
+
+    /// Screen position of a tile's top-left corner, taking the copy nearest the view centre
+    fn tile_to_screen(&self, rect: Rect, x: f32, y: f32) -> Pos2 {
+        let width = self.world.width as f32;
+        let dx = (x - self.center.x + width / 2.0).rem_euclid(width) - width / 2.0;
+        rect.center() + Vec2::new(dx, y - self.center.y) * self.zoom
+    }
+
+    fn tile_at(&self, rect: Rect, pos: Pos2) -> Option<(usize, usize)> {
+        let tile = self.screen_to_tile(rect, pos);
+        if tile.y < 0.0 || tile.y >= self.world.height as f32 {
+            return None;
+        }
+        let x = (tile.x.floor() as i64).rem_euclid(self.world.width as i64) as usize;
+        Some((x, tile.y as usize))
+    }
+
+    fn clamp_view(&mut self) {
+        self.zoom = self.zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        self.center.x = self.center.x.rem_euclid(self.world.width as f32);
+        self.center.y = self.center.y.clamp(0.0, self.world.height as f32);
+    }
+
+    fn draw_map(&mut self, ui: &mut egui::Ui) {
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        let rect = response.rect;
+
+        // Pan by dragging, zoom around the pointer with the scroll wheel or pinch
+        if response.dragged() {
+            self.center -= response.drag_delta() / self.zoom;
+        }
+        if let Some(pointer) = response.hover_pos() {
+            let (scroll, pinch) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+            let factor = (scroll * 0.002).exp() * pinch;
+            if factor != 1.0 {
+                let before = self.screen_to_tile(rect, pointer);
+                self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+                let after = self.screen_to_tile(rect, pointer);
+                self.center += before - after;
+            }
+        }
+        self.clamp_view();
+        if response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                self.selected = self.tile_at(rect, pos);
+            }
+        }
+
+        // Base layer, drawn three times so the horizontal wrap is seamless
+        let texture = self.world_texture(ui.ctx());
+        let world_size = Vec2::new(self.world.width as f32, self.world.height as f32) * self.zoom;
+        let origin = rect.center() - self.center * self.zoom;
+        let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+        for copy in -1..=1 {
+            let min = origin + Vec2::new(copy as f32 * world_size.x, 0.0);
+            painter.image(texture, Rect::from_min_size(min, world_size), uv, Color32::WHITE);
+        }
+
+        if self.zoom >= LOCAL_ZOOM_THRESHOLD {
+            self.draw_local_chunks(ui.ctx(), &painter, rect);
+        }
+        self.draw_overlays(&painter, rect);
+
+        if let Some((x, y)) = self.selected {
+            let min = self.tile_to_screen(rect, x as f32, y as f32);
+            let tile = Rect::from_min_size(min, Vec2::splat(self.zoom.max(3.0)));
+            painter.rect_stroke(tile, 0.0, Stroke::new(2.0, Color32::YELLOW), StrokeKind::Outside);
+        }
+    }
+
+    /// Stream local chunks for the visible world tiles through the chunk cache
+    fn draw_local_chunks(&mut self, ctx: &egui::Context, painter: &egui::Painter, rect: Rect) {
+        if self.chunk_textures.len() > MAX_CHUNK_TEXTURES {
+            self.chunk_textures.clear();
+        }
+        let top_left = self.screen_to_tile(rect, rect.min);
+        let bottom_right = self.screen_to_tile(rect, rect.max);
+        let options = ExportOptions { budget: self.budget.clone(), ..Default::default() };
+        let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+        let mut generated = 0;
+
+        for ty in top_left.y.floor().max(0.0) as usize..(bottom_right.y.ceil() as usize).min(self.world.height) {
+            for tx in top_left.x.floor() as i64..bottom_right.x.ceil() as i64 {
+                let wx = tx.rem_euclid(self.world.width as i64) as usize;
+                if !self.chunk_textures.contains_key(&(wx, ty)) {
+                    if generated >= CHUNKS_PER_FRAME {
+                        ctx.request_repaint();
+                        continue;
+                    }
+                    let chunk = self.chunk_cache.get_or_generate_local(&self.world, wx, ty);
+                    let image = multiscale::render_chunk_image(chunk, &options);
+                    let size = [image.width() as usize, image.height() as usize];
+                    let texture = ctx.load_texture(
+                        format!("chunk_{}_{}", wx, ty),
+                        ColorImage::from_rgb(size, image.as_raw()),
+                        TextureOptions::NEAREST,
+                    );
+                    self.chunk_textures.insert((wx, ty), texture);
+                    generated += 1;
+                }
+                let min = self.tile_to_screen(rect, wx as f32, ty as f32);
+                let tile = Rect::from_min_size(min, Vec2::splat(self.zoom));
+                painter.image(self.chunk_textures[&(wx, ty)].id(), tile, uv, Color32::WHITE);
+                if self.overlays.chunk_grid {
+                    painter.rect_stroke(tile, 0.0, Stroke::new(1.0, Color32::from_gray(60)), StrokeKind::Inside);
+                }
+            }
+        }
+    }
+
+    fn draw_overlays(&self, painter: &egui::Painter, rect: Rect) {
+        let half = Vec2::splat(self.zoom / 2.0);
+        let marker = (self.zoom / 2.0).clamp(2.0, 8.0);
+
+        if self.overlays.ley_lines {
+            if let Some(magic) = self.world.magic.as_ref() {
+                for line in &magic.lines {
+                    for &(x, y) in &line.path {
+                        let pos = self.tile_to_screen(rect, x as f32, y as f32) + half;
+                        painter.circle_filled(pos, (marker / 3.0).max(1.0), Color32::from_rgb(190, 120, 255));
+                    }
+                }
+            }
+        }
+
+        if self.overlays.aurora {
+            if let Some(polar) = self.world.polar.as_ref() {
+                for (x, y, &a) in polar.aurora.iter() {
+                    if a < 0.1 {
+                        continue;
+                    }
+                    let min = self.tile_to_screen(rect, x as f32, y as f32);
+                    let tile = Rect::from_min_size(min, Vec2::splat(self.zoom));
+                    if rect.intersects(tile) {
+                        painter.rect_filled(tile, 0.0, Color32::from_rgba_unmultiplied(80, 255, 160, (a * 110.0) as u8));
+                    }
+                }
+            }
+        }
+
+        if let Some(history) = self.world.history.as_ref() {
+            if self.overlays.settlements {
+                for settlement in history.territories.settlements.values() {
+                    let alive = settlement.founded.0 <= self.year
+                        && settlement.abandoned.is_none_or(|y| y.0 > self.year);
+                    if !alive {
+                        continue;
+                    }
+                    let faction = settlement.current_faction.unwrap_or(settlement.original_faction);
+                    let (r, g, b) = history.factions.get(faction).map(|f| f.color).unwrap_or((255, 255, 255));
+                    let pos = self.tile_to_screen(rect, settlement.x as f32, settlement.y as f32) + half;
+                    painter.circle_filled(pos, marker, Color32::from_rgb(r, g, b));
+                    painter.circle_stroke(pos, marker, Stroke::new(1.0, Color32::BLACK));
+                }
+            }
+            if self.overlays.events {
+                for event in events_near(&self.world, self.year) {
+                    if let Some((x, y)) = event.location {
+                        let pos = self.tile_to_screen(rect, x as f32, y as f32) + half;
+                        painter.circle_stroke(pos, marker * 1.5, Stroke::new(2.0, Color32::RED));
+                    }
+                }
+            }
+        }
+
+        if self.overlays.water_names {
+            if let Some(gazetteer) = self.world.gazetteer.as_ref() {
+                for entry in &gazetteer.entries {
+                    let pos = self.tile_to_screen(rect, entry.x as f32, entry.y as f32) + half;
+                    if rect.contains(pos) {
+                        painter.text(pos, Align2::CENTER_CENTER, &entry.name, FontId::proportional(12.0), Color32::from_rgb(200, 230, 255));
+                    }
+                }
+            }
+        }
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Layers");
+        for &layer in Layer::all() {
+            ui.selectable_value(&mut self.layer, layer, layer.name());
+        }
+        ui.separator();
+        ui.heading("Overlays");
+        ui.checkbox(&mut self.overlays.settlements, "Settlements");
+        ui.checkbox(&mut self.overlays.events, "Historical events");
+        ui.checkbox(&mut self.overlays.ley_lines, "Ley lines");
+        ui.checkbox(&mut self.overlays.aurora, "Aurora");
+        ui.checkbox(&mut self.overlays.water_names, "Water names");
+        ui.checkbox(&mut self.overlays.chunk_grid, "Chunk grid");
+        ui.separator();
+        ui.label(format!("Zoom: {:.1} px/tile", self.zoom));
+        if self.zoom >= LOCAL_ZOOM_THRESHOLD {
+            ui.label(format!("Local chunks: {}", self.chunk_textures.len()));
+        }
+        if ui.button("Fit world").clicked() {
+            self.center = Vec2::new(self.world.width as f32 / 2.0, self.world.height as f32 / 2.0);
+            self.zoom = 2.0;
+        }
+        ui.separator();
+        ui.heading("Export");
+        if ui.button("Layer PNG").clicked() {
+            self.export_layer_png();
+        }
+        if ui.button("Local area PNG").clicked() {
+            self.export_local_area();
+        }
+        if ui.button("Timeline").clicked() {
+            let path = format!("chronicle_{}.txt", self.world.seed);
+            self.status = Some(match self.world.history.as_ref().map(|h| h.export_timeline(&path)) {
+                Some(Ok(())) => format!("Exported {}", path),
+                Some(Err(e)) => format!("Export failed: {}", e),
+                None => "No history to export".to_string(),
+            });
+        }
+        if ui.button("Gazetteer").clicked() {
+            let path = format!("gazetteer_{}.csv", self.world.seed);
+            self.status = Some(match self.world.gazetteer.as_ref().map(|g| g.export(&path)) {
+                Some(Ok(())) => format!("Exported {}", path),
+                Some(Err(e)) => format!("Export failed: {}", e),
+                None => "No gazetteer to export".to_string(),
+            });
+        }
+        if let Some(status) = &self.status {
+            ui.separator();
+            ui.label(status);
+        }
+    }
+
+    fn export_layer_png(&mut self) {
+        let path = format!("world_{}_{}.png", self.layer.name().to_lowercase(), self.world.seed);
+        let rgb = layer_rgb(&self.world, self.layer);
+        let result = image::RgbImage::from_raw(self.world.width as u32, self.world.height as u32, rgb)
+            .expect("layer buffer matches world size")
+            .save(&path);
+        self.status = Some(match result {
+            Ok(()) => format!("Exported {}", path),
+            Err(e) => format!("Export failed: {}", e),
+        });
+    }
+
+    fn export_local_area(&mut self) {
+        let (x, y) = self.selected.unwrap_or((self.center.x as usize, self.center.y as usize));
+        let path = format!("local_{}_{}_{}.png", self.world.seed, x, y);
+        let options = ExportOptions { budget: self.budget.clone(), ..Default::default() };
+        let result = multiscale::export_local_area(&self.world, x, y, 2, &path, &options);
+        self.status = Some(match result {
+            Ok((w, h)) => format!("Exported {} ({}x{})", path, w, h),
+            Err(e) => format!("Export failed: {}", e),
+        });
+    }
+
+    fn inspector(&self, ui: &mut egui::Ui) {
+        ui.heading("Tile");
+        let Some((x, y)) = self.selected else {
+            ui.label("Click the map to inspect a tile");
+            return;
+        };
+        let info = self.world.get_tile_info(x, y);
+        ui.label(format!("({}, {})", x, y));
+        ui.label(info.biome.display_name());
+        ui.label(format!("Elevation: {}", info.elevation_str()));
+        ui.label(format!("Temperature: {}", info.temperature_str()));
+        ui.label(format!("Moisture: {}", info.moisture_str()));
+        ui.label(format!("Stress: {}", info.stress_str()));
+        let water = self.world.water_bodies.iter()
+            .find(|wb| wb.id == info.water_body_id)
+            .and_then(|wb| wb.name.clone());
+        ui.label(format!("Water: {}", water.unwrap_or_else(|| info.water_body_str())));
+        if let Some(summary) = self.world.history.as_ref().and_then(|h| h.tile_info(x, y).summary()) {
+            ui.separator();
+            ui.label(summary);
+        }
+        if let Some(magic) = self.world.magic.as_ref().and_then(|m| m.describe(x, y)) {
+            ui.separator();
+            ui.label(magic);
+        }
+        if let Some(polar) = self.world.polar.as_ref().and_then(|p| p.describe(x, y)) {
+            ui.separator();
+            ui.label(polar);
+        }
+    }
+
+    fn timeline(&mut self, ui: &mut egui::Ui) {
+        let (start, end) = history_span(&self.world);
+        ui.horizontal(|ui| {
+            ui.label("History");
+            ui.add(egui::Slider::new(&mut self.year, start..=end).text("year"));
+            ui.label(Year(self.year).to_string());
+            let era = self.world.history.as_ref().and_then(|h| {
+                h.timeline.eras.iter().find(|e| e.start.0 <= self.year && self.year <= e.end.0)
+            });
+            if let Some(era) = era {
+                ui.label(format!("— {}", era.name));
+            }
+        });
+        egui::ScrollArea::vertical().max_height(90.0).show(ui, |ui| {
+            for event in events_near(&self.world, self.year).into_iter().rev() {
+                let text = format!("{}: {}", event.year, event.name);
+                match event.location {
+                    Some((x, y)) => if ui.link(text).clicked() {
+                        self.selected = Some((x, y));
+                        self.center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                    },
+                    None => { ui.label(text); }
+                }
+            }
+        });
+    }
+}
+
+impl eframe::App for ViewerApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_reload(ctx);
+        egui::SidePanel::left("controls").resizable(false).show(ctx, |ui| self.controls(ui));
+        egui::SidePanel::right("inspector").min_width(200.0).show(ctx, |ui| self.inspector(ui));
+        egui::TopBottomPanel::bottom("timeline").show(ctx, |ui| self.timeline(ui));
+        egui::CentralPanel::default()
+            .frame(egui::Frame::NONE)
+            .show(ctx, |ui| self.draw_map(ui));
+    }
+}
+
+/// Open the desktop viewer for a generated world (blocks until the window closes).
+/// With `hot_reload`, edits to the watched tuning file regenerate the affected stages.
+/// Streamed local chunks are cached within `budget`.
+pub fn run_viewer(world: WorldData, hot_reload: Option<HotReload>, budget: ResourceBudget) -> Result<(), eframe::Error> {
+    let title = format!("Planet Generator — seed {}", world.seed);
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([1280.0, 800.0])
+            .with_title(title.clone()),
+        ..Default::default()
+    };
+    eframe::run_native(&title, options, Box::new(|_cc| Ok(Box::new(ViewerApp::new(world, hot_reload, budget)))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::generate_world;
+
+    #[test]
+    fn test_layer_rgb_covers_every_tile() {
+        let world = generate_world(64, 32, 5);
+        for &layer in Layer::all() {
+            assert_eq!(layer_rgb(&world, layer).len(), 64 * 32 * 3);
+        }
+    }
+
+    #[test]
+    fn test_events_near_stays_in_window() {
+        let world = generate_world(64, 32, 5);
+        let (start, end) = history_span(&world);
+        assert!(start <= end);
+        for year in [start, (start + end) / 2, end] {
+            for event in events_near(&world, year) {
+                assert!(event.year.0 <= year && event.year.0 > year - EVENT_WINDOW);
+            }
+        }
+    }
+}
@@ -31,7 +31,7 @@ impl WaterBodyType {
 }
 
 /// Water body identifier (0 = land/none, 1+ = water body ID)
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct WaterBodyId(pub u16);
 
 impl WaterBodyId {
@@ -52,7 +52,7 @@ impl WaterBodyId {
 }
 
 /// Information about a water body
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct WaterBody {
     pub id: WaterBodyId,
     pub body_type: WaterBodyType,
@@ -64,6 +64,8 @@ pub struct WaterBody {
     pub touches_south_edge: bool,
     /// Bounding box (min_x, min_y, max_x, max_y)
     pub bounds: (usize, usize, usize, usize),
+    /// Proper name (assigned by the gazetteer)
+    pub name: Option<String>,
 }
 
 impl WaterBody {
@@ -78,6 +80,7 @@ impl WaterBody {
             touches_north_edge: false,
             touches_south_edge: false,
             bounds: (usize::MAX, usize::MAX, 0, 0),
+            name: None,
         }
     }
 
@@ -413,7 +416,7 @@ pub fn water_body_stats(water_bodies: &[WaterBody]) -> WaterBodyStats {
 }
 
 /// Statistics about water bodies
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct WaterBodyStats {
     pub ocean_tiles: usize,
     pub lake_count: usize,
@@ -0,0 +1,464 @@
+//! Wind fetch, wave energy and what the sea does to its coasts
+//!
+//! Waves grow with the stretch of open water the wind blows across before it
+//! reaches a shore: the fetch. Each sea tile along a coast measures its fetch
+//! upwind along the prevailing wind of its latitude, fanned a little to either
+//! side because the wind is never quite steady. A coast takes the wave energy of
+//! the water in front of it, scaled by how squarely it faces the wind; swell
+//! wrapping around headlands keeps even lee coasts from being entirely calm. Only
+//! the sea, water reaching the map's north or south edge, raises waves here.
+//!
+//! Exposed coasts are cut back: cliffs retreat and narrow headlands drown, while
+//! sheltered bays keep their shape and low sandy shores are reworked rather than
+//! cut. Deep, sheltered, enclosed water makes a good harbour, which draws port
+//! cities. Onshore gales driving a heavy sea against a rocky coast make a lee
+//! shore, and the history records the ships it claimed.
+
+#[cfg(feature = "history")]
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "history")]
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+
+use crate::aeolian;
+#[cfg(feature = "history")]
+use crate::history::types::Year;
+#[cfg(feature = "history")]
+use crate::history::{EventType, HistoricalEvent, WorldHistory};
+use crate::tilemap::Tilemap;
+
+/// Parameters for wave exposure and coastal erosion
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct WaveConfig {
+    /// Longest fetch (km) traced upwind; seas beyond it are fully developed
+    pub max_fetch_km: f32,
+    /// Fetch (km) at which waves reach about two thirds of their full energy
+    pub fetch_scale_km: f32,
+    /// Angle (degrees) either side of the prevailing wind also traced
+    pub wind_spread: f32,
+    /// Share of the energy a coast takes when facing away from the wind
+    pub swell: f32,
+    /// Height (m) cut from a fully exposed coastal tile
+    pub cliff_retreat: f32,
+    /// Coastal land below this height (m) is sand the waves rework, not rock they cut
+    pub beach_height: f32,
+    /// Depth (m) at which a harbour takes any ship
+    pub harbor_depth: f32,
+    /// Chance over a port's life that its lee shore wrecks a ship, at full danger
+    pub wreck_chance: f32,
+}
+
+impl Default for WaveConfig {
+    fn default() -> Self {
+        Self {
+            max_fetch_km: 1000.0,
+            fetch_scale_km: 300.0,
+            wind_spread: 30.0,
+            swell: 0.2,
+            cliff_retreat: 40.0,
+            beach_height: 10.0,
+            harbor_depth: 20.0,
+            wreck_chance: 0.8,
+        }
+    }
+}
+
+/// Wave exposure along the coasts
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct WaveMap {
+    /// Open sea (km) upwind of each coastal sea tile
+    pub fetch: Tilemap<f32>,
+    /// Wave energy (0-1) reaching each coastal tile, land or water
+    pub energy: Tilemap<f32>,
+    /// Harbour quality (0-1) of each coastal land tile
+    pub harbor: Tilemap<f32>,
+    /// Lee-shore danger (0-1) of each coastal sea tile
+    pub lee_shore: Tilemap<f32>,
+    /// Rock (m, summed over tiles) the waves cut from the coasts
+    pub eroded: f64,
+    /// Coastal tiles cut back below sea level
+    pub drowned: usize,
+}
+
+impl WaveMap {
+    /// Short description for tile info panels
+    pub fn describe(&self, x: usize, y: usize) -> Option<String> {
+        let energy = *self.energy.get(x, y);
+        if energy <= 0.0 {
+            return None;
+        }
+        let mut parts = vec![format!("Wave energy {:.0}%", energy * 100.0)];
+        if *self.harbor.get(x, y) >= 0.1 {
+            parts.push(format!("harbour {:.0}%", self.harbor.get(x, y) * 100.0));
+        }
+        if *self.lee_shore.get(x, y) >= 0.1 {
+            parts.push(format!("lee shore {:.0}%", self.lee_shore.get(x, y) * 100.0));
+        }
+        Some(parts.join(", "))
+    }
+}
+
+/// Water connected to the north or south edge of the map: the sea, without the
+/// lakes and rivers inland
+fn open_sea(heightmap: &Tilemap<f32>) -> Tilemap<bool> {
+    let mut sea = Tilemap::new_with(heightmap.width, heightmap.height, false);
+    let mut queue = VecDeque::new();
+    for y in [0, heightmap.height - 1] {
+        for x in 0..heightmap.width {
+            if *heightmap.get(x, y) <= 0.0 && !*sea.get(x, y) {
+                sea.set(x, y, true);
+                queue.push_back((x, y));
+            }
+        }
+    }
+    while let Some((x, y)) = queue.pop_front() {
+        for (nx, ny) in heightmap.neighbors(x, y) {
+            if *heightmap.get(nx, ny) <= 0.0 && !*sea.get(nx, ny) {
+                sea.set(nx, ny, true);
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    sea
+}
+
+/// Open sea (in tiles) upwind of a sea tile, stopping at land or the map's
+/// north and south edges
+fn fetch_tiles(sea: &Tilemap<bool>, x: usize, y: usize, (dx, dy): (f32, f32), max_tiles: usize) -> usize {
+    let (mut px, mut py) = (x as f32 + 0.5, y as f32 + 0.5);
+    for step in 1..=max_tiles {
+        px += dx;
+        py += dy;
+        if py < 0.0 || py >= sea.height as f32 {
+            return step - 1;
+        }
+        let tx = (px.floor() as i64).rem_euclid(sea.width as i64) as usize;
+        if !sea.get(tx, py as usize) {
+            return step - 1;
+        }
+    }
+    max_tiles
+}
+
+/// Measure fetch, wave energy, harbours and lee shores on the current coasts
+pub fn measure_waves(heightmap: &Tilemap<f32>, km_per_tile: f32, config: &WaveConfig) -> WaveMap {
+    let (width, height) = (heightmap.width, heightmap.height);
+    let max_tiles = ((config.max_fetch_km / km_per_tile).ceil() as usize).clamp(1, width.max(height));
+    let spread = config.wind_spread.to_radians();
+    let develop = |fetch_km: f32| 1.0 - (-fetch_km / config.fetch_scale_km).exp();
+    let open = open_sea(heightmap);
+    let is_sea = |x: usize, y: usize| *open.get(x, y);
+    let is_coast = |x: usize, y: usize| {
+        let water = is_sea(x, y);
+        heightmap.neighbors_8(x, y).into_iter().any(|(nx, ny)| is_sea(nx, ny) != water)
+    };
+
+    // Fetch and the sea it raises, along the coasts
+    let mut fetch = Tilemap::new_with(width, height, 0.0f32);
+    let mut sea = Tilemap::new_with(width, height, 0.0f32);
+    for y in 0..height {
+        let (wx, wy) = aeolian::prevailing_wind(y, height);
+        for x in 0..width {
+            if !is_sea(x, y) || !is_coast(x, y) {
+                continue;
+            }
+            let traced: usize = [-spread, 0.0, spread]
+                .iter()
+                .map(|a| {
+                    let (sin, cos) = a.sin_cos();
+                    fetch_tiles(&open, x, y, (-(wx * cos - wy * sin), -(wx * sin + wy * cos)), max_tiles)
+                })
+                .sum();
+            let km = traced as f32 / 3.0 * km_per_tile;
+            fetch.set(x, y, km);
+            sea.set(x, y, develop(km));
+        }
+    }
+
+    let mut energy = Tilemap::new_with(width, height, 0.0f32);
+    let mut harbor = Tilemap::new_with(width, height, 0.0f32);
+    let mut lee_shore = Tilemap::new_with(width, height, 0.0f32);
+    for y in 0..height {
+        let wind = aeolian::prevailing_wind(y, height);
+        for x in 0..width {
+            if !is_coast(x, y) {
+                continue;
+            }
+            // Share of the wind blowing from (x, y) towards a neighbour (negative: from it)
+            let facing = |(nx, ny): (usize, usize)| {
+                let dx = match nx as i64 - x as i64 {
+                    d if d > 1 => -1.0,
+                    d if d < -1 => 1.0,
+                    d => d as f32,
+                };
+                let dy = ny as f32 - y as f32;
+                (dx * wind.0 + dy * wind.1) / dx.hypot(dy)
+            };
+            let neighbors = heightmap.neighbors_8(x, y);
+
+            if is_sea(x, y) {
+                // Onshore wind piling this sea against the land, worst under cliffs
+                let onshore = neighbors
+                    .iter()
+                    .filter(|&&(nx, ny)| !is_sea(nx, ny))
+                    .map(|&n| facing(n).max(0.0))
+                    .fold(0.0f32, f32::max);
+                let relief = neighbors.iter().map(|&(nx, ny)| *heightmap.get(nx, ny)).fold(0.0f32, f32::max);
+                let rock = (0.4 + relief / 300.0).min(1.0);
+                let swell = config.swell + (1.0 - config.swell) * onshore;
+                energy.set(x, y, sea.get(x, y) * swell);
+                lee_shore.set(x, y, sea.get(x, y) * onshore * rock);
+            } else if *heightmap.get(x, y) > 0.0 {
+                // Waves from the sea in front, strongest where the wind blows onshore
+                let waves = neighbors
+                    .iter()
+                    .filter(|&&(nx, ny)| is_sea(nx, ny))
+                    .map(|&(nx, ny)| {
+                        let onshore = (-facing((nx, ny))).max(0.0);
+                        sea.get(nx, ny) * (config.swell + (1.0 - config.swell) * onshore)
+                    })
+                    .fold(0.0f32, f32::max);
+                energy.set(x, y, waves);
+
+                // Deep, sheltered water in an enclosed bay
+                let depth = neighbors
+                    .iter()
+                    .filter(|&&(nx, ny)| is_sea(nx, ny))
+                    .map(|&(nx, ny)| (-heightmap.get(nx, ny) / config.harbor_depth).clamp(0.0, 1.0))
+                    .fold(0.0f32, f32::max);
+                let (mut land, mut around) = (0, 0);
+                for oy in -3i64..=3 {
+                    for ox in -3i64..=3 {
+                        let ny = y as i64 + oy;
+                        if (ox, oy) == (0, 0) || ny < 0 || ny >= height as i64 {
+                            continue;
+                        }
+                        let nx = (x as i64 + ox).rem_euclid(width as i64) as usize;
+                        around += 1;
+                        land += usize::from(!is_sea(nx, ny as usize));
+                    }
+                }
+                let enclosure = land as f32 / around as f32;
+                harbor.set(x, y, ((1.0 - waves) * depth * (0.5 + enclosure)).min(1.0));
+            }
+        }
+    }
+
+    WaveMap { fetch, energy, harbor, lee_shore, eroded: 0.0, drowned: 0 }
+}
+
+/// Cut the exposed coasts back in proportion to the wave energy reaching them,
+/// then measure the waves again on the new coastline
+pub fn apply_waves(heightmap: &mut Tilemap<f32>, km_per_tile: f32, config: &WaveConfig) -> WaveMap {
+    let exposure = measure_waves(heightmap, km_per_tile, config);
+    let (mut eroded, mut drowned) = (0.0f64, 0);
+    for (x, y, &energy) in exposure.energy.iter() {
+        let h = *heightmap.get(x, y);
+        if energy <= 0.0 || h < config.beach_height {
+            continue;
+        }
+        let cut = energy * config.cliff_retreat;
+        if cut >= h {
+            // The cliff retreats past this tile, leaving a wave-cut platform
+            heightmap.set(x, y, -1.0 - energy * 4.0);
+            eroded += h as f64;
+            drowned += 1;
+        } else {
+            heightmap.set(x, y, h - cut);
+            eroded += cut as f64;
+        }
+    }
+
+    let mut waves = measure_waves(heightmap, km_per_tile, config);
+    waves.eroded = eroded;
+    waves.drowned = drowned;
+    waves
+}
+
+/// Ship names: "the Grey Gull"
+#[cfg(feature = "history")]
+const SHIP_ADJECTIVES: [&str; 10] =
+    ["Grey", "Swift", "Faithful", "Silver", "Bold", "Merry", "Northern", "Crimson", "Patient", "Wandering"];
+#[cfg(feature = "history")]
+const SHIP_NOUNS: [&str; 10] =
+    ["Gull", "Maiden", "Star", "Heron", "Promise", "Lantern", "Otter", "Fortune", "Swan", "Tern"];
+
+/// Wreck ships on the lee shores off coastal settlements. Each settlement
+/// within reach of a lee shore loses a ship at some point in its life with a
+/// chance that grows with the danger there. Returns how many wrecks were added.
+#[cfg(feature = "history")]
+pub fn apply_shipwreck_history(history: &mut WorldHistory, map: &WaveMap, config: &WaveConfig, seed: u64) -> usize {
+    let (start, end) = match (history.timeline.eras.first(), history.timeline.eras.last()) {
+        (Some(first), Some(last)) => (first.start, last.end),
+        _ => return 0,
+    };
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(0x5EA));
+    let (width, height) = (map.lee_shore.width, map.lee_shore.height);
+    let mut added = 0;
+
+    let mut settlement_ids: Vec<_> = history.territories.settlements.keys().copied().collect();
+    settlement_ids.sort_by_key(|id| id.0);
+    for id in settlement_ids {
+        let settlement = &history.territories.settlements[&id];
+        let (founded, last) = (settlement.founded.max(start), settlement.abandoned.unwrap_or(end));
+        if last <= founded {
+            continue;
+        }
+
+        // The most dangerous water within a short sail of the port
+        let mut worst = None;
+        for oy in -4i64..=4 {
+            for ox in -4i64..=4 {
+                let y = settlement.y as i64 + oy;
+                if y < 0 || y >= height as i64 {
+                    continue;
+                }
+                let x = (settlement.x as i64 + ox).rem_euclid(width as i64) as usize;
+                let danger = *map.lee_shore.get(x, y as usize);
+                if danger > worst.map_or(0.0, |(_, d)| d) {
+                    worst = Some(((x, y as usize), danger));
+                }
+            }
+        }
+        let Some(((x, y), danger)) = worst else {
+            continue;
+        };
+        if rng.gen::<f32>() >= danger * config.wreck_chance {
+            continue;
+        }
+
+        let year = Year(rng.gen_range(founded.0 + 1..=last.0));
+        let ship = format!(
+            "{} {}",
+            SHIP_ADJECTIVES[rng.gen_range(0..SHIP_ADJECTIVES.len())],
+            SHIP_NOUNS[rng.gen_range(0..SHIP_NOUNS.len())]
+        );
+        let drowned = rng.gen_range(5..=40 + settlement.peak_population / 100);
+        let faction = settlement.current_faction.or(Some(settlement.original_faction));
+        let description = format!(
+            "The {}, out of {}, was driven onto the rocks by an onshore gale; {} were drowned",
+            ship, settlement.name, drowned
+        );
+
+        let event_id = history.timeline.new_id();
+        history.timeline.add_event(HistoricalEvent {
+            id: event_id,
+            year,
+            event_type: EventType::Shipwreck,
+            faction,
+            other_faction: None,
+            location: Some((x, y)),
+            settlement: Some(id),
+            name: format!("The Wreck of the {}", ship),
+            description,
+            casualties: drowned,
+            has_evidence: EventType::Shipwreck.leaves_evidence(),
+        });
+        added += 1;
+    }
+
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "history")]
+    use crate::history::territories::{Settlement, TerritoryRegistry};
+    #[cfg(feature = "history")]
+    use crate::history::timeline::Era;
+    #[cfg(feature = "history")]
+    use crate::history::types::*;
+
+    /// A 64x32 map: open sea west of x = 24, and a 200 m coast east of it with a
+    /// deep bay opening onto the lee side at y = 23, and a pond inland
+    fn coast() -> Tilemap<f32> {
+        let mut heightmap = Tilemap::new_with(64, 32, -100.0f32);
+        for y in 0..32 {
+            for x in 24..56 {
+                heightmap.set(x, y, 200.0);
+            }
+        }
+        for y in 23..27 {
+            for x in 46..56 {
+                heightmap.set(x, y, -40.0);
+            }
+        }
+        for y in 14..17 {
+            for x in 34..37 {
+                heightmap.set(x, y, -40.0);
+            }
+        }
+        heightmap
+    }
+
+    #[test]
+    fn test_windward_coasts_erode_and_sheltered_bays_make_harbors() {
+        let config = WaveConfig::default();
+        let before = coast();
+        // Row 8 lies in the westerlies, blowing east onto the coast at x = 24
+        assert!(aeolian::prevailing_wind(8, 32).0 > 0.5);
+
+        let exposure = measure_waves(&before, 10.0, &config);
+        assert!(*exposure.fetch.get(23, 8) > 100.0);
+        let windward = *exposure.energy.get(24, 8);
+        let lee = *exposure.energy.get(55, 8);
+        assert!(windward > 0.3 && windward > lee * 2.0, "windward {} lee {}", windward, lee);
+        assert!(*exposure.lee_shore.get(23, 8) > *exposure.lee_shore.get(56, 8));
+        // The bay is calm, deep and enclosed: a better harbour than the open coast
+        assert!(*exposure.harbor.get(50, 22) > *exposure.harbor.get(24, 8));
+        assert_eq!(*exposure.harbor.get(30, 20), 0.0, "inland tiles are no harbour");
+        // Ponds inland raise no waves and make no harbours
+        assert_eq!(*exposure.energy.get(33, 15), 0.0);
+        assert_eq!(*exposure.harbor.get(33, 15), 0.0);
+
+        let mut heightmap = before.clone();
+        let waves = apply_waves(&mut heightmap, 10.0, &config);
+        assert!(waves.eroded > 0.0);
+        let cut = |x: usize| before.get(x, 8) - heightmap.get(x, 8);
+        assert!(cut(24) > cut(55), "exposed coasts erode more");
+        assert_eq!(cut(30), 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_lee_shores_wreck_ships() {
+        let config = WaveConfig { wreck_chance: 1.0, ..Default::default() };
+        let mut map = measure_waves(&coast(), 10.0, &config);
+        map.lee_shore.set(23, 8, 1.0);
+
+        let mut history = WorldHistory::empty();
+        history.timeline.eras.push(Era {
+            name: "Age of Sail".to_string(),
+            era_type: EraType::GoldenAge,
+            start: Year(-1000),
+            end: Year(0),
+            events: Vec::new(),
+        });
+        history.territories = TerritoryRegistry::new(64, 32);
+        for (id, x) in [(0u32, 25usize), (1, 32)] {
+            history.territories.add_settlement(Settlement {
+                id: SettlementId(id),
+                name: format!("Port{}", id),
+                settlement_type: SettlementType::Town,
+                original_faction: FactionId(0),
+                current_faction: Some(FactionId(0)),
+                x,
+                y: 8,
+                size: 1,
+                state: SettlementState::Thriving,
+                founded: Year(-900),
+                abandoned: None,
+                abandonment_reason: None,
+                peak_population: 800,
+                architecture: ArchitectureStyle::Rustic,
+                occupations: vec![(FactionId(0), Year(-900), None)],
+            });
+        }
+
+        let added = apply_shipwreck_history(&mut history, &map, &config, 3);
+        assert_eq!(added, 1, "only the port on the coast loses a ship");
+        let wrecks = history.timeline.events_at(23, 8);
+        assert!(wrecks.iter().any(|e| e.event_type == EventType::Shipwreck && e.settlement == Some(SettlementId(0))));
+    }
+}
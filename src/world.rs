@@ -5,19 +5,69 @@
 use rand_chacha::ChaCha8Rng;
 use rand::SeedableRng;
 
-use crate::biomes::{self, ExtendedBiome};
+use crate::aeolian::{self, DuneConfig, DuneMap, DuneType};
+use crate::biome_constraints::{self, PlacementSpec};
+use crate::biomes::{self, ExtendedBiome, WorldBiomeConfig};
 use crate::biome_feathering::{self, BiomeFeatherMap, FeatherConfig};
+use crate::budget::{self, BudgetError, MemoryEstimate, ResourceBudget};
+use crate::cave_biomes::{self, CaveBiome, CaveBiomeConfig, CaveBiomeMap};
+use crate::chemistry::{self, ClimateChemistry};
 use crate::climate;
-use crate::erosion::RiverNetwork;
+use crate::coast_character::{self, CoastCharacter, CoastCharacterParams, CoastType};
+use crate::coastline;
+use crate::erosion::{self, ErosionParams, ErosionStats, RiverNetwork, SpectralShaping};
+#[cfg(feature = "history")]
+use crate::exploration::{self, ExplorationConfig, ExplorationRecord};
+use crate::flora::{self, FloraCatalog, FloraConfig, FloraRegistry};
+use crate::gazetteer::{self, Gazetteer};
+use crate::geothermal::{self, GeothermalConfig, GeothermalMap};
 use crate::heightmap;
-use crate::history::{WorldHistory, generate_world_history};
-use crate::plates::{self, Plate, PlateId};
+use crate::hot_reload::StageCache;
+use crate::lakes::{self, LakeGraph};
+use crate::landforms::{self, Landform, LandformMap, LandformParams};
+use crate::loess::{self, LoessConfig, LoessMap};
+#[cfg(feature = "history")]
+use crate::magic::{self, MagicConfig, MagicMap};
+use crate::mass_wasting::{self, MassWastingConfig, MassWastingMap};
+use crate::names::NameLayer;
+use crate::polar::{self, PolarMap};
+use crate::history::WorldHistory;
+#[cfg(feature = "history")]
+use crate::history::{CuisineConfig, apply_cuisine, generate_world_history};
+use crate::plates::{self, DriftParams, Plate, PlateId, WorldStyle};
 use crate::scale::MapScale;
+#[cfg(feature = "history")]
+use crate::scenario::{self, Scenario, ScenarioState, ScriptedEvent};
+use crate::sketch::Sketch;
+use crate::seismic::{self, SeismicConfig, SeismicMap};
+use crate::succession::{self, SuccessionConfig, SuccessionMap};
 use crate::tilemap::Tilemap;
+use crate::underground_rivers::{self, UndergroundRiverConfig, UndergroundRiverMap};
 use crate::water_bodies::{self, WaterBody, WaterBodyId, WaterBodyType};
+use crate::waves::{self, WaveConfig, WaveMap};
 use crate::zlevel::{self, Tilemap3D, ZTile};
 
+#[cfg(feature = "simulation")]
+pub use crate::aging::advance_ages;
+
+/// Header identifying a saved world file (and its format version)
+const WORLD_FILE_MAGIC: &[u8; 8] = b"PLANETW2";
+
+/// Flag in the byte after the header: the file carries the history layers
+/// (history, magic, exploration, scenario), which only `history` builds have
+const WORLD_FILE_HISTORY: u8 = 1;
+
+/// Layer flags this build reads and writes
+fn world_file_layers() -> u8 {
+    if cfg!(feature = "history") {
+        WORLD_FILE_HISTORY
+    } else {
+        0
+    }
+}
+
 /// All generated world data bundled together
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct WorldData {
     /// Random seed used for generation
     pub seed: u64,
@@ -47,6 +97,8 @@ pub struct WorldData {
     pub water_body_map: Tilemap<WaterBodyId>,
     /// List of water bodies with metadata
     pub water_bodies: Vec<WaterBody>,
+    /// Lake water balance and the outlet rivers linking lakes
+    pub lakes: Option<LakeGraph>,
     /// 3D Z-level map (voxel-like terrain data)
     pub zlevels: Tilemap3D<ZTile>,
     /// Surface Z-level at each (x, y) position
@@ -57,6 +109,43 @@ pub struct WorldData {
     pub river_network: Option<RiverNetwork>,
     /// Biome feathering map for smooth transitions
     pub biome_feather_map: Option<BiomeFeatherMap>,
+    /// Ley lines and mana field (optional magic layer)
+    #[cfg(feature = "history")]
+    pub magic: Option<MagicMap>,
+    /// Polar ice caps and aurora band
+    pub polar: Option<PolarMap>,
+    /// Named oceans, seas, lakes and rivers
+    pub gazetteer: Option<Gazetteer>,
+    /// Every place name keyed by the tile it is labelled on
+    pub names: Option<NameLayer>,
+    /// Mesas, river terraces and badlands built after erosion
+    pub landforms: Option<LandformMap>,
+    /// Desert dune fields
+    pub dunes: Option<DuneMap>,
+    /// Wind-blown silt downwind of deserts and glaciers
+    pub loess: Option<LoessMap>,
+    /// How each faction charted the world
+    #[cfg(feature = "history")]
+    pub exploration: Option<ExplorationRecord>,
+    /// Underground biome of each cave region
+    pub cave_biomes: Option<CaveBiomeMap>,
+    /// Rivers sunk into karst and caverns, with their waterfalls and resurgences
+    pub underground_rivers: Option<UndergroundRiverMap>,
+    /// Temperature at depth, from surface climate down to geothermal heat
+    pub geothermal: Option<GeothermalMap>,
+    /// Fault lines, seismic hazard and the earthquakes history recorded
+    pub seismic: Option<SeismicMap>,
+    /// Landslide scars, debris and landslide and avalanche hazard
+    pub mass_wasting: Option<MassWastingMap>,
+    /// Wind fetch, wave energy, harbours and lee shores along the coasts
+    pub waves: Option<WaveMap>,
+    /// Years since each tile was cleared, and how far it has grown back
+    pub succession: Option<SuccessionMap>,
+    /// Plant species growing in each biome and their regional patches
+    pub flora: Option<FloraCatalog>,
+    /// Authored events still to come as the world ages
+    #[cfg(feature = "history")]
+    pub scenario: Option<ScenarioState>,
 }
 
 impl WorldData {
@@ -97,12 +186,151 @@ impl WorldData {
             hardness_map,
             water_body_map,
             water_bodies,
+            lakes: None,
             zlevels,
             surface_z,
             history,
             river_network,
             biome_feather_map,
+            #[cfg(feature = "history")]
+            magic: None,
+            polar: None,
+            gazetteer: None,
+            names: None,
+            landforms: None,
+            dunes: None,
+            loess: None,
+            #[cfg(feature = "history")]
+            exploration: None,
+            cave_biomes: None,
+            underground_rivers: None,
+            geothermal: None,
+            seismic: None,
+            mass_wasting: None,
+            waves: None,
+            succession: None,
+            flora: None,
+            #[cfg(feature = "history")]
+            scenario: None,
+        }
+    }
+
+    /// Save the world to a file, so later commands can load it instead of regenerating.
+    /// Only builds with the same `history` feature setting can load it back.
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writer.write_all(WORLD_FILE_MAGIC)?;
+        writer.write_all(&[world_file_layers()])?;
+        bincode::serialize_into(&mut writer, self).map_err(std::io::Error::other)?;
+        writer.flush()
+    }
+
+    /// Load a world written by [`WorldData::save`]
+    pub fn load(path: &str) -> std::io::Result<Self> {
+        use std::io::Read;
+
+        let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != WORLD_FILE_MAGIC {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "not a saved world file"));
+        }
+        let mut layers = [0u8];
+        reader.read_exact(&mut layers)?;
+        if layers[0] != world_file_layers() {
+            let message = if layers[0] & WORLD_FILE_HISTORY != 0 {
+                "world was saved with history, but this build lacks the `history` feature"
+            } else {
+                "world was saved by a build without the `history` feature; regenerate it with this one"
+            };
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, message));
         }
+        bincode::deserialize_from(reader).map_err(std::io::Error::other)
+    }
+
+    /// Replace the history with a new age generated on the same terrain. The
+    /// structures of the previous age stay carved into the z-levels as ruins.
+    #[cfg(feature = "history")]
+    pub fn regenerate_history(&mut self, seed: u64) {
+        let mut history = generate_world_history(
+            &mut self.zlevels,
+            &self.surface_z,
+            &self.heightmap,
+            &self.biomes,
+            &self.water_body_map,
+            &self.stress_map,
+            seed,
+        );
+        if let Some(ref dunes) = self.dunes {
+            aeolian::apply_dune_history(&mut history, dunes);
+        }
+        if let Some(ref geothermal) = self.geothermal {
+            geothermal::apply_underground_comfort(&mut history, geothermal, &self.surface_z);
+        }
+        if let Some(ref mut seismic) = self.seismic {
+            // Each age keeps the faults but feels only its own earthquakes
+            seismic.earthquakes.clear();
+            seismic::apply_earthquake_history(
+                &mut history,
+                seismic,
+                &mut self.heightmap,
+                &self.plate_map,
+                &SeismicConfig::default(),
+                seed,
+            );
+        }
+        if let Some(ref mass_wasting) = self.mass_wasting {
+            mass_wasting::apply_mass_wasting_history(
+                &mut history,
+                mass_wasting,
+                self.seismic.as_ref(),
+                &mut self.zlevels,
+                &self.surface_z,
+                &MassWastingConfig::default(),
+                seed,
+            );
+        }
+        if let Some(ref waves) = self.waves {
+            waves::apply_shipwreck_history(&mut history, waves, &WaveConfig::default(), seed);
+        }
+        let record = exploration::simulate_exploration(&history, &self.heightmap, &ExplorationConfig::default(), seed);
+        exploration::apply_exploration_history(&mut history, &record);
+        if let Some(ref flora) = self.flora {
+            flora::apply_herbal_medicine(&mut history, flora, &FloraConfig::default());
+        }
+        apply_cuisine(
+            &mut history,
+            self.flora.as_ref(),
+            &self.heightmap,
+            &self.biomes,
+            &self.water_body_map,
+            &CuisineConfig::default(),
+            seed,
+        );
+        if let Some(ref mut succession) = self.succession {
+            // The old age's fields are left to regrow; the new age clears its own
+            let config = SuccessionConfig::default();
+            succession.cultivated.fill(false);
+            succession::apply_history_disturbances(succession, &history, &config);
+            succession::apply_succession_biomes(&mut self.biomes, succession, &config);
+            if self.biome_feather_map.is_some() {
+                self.biome_feather_map =
+                    Some(biome_feathering::compute_biome_feathering(&self.biomes, &FeatherConfig::default(), seed));
+            }
+        }
+        self.history = Some(history);
+        self.exploration = Some(record);
+        self.names = Some(NameLayer::of(self));
+    }
+
+    /// Temperature (°C) at a z-level, falling back to the surface climate
+    /// for worlds generated without the depth model
+    pub fn temperature_at(&self, x: usize, y: usize, z: i32) -> f32 {
+        self.geothermal
+            .as_ref()
+            .map_or(*self.temperature.get(x, y), |g| g.temperature_at(x, y, z))
     }
 
     /// Get tile info at coordinates
@@ -249,148 +477,1099 @@ impl TileInfo {
 }
 
 /// Generate a complete world with the given parameters.
-/// This is a convenience function that encapsulates the full generation pipeline.
+/// This is a convenience wrapper around `WorldGenerator`, with structures and
+/// (in `history` builds) history.
 ///
 /// Note: This version skips erosion for faster generation (useful for exploration/preview).
-/// For full quality with erosion, use the main generation pipeline.
+/// For full quality with erosion, use `WorldGenerator`.
 pub fn generate_world(width: usize, height: usize, seed: u64) -> WorldData {
     // Special seed 666: Generate a minimal test world (4x4) for debugging
     if seed == 666 {
         return generate_test_world();
     }
 
-    let mut rng = ChaCha8Rng::seed_from_u64(seed);
-    let scale = MapScale::default();
-
-    // Generate tectonic plates
-    let (plate_map, plates) = plates::generate_plates(width, height, None, &mut rng);
-
-    // Calculate stress at plate boundaries
-    let stress_map = plates::calculate_stress(&plate_map, &plates);
-
-    // Generate heightmap
-    let heightmap = heightmap::generate_heightmap(&plate_map, &plates, &stress_map, seed);
-
-    // Generate climate
-    let temperature = climate::generate_temperature(&heightmap, width, height);
-    let moisture = climate::generate_moisture(&heightmap, width, height);
-
-    // Generate extended biomes
-    let biome_config = biomes::WorldBiomeConfig::default();
-    let mut extended_biomes = biomes::generate_extended_biomes(
-        &heightmap,
-        &temperature,
-        &moisture,
-        &stress_map,
-        &biome_config,
-        seed,
-    );
-
-    // Detect water bodies
-    let (water_body_map, water_bodies_list) = water_bodies::detect_water_bodies(&heightmap);
-
-    // Apply rare biome replacements
-    biomes::apply_biome_replacements(
-        &mut extended_biomes,
-        &heightmap,
-        &temperature,
-        &moisture,
-        &stress_map,
-        seed,
-    );
-
-    // Apply fantasy lake conversions
-    water_bodies::apply_fantasy_lake_conversions(
-        &mut extended_biomes,
-        &water_bodies_list,
-        &water_body_map,
-        &temperature,
-        &stress_map,
-        seed,
-    );
-
-    // Place unique biomes
-    biomes::place_unique_biomes(
-        &mut extended_biomes,
-        &heightmap,
-        seed,
-    );
-
-    // Compute biome feathering map for smooth transitions
-    let feather_config = FeatherConfig::default();
-    let biome_feather_map = biome_feathering::compute_biome_feathering(
-        &extended_biomes,
-        &feather_config,
-        seed,
-    );
+    let erosion = ErosionParams {
+        enable_rivers: false,
+        enable_hydraulic: false,
+        enable_glacial: false,
+        enable_analysis: false,
+        ..Default::default()
+    };
+    let generator = WorldGenerator::new().size(width, height).seed(seed).erosion(erosion).with_structures();
+    #[cfg(feature = "history")]
+    let generator = generator.with_history(HistoryConfig::default());
+    generator.generate()
+}
 
-    // Generate Z-level data
-    let (mut zlevels, surface_z) = zlevel::generate_zlevels(&heightmap);
-
-    // Generate underground water
-    zlevel::generate_underground_water(
-        &mut zlevels,
-        &surface_z,
-        &heightmap,
-        &moisture,
-        seed,
-    );
-
-    // Generate cave system
-    zlevel::generate_caves(
-        &mut zlevels,
-        &surface_z,
-        &heightmap,
-        &moisture,
-        &stress_map,
-        seed,
-    );
-
-    // Generate structures (castles, cities, villages, mines, roads)
-    crate::structures::generate_structures(
-        &mut zlevels,
-        &surface_z,
-        &heightmap,
-        &moisture,
-        &temperature,
-        &extended_biomes,
-        &stress_map,
-        &water_body_map,
-        seed,
-    );
-
-    // Generate world history (factions, events, settlements, monsters, trade)
-    let history = generate_world_history(
-        &mut zlevels,
-        &surface_z,
-        &heightmap,
-        &extended_biomes,
-        &water_body_map,
-        &stress_map,
-        seed,
-    );
-
-    // Generate Bezier river network (Phase 1)
-    let river_network = crate::erosion::trace_bezier_rivers(&heightmap, None, seed);
-
-    WorldData::new(
-        seed,
-        scale,
-        heightmap,
-        temperature,
-        moisture,
-        extended_biomes,
-        stress_map,
-        plate_map,
-        plates,
-        None, // No hardness map without erosion
-        water_body_map,
-        water_bodies_list,
-        zlevels,
-        surface_z,
-        Some(history),
-        Some(river_network),
-        Some(biome_feather_map),
+// =============================================================================
+// WORLD GENERATOR
+// =============================================================================
+
+/// Progress reported by `WorldGenerator` while it runs
+#[derive(Clone, Debug)]
+pub enum Progress {
+    /// A pipeline stage is starting: short stage name and a human-readable description
+    Stage(&'static str, &'static str),
+    /// A summary line about the stage that just ran
+    Detail(String),
+}
+
+/// Options for the history stage of `WorldGenerator`
+#[cfg(feature = "history")]
+#[derive(Clone, Debug, Default)]
+pub struct HistoryConfig {
+    /// Also generate ley lines and let history react to them
+    pub magic: Option<MagicConfig>,
+}
+
+/// Builder for the full generation pipeline (plates, erosion, climate, biomes,
+/// z-levels, structures, history), producing the same world the CLI does.
+///
+/// ```no_run
+/// use planet_generator::world::WorldGenerator;
+///
+/// let world = WorldGenerator::new()
+///     .size(256, 128)
+///     .seed(42)
+///     .with_structures()
+///     .with_history(Default::default())
+///     .on_progress(|p| println!("{:?}", p))
+///     .generate();
+/// ```
+pub struct WorldGenerator<'a> {
+    width: usize,
+    height: usize,
+    seed: Option<u64>,
+    plates: Option<usize>,
+    style: WorldStyle,
+    continent_mask: Option<plates::ContinentMask>,
+    scale_invariant: bool,
+    base_heightmap: Option<Tilemap<f32>>,
+    scale: MapScale,
+    erosion: ErosionParams,
+    coast: CoastCharacterParams,
+    landforms: LandformParams,
+    biomes: WorldBiomeConfig,
+    chemistry: Option<ClimateChemistry>,
+    temperature_offset: f32,
+    unique_biomes: PlacementSpec,
+    structures: bool,
+    #[cfg(feature = "history")]
+    history: Option<HistoryConfig>,
+    #[cfg(feature = "history")]
+    scenario: Option<Scenario>,
+    sketch: Option<Sketch>,
+    drift: Option<DriftParams>,
+    rift_valleys: bool,
+    spectral: Option<SpectralShaping>,
+    budget: ResourceBudget,
+    progress: Option<Box<dyn FnMut(Progress) + 'a>>,
+}
+
+impl Default for WorldGenerator<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> WorldGenerator<'a> {
+    pub fn new() -> Self {
+        Self {
+            width: 512,
+            height: 256,
+            seed: None,
+            plates: None,
+            style: WorldStyle::Random,
+            continent_mask: None,
+            scale_invariant: false,
+            base_heightmap: None,
+            scale: MapScale::default(),
+            erosion: ErosionParams::default(),
+            coast: CoastCharacterParams::default(),
+            landforms: LandformParams::default(),
+            biomes: WorldBiomeConfig::default(),
+            chemistry: None,
+            temperature_offset: 0.0,
+            unique_biomes: biome_constraints::default_spec(),
+            structures: false,
+            #[cfg(feature = "history")]
+            history: None,
+            #[cfg(feature = "history")]
+            scenario: None,
+            sketch: None,
+            drift: None,
+            rift_valleys: false,
+            spectral: None,
+            budget: ResourceBudget::default(),
+            progress: None,
+        }
+    }
+
+    /// Map size in tiles (default 512x256)
+    pub fn size(mut self, width: usize, height: usize) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Random seed (default: a random seed)
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Number of tectonic plates (default: random 6-15)
+    pub fn plates(mut self, count: usize) -> Self {
+        self.plates = Some(count);
+        self
+    }
+
+    /// Continent layout the plates are seeded for (default: random)
+    pub fn world_style(mut self, style: WorldStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Hand-drawn mask (white land, black ocean) the continental plates are seeded on
+    pub fn continent_mask(mut self, mask: plates::ContinentMask) -> Self {
+        self.continent_mask = Some(mask);
+        self
+    }
+
+    /// Generate plates, stress, heightmap and moisture in a resolution-independent way
+    pub fn scale_invariant(mut self) -> Self {
+        self.scale_invariant = true;
+        self
+    }
+
+    /// Use an existing heightmap (e.g. an imported DEM) instead of a generated one.
+    /// It must match the generator size; plate stress is ignored.
+    pub fn heightmap(mut self, heightmap: Tilemap<f32>) -> Self {
+        self.base_heightmap = Some(heightmap);
+        self
+    }
+
+    pub fn map_scale(mut self, scale: MapScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn erosion(mut self, params: ErosionParams) -> Self {
+        self.erosion = params;
+        self
+    }
+
+    /// Fjord, ria and barrier island parameters
+    pub fn coast(mut self, params: CoastCharacterParams) -> Self {
+        self.coast = params;
+        self
+    }
+
+    /// Mesa, river terrace and badland parameters
+    pub fn landforms(mut self, params: LandformParams) -> Self {
+        self.landforms = params;
+        self
+    }
+
+    pub fn biomes(mut self, config: WorldBiomeConfig) -> Self {
+        self.biomes = config;
+        self
+    }
+
+    /// Alien climate chemistry (replaces the biome palette and skips polar ice)
+    pub fn chemistry(mut self, chemistry: ClimateChemistry) -> Self {
+        self.chemistry = Some(chemistry);
+        self
+    }
+
+    /// Degrees C added to the whole temperature map (negative = a colder world)
+    pub fn temperature_offset(mut self, degrees: f32) -> Self {
+        self.temperature_offset = degrees;
+        self
+    }
+
+    /// Unique biome rules (default: the built-in spec)
+    pub fn unique_biomes(mut self, spec: PlacementSpec) -> Self {
+        self.unique_biomes = spec;
+        self
+    }
+
+    /// Place castles, cities, villages and roads
+    pub fn with_structures(mut self) -> Self {
+        self.structures = true;
+        self
+    }
+
+    /// Simulate factions, events, settlements, monsters and trade
+    #[cfg(feature = "history")]
+    pub fn with_history(mut self, config: HistoryConfig) -> Self {
+        self.history = Some(config);
+        self
+    }
+
+    /// Authored events: past ones are applied while generating, later ones
+    /// when the world is aged past their year
+    #[cfg(feature = "history")]
+    pub fn scenario(mut self, scenario: Scenario) -> Self {
+        self.scenario = Some(scenario);
+        self
+    }
+
+    /// Hold plates, relief and climate to a hand-drawn sketch of land, sea,
+    /// mountains and deserts (resampled to the map size)
+    pub fn sketch(mut self, sketch: Sketch) -> Self {
+        self.sketch = Some(sketch);
+        self
+    }
+
+    /// Drift the plates through earlier epochs before building the heightmap,
+    /// so old worn-down ranges and rifts sit alongside the young boundary belts
+    pub fn drift(mut self, params: DriftParams) -> Self {
+        self.drift = Some(params);
+        self
+    }
+
+    /// Carve rift valleys, with stepped faults, raised shoulders and rift
+    /// lakes, where continental plates pull apart
+    pub fn rift_valleys(mut self) -> Self {
+        self.rift_valleys = true;
+        self
+    }
+
+    /// Nudge the eroded terrain toward a target power-spectrum slope, leaving
+    /// carved river channels as they are
+    pub fn spectral_shaping(mut self, shaping: SpectralShaping) -> Self {
+        self.spectral = Some(shaping);
+        self
+    }
+
+    /// Threads, memory and GPU use the run must stay within
+    pub fn budget(mut self, budget: ResourceBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Predict the memory this configuration needs, without generating anything
+    pub fn estimate_memory(&self) -> MemoryEstimate {
+        #[cfg(feature = "history")]
+        let history = self.history.is_some();
+        #[cfg(not(feature = "history"))]
+        let history = false;
+        budget::estimate_memory(self.width, self.height, &self.erosion, history)
+    }
+
+    /// Receive stage starts and summary lines as generation runs
+    pub fn on_progress(mut self, callback: impl FnMut(Progress) + 'a) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Run the pipeline
+    pub fn generate(self) -> WorldData {
+        self.run(false).0
+    }
+
+    /// Run the pipeline, or refuse to start if the memory estimate exceeds the budget
+    pub fn try_generate(self) -> Result<WorldData, BudgetError> {
+        self.budget.check(&self.estimate_memory())?;
+        Ok(self.generate())
+    }
+
+    /// Run the pipeline, also keeping the stage snapshots needed for hot reloading
+    pub fn generate_with_stage_cache(self) -> (WorldData, StageCache) {
+        let (world, cache) = self.run(true);
+        (world, cache.expect("stage cache was requested"))
+    }
+
+    fn run(mut self, keep_stage_cache: bool) -> (WorldData, Option<StageCache>) {
+        let (width, height) = (self.width, self.height);
+        let seed = self.seed.unwrap_or_else(rand::random);
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let config = self.stage_config();
+        let mut report = |progress: Progress| {
+            if let Some(ref mut callback) = self.progress {
+                callback(progress);
+            }
+        };
+
+        // Tectonic plates
+        report(Progress::Stage("plates", "Generating tectonic plates"));
+        let (mut plate_map, mut plates) = if self.scale_invariant {
+            plates::generate_plates_invariant(width, height, self.plates, self.style, self.continent_mask.as_ref(), &mut rng)
+        } else {
+            plates::generate_plates(width, height, self.plates, self.style, self.continent_mask.as_ref(), &mut rng)
+        };
+        let continental = plates.iter().filter(|p| p.plate_type == plates::PlateType::Continental).count();
+        report(Progress::Detail(format!(
+            "Created {} plates ({} continental, {} oceanic), {} layout",
+            plates.len(),
+            continental,
+            plates.len() - continental,
+            if self.continent_mask.is_some() { "masked" } else { self.style.name() }
+        )));
+        if let Some(ref sketch) = self.sketch {
+            let continental = sketch.fit_plates(&plate_map, &mut plates, &mut rng);
+            report(Progress::Detail(format!(
+                "Fitted plates to the sketch: {} continental for {:.0}% painted land",
+                continental,
+                sketch.land_fraction() * 100.0
+            )));
+        }
+
+        let uplift = match self.drift {
+            Some(ref params) if self.base_heightmap.is_none() => {
+                let (drifted, history) = plates::simulate_drift(&plate_map, &plates, params, &mut rng);
+                plate_map = drifted;
+                let (uplifted, rifted) = history.coverage();
+                report(Progress::Detail(format!(
+                    "Drifted plates over {} epochs: {:.0}% uplifted, {:.0}% rifted",
+                    history.epochs,
+                    uplifted * 100.0,
+                    rifted * 100.0
+                )));
+                Some(history)
+            }
+            _ => None,
+        };
+
+        // Stress at plate boundaries (an imported heightmap already has its own relief)
+        report(Progress::Stage("stress", "Calculating plate stress"));
+        let mut stress_map = if self.base_heightmap.is_some() {
+            Tilemap::new_with(width, height, 0.0f32)
+        } else if self.scale_invariant {
+            plates::calculate_stress_invariant(&plate_map, &plates)
+        } else {
+            plates::calculate_stress(&plate_map, &plates)
+        };
+        if let Some(ref sketch) = self.sketch {
+            sketch.bias_stress(&mut stress_map);
+        }
+
+        report(Progress::Stage("heightmap", "Generating heightmap"));
+        let mut heightmap = match self.base_heightmap.take() {
+            Some(map) => map,
+            None => {
+                let (mut heightmap, tile_scale) = if self.scale_invariant {
+                    let map = heightmap::generate_heightmap_invariant(&plate_map, &plates, &stress_map, uplift.as_ref(), seed);
+                    (map, heightmap::invariant_tile_scale(width))
+                } else {
+                    (heightmap::generate_heightmap(&plate_map, &plates, &stress_map, uplift.as_ref(), seed), 1.0)
+                };
+                if self.rift_valleys {
+                    heightmap::carve_rift_valleys(&mut heightmap, &plate_map, &plates, &stress_map, seed, tile_scale);
+                }
+                heightmap
+            }
+        };
+        // Later stages (erosion hardness, seismicity) see the drift history as stress
+        if let Some(ref history) = uplift {
+            history.apply_to_stress(&mut stress_map);
+        }
+        if let Some(ref sketch) = self.sketch {
+            sketch.fit_heightmap(&mut heightmap);
+            report(Progress::Detail(format!(
+                "Fitted heightmap to the sketch: {:.0}% of painted tiles match",
+                sketch.agreement(&heightmap) * 100.0
+            )));
+        }
+        report(Progress::Detail(format!("Heightmap range: {}", elevation_summary(&heightmap))));
+
+        // Climate (needed for glacial erosion temperature zones)
+        report(Progress::Stage("climate", "Generating climate"));
+        let mut temperature = climate::generate_temperature(&heightmap, width, height);
+        if self.temperature_offset != 0.0 {
+            for (_, _, t) in temperature.iter_mut() {
+                *t += self.temperature_offset;
+            }
+        }
+        // Scale-invariant worlds measure coastal moisture bands in world space, not tiles
+        let mut moisture = if self.scale_invariant {
+            climate::generate_moisture_scaled(&heightmap, width, height, &heightmap::invariant_scale(width))
+        } else {
+            climate::generate_moisture(&heightmap, width, height)
+        };
+        if let Some(ref sketch) = self.sketch {
+            sketch.bias_moisture(&mut moisture);
+        }
+        let (min_t, max_t) = temperature.iter().fold((f32::MAX, f32::MIN), |(lo, hi), (_, _, &t)| (lo.min(t), hi.max(t)));
+        report(Progress::Detail(format!("Temperature range: {:.1}°C to {:.1}°C", min_t, max_t)));
+
+        let inputs = StageInputs {
+            seed,
+            plate_map: &plate_map,
+            plates: &plates,
+            stress_map: &stress_map,
+            temperature: &temperature,
+            moisture: &moisture,
+        };
+        let mut stage_cache = keep_stage_cache.then(|| StageCache::new(&config, &heightmap, &inputs));
+
+        let (erosion_stats, hardness_map) = config.erode(&mut heightmap, &inputs, &mut rng, &mut report);
+        let landform_map = config.shape_terrain(&mut heightmap, &inputs, &mut report);
+        if let Some(ref mut cache) = stage_cache {
+            cache.record_erosion(&heightmap, erosion_stats.glacial_erosion.as_ref(), &hardness_map, &landform_map);
+        }
+
+        let coasted = config.shape_coast(heightmap, &inputs, erosion_stats.glacial_erosion.as_ref(), &mut report);
+        if let Some(ref mut cache) = stage_cache {
+            cache.record_coast(&coasted);
+        }
+
+        report(Progress::Stage("seismic", "Tracing fault lines"));
+        #[cfg_attr(not(feature = "history"), allow(unused_mut))]
+        let mut seismic_map = seismic::generate_seismic(&plate_map, &plates, &stress_map, &SeismicConfig::default());
+        report(Progress::Detail(format!("  {} faults", seismic_map.faults.len())));
+
+        let mut surface = config.build_surface(
+            coasted,
+            &inputs,
+            erosion_stats.glacial_erosion.as_ref(),
+            Some(&seismic_map),
+            &mut report,
+        );
+
+        #[cfg(feature = "history")]
+        let (mut magic_map, mut exploration_record) = (None, None);
+        #[cfg(feature = "history")]
+        let mut history = self.history.as_ref().map(|config| {
+            report(Progress::Stage("history", "Generating world history"));
+            let mut history = generate_world_history(
+                &mut surface.zlevels,
+                &surface.surface_z,
+                &surface.heightmap,
+                &surface.biomes,
+                &surface.water_body_map,
+                &stress_map,
+                seed,
+            );
+            let buried = aeolian::apply_dune_history(&mut history, &surface.dunes);
+            report(Progress::Detail(format!("  {} settlements and roads buried by migrating dunes", buried)));
+            let uncomfortable = geothermal::apply_underground_comfort(&mut history, &surface.geothermal, &surface.surface_z);
+            report(Progress::Detail(format!("  {} underground settlements in uncomfortable rock", uncomfortable)));
+            let quakes = seismic::apply_earthquake_history(
+                &mut history,
+                &mut seismic_map,
+                &mut surface.heightmap,
+                &plate_map,
+                &SeismicConfig::default(),
+                seed,
+            );
+            report(Progress::Detail(format!("  {} earthquakes", quakes)));
+            let slides = mass_wasting::apply_mass_wasting_history(
+                &mut history,
+                &surface.mass_wasting,
+                Some(&seismic_map),
+                &mut surface.zlevels,
+                &surface.surface_z,
+                &MassWastingConfig::default(),
+                seed,
+            );
+            report(Progress::Detail(format!("  {} landslides and avalanches", slides)));
+            let wrecks = waves::apply_shipwreck_history(&mut history, &surface.waves, &WaveConfig::default(), seed);
+            report(Progress::Detail(format!("  {} ships wrecked on lee shores", wrecks)));
+
+            report(Progress::Stage("exploration", "Simulating exploration"));
+            let record = exploration::simulate_exploration(&history, &surface.heightmap, &ExplorationConfig::default(), seed);
+            exploration::apply_exploration_history(&mut history, &record);
+            report(Progress::Detail(format!(
+                "  {} expeditions, {} named discoveries",
+                record.expeditions.len(),
+                record.discoveries.len()
+            )));
+            exploration_record = Some(record);
+
+            // The magic layer reshapes history around ley nodes
+            if let Some(ref magic_config) = config.magic {
+                report(Progress::Stage("magic", "Generating ley lines"));
+                let map = magic::generate_magic(&surface.heightmap, &surface.biomes, &stress_map, magic_config, seed);
+                let events = magic::apply_magic_history(&mut history, &map, seed);
+                report(Progress::Detail(format!(
+                    "  {} ley nodes, {} ley lines, {} anomalies, {} magical events",
+                    map.nodes.len(),
+                    map.lines.len(),
+                    map.anomalies.len(),
+                    events
+                )));
+                magic_map = Some(map);
+            }
+            history
+        });
+        #[cfg(not(feature = "history"))]
+        let history: Option<WorldHistory> = None;
+
+        config.regrow(&mut surface, &inputs, history.as_ref(), &mut report);
+
+        #[cfg(feature = "history")]
+        if let Some(ref mut history) = history {
+            let flora_catalog = surface.flora.as_ref().expect("regrow draws the flora");
+            let eased = flora::apply_herbal_medicine(history, flora_catalog, &FloraConfig::default());
+            report(Progress::Detail(format!("  {} plagues eased by herbalists", eased)));
+
+            report(Progress::Stage("cuisine", "Naming signature foods and goods"));
+            let goods = apply_cuisine(
+                history,
+                Some(flora_catalog),
+                &surface.heightmap,
+                &surface.biomes,
+                &surface.water_body_map,
+                &CuisineConfig::default(),
+                seed,
+            );
+            report(Progress::Detail(format!("  {} signature goods", goods)));
+
+            // Authored events go in last, so no later pass reshapes them
+            if !config.scripted_past.is_empty() {
+                report(Progress::Stage("scenario", "Writing scenario events into history"));
+                let written = scenario::inject_events(history, &config.scripted_past, 0);
+                report(Progress::Detail(format!("  {} scenario events", written)));
+            }
+        }
+
+        config.finish_surface(&mut surface, &inputs, history.as_ref(), &mut report);
+
+        let mut world = WorldData::new(
+            seed,
+            self.scale,
+            surface.heightmap,
+            surface.temperature,
+            moisture,
+            surface.biomes,
+            stress_map,
+            plate_map,
+            plates,
+            Some(hardness_map),
+            surface.water_body_map,
+            surface.water_bodies,
+            surface.zlevels,
+            surface.surface_z,
+            history,
+            surface.river_network,
+            surface.biome_feather_map,
+        );
+        #[cfg(feature = "history")]
+        {
+            world.magic = magic_map;
+            world.exploration = exploration_record;
+            world.scenario = self.scenario.map(|scenario| ScenarioState { scenario, elapsed: 0 });
+        }
+        world.landforms = Some(landform_map);
+        world.dunes = Some(surface.dunes);
+        world.loess = Some(surface.loess);
+        world.cave_biomes = Some(surface.cave_biomes);
+        world.underground_rivers = Some(surface.underground_rivers);
+        world.geothermal = Some(surface.geothermal);
+        world.seismic = Some(seismic_map);
+        world.mass_wasting = Some(surface.mass_wasting);
+        world.waves = Some(surface.waves);
+        world.succession = surface.succession;
+        world.flora = surface.flora;
+        world.polar = surface.polar;
+        world.gazetteer = surface.gazetteer;
+        world.lakes = Some(surface.lakes);
+        world.names = Some(NameLayer::of(&world));
+        (world, stage_cache)
+    }
+
+    /// Settings the stages after erosion read, with the budget applied to erosion
+    fn stage_config(&self) -> StageConfig {
+        let mut erosion = self.erosion.clone();
+        self.budget.apply_to_erosion(&mut erosion);
+        StageConfig {
+            scale: self.scale,
+            erosion,
+            spectral: self.spectral.clone(),
+            coast: self.coast.clone(),
+            landforms: self.landforms.clone(),
+            biomes: self.biomes.clone(),
+            chemistry: self.chemistry.clone(),
+            unique_biomes: self.unique_biomes.clone(),
+            structures: self.structures,
+            #[cfg(feature = "history")]
+            scripted_past: self.scenario.as_ref().map(Scenario::past).unwrap_or_default(),
+            budget: self.budget.clone(),
+        }
+    }
+}
+
+// =============================================================================
+// SHARED STAGES
+// =============================================================================
+
+/// Generator settings the stages after erosion read. `WorldGenerator` runs the
+/// stages with them, and `StageCache` keeps a copy so a hot reload rebuilds the
+/// world the same way.
+#[derive(Clone, Debug)]
+pub struct StageConfig {
+    pub(crate) scale: MapScale,
+    pub(crate) erosion: ErosionParams,
+    pub(crate) spectral: Option<SpectralShaping>,
+    pub(crate) coast: CoastCharacterParams,
+    pub(crate) landforms: LandformParams,
+    pub(crate) biomes: WorldBiomeConfig,
+    pub(crate) chemistry: Option<ClimateChemistry>,
+    pub(crate) unique_biomes: PlacementSpec,
+    pub(crate) structures: bool,
+    /// Scenario events before year 0 (comet strikes are carved into the terrain)
+    #[cfg(feature = "history")]
+    pub(crate) scripted_past: Vec<ScriptedEvent>,
+    pub(crate) budget: ResourceBudget,
+}
+
+impl Default for StageConfig {
+    fn default() -> Self {
+        WorldGenerator::new().stage_config()
+    }
+}
+
+/// Layers fixed before erosion, read by every later stage
+pub(crate) struct StageInputs<'a> {
+    pub seed: u64,
+    pub plate_map: &'a Tilemap<PlateId>,
+    pub plates: &'a [Plate],
+    pub stress_map: &'a Tilemap<f32>,
+    /// Temperature before any alien chemistry remaps it
+    pub temperature: &'a Tilemap<f32>,
+    pub moisture: &'a Tilemap<f32>,
+}
+
+/// Heightmap as it leaves the coast stage, with the coast classification and wave exposure
+#[derive(Clone)]
+pub(crate) struct Coasted {
+    pub heightmap: Tilemap<f32>,
+    pub coast: CoastCharacter,
+    pub waves: WaveMap,
+}
+
+/// Everything built on top of the finished terrain
+pub(crate) struct Surface {
+    pub heightmap: Tilemap<f32>,
+    /// Temperature as the world reports it (remapped by alien chemistry)
+    pub temperature: Tilemap<f32>,
+    pub biomes: Tilemap<ExtendedBiome>,
+    pub water_body_map: Tilemap<WaterBodyId>,
+    pub water_bodies: Vec<WaterBody>,
+    pub lakes: LakeGraph,
+    pub polar: Option<PolarMap>,
+    pub dunes: DuneMap,
+    pub loess: LoessMap,
+    pub mass_wasting: MassWastingMap,
+    pub zlevels: Tilemap3D<ZTile>,
+    pub surface_z: Tilemap<i32>,
+    pub underground_rivers: UndergroundRiverMap,
+    pub geothermal: GeothermalMap,
+    pub cave_biomes: CaveBiomeMap,
+    pub waves: WaveMap,
+    pub succession: Option<SuccessionMap>,
+    pub flora: Option<FloraCatalog>,
+    pub biome_feather_map: Option<BiomeFeatherMap>,
+    pub gazetteer: Option<Gazetteer>,
+    pub river_network: Option<RiverNetwork>,
+}
+
+impl StageConfig {
+    /// Erosion, then spectral shaping of the eroded terrain when requested
+    pub(crate) fn erode(
+        &self,
+        heightmap: &mut Tilemap<f32>,
+        inputs: &StageInputs,
+        rng: &mut ChaCha8Rng,
+        report: &mut dyn FnMut(Progress),
+    ) -> (ErosionStats, Tilemap<f32>) {
+        report(Progress::Stage("erosion", "Simulating erosion"));
+        let (erosion_stats, hardness_map) = erosion::simulate_erosion(
+            heightmap,
+            inputs.plate_map,
+            inputs.plates,
+            inputs.stress_map,
+            inputs.temperature,
+            &self.erosion,
+            rng,
+            inputs.seed,
+        );
+        report(Progress::Detail(format!(
+            "Erosion: {:.1} eroded, {:.1} deposited (max {:.2} / {:.2})",
+            erosion_stats.total_eroded, erosion_stats.total_deposited, erosion_stats.max_erosion, erosion_stats.max_deposition
+        )));
+        if !erosion_stats.hotspots.is_empty() {
+            let plateaus = erosion_stats.hotspots.iter().filter(|h| h.kind == plates::HotspotKind::BasaltPlateau).count();
+            report(Progress::Detail(format!(
+                "Hotspots: {} island chains, {} basalt plateaus",
+                erosion_stats.hotspots.len() - plateaus,
+                plateaus
+            )));
+        }
+        report(Progress::Detail(format!("Post-erosion heightmap range: {}", elevation_summary(heightmap))));
+
+        if let Some(ref shaping) = self.spectral {
+            report(Progress::Stage("spectrum", "Shaping terrain spectrum"));
+            let shaped = erosion::shape_spectrum(heightmap, shaping);
+            report(Progress::Detail(format!(
+                "Spectral slope {:.2} -> {:.2} (target {:.2}, {} river tiles held)",
+                shaped.slope_before, shaped.slope_after, shaping.slope, shaped.river_tiles
+            )));
+        }
+        (erosion_stats, hardness_map)
+    }
+
+    /// Organic shorelines, regional terrain texture, then mesas, river terraces
+    /// and badlands on the eroded heightmap
+    pub(crate) fn shape_terrain(
+        &self,
+        heightmap: &mut Tilemap<f32>,
+        inputs: &StageInputs,
+        report: &mut dyn FnMut(Progress),
+    ) -> LandformMap {
+        let seed = inputs.seed;
+        report(Progress::Stage("coastline", "Applying coastline jittering"));
+        let coastline_params = coastline::CoastlineParams::default();
+        let coastline_network = coastline::generate_coastline_network(heightmap, &coastline_params, seed);
+        coastline::apply_coastline_to_heightmap(&coastline_network, heightmap, coastline_params.blend_width);
+
+        report(Progress::Stage("terrain_noise", "Applying terrain noise layers"));
+        heightmap::apply_regional_noise_stacks(heightmap, inputs.stress_map, seed);
+
+        // Chosen by rock type and climate
+        report(Progress::Stage("landforms", "Building mesas, terraces and badlands"));
+        let rocks = erosion::generate_material_map(inputs.plate_map, inputs.plates, heightmap, inputs.stress_map, seed);
+        let landform_map = landforms::apply_landforms(heightmap, &rocks, inputs.moisture, &self.landforms, seed);
+        report(Progress::Detail(format!(
+            "  {} mesa, {} terrace, {} badland tiles",
+            landform_map.count(Landform::Mesa),
+            landform_map.count(Landform::Terrace),
+            landform_map.count(Landform::Badlands)
+        )));
+        landform_map
+    }
+
+    /// Fjords, rias and barrier islands from the glacial and river history, then wave erosion
+    pub(crate) fn shape_coast(
+        &self,
+        mut heightmap: Tilemap<f32>,
+        inputs: &StageInputs,
+        glacial_erosion: Option<&Tilemap<f32>>,
+        report: &mut dyn FnMut(Progress),
+    ) -> Coasted {
+        report(Progress::Stage("coast_character", "Shaping coastline character"));
+        let glaciation = coast_character::glaciation_history(&heightmap, inputs.temperature, glacial_erosion, &self.coast);
+        let coast =
+            coast_character::apply_coast_character(&mut heightmap, inputs.temperature, &glaciation, &self.coast, inputs.seed);
+        report(Progress::Detail(format!(
+            "  {} fjords, {} rias, {} barrier island tiles, {} lagoon tiles",
+            coast.fjords,
+            coast.rias,
+            coast.count(CoastType::BarrierIsland),
+            coast.count(CoastType::Lagoon)
+        )));
+
+        report(Progress::Stage("waves", "Measuring wind fetch and wave energy"));
+        let waves = waves::apply_waves(&mut heightmap, self.scale.km_per_tile, &WaveConfig::default());
+        report(Progress::Detail(format!(
+            "  {:.0} m of rock cut from exposed coasts, {} coastal tiles drowned",
+            waves.eroded, waves.drowned
+        )));
+        Coasted { heightmap, coast, waves }
+    }
+
+    /// Water bodies, biomes, surface deposits, slope failure, z-levels, caves
+    /// and structures on the finished coastline
+    pub(crate) fn build_surface(
+        &self,
+        coasted: Coasted,
+        inputs: &StageInputs,
+        glacial_erosion: Option<&Tilemap<f32>>,
+        seismic: Option<&SeismicMap>,
+        report: &mut dyn FnMut(Progress),
+    ) -> Surface {
+        let Coasted { mut heightmap, coast, waves } = coasted;
+        let StageInputs { seed, stress_map, moisture, .. } = *inputs;
+
+        // Authored comet strikes of the past, carved before water and biomes settle around them
+        #[cfg(feature = "history")]
+        let impacts = scenario::carve_comet_strikes(&mut heightmap, &self.scripted_past);
+
+        report(Progress::Stage("water_bodies", "Detecting water bodies"));
+        let (water_body_map, water_bodies_list) = water_bodies::detect_water_bodies(&heightmap);
+        let water_stats = water_bodies::water_body_stats(&water_bodies_list);
+        report(Progress::Detail(format!(
+            "Found {} lakes, {} river tiles, {} ocean tiles",
+            water_bodies::count_lakes(&water_bodies_list),
+            water_stats.river_tiles,
+            water_stats.ocean_tiles
+        )));
+        report(Progress::Stage("lakes", "Balancing lakes and their outlets"));
+        let lake_graph = lakes::build_lake_graph(
+            &heightmap,
+            &water_body_map,
+            &water_bodies_list,
+            moisture,
+            inputs.temperature,
+            self.scale.km_per_tile,
+        );
+        report(Progress::Detail(format!(
+            "  {} lakes spill into another lake, {} terminal saline lakes",
+            lake_graph.chained(),
+            lake_graph.saline()
+        )));
+
+        report(Progress::Stage("biomes", "Classifying biomes"));
+        let mut extended_biomes =
+            biomes::generate_extended_biomes(&heightmap, inputs.temperature, moisture, stress_map, &self.biomes, seed);
+
+        // Rare biomes replace common ones, then lagoons and whole-lake conversions
+        let rare_clusters =
+            biomes::apply_biome_replacements(&mut extended_biomes, &heightmap, inputs.temperature, moisture, stress_map, seed);
+        report(Progress::Detail(format!("Created {} rare biome clusters", rare_clusters)));
+        coast_character::apply_coast_biomes(&mut extended_biomes, &coast);
+        let fantasy_lakes = water_bodies::apply_fantasy_lake_conversions(
+            &mut extended_biomes,
+            &water_bodies_list,
+            &water_body_map,
+            inputs.temperature,
+            stress_map,
+            seed,
+        );
+        if fantasy_lakes > 0 {
+            report(Progress::Detail(format!("Converted {} lakes to fantasy biomes", fantasy_lakes)));
+        }
+
+        // Exotic worlds swap in their own temperature range and biome palette
+        let temperature = match self.chemistry {
+            Some(ref chem) => {
+                let remapped = chem.remap_temperature(inputs.temperature);
+                extended_biomes = chemistry::generate_exotic_biomes(&heightmap, &remapped, moisture, chem);
+                report(Progress::Detail(format!("Applied {} biome palette", chem.name)));
+                remapped
+            }
+            None => inputs.temperature.clone(),
+        };
+
+        // Unique biomes (exactly one per map)
+        let placement_layers = biome_constraints::PlacementLayers {
+            heightmap: &heightmap,
+            temperature: &temperature,
+            moisture,
+            stress_map,
+        };
+        let placement = biome_constraints::solve_placement(&self.unique_biomes, &mut extended_biomes, &placement_layers, seed);
+        if !placement.placed.is_empty() {
+            report(Progress::Detail(format!("Placed {} unique biomes", placement.placed.len())));
+        }
+        for failure in &placement.failures {
+            report(Progress::Detail(format!("  Unique biome not placed: {}", failure)));
+        }
+
+        // Polar caps: ice sheets, pack ice and polynyas (water-ice worlds only)
+        let polar_map = if self.chemistry.is_none() {
+            let polar = polar::generate_polar(&heightmap, &temperature, &polar::PolarConfig::default(), seed);
+            let changed = polar::apply_polar_biomes(&mut extended_biomes, &polar);
+            report(Progress::Detail(format!(
+                "Polar caps: {} ice sheet, {} pack ice, {} polynya tiles ({} biomes changed)",
+                polar.count(polar::PolarIce::IceSheet),
+                polar.count(polar::PolarIce::SeaIce),
+                polar.count(polar::PolarIce::Polynya),
+                changed
+            )));
+            Some(polar)
+        } else {
+            None
+        };
+
+        #[cfg(feature = "history")]
+        {
+            let starfall = scenario::mark_crater_biomes(&mut extended_biomes, &heightmap, &impacts);
+            if starfall > 0 {
+                report(Progress::Detail(format!("Scenario comet strikes left {} starfall crater tiles", starfall)));
+            }
+        }
+
+        // Dune fields in the sandy deserts
+        report(Progress::Stage("dunes", "Building dune fields"));
+        let dune_map = aeolian::generate_dunes(&heightmap, &extended_biomes, &DuneConfig::default());
+        report(Progress::Detail(format!(
+            "  {} barchan, {} longitudinal, {} star dune tiles",
+            dune_map.count(DuneType::Barchan),
+            dune_map.count(DuneType::Longitudinal),
+            dune_map.count(DuneType::Star)
+        )));
+
+        report(Progress::Stage("loess", "Settling wind-blown loess"));
+        let loess_map = loess::generate_loess(&heightmap, moisture, Some(&dune_map), glacial_erosion, &LoessConfig::default());
+        let budget = &loess_map.budget;
+        report(Progress::Detail(format!(
+            "  {:.0} m of silt lifted ({:.0} desert, {:.0} outwash): {:.0} on land, {:.0} at sea, {:.0} off map",
+            budget.emitted(),
+            budget.from_deserts,
+            budget.from_outwash,
+            budget.deposited,
+            budget.lost_at_sea,
+            budget.off_map
+        )));
+
+        report(Progress::Stage("mass_wasting", "Failing unstable slopes"));
+        let mass_wasting_map = mass_wasting::apply_mass_wasting(
+            &mut heightmap,
+            moisture,
+            &temperature,
+            &extended_biomes,
+            seismic,
+            &MassWastingConfig::default(),
+        );
+        report(Progress::Detail(format!("  {:.0} m of rock moved downslope", mass_wasting_map.moved)));
+
+        report(Progress::Stage("zlevels", "Generating Z-level data"));
+        let (mut zlevels, surface_z) = zlevel::generate_zlevels(&heightmap);
+
+        report(Progress::Stage("underground_water", "Generating underground water"));
+        zlevel::generate_underground_water(&mut zlevels, &surface_z, &heightmap, moisture, seed);
+
+        report(Progress::Stage("caves", "Generating cave system"));
+        zlevel::generate_caves(&mut zlevels, &surface_z, &heightmap, moisture, stress_map, seed);
+
+        report(Progress::Stage("underground_rivers", "Sinking rivers into karst and caverns"));
+        let underground_river_map = underground_rivers::generate_underground_rivers(
+            &mut zlevels,
+            &surface_z,
+            &heightmap,
+            &extended_biomes,
+            self.scale.km_per_tile,
+            &UndergroundRiverConfig::default(),
+        );
+        report(Progress::Detail(format!(
+            "  {} rivers sink underground ({:.0} m³/s): {} resurgence springs, {} cave waterfalls",
+            underground_river_map.rivers.len(),
+            underground_river_map.diverted,
+            underground_river_map.springs(),
+            underground_river_map.waterfalls()
+        )));
+
+        report(Progress::Stage("geothermal", "Computing depth temperatures"));
+        let geothermal_map =
+            geothermal::generate_geothermal(&heightmap, &temperature, stress_map, &zlevels, &GeothermalConfig::default());
+
+        report(Progress::Stage("cave_biomes", "Assigning cave biomes"));
+        let cave_biome_map =
+            cave_biomes::generate_cave_biomes(&mut zlevels, &geothermal_map, moisture, &CaveBiomeConfig::default(), seed);
+        let frozen = geothermal::freeze_cave_lakes(&mut zlevels, &geothermal_map);
+        report(Progress::Detail(format!(
+            "  {} cave regions: {} mushroom caverns, {} crystal galleries, {} sunless seas",
+            cave_biome_map.regions.len(),
+            cave_biome_map.count(CaveBiome::MushroomCavern),
+            cave_biome_map.count(CaveBiome::CrystalGallery),
+            cave_biome_map.count(CaveBiome::SunlessSea)
+        )));
+        report(Progress::Detail(format!("  {} cave lake tiles frozen in cold rock", frozen)));
+
+        if self.structures {
+            report(Progress::Stage("structures", "Generating structures"));
+            crate::structures::generate_structures(
+                &mut zlevels,
+                &surface_z,
+                &heightmap,
+                moisture,
+                &temperature,
+                &extended_biomes,
+                stress_map,
+                &water_body_map,
+                Some(&waves.harbor),
+                seed,
+            );
+        }
+
+        Surface {
+            heightmap,
+            temperature,
+            biomes: extended_biomes,
+            water_body_map,
+            water_bodies: water_bodies_list,
+            lakes: lake_graph,
+            polar: polar_map,
+            dunes: dune_map,
+            loess: loess_map,
+            mass_wasting: mass_wasting_map,
+            zlevels,
+            surface_z,
+            underground_rivers: underground_river_map,
+            geothermal: geothermal_map,
+            cave_biomes: cave_biome_map,
+            waves,
+            succession: None,
+            flora: None,
+            biome_feather_map: None,
+            gazetteer: None,
+            river_network: None,
+        }
+    }
+
+    /// Fields and burned forest growing back since history cleared them, then
+    /// the plant species of each biome
+    pub(crate) fn regrow(
+        &self,
+        surface: &mut Surface,
+        inputs: &StageInputs,
+        history: Option<&WorldHistory>,
+        report: &mut dyn FnMut(Progress),
+    ) {
+        report(Progress::Stage("succession", "Regrowing fields and burned forest"));
+        let succession_config = SuccessionConfig::default();
+        let succession_map = succession::generate_succession(&surface.biomes, history, &succession_config);
+        succession::apply_succession_biomes(&mut surface.biomes, &succession_map, &succession_config);
+        report(Progress::Detail(format!(
+            "  {} tiles farmed, {} recovering",
+            succession_map.cultivated.iter().filter(|(_, _, &c)| c).count(),
+            succession_map.since.iter().filter(|(_, _, &s)| s < succession_config.old_growth_years).count()
+        )));
+        surface.succession = Some(succession_map);
+
+        report(Progress::Stage("flora", "Drawing plant species for each biome"));
+        let flora_catalog =
+            flora::generate_flora(&surface.biomes, &FloraRegistry::builtin(), &FloraConfig::default(), inputs.seed);
+        report(Progress::Detail(format!(
+            "  {} species in {} biomes",
+            flora_catalog.species.len(),
+            flora_catalog.by_biome.len()
+        )));
+        surface.flora = Some(flora_catalog);
+    }
+
+    /// Biome feathering, water body names and the traced river network
+    pub(crate) fn finish_surface(
+        &self,
+        surface: &mut Surface,
+        inputs: &StageInputs,
+        history: Option<&WorldHistory>,
+        report: &mut dyn FnMut(Progress),
+    ) {
+        let seed = inputs.seed;
+        report(Progress::Stage("feathering", "Computing biome feathering map"));
+        surface.biome_feather_map =
+            Some(biome_feathering::compute_biome_feathering(&surface.biomes, &FeatherConfig::default(), seed));
+
+        // Name water bodies in the tongues of the nearest factions
+        report(Progress::Stage("naming", "Naming water bodies"));
+        let water_names = gazetteer::name_water_bodies(
+            &surface.heightmap,
+            &surface.water_body_map,
+            &mut surface.water_bodies,
+            history,
+            seed,
+        );
+        report(Progress::Detail(format!("  Named {} water features", water_names.entries.len())));
+        surface.gazetteer = Some(water_names);
+
+        report(Progress::Stage("rivers", "Tracing river network"));
+        surface.river_network = Some(erosion::trace_bezier_rivers(&surface.heightmap, None, seed));
+    }
+}
+
+/// "min to max (percent above sea level)" for progress reports
+fn elevation_summary(heightmap: &Tilemap<f32>) -> String {
+    let (min_h, max_h) = heightmap.iter().fold((f32::MAX, f32::MIN), |(lo, hi), (_, _, &h)| (lo.min(h), hi.max(h)));
+    let above_sea = heightmap.iter().filter(|(_, _, &h)| h > 0.0).count();
+    format!(
+        "{:.1}m to {:.1}m ({:.1}% above sea level)",
+        min_h,
+        max_h,
+        100.0 * above_sea as f64 / (heightmap.width * heightmap.height) as f64
     )
 }
 
@@ -447,10 +1626,100 @@ pub fn generate_test_world() -> WorldData {
         hardness_map: None,
         water_body_map,
         water_bodies,
+        lakes: None,
         zlevels,
         surface_z,
         history: None,
         river_network: None,
         biome_feather_map: None,
+        #[cfg(feature = "history")]
+        magic: None,
+        polar: None,
+        gazetteer: None,
+        names: None,
+        landforms: None,
+        dunes: None,
+        loess: None,
+        #[cfg(feature = "history")]
+        exploration: None,
+        cave_biomes: None,
+        underground_rivers: None,
+        geothermal: None,
+        seismic: None,
+        mass_wasting: None,
+        waves: None,
+        succession: None,
+        flora: None,
+        #[cfg(feature = "history")]
+        scenario: None,
+    }
+}
+
+#[cfg(all(test, feature = "history"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_world_generator_runs_full_pipeline() {
+        let erosion = ErosionParams { hydraulic_iterations: 2_000, glacial_timesteps: 20, ..Default::default() };
+        let mut stages = Vec::new();
+        let world = WorldGenerator::new()
+            .size(64, 32)
+            .seed(9)
+            .erosion(erosion.clone())
+            .with_structures()
+            .with_history(HistoryConfig { magic: Some(MagicConfig::default()) })
+            .on_progress(|p| {
+                if let Progress::Stage(name, _) = p {
+                    stages.push(name);
+                }
+            })
+            .generate();
+
+        assert_eq!((world.width, world.height, world.seed), (64, 32, 9));
+        assert!(world.hardness_map.is_some());
+        assert!(world.history.is_some() && world.magic.is_some());
+        assert!(world.polar.is_some() && world.gazetteer.is_some());
+        for stage in ["plates", "erosion", "biomes", "structures", "history", "magic", "naming"] {
+            assert!(stages.contains(&stage), "missing stage {}", stage);
+        }
+
+        // History and structures are opt-in
+        let bare = WorldGenerator::new().size(64, 32).seed(9).erosion(erosion).generate();
+        assert!(bare.history.is_none() && bare.magic.is_none());
+    }
+
+    #[test]
+    fn test_world_file_round_trip() {
+        let erosion = ErosionParams { hydraulic_iterations: 2_000, glacial_timesteps: 20, ..Default::default() };
+        let world = WorldGenerator::new()
+            .size(48, 24)
+            .seed(4)
+            .erosion(erosion)
+            .with_history(HistoryConfig::default())
+            .generate();
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap();
+        world.save(path).unwrap();
+        let loaded = WorldData::load(path).unwrap();
+
+        assert_eq!((loaded.width, loaded.height, loaded.seed), (48, 24, 4));
+        assert!(loaded.heightmap.iter().zip(world.heightmap.iter()).all(|(a, b)| a.2 == b.2));
+        assert!(loaded.biomes.iter().zip(world.biomes.iter()).all(|(a, b)| a.2 == b.2));
+        assert_eq!(loaded.plates.len(), world.plates.len());
+        let events = |w: &WorldData| w.history.as_ref().map(|h| h.timeline.events.len());
+        assert_eq!(events(&loaded), events(&world));
+
+        // A file from a build without history is refused with a clear message
+        let mut bytes = std::fs::read(path).unwrap();
+        bytes[WORLD_FILE_MAGIC.len()] = 0;
+        std::fs::write(path, &bytes).unwrap();
+        let error = WorldData::load(path).err().unwrap();
+        assert!(error.to_string().contains("`history` feature"), "{}", error);
+
+        // Anything else is rejected rather than misread
+        std::fs::write(path, b"not a world").unwrap();
+        assert!(WorldData::load(path).is_err());
     }
 }
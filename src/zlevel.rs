@@ -47,7 +47,7 @@ pub const CAVERN_3_MAX: i32 = -11;
 pub const MIN_ROCK_ABOVE_CAVE: i32 = 2;
 
 /// Content of a tile at a specific Z-level
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub enum ZTile {
     /// Empty space above surface
     #[default]
@@ -104,6 +104,8 @@ pub enum ZTile {
     // === Water Integration ===
     /// Underground lake in cavern
     CaveLake,
+    /// Cave lake frozen solid by cold rock
+    FrozenCaveLake,
     /// Where water enters cave from above
     Waterfall,
 
@@ -324,7 +326,7 @@ impl ZTile {
             ZTile::Stalagmite | ZTile::Pillar | ZTile::Flowstone |
             ZTile::FungalGrowth | ZTile::GiantMushroom | ZTile::CrystalFormation |
             ZTile::CaveMoss | ZTile::MagmaPool | ZTile::MagmaTube |
-            ZTile::ObsidianFloor | ZTile::CaveLake | ZTile::Waterfall |
+            ZTile::ObsidianFloor | ZTile::CaveLake | ZTile::FrozenCaveLake | ZTile::Waterfall |
             ZTile::RampUp | ZTile::RampDown | ZTile::RampBoth
         )
     }
@@ -336,7 +338,7 @@ impl ZTile {
             self,
             ZTile::Air | ZTile::CaveFloor | ZTile::Flowstone |
             ZTile::FungalGrowth | ZTile::CaveMoss | ZTile::ObsidianFloor |
-            ZTile::MagmaTube | ZTile::Surface |
+            ZTile::MagmaTube | ZTile::FrozenCaveLake | ZTile::Surface |
             ZTile::RampUp | ZTile::RampDown | ZTile::RampBoth |
             // Structure tiles that are passable
             ZTile::StoneFloor | ZTile::WoodFloor | ZTile::CobblestoneFloor |
@@ -466,7 +468,7 @@ impl ZTile {
 /// - x: horizontal position (wraps)
 /// - y: vertical position on the 2D map (north-south)
 /// - z: elevation level (-16 to +16, where 0 is sea level)
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct Tilemap3D<T> {
     /// Map width in tiles
     pub width: usize,
@@ -926,7 +928,7 @@ struct CavernLayerParams {
 }
 
 /// Represents a cave chamber for connectivity calculations
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 struct CaveChamber {
     id: usize,
     tiles: Vec<(usize, usize, i32)>,
@@ -935,7 +937,7 @@ struct CaveChamber {
 }
 
 /// Edge for Minimum Spanning Tree
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
 struct Edge {
     from: usize,
     to: usize,